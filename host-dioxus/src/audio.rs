@@ -2,23 +2,139 @@
 //!
 //! `AudioManager` 只追踪音频逻辑状态（当前 BGM、音量、duck），
 //! 不做任何 I/O。实际播放由前端负责。
+//!
+//! 有效音量 = master × 通道音量（BGM 另乘 duck 系数与脚本音量）；每帧投影到
+//! `AudioRenderState`，因此修改 master 会实时作用于正在播放的 BGM。
+//!
+//! 一次性音效由 [`SfxVoices`] 限制并发数：前端不回报播放结束，
+//! 因此每个音效按 [`SFX_ASSUMED_LIFETIME`] 估算存活期，超出上限时
+//! 替换最低优先级中最旧的一个（发出停止请求），或丢弃新音效。
+//!
+//! silent 模式（[`AudioManager::silent`]）用于 CI / 无声卡环境：接口与状态追踪
+//! 照常工作（存档仍能记录当前 BGM），但不向前端发出任何播放请求。
 
 use tracing::debug;
 
 use crate::render_state::{AudioRenderState, BgmState, BgmTransition, SfxRequest};
 use crate::resources::normalize_logical_path;
 
+/// 同时播放的一次性音效上限
+pub const MAX_ACTIVE_SFX: usize = 8;
+/// 音效估算存活期（秒）；超过后不再计入活跃数
+pub const SFX_ASSUMED_LIFETIME: f32 = 3.0;
+
+/// 音效优先级：满额时低优先级先被替换
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SfxPriority {
+    /// 界面反馈音（点击、悬停）
+    Low,
+    /// 脚本音效
+    Normal,
+    High,
+}
+
+/// 新音效的准入结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SfxAdmission {
+    /// 可以播放；`evicted` 为需要停止的旧音效
+    Play { id: u64, evicted: Option<u64> },
+    /// 满额且活跃音效优先级都更高，丢弃
+    Dropped,
+}
+
+#[derive(Debug, Clone)]
+struct SfxVoice {
+    id: u64,
+    priority: SfxPriority,
+    age: f32,
+}
+
+/// 活跃一次性音效的轻量追踪（只计数，不持有播放句柄）
+#[derive(Debug, Clone)]
+pub struct SfxVoices {
+    max_active: usize,
+    next_id: u64,
+    active: Vec<SfxVoice>,
+}
+
+impl SfxVoices {
+    pub fn new(max_active: usize) -> Self {
+        Self {
+            max_active: max_active.max(1),
+            next_id: 0,
+            active: Vec::new(),
+        }
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// 为新音效申请一个播放位
+    pub fn admit(&mut self, priority: SfxPriority) -> SfxAdmission {
+        let mut evicted = None;
+        if self.active.len() >= self.max_active {
+            // active 按开始时间排序，min_by_key 取到同优先级中最旧的
+            let (victim, victim_priority) = self
+                .active
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, v)| v.priority)
+                .map(|(i, v)| (i, v.priority))
+                .expect("invariant: active is non-empty when full");
+            if victim_priority > priority {
+                return SfxAdmission::Dropped;
+            }
+            evicted = Some(self.active.remove(victim).id);
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.active.push(SfxVoice {
+            id,
+            priority,
+            age: 0.0,
+        });
+        SfxAdmission::Play { id, evicted }
+    }
+
+    /// 推进存活计时，移除估算已播完的音效
+    pub fn update(&mut self, dt: f32) {
+        for voice in &mut self.active {
+            voice.age += dt;
+        }
+        self.active.retain(|v| v.age < SFX_ASSUMED_LIFETIME);
+    }
+}
+
+/// 脚本音量（`bgmVol`）的线性过渡
+#[derive(Debug, Clone, Copy)]
+struct VolumeFade {
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
 /// 音频管理器（headless 状态追踪）
 pub struct AudioManager {
     current_bgm_path: Option<String>,
     bgm_looping: bool,
+    master_volume: f32,
     bgm_volume: f32,
     sfx_volume: f32,
     muted: bool,
     duck_multiplier: f32,
     duck_target: f32,
+    /// 脚本设置的 BGM 音量系数，与玩家设置相乘
+    script_volume: f32,
+    script_fade: Option<VolumeFade>,
     sfx_queue: Vec<SfxRequest>,
+    sfx_voices: SfxVoices,
+    sfx_stop_queue: Vec<u64>,
     pending_transition: Option<f32>,
+    /// silent 模式：不产出任何播放请求
+    silent: bool,
 }
 
 impl Default for AudioManager {
@@ -37,13 +153,67 @@ impl AudioManager {
         Self {
             current_bgm_path: None,
             bgm_looping: true,
+            master_volume: 1.0,
             bgm_volume: 1.0,
             sfx_volume: 1.0,
             muted: false,
             duck_multiplier: 1.0,
             duck_target: 1.0,
+            script_volume: 1.0,
+            script_fade: None,
             sfx_queue: Vec::new(),
+            sfx_voices: SfxVoices::new(MAX_ACTIVE_SFX),
+            sfx_stop_queue: Vec::new(),
             pending_transition: None,
+            silent: false,
+        }
+    }
+
+    /// 不播放任何声音的音频管理器；上层调用无需区分
+    pub fn silent() -> Self {
+        Self {
+            silent: true,
+            ..Self::new()
+        }
+    }
+
+    pub fn is_silent(&self) -> bool {
+        self.silent
+    }
+
+    /// 是否有 BGM 正在向玩家播放（silent 模式下始终为 false，但仍记录当前 BGM 路径）
+    pub fn is_playing(&self) -> bool {
+        !self.silent && self.current_bgm_path.is_some()
+    }
+
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.clamp(0.0, 1.0);
+    }
+
+    pub fn master_volume(&self) -> f32 {
+        self.master_volume
+    }
+
+    /// 静音时 BGM 与 SFX 的有效音量均为 0，音量设置本身保留
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    /// BGM 有效音量（master × bgm × duck × 脚本音量，静音时为 0）
+    pub fn effective_bgm_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * self.bgm_volume * self.duck_multiplier * self.script_volume
+        }
+    }
+
+    /// SFX 有效音量（master × sfx，静音时为 0）
+    pub fn effective_sfx_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume * self.sfx_volume
         }
     }
 
@@ -90,13 +260,37 @@ impl AudioManager {
     }
 
     pub fn play_sfx(&mut self, path: &str) {
+        self.play_sfx_with_priority(path, SfxPriority::Normal);
+    }
+
+    /// 播放一次性音效；活跃数满额时按优先级替换或丢弃
+    pub fn play_sfx_with_priority(&mut self, path: &str, priority: SfxPriority) {
+        if self.silent {
+            return;
+        }
         let logical_path = normalize_logical_path(path);
-        let volume = if self.muted { 0.0 } else { self.sfx_volume };
+        let (id, evicted) = match self.sfx_voices.admit(priority) {
+            SfxAdmission::Play { id, evicted } => (id, evicted),
+            SfxAdmission::Dropped => {
+                debug!(path = %logical_path, ?priority, "SFX state: dropped (limit reached)");
+                return;
+            }
+        };
+        if let Some(old) = evicted {
+            // 被替换的音效若还在本帧队列中，直接撤回即可
+            let queued = self.sfx_queue.len();
+            self.sfx_queue.retain(|r| r.id != old);
+            if self.sfx_queue.len() == queued {
+                self.sfx_stop_queue.push(old);
+            }
+        }
+        let volume = self.effective_sfx_volume();
         self.sfx_queue.push(SfxRequest {
+            id,
             path: logical_path.clone(),
             volume,
         });
-        debug!(path = %logical_path, "SFX state: queued");
+        debug!(path = %logical_path, id, "SFX state: queued");
     }
 
     pub fn duck(&mut self) {
@@ -109,7 +303,48 @@ impl AudioManager {
         debug!("BGM unduck -> 100%");
     }
 
+    /// 脚本调整 BGM 音量：从当前值线性过渡到 `volume`，`duration` 为 0 时立即生效
+    pub fn set_script_bgm_volume(&mut self, volume: f32, duration: f32) {
+        let volume = volume.clamp(0.0, 1.0);
+        if duration <= 0.0 {
+            self.script_volume = volume;
+            self.script_fade = None;
+        } else {
+            self.script_fade = Some(VolumeFade {
+                from: self.script_volume,
+                to: volume,
+                duration,
+                elapsed: 0.0,
+            });
+        }
+        debug!(volume, duration, "BGM script volume");
+    }
+
+    /// 脚本音量的目标值（过渡中为过渡终点），存档时保存此值
+    pub fn script_bgm_volume_target(&self) -> f32 {
+        self.script_fade
+            .as_ref()
+            .map_or(self.script_volume, |fade| fade.to)
+    }
+
+    /// 恢复脚本音量为 1（新会话开始时调用）
+    pub fn reset_script_bgm_volume(&mut self) {
+        self.script_volume = 1.0;
+        self.script_fade = None;
+    }
+
     pub fn update(&mut self, dt: f32) {
+        self.sfx_voices.update(dt);
+        if let Some(fade) = self.script_fade.as_mut() {
+            fade.elapsed += dt;
+            if fade.elapsed >= fade.duration {
+                self.script_volume = fade.to;
+                self.script_fade = None;
+            } else {
+                let t = fade.elapsed / fade.duration;
+                self.script_volume = fade.from + (fade.to - fade.from) * t;
+            }
+        }
         let diff = self.duck_target - self.duck_multiplier;
         if diff.abs() > 0.001 {
             let step = Self::DUCK_FADE_SPEED * dt;
@@ -122,19 +357,23 @@ impl AudioManager {
     }
 
     pub fn drain_audio_state(&mut self) -> AudioRenderState {
-        let bgm = self.current_bgm_path.as_ref().map(|path| {
-            let volume = if self.muted {
-                0.0
-            } else {
-                self.bgm_volume * self.duck_multiplier
+        if self.silent {
+            self.pending_transition = None;
+            return AudioRenderState {
+                bgm: None,
+                sfx_queue: Vec::new(),
+                sfx_stop: Vec::new(),
+                bgm_transition: None,
             };
-            BgmState {
-                path: path.clone(),
-                looping: self.bgm_looping,
-                volume,
-            }
+        }
+        let volume = self.effective_bgm_volume();
+        let bgm = self.current_bgm_path.as_ref().map(|path| BgmState {
+            path: path.clone(),
+            looping: self.bgm_looping,
+            volume,
         });
         let sfx_queue = std::mem::take(&mut self.sfx_queue);
+        let sfx_stop = std::mem::take(&mut self.sfx_stop_queue);
         let bgm_transition = self
             .pending_transition
             .take()
@@ -142,6 +381,7 @@ impl AudioManager {
         AudioRenderState {
             bgm,
             sfx_queue,
+            sfx_stop,
             bgm_transition,
         }
     }
@@ -207,6 +447,66 @@ mod tests {
         assert!(state2.sfx_queue.is_empty());
     }
 
+    #[test]
+    fn master_volume_scales_channels_and_keeps_ratio() {
+        let mut am = new_manager();
+        am.set_bgm_volume(0.8);
+        am.set_sfx_volume(0.4);
+        am.play_bgm("bgm/track1.ogg", true, None);
+        am.drain_audio_state();
+
+        am.set_master_volume(0.5);
+        am.play_sfx("sfx/click.ogg");
+        let state = am.drain_audio_state();
+        let bgm = state.bgm.expect("bgm should be playing").volume;
+        let sfx = state.sfx_queue[0].volume;
+        assert!((bgm - 0.5 * 0.8).abs() < 1e-6);
+        assert!((sfx - 0.5 * 0.4).abs() < 1e-6);
+        assert!((bgm / sfx - 0.8 / 0.4).abs() < 1e-6);
+
+        // 正在播放的 BGM 下一帧即生效
+        am.set_master_volume(0.25);
+        let bgm = am.drain_audio_state().bgm.unwrap().volume;
+        assert!((bgm - 0.25 * 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn script_volume_interpolates_and_multiplies_settings() {
+        let mut am = new_manager();
+        am.set_bgm_volume(0.8);
+        am.play_bgm("bgm/track1.ogg", true, None);
+
+        am.set_script_bgm_volume(0.3, 1.0);
+        assert_eq!(am.script_volume, 1.0);
+        am.update(0.5);
+        assert!((am.script_volume - 0.65).abs() < 1e-6);
+        // 存档保存过渡终点
+        assert_eq!(am.script_bgm_volume_target(), 0.3);
+        let bgm = am.drain_audio_state().bgm.unwrap().volume;
+        assert!((bgm - 0.8 * 0.65).abs() < 1e-6);
+
+        // 过渡结束后停在目标值
+        am.update(0.6);
+        assert_eq!(am.script_volume, 0.3);
+        am.update(1.0);
+        assert_eq!(am.script_volume, 0.3);
+
+        // 玩家调整设置时脚本系数保持不变
+        am.set_bgm_volume(0.5);
+        let bgm = am.drain_audio_state().bgm.unwrap().volume;
+        assert!((bgm - 0.5 * 0.3).abs() < 1e-6);
+
+        // 过渡中途改目标，从当前值继续
+        am.set_script_bgm_volume(1.0, 0.4);
+        am.update(0.2);
+        assert!((am.script_volume - 0.65).abs() < 1e-6);
+
+        am.set_script_bgm_volume(0.5, 0.0);
+        assert_eq!(am.script_volume, 0.5);
+        am.update(0.1);
+        assert_eq!(am.script_volume, 0.5);
+    }
+
     #[test]
     fn drain_consumes_pending_transition() {
         let mut am = new_manager();
@@ -216,4 +516,101 @@ mod tests {
         let second = am.drain_audio_state();
         assert!(second.bgm_transition.is_none());
     }
+
+    #[test]
+    fn sfx_voices_stay_within_limit_and_evict_oldest() {
+        let mut voices = SfxVoices::new(3);
+        let mut evicted = Vec::new();
+        for _ in 0..10 {
+            match voices.admit(SfxPriority::Normal) {
+                SfxAdmission::Play { evicted: e, .. } => evicted.extend(e),
+                SfxAdmission::Dropped => panic!("same priority should replace"),
+            }
+            assert!(voices.active_count() <= 3);
+        }
+        assert_eq!(evicted, (0..7).collect::<Vec<u64>>());
+
+        voices.update(SFX_ASSUMED_LIFETIME);
+        assert_eq!(voices.active_count(), 0);
+    }
+
+    #[test]
+    fn low_priority_sfx_is_replaced_first_and_dropped_when_outranked() {
+        let mut voices = SfxVoices::new(3);
+        voices.admit(SfxPriority::Normal);
+        voices.admit(SfxPriority::Low);
+        voices.admit(SfxPriority::Normal);
+
+        assert_eq!(
+            voices.admit(SfxPriority::Normal),
+            SfxAdmission::Play {
+                id: 3,
+                evicted: Some(1)
+            }
+        );
+        assert_eq!(voices.admit(SfxPriority::Low), SfxAdmission::Dropped);
+        assert_eq!(voices.active_count(), 3);
+    }
+
+    #[test]
+    fn play_sfx_over_limit_withdraws_queued_or_requests_stop() {
+        let mut am = new_manager();
+        for _ in 0..MAX_ACTIVE_SFX + 2 {
+            am.play_sfx("sfx/click.ogg");
+        }
+        assert_eq!(am.sfx_voices.active_count(), MAX_ACTIVE_SFX);
+        let state = am.drain_audio_state();
+        // 同一帧内被替换的请求直接撤回，不需要停止
+        assert_eq!(state.sfx_queue.len(), MAX_ACTIVE_SFX);
+        assert!(state.sfx_stop.is_empty());
+
+        am.play_sfx("sfx/click.ogg");
+        let state = am.drain_audio_state();
+        assert_eq!(state.sfx_queue.len(), 1);
+        assert_eq!(state.sfx_stop, vec![2]);
+    }
+
+    #[test]
+    fn silent_manager_accepts_all_calls_without_output() {
+        let mut am = AudioManager::silent();
+        assert!(am.is_silent());
+        am.set_master_volume(0.5);
+        am.set_bgm_volume(0.8);
+        am.set_sfx_volume(0.4);
+        am.play_bgm("bgm/track1.ogg", true, Some(1.0));
+        am.duck();
+        am.set_script_bgm_volume(0.5, 1.0);
+        for _ in 0..(MAX_ACTIVE_SFX * 2) {
+            am.play_sfx_with_priority("sfx/click.ogg", SfxPriority::High);
+        }
+        am.update(0.5);
+
+        // 状态照常追踪（存档依赖当前 BGM），但不算"正在播放"
+        assert_eq!(am.current_bgm_path(), Some("bgm/track1.ogg"));
+        assert!(!am.is_playing());
+        assert_eq!(am.master_volume(), 0.5);
+        assert_eq!(am.sfx_voices.active_count(), 0);
+
+        let state = am.drain_audio_state();
+        assert!(state.bgm.is_none());
+        assert!(state.sfx_queue.is_empty());
+        assert!(state.sfx_stop.is_empty());
+        assert!(state.bgm_transition.is_none());
+
+        am.stop_bgm(Some(1.0));
+        am.stop_bgm(None);
+        assert_eq!(am.current_bgm_path(), None);
+        assert!(am.drain_audio_state().bgm_transition.is_none());
+    }
+
+    #[test]
+    fn is_playing_follows_bgm_state() {
+        let mut am = new_manager();
+        assert!(!am.is_silent());
+        assert!(!am.is_playing());
+        am.play_bgm("bgm/track1.ogg", true, None);
+        assert!(am.is_playing());
+        am.stop_bgm(None);
+        assert!(!am.is_playing());
+    }
 }
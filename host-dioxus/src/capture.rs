@@ -0,0 +1,136 @@
+//! # 画面抓取
+//!
+//! 调试截图与存档缩略图共用的 WebView 抓帧：用 html2canvas 把 DOM 元素渲染为 PNG。
+//!
+//! html2canvas 优先从 [`HTML2CANVAS_PATH`] 经 `ring-asset` 协议本地加载；
+//! 项目未放置该文件时回退到 [`HTML2CANVAS_CDN`]（需要网络）。
+
+use dioxus::prelude::document;
+
+use crate::layout_config::UiAssetPaths;
+
+/// html2canvas 脚本的逻辑路径（相对 assets 根目录）
+pub const HTML2CANVAS_PATH: &str = "ui/vendor/html2canvas.min.js";
+
+/// 本地脚本缺失时使用的 CDN 地址
+pub const HTML2CANVAS_CDN: &str =
+    "https://cdnjs.cloudflare.com/ajax/libs/html2canvas/1.4.1/html2canvas.min.js";
+
+/// 抓帧 JS 模板：`{lib_url}` / `{cdn_url}` / `{selector}` / `{size}` / `{resize}`
+/// 由 [`capture_js`] 替换。
+///
+/// 返回 `{ width, height, data }`（data 为 base64 PNG）或 `{ error }`。
+const CAPTURE_JS: &str = r#"
+(async function() {
+    try {
+        // 加载 html2canvas（仅首次）：先本地，失败再回退 CDN
+        if (!window.__html2canvasLoaded) {
+            const load = (src) => new Promise((resolve, reject) => {
+                const script = document.createElement('script');
+                script.src = src;
+                script.onload = resolve;
+                script.onerror = () => { script.remove(); reject(new Error('html2canvas load failed: ' + src)); };
+                document.head.appendChild(script);
+            });
+            await load({lib_url}).catch(() => load({cdn_url}));
+            window.__html2canvasLoaded = true;
+        }
+
+        const target = document.querySelector({selector});
+        if (!target) {
+            dioxus.send({ error: {selector} + ' not found' });
+            return;
+        }
+
+        const size = {size};
+        const options = { useCORS: true, allowTaint: false, scale: 1, logging: false };
+        if (size) {
+            options.width = size[0];
+            options.height = size[1];
+        }
+        let canvas = await html2canvas(target, options);
+
+        const resize = {resize};
+        if (resize) {
+            const scaled = document.createElement('canvas');
+            scaled.width = resize[0];
+            scaled.height = resize[1];
+            scaled.getContext('2d').drawImage(canvas, 0, 0, scaled.width, scaled.height);
+            canvas = scaled;
+        }
+
+        // 去掉 "data:image/png;base64," 前缀
+        const base64 = canvas.toDataURL('image/png').split(',')[1];
+        dioxus.send({ width: canvas.width, height: canvas.height, data: base64 });
+    } catch (e) {
+        dioxus.send({ error: e.message || String(e) });
+    }
+})();
+"#;
+
+/// 一次抓帧的结果
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    /// base64 编码的 PNG
+    pub data_base64: String,
+}
+
+impl CapturedFrame {
+    /// 解码为 PNG 字节
+    pub fn decode_png(&self) -> Result<Vec<u8>, String> {
+        use base64::Engine as _;
+        base64::engine::general_purpose::STANDARD
+            .decode(&self.data_base64)
+            .map_err(|e| format!("PNG 数据无效: {e}"))
+    }
+}
+
+/// 抓帧输出尺寸
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureSize {
+    /// 按固定区域抓取（html2canvas 的 `width` / `height` 选项），不缩放
+    Fixed(u32, u32),
+    /// 按元素实际尺寸抓取后缩放到该尺寸
+    Scaled(u32, u32),
+}
+
+/// 生成抓取 `selector` 元素的 JS
+fn capture_js(selector: &str, size: CaptureSize) -> String {
+    let quote = |s: &str| serde_json::Value::String(s.to_string()).to_string();
+    let pair = |width: u32, height: u32| format!("[{width}, {height}]");
+    let (fixed, resize) = match size {
+        CaptureSize::Fixed(width, height) => (pair(width, height), "null".to_string()),
+        CaptureSize::Scaled(width, height) => ("null".to_string(), pair(width, height)),
+    };
+    CAPTURE_JS
+        .replace(
+            "{lib_url}",
+            &quote(&UiAssetPaths::asset_url(HTML2CANVAS_PATH)),
+        )
+        .replace("{cdn_url}", &quote(HTML2CANVAS_CDN))
+        .replace("{selector}", &quote(selector))
+        .replace("{size}", &fixed)
+        .replace("{resize}", &resize)
+}
+
+/// 在 WebView 中抓取匹配 `selector` 的元素
+///
+/// 抓帧与 PNG 编码都在 WebView 中异步完成，不阻塞调用方所在的 tick 循环。
+pub async fn capture_element(selector: &str, size: CaptureSize) -> Result<CapturedFrame, String> {
+    let mut eval = document::eval(&capture_js(selector, size));
+    let msg: serde_json::Value = eval.recv().await.map_err(|e| format!("eval 失败: {e}"))?;
+    if let Some(err) = msg.get("error").and_then(|v| v.as_str()) {
+        return Err(err.to_string());
+    }
+    let data = msg
+        .get("data")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "抓帧结果缺少 data".to_string())?;
+    let dimension = |key: &str| msg.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    Ok(CapturedFrame {
+        width: dimension("width"),
+        height: dimension("height"),
+        data_base64: data.to_string(),
+    })
+}
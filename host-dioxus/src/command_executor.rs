@@ -1,13 +1,14 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use serde::Serialize;
-use vn_runtime::command::{Command, TextMode, Transition, TransitionArg};
+use thiserror::Error;
+use vn_runtime::command::{Command, TextMode, TimedCommand, Transition, TransitionArg};
 use vn_runtime::state::VarValue;
 
 use crate::manifest::Manifest;
 use crate::render_state::{
-    BackgroundTransition, ChoiceItem, RenderState, SceneTransition, SceneTransitionKind,
-    SceneTransitionPhaseState,
+    CharacterBounce, ChoiceItem, ExpressionCrossfade, IdleKind, RenderState, SceneTransition,
+    SceneTransitionKind, SceneTransitionPhaseState, TransitionEvent,
 };
 
 #[derive(Debug, Clone, PartialEq, Default, Serialize)]
@@ -25,10 +26,21 @@ pub enum ExecuteResult {
     RequestUI {
         key: String,
         mode: String,
-        params: HashMap<String, VarValue>,
+        params: BTreeMap<String, VarValue>,
     },
 }
 
+/// 命令执行失败：RenderState 无法进入该命令要求的状态
+///
+/// 批次执行前统一校验，任一命令不满足前置条件时整批不执行，见
+/// [`CommandExecutor::execute_batch`]。
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExecuteError {
+    /// 选项列表为空，玩家无法继续
+    #[error("选项列表为空")]
+    EmptyChoices,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub enum AudioCommand {
     PlayBgm {
@@ -41,6 +53,10 @@ pub enum AudioCommand {
     },
     BgmDuck,
     BgmUnduck,
+    SetBgmVolume {
+        volume: f32,
+        duration: f32,
+    },
     PlaySfx {
         path: String,
     },
@@ -54,11 +70,21 @@ pub struct SceneEffectRequest {
 
 #[derive(Debug, Clone)]
 pub enum SceneEffectKind {
-    Shake { amplitude_x: f32, amplitude_y: f32 },
+    Shake {
+        amplitude_x: f32,
+        amplitude_y: f32,
+    },
     Blur,
     BlurOut,
-    Dim { level: f32 },
+    Dim {
+        level: f32,
+    },
     DimReset,
+    /// 镜头平移到 (x, y) 像素（驱动视差背景）
+    Pan {
+        x: f32,
+        y: f32,
+    },
 }
 
 impl SceneEffectRequest {
@@ -78,6 +104,20 @@ impl SceneEffectRequest {
                     duration: duration.unwrap_or(0.5),
                 }
             }
+        } else if name_lower.starts_with("pan") {
+            // panReset 回到原点；pan(x: .., y: ..) 平移到目标位置，未写的坐标为 0
+            let (x, y) = if name_lower.contains("reset") {
+                (0.0, 0.0)
+            } else {
+                (
+                    Self::extract_named(args, "x").unwrap_or(0.0),
+                    Self::extract_named(args, "y").unwrap_or(0.0),
+                )
+            };
+            SceneEffectRequest {
+                kind: SceneEffectKind::Pan { x, y },
+                duration: duration.unwrap_or(0.0),
+            }
         } else if name_lower.contains("dim") {
             if name_lower.contains("reset") {
                 SceneEffectRequest {
@@ -121,6 +161,13 @@ impl SceneEffectRequest {
         None
     }
 
+    fn extract_named(args: &[(Option<String>, TransitionArg)], name: &str) -> Option<f32> {
+        args.iter().find_map(|(key, val)| match val {
+            TransitionArg::Number(n) if key.as_deref() == Some(name) => Some(*n as f32),
+            _ => None,
+        })
+    }
+
     fn extract_level(args: &[(Option<String>, TransitionArg)]) -> f32 {
         for (key, val) in args {
             if key.as_deref() == Some("level")
@@ -133,10 +180,124 @@ impl SceneEffectRequest {
     }
 }
 
+/// `bounce` 未写高度时的首跳高度（像素）
+const BOUNCE_DEFAULT_HEIGHT: f64 = 20.0;
+/// 首跳高度上限（像素）
+const BOUNCE_MAX_HEIGHT: f64 = 200.0;
+/// 弹跳次数上限
+const BOUNCE_MAX_TIMES: f64 = 8.0;
+
+/// 立绘动画命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum CharacterAnimationCommand {
+    /// 循环待机动画，持续到被其他动画覆盖或角色隐藏
+    Idle { alias: String, kind: IdleKind },
+    /// 停止待机动画
+    StopIdle { alias: String },
+    /// 一次性衰减弹跳：首跳 `height` 像素，共 `times` 跳
+    Bounce {
+        alias: String,
+        height: f32,
+        times: u32,
+    },
+}
+
+impl CharacterAnimationCommand {
+    /// 由 `ShowCharacter` 的 idle 参数生成
+    ///
+    /// 每次 show 都会重新决定待机动画：不带 idle（或名称未知）即停止，
+    /// 使移动等新动画覆盖掉之前的循环。
+    fn from_show(alias: &str, idle: Option<&str>) -> Self {
+        match idle.map(|name| (name, IdleKind::from_name(name))) {
+            Some((_, Some(kind))) => Self::Idle {
+                alias: alias.to_string(),
+                kind,
+            },
+            Some((name, None)) => {
+                tracing::warn!(alias, idle = name, "未知待机动画，已忽略");
+                Self::StopIdle {
+                    alias: alias.to_string(),
+                }
+            }
+            None => Self::StopIdle {
+                alias: alias.to_string(),
+            },
+        }
+    }
+
+    /// 由 show 的 `with bounce(height, times)` 生成弹跳；其他过渡返回 None
+    ///
+    /// 参数可按位置或名称（`height` / `times`）给出；高度缺省 20 像素、次数缺省 1，
+    /// 超出范围时截断。
+    fn from_transition(alias: &str, transition: Option<&Transition>) -> Option<Self> {
+        let transition = transition.filter(|t| t.name.eq_ignore_ascii_case("bounce"))?;
+        let number = |key: &str, index: usize| match transition.get_arg(key, index) {
+            Some(TransitionArg::Number(n)) => Some(*n),
+            _ => None,
+        };
+        let height = number("height", 0)
+            .unwrap_or(BOUNCE_DEFAULT_HEIGHT)
+            .clamp(0.0, BOUNCE_MAX_HEIGHT);
+        let times = number("times", 1).map_or(1.0, |n| n.round().clamp(1.0, BOUNCE_MAX_TIMES));
+        Some(Self::Bounce {
+            alias: alias.to_string(),
+            height: height as f32,
+            times: times as u32,
+        })
+    }
+
+    fn apply(&self, rs: &mut RenderState) {
+        match self {
+            Self::Idle { alias, kind } => {
+                if let Some(c) = rs.visible_characters.get_mut(alias) {
+                    c.idle = Some(*kind);
+                }
+            }
+            Self::StopIdle { alias } => {
+                if let Some(c) = rs.visible_characters.get_mut(alias) {
+                    c.idle = None;
+                }
+            }
+            Self::Bounce {
+                alias,
+                height,
+                times,
+            } => {
+                if let Some(c) = rs.visible_characters.get_mut(alias) {
+                    c.bounce = Some(CharacterBounce::new(*height, *times));
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct CommandOutput {
     pub audio_command: Option<AudioCommand>,
     pub scene_effect_request: Option<SceneEffectRequest>,
+    /// 执行中降级处理的问题（如遮罩缺失），由调用方提示给玩家
+    pub warnings: Vec<String>,
+    /// 本条命令产生的过渡生命周期事件
+    pub transition_events: Vec<TransitionEvent>,
+    /// timeline 中待到时执行的命令
+    pub scheduled: Vec<ScheduledCommand>,
+}
+
+/// timeline 中待执行的命令
+#[derive(Debug, Clone)]
+pub struct ScheduledCommand {
+    /// 相对 timeline 开始的延时（秒）
+    pub delay: f32,
+    pub command: Command,
+}
+
+impl From<&TimedCommand> for ScheduledCommand {
+    fn from(step: &TimedCommand) -> Self {
+        Self {
+            delay: step.delay as f32,
+            command: step.command.clone(),
+        }
+    }
 }
 
 enum TransitionKind {
@@ -145,6 +306,8 @@ enum TransitionKind {
     Fade,
     FadeWhite,
     Move,
+    /// 立绘换表情的交叉淡化；其他目标上等价 dissolve
+    Crossfade,
     Rule {
         mask_path: String,
         reversed: bool,
@@ -160,6 +323,9 @@ fn resolve_transition(transition: &Transition) -> (TransitionKind, f32) {
         "fade" => (TransitionKind::Fade, duration.unwrap_or(0.5)),
         "fadewhite" => (TransitionKind::FadeWhite, duration.unwrap_or(0.5)),
         "move" | "slide" => (TransitionKind::Move, duration.unwrap_or(0.3)),
+        "crossfade" => (TransitionKind::Crossfade, duration.unwrap_or(0.2)),
+        // 弹跳不做透明度过渡，动画见 `CharacterAnimationCommand::Bounce`
+        "bounce" => (TransitionKind::None, 0.0),
         "none" => (TransitionKind::None, 0.0),
         "rule" => {
             let mask_path = transition
@@ -190,10 +356,22 @@ fn resolve_transition(transition: &Transition) -> (TransitionKind, f32) {
     }
 }
 
+/// CG 显隐只做透明度渐变：任何非 none 过渡都按其时长淡入淡出
+fn cg_fade_duration(transition: Option<&Transition>) -> Option<f32> {
+    match resolve_transition(transition?) {
+        (TransitionKind::None, _) => None,
+        (_, duration) => Some(duration),
+    }
+}
+
 pub struct BatchOutput {
     pub result: ExecuteResult,
     pub audio_commands: Vec<AudioCommand>,
     pub scene_effect_request: Option<SceneEffectRequest>,
+    pub warnings: Vec<String>,
+    pub transition_events: Vec<TransitionEvent>,
+    /// timeline 中尚未到时的命令（按延时升序），由调用方按时间执行
+    pub scheduled: Vec<ScheduledCommand>,
 }
 
 #[derive(Debug)]
@@ -214,45 +392,99 @@ impl CommandExecutor {
         }
     }
 
+    /// 执行单条命令（不检查资源存在性）
     pub fn execute(
         &mut self,
         cmd: &Command,
         rs: &mut RenderState,
         manifest: &Manifest,
-    ) -> ExecuteResult {
+    ) -> Result<ExecuteResult, ExecuteError> {
+        self.execute_with(cmd, rs, manifest, &|_| true)
+    }
+
+    /// 执行单条命令；`resource_exists` 用于预检查过渡遮罩等可选资源
+    pub fn execute_with(
+        &mut self,
+        cmd: &Command,
+        rs: &mut RenderState,
+        manifest: &Manifest,
+        resource_exists: &dyn Fn(&str) -> bool,
+    ) -> Result<ExecuteResult, ExecuteError> {
         self.last_output = CommandOutput::default();
-        match cmd {
-            Command::ShowBackground { .. } | Command::ChangeScene { .. } => {
-                self.execute_background(cmd, rs)
+        check_preconditions(cmd)?;
+        Ok(match cmd {
+            Command::ShowBackground { .. }
+            | Command::ChangeScene { .. }
+            | Command::FillColor { .. } => self.execute_background(cmd, rs, resource_exists),
+            Command::SetBackgroundLayers { layers } => {
+                rs.set_background_layers(layers.clone());
+                ExecuteResult::Ok
+            }
+            Command::ShowCg { path, transition } => {
+                rs.show_cg(path.clone(), cg_fade_duration(transition.as_ref()));
+                ExecuteResult::Ok
+            }
+            Command::HideCg { transition } => {
+                rs.hide_cg(cg_fade_duration(transition.as_ref()));
+                ExecuteResult::Ok
             }
             Command::ShowCharacter { .. }
             | Command::HideCharacter { .. }
+            | Command::ShowBubble { .. }
             | Command::ClearCharacters => self.execute_character(cmd, rs, manifest),
             Command::ShowText { .. }
             | Command::ExtendText { .. }
             | Command::TextBoxHide
             | Command::TextBoxShow
+            | Command::TextBoxAutoHide
             | Command::TextBoxClear
+            | Command::SetTextboxStyle { .. }
             | Command::SetTextMode(_) => self.execute_text(cmd, rs),
             Command::PresentChoices { .. } => self.execute_choices(cmd, rs),
             Command::PlayBgm { .. }
             | Command::StopBgm { .. }
             | Command::BgmDuck
             | Command::BgmUnduck
+            | Command::SetBgmVolume { .. }
             | Command::PlaySfx { .. } => self.execute_audio(cmd),
             Command::ChapterMark { .. } | Command::TitleCard { .. } => {
                 self.execute_effects(cmd, rs)
             }
             Command::SceneEffect { .. } => self.execute_scene_effect(cmd),
+            Command::Rumble { .. } => self.execute_rumble(cmd, rs),
+            Command::SetWeather { kind, intensity } => {
+                rs.set_weather(*kind, *intensity as f32);
+                ExecuteResult::Ok
+            }
+            Command::SetFilter { kind, intensity } => {
+                rs.set_filter(*kind, *intensity as f32);
+                ExecuteResult::Ok
+            }
+            Command::SetRimLight { alias, intensity } => {
+                if !rs.set_rim_light(alias, *intensity as f32) {
+                    tracing::warn!(alias = %alias, "rimLight 目标角色不在场，已忽略");
+                }
+                ExecuteResult::Ok
+            }
             Command::Cutscene { .. } | Command::FullRestart | Command::RequestUI { .. } => {
                 self.execute_control(cmd)
             }
-        }
+            Command::Timeline { steps } => {
+                // 单条执行时全部交给调用方调度；批次执行会先展开到时的动作
+                self.last_output.scheduled = steps.iter().map(ScheduledCommand::from).collect();
+                ExecuteResult::Ok
+            }
+        })
     }
 
     // ── 背景 / 场景切换 ──────────────────────────────────────────────────────
 
-    fn execute_background(&self, cmd: &Command, rs: &mut RenderState) -> ExecuteResult {
+    fn execute_background(
+        &mut self,
+        cmd: &Command,
+        rs: &mut RenderState,
+        resource_exists: &dyn Fn(&str) -> bool,
+    ) -> ExecuteResult {
         match cmd {
             Command::ShowBackground {
                 path, transition, ..
@@ -262,11 +494,7 @@ impl CommandExecutor {
                     match kind {
                         TransitionKind::None => rs.set_background(path.clone()),
                         _ => {
-                            rs.background_transition = Some(BackgroundTransition {
-                                old_background: rs.current_background.clone(),
-                                new_background: path.clone(),
-                                duration,
-                            });
+                            rs.begin_background_transition(path.clone(), duration);
                             rs.set_background(path.clone());
                         }
                     }
@@ -275,53 +503,64 @@ impl CommandExecutor {
                 }
                 ExecuteResult::Ok
             }
+            Command::FillColor { color, transition } => {
+                if let Some((kind, duration)) = transition.as_ref().map(resolve_transition)
+                    && !matches!(kind, TransitionKind::None)
+                {
+                    rs.begin_background_transition(color.to_hex(), duration);
+                }
+                rs.set_fill(*color);
+                ExecuteResult::Ok
+            }
             Command::ChangeScene {
                 path, transition, ..
             } => {
                 if let Some(t) = transition {
-                    let (kind, duration) = resolve_transition(t);
-                    match kind {
-                        TransitionKind::Dissolve | TransitionKind::Move => {
-                            rs.background_transition = Some(BackgroundTransition {
-                                old_background: rs.current_background.clone(),
-                                new_background: path.clone(),
-                                duration,
-                            });
+                    let (mut kind, duration) = resolve_transition(t);
+                    // 遮罩缺失时降级为普通 Fade，避免前端加载失败
+                    if let TransitionKind::Rule { mask_path, .. } = &kind
+                        && (mask_path.is_empty() || !resource_exists(mask_path))
+                    {
+                        tracing::warn!(mask = %mask_path, "Rule 遮罩不存在，降级为 Fade");
+                        self.last_output
+                            .warnings
+                            .push(format!("过渡遮罩不存在，已改用淡入淡出：{mask_path}"));
+                        kind = TransitionKind::Fade;
+                    }
+                    let mask_kind = match kind {
+                        TransitionKind::Dissolve
+                        | TransitionKind::Move
+                        | TransitionKind::Crossfade => {
+                            rs.begin_background_transition(path.clone(), duration);
                             rs.set_background(path.clone());
+                            None
                         }
-                        TransitionKind::Fade => {
-                            rs.scene_transition = Some(SceneTransition {
-                                transition_type: SceneTransitionKind::Fade,
-                                phase: SceneTransitionPhaseState::FadeIn,
-                                duration,
-                                pending_background: Some(path.clone()),
-                            });
-                        }
-                        TransitionKind::FadeWhite => {
-                            rs.scene_transition = Some(SceneTransition {
-                                transition_type: SceneTransitionKind::FadeWhite,
-                                phase: SceneTransitionPhaseState::FadeIn,
-                                duration,
-                                pending_background: Some(path.clone()),
-                            });
-                        }
+                        TransitionKind::Fade => Some(SceneTransitionKind::Fade),
+                        TransitionKind::FadeWhite => Some(SceneTransitionKind::FadeWhite),
                         TransitionKind::Rule {
                             mask_path,
                             reversed,
                             ramp,
-                        } => {
-                            rs.scene_transition = Some(SceneTransition {
-                                transition_type: SceneTransitionKind::Rule {
-                                    mask_path,
-                                    reversed,
-                                    ramp,
-                                },
-                                phase: SceneTransitionPhaseState::FadeIn,
-                                duration,
-                                pending_background: Some(path.clone()),
-                            });
+                        } => Some(SceneTransitionKind::Rule {
+                            mask_path,
+                            reversed,
+                            ramp,
+                        }),
+                        TransitionKind::None => {
+                            rs.set_background(path.clone());
+                            None
                         }
-                        TransitionKind::None => rs.set_background(path.clone()),
+                    };
+                    if let Some(transition_type) = mask_kind {
+                        rs.scene_transition = Some(SceneTransition {
+                            transition_type,
+                            phase: SceneTransitionPhaseState::FadeIn,
+                            duration,
+                            pending_background: Some(path.clone()),
+                        });
+                        self.last_output
+                            .transition_events
+                            .push(TransitionEvent::Started);
                     }
                 } else {
                     rs.set_background(path.clone());
@@ -346,6 +585,7 @@ impl CommandExecutor {
                 alias,
                 position,
                 transition,
+                idle,
             } => {
                 let (kind, duration) = transition
                     .as_ref()
@@ -359,10 +599,26 @@ impl CommandExecutor {
                 if let Some(c) = rs.visible_characters.get_mut(alias) {
                     let is_position_change = c.position != *position;
                     let is_same_texture = c.texture_path == *path;
+                    // 同别名同位置只换图：换表情，旧纹理淡出而非整体重新入场
+                    let is_expression_change = !is_same_texture
+                        && !is_position_change
+                        && !c.fading_out
+                        && matches!(kind, TransitionKind::Crossfade);
 
+                    c.crossfade = is_expression_change.then(|| ExpressionCrossfade {
+                        old_texture: c.texture_path.clone(),
+                        duration,
+                        elapsed: 0.0,
+                    });
                     c.texture_path = path.clone();
+                    if is_position_change {
+                        c.show_seq = rs.next_show_seq;
+                        rs.next_show_seq += 1;
+                    }
                     c.position = *position;
                     c.target_alpha = 1.0;
+                    // 淡出途中再次 show：取消移除，从当前 alpha 淡回
+                    c.fading_out = false;
                     c.pos_x = preset.x;
                     c.pos_y = preset.y;
                     c.anchor_x = group.anchor.x;
@@ -371,7 +627,7 @@ impl CommandExecutor {
 
                     if is_position_change && matches!(kind, TransitionKind::Move) {
                         c.transition_duration = Some(duration);
-                    } else if is_same_texture && is_position_change {
+                    } else if is_expression_change || (is_same_texture && is_position_change) {
                         c.transition_duration = None;
                         c.alpha = 1.0;
                     } else {
@@ -395,9 +651,18 @@ impl CommandExecutor {
                         c.target_alpha = 1.0;
                     }
                 }
+                CharacterAnimationCommand::from_show(alias, idle.as_deref()).apply(rs);
+                if let Some(bounce) =
+                    CharacterAnimationCommand::from_transition(alias, transition.as_ref())
+                {
+                    bounce.apply(rs);
+                }
+                rs.apply_auto_layout();
                 ExecuteResult::Ok
             }
             Command::HideCharacter { alias, transition } => {
+                // 气泡不随立绘淡出，隐藏时立即移除
+                rs.remove_bubble(alias);
                 if let Some(t) = transition {
                     let (_, duration) = resolve_transition(t);
                     if let Some(c) = rs.visible_characters.get_mut(alias) {
@@ -408,6 +673,11 @@ impl CommandExecutor {
                 } else {
                     rs.hide_character(alias);
                 }
+                rs.apply_auto_layout();
+                ExecuteResult::Ok
+            }
+            Command::ShowBubble { alias, content } => {
+                rs.set_bubble(alias.clone(), content.clone());
                 ExecuteResult::Ok
             }
             Command::ClearCharacters => {
@@ -420,13 +690,14 @@ impl CommandExecutor {
 
     // ── 文本框 ───────────────────────────────────────────────────────────────
 
-    fn execute_text(&self, cmd: &Command, rs: &mut RenderState) -> ExecuteResult {
+    fn execute_text(&mut self, cmd: &Command, rs: &mut RenderState) -> ExecuteResult {
         match cmd {
             Command::ShowText {
                 speaker,
                 content,
                 inline_effects,
                 no_wait,
+                text_speed,
             } => {
                 rs.start_typewriter(
                     speaker.clone(),
@@ -434,6 +705,7 @@ impl CommandExecutor {
                     inline_effects.clone(),
                     *no_wait,
                 );
+                rs.set_dialogue_speed_factor(*text_speed as f32);
                 if rs.text_mode == TextMode::NVL {
                     rs.nvl_entries.push(crate::render_state::NvlEntry {
                         speaker: speaker.clone(),
@@ -449,6 +721,23 @@ impl CommandExecutor {
                 inline_effects,
                 no_wait,
             } => {
+                if rs.dialogue.is_none() {
+                    // 清屏或选项之后没有可续接的对话：按旁白新起一句，不中断演出
+                    tracing::warn!(content = %content, "没有可续接的对话，按新对话显示");
+                    self.last_output.warnings.push(format!(
+                        "续接文本前没有正在显示的对话，已按新对话显示：{content}"
+                    ));
+                    return self.execute_text(
+                        &Command::ShowText {
+                            speaker: None,
+                            content: content.clone(),
+                            inline_effects: inline_effects.clone(),
+                            no_wait: *no_wait,
+                            text_speed: 1.0,
+                        },
+                        rs,
+                    );
+                }
                 rs.extend_dialogue(content.clone(), inline_effects.clone(), *no_wait);
                 ExecuteResult::WaitForClick
             }
@@ -458,12 +747,21 @@ impl CommandExecutor {
             }
             Command::TextBoxShow => {
                 rs.ui_visible = true;
+                rs.textbox_auto_hidden = false;
+                ExecuteResult::Ok
+            }
+            Command::TextBoxAutoHide => {
+                rs.textbox_auto_hidden = true;
                 ExecuteResult::Ok
             }
             Command::TextBoxClear => {
                 rs.clear_dialogue();
                 ExecuteResult::Ok
             }
+            Command::SetTextboxStyle { name } => {
+                rs.textbox_style = (name != "default").then(|| name.clone());
+                ExecuteResult::Ok
+            }
             Command::SetTextMode(mode) => {
                 if *mode == TextMode::ADV {
                     rs.nvl_entries.clear();
@@ -478,15 +776,27 @@ impl CommandExecutor {
     // ── 选项 ─────────────────────────────────────────────────────────────────
 
     fn execute_choices(&self, cmd: &Command, rs: &mut RenderState) -> ExecuteResult {
-        let Command::PresentChoices { choices, style } = cmd else {
+        let Command::PresentChoices {
+            choices,
+            style,
+            prompt,
+        } = cmd
+        else {
             unreachable!("execute_choices: unexpected command");
         };
-        rs.clear_dialogue();
+        // 有提示时先在对话框打出提示，打完后选项面板才出现（见 `ChoicePanel`）
+        match prompt {
+            Some(prompt) => rs.start_typewriter(None, prompt.clone(), Vec::new(), false),
+            None => rs.clear_dialogue(),
+        }
         let items = choices
             .iter()
             .map(|c| ChoiceItem {
                 text: c.text.clone(),
                 target_label: c.target_label.clone(),
+                enabled: c.enabled,
+                disabled_reason: c.disabled_reason.clone(),
+                visited: c.visited,
             })
             .collect();
         rs.set_choices(items, style.clone());
@@ -509,6 +819,10 @@ impl CommandExecutor {
             },
             Command::BgmDuck => AudioCommand::BgmDuck,
             Command::BgmUnduck => AudioCommand::BgmUnduck,
+            Command::SetBgmVolume { volume, duration } => AudioCommand::SetBgmVolume {
+                volume: *volume as f32,
+                duration: *duration as f32,
+            },
             Command::PlaySfx { path } => AudioCommand::PlaySfx { path: path.clone() },
             _ => unreachable!("execute_audio: unexpected command"),
         });
@@ -543,6 +857,18 @@ impl CommandExecutor {
         ExecuteResult::Ok
     }
 
+    fn execute_rumble(&self, cmd: &Command, rs: &mut RenderState) -> ExecuteResult {
+        let Command::Rumble {
+            intensity,
+            duration,
+        } = cmd
+        else {
+            unreachable!("execute_rumble: unexpected command");
+        };
+        rs.request_rumble(*intensity as f32, *duration as f32);
+        ExecuteResult::Ok
+    }
+
     // ── 流程控制 ─────────────────────────────────────────────────────────────
 
     fn execute_control(&self, cmd: &Command) -> ExecuteResult {
@@ -560,17 +886,40 @@ impl CommandExecutor {
         }
     }
 
+    /// 执行一批命令（事务式）
+    ///
+    /// 执行前先校验全部命令的前置条件（失败只可能来自前置条件），任一命令不满足
+    /// 则整批不执行，`rs` 保持批次执行前的状态；无需复制整个 RenderState
+    /// （其中的 NVL 历史等随游戏进程增长）。
+    ///
+    /// timeline 中延时为 0 的动作按顺序并入本批次，其余放入 [`BatchOutput::scheduled`]。
     pub fn execute_batch(
         &mut self,
         cmds: &[Command],
         rs: &mut RenderState,
         manifest: &Manifest,
-    ) -> BatchOutput {
+        resource_exists: &dyn Fn(&str) -> bool,
+    ) -> Result<BatchOutput, ExecuteError> {
         let mut final_result = ExecuteResult::Ok;
         let mut audio_commands = Vec::new();
         let mut scene_effect_request = None;
+        let mut warnings = Vec::new();
+        let mut transition_events = Vec::new();
+        let (cmds, scheduled) = expand_timelines(cmds);
+        if let Err(error) = cmds.iter().copied().try_for_each(check_preconditions) {
+            self.last_output = CommandOutput::default();
+            return Err(error);
+        }
         for cmd in cmds {
-            let result = self.execute(cmd, rs, manifest);
+            let result = match self.execute_with(cmd, rs, manifest, resource_exists) {
+                Ok(result) => result,
+                Err(error) => {
+                    self.last_output = CommandOutput::default();
+                    return Err(error);
+                }
+            };
+            warnings.append(&mut self.last_output.warnings);
+            transition_events.append(&mut self.last_output.transition_events);
             if let Some(audio) = self.last_output.audio_command.take() {
                 audio_commands.push(audio);
             }
@@ -581,11 +930,45 @@ impl CommandExecutor {
                 final_result = result;
             }
         }
-        BatchOutput {
+        Ok(BatchOutput {
             result: final_result,
             audio_commands,
             scene_effect_request,
+            warnings,
+            transition_events,
+            scheduled,
+        })
+    }
+}
+
+/// 展开批次中的 timeline：立即执行的命令按原顺序返回，延时命令按延时升序另列
+fn expand_timelines(cmds: &[Command]) -> (Vec<&Command>, Vec<ScheduledCommand>) {
+    let mut immediate = Vec::with_capacity(cmds.len());
+    let mut scheduled = Vec::new();
+    for cmd in cmds {
+        let Command::Timeline { steps } = cmd else {
+            immediate.push(cmd);
+            continue;
+        };
+        for step in steps {
+            if step.delay <= 0.0 {
+                immediate.push(&step.command);
+            } else {
+                scheduled.push(ScheduledCommand::from(step));
+            }
+        }
+    }
+    scheduled.sort_by(|a, b| a.delay.total_cmp(&b.delay));
+    (immediate, scheduled)
+}
+
+/// 执行前校验：失败时命令不会修改 RenderState
+fn check_preconditions(cmd: &Command) -> Result<(), ExecuteError> {
+    match cmd {
+        Command::PresentChoices { choices, .. } if choices.is_empty() => {
+            Err(ExecuteError::EmptyChoices)
         }
+        _ => Ok(()),
     }
 }
 
@@ -594,7 +977,7 @@ mod tests {
     use super::*;
     use crate::manifest::Manifest;
     use crate::render_state::RenderState;
-    use vn_runtime::command::{Command, Position, Transition};
+    use vn_runtime::command::{Color, Command, Position, Transition};
 
     fn setup() -> (CommandExecutor, RenderState, Manifest) {
         (
@@ -612,12 +995,208 @@ mod tests {
             content: "Hello world".to_string(),
             inline_effects: vec![],
             no_wait: false,
+            text_speed: 0.5,
         };
-        let result = exec.execute(&cmd, &mut rs, &manifest);
+        let result = exec.execute(&cmd, &mut rs, &manifest).unwrap();
         assert_eq!(result, ExecuteResult::WaitForClick);
         let dialogue = rs.dialogue.expect("dialogue should be set");
         assert_eq!(dialogue.speaker.as_deref(), Some("Alice"));
         assert_eq!(dialogue.content, "Hello world");
+        assert_eq!(dialogue.speed_factor, 0.5);
+    }
+
+    #[test]
+    fn present_choices_with_prompt_types_prompt_into_dialogue() {
+        let (mut exec, mut rs, manifest) = setup();
+        let choice = |text: &str| vn_runtime::command::Choice {
+            text: text.to_string(),
+            target_label: text.to_string(),
+            enabled: true,
+            disabled_reason: None,
+            visited: false,
+        };
+        let cmd = Command::PresentChoices {
+            style: None,
+            choices: vec![choice("sea"), choice("mountain")],
+            prompt: Some("要去哪里呢？".to_string()),
+        };
+        let result = exec.execute(&cmd, &mut rs, &manifest).unwrap();
+        assert_eq!(result, ExecuteResult::WaitForChoice { choice_count: 2 });
+        let dialogue = rs.dialogue.as_ref().expect("prompt shown in dialogue");
+        assert_eq!(dialogue.speaker, None);
+        assert_eq!(dialogue.content, "要去哪里呢？");
+        assert!(!rs.is_dialogue_complete(), "提示按打字机逐字显示");
+        assert_eq!(rs.choices.as_ref().unwrap().choices.len(), 2);
+
+        // 无提示时清空对话框
+        let cmd = Command::PresentChoices {
+            style: None,
+            choices: vec![choice("sea")],
+            prompt: None,
+        };
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
+        assert!(rs.dialogue.is_none());
+    }
+
+    #[test]
+    fn auto_hidden_textbox_reappears_with_next_dialogue() {
+        let (mut exec, mut rs, manifest) = setup();
+        exec.execute(&Command::TextBoxAutoHide, &mut rs, &manifest)
+            .unwrap();
+        assert!(!rs.textbox_visible());
+        assert!(rs.ui_visible, "自动隐藏不应改动显式可见性");
+
+        let show_text = Command::ShowText {
+            speaker: None,
+            content: "继续".to_string(),
+            inline_effects: vec![],
+            no_wait: false,
+            text_speed: 1.0,
+        };
+        exec.execute(&show_text, &mut rs, &manifest).unwrap();
+        assert!(rs.textbox_visible());
+
+        // 显式 textBoxHide 不随对话恢复
+        exec.execute(&Command::TextBoxHide, &mut rs, &manifest)
+            .unwrap();
+        exec.execute(&show_text, &mut rs, &manifest).unwrap();
+        assert!(!rs.textbox_visible());
+    }
+
+    #[test]
+    fn change_scene_rule_with_missing_mask_falls_back_to_fade() {
+        let (mut exec, mut rs, manifest) = setup();
+        let cmd = Command::ChangeScene {
+            path: "bg/room.png".to_string(),
+            transition: Some(Transition::with_named_args(
+                "rule",
+                vec![(
+                    Some("mask".to_string()),
+                    TransitionArg::String("masks/missing.png".to_string()),
+                )],
+            )),
+        };
+
+        let result = exec
+            .execute_with(&cmd, &mut rs, &manifest, &|_| false)
+            .unwrap();
+        assert_eq!(result, ExecuteResult::Ok);
+        let st = rs.scene_transition.as_ref().expect("scene transition");
+        assert_eq!(st.transition_type, SceneTransitionKind::Fade);
+        assert_eq!(st.pending_background.as_deref(), Some("bg/room.png"));
+        assert_eq!(exec.last_output.warnings.len(), 1);
+        assert!(exec.last_output.warnings[0].contains("masks/missing.png"));
+
+        // 遮罩存在时保持 Rule
+        let mut rs = RenderState::new();
+        exec.execute_with(&cmd, &mut rs, &manifest, &|p| p == "masks/missing.png")
+            .unwrap();
+        assert!(matches!(
+            rs.scene_transition.unwrap().transition_type,
+            SceneTransitionKind::Rule { .. }
+        ));
+        assert!(exec.last_output.warnings.is_empty());
+    }
+
+    #[test]
+    fn failed_batch_leaves_render_state_untouched() {
+        let (mut exec, mut rs, manifest) = setup();
+        rs.set_background("bg/room.png".to_string());
+        let before = serde_json::to_value(&rs).unwrap();
+        let cmds = vec![
+            Command::ShowBackground {
+                path: "bg/street.png".to_string(),
+                transition: None,
+            },
+            Command::ShowText {
+                speaker: None,
+                content: "Hello".to_string(),
+                inline_effects: vec![],
+                no_wait: false,
+                text_speed: 1.0,
+            },
+            Command::PresentChoices {
+                style: None,
+                choices: vec![],
+                prompt: None,
+            },
+        ];
+
+        let err = exec
+            .execute_batch(&cmds, &mut rs, &manifest, &|_| true)
+            .err()
+            .expect("empty choices should fail the batch");
+        assert_eq!(err, ExecuteError::EmptyChoices);
+        assert_eq!(serde_json::to_value(&rs).unwrap(), before);
+        assert!(exec.last_output.warnings.is_empty());
+
+        // 去掉失败命令后整批提交
+        let output = exec
+            .execute_batch(&cmds[..2], &mut rs, &manifest, &|_| true)
+            .unwrap();
+        assert_eq!(output.result, ExecuteResult::WaitForClick);
+        assert_eq!(rs.current_background.as_deref(), Some("bg/street.png"));
+        assert!(rs.dialogue.is_some());
+    }
+
+    #[test]
+    fn extend_text_after_clear_warns_and_starts_new_dialogue() {
+        let (mut exec, mut rs, manifest) = setup();
+        let show = Command::ShowText {
+            speaker: Some("Alice".to_string()),
+            content: "Hello".to_string(),
+            inline_effects: vec![],
+            no_wait: false,
+            text_speed: 1.0,
+        };
+        exec.execute(&show, &mut rs, &manifest).unwrap();
+        exec.execute(&Command::TextBoxClear, &mut rs, &manifest)
+            .unwrap();
+        assert!(rs.dialogue.is_none());
+
+        let extend = Command::ExtendText {
+            content: "more".to_string(),
+            inline_effects: vec![],
+            no_wait: false,
+        };
+        assert_eq!(
+            exec.execute(&extend, &mut rs, &manifest),
+            Ok(ExecuteResult::WaitForClick)
+        );
+        let dialogue = rs
+            .dialogue
+            .as_ref()
+            .expect("extend should start new dialogue");
+        assert_eq!(dialogue.speaker, None);
+        assert_eq!(dialogue.content, "more");
+        assert_eq!(exec.last_output.warnings.len(), 1);
+    }
+
+    #[test]
+    fn show_cg_fades_in_and_hide_cg_fades_out() {
+        let (mut exec, mut rs, manifest) = setup();
+        rs.set_background("bg/room.png".to_string());
+        let cmd = Command::ShowCg {
+            path: "cg/cg1.jpg".to_string(),
+            transition: Some(Transition::simple("fade")),
+        };
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
+        let cg = rs.cg.clone().expect("cg should be shown");
+        assert_eq!(cg.path, "cg/cg1.jpg");
+        assert_eq!(cg.alpha, 0.0);
+        assert_eq!(rs.current_background.as_deref(), Some("bg/room.png"));
+
+        rs.update_cg(0.5);
+        assert_eq!(rs.cg.as_ref().unwrap().alpha, 1.0);
+        assert!(!rs.is_cg_transitioning());
+
+        let hide = Command::HideCg {
+            transition: Some(Transition::simple("dissolve")),
+        };
+        exec.execute(&hide, &mut rs, &manifest).unwrap();
+        assert!(rs.cg.as_ref().unwrap().hiding);
+        rs.update_cg(0.3);
+        assert!(rs.cg.is_none());
     }
 
     #[test]
@@ -628,12 +1207,37 @@ mod tests {
             path: "bg/new.png".to_string(),
             transition: Some(Transition::simple("dissolve")),
         };
-        exec.execute(&cmd, &mut rs, &manifest);
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
         assert_eq!(rs.current_background.as_deref(), Some("bg/new.png"));
         let bt = rs.background_transition.expect("should be set");
         assert_eq!(bt.old_background.as_deref(), Some("bg/old.png"));
     }
 
+    #[test]
+    fn fill_color_replaces_background_and_fades_from_old_image() {
+        let (mut exec, mut rs, manifest) = setup();
+        rs.set_background("bg/old.png".to_string());
+        let cmd = Command::FillColor {
+            color: Color::BLACK,
+            transition: Some(Transition::simple("fade")),
+        };
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
+        assert_eq!(rs.background_fill, Some(Color::BLACK));
+        assert_eq!(rs.current_background, None);
+        let bt = rs.background_transition.clone().expect("should be set");
+        assert_eq!(bt.old_background.as_deref(), Some("bg/old.png"));
+        assert_eq!(bt.new_background, "#000000");
+
+        // 切回图片背景时清除纯色
+        let cmd = Command::ShowBackground {
+            path: "bg/new.png".to_string(),
+            transition: None,
+        };
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
+        assert_eq!(rs.background_fill, None);
+        assert_eq!(rs.current_background.as_deref(), Some("bg/new.png"));
+    }
+
     #[test]
     fn show_character_adds_to_visible() {
         let (mut exec, mut rs, manifest) = setup();
@@ -642,11 +1246,256 @@ mod tests {
             alias: "alice".to_string(),
             position: Position::Center,
             transition: None,
+            idle: None,
         };
-        exec.execute(&cmd, &mut rs, &manifest);
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
         assert!(rs.visible_characters.contains_key("alice"));
     }
 
+    #[test]
+    fn same_alias_new_texture_crossfades_instead_of_reentering() {
+        let (mut exec, mut rs, manifest) = setup();
+        let show = |path: &str, transition: Option<Transition>| Command::ShowCharacter {
+            path: path.to_string(),
+            alias: "royu".to_string(),
+            position: Position::Center,
+            transition,
+            idle: None,
+        };
+        exec.execute(&show("char/royu/normal.png", None), &mut rs, &manifest)
+            .unwrap();
+
+        exec.execute(
+            &show("char/royu/smile.png", Some(Transition::simple("crossfade"))),
+            &mut rs,
+            &manifest,
+        )
+        .unwrap();
+
+        let sprite = &rs.visible_characters["royu"];
+        assert_eq!(sprite.texture_path, "char/royu/smile.png");
+        // 不重新入场：整体保持不透明，无 alpha 过渡
+        assert_eq!(sprite.alpha, 1.0);
+        assert_eq!(sprite.transition_duration, None);
+        let crossfade = sprite.crossfade.as_ref().expect("应触发表情交叉淡化");
+        assert_eq!(crossfade.old_texture, "char/royu/normal.png");
+        assert_eq!(crossfade.duration, 0.2);
+
+        // 同图再 show 不触发交叉淡化
+        exec.execute(
+            &show("char/royu/smile.png", Some(Transition::simple("crossfade"))),
+            &mut rs,
+            &manifest,
+        )
+        .unwrap();
+        assert_eq!(rs.visible_characters["royu"].crossfade, None);
+    }
+
+    #[test]
+    fn idle_argument_parses_into_animation_command() {
+        assert_eq!(
+            CharacterAnimationCommand::from_show("royu", Some("breathe")),
+            CharacterAnimationCommand::Idle {
+                alias: "royu".to_string(),
+                kind: IdleKind::Breathe,
+            }
+        );
+        assert_eq!(
+            CharacterAnimationCommand::from_show("royu", Some("SWAY")),
+            CharacterAnimationCommand::Idle {
+                alias: "royu".to_string(),
+                kind: IdleKind::Sway,
+            }
+        );
+        for idle in [None, Some("spin")] {
+            assert_eq!(
+                CharacterAnimationCommand::from_show("royu", idle),
+                CharacterAnimationCommand::StopIdle {
+                    alias: "royu".to_string(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn bounce_transition_parses_into_animation_command() {
+        let bounce = |args: Vec<(Option<String>, TransitionArg)>| {
+            let transition = Transition::with_named_args("bounce", args);
+            CharacterAnimationCommand::from_transition("royu", Some(&transition))
+        };
+        let expected = |height: f32, times: u32| {
+            Some(CharacterAnimationCommand::Bounce {
+                alias: "royu".to_string(),
+                height,
+                times,
+            })
+        };
+
+        // bounce(30, 2)
+        assert_eq!(
+            bounce(vec![
+                (None, TransitionArg::Number(30.0)),
+                (None, TransitionArg::Number(2.0)),
+            ]),
+            expected(30.0, 2)
+        );
+        // 命名参数、缺省值与截断
+        assert_eq!(
+            bounce(vec![(Some("times".into()), TransitionArg::Number(3.0))]),
+            expected(20.0, 3)
+        );
+        assert_eq!(bounce(vec![]), expected(20.0, 1));
+        assert_eq!(
+            bounce(vec![
+                (None, TransitionArg::Number(999.0)),
+                (None, TransitionArg::Number(0.0)),
+            ]),
+            expected(200.0, 1)
+        );
+        // 非 bounce 过渡不产生弹跳
+        let dissolve = Transition::simple("dissolve");
+        assert_eq!(
+            CharacterAnimationCommand::from_transition("royu", Some(&dissolve)),
+            None
+        );
+        assert_eq!(
+            CharacterAnimationCommand::from_transition("royu", None),
+            None
+        );
+    }
+
+    #[test]
+    fn show_with_bounce_starts_decaying_hops_without_fade() {
+        let (mut exec, mut rs, manifest) = setup();
+        let cmd = Command::ShowCharacter {
+            path: "char/royu/normal.png".to_string(),
+            alias: "royu".to_string(),
+            position: Position::Center,
+            transition: Some(Transition::with_args(
+                "Bounce",
+                vec![TransitionArg::Number(30.0), TransitionArg::Number(2.0)],
+            )),
+            idle: None,
+        };
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
+
+        let sprite = &rs.visible_characters["royu"];
+        assert_eq!(sprite.alpha, 1.0);
+        assert_eq!(sprite.transition_duration, None);
+        let mut bounce = sprite.bounce.clone().expect("应开始弹跳");
+        assert_eq!((bounce.height, bounce.times), (30.0, 2));
+
+        // 第一跳顶点约 30px，第二跳顶点减半，结束后归零
+        let hop = bounce.duration() / 2.0;
+        bounce.advance(hop / 2.0);
+        assert!((bounce.offset_y() + 30.0).abs() < 0.01);
+        bounce.advance(hop);
+        assert!((bounce.offset_y() + 15.0).abs() < 0.01);
+        assert!(bounce.advance(hop));
+        assert_eq!(bounce.offset_y(), 0.0);
+    }
+
+    #[test]
+    fn show_with_idle_loops_until_reshown_without_it() {
+        let (mut exec, mut rs, manifest) = setup();
+        let show = |idle: Option<&str>, position| Command::ShowCharacter {
+            path: "char/royu/normal.png".to_string(),
+            alias: "royu".to_string(),
+            position,
+            transition: None,
+            idle: idle.map(str::to_string),
+        };
+
+        exec.execute(&show(Some("breathe"), Position::Center), &mut rs, &manifest)
+            .unwrap();
+        assert_eq!(rs.visible_characters["royu"].idle, Some(IdleKind::Breathe));
+
+        exec.execute(&show(None, Position::Left), &mut rs, &manifest)
+            .unwrap();
+        assert_eq!(rs.visible_characters["royu"].idle, None);
+    }
+
+    #[test]
+    fn textbox_style_is_recorded_and_default_resets_it() {
+        let (mut exec, mut rs, manifest) = setup();
+        let style = |name: &str| Command::SetTextboxStyle {
+            name: name.to_string(),
+        };
+        exec.execute(&style("memory"), &mut rs, &manifest).unwrap();
+        assert_eq!(rs.textbox_style.as_deref(), Some("memory"));
+        exec.execute(&style("default"), &mut rs, &manifest).unwrap();
+        assert_eq!(rs.textbox_style, None);
+    }
+
+    #[test]
+    fn rumble_bumps_request_id() {
+        let (mut exec, mut rs, manifest) = setup();
+        let cmd = Command::Rumble {
+            intensity: 0.8,
+            duration: 0.5,
+        };
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
+        let rumble = rs.rumble.expect("rumble should be requested");
+        assert_eq!(rumble.id, 2);
+        assert_eq!(rumble.intensity, 0.8);
+    }
+
+    #[test]
+    fn set_weather_creates_and_clears_overlay() {
+        let (mut exec, mut rs, manifest) = setup();
+        exec.execute(
+            &Command::SetWeather {
+                kind: vn_runtime::WeatherKind::Snow,
+                intensity: 0.5,
+            },
+            &mut rs,
+            &manifest,
+        )
+        .unwrap();
+        let weather = rs.weather.as_ref().expect("weather should be set");
+        assert_eq!(weather.kind, vn_runtime::WeatherKind::Snow);
+        assert_eq!(weather.particles.len(), weather.target_count());
+
+        exec.execute(
+            &Command::SetWeather {
+                kind: vn_runtime::WeatherKind::None,
+                intensity: 0.5,
+            },
+            &mut rs,
+            &manifest,
+        )
+        .unwrap();
+        assert!(rs.weather.is_none());
+    }
+
+    #[test]
+    fn set_filter_applies_css_and_none_clears() {
+        let (mut exec, mut rs, manifest) = setup();
+        exec.execute(
+            &Command::SetFilter {
+                kind: vn_runtime::FilterKind::Sepia,
+                intensity: 0.6,
+            },
+            &mut rs,
+            &manifest,
+        )
+        .unwrap();
+        let filter = rs.screen_filter.as_ref().expect("filter should be set");
+        assert_eq!(filter.css(), "sepia(0.6)");
+
+        exec.execute(
+            &Command::SetFilter {
+                kind: vn_runtime::FilterKind::None,
+                intensity: 1.0,
+            },
+            &mut rs,
+            &manifest,
+        )
+        .unwrap();
+        assert!(rs.screen_filter.is_none());
+    }
+
     #[test]
     fn play_bgm_produces_audio_command() {
         let (mut exec, mut rs, manifest) = setup();
@@ -654,7 +1503,7 @@ mod tests {
             path: "bgm/theme.ogg".to_string(),
             looping: true,
         };
-        exec.execute(&cmd, &mut rs, &manifest);
+        exec.execute(&cmd, &mut rs, &manifest).unwrap();
         assert!(matches!(
             exec.last_output.audio_command,
             Some(AudioCommand::PlayBgm { .. })
@@ -2,8 +2,10 @@ mod confirm_dialog;
 mod game_menu_frame;
 mod skip_indicator;
 mod toast;
+mod var_watch;
 
 pub use confirm_dialog::{ConfirmDialog, PendingConfirm};
 pub use game_menu_frame::GameMenuFrame;
 pub use skip_indicator::SkipIndicator;
-pub use toast::{ToastLayer, ToastQueue};
+pub use toast::{ToastLayer, ToastQueue, ToastType};
+pub use var_watch::VarWatchOverlay;
@@ -1,6 +1,6 @@
 use dioxus::prelude::*;
 
-/// Toast 消息类型（Warning/Info 暂无调用方，保留为完整 API）
+/// Toast 消息类型（Info 暂无调用方，保留为完整 API）
 #[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub enum ToastType {
@@ -16,6 +16,8 @@ pub struct ToastMessage {
     pub text: String,
     pub toast_type: ToastType,
     pub id: u64,
+    /// 详情（非空时 Toast 可点击，打开详情面板）
+    pub details: Vec<String>,
 }
 
 /// Toast 队列状态
@@ -23,16 +25,29 @@ pub struct ToastMessage {
 pub struct ToastQueue {
     pub messages: Vec<ToastMessage>,
     next_id: u64,
+    /// 当前打开的详情面板（标题, 逐行内容）
+    pub detail_panel: Option<(String, Vec<String>)>,
 }
 
 // toast 各类型便捷方法：供未来 UI 屏幕调用，暂无调用方但保留为公开 API。
 #[allow(dead_code)]
 impl ToastQueue {
     pub fn push(&mut self, text: impl Into<String>, toast_type: ToastType) {
+        self.push_with_details(text, toast_type, Vec::new());
+    }
+
+    /// 推送带详情的 Toast，点击后打开详情面板
+    pub fn push_with_details(
+        &mut self,
+        text: impl Into<String>,
+        toast_type: ToastType,
+        details: Vec<String>,
+    ) {
         self.messages.push(ToastMessage {
             text: text.into(),
             toast_type,
             id: self.next_id,
+            details,
         });
         self.next_id += 1;
     }
@@ -59,17 +74,36 @@ impl ToastQueue {
 pub fn ToastLayer() -> Element {
     let mut toast_queue = use_context::<Signal<ToastQueue>>();
     let messages = toast_queue.read().messages.clone();
+    let detail_panel = toast_queue.read().detail_panel.clone();
 
-    if messages.is_empty() {
+    if messages.is_empty() && detail_panel.is_none() {
         return rsx! {};
     }
 
     rsx! {
+        if let Some((title, lines)) = detail_panel {
+            div { class: "toast-detail",
+                div { class: "toast-detail__panel",
+                    div { class: "toast-detail__title", "{title}" }
+                    div { class: "toast-detail__body",
+                        for (i, line) in lines.iter().enumerate() {
+                            pre { key: "{i}", class: "toast-detail__line", "{line}" }
+                        }
+                    }
+                    button {
+                        class: "toast-detail__close",
+                        onclick: move |_| toast_queue.write().detail_panel = None,
+                        "关闭"
+                    }
+                }
+            }
+        }
         div { class: "toast-layer",
             for msg in &messages {
                 {
                     let id = msg.id;
                     let text = msg.text.clone();
+                    let text_for_panel = msg.text.clone();
                     let modifier = match msg.toast_type {
                         ToastType::Info => "toast--info",
                         ToastType::Success => "toast--success",
@@ -77,13 +111,23 @@ pub fn ToastLayer() -> Element {
                         ToastType::Error => "toast--error",
                     };
 
+                    let details = msg.details.clone();
+                    let clickable = if details.is_empty() { "" } else { " toast--clickable" };
+
                     rsx! {
                         div {
                             key: "{id}",
-                            class: "toast {modifier}",
+                            class: "toast {modifier}{clickable}",
                             onanimationend: move |_| {
                                 toast_queue.write().remove(id);
                             },
+                            onclick: move |_| {
+                                if !details.is_empty() {
+                                    let mut queue = toast_queue.write();
+                                    queue.detail_panel = Some((text_for_panel.clone(), details.clone()));
+                                    queue.remove(id);
+                                }
+                            },
                             "{text}"
                         }
                     }
@@ -0,0 +1,49 @@
+use dioxus::prelude::*;
+use vn_runtime::state::VarValue;
+
+use crate::render_state::RenderState;
+use crate::state::AppState;
+
+fn format_var(value: &VarValue) -> String {
+    match value {
+        VarValue::Int(n) => n.to_string(),
+        VarValue::Float(f) => f.to_string(),
+        VarValue::String(s) => format!("\"{s}\""),
+        VarValue::Bool(b) => b.to_string(),
+    }
+}
+
+/// 变量监视浮层（F1 切换，仅 debug 构建）
+///
+/// 订阅 `render_state` 以随 tick 刷新，按变量名排序列出当前会话变量。
+#[component]
+pub fn VarWatchOverlay(render_state: Signal<RenderState>) -> Element {
+    // 只为登记对 render_state 的订阅，读守卫立即释放，不需要其中的数据
+    let _ = render_state.read();
+    let app_state = use_context::<AppState>();
+
+    let vars: Vec<(String, String)> = app_state
+        .inner
+        .lock()
+        .ok()
+        .and_then(|inner| {
+            inner.runtime.as_ref().map(|rt| {
+                rt.iter_vars()
+                    .map(|(name, value)| (name.to_string(), format_var(value)))
+                    .collect()
+            })
+        })
+        .unwrap_or_default();
+
+    rsx! {
+        div { class: "var-watch",
+            div { class: "var-watch__title", "变量监视 ({vars.len()})" }
+            for (name, value) in vars {
+                div { key: "{name}", class: "var-watch__row",
+                    span { class: "var-watch__name", "{name}" }
+                    span { class: "var-watch__value", "{value}" }
+                }
+            }
+        }
+    }
+}
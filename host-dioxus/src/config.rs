@@ -3,6 +3,7 @@
 //! 开发期与运行期共用的严格配置契约。
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::info;
@@ -34,6 +35,8 @@ pub struct AppConfig {
     pub debug: DebugConfig,
     pub audio: AudioConfig,
     pub resources: ResourceConfig,
+    #[serde(default)]
+    pub i18n: I18nConfig,
 }
 
 /// 窗口配置
@@ -44,6 +47,9 @@ pub struct WindowConfig {
     pub height: u32,
     pub title: String,
     pub fullscreen: bool,
+    /// 玩家能否拖动调整窗口大小；开启后退出时的窗口尺寸会写入用户设置，下次启动恢复
+    #[serde(default)]
+    pub resizable: bool,
 }
 
 /// 调试配置
@@ -61,6 +67,28 @@ pub struct DebugConfig {
     /// 优先级：环境变量 `RING_DEBUG_PORT` > 此字段。
     #[serde(default = "default_debug_port")]
     pub debug_server_port: u16,
+    /// 开发者快捷键总开关。`None` 表示使用编译默认值（debug=on, release=off）。
+    #[serde(default)]
+    pub enable_shortcuts: Option<bool>,
+    /// 开发者快捷键映射：键名（同 `KeyboardEvent.key`，如 `"F5"`）→ 调试动作
+    #[serde(default = "default_debug_shortcuts")]
+    pub shortcuts: BTreeMap<String, DebugAction>,
+}
+
+/// 开发者快捷键可触发的调试动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugAction {
+    /// 切换变量监视浮层
+    ToggleVarWatch,
+    /// 立即完成进行中的过渡
+    SkipTransitions,
+    /// 回到标题画面（不写 Continue 存档）
+    ReturnToTitle,
+    /// 快速存档
+    QuickSave,
+    /// 快速读档
+    QuickLoad,
 }
 
 /// 音频配置
@@ -71,6 +99,41 @@ pub struct AudioConfig {
     pub bgm_volume: f32,
     pub sfx_volume: f32,
     pub muted: bool,
+    /// silent 模式：不向前端发出任何播放请求（CI / 无声卡环境）。
+    /// 优先级：环境变量 `RING_AUDIO_SILENT` > 此字段。
+    #[serde(default)]
+    pub silent: bool,
+}
+
+/// 多语言配置
+///
+/// 语言表位于 `{tables_dir}/{locale}.json`，对话中的 `@key` 按当前语言查找。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct I18nConfig {
+    /// 默认语言（缺 key 时的回退语言）
+    pub default_locale: String,
+    /// 当前语言；`None` 时使用默认语言
+    pub locale: Option<String>,
+    /// 语言表目录（相对于资源根目录）
+    pub tables_dir: String,
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            default_locale: "zh".to_string(),
+            locale: None,
+            tables_dir: "i18n".to_string(),
+        }
+    }
+}
+
+impl I18nConfig {
+    /// 实际使用的当前语言
+    pub fn current_locale(&self) -> &str {
+        self.locale.as_deref().unwrap_or(&self.default_locale)
+    }
 }
 
 /// 资源配置
@@ -78,6 +141,15 @@ pub struct AudioConfig {
 #[serde(deny_unknown_fields)]
 pub struct ResourceConfig {
     pub texture_cache_size_mb: u32,
+    /// 音频缓存大小（MB），按文件字节数估算
+    #[serde(default = "default_audio_cache_size_mb")]
+    pub audio_cache_size_mb: u32,
+    /// 纹理缓存键忽略路径大小写
+    #[serde(default)]
+    pub fold_path_case: bool,
+    /// 纹理缓存键统一 Unicode NFC 形式
+    #[serde(default)]
+    pub unicode_nfc_paths: bool,
 }
 
 impl Default for AppConfig {
@@ -95,6 +167,7 @@ impl Default for AppConfig {
             debug: DebugConfig::default(),
             audio: AudioConfig::default(),
             resources: ResourceConfig::default(),
+            i18n: I18nConfig::default(),
         }
     }
 }
@@ -106,6 +179,20 @@ impl Default for WindowConfig {
             height: 1080,
             title: "Ring VN Engine".to_string(),
             fullscreen: false,
+            resizable: false,
+        }
+    }
+}
+
+/// 启动窗口的默认逻辑尺寸（没有可恢复的尺寸时使用）
+pub const DEFAULT_WINDOW_SIZE: (u32, u32) = (1280, 720);
+
+impl WindowConfig {
+    /// 启动时的窗口尺寸：可调整大小且有保存的尺寸时恢复，否则取 [`DEFAULT_WINDOW_SIZE`]
+    pub fn initial_size(&self, saved: Option<[u32; 2]>) -> (u32, u32) {
+        match saved {
+            Some([width, height]) if self.resizable && width > 0 && height > 0 => (width, height),
+            _ => DEFAULT_WINDOW_SIZE,
         }
     }
 }
@@ -117,14 +204,43 @@ impl Default for AudioConfig {
             bgm_volume: 0.8,
             sfx_volume: 1.0,
             muted: false,
+            silent: false,
         }
     }
 }
 
+impl AudioConfig {
+    /// 是否以 silent 模式创建 AudioManager（环境变量 > config 字段）
+    pub fn resolve_silent(&self) -> bool {
+        match std::env::var("RING_AUDIO_SILENT").ok().as_deref() {
+            Some("1") | Some("true") => true,
+            Some("0") | Some("false") => false,
+            _ => self.silent,
+        }
+    }
+}
+
+fn default_audio_cache_size_mb() -> u32 {
+    64
+}
+
 fn default_debug_port() -> u16 {
     9876
 }
 
+fn default_debug_shortcuts() -> BTreeMap<String, DebugAction> {
+    [
+        ("F1", DebugAction::ToggleVarWatch),
+        ("F3", DebugAction::SkipTransitions),
+        ("F4", DebugAction::ReturnToTitle),
+        ("F5", DebugAction::QuickSave),
+        ("F9", DebugAction::QuickLoad),
+    ]
+    .into_iter()
+    .map(|(key, action)| (key.to_string(), action))
+    .collect()
+}
+
 impl Default for DebugConfig {
     fn default() -> Self {
         Self {
@@ -133,6 +249,8 @@ impl Default for DebugConfig {
             log_file: None,
             enable_debug_server: None,
             debug_server_port: default_debug_port(),
+            enable_shortcuts: None,
+            shortcuts: default_debug_shortcuts(),
         }
     }
 }
@@ -156,12 +274,28 @@ impl DebugConfig {
             .unwrap_or(self.debug_server_port);
         Some(port)
     }
+
+    /// 开发者快捷键是否启用（config 字段 > 编译默认值）
+    pub fn shortcuts_enabled(&self) -> bool {
+        self.enable_shortcuts.unwrap_or(cfg!(debug_assertions))
+    }
+
+    /// 按配置决定按键触发的调试动作；快捷键整体关闭或按键未映射时返回 None
+    pub fn shortcut_action(&self, key: &str) -> Option<DebugAction> {
+        if !self.shortcuts_enabled() {
+            return None;
+        }
+        self.shortcuts.get(key).copied()
+    }
 }
 
 impl Default for ResourceConfig {
     fn default() -> Self {
         Self {
             texture_cache_size_mb: 256,
+            audio_cache_size_mb: default_audio_cache_size_mb(),
+            fold_path_case: false,
+            unicode_nfc_paths: false,
         }
     }
 }
@@ -219,6 +353,11 @@ impl AppConfig {
                 "manifest_path 不能为空".to_string(),
             ));
         }
+        if self.i18n.default_locale.trim().is_empty() {
+            return Err(ConfigError::ValidationFailed(
+                "i18n.default_locale 不能为空".to_string(),
+            ));
+        }
         if self.start_script_path.trim().is_empty() {
             return Err(ConfigError::ValidationFailed(
                 "start_script_path 不能为空".to_string(),
@@ -245,6 +384,11 @@ impl AppConfig {
                 "resources.texture_cache_size_mb 必须大于 0".to_string(),
             ));
         }
+        if self.resources.audio_cache_size_mb == 0 {
+            return Err(ConfigError::ValidationFailed(
+                "resources.audio_cache_size_mb 必须大于 0".to_string(),
+            ));
+        }
         Ok(())
     }
 }
@@ -314,6 +458,45 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn debug_shortcut_action_follows_switch_and_mapping() {
+        let mut debug = DebugConfig {
+            enable_shortcuts: Some(true),
+            ..DebugConfig::default()
+        };
+        assert_eq!(debug.shortcut_action("F5"), Some(DebugAction::QuickSave));
+        assert_eq!(debug.shortcut_action("F2"), None);
+
+        // 重新映射：只认配置里的键
+        debug.shortcuts = BTreeMap::from([("F8".to_string(), DebugAction::SkipTransitions)]);
+        assert_eq!(debug.shortcut_action("F3"), None);
+        assert_eq!(
+            debug.shortcut_action("F8"),
+            Some(DebugAction::SkipTransitions)
+        );
+
+        // 总开关关闭时任何键都不触发
+        debug.enable_shortcuts = Some(false);
+        assert_eq!(debug.shortcut_action("F8"), None);
+    }
+
+    #[test]
+    fn debug_shortcuts_parse_from_config() {
+        let debug: DebugConfig = serde_json::from_str(
+            r#"{
+                "script_check": true, "log_level": null, "log_file": null,
+                "enable_shortcuts": true,
+                "shortcuts": { "F6": "return_to_title" }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            debug.shortcut_action("F6"),
+            Some(DebugAction::ReturnToTitle)
+        );
+        assert_eq!(debug.shortcut_action("F1"), None);
+    }
+
     #[test]
     fn validate_rejects_empty_start_script_path() {
         let root = std::env::temp_dir().join("ring_host_dioxus_config_validate");
@@ -17,8 +17,10 @@ use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc, oneshot};
 use tracing::{error, info};
+use vn_runtime::Breakpoint;
 
 use crate::render_state::{HostScreen, PlaybackMode, RenderState};
+use crate::save_manager::SaveInfo;
 use crate::state::{AppState, WaitingFor};
 
 // ── 截图通道 ─────────────────────────────────────────────────────────────────
@@ -74,6 +76,8 @@ struct FullStateResponse {
     playback_mode: PlaybackMode,
     host_screen: HostScreen,
     history_count: usize,
+    /// Continue 存档信息（不存在时为 null）
+    continue_save: Option<SaveInfo>,
 }
 
 #[derive(Serialize)]
@@ -125,6 +129,16 @@ struct PlaybackModeRequest {
     mode: String,
 }
 
+/// 断点集合（整体替换）
+#[derive(Deserialize)]
+struct BreakpointsRequest {
+    /// 源码行号，按当前执行的脚本匹配
+    #[serde(default)]
+    lines: Vec<usize>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
 // ── 路由 ─────────────────────────────────────────────────────────────────────
 
 fn build_router(state: ServerState) -> Router {
@@ -145,9 +159,12 @@ fn build_router(state: ServerState) -> Router {
         .route("/api/navigate", post(handle_navigate))
         .route("/api/start_game", post(handle_start_game))
         .route("/api/playback_mode", post(handle_playback_mode))
+        .route("/api/breakpoints", post(handle_breakpoints))
+        .route("/api/continue", post(handle_continue))
         // 诊断
         .route("/api/diag/transitions", get(handle_diag_transitions))
         .route("/api/diag/typewriter", get(handle_diag_typewriter))
+        .route("/api/diag/runtime", get(handle_diag_runtime))
         .with_state(state)
 }
 
@@ -233,6 +250,10 @@ async fn handle_state(State(state): State<ServerState>) -> impl IntoResponse {
         playback_mode: inner.playback_mode.clone(),
         host_screen: inner.render_state.host_screen.clone(),
         history_count: inner.history.len(),
+        continue_save: inner
+            .services
+            .as_ref()
+            .and_then(|svc| svc.saves.get_continue_info()),
     };
     drop(inner);
     Json(resp)
@@ -255,8 +276,11 @@ async fn handle_state_scene(State(state): State<ServerState>) -> impl IntoRespon
     let inner = lock_inner(&state);
     Json(serde_json::json!({
         "current_background": inner.render_state.current_background,
+        "background_fill": inner.render_state.background_fill.map(|c| c.to_hex()),
+        "background_layers": inner.render_state.background_layers,
         "visible_characters": inner.render_state.visible_characters,
         "scene_effect": inner.render_state.scene_effect,
+        "screen_filter": inner.render_state.screen_filter,
         "background_transition": inner.render_state.background_transition,
         "scene_transition": inner.render_state.scene_transition,
         "title_card": inner.render_state.title_card,
@@ -278,8 +302,12 @@ async fn handle_state_choices(State(state): State<ServerState>) -> impl IntoResp
 
 async fn handle_state_audio(State(state): State<ServerState>) -> impl IntoResponse {
     let inner = lock_inner(&state);
+    let manager = inner.services.as_ref().map(|svc| &svc.audio);
     Json(serde_json::json!({
         "audio": inner.render_state.audio,
+        "silent": manager.is_some_and(|am| am.is_silent()),
+        "bgm_playing": manager.is_some_and(|am| am.is_playing()),
+        "bgm_path": manager.and_then(|am| am.current_bgm_path()),
     }))
 }
 
@@ -319,6 +347,12 @@ async fn handle_choose(
             ),
         ));
     }
+    if !inner.render_state.is_choice_enabled(req.index) {
+        return Err(err_json(
+            StatusCode::CONFLICT,
+            format!("选项 {} 当前不可选", req.index),
+        ));
+    }
     inner.process_choose(req.index);
     Ok(Json(action_summary(&inner)))
 }
@@ -438,6 +472,49 @@ async fn handle_playback_mode(
     Ok(Json(action_summary(&inner)))
 }
 
+// ── POST /api/breakpoints ────────────────────────────────────────────────────
+
+async fn handle_breakpoints(
+    State(state): State<ServerState>,
+    Json(req): Json<BreakpointsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let mut inner = lock_inner(&state);
+    if inner.runtime.is_none() {
+        return Err(err_json(StatusCode::CONFLICT, "游戏未启动"));
+    }
+    let breakpoints = req
+        .lines
+        .into_iter()
+        .map(Breakpoint::Line)
+        .chain(req.labels.into_iter().map(Breakpoint::Label));
+    inner.set_breakpoints(breakpoints);
+    let current: Vec<Breakpoint> = inner
+        .runtime
+        .as_ref()
+        .map(|rt| rt.breakpoints().iter().cloned().collect())
+        .unwrap_or_default();
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "breakpoints": current,
+    })))
+}
+
+// ── POST /api/continue ───────────────────────────────────────────────────────
+
+async fn handle_continue(
+    State(state): State<ServerState>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let mut inner = lock_inner(&state);
+    if inner.waiting != WaitingFor::Breakpoint {
+        return Err(err_json(
+            StatusCode::CONFLICT,
+            format!("当前未停在断点，waiting={:?}", inner.waiting),
+        ));
+    }
+    inner.continue_from_breakpoint();
+    Ok(Json(action_summary(&inner)))
+}
+
 // ── GET /api/diag/transitions ────────────────────────────────────────────────
 
 async fn handle_diag_transitions(State(state): State<ServerState>) -> impl IntoResponse {
@@ -472,6 +549,26 @@ async fn handle_diag_typewriter(State(state): State<ServerState>) -> impl IntoRe
     }))
 }
 
+// ── GET /api/diag/runtime ────────────────────────────────────────────────────
+
+async fn handle_diag_runtime(State(state): State<ServerState>) -> impl IntoResponse {
+    let inner = lock_inner(&state);
+    let metrics = inner.runtime.as_ref().and_then(|rt| rt.metrics());
+    let (texture_cache, audio_cache) = {
+        let rm = crate::init::asset_resources()
+            .lock()
+            .expect("invariant: asset resource mutex not poisoned");
+        (rm.texture_cache().stats(), rm.audio_cache().stats())
+    };
+    Json(serde_json::json!({
+        "enabled": metrics.is_some(),
+        "metrics": metrics,
+        "average_tick_nodes": metrics.map(|m| m.average_tick_nodes()),
+        "texture_cache": texture_cache,
+        "audio_cache": audio_cache,
+    }))
+}
+
 // ── GET /api/screenshot ──────────────────────────────────────────────────────
 
 async fn handle_screenshot(
@@ -497,48 +594,3 @@ async fn handle_screenshot(
         Err(_) => Err(err_json(StatusCode::GATEWAY_TIMEOUT, "截图超时 (10s)")),
     }
 }
-
-// ── 截图 JS 代码 ────────────────────────────────────────────────────────────
-
-/// 注入到 WebView 中的截图 JS 代码。
-///
-/// 使用 html2canvas（动态加载），捕获 `.game-container` 元素。
-/// 返回 JSON: `{ width, height, data }` 其中 data 是 base64 PNG。
-pub const SCREENSHOT_JS: &str = r#"
-(async function() {
-    try {
-        // 动态加载 html2canvas（仅首次）
-        if (!window.__html2canvasLoaded) {
-            await new Promise((resolve, reject) => {
-                const script = document.createElement('script');
-                script.src = 'https://cdnjs.cloudflare.com/ajax/libs/html2canvas/1.4.1/html2canvas.min.js';
-                script.onload = () => { window.__html2canvasLoaded = true; resolve(); };
-                script.onerror = (e) => reject(new Error('html2canvas load failed'));
-                document.head.appendChild(script);
-            });
-        }
-
-        const container = document.querySelector('.game-container');
-        if (!container) {
-            dioxus.send({ error: 'game-container not found' });
-            return;
-        }
-
-        const canvas = await html2canvas(container, {
-            useCORS: true,
-            allowTaint: false,
-            scale: 1,
-            width: 1920,
-            height: 1080,
-            logging: false,
-        });
-
-        const dataUrl = canvas.toDataURL('image/png');
-        // 去掉 "data:image/png;base64," 前缀
-        const base64 = dataUrl.split(',')[1];
-        dioxus.send({ width: canvas.width, height: canvas.height, data: base64 });
-    } catch (e) {
-        dioxus.send({ error: e.message || String(e) });
-    }
-})();
-"#;
@@ -4,6 +4,7 @@
 //! 不依赖任何 Tauri API。
 
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 use tracing::{info, warn};
 
@@ -12,10 +13,13 @@ use crate::config::{self, AppConfig};
 use crate::error::HostError;
 use crate::layout_config::UiLayoutConfig;
 use crate::manifest;
-use crate::resources::{self, LogicalPath, ResourceManager};
+use crate::resources::{self, LogicalPath, PathNormalization, ResourceManager};
 use crate::save_manager::SaveManager;
 use crate::screen_defs::ScreenDefinitions;
-use crate::state::{AppStateInner, PersistentStore, Services};
+use crate::script_check::{self, ScriptCheckSummary};
+use crate::state::{
+    AppStateInner, PersistentStore, Services, SettingsWatcher, USER_SETTINGS_FILE, UserSettings,
+};
 
 /// 简易 percent-decode：处理 URL 路径中的 `%XX` 编码（如中文文件名）。
 pub fn percent_decode(input: &str) -> String {
@@ -110,6 +114,76 @@ fn create_resource_manager(
     }
 }
 
+/// 根据配置创建 ResourceManager，并应用缓存预算与路径归一规则
+fn configured_resource_manager(
+    cfg: &AppConfig,
+    assets_root: &Path,
+    project_root: &Path,
+) -> Result<ResourceManager, HostError> {
+    let mut rm = create_resource_manager(cfg, assets_root, project_root)?;
+    rm.set_budget_mb(cfg.resources.texture_cache_size_mb as usize);
+    rm.set_audio_budget_mb(cfg.resources.audio_cache_size_mb as usize);
+    rm.set_path_normalization(PathNormalization {
+        fold_case: cfg.resources.fold_path_case,
+        unicode_nfc: cfg.resources.unicode_nfc_paths,
+    });
+    Ok(rm)
+}
+
+fn resolve_assets_root(cfg: &AppConfig, project_root: &Path) -> PathBuf {
+    if cfg.assets_root.is_relative() {
+        project_root.join(&cfg.assets_root)
+    } else {
+        cfg.assets_root.clone()
+    }
+}
+
+/// `ring-asset` 协议使用的资源管理器（首次请求时创建）
+///
+/// 协议 handler 在 AppState 之前注册，且不应与 tick 循环争用 AppState 锁，因此持有独立实例：
+/// 纹理与音频字节经其 LRU 缓存提供，UI 素材钉住常驻。
+/// 配置读取失败时回退为 `assets/` 文件系统来源，完整校验留给 [`initialize_inner`]。
+pub fn asset_resources() -> &'static Mutex<ResourceManager> {
+    static ASSET_RESOURCES: OnceLock<Mutex<ResourceManager>> = OnceLock::new();
+    ASSET_RESOURCES.get_or_init(|| {
+        let project_root = find_project_root();
+        let rm = AppConfig::load(project_root.join("config.json"))
+            .map_err(|e| e.to_string())
+            .and_then(|cfg| {
+                let assets_root = resolve_assets_root(&cfg, &project_root);
+                configured_resource_manager(&cfg, &assets_root, &project_root)
+                    .map_err(|e| e.to_string())
+            });
+        let mut rm = rm.unwrap_or_else(|error| {
+            warn!(%error, "ring-asset 资源配置加载失败，回退为 assets/ 文件系统来源");
+            ResourceManager::new(project_root.join("assets"))
+        });
+        if let Ok(layout) = UiLayoutConfig::load(&rm) {
+            for path in layout.assets.all_paths() {
+                rm.texture_cache_mut().pin(&LogicalPath::new(path));
+            }
+        }
+        Mutex::new(rm)
+    })
+}
+
+/// 启动窗口参数：是否可调整大小 + 实际使用的初始尺寸（恢复上次保存的尺寸）
+///
+/// 在 Dioxus 启动前调用；配置或用户设置读取失败时回退默认值，完整校验留给 [`initialize_inner`]。
+pub fn startup_window() -> (bool, (u32, u32)) {
+    let project_root = find_project_root();
+    let cfg = AppConfig::load(project_root.join("config.json")).unwrap_or_default();
+    let saves_dir = if cfg.saves_dir.is_relative() {
+        project_root.join(&cfg.saves_dir)
+    } else {
+        cfg.saves_dir.clone()
+    };
+    let saved = UserSettings::load(saves_dir.join(USER_SETTINGS_FILE))
+        .ok()
+        .and_then(|s| s.window_size);
+    (cfg.window.resizable, cfg.window.initial_size(saved))
+}
+
 /// 初始化 AppStateInner 的子系统（config、resources、manifest、audio、saves）。
 pub fn initialize_inner(inner: &mut AppStateInner) -> Result<(), Box<dyn std::error::Error>> {
     let project_root = find_project_root();
@@ -119,14 +193,10 @@ pub fn initialize_inner(inner: &mut AppStateInner) -> Result<(), Box<dyn std::er
     let cfg = AppConfig::load(&cfg_path)?;
     cfg.validate(&project_root)?;
 
-    let assets_root = if cfg.assets_root.is_relative() {
-        project_root.join(&cfg.assets_root)
-    } else {
-        cfg.assets_root.clone()
-    };
+    let assets_root = resolve_assets_root(&cfg, &project_root);
     info!(assets = %assets_root.display(), "资源根目录");
 
-    let rm = create_resource_manager(&cfg, &assets_root, &project_root)?;
+    let rm = configured_resource_manager(&cfg, &assets_root, &project_root)?;
 
     let manifest_logical = LogicalPath::new(&cfg.manifest_path);
     if !rm.resource_exists(&manifest_logical) {
@@ -147,6 +217,7 @@ pub fn initialize_inner(inner: &mut AppStateInner) -> Result<(), Box<dyn std::er
         cfg.saves_dir.clone()
     };
     let sm = SaveManager::new(&saves_dir);
+    sm.cleanup_temp_files();
 
     let manifest_content = rm.read_text(&manifest_logical)?;
     let (mf, manifest_warnings) = manifest::Manifest::parse_and_validate(&manifest_content)?;
@@ -155,9 +226,16 @@ pub fn initialize_inner(inner: &mut AppStateInner) -> Result<(), Box<dyn std::er
     }
     info!(presets = mf.presets.len(), "Manifest 加载完成");
 
-    let mut am = AudioManager::new();
+    let mut am = if cfg.audio.resolve_silent() {
+        info!("音频以 silent 模式运行，不会播放任何声音");
+        AudioManager::silent()
+    } else {
+        AudioManager::new()
+    };
+    am.set_master_volume(cfg.audio.master_volume);
     am.set_bgm_volume(cfg.audio.bgm_volume);
     am.set_sfx_volume(cfg.audio.sfx_volume);
+    am.set_muted(cfg.audio.muted);
     info!("AudioManager 初始化成功");
 
     // UI 数据驱动配置加载
@@ -165,6 +243,7 @@ pub fn initialize_inner(inner: &mut AppStateInner) -> Result<(), Box<dyn std::er
     let screen_defs = ScreenDefinitions::load(&rm)?;
 
     inner.persistent_store = PersistentStore::load(&saves_dir);
+    let settings_path = saves_dir.join(USER_SETTINGS_FILE);
     inner.services = Some(Services {
         audio: am,
         resources: rm,
@@ -174,6 +253,38 @@ pub fn initialize_inner(inner: &mut AppStateInner) -> Result<(), Box<dyn std::er
         layout,
         screen_defs,
     });
+    if settings_path.exists() {
+        match UserSettings::load(&settings_path) {
+            Ok(settings) => inner.apply_user_settings(settings),
+            Err(e) => warn!(error = %e, "用户设置加载失败，使用默认设置"),
+        }
+    }
+    inner.settings_watcher = Some(SettingsWatcher::new(&settings_path));
     info!("子系统初始化完成");
     Ok(())
 }
+
+/// 启动时脚本检查（`debug.script_check` 关闭或未初始化时返回 None）
+pub fn run_startup_script_check(inner: &AppStateInner) -> Option<ScriptCheckSummary> {
+    let services = inner.services.as_ref()?;
+    if !services.config.debug.script_check {
+        return None;
+    }
+    let rm = &services.resources;
+    let summary = script_check::check_scripts(
+        &services.config.start_script_path,
+        |path| rm.read_text_optional(&LogicalPath::new(path)),
+        |path| rm.resource_exists(&LogicalPath::new(path)),
+        &services.manifest.sfx_pools,
+    );
+    for diagnostic in &summary.diagnostics.diagnostics {
+        warn!(%diagnostic, "脚本检查");
+    }
+    info!(
+        scripts = summary.scripts_checked,
+        errors = summary.error_count(),
+        warnings = summary.warn_count(),
+        "脚本检查完成"
+    );
+    Some(summary)
+}
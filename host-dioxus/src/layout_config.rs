@@ -6,6 +6,8 @@
 //! 复用自 `host/src/ui/layout/mod.rs`，移除 ScaleContext 和 egui 依赖。
 //! Dioxus host 中缩放由 CSS `transform: scale()` 负责，无需 Rust 侧 ScaleContext。
 
+use std::collections::BTreeMap;
+
 use serde::Deserialize;
 
 use crate::resources::{LogicalPath, ResourceManager};
@@ -60,6 +62,8 @@ pub struct FontConfig {
 pub struct DialogueLayoutConfig {
     /// 文本框高度（基准像素）
     pub textbox_height: f32,
+    /// 文本框边框 (left, top, right, bottom)
+    pub textbox_borders: [f32; 4],
     /// 名字 X 偏移
     pub name_xpos: f32,
     /// 名字 Y 偏移
@@ -212,6 +216,87 @@ pub struct NotifyConfig {
     pub frame_borders: [f32; 4],
 }
 
+// ─── Chapter mark ────────────────────────────────────────────────────────────
+
+/// 章节标记显示位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChapterMarkAnchor {
+    /// 画面中央（大标题）
+    Center,
+    /// 左上角（小标）
+    TopLeft,
+}
+
+impl ChapterMarkAnchor {
+    /// 对应的 CSS 修饰类
+    pub fn css_class(self) -> &'static str {
+        match self {
+            ChapterMarkAnchor::Center => "vn-chapter-mark--center",
+            ChapterMarkAnchor::TopLeft => "vn-chapter-mark--top-left",
+        }
+    }
+}
+
+/// 单个级别的章节标记样式
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChapterMarkStyle {
+    /// 字号
+    pub text_size: f32,
+    /// 显示位置
+    pub anchor: ChapterMarkAnchor,
+}
+
+/// 章节标记配置：按标题级别（`#` 数量）选择样式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChapterMarkLayoutConfig {
+    /// level 1（`#`）
+    pub level1: ChapterMarkStyle,
+    /// level 2（`##`）
+    pub level2: ChapterMarkStyle,
+    /// level 3 及以下
+    pub other: ChapterMarkStyle,
+}
+
+impl ChapterMarkLayoutConfig {
+    /// 按章节级别选择样式
+    pub fn style_for(&self, level: u8) -> &ChapterMarkStyle {
+        match level {
+            0 | 1 => &self.level1,
+            2 => &self.level2,
+            _ => &self.other,
+        }
+    }
+}
+
+// ─── Textbox styles ──────────────────────────────────────────────────────────
+
+/// 对话框样式（脚本 `textBoxStyle <name>` 切换）；未填写的项沿用默认外观
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TextboxStyle {
+    /// 对话框 NinePatch 背景纹理
+    #[serde(default)]
+    pub textbox: Option<String>,
+    /// 名牌 NinePatch 背景纹理
+    #[serde(default)]
+    pub namebox: Option<String>,
+    /// 对话框纹理边框 (left, top, right, bottom)，替代 `dialogue.textbox_borders`
+    #[serde(default)]
+    pub textbox_borders: Option<[f32; 4]>,
+    /// 名牌纹理边框 (left, top, right, bottom)，替代 `dialogue.namebox_borders`
+    #[serde(default)]
+    pub namebox_borders: Option<[f32; 4]>,
+    /// 正文颜色（CSS 颜色值）
+    #[serde(default)]
+    pub text_color: Option<String>,
+    /// 说话人名颜色（CSS 颜色值）
+    #[serde(default)]
+    pub name_color: Option<String>,
+}
+
 // ─── Asset paths ─────────────────────────────────────────────────────────────
 
 /// UI 素材路径配置
@@ -249,6 +334,35 @@ impl UiAssetPaths {
         format!("http://ring-asset.localhost/{path}")
     }
 
+    /// 全部 UI 素材的逻辑路径（资源缓存据此钉住常驻纹理）
+    pub fn all_paths(&self) -> [&str; 23] {
+        [
+            &self.textbox,
+            &self.namebox,
+            &self.frame,
+            &self.main_menu_overlay,
+            &self.game_menu_overlay,
+            &self.confirm_overlay,
+            &self.skip,
+            &self.notify,
+            &self.main_summer,
+            &self.main_winter,
+            &self.game_menu_bg,
+            &self.button_idle,
+            &self.button_hover,
+            &self.choice_idle,
+            &self.choice_hover,
+            &self.slot_idle,
+            &self.slot_hover,
+            &self.quick_idle,
+            &self.quick_hover,
+            &self.slider_idle_bar,
+            &self.slider_hover_bar,
+            &self.slider_idle_thumb,
+            &self.slider_hover_thumb,
+        ]
+    }
+
     /// 将 screens.json 中的资源 key（如 "main_summer"）解析为实际逻辑路径
     pub fn resolve_key(&self, key: &str) -> String {
         match key {
@@ -303,6 +417,10 @@ pub struct UiLayoutConfig {
     pub skip_indicator: SkipIndicatorConfig,
     /// 通知
     pub notify: NotifyConfig,
+    /// 章节标记
+    pub chapter_mark: ChapterMarkLayoutConfig,
+    /// 对话框样式表（样式名 → 样式）
+    pub textbox_styles: BTreeMap<String, TextboxStyle>,
     /// 素材路径
     pub assets: UiAssetPaths,
 }
@@ -353,6 +471,7 @@ impl UiLayoutConfig {
             },
             dialogue: DialogueLayoutConfig {
                 textbox_height: 278.0,
+                textbox_borders: [30.0, 30.0, 30.0, 30.0],
                 name_xpos: 360.0,
                 name_ypos: 0.0,
                 namebox_borders: [5.0, 5.0, 5.0, 5.0],
@@ -409,6 +528,21 @@ impl UiLayoutConfig {
                 ypos: 68.0,
                 frame_borders: [24.0, 8.0, 60.0, 8.0],
             },
+            chapter_mark: ChapterMarkLayoutConfig {
+                level1: ChapterMarkStyle {
+                    text_size: 75.0,
+                    anchor: ChapterMarkAnchor::Center,
+                },
+                level2: ChapterMarkStyle {
+                    text_size: 36.0,
+                    anchor: ChapterMarkAnchor::TopLeft,
+                },
+                other: ChapterMarkStyle {
+                    text_size: 30.0,
+                    anchor: ChapterMarkAnchor::TopLeft,
+                },
+            },
+            textbox_styles: BTreeMap::new(),
             assets: UiAssetPaths {
                 textbox: "gui/textbox.png".into(),
                 namebox: "gui/namebox.png".into(),
@@ -469,4 +603,33 @@ mod tests {
         assert_eq!(config.base_height, 1080.0);
         assert!(!config.assets.textbox.is_empty());
     }
+
+    #[test]
+    fn chapter_mark_style_depends_on_level() {
+        let config: UiLayoutConfig = serde_json::from_str(LAYOUT_JSON).unwrap();
+        let marks = &config.chapter_mark;
+
+        let title = marks.style_for(1);
+        let corner = marks.style_for(2);
+        assert_eq!(title.anchor, ChapterMarkAnchor::Center);
+        assert_eq!(corner.anchor, ChapterMarkAnchor::TopLeft);
+        assert!(title.text_size > corner.text_size);
+
+        assert_eq!(marks.style_for(3), &marks.other);
+        assert_eq!(marks.style_for(6), &marks.other);
+    }
+
+    #[test]
+    fn textbox_style_fields_are_optional() {
+        let config: UiLayoutConfig = serde_json::from_str(LAYOUT_JSON).unwrap();
+        let memory = &config.textbox_styles["memory"];
+        assert!(memory.text_color.is_some());
+        assert_eq!(memory.textbox, None);
+
+        let style: TextboxStyle =
+            serde_json::from_str(r#"{ "textbox": "gui/textbox_diary.png" }"#).unwrap();
+        assert_eq!(style.textbox.as_deref(), Some("gui/textbox_diary.png"));
+        assert_eq!(style.name_color, None);
+        assert!(serde_json::from_str::<TextboxStyle>(r#"{ "colour": "red" }"#).is_err());
+    }
 }
@@ -3,6 +3,7 @@
 
 // ── 后端模块（Phase 1 迁移自 host-tauri，无 Tauri 依赖） ──
 pub mod audio;
+pub mod capture;
 pub mod command_executor;
 pub mod config;
 pub mod error;
@@ -10,11 +11,16 @@ pub mod init;
 pub mod layout_config;
 pub mod manifest;
 pub mod map_data;
+pub mod nine_slice;
 pub mod render_state;
 pub mod resources;
 pub mod save_manager;
 pub mod screen_defs;
+pub mod script_check;
 pub mod state;
+pub mod text_effect;
+pub mod touch;
+pub mod weather;
 
 #[cfg(not(target_arch = "wasm32"))]
 pub mod debug_server;
@@ -25,7 +31,6 @@ mod screens;
 mod vn;
 
 use std::borrow::Cow;
-use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
@@ -48,15 +53,33 @@ pub fn now_secs() -> u64 {
     }
 }
 
+/// 获取单调递增的毫秒时间，用于输入去重等短时间窗口判断
+pub fn now_millis() -> f64 {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+}
+
 use dioxus::desktop::Config;
 use dioxus::desktop::tao::dpi::LogicalSize;
+use dioxus::desktop::tao::event::{Event, WindowEvent};
 use dioxus::desktop::tao::window::WindowBuilder;
 use dioxus::desktop::wry::http;
 use dioxus::prelude::*;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use components::{ConfirmDialog, PendingConfirm, SkipIndicator, ToastLayer, ToastQueue};
+use components::{ConfirmDialog, PendingConfirm, SkipIndicator, ToastLayer, ToastQueue, ToastType};
+use config::DebugAction;
 use render_state::{HostScreen, RenderState};
+use screen_defs::ActionDef;
 use screens::{HistoryScreen, InGameMenu, SaveLoadScreen, SettingsScreen, TitleScreen};
 use state::{AppState, AppStateInner};
 use vn::VNScene;
@@ -92,6 +115,7 @@ fn main() {
     tracing_subscriber::fmt::init();
 
     let css_head = format!("<style>{GLOBAL_CSS}</style>{SCALE_JS}");
+    let (resizable, (width, height)) = init::startup_window();
 
     dioxus::LaunchBuilder::new()
         .with_cfg(
@@ -99,7 +123,8 @@ fn main() {
                 .with_window(
                     WindowBuilder::new()
                         .with_title("Ring Engine")
-                        .with_inner_size(LogicalSize::new(1280, 720)),
+                        .with_inner_size(LogicalSize::new(width, height))
+                        .with_resizable(resizable),
                 )
                 .with_menu(None)
                 .with_custom_head(css_head)
@@ -159,14 +184,24 @@ fn ring_asset_handler(
             .unwrap();
     }
 
-    let assets_root = find_assets_root();
-    let full_path = assets_root.join(&path_clean);
-
-    tracing::debug!(uri = %uri, resolved = %full_path.display(), "ring-asset request");
+    tracing::debug!(uri = %uri, path = %path_clean, "ring-asset request");
 
     let mime = guess_mime(&path_clean);
+    let logical = resources::LogicalPath::new(&path_clean);
+    let result = {
+        let mut rm = init::asset_resources()
+            .lock()
+            .expect("invariant: asset resource mutex not poisoned");
+        if mime.starts_with("image/") {
+            rm.read_texture(&logical)
+        } else if mime.starts_with("audio/") {
+            rm.read_audio(&logical)
+        } else {
+            rm.read_bytes(&logical).map(Arc::from)
+        }
+    };
 
-    match std::fs::read(&full_path) {
+    match result {
         Ok(bytes) => {
             // games/*/**.html: 自动注入 engine JS SDK（postMessage 桥接）
             let body = if path_clean.starts_with("games/") && mime == "text/html" {
@@ -174,7 +209,7 @@ fn ring_asset_handler(
                 let injected = inject_engine_sdk(&html);
                 Cow::from(injected.into_bytes())
             } else {
-                Cow::from(bytes)
+                Cow::from(bytes.to_vec())
             };
             http::Response::builder()
                 .status(200)
@@ -213,22 +248,6 @@ fn percent_decode(input: &str) -> String {
     String::from_utf8(out).unwrap_or_else(|_| input.to_string())
 }
 
-fn find_assets_root() -> PathBuf {
-    let cwd = std::env::current_dir().unwrap_or_default();
-    let mut dir: &Path = &cwd;
-    loop {
-        let candidate = dir.join("assets");
-        if candidate.is_dir() {
-            return candidate;
-        }
-        match dir.parent() {
-            Some(parent) => dir = parent,
-            None => break,
-        }
-    }
-    cwd.join("assets")
-}
-
 fn guess_mime(path: &str) -> &'static str {
     match path.rsplit('.').next().unwrap_or("") {
         "jpg" | "jpeg" => "image/jpeg",
@@ -350,43 +369,63 @@ async fn screenshot_bridge(mut rx: tokio::sync::mpsc::Receiver<debug_server::Scr
     while let Some(req) = rx.recv().await {
         // 每个请求启动独立 eval，避免阻塞后续请求
         spawn(async move {
-            let mut eval = document::eval(debug_server::SCREENSHOT_JS);
-            match eval.recv().await {
-                Ok(msg) => {
-                    let result: serde_json::Value = msg;
-                    if let Some(err) = result.get("error").and_then(|v| v.as_str()) {
-                        // receiver 可能已因超时而被丢弃，忽略发送失败
-                        let _ = req.reply.send(Err(err.to_string()));
-                    } else {
-                        let width =
-                            result.get("width").and_then(|v| v.as_u64()).unwrap_or(1920) as u32;
-                        let height = result
-                            .get("height")
-                            .and_then(|v| v.as_u64())
-                            .unwrap_or(1080) as u32;
-                        let data = result
-                            .get("data")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        // receiver 可能已因超时而被丢弃，忽略发送失败
-                        let _ = req.reply.send(Ok(debug_server::ScreenshotData {
-                            format: "png".to_string(),
-                            width,
-                            height,
-                            data_base64: data,
-                        }));
-                    }
-                }
-                Err(e) => {
-                    // receiver 可能已因超时而被丢弃，忽略发送失败
-                    let _ = req.reply.send(Err(format!("eval 失败: {e}")));
-                }
-            }
+            let result = capture::capture_element(
+                ".game-container",
+                capture::CaptureSize::Fixed(1920, 1080),
+            )
+            .await
+            .map(|frame| debug_server::ScreenshotData {
+                format: "png".to_string(),
+                width: frame.width,
+                height: frame.height,
+                data_base64: frame.data_base64,
+            });
+            // receiver 可能已因超时而被丢弃，忽略发送失败
+            let _ = req.reply.send(result);
         });
     }
 }
 
+// ---------------------------------------------------------------------------
+// Save thumbnail capture
+// ---------------------------------------------------------------------------
+
+/// 在 WebView 中抓取场景层（`.vn-scene`，不含覆盖在其上的菜单）并缩放到缩略图尺寸
+///
+/// 失败时返回 `None` 并记录告警；缺少缩略图的存档仍可正常读取。
+async fn capture_scene_thumbnail(width: u32, height: u32) -> Option<Vec<u8>> {
+    let result = capture::capture_element(".vn-scene", capture::CaptureSize::Scaled(width, height))
+        .await
+        .and_then(|frame| frame.decode_png());
+    match result {
+        Ok(png) => Some(png),
+        Err(e) => {
+            warn!(error = %e, "缩略图抓帧失败");
+            None
+        }
+    }
+}
+
+/// 抓取场景画面并补写到已写入的存档槽位（快速存档等场景仍可见时的保存）
+async fn capture_save_thumbnail(app_state: AppState, slot: u32, width: u32, height: u32) {
+    let Some(png) = capture_scene_thumbnail(width, height).await else {
+        return;
+    };
+    if let Ok(mut inner) = app_state.inner.lock()
+        && let Err(e) = inner.complete_thumbnail(slot, &png)
+    {
+        warn!(slot, error = %e, "缩略图补写失败");
+    }
+}
+
+/// 打开存档界面前抓取场景画面，抓取结束（无论成败）后再切换界面
+async fn capture_scene_before_save_screen(app_state: AppState, width: u32, height: u32) {
+    let png = capture_scene_thumbnail(width, height).await;
+    if let Ok(mut inner) = app_state.inner.lock() {
+        inner.finish_scene_capture(png);
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Root component
 // ---------------------------------------------------------------------------
@@ -402,12 +441,15 @@ fn App() -> Element {
         use_context_provider(|| Signal::new(None));
 
     // Toast 队列（全局 Signal）
-    let _toast_queue: Signal<ToastQueue> =
+    let mut toast_queue: Signal<ToastQueue> =
         use_context_provider(|| Signal::new(ToastQueue::default()));
 
     // 初始化阶段
     let mut init_phase = use_signal(|| InitPhase::Loading);
 
+    // 变量监视浮层（调试快捷键切换，默认 F1）
+    let mut var_watch = use_signal(|| false);
+
     // RenderState signal：tick loop 每帧更新
     let mut render_state = use_signal(RenderState::new);
 
@@ -431,6 +473,25 @@ fn App() -> Element {
                             .expect("invariant: app state mutex not poisoned");
                         inner.frontend_connected(Some("dioxus-desktop".to_string()));
                     }
+                    let script_check = {
+                        let inner = app_state_init
+                            .inner
+                            .lock()
+                            .expect("invariant: app state mutex not poisoned");
+                        init::run_startup_script_check(&inner)
+                    };
+                    if let Some(toast) = script_check.and_then(|summary| summary.toast()) {
+                        let toast_type = if toast.has_errors {
+                            ToastType::Error
+                        } else {
+                            ToastType::Warning
+                        };
+                        toast_queue.write().push_with_details(
+                            toast.text,
+                            toast_type,
+                            toast.details,
+                        );
+                    }
                     #[cfg(not(target_arch = "wasm32"))]
                     {
                         let debug_port = {
@@ -464,6 +525,33 @@ fn App() -> Element {
         });
     });
 
+    // 窗口尺寸变化时记录，退出时连同其他用户设置写回 user_settings.json
+    let app_state_window = app_state.clone();
+    // 每个窗口事件都会回调，只在处理的事件上加锁，避免与 tick 循环争用
+    dioxus::desktop::use_wry_event_handler(move |event, _| match event {
+        Event::WindowEvent {
+            event: WindowEvent::Resized(size),
+            ..
+        } => {
+            let size = size.to_logical::<u32>(dioxus::desktop::window().scale_factor());
+            if let Ok(mut inner) = app_state_window.inner.lock() {
+                inner.record_window_size(size.width, size.height);
+            }
+        }
+        Event::WindowEvent {
+            event: WindowEvent::CloseRequested,
+            ..
+        }
+        | Event::LoopDestroyed => {
+            if let Ok(mut inner) = app_state_window.inner.lock()
+                && let Err(e) = inner.save_user_settings()
+            {
+                warn!(error = %e, "用户设置保存失败");
+            }
+        }
+        _ => {}
+    });
+
     // Tick loop：~30 FPS
     let app_state_tick = app_state.clone();
     use_hook(move || {
@@ -472,7 +560,34 @@ fn App() -> Element {
                 tokio::time::sleep(Duration::from_millis(33)).await;
                 if let Ok(mut inner) = app_state_tick.inner.lock() {
                     inner.process_tick(1.0 / 30.0);
+                    let scene_capture = inner.take_scene_capture_request();
+                    let thumbnail_slots = inner.take_thumbnail_requests();
+                    if scene_capture || !thumbnail_slots.is_empty() {
+                        let layout = &inner.services().layout.save_load;
+                        let (thumb_w, thumb_h) = (
+                            layout.thumbnail_width as u32,
+                            layout.thumbnail_height as u32,
+                        );
+                        if scene_capture {
+                            spawn(capture_scene_before_save_screen(
+                                app_state_tick.clone(),
+                                thumb_w,
+                                thumb_h,
+                            ));
+                        }
+                        for slot in thumbnail_slots {
+                            spawn(capture_save_thumbnail(
+                                app_state_tick.clone(),
+                                slot,
+                                thumb_w,
+                                thumb_h,
+                            ));
+                        }
+                    }
                     render_state.set(inner.render_state.clone());
+                    for warning in inner.pending_warnings.drain(..) {
+                        toast_queue.write().push(warning, ToastType::Warning);
+                    }
                 }
             }
         });
@@ -485,8 +600,14 @@ fn App() -> Element {
             let mut eval = document::eval(
                 r#"
                 document.addEventListener("keydown", function(e) {
+                    // 文本框输入时（历史搜索、存档备注）只放行 Escape
+                    if (e.target.matches?.("input[type=text], textarea") && e.key !== "Escape") {
+                        return;
+                    }
                     dioxus.send({ type: "down", key: e.key, code: e.code });
-                    if (["Escape", " ", "Enter", "Control", "Backspace"].includes(e.key)) {
+                    // F1–F10 留给调试快捷键（避免 F5 刷新 WebView 等默认行为）
+                    if (["Escape", " ", "Enter", "Control", "Backspace"].includes(e.key)
+                        || /^F([1-9]|10)$/.test(e.key)) {
                         e.preventDefault();
                     }
                 });
@@ -504,6 +625,24 @@ fn App() -> Element {
                 let key = msg.get("key").and_then(|v| v.as_str()).unwrap_or("");
 
                 if let Ok(mut inner) = app_state_keys.inner.lock() {
+                    let debug_action = inner
+                        .services
+                        .as_ref()
+                        .filter(|_| event_type == "down")
+                        .and_then(|services| services.config.debug.shortcut_action(key));
+                    if let Some(action) = debug_action {
+                        match action {
+                            DebugAction::ToggleVarWatch => var_watch.toggle(),
+                            DebugAction::SkipTransitions => {
+                                inner.skip_active_transitions();
+                            }
+                            DebugAction::ReturnToTitle => inner.return_to_title(false),
+                            DebugAction::QuickSave => inner.execute_action(&ActionDef::QuickSave),
+                            DebugAction::QuickLoad => inner.execute_action(&ActionDef::QuickLoad),
+                        }
+                        continue;
+                    }
+
                     match (event_type, key) {
                         ("down", "Escape") => {
                             let screen = inner.render_state.host_screen.clone();
@@ -560,6 +699,51 @@ fn App() -> Element {
         });
     });
 
+    // 触摸输入：JS 只上报起止点与选项矩形，手势识别在 Rust 侧（见 touch.rs）
+    let app_state_touch = app_state.clone();
+    use_hook(move || {
+        spawn(async move {
+            let mut eval = document::eval(
+                r#"
+                let start = null;
+                document.addEventListener("touchstart", function(e) {
+                    if (!e.target.closest(".vn-scene") || e.touches.length !== 1) {
+                        start = null;
+                        return;
+                    }
+                    const t = e.touches[0];
+                    start = { x: t.clientX, y: t.clientY };
+                }, { passive: true });
+                document.addEventListener("touchend", function(e) {
+                    if (!start || e.changedTouches.length !== 1) return;
+                    const t = e.changedTouches[0];
+                    const rects = Array.from(document.querySelectorAll(".vn-choices__btn"))
+                        .map(function(el) {
+                            const r = el.getBoundingClientRect();
+                            return { x: r.left, y: r.top, width: r.width, height: r.height };
+                        });
+                    // 阻止 WebView 合成 click，Rust 侧另有时间窗口兜底
+                    e.preventDefault();
+                    dioxus.send({
+                        start: start,
+                        end: { x: t.clientX, y: t.clientY },
+                        choice_rects: rects,
+                    });
+                    start = null;
+                }, { passive: false });
+                "#,
+            );
+
+            loop {
+                let stroke: Result<touch::TouchStroke, _> = eval.recv().await;
+                let Ok(stroke) = stroke else { break };
+                if let Ok(mut inner) = app_state_touch.inner.lock() {
+                    inner.process_touch(&stroke, now_millis());
+                }
+            }
+        });
+    });
+
     // 根据初始化阶段和 host_screen 路由渲染
     let phase = init_phase.read().clone();
     match phase {
@@ -601,6 +785,9 @@ fn App() -> Element {
                             HistoryScreen { render_state }
                         },
                     }
+                    if var_watch() {
+                        VarWatchOverlay { render_state }
+                    }
                     // 确认弹窗（z-index 最高，覆盖所有页面）
                     ConfirmDialog {}
                     // Toast 提示（右上角）
@@ -1,11 +1,13 @@
 //! 资源清单（Manifest）
 //!
-//! 立绘元数据管理：角色组配置、锚点、预缩放、站位预设。
+//! 立绘元数据管理：角色组配置、锚点、预缩放、站位预设；
+//! 以及章节脚本包含表（声明脚本顺序与标题）。
 
 use crate::resources::normalize_logical_path;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use vn_runtime::ScriptIndexEntry;
 
 /// 2D 点（归一化坐标）
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -120,6 +122,12 @@ pub struct Manifest {
     pub presets: HashMap<String, PositionPreset>,
     #[serde(default)]
     pub defaults: DefaultsConfig,
+    /// 章节脚本包含表（按声明顺序）
+    #[serde(default)]
+    pub scripts: Vec<ScriptIndexEntry>,
+    /// 音效池：池名 → 候选音效路径（脚本 `playSfx @池名` 从中随机抽取）
+    #[serde(default)]
+    pub sfx_pools: BTreeMap<String, Vec<String>>,
 }
 
 impl Manifest {
@@ -155,6 +163,8 @@ impl Manifest {
             characters: CharactersConfig::default(),
             presets,
             defaults: DefaultsConfig::default(),
+            scripts: Vec::new(),
+            sfx_pools: BTreeMap::new(),
         }
     }
 
@@ -293,4 +303,38 @@ mod tests {
             ManifestWarning::UnknownGroup { group, .. } if group == "missing_group"
         )));
     }
+
+    #[test]
+    fn scripts_listed_in_declared_order_with_undeclared_appended() {
+        let content = r#"
+        {
+          "scripts": [
+            { "path": "scripts/prologue.md", "title": "序章" },
+            { "path": "scripts/chapter10.md", "title": "第十章" }
+          ]
+        }
+        "#;
+        let (manifest, _) = Manifest::parse_and_validate(content).unwrap();
+        let discovered = [
+            "scripts/bonus.md",
+            "scripts/chapter10.md",
+            "scripts/appendix.md",
+            "scripts/prologue.md",
+        ]
+        .map(String::from);
+
+        let listed = vn_runtime::order_scripts(&manifest.scripts, discovered);
+        let paths: Vec<&str> = listed.iter().map(|s| s.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "scripts/prologue.md",
+                "scripts/chapter10.md",
+                "scripts/appendix.md",
+                "scripts/bonus.md",
+            ]
+        );
+        assert_eq!(listed[1].title.as_deref(), Some("第十章"));
+        assert_eq!(listed[2].title, None);
+    }
 }
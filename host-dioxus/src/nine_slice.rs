@@ -0,0 +1,62 @@
+//! 九宫格（nine-slice）背景
+//!
+//! 对话框、名牌与选项按钮的背景纹理按边距切成 3×3 九块：
+//! 四角保持原尺寸，上下边只横向拉伸、左右边只纵向拉伸，中心双向拉伸。
+//! 前端用 CSS `border-image` 绘制（目标尺寸容不下边距时由浏览器等比缩小四角），
+//! 这里负责由 layout.json 的边框配置生成对应样式。
+
+/// 九宫格边距（纹理像素，同时也是绘制时四角的尺寸）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NineSlice {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl NineSlice {
+    /// 由 layout.json 的边框数组 `(left, top, right, bottom)` 构造
+    pub fn from_borders([left, top, right, bottom]: [f32; 4]) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    /// 内联 CSS：`border-image-slice` / `border-image-width`，`source` 给出时同时覆盖纹理
+    pub fn css(&self, source: Option<&str>) -> String {
+        let source = source
+            .map(|url| format!("border-image-source: url(\"{url}\");"))
+            .unwrap_or_default();
+        format!(
+            "{source}border-image-slice: {t} {r} {b} {l} fill;border-image-width: {t}px {r}px {b}px {l}px;",
+            t = self.top,
+            r = self.right,
+            b = self.bottom,
+            l = self.left,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn css_lists_edges_in_border_image_order() {
+        let slice = NineSlice::from_borders([150.0, 8.0, 120.0, 6.0]);
+        assert_eq!(
+            slice.css(None),
+            "border-image-slice: 8 120 6 150 fill;border-image-width: 8px 120px 6px 150px;"
+        );
+        assert!(
+            slice
+                .css(Some("http://ring-asset.localhost/gui/textbox.png"))
+                .starts_with(
+                    "border-image-source: url(\"http://ring-asset.localhost/gui/textbox.png\");"
+                )
+        );
+    }
+}
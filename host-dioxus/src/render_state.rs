@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
+use vn_runtime::command::{BackgroundLayer, Color, FilterKind, WeatherKind};
 use vn_runtime::command::{InlineEffect, InlineEffectKind, Position, TextMode};
 use vn_runtime::state::VarValue;
 
+use crate::weather::WeatherState;
+
 /// 活跃的 UI 模式请求
 #[derive(Debug, Clone, Serialize)]
 pub struct UiModeRequest {
@@ -30,6 +33,11 @@ impl HostScreen {
     pub fn allows_progression(&self) -> bool {
         matches!(self, Self::InGame)
     }
+
+    /// 该界面下场景层（`.vn-scene`）是否在渲染，可供抓取缩略图
+    pub fn shows_scene(&self) -> bool {
+        matches!(self, Self::InGame | Self::InGameMenu)
+    }
 }
 
 pub fn var_value_to_json(v: &VarValue) -> serde_json::Value {
@@ -67,31 +75,140 @@ pub(crate) fn position_to_preset_name(position: Position) -> &'static str {
         Position::Right => "right",
         Position::NearRight => "nearright",
         Position::FarRight => "farright",
+        // 纵向位置与缩放沿用中央预设，横向由自动布局计算
+        Position::Auto => "center",
     }
 }
 
+/// 自动布局下 `count` 个角色从左到右的目标 x（舞台宽度比例）
+///
+/// `occupied` 为固定站位角色已占用的 x，把舞台切成若干空档；角色逐个放入
+/// 放入后间距最大的空档，同一空档内等分站位。没有固定角色时整个舞台是一个空档：
+/// 2 人 1/3、2/3，3 人 1/4、1/2、3/4。
+pub fn auto_layout_xs(occupied: &[f32], count: usize) -> Vec<f32> {
+    let mut bounds: Vec<f32> = occupied.iter().map(|x| x.clamp(0.0, 1.0)).collect();
+    bounds.push(0.0);
+    bounds.push(1.0);
+    bounds.sort_by(f32::total_cmp);
+    let gaps: Vec<(f32, f32)> = bounds.windows(2).map(|w| (w[0], w[1])).collect();
+
+    let mut counts = vec![0usize; gaps.len()];
+    for _ in 0..count {
+        let spacing = |i: usize| (gaps[i].1 - gaps[i].0) / (counts[i] + 2) as f32;
+        if let Some(best) =
+            (0..gaps.len()).max_by(|&a, &b| spacing(a).total_cmp(&spacing(b)).then(b.cmp(&a)))
+        {
+            counts[best] += 1;
+        }
+    }
+
+    gaps.iter()
+        .zip(counts)
+        .flat_map(|(&(lo, hi), n)| {
+            let slots = (n + 1) as f32;
+            (1..=n).map(move |i| lo + (hi - lo) * i as f32 / slots)
+        })
+        .collect()
+}
+
+/// 视差图层在镜头平移 `camera_pan`（像素）下的偏移量
+///
+/// 图层与镜头反向移动，偏移按视差系数缩放：远景系数小、移动慢，从而产生纵深。
+pub fn parallax_offset(parallax: f64, camera_pan: (f32, f32)) -> (f32, f32) {
+    let factor = parallax as f32;
+    (-camera_pan.0 * factor, -camera_pan.1 * factor)
+}
+
+/// 中文排版中成对出现、占两个全角位的标点（`……`、`——`）
+const PAIRED_PUNCTUATION: [char; 2] = ['…', '—'];
+
+/// 打字机下一步显示的字符数：成对标点一次显示两个，避免拆半显示
+///
+/// 两个字符之间恰有内联效果（如 `{wait}`）时仍逐字推进，保证效果按位置触发。
+fn typewriter_step(content: &str, visible: usize, effects: &[InlineEffect]) -> usize {
+    let mut next = content.chars().skip(visible);
+    match (next.next(), next.next()) {
+        (Some(a), Some(b))
+            if a == b
+                && PAIRED_PUNCTUATION.contains(&a)
+                && !effects.iter().any(|e| e.position == visible + 1) =>
+        {
+            2
+        }
+        _ => 1,
+    }
+}
+
+/// 逐字淡入模式下单个字符从透明到不透明的时长（秒）
+pub const CHAR_FADE_DURATION: f32 = 0.3;
+
+/// 对话文本的显示方式（用户设置）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextRevealMode {
+    /// 打字机：字符出现即完全不透明
+    #[default]
+    Typewriter,
+    /// 逐字淡入：字符按打字机节奏依次出现，各自在 [`CHAR_FADE_DURATION`] 内淡入
+    FadeIn,
+}
+
 /// 当前帧的完整渲染状态
 #[derive(Debug, Clone, Serialize)]
 pub struct RenderState {
     pub current_background: Option<String>,
+    /// 纯色背景（与 `current_background` 互斥）
+    pub background_fill: Option<Color>,
+    /// 多层视差背景（按远到近排列，与 `current_background`/`background_fill` 互斥）
+    pub background_layers: Vec<BackgroundLayer>,
+    /// 镜头平移量（像素），视差背景各层据此按系数偏移
+    pub camera_pan: (f32, f32),
+    /// 全屏 CG（覆盖背景与立绘）
+    pub cg: Option<CgState>,
     pub visible_characters: HashMap<String, CharacterSprite>,
+    /// 下一个入场角色的顺序号（自动布局按入场顺序排列）
+    #[serde(skip)]
+    pub next_show_seq: u64,
+    /// 角色头顶气泡（按显示顺序，绑定角色别名）
+    pub bubbles: Vec<SpeechBubble>,
     pub dialogue: Option<DialogueState>,
     pub chapter_mark: Option<ChapterMarkState>,
     pub choices: Option<ChoicesState>,
     pub ui_visible: bool,
+    /// 演出空档中对话框被自动隐藏（下一条文本出现时恢复，不影响 `ui_visible`）
+    pub textbox_auto_hidden: bool,
     pub title_card: Option<TitleCardState>,
     pub scene_effect: SceneEffectState,
     pub text_mode: TextMode,
+    /// 对话框样式名（None 为默认样式）
+    pub textbox_style: Option<String>,
     pub nvl_entries: Vec<NvlEntry>,
     pub background_transition: Option<BackgroundTransition>,
     pub scene_transition: Option<SceneTransition>,
     pub cutscene: Option<CutsceneState>,
+    pub rumble: Option<RumbleRequest>,
+    pub weather: Option<WeatherState>,
+    /// 整帧滤镜（作用于场景画面，不影响对话框等 UI）
+    pub screen_filter: Option<ScreenFilter>,
     pub playback_mode: PlaybackMode,
     pub audio: AudioRenderState,
+    /// 即将用到的图片（隐藏预取，见 `VNRuntime::upcoming_resources`）
+    pub preload_images: Vec<String>,
     pub active_ui_mode: Option<UiModeRequest>,
     pub host_screen: HostScreen,
 }
 
+/// 全屏 CG 状态（Contain 模式显示，淡入淡出与立绘 alpha 过渡同理）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CgState {
+    pub path: String,
+    pub alpha: f32,
+    pub target_alpha: f32,
+    pub transition_duration: Option<f32>,
+    /// 正在淡出，完成后移除
+    pub hiding: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct CharacterSprite {
     pub texture_path: String,
@@ -110,11 +227,190 @@ pub struct CharacterSprite {
     pub anchor_x: f32,
     pub anchor_y: f32,
     pub render_scale: f32,
+    /// 循环待机动画；None 表示静止
+    pub idle: Option<IdleKind>,
+    /// 表情交叉淡化中：旧表情叠在新纹理之上淡出
+    pub crossfade: Option<ExpressionCrossfade>,
+    /// 轮廓光强度（0.0 ~ 1.0，0 为关闭）
+    pub rim_light: f32,
+    /// 一次性弹跳动画（`with bounce`），结束后清空；不随存档保存
+    pub bounce: Option<CharacterBounce>,
+    /// 入场顺序号；换站位时重新编号，自动布局中排到最右
+    #[serde(skip)]
+    pub show_seq: u64,
+}
+
+/// 角色头顶气泡（旁注/心声），绘制在对应立绘上方
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpeechBubble {
+    pub alias: String,
+    pub content: String,
+}
+
+/// 同一角色切换表情时的交叉淡化（不重新入场）
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpressionCrossfade {
+    /// 旧表情纹理
+    pub old_texture: String,
+    pub duration: f32,
+    /// 已经过时间（内部计时，不推送到前端）
+    #[serde(skip)]
+    pub elapsed: f32,
+}
+
+impl ExpressionCrossfade {
+    /// 推进计时，返回是否已完成
+    pub fn advance(&mut self, dt: f32) -> bool {
+        self.elapsed += dt;
+        self.elapsed >= self.duration
+    }
+}
+
+/// 每一跳的时长（秒）
+const BOUNCE_SECONDS_PER_HOP: f32 = 0.35;
+
+/// 立绘弹跳（惊讶/开心强调）：沿 y 方向连跳数次，每跳高度减半
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CharacterBounce {
+    /// 首跳高度（像素）
+    pub height: f32,
+    /// 弹跳次数
+    pub times: u32,
+    /// 已经过时间（内部计时，不推送到前端）
+    #[serde(skip)]
+    pub elapsed: f32,
+}
+
+impl CharacterBounce {
+    pub fn new(height: f32, times: u32) -> Self {
+        Self {
+            height,
+            times,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        BOUNCE_SECONDS_PER_HOP * self.times as f32
+    }
+
+    /// 推进计时，返回是否已完成
+    pub fn advance(&mut self, dt: f32) -> bool {
+        self.elapsed += dt;
+        self.elapsed >= self.duration()
+    }
+
+    /// 当前纵向偏移（像素，向上为负）
+    pub fn offset_y(&self) -> f32 {
+        if self.elapsed >= self.duration() {
+            return 0.0;
+        }
+        let hop = (self.elapsed / BOUNCE_SECONDS_PER_HOP).floor();
+        let phase = (self.elapsed - hop * BOUNCE_SECONDS_PER_HOP) / BOUNCE_SECONDS_PER_HOP;
+        let amplitude = self.height * 0.5_f32.powi(hop as i32);
+        -amplitude * (std::f32::consts::PI * phase).sin()
+    }
+}
+
+/// 轮廓光偏移半径（像素）
+const RIM_LIGHT_RADIUS_PX: f32 = 3.0;
+/// 轮廓光强度为 1 时每层剪影的不透明度
+const RIM_LIGHT_MAX_ALPHA: f32 = 0.6;
+/// 轮廓光的偏移方向（八方向单位向量）
+const RIM_LIGHT_DIRECTIONS: [(f32, f32); 8] = [
+    (1.0, 0.0),
+    (-1.0, 0.0),
+    (0.0, 1.0),
+    (0.0, -1.0),
+    (0.7071, 0.7071),
+    (0.7071, -0.7071),
+    (-0.7071, 0.7071),
+    (-0.7071, -0.7071),
+];
+
+/// 轮廓光的一层绘制：在立绘下方偏移 `(dx, dy)` 像素叠一层半透明亮色剪影
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RimLightPass {
+    pub dx: f32,
+    pub dy: f32,
+    pub alpha: f32,
+}
+
+impl CharacterSprite {
+    /// 弹跳动画叠加的纵向偏移（像素）；未在弹跳时为 0
+    pub fn bounce_offset_y(&self) -> f32 {
+        self.bounce.as_ref().map_or(0.0, CharacterBounce::offset_y)
+    }
+
+    /// 轮廓光需要额外绘制的剪影层；未开启（强度为 0）时为空
+    pub fn rim_light_passes(&self) -> Vec<RimLightPass> {
+        if self.rim_light <= 0.0 {
+            return Vec::new();
+        }
+        let alpha = self.rim_light.min(1.0) * RIM_LIGHT_MAX_ALPHA;
+        RIM_LIGHT_DIRECTIONS
+            .iter()
+            .map(|&(x, y)| RimLightPass {
+                dx: x * RIM_LIGHT_RADIUS_PX,
+                dy: y * RIM_LIGHT_RADIUS_PX,
+                alpha,
+            })
+            .collect()
+    }
+}
+
+/// 按绘制顺序（由下到上）排列立绘
+///
+/// z_order 相同时正在淡出的立绘垫在下层，与同时淡入的新立绘交叉时不会互相遮挡跳变；
+/// 其余按 alias 排序，保证每帧顺序稳定。
+pub fn characters_in_draw_order(
+    characters: &HashMap<String, CharacterSprite>,
+) -> Vec<(&String, &CharacterSprite)> {
+    let mut sorted: Vec<_> = characters.iter().collect();
+    sorted.sort_by(|(a_alias, a), (b_alias, b)| {
+        a.z_order
+            .cmp(&b.z_order)
+            .then(b.fading_out.cmp(&a.fading_out))
+            .then(a_alias.cmp(b_alias))
+    });
+    sorted
+}
+
+/// 立绘循环待机动画种类（前端以 CSS 关键帧循环播放）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleKind {
+    /// 呼吸：以锚点为中心轻微缩放
+    Breathe,
+    /// 摇摆：轻微左右位移
+    Sway,
+}
+
+impl IdleKind {
+    /// 从脚本名解析（大小写不敏感），未知名称返回 None
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "breathe" => Some(Self::Breathe),
+            "sway" => Some(Self::Sway),
+            _ => None,
+        }
+    }
+
+    /// CSS 类名后缀 / 存档名
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Breathe => "breathe",
+            Self::Sway => "sway",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct BackgroundTransition {
     pub old_background: Option<String>,
+    /// 旧背景为纯色时的颜色
+    pub old_fill: Option<Color>,
+    /// 新背景路径；切换到纯色时为 `#rrggbb`
     pub new_background: String,
     pub duration: f32,
 }
@@ -146,6 +442,61 @@ pub enum SceneTransitionPhaseState {
     Completed,
 }
 
+/// 场景过渡生命周期事件
+///
+/// 由命令执行器（`Started`）与过渡推进（`Midpoint` / `Completed`）产出，
+/// 主循环据此换背景、解除等待，不再轮询 phase。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionEvent {
+    /// 过渡已开始（遮罩开始淡入）
+    Started,
+    /// 遮罩完全覆盖画面，此时切换背景
+    Midpoint,
+    /// 过渡结束
+    Completed,
+}
+
+impl SceneTransition {
+    /// 遮罩完全覆盖后的停留时长（秒）
+    pub const HOLD_DURATION: f32 = 0.2;
+
+    /// 按当前阶段已经过的时间推进 phase，返回阶段切换产生的事件
+    ///
+    /// 返回值第一项表示 phase 是否变化（调用方据此重置阶段计时）。
+    pub fn advance(&mut self, phase_elapsed: f32) -> (bool, Option<TransitionEvent>) {
+        match self.phase {
+            SceneTransitionPhaseState::FadeIn if phase_elapsed >= self.duration => {
+                self.phase = SceneTransitionPhaseState::Hold;
+                (true, Some(TransitionEvent::Midpoint))
+            }
+            SceneTransitionPhaseState::Hold if phase_elapsed >= Self::HOLD_DURATION => {
+                self.phase = SceneTransitionPhaseState::FadeOut;
+                (true, None)
+            }
+            SceneTransitionPhaseState::FadeOut if phase_elapsed >= self.duration => {
+                self.phase = SceneTransitionPhaseState::Completed;
+                (true, Some(TransitionEvent::Completed))
+            }
+            _ => (false, None),
+        }
+    }
+
+    /// 立即推到终态，按序返回尚未产出的事件
+    pub fn finish(&mut self) -> Vec<TransitionEvent> {
+        let events = match self.phase {
+            SceneTransitionPhaseState::FadeIn => {
+                vec![TransitionEvent::Midpoint, TransitionEvent::Completed]
+            }
+            SceneTransitionPhaseState::Hold | SceneTransitionPhaseState::FadeOut => {
+                vec![TransitionEvent::Completed]
+            }
+            SceneTransitionPhaseState::Completed => Vec::new(),
+        };
+        self.phase = SceneTransitionPhaseState::Completed;
+        events
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct DialogueState {
     pub speaker: Option<String>,
@@ -156,6 +507,79 @@ pub struct DialogueState {
     pub no_wait: bool,
     pub inline_wait: Option<InlineWait>,
     pub effective_cps: Option<EffectiveCps>,
+    /// 整行速度系数（脚本 `角色[speed=N]：` 标注），缺省 1.0
+    pub speed_factor: f32,
+    /// 已显示字符各自的淡入进度（0.0 ~ 1.0），与 `visible_chars` 等长
+    pub char_alpha: Vec<f32>,
+}
+
+impl DialogueState {
+    /// 已显示的文本按粗体标记切成 `(文本, 是否粗体)` 片段
+    pub fn visible_segments(&self) -> Vec<(String, bool)> {
+        styled_segments(&self.content, &self.inline_effects, self.visible_chars)
+    }
+
+    /// 已显示的字符逐个返回 `(字符, 是否粗体, 不透明度)`，供逐字淡入渲染
+    pub fn visible_glyphs(&self) -> Vec<(char, bool, f32)> {
+        let mut bold = false;
+        self.content
+            .chars()
+            .take(self.visible_chars)
+            .enumerate()
+            .map(|(i, ch)| {
+                for effect in self.inline_effects.iter().filter(|e| e.position == i) {
+                    match effect.kind {
+                        InlineEffectKind::BoldStart => bold = true,
+                        InlineEffectKind::BoldEnd => bold = false,
+                        _ => {}
+                    }
+                }
+                (ch, bold, self.char_alpha.get(i).copied().unwrap_or(1.0))
+            })
+            .collect()
+    }
+
+    /// 当前应显示的指示器：打完或停在 `{wait}` 点击等待处时提示点击，否则提示仍在说话
+    pub fn indicator(&self) -> DialogueIndicator {
+        if self.is_complete || matches!(self.inline_wait, Some(InlineWait::Click)) {
+            DialogueIndicator::Advance
+        } else {
+            DialogueIndicator::Typing
+        }
+    }
+}
+
+/// 对话框文本末尾的指示器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogueIndicator {
+    /// 文本仍在打出：跳动的省略点
+    Typing,
+    /// 等待点击推进：闪烁的箭头
+    Advance,
+}
+
+/// 取文本前 `visible_chars` 个字符，按 `BoldStart` / `BoldEnd` 切成 `(文本, 是否粗体)` 片段
+pub fn styled_segments(
+    content: &str,
+    effects: &[InlineEffect],
+    visible_chars: usize,
+) -> Vec<(String, bool)> {
+    let mut segments: Vec<(String, bool)> = Vec::new();
+    let mut bold = false;
+    for (i, ch) in content.chars().take(visible_chars).enumerate() {
+        for effect in effects.iter().filter(|e| e.position == i) {
+            match effect.kind {
+                InlineEffectKind::BoldStart => bold = true,
+                InlineEffectKind::BoldEnd => bold = false,
+                _ => {}
+            }
+        }
+        match segments.last_mut() {
+            Some((text, style)) if *style == bold => text.push(ch),
+            _ => segments.push((ch.to_string(), bold)),
+        }
+    }
+    segments
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -186,6 +610,29 @@ pub struct TitleCardState {
     pub elapsed: f32,
 }
 
+/// 整帧滤镜模糊强度为 1 时的模糊半径（像素）
+const FILTER_MAX_BLUR_PX: f32 = 8.0;
+
+/// 整帧滤镜
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScreenFilter {
+    pub kind: FilterKind,
+    /// 强度（0.0 ~ 1.0）
+    pub intensity: f32,
+}
+
+impl ScreenFilter {
+    /// 对应的 CSS `filter` 函数
+    pub fn css(&self) -> String {
+        match self.kind {
+            FilterKind::None => String::new(),
+            FilterKind::Grayscale => format!("grayscale({})", self.intensity),
+            FilterKind::Sepia => format!("sepia({})", self.intensity),
+            FilterKind::Blur => format!("blur({}px)", self.intensity * FILTER_MAX_BLUR_PX),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct SceneEffectState {
     pub shake_offset_x: f32,
@@ -214,14 +661,61 @@ pub struct ChapterMarkState {
 pub struct ChoiceItem {
     pub text: String,
     pub target_label: String,
+    /// 是否可选；不可选时灰显但仍显示
+    pub enabled: bool,
+    /// 不可选的理由
+    pub disabled_reason: Option<String>,
+    /// 此前是否选过（已走过的分支）
+    pub visited: bool,
 }
 
+/// 选项激活动画时长（秒）：激活进度从 0 变到 1 所需时间
+pub const CHOICE_HIGHLIGHT_DURATION: f32 = 0.15;
+/// 激活选项的目标缩放
+pub const CHOICE_ACTIVE_SCALE: f32 = 1.05;
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ChoicesState {
     pub choices: Vec<ChoiceItem>,
     pub style: Option<String>,
     pub selected_index: usize,
     pub hovered_index: Option<usize>,
+    /// 每项的激活动画进度（0 = 常态，1 = 完全放大高亮）
+    pub highlight: Vec<f32>,
+}
+
+impl ChoicesState {
+    /// 当前激活的选项：悬停优先，否则为选中项；不可选的项不会激活
+    pub fn active_index(&self) -> Option<usize> {
+        let index = self.hovered_index.unwrap_or(self.selected_index);
+        self.choices
+            .get(index)
+            .is_some_and(|c| c.enabled)
+            .then_some(index)
+    }
+
+    /// 推进激活动画：激活项进度向 1、其余项向 0 线性插值
+    pub fn update_highlight(&mut self, dt: f32) {
+        let active = self.active_index();
+        let step = dt / CHOICE_HIGHLIGHT_DURATION;
+        for (i, progress) in self.highlight.iter_mut().enumerate() {
+            *progress = if Some(i) == active {
+                (*progress + step).min(1.0)
+            } else {
+                (*progress - step).max(0.0)
+            };
+        }
+    }
+
+    /// 第 `index` 项的激活进度
+    pub fn item_highlight(&self, index: usize) -> f32 {
+        self.highlight.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// 第 `index` 项的当前缩放
+    pub fn item_scale(&self, index: usize) -> f32 {
+        1.0 + (CHOICE_ACTIVE_SCALE - 1.0) * self.item_highlight(index)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -230,6 +724,16 @@ pub struct CutsceneState {
     pub is_playing: bool,
 }
 
+/// 最近一次手柄震动请求。
+///
+/// `id` 单调递增，前端据此判断是否为新请求（同参数连续震动也能触发）。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RumbleRequest {
+    pub id: u64,
+    pub intensity: f32,
+    pub duration: f32,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PlaybackMode {
@@ -242,6 +746,8 @@ pub enum PlaybackMode {
 pub struct AudioRenderState {
     pub bgm: Option<BgmState>,
     pub sfx_queue: Vec<SfxRequest>,
+    /// 需要提前停止的音效 id（被并发上限替换）
+    pub sfx_stop: Vec<u64>,
     pub bgm_transition: Option<BgmTransition>,
 }
 
@@ -259,6 +765,8 @@ pub struct BgmState {
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SfxRequest {
+    /// 播放实例 id，用于提前停止
+    pub id: u64,
     pub path: String,
     pub volume: f32,
 }
@@ -268,6 +776,7 @@ impl AudioRenderState {
         Self {
             bgm: None,
             sfx_queue: Vec::new(),
+            sfx_stop: Vec::new(),
             bgm_transition: None,
         }
     }
@@ -281,20 +790,32 @@ impl RenderState {
     pub fn new() -> Self {
         Self {
             current_background: None,
+            background_fill: None,
+            background_layers: Vec::new(),
+            camera_pan: (0.0, 0.0),
+            cg: None,
             visible_characters: HashMap::new(),
+            next_show_seq: 0,
+            bubbles: Vec::new(),
             dialogue: None,
             chapter_mark: None,
             choices: None,
             ui_visible: true,
+            textbox_auto_hidden: false,
             title_card: None,
             scene_effect: SceneEffectState::default(),
             text_mode: TextMode::ADV,
+            textbox_style: None,
             nvl_entries: Vec::new(),
             background_transition: None,
             scene_transition: None,
             cutscene: None,
+            rumble: None,
+            weather: None,
+            screen_filter: None,
             playback_mode: PlaybackMode::Normal,
             audio: AudioRenderState::silent(),
+            preload_images: Vec::new(),
             active_ui_mode: None,
             host_screen: HostScreen::Title,
         }
@@ -302,6 +823,103 @@ impl RenderState {
 
     pub fn set_background(&mut self, path: String) {
         self.current_background = Some(path);
+        self.background_fill = None;
+        self.background_layers.clear();
+    }
+
+    /// 切换为纯色背景
+    pub fn set_fill(&mut self, color: Color) {
+        self.current_background = None;
+        self.background_fill = Some(color);
+        self.background_layers.clear();
+    }
+
+    /// 切换为多层视差背景
+    pub fn set_background_layers(&mut self, layers: Vec<BackgroundLayer>) {
+        self.current_background = None;
+        self.background_fill = None;
+        self.background_layers = layers;
+    }
+
+    /// 各视差图层在当前镜头平移下的偏移量（与 `background_layers` 一一对应）
+    pub fn background_layer_offsets(&self) -> Vec<(f32, f32)> {
+        self.background_layers
+            .iter()
+            .map(|layer| parallax_offset(layer.parallax, self.camera_pan))
+            .collect()
+    }
+
+    /// 记录背景交叉淡化的旧背景（图片或纯色），新背景随后由调用方设置
+    pub fn begin_background_transition(&mut self, new_background: String, duration: f32) {
+        self.background_transition = Some(BackgroundTransition {
+            old_background: self.current_background.clone(),
+            old_fill: self.background_fill,
+            new_background,
+            duration,
+        });
+    }
+
+    /// 显示 CG；`fade` 为淡入时长（秒），None 表示立即显示
+    pub fn show_cg(&mut self, path: String, fade: Option<f32>) {
+        let fade = fade.filter(|d| *d > 0.0);
+        self.cg = Some(CgState {
+            path,
+            alpha: if fade.is_some() { 0.0 } else { 1.0 },
+            target_alpha: 1.0,
+            transition_duration: fade,
+            hiding: false,
+        });
+    }
+
+    /// 隐藏 CG；`fade` 为淡出时长（秒），None 表示立即移除
+    pub fn hide_cg(&mut self, fade: Option<f32>) {
+        match (self.cg.as_mut(), fade.filter(|d| *d > 0.0)) {
+            (Some(cg), Some(duration)) => {
+                cg.target_alpha = 0.0;
+                cg.transition_duration = Some(duration);
+                cg.hiding = true;
+            }
+            _ => self.cg = None,
+        }
+    }
+
+    /// 推进 CG 淡入淡出，淡出完成后移除
+    pub fn update_cg(&mut self, dt: f32) {
+        let Some(cg) = self.cg.as_mut() else {
+            return;
+        };
+        if let Some(duration) = cg.transition_duration {
+            let step = dt / duration;
+            cg.alpha = if cg.alpha < cg.target_alpha {
+                (cg.alpha + step).min(cg.target_alpha)
+            } else {
+                (cg.alpha - step).max(cg.target_alpha)
+            };
+            if (cg.alpha - cg.target_alpha).abs() <= f32::EPSILON {
+                cg.transition_duration = None;
+            }
+        }
+        if cg.hiding && cg.transition_duration.is_none() {
+            self.cg = None;
+        }
+    }
+
+    /// CG 是否正在淡入淡出
+    pub fn is_cg_transitioning(&self) -> bool {
+        self.cg
+            .as_ref()
+            .is_some_and(|cg| cg.transition_duration.is_some())
+    }
+
+    /// 立即完成 CG 淡入淡出
+    pub fn finish_cg_transition(&mut self) {
+        if let Some(cg) = self.cg.as_mut() {
+            cg.alpha = cg.target_alpha;
+            cg.transition_duration = None;
+        }
+        if self.cg.as_ref().is_some_and(|cg| cg.hiding) {
+            self.cg = None;
+        }
     }
 
     pub fn show_character(
@@ -331,16 +949,63 @@ impl RenderState {
             anchor_x: group.anchor.x,
             anchor_y: group.anchor.y,
             render_scale: group.pre_scale * preset.scale,
+            idle: None,
+            crossfade: None,
+            rim_light: 0.0,
+            bounce: None,
+            show_seq: self.next_show_seq,
         };
+        self.next_show_seq += 1;
         self.visible_characters.insert(alias, sprite);
     }
 
+    /// 按入场顺序排列的立绘
+    pub fn characters_in_show_order(&self) -> Vec<(&String, &CharacterSprite)> {
+        let mut sorted: Vec<_> = self.visible_characters.iter().collect();
+        sorted.sort_by_key(|(_, c)| c.show_seq);
+        sorted
+    }
+
+    /// 重新计算自动布局站位
+    ///
+    /// 只有 `auto` 站位的角色参与自动布局：按入场顺序从左到右排在固定站位角色
+    /// 留出的空档中（见 [`auto_layout_xs`]）。固定站位与淡出中的角色保持原位。
+    pub fn apply_auto_layout(&mut self) {
+        let (auto, fixed): (Vec<_>, Vec<_>) = self
+            .characters_in_show_order()
+            .into_iter()
+            .filter(|(_, c)| !c.fading_out)
+            .partition(|(_, c)| c.position == Position::Auto);
+        let occupied: Vec<f32> = fixed.iter().map(|(_, c)| c.pos_x).collect();
+        let auto: Vec<String> = auto.into_iter().map(|(alias, _)| alias.clone()).collect();
+
+        for (alias, x) in auto.iter().zip(auto_layout_xs(&occupied, auto.len())) {
+            if let Some(c) = self.visible_characters.get_mut(alias) {
+                c.pos_x = x;
+            }
+        }
+    }
+
     pub fn hide_character(&mut self, alias: &str) {
         self.visible_characters.remove(alias);
+        self.remove_bubble(alias);
     }
 
     pub fn hide_all_characters(&mut self) {
         self.visible_characters.clear();
+        self.bubbles.clear();
+    }
+
+    /// 显示角色气泡：同一角色只保留一个，再次显示时替换并移到最上层；`content` 为空时清除
+    pub fn set_bubble(&mut self, alias: String, content: String) {
+        self.remove_bubble(&alias);
+        if !content.is_empty() {
+            self.bubbles.push(SpeechBubble { alias, content });
+        }
+    }
+
+    pub fn remove_bubble(&mut self, alias: &str) {
+        self.bubbles.retain(|b| b.alias != alias);
     }
 
     pub fn set_dialogue(
@@ -350,6 +1015,7 @@ impl RenderState {
         inline_effects: Vec<InlineEffect>,
         no_wait: bool,
     ) {
+        self.textbox_auto_hidden = false;
         self.dialogue = Some(DialogueState {
             speaker,
             content,
@@ -359,9 +1025,18 @@ impl RenderState {
             no_wait,
             inline_wait: None,
             effective_cps: None,
+            speed_factor: 1.0,
+            char_alpha: Vec::new(),
         });
     }
 
+    /// 设置当前对话的整行速度系数
+    pub fn set_dialogue_speed_factor(&mut self, factor: f32) {
+        if let Some(d) = self.dialogue.as_mut() {
+            d.speed_factor = factor;
+        }
+    }
+
     pub fn start_typewriter(
         &mut self,
         speaker: Option<String>,
@@ -380,7 +1055,9 @@ impl RenderState {
             return true;
         }
 
-        d.visible_chars += 1;
+        d.visible_chars += typewriter_step(&d.content, d.visible_chars, &d.inline_effects);
+        // 新出现的字符从透明开始淡入，先出现的字已领先，形成错峰
+        d.char_alpha.resize(d.visible_chars, 0.0);
 
         for effect in &d.inline_effects {
             if effect.position == d.visible_chars {
@@ -400,6 +1077,8 @@ impl RenderState {
                     InlineEffectKind::ResetCps => {
                         d.effective_cps = None;
                     }
+                    // 粗体只影响渲染样式，见 `styled_segments`
+                    InlineEffectKind::BoldStart | InlineEffectKind::BoldEnd => {}
                 }
             }
         }
@@ -419,9 +1098,21 @@ impl RenderState {
         complete
     }
 
+    /// 推进已显示字符的淡入进度
+    pub fn advance_char_fade(&mut self, dt: f32) {
+        let Some(d) = self.dialogue.as_mut() else {
+            return;
+        };
+        let step = dt / CHAR_FADE_DURATION;
+        for alpha in d.char_alpha.iter_mut().filter(|a| **a < 1.0) {
+            *alpha = (*alpha + step).min(1.0);
+        }
+    }
+
     pub fn complete_typewriter(&mut self) {
         if let Some(d) = self.dialogue.as_mut() {
             d.visible_chars = d.content.chars().count();
+            d.char_alpha = vec![1.0; d.visible_chars];
             d.is_complete = true;
             d.inline_wait = None;
             d.effective_cps = None;
@@ -438,6 +1129,7 @@ impl RenderState {
         inline_effects: Vec<InlineEffect>,
         no_wait: bool,
     ) {
+        self.textbox_auto_hidden = false;
         if let Some(d) = self.dialogue.as_mut() {
             let old_len = d.content.chars().count();
             d.content.push_str(&content);
@@ -508,9 +1200,9 @@ impl RenderState {
             return base_speed;
         };
         match &d.effective_cps {
-            None => base_speed,
+            None => base_speed * d.speed_factor,
             Some(EffectiveCps::Absolute(cps)) => *cps as f32,
-            Some(EffectiveCps::Relative(mul)) => base_speed * *mul as f32,
+            Some(EffectiveCps::Relative(mul)) => base_speed * d.speed_factor * *mul as f32,
         }
     }
 
@@ -557,18 +1249,95 @@ impl RenderState {
         }
     }
 
+    pub fn request_rumble(&mut self, intensity: f32, duration: f32) {
+        let id = self.rumble.as_ref().map_or(1, |r| r.id + 1);
+        self.rumble = Some(RumbleRequest {
+            id,
+            intensity,
+            duration,
+        });
+    }
+
+    /// 设置天气；`WeatherKind::None` 或强度为 0 时清除覆盖层
+    pub fn set_weather(&mut self, kind: WeatherKind, intensity: f32) {
+        self.weather = if kind == WeatherKind::None || intensity <= 0.0 {
+            None
+        } else {
+            Some(WeatherState::new(kind, intensity))
+        };
+    }
+
+    /// 设置整帧滤镜；`FilterKind::None` 或强度为 0 时清除
+    pub fn set_filter(&mut self, kind: FilterKind, intensity: f32) {
+        self.screen_filter = if kind == FilterKind::None || intensity <= 0.0 {
+            None
+        } else {
+            Some(ScreenFilter { kind, intensity })
+        };
+    }
+
+    /// 对话框（ADV 对话框 / NVL 面板 / 快捷菜单）当前是否显示
+    pub fn textbox_visible(&self) -> bool {
+        self.ui_visible && !self.textbox_auto_hidden
+    }
+
+    /// 设置角色轮廓光；角色不在场时返回 false
+    pub fn set_rim_light(&mut self, alias: &str, intensity: f32) -> bool {
+        match self.visible_characters.get_mut(alias) {
+            Some(sprite) => {
+                sprite.rim_light = intensity.clamp(0.0, 1.0);
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn set_choices(&mut self, choices: Vec<ChoiceItem>, style: Option<String>) {
+        self.textbox_auto_hidden = false;
+        let selected_index = choices.iter().position(|c| c.enabled).unwrap_or(0);
+        let highlight = vec![0.0; choices.len()];
         self.choices = Some(ChoicesState {
             choices,
             style,
-            selected_index: 0,
+            selected_index,
             hovered_index: None,
+            highlight,
         });
     }
 
+    /// 更新悬停项；悬停到可选项时同时将其设为选中项
+    pub fn set_choice_hover(&mut self, index: Option<usize>) {
+        let enabled = index.is_some_and(|i| self.is_choice_enabled(i));
+        let Some(choices) = self.choices.as_mut() else {
+            return;
+        };
+        match index {
+            Some(i) if enabled => {
+                choices.hovered_index = Some(i);
+                choices.selected_index = i;
+            }
+            _ => choices.hovered_index = None,
+        }
+    }
+
+    /// 推进选项激活动画
+    pub fn update_choices(&mut self, dt: f32) {
+        if let Some(choices) = self.choices.as_mut() {
+            choices.update_highlight(dt);
+        }
+    }
+
     pub fn clear_choices(&mut self) {
         self.choices = None;
     }
+
+    /// 当前选项中第 `index` 项是否存在且可选
+    pub fn is_choice_enabled(&self, index: usize) -> bool {
+        self.choices
+            .as_ref()
+            .and_then(|c| c.choices.get(index))
+            .is_some_and(|c| c.enabled)
+    }
 }
 
 impl Default for RenderState {
@@ -600,6 +1369,58 @@ mod tests {
         assert_eq!(rs.dialogue.as_ref().unwrap().visible_chars, 1);
     }
 
+    #[test]
+    fn advance_typewriter_reveals_paired_ellipsis_and_dash_together() {
+        let mut rs = make_typewriter("嗯……啊…——");
+        let mut steps = Vec::new();
+        while !rs.advance_typewriter() {
+            steps.push(rs.dialogue.as_ref().unwrap().visible_chars);
+        }
+        steps.push(rs.dialogue.as_ref().unwrap().visible_chars);
+        // 嗯 / …… / 啊 / … / ——：单个 … 逐字推进
+        assert_eq!(steps, [1, 3, 4, 5, 7]);
+    }
+
+    #[test]
+    fn char_fade_staggers_alpha_and_ends_fully_opaque() {
+        let mut rs = make_typewriter("ABC");
+        rs.advance_typewriter(); // 'A'
+        rs.advance_char_fade(0.1);
+        rs.advance_typewriter(); // 'B'
+        rs.advance_char_fade(0.1);
+        rs.advance_typewriter(); // 'C'
+        let alpha = |rs: &RenderState| rs.dialogue.as_ref().unwrap().char_alpha.clone();
+
+        // 先出现的字领先：A 已淡入两帧，B 一帧，C 刚出现
+        let a = alpha(&rs);
+        assert!((a[0] - 0.2 / CHAR_FADE_DURATION).abs() < 1e-5);
+        assert!((a[1] - 0.1 / CHAR_FADE_DURATION).abs() < 1e-5);
+        assert_eq!(a[2], 0.0);
+        assert!(a[0] > a[1] && a[1] > a[2]);
+
+        rs.advance_char_fade(0.1);
+        let b = alpha(&rs);
+        assert_eq!(b[0], 1.0, "淡入进度封顶为 1");
+        assert!(b[1] < 1.0 && b[2] > 0.0);
+
+        rs.advance_char_fade(CHAR_FADE_DURATION);
+        assert!(alpha(&rs).iter().all(|&x| x == 1.0));
+        let glyphs = rs.dialogue.as_ref().unwrap().visible_glyphs();
+        assert_eq!(glyphs.len(), 3);
+        assert!(glyphs.iter().all(|&(_, bold, a)| !bold && a == 1.0));
+    }
+
+    #[test]
+    fn complete_typewriter_makes_every_char_opaque() {
+        let mut rs = make_typewriter("Hello");
+        rs.advance_typewriter();
+        rs.complete_typewriter();
+        assert_eq!(
+            rs.dialogue.as_ref().unwrap().char_alpha,
+            vec![1.0; "Hello".len()]
+        );
+    }
+
     #[test]
     fn advance_typewriter_returns_true_when_all_chars_visible() {
         let mut rs = make_typewriter("Hi");
@@ -622,6 +1443,28 @@ mod tests {
         assert!(rs.is_inline_click_wait());
     }
 
+    #[test]
+    fn dialogue_indicator_switches_from_typing_to_advance() {
+        let indicator = |rs: &RenderState| rs.dialogue.as_ref().unwrap().indicator();
+        let effects = vec![InlineEffect {
+            position: 1,
+            kind: InlineEffectKind::Wait(None),
+        }];
+        let mut rs = RenderState::new();
+        rs.start_typewriter(None, "AB".to_string(), effects, false);
+        assert_eq!(indicator(&rs), DialogueIndicator::Typing);
+
+        // 句中点击等待：提示点击
+        rs.advance_typewriter();
+        assert_eq!(indicator(&rs), DialogueIndicator::Advance);
+        rs.clear_inline_wait();
+        assert_eq!(indicator(&rs), DialogueIndicator::Typing);
+
+        rs.advance_typewriter();
+        assert!(rs.is_dialogue_complete());
+        assert_eq!(indicator(&rs), DialogueIndicator::Advance);
+    }
+
     #[test]
     fn advance_typewriter_fires_timed_wait_at_position() {
         let effects = vec![InlineEffect {
@@ -635,6 +1478,32 @@ mod tests {
         assert!(matches!(d.inline_wait, Some(InlineWait::Timed { remaining }) if remaining > 0.0));
     }
 
+    #[test]
+    fn typewriter_holds_at_timed_pause_then_resumes() {
+        // `长句，{w=0.5}继续`：停顿标记位于第 3 个字符之后
+        let effects = vec![InlineEffect {
+            position: 3,
+            kind: InlineEffectKind::Wait(Some(0.5)),
+        }];
+        let mut rs = RenderState::new();
+        rs.start_typewriter(None, "长句，继续".to_string(), effects, false);
+        for _ in 0..3 {
+            rs.advance_typewriter();
+        }
+        assert_eq!(rs.dialogue.as_ref().unwrap().visible_chars, 3);
+        assert!(rs.has_inline_wait());
+        assert!(!rs.is_inline_click_wait());
+
+        assert!(!rs.update_inline_wait(0.3));
+        assert!(rs.has_inline_wait());
+        assert!(rs.update_inline_wait(0.3));
+        assert!(!rs.has_inline_wait());
+
+        rs.advance_typewriter();
+        rs.advance_typewriter();
+        assert!(rs.is_dialogue_complete());
+    }
+
     #[test]
     fn advance_typewriter_fires_set_cps_absolute() {
         let effects = vec![InlineEffect {
@@ -750,6 +1619,21 @@ mod tests {
         assert_eq!(rs.effective_text_speed(30.0), 60.0);
     }
 
+    #[test]
+    fn effective_text_speed_applies_line_speed_factor() {
+        let effects = vec![InlineEffect {
+            position: 1,
+            kind: InlineEffectKind::SetCpsRelative(2.0),
+        }];
+        let mut rs = RenderState::new();
+        rs.start_typewriter(None, "AB".to_string(), effects, false);
+        rs.set_dialogue_speed_factor(0.5);
+        assert_eq!(rs.effective_text_speed(30.0), 15.0);
+        rs.advance_typewriter();
+        // 行内相对倍率在整行系数基础上叠加
+        assert_eq!(rs.effective_text_speed(30.0), 30.0);
+    }
+
     // ── update_chapter_mark ────────────────────────────────────────────────────
 
     #[test]
@@ -818,6 +1702,135 @@ mod tests {
         assert!(rs.is_inline_click_wait());
     }
 
+    // ── bubbles ───────────────────────────────────────────────────────────────
+
+    #[test]
+    fn bubbles_replace_per_alias_and_follow_character_removal() {
+        let mut rs = RenderState::new();
+        let contents = |rs: &RenderState| -> Vec<(String, String)> {
+            rs.bubbles
+                .iter()
+                .map(|b| (b.alias.clone(), b.content.clone()))
+                .collect()
+        };
+
+        rs.set_bubble("royu".into(), "……".into());
+        rs.set_bubble("alice".into(), "哼".into());
+        rs.set_bubble("royu".into(), "好紧张".into());
+        assert_eq!(
+            contents(&rs),
+            [
+                ("alice".to_string(), "哼".to_string()),
+                ("royu".to_string(), "好紧张".to_string()),
+            ]
+        );
+
+        rs.set_bubble("alice".into(), String::new());
+        rs.hide_character("royu");
+        assert!(rs.bubbles.is_empty());
+    }
+
+    // ── auto layout ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn auto_layout_spreads_characters_evenly() {
+        let close = |xs: Vec<f32>, expected: &[f32]| {
+            assert_eq!(xs.len(), expected.len());
+            for (x, e) in xs.iter().zip(expected) {
+                assert!((x - e).abs() < 1e-6, "{xs:?} != {expected:?}");
+            }
+        };
+        close(auto_layout_xs(&[], 2), &[1.0 / 3.0, 2.0 / 3.0]);
+        close(auto_layout_xs(&[], 3), &[0.25, 0.5, 0.75]);
+        close(auto_layout_xs(&[], 4), &[0.2, 0.4, 0.6, 0.8]);
+        // 固定角色站在中央时，自动布局的角色分到两侧空档
+        close(auto_layout_xs(&[0.5], 1), &[0.25]);
+        close(auto_layout_xs(&[0.5], 2), &[0.25, 0.75]);
+    }
+
+    #[test]
+    fn only_auto_characters_are_laid_out_around_fixed_ones() {
+        let manifest = crate::manifest::Manifest::with_defaults();
+        let mut rs = RenderState::new();
+        for (alias, position) in [
+            ("royu", Position::Auto),
+            ("kaze", Position::Center),
+            ("sora", Position::Auto),
+            ("mika", Position::Center),
+        ] {
+            rs.show_character(alias.into(), format!("{alias}.png"), position, &manifest);
+        }
+        rs.apply_auto_layout();
+        let x = |rs: &RenderState, alias: &str| rs.visible_characters[alias].pos_x;
+        let center = manifest.get_preset("center").x;
+
+        // 同站位的固定角色不参与布局
+        assert_eq!([x(&rs, "kaze"), x(&rs, "mika")], [center, center]);
+        let (royu, sora) = (x(&rs, "royu"), x(&rs, "sora"));
+        assert!(royu < center && center < sora, "{royu} {center} {sora}");
+
+        // 固定角色离场后，自动布局的角色占满整个舞台
+        rs.hide_character("kaze");
+        rs.hide_character("mika");
+        rs.apply_auto_layout();
+        assert!((x(&rs, "royu") - 1.0 / 3.0).abs() < 1e-6);
+        assert!((x(&rs, "sora") - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rim_light_adds_outline_passes_only_when_enabled() {
+        let manifest = crate::manifest::Manifest::with_defaults();
+        let mut rs = RenderState::new();
+        rs.show_character(
+            "royu".into(),
+            "royu.png".into(),
+            Position::Center,
+            &manifest,
+        );
+        assert!(rs.visible_characters["royu"].rim_light_passes().is_empty());
+
+        assert!(rs.set_rim_light("royu", 0.5));
+        let passes = rs.visible_characters["royu"].rim_light_passes();
+        assert_eq!(passes.len(), RIM_LIGHT_DIRECTIONS.len());
+        assert!(passes.iter().all(|p| p.alpha == 0.5 * RIM_LIGHT_MAX_ALPHA));
+
+        // 强度 0 即关闭，不再额外绘制
+        assert!(rs.set_rim_light("royu", 0.0));
+        assert!(rs.visible_characters["royu"].rim_light_passes().is_empty());
+        assert!(!rs.set_rim_light("kaze", 1.0));
+    }
+
+    // ── styled_segments ───────────────────────────────────────────────────────
+
+    #[test]
+    fn styled_segments_split_visible_text_at_bold_markers() {
+        let effects = [
+            InlineEffect {
+                position: 2,
+                kind: InlineEffectKind::BoldStart,
+            },
+            InlineEffect {
+                position: 4,
+                kind: InlineEffectKind::BoldEnd,
+            },
+        ];
+        let segments = |visible| styled_segments("这是重点。", &effects, visible);
+        assert_eq!(
+            segments(5),
+            [
+                ("这是".to_string(), false),
+                ("重点".to_string(), true),
+                ("。".to_string(), false),
+            ]
+        );
+        // 打字到粗体中途
+        assert_eq!(
+            segments(3),
+            [("这是".to_string(), false), ("重".to_string(), true)]
+        );
+        assert!(segments(0).is_empty());
+    }
+
     // ── var_value ↔ json conversion ───────────────────────────────────────────
 
     #[test]
@@ -840,4 +1853,89 @@ mod tests {
         let json = var_value_to_json(&v);
         assert_eq!(json_to_var_value(&json), VarValue::String("hello".into()));
     }
+
+    #[test]
+    fn disabled_choices_are_not_enabled_or_preselected() {
+        let item = |text: &str, enabled: bool| ChoiceItem {
+            text: text.into(),
+            target_label: text.into(),
+            enabled,
+            disabled_reason: (!enabled).then(|| "需要钥匙".to_string()),
+            visited: false,
+        };
+        let mut rs = RenderState::new();
+        rs.set_choices(vec![item("door", false), item("leave", true)], None);
+
+        assert!(!rs.is_choice_enabled(0));
+        assert!(rs.is_choice_enabled(1));
+        assert!(!rs.is_choice_enabled(2));
+        assert_eq!(rs.choices.as_ref().unwrap().selected_index, 1);
+    }
+
+    #[test]
+    fn choice_highlight_interpolates_and_previous_item_falls_back() {
+        let item = |text: &str| ChoiceItem {
+            text: text.to_string(),
+            target_label: text.to_string(),
+            enabled: true,
+            disabled_reason: None,
+            visited: false,
+        };
+        let mut rs = RenderState::new();
+        rs.set_choices(vec![item("a"), item("b")], None);
+        let step = CHOICE_HIGHLIGHT_DURATION / 3.0;
+
+        // 选中项（第一项）逐帧放大，未选中项保持原尺寸
+        rs.update_choices(step);
+        let choices = rs.choices.as_ref().unwrap();
+        let expected = 1.0 + (CHOICE_ACTIVE_SCALE - 1.0) / 3.0;
+        assert!((choices.item_scale(0) - expected).abs() < 1e-5);
+        assert_eq!(choices.item_scale(1), 1.0);
+
+        rs.update_choices(CHOICE_HIGHLIGHT_DURATION);
+        assert_eq!(
+            rs.choices.as_ref().unwrap().item_scale(0),
+            CHOICE_ACTIVE_SCALE
+        );
+
+        // 悬停切换选中：旧项回落，新项放大
+        rs.set_choice_hover(Some(1));
+        rs.update_choices(step);
+        let choices = rs.choices.as_ref().unwrap();
+        assert_eq!(choices.selected_index, 1);
+        assert!((choices.item_highlight(0) - 2.0 / 3.0).abs() < 1e-5);
+        assert!((choices.item_highlight(1) - 1.0 / 3.0).abs() < 1e-5);
+
+        rs.update_choices(CHOICE_HIGHLIGHT_DURATION);
+        let choices = rs.choices.as_ref().unwrap();
+        assert_eq!(choices.item_scale(0), 1.0);
+        assert_eq!(choices.item_scale(1), CHOICE_ACTIVE_SCALE);
+    }
+
+    #[test]
+    fn parallax_layers_offset_by_coefficient_when_camera_pans() {
+        let layer = |path: &str, parallax: f64| BackgroundLayer {
+            path: path.to_string(),
+            parallax,
+        };
+        let mut rs = RenderState::new();
+        rs.set_background("bg/old.png".to_string());
+        rs.set_background_layers(vec![
+            layer("bg/sky.png", 0.0),
+            layer("bg/town.png", 0.5),
+            layer("bg/street.png", 1.0),
+        ]);
+        assert_eq!(rs.current_background, None);
+        assert_eq!(rs.background_layer_offsets(), vec![(0.0, 0.0); 3]);
+
+        // 镜头右移 100px、下移 40px：近景反向移动最多，远景不动
+        rs.camera_pan = (100.0, 40.0);
+        assert_eq!(
+            rs.background_layer_offsets(),
+            vec![(-0.0, -0.0), (-50.0, -20.0), (-100.0, -40.0)]
+        );
+
+        rs.set_background("bg/room.png".to_string());
+        assert!(rs.background_layers.is_empty());
+    }
 }
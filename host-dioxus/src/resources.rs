@@ -1,9 +1,14 @@
 //! 资源管理系统
 //!
-//! 提供 [`LogicalPath`] 路径规范化、[`ResourceSource`] 后端抽象和 [`ResourceManager`] 统一入口。
+//! 提供 [`LogicalPath`] 路径规范化、[`ResourceSource`] 后端抽象、[`AssetCache`] 纹理/音频缓存
+//! 和 [`ResourceManager`] 统一入口。
 
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
+use tracing::debug;
+use unicode_normalization::UnicodeNormalization;
 
 // ── LogicalPath ──────────────────────────────────────────────────────────────
 
@@ -36,6 +41,14 @@ impl LogicalPath {
             ""
         }
     }
+
+    /// 按归一选项生成缓存键（读取资源仍使用原路径）
+    pub fn cache_key(&self, normalization: PathNormalization) -> LogicalPath {
+        if normalization == PathNormalization::default() {
+            return self.clone();
+        }
+        Self(normalize_logical_path_with(&self.0, normalization))
+    }
 }
 
 impl std::fmt::Display for LogicalPath {
@@ -44,6 +57,30 @@ impl std::fmt::Display for LogicalPath {
     }
 }
 
+/// 可选的路径归一规则
+///
+/// 不同平台文件系统的大小写敏感性与 Unicode 组合形式（macOS 常见 NFD）不同，
+/// 脚本里写法不一致的路径可能指向同一资源。开启后用于统一缓存键。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathNormalization {
+    /// 忽略大小写（统一为小写）
+    pub fold_case: bool,
+    /// Unicode 统一为 NFC 组合形式
+    pub unicode_nfc: bool,
+}
+
+/// 在 [`normalize_logical_path`] 基础上按选项做 NFC 与大小写归一
+pub fn normalize_logical_path_with(path: &str, normalization: PathNormalization) -> String {
+    let mut result = normalize_logical_path(path);
+    if normalization.unicode_nfc {
+        result = result.nfc().collect();
+    }
+    if normalization.fold_case {
+        result = result.to_lowercase();
+    }
+    result
+}
+
 /// 规范化逻辑路径：统一分隔符、处理 `.` `..`、去除 `assets/` 前缀。
 pub fn normalize_logical_path(path: &str) -> String {
     let normalized = path.replace('\\', "/");
@@ -208,11 +245,170 @@ mod zip_source {
 
 pub use zip_source::ZipSource;
 
+// ── AssetCache ───────────────────────────────────────────────────────────────
+
+/// 纹理缓存默认预算（MB），与 `ResourceConfig::texture_cache_size_mb` 默认值一致。
+pub const DEFAULT_TEXTURE_CACHE_MB: usize = 256;
+
+/// 音频缓存默认预算（MB），与 `ResourceConfig::audio_cache_size_mb` 默认值一致。
+pub const DEFAULT_AUDIO_CACHE_MB: usize = 64;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+struct CacheEntry {
+    data: Arc<[u8]>,
+    last_used: u64,
+}
+
+/// 缓存占用统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct CacheStats {
+    /// 条目数
+    pub entries: usize,
+    /// 已占用字节数（按原始数据长度估算）
+    pub used_bytes: usize,
+    /// 预算字节数
+    pub budget_bytes: usize,
+}
+
+/// 按字节预算管理的 LRU 资源缓存（纹理与音频各持有一份）。
+///
+/// - 超出预算时按最近最少使用顺序驱逐
+/// - pinned 条目永不被驱逐（pinned 总量超出预算时允许暂时超限）
+pub struct AssetCache {
+    entries: HashMap<LogicalPath, CacheEntry>,
+    pinned: HashSet<LogicalPath>,
+    budget_bytes: usize,
+    used_bytes: usize,
+    clock: u64,
+    /// 缓存键归一规则，避免写法不同的同一资源被缓存两份
+    normalization: PathNormalization,
+}
+
+impl AssetCache {
+    pub fn new(budget_mb: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            pinned: HashSet::new(),
+            budget_bytes: budget_mb.saturating_mul(BYTES_PER_MB),
+            used_bytes: 0,
+            clock: 0,
+            normalization: PathNormalization::default(),
+        }
+    }
+
+    /// 设置缓存键归一规则；已有条目按新规则重建键（同键条目保留最近使用的一份）
+    pub fn set_normalization(&mut self, normalization: PathNormalization) {
+        self.normalization = normalization;
+        let mut entries: Vec<_> = self.entries.drain().collect();
+        entries.sort_by_key(|(_, entry)| entry.last_used);
+        for (path, entry) in entries {
+            self.entries.insert(path.cache_key(normalization), entry);
+        }
+        self.pinned = self
+            .pinned
+            .drain()
+            .map(|path| path.cache_key(normalization))
+            .collect();
+        self.used_bytes = self.entries.values().map(|e| e.data.len()).sum();
+    }
+
+    fn key(&self, path: &LogicalPath) -> LogicalPath {
+        path.cache_key(self.normalization)
+    }
+
+    /// 读取缓存并刷新 LRU 时间
+    pub fn get(&mut self, path: &LogicalPath) -> Option<Arc<[u8]>> {
+        self.clock += 1;
+        let key = self.key(path);
+        let entry = self.entries.get_mut(&key)?;
+        entry.last_used = self.clock;
+        Some(entry.data.clone())
+    }
+
+    /// 写入缓存，必要时驱逐旧条目
+    pub fn insert(&mut self, path: LogicalPath, data: Arc<[u8]>) {
+        self.clock += 1;
+        let size = data.len();
+        if let Some(old) = self.entries.insert(
+            self.key(&path),
+            CacheEntry {
+                data,
+                last_used: self.clock,
+            },
+        ) {
+            self.used_bytes -= old.data.len();
+        }
+        self.used_bytes += size;
+        self.evict_to_budget();
+    }
+
+    /// 钉住条目，使其不被驱逐（条目可以尚未缓存）
+    pub fn pin(&mut self, path: &LogicalPath) {
+        self.pinned.insert(self.key(path));
+    }
+
+    /// 调整预算：下调时立即按 LRU 驱逐至新预算内，上调只改上限。
+    pub fn set_budget_mb(&mut self, budget_mb: usize) {
+        self.budget_bytes = budget_mb.saturating_mul(BYTES_PER_MB);
+        self.evict_to_budget();
+    }
+
+    pub fn budget_bytes(&self) -> usize {
+        self.budget_bytes
+    }
+
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    pub fn contains(&self, path: &LogicalPath) -> bool {
+        self.entries.contains_key(&self.key(path))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            used_bytes: self.used_bytes,
+            budget_bytes: self.budget_bytes,
+        }
+    }
+
+    fn evict_to_budget(&mut self) {
+        while self.used_bytes > self.budget_bytes {
+            let victim = self
+                .entries
+                .iter()
+                .filter(|(path, _)| !self.pinned.contains(*path))
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone());
+            let Some(victim) = victim else {
+                // 只剩 pinned 条目，允许暂时超限
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&victim) {
+                self.used_bytes -= entry.data.len();
+                debug!(path = %victim, size = entry.data.len(), "资源缓存驱逐");
+            }
+        }
+    }
+}
+
 // ── ResourceManager ──────────────────────────────────────────────────────────
 
 pub struct ResourceManager {
     source: Box<dyn ResourceSource>,
     base_path: PathBuf,
+    texture_cache: AssetCache,
+    audio_cache: AssetCache,
 }
 
 impl ResourceManager {
@@ -221,6 +417,8 @@ impl ResourceManager {
         Self {
             source: Box::new(FsSource::new(&base)),
             base_path: base,
+            texture_cache: AssetCache::new(DEFAULT_TEXTURE_CACHE_MB),
+            audio_cache: AssetCache::new(DEFAULT_AUDIO_CACHE_MB),
         }
     }
 
@@ -228,7 +426,57 @@ impl ResourceManager {
         Self {
             source,
             base_path: base_path.into(),
+            texture_cache: AssetCache::new(DEFAULT_TEXTURE_CACHE_MB),
+            audio_cache: AssetCache::new(DEFAULT_AUDIO_CACHE_MB),
+        }
+    }
+
+    /// 读取纹理字节，命中缓存时直接返回
+    pub fn read_texture(&mut self, path: &LogicalPath) -> Result<Arc<[u8]>, ResourceError> {
+        if let Some(data) = self.texture_cache.get(path) {
+            return Ok(data);
+        }
+        let data: Arc<[u8]> = self.source.read_bytes(path)?.into();
+        self.texture_cache.insert(path.clone(), data.clone());
+        Ok(data)
+    }
+
+    /// 读取音频字节，命中缓存时直接返回
+    pub fn read_audio(&mut self, path: &LogicalPath) -> Result<Arc<[u8]>, ResourceError> {
+        if let Some(data) = self.audio_cache.get(path) {
+            return Ok(data);
         }
+        let data: Arc<[u8]> = self.source.read_bytes(path)?.into();
+        self.audio_cache.insert(path.clone(), data.clone());
+        Ok(data)
+    }
+
+    /// 调整纹理缓存预算（MB），下调时立即驱逐至新预算内
+    pub fn set_budget_mb(&mut self, budget_mb: usize) {
+        self.texture_cache.set_budget_mb(budget_mb);
+    }
+
+    /// 调整音频缓存预算（MB），下调时立即驱逐至新预算内
+    pub fn set_audio_budget_mb(&mut self, budget_mb: usize) {
+        self.audio_cache.set_budget_mb(budget_mb);
+    }
+
+    /// 设置纹理与音频缓存键的路径归一规则
+    pub fn set_path_normalization(&mut self, normalization: PathNormalization) {
+        self.texture_cache.set_normalization(normalization);
+        self.audio_cache.set_normalization(normalization);
+    }
+
+    pub fn audio_cache(&self) -> &AssetCache {
+        &self.audio_cache
+    }
+
+    pub fn texture_cache(&self) -> &AssetCache {
+        &self.texture_cache
+    }
+
+    pub fn texture_cache_mut(&mut self) -> &mut AssetCache {
+        &mut self.texture_cache
     }
 
     pub fn read_text(&self, path: &LogicalPath) -> Result<String, ResourceError> {
@@ -301,6 +549,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalize_with_folds_case_and_unicode_form() {
+        let all = PathNormalization {
+            fold_case: true,
+            unicode_nfc: true,
+        };
+        // "é" 的 NFC（U+00E9）与 NFD（e + U+0301）
+        let nfc = "bg/Caf\u{e9}.PNG";
+        let nfd = "assets/BG/cafe\u{301}.png";
+        assert_eq!(normalize_logical_path_with(nfc, all), "bg/caf\u{e9}.png");
+        assert_eq!(
+            normalize_logical_path_with(nfc, all),
+            normalize_logical_path_with(nfd, all)
+        );
+
+        // 默认不归一，保持与 normalize_logical_path 一致
+        let none = PathNormalization::default();
+        assert_eq!(normalize_logical_path_with(nfd, none), "BG/cafe\u{301}.png");
+        assert_ne!(
+            LogicalPath::new(nfc).cache_key(none),
+            LogicalPath::new(nfd).cache_key(none)
+        );
+        assert_eq!(
+            LogicalPath::new(nfc).cache_key(all),
+            LogicalPath::new(nfd).cache_key(all)
+        );
+    }
+
     #[test]
     fn fs_source_read_text() {
         let dir = std::env::temp_dir().join("ring_dioxus_test_fs");
@@ -320,6 +596,113 @@ mod tests {
         assert_eq!(guess_mime_type("unknown.xyz"), "application/octet-stream");
     }
 
+    mod texture_cache_tests {
+        use super::*;
+
+        fn mb(n: usize) -> Arc<[u8]> {
+            vec![0u8; n * BYTES_PER_MB].into()
+        }
+
+        #[test]
+        fn lowering_budget_evicts_lru_until_within_budget() {
+            let mut cache = AssetCache::new(8);
+            for name in ["a.png", "b.png", "c.png", "d.png"] {
+                cache.insert(LogicalPath::new(name), mb(2));
+            }
+            // 访问 a，使 b 成为最久未使用
+            cache.get(&LogicalPath::new("a.png"));
+
+            cache.set_budget_mb(4);
+
+            assert!(cache.used_bytes() <= cache.budget_bytes());
+            assert!(cache.contains(&LogicalPath::new("a.png")));
+            assert!(cache.contains(&LogicalPath::new("d.png")));
+            assert!(!cache.contains(&LogicalPath::new("b.png")));
+            assert!(!cache.contains(&LogicalPath::new("c.png")));
+        }
+
+        #[test]
+        fn lowering_budget_never_evicts_pinned() {
+            let mut cache = AssetCache::new(8);
+            let pinned = LogicalPath::new("bg/pinned.png");
+            cache.insert(pinned.clone(), mb(3));
+            cache.pin(&pinned);
+            cache.insert(LogicalPath::new("b.png"), mb(2));
+            cache.insert(LogicalPath::new("c.png"), mb(2));
+
+            cache.set_budget_mb(2);
+
+            assert!(cache.contains(&pinned));
+            assert_eq!(cache.len(), 1);
+            // pinned 单独超出预算时允许暂时超限
+            assert_eq!(cache.used_bytes(), 3 * BYTES_PER_MB);
+        }
+
+        #[test]
+        fn normalized_cache_treats_path_variants_as_one_entry() {
+            let mut cache = AssetCache::new(8);
+            cache.set_normalization(PathNormalization {
+                fold_case: true,
+                unicode_nfc: true,
+            });
+            cache.insert(LogicalPath::new("bg/Caf\u{e9}.png"), mb(2));
+            cache.insert(LogicalPath::new("BG/cafe\u{301}.PNG"), mb(2));
+
+            assert_eq!(cache.len(), 1);
+            assert_eq!(cache.used_bytes(), 2 * BYTES_PER_MB);
+            assert!(cache.get(&LogicalPath::new("bg/café.png")).is_some());
+        }
+
+        #[test]
+        fn raising_budget_keeps_entries() {
+            let mut cache = AssetCache::new(4);
+            cache.insert(LogicalPath::new("a.png"), mb(2));
+            cache.insert(LogicalPath::new("b.png"), mb(2));
+            cache.set_budget_mb(16);
+            assert_eq!(cache.len(), 2);
+            assert_eq!(cache.budget_bytes(), 16 * BYTES_PER_MB);
+        }
+    }
+
+    mod audio_cache_tests {
+        use super::*;
+
+        #[test]
+        fn audio_cache_evicts_oldest_over_budget_and_counts_bytes() {
+            let dir = std::env::temp_dir()
+                .join(format!("ring_dioxus_audio_cache_{}", std::process::id()));
+            std::fs::create_dir_all(dir.join("bgm")).unwrap();
+            for name in ["a", "b", "c"] {
+                std::fs::write(dir.join(format!("bgm/{name}.ogg")), vec![0u8; BYTES_PER_MB])
+                    .unwrap();
+            }
+            let path = |name: &str| LogicalPath::new(&format!("bgm/{name}.ogg"));
+
+            let mut rm = ResourceManager::new(&dir);
+            rm.set_audio_budget_mb(2);
+            rm.read_audio(&path("a")).unwrap();
+            rm.read_audio(&path("b")).unwrap();
+            assert_eq!(rm.audio_cache().used_bytes(), 2 * BYTES_PER_MB);
+
+            // 超出预算：最久未使用的 a 被驱逐，纹理缓存不受影响
+            rm.read_audio(&path("c")).unwrap();
+            assert!(!rm.audio_cache().contains(&path("a")));
+            assert!(rm.audio_cache().contains(&path("b")));
+            assert!(rm.audio_cache().contains(&path("c")));
+            assert_eq!(
+                rm.audio_cache().stats(),
+                CacheStats {
+                    entries: 2,
+                    used_bytes: 2 * BYTES_PER_MB,
+                    budget_bytes: 2 * BYTES_PER_MB,
+                }
+            );
+            assert!(rm.texture_cache().is_empty());
+
+            std::fs::remove_dir_all(&dir).ok();
+        }
+    }
+
     mod zip_tests {
         use super::*;
         use std::io::Write;
@@ -1,16 +1,23 @@
 //! 存档管理系统
 
+use std::collections::BTreeMap;
 use std::fs::{self, File};
-use std::io::{Read, Write};
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 
 use base64::Engine as _;
-use serde::Serialize;
-use tracing::info;
-use vn_runtime::{SaveData, SaveError};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use vn_runtime::{SaveData, SaveError, SavePreview, SaveSlot};
 
 pub const MAX_SAVE_SLOTS: u32 = 99;
 const CONTINUE_SAVE_NAME: &str = "continue.json";
+/// 自动存档历史的文件名前缀（文件名形如 `auto_000042.json`，序号递增）
+const AUTOSAVE_PREFIX: &str = "auto_";
+/// 人读的槽位索引（slot → 章节标题/时间），仅供外部识别，读档不依赖
+const INDEX_FILE_NAME: &str = "index.json";
+/// 原子写入时临时文件的后缀（文件名形如 `.slot_001.json.tmp`）
+const TEMP_FILE_SUFFIX: &str = ".tmp";
 
 pub struct SaveManager {
     saves_dir: PathBuf,
@@ -35,33 +42,35 @@ impl SaveManager {
         self.saves_dir.join(format!("slot_{:03}.json", slot))
     }
 
+    /// 按元数据中的存档位置写入（Continue 存档写入 `continue.json`）
     pub fn save(&self, data: &SaveData) -> Result<(), SaveError> {
+        let SaveSlot::Slot(slot) = data.metadata.slot else {
+            return self.save_continue(data);
+        };
         self.ensure_dir()?;
-        let path = self.slot_path(data.metadata.slot);
+        let path = self.slot_path(slot);
         let json = data.to_json()?;
-        let mut file = File::create(&path)
-            .map_err(|e| SaveError::IoError(format!("无法创建存档文件: {}", e)))?;
-        file.write_all(json.as_bytes())
+        write_atomic(&path, |file| file.write_all(json.as_bytes()))
             .map_err(|e| SaveError::IoError(format!("无法写入存档文件: {}", e)))?;
         info!(path = %path.display(), "存档保存成功");
+        self.update_index(slot, Some(SaveIndexEntry::from_data(data)));
         Ok(())
     }
 
     pub fn load(&self, slot: u32) -> Result<SaveData, SaveError> {
         let path = self.slot_path(slot);
-        if !path.exists() {
-            return Err(SaveError::NotFound(path.to_string_lossy().to_string()));
-        }
-        let mut file = File::open(&path)
-            .map_err(|e| SaveError::IoError(format!("无法打开存档文件: {}", e)))?;
-        let mut json = String::new();
-        file.read_to_string(&mut json)
-            .map_err(|e| SaveError::IoError(format!("无法读取存档文件: {}", e)))?;
+        let json = read_save_file(&path)?;
         let data = SaveData::from_json(&json)?;
         info!(path = %path.display(), "存档读取成功");
         Ok(data)
     }
 
+    /// 只读预览存档；版本不兼容或字段残缺、无法 `load` 时仍尽量给出元数据与变量
+    pub fn preview(&self, slot: u32) -> Result<SavePreview, SaveError> {
+        let json = read_save_file(&self.slot_path(slot))?;
+        SaveData::preview(&json)
+    }
+
     pub fn delete(&self, slot: u32) -> Result<(), SaveError> {
         let path = self.slot_path(slot);
         if path.exists() {
@@ -69,13 +78,97 @@ impl SaveManager {
                 .map_err(|e| SaveError::IoError(format!("无法删除存档文件: {}", e)))?;
             info!(path = %path.display(), "存档删除成功");
         }
-        let thumb = self.thumbnail_path(slot);
-        if thumb.exists() {
-            let _ = fs::remove_file(&thumb);
-        }
+        self.delete_thumbnail(slot);
+        self.update_index(slot, None);
         Ok(())
     }
 
+    pub fn index_path(&self) -> PathBuf {
+        self.saves_dir.join(INDEX_FILE_NAME)
+    }
+
+    /// 读取槽位索引；文件缺失或损坏时按现有存档重建
+    pub fn read_index(&self) -> BTreeMap<u32, SaveIndexEntry> {
+        self.read_stored_index()
+            .unwrap_or_else(|| self.rebuild_index())
+    }
+
+    /// 磁盘上的索引文件；缺失或无法解析时返回 None
+    fn read_stored_index(&self) -> Option<BTreeMap<u32, SaveIndexEntry>> {
+        fs::read_to_string(self.index_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    fn rebuild_index(&self) -> BTreeMap<u32, SaveIndexEntry> {
+        self.slot_files()
+            .into_iter()
+            .filter_map(|(slot, _)| {
+                let data = self.load(slot).ok()?;
+                Some((slot, SaveIndexEntry::from_data(&data)))
+            })
+            .collect()
+    }
+
+    /// 与目录中存档文件对齐的索引
+    ///
+    /// 文件已不存在的条目被丢弃；索引中缺少的存档（索引缺失、旧版本写入或从别处拷入）
+    /// 单独完整读取一次并补写回索引，损坏的存档不进入索引。
+    fn synced_index(&self) -> BTreeMap<u32, SaveIndexEntry> {
+        let files = self.slot_files();
+        let mut index = self.read_stored_index().unwrap_or_default();
+        let before = index.len();
+        index.retain(|slot, _| files.iter().any(|(s, _)| s == slot));
+        let mut changed = index.len() != before;
+        for (slot, path) in &files {
+            if index.contains_key(slot) {
+                continue;
+            }
+            match self.load(*slot) {
+                Ok(data) => {
+                    index.insert(*slot, SaveIndexEntry::from_data(&data));
+                    changed = true;
+                }
+                Err(e) => warn!(path = %path.display(), error = %e, "跳过损坏的存档"),
+            }
+        }
+        if changed {
+            self.write_index(&index);
+        }
+        index
+    }
+
+    /// 更新索引中的单个槽位（`None` 表示移除）；写入失败只记录告警，不影响存档本身
+    fn update_index(&self, slot: u32, entry: Option<SaveIndexEntry>) {
+        if !self.saves_dir.exists() {
+            return;
+        }
+        let mut index = self.read_index();
+        match entry {
+            Some(entry) => {
+                index.insert(slot, entry);
+            }
+            None => {
+                index.remove(&slot);
+            }
+        }
+        self.write_index(&index);
+    }
+
+    fn write_index(&self, index: &BTreeMap<u32, SaveIndexEntry>) {
+        if !self.saves_dir.exists() {
+            return;
+        }
+        let result = serde_json::to_string_pretty(index)
+            .map_err(io::Error::other)
+            .and_then(|json| {
+                write_atomic(&self.index_path(), |file| file.write_all(json.as_bytes()))
+            });
+        if let Err(e) = result {
+            warn!(error = %e, "存档索引写入失败");
+        }
+    }
+
     pub fn thumbnail_path(&self, slot: u32) -> PathBuf {
         self.saves_dir.join(format!("thumb_{:03}.png", slot))
     }
@@ -83,21 +176,66 @@ impl SaveManager {
     pub fn save_thumbnail_png(&self, slot: u32, png_bytes: &[u8]) -> Result<(), SaveError> {
         self.ensure_dir()?;
         let path = self.thumbnail_path(slot);
-        let mut file = File::create(&path)
-            .map_err(|e| SaveError::IoError(format!("创建缩略图文件失败: {e}")))?;
-        file.write_all(png_bytes)
+        write_atomic(&path, |file| file.write_all(png_bytes))
             .map_err(|e| SaveError::IoError(format!("写入缩略图失败: {e}")))?;
         info!(path = %path.display(), "缩略图保存成功");
         Ok(())
     }
 
+    /// 删除槽位缩略图（不存在时忽略；删除失败只记录告警，残留缩略图不影响读档）
+    pub fn delete_thumbnail(&self, slot: u32) {
+        let path = self.thumbnail_path(slot);
+        if let Err(e) = fs::remove_file(&path)
+            && e.kind() != io::ErrorKind::NotFound
+        {
+            warn!(path = %path.display(), error = %e, "删除缩略图失败");
+        }
+    }
+
     pub fn load_thumbnail_base64(&self, slot: u32) -> Option<String> {
         let path = self.thumbnail_path(slot);
         let bytes = fs::read(&path).ok()?;
         Some(base64::engine::general_purpose::STANDARD.encode(&bytes))
     }
 
+    /// 列出可正常读取的存档；按索引判断，不逐个解析已索引的存档文件
+    ///
+    /// 未进入索引且无法读取的存档会被跳过并记录警告。
     pub fn list_saves(&self) -> Vec<(u32, PathBuf)> {
+        self.synced_index()
+            .into_keys()
+            .map(|slot| (slot, self.slot_path(slot)))
+            .collect()
+    }
+
+    /// 检查所有存档文件的健康状态（包含损坏的槽位）
+    pub fn verify_all(&self) -> Vec<(u32, Result<(), SaveError>)> {
+        self.slot_files()
+            .into_iter()
+            .map(|(slot, _)| (slot, self.load(slot).map(|_| ())))
+            .collect()
+    }
+
+    /// 已占用的编号槽位数（含损坏的存档，不含 Continue 存档）
+    pub fn count_saves(&self) -> usize {
+        self.slot_files().len()
+    }
+
+    /// 存档目录中所有文件（存档、缩略图、Continue 存档、索引）的总字节数
+    pub fn total_size_bytes(&self) -> u64 {
+        let Ok(entries) = fs::read_dir(&self.saves_dir) else {
+            return 0;
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| entry.metadata().ok())
+            .filter(|meta| meta.is_file())
+            .map(|meta| meta.len())
+            .sum()
+    }
+
+    /// 存档目录中所有 `slot_NNN.json` 文件（不校验内容）
+    fn slot_files(&self) -> Vec<(u32, PathBuf)> {
         let mut saves = Vec::new();
         if !self.saves_dir.exists() {
             return saves;
@@ -108,7 +246,7 @@ impl SaveManager {
                 if let Some(name) = path.file_name().and_then(|n| n.to_str())
                     && name.starts_with("slot_")
                     && name.ends_with(".json")
-                    && let Ok(slot) = name[5..8].parse::<u32>()
+                    && let Some(Ok(slot)) = name.get(5..8).map(str::parse::<u32>)
                 {
                     saves.push((slot, path));
                 }
@@ -118,18 +256,27 @@ impl SaveManager {
         saves
     }
 
+    /// 槽位存档信息（来自索引）；不存在或已损坏时返回 None
     pub fn get_save_info(&self, slot: u32) -> Option<SaveInfo> {
         if !self.slot_path(slot).exists() {
             return None;
         }
-        let data = self.load(slot).ok()?;
-        Some(SaveInfo {
-            slot: Some(slot),
-            timestamp: data.metadata.timestamp.clone(),
-            chapter_title: data.metadata.chapter_title.clone(),
-            script_id: data.runtime_state.position.script_id.clone(),
-            play_time_secs: data.metadata.play_time_secs,
-        })
+        let entry = self.synced_index().remove(&slot)?;
+        Some(SaveInfo::from_index(slot, entry))
+    }
+
+    /// Continue 存档信息；不存在或读取失败时返回 None
+    pub fn get_continue_info(&self) -> Option<SaveInfo> {
+        if !self.has_continue() {
+            return None;
+        }
+        match self.load_continue() {
+            Ok(data) => Some(SaveInfo::from_data(SaveSlot::Continue, &data)),
+            Err(e) => {
+                warn!(error = %e, "Continue 存档信息读取失败");
+                None
+            }
+        }
     }
 
     fn continue_path(&self) -> PathBuf {
@@ -140,9 +287,7 @@ impl SaveManager {
         self.ensure_dir()?;
         let path = self.continue_path();
         let json = data.to_json()?;
-        let mut file = File::create(&path)
-            .map_err(|e| SaveError::IoError(format!("无法创建 Continue 存档: {}", e)))?;
-        file.write_all(json.as_bytes())
+        write_atomic(&path, |file| file.write_all(json.as_bytes()))
             .map_err(|e| SaveError::IoError(format!("无法写入 Continue 存档: {}", e)))?;
         info!(path = %path.display(), "Continue 存档保存成功");
         Ok(())
@@ -158,11 +303,110 @@ impl SaveManager {
         let mut json = String::new();
         file.read_to_string(&mut json)
             .map_err(|e| SaveError::IoError(format!("无法读取 Continue 存档: {}", e)))?;
-        let data = SaveData::from_json(&json)?;
+        let mut data = SaveData::from_json(&json)?;
+        // 旧版本以 slot 0 标记 Continue 存档
+        data.metadata.slot = SaveSlot::Continue;
         info!(path = %path.display(), "Continue 存档读取成功");
         Ok(data)
     }
 
+    /// 清理上次异常退出残留的原子写入临时文件，返回清理数量
+    pub fn cleanup_temp_files(&self) -> usize {
+        let Ok(entries) = fs::read_dir(&self.saves_dir) else {
+            return 0;
+        };
+        let mut removed = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_temp = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.') && n.ends_with(TEMP_FILE_SUFFIX));
+            if !is_temp {
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => removed += 1,
+                Err(e) => warn!(path = %path.display(), error = %e, "清理存档临时文件失败"),
+            }
+        }
+        if removed > 0 {
+            info!(count = removed, "已清理残留的存档临时文件");
+        }
+        removed
+    }
+
+    pub fn autosave_path(&self, seq: u32) -> PathBuf {
+        self.saves_dir
+            .join(format!("{AUTOSAVE_PREFIX}{seq:06}.json"))
+    }
+
+    /// 追加一份自动存档历史，返回其序号（比现有最大序号大 1）
+    pub fn save_autosave(&self, data: &SaveData) -> Result<u32, SaveError> {
+        self.ensure_dir()?;
+        let seq = self.autosave_files().last().map_or(1, |(seq, _)| seq + 1);
+        let path = self.autosave_path(seq);
+        let json = data.to_json()?;
+        write_atomic(&path, |file| file.write_all(json.as_bytes()))
+            .map_err(|e| SaveError::IoError(format!("无法写入自动存档: {}", e)))?;
+        info!(path = %path.display(), "自动存档保存成功");
+        Ok(seq)
+    }
+
+    pub fn load_autosave(&self, seq: u32) -> Result<SaveData, SaveError> {
+        let json = read_save_file(&self.autosave_path(seq))?;
+        let mut data = SaveData::from_json(&json)?;
+        data.metadata.slot = SaveSlot::Continue;
+        Ok(data)
+    }
+
+    /// 自动存档历史的序号，从旧到新
+    pub fn list_autosaves(&self) -> Vec<u32> {
+        self.autosave_files()
+            .into_iter()
+            .map(|(seq, _)| seq)
+            .collect()
+    }
+
+    /// 只保留最近 `keep_count` 个自动存档，删除其余较旧的，返回删除数量
+    ///
+    /// 只处理 `auto_*.json`；编号存档与 Continue 存档不受影响。
+    pub fn prune_autosaves(&self, keep_count: usize) -> Result<usize, SaveError> {
+        let files = self.autosave_files();
+        let excess = files.len().saturating_sub(keep_count);
+        for (_, path) in &files[..excess] {
+            fs::remove_file(path)
+                .map_err(|e| SaveError::IoError(format!("无法删除自动存档: {}", e)))?;
+        }
+        if excess > 0 {
+            info!(removed = excess, kept = keep_count, "已清理过期自动存档");
+        }
+        Ok(excess)
+    }
+
+    /// 存档目录中所有 `auto_NNNNNN.json` 文件，按序号从旧到新
+    fn autosave_files(&self) -> Vec<(u32, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&self.saves_dir) else {
+            return Vec::new();
+        };
+        let mut files: Vec<(u32, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let seq = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_prefix(AUTOSAVE_PREFIX)?
+                    .strip_suffix(".json")?
+                    .parse()
+                    .ok()?;
+                Some((seq, path))
+            })
+            .collect();
+        files.sort_by_key(|(seq, _)| *seq);
+        files
+    }
+
     pub fn has_continue(&self) -> bool {
         self.continue_path().exists()
     }
@@ -178,6 +422,43 @@ impl SaveManager {
     }
 }
 
+/// 读取存档文件文本；文件不存在映射为 [`SaveError::NotFound`]
+fn read_save_file(path: &Path) -> Result<String, SaveError> {
+    fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => SaveError::NotFound(path.to_string_lossy().to_string()),
+        _ => SaveError::IoError(format!("无法读取存档文件: {}", e)),
+    })
+}
+
+/// 目标文件对应的同目录临时文件路径
+fn temp_path_for(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}{TEMP_FILE_SUFFIX}"))
+}
+
+/// 先写入同目录临时文件并落盘，再 rename 覆盖目标文件。
+///
+/// 写入中途失败时删除临时文件，目标文件保持原样。
+fn write_atomic(path: &Path, write: impl FnOnce(&mut File) -> io::Result<()>) -> io::Result<()> {
+    let tmp = temp_path_for(path);
+    let result = File::create(&tmp)
+        .and_then(|mut file| {
+            write(&mut file)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp, path));
+    if result.is_err()
+        && let Err(e) = fs::remove_file(&tmp)
+        && e.kind() != io::ErrorKind::NotFound
+    {
+        warn!(path = %tmp.display(), error = %e, "清理临时文件失败");
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -205,10 +486,23 @@ mod tests {
         let sm = SaveManager::new(&dir);
         sm.save(&make_save(1)).unwrap();
         let loaded = sm.load(1).unwrap();
-        assert_eq!(loaded.metadata.slot, 1);
+        assert_eq!(loaded.metadata.slot, SaveSlot::Slot(1));
         assert_eq!(loaded.runtime_state.position.script_id, "test_script");
     }
 
+    #[test]
+    fn save_info_exposes_label() {
+        let dir = unique_temp_dir("label");
+        let sm = SaveManager::new(&dir);
+        sm.save(&make_save(3).with_label("分支 B")).unwrap();
+        sm.save(&make_save(4)).unwrap();
+        assert_eq!(
+            sm.get_save_info(3).unwrap().label.as_deref(),
+            Some("分支 B")
+        );
+        assert_eq!(sm.get_save_info(4).unwrap().label, None);
+    }
+
     #[test]
     fn load_nonexistent_slot_returns_not_found() {
         let dir = unique_temp_dir("notfound");
@@ -242,6 +536,27 @@ mod tests {
         assert_eq!(slots, [1, 3, 5]);
     }
 
+    #[test]
+    fn corrupted_slot_is_skipped_and_reported() {
+        let dir = unique_temp_dir("corrupt");
+        let sm = SaveManager::new(&dir);
+        sm.save(&make_save(1)).unwrap();
+        sm.save(&make_save(3)).unwrap();
+        std::fs::write(sm.slot_path(2), "{ not valid json").unwrap();
+
+        let slots: Vec<u32> = sm.list_saves().into_iter().map(|(s, _)| s).collect();
+        assert_eq!(slots, vec![1, 3]);
+        assert!(sm.get_save_info(2).is_none());
+        assert!(sm.get_save_info(3).is_some());
+
+        let report = sm.verify_all();
+        assert_eq!(report.len(), 3);
+        assert!(report[0].1.is_ok());
+        assert_eq!(report[1].0, 2);
+        assert!(report[1].1.is_err());
+        assert!(report[2].1.is_ok());
+    }
+
     #[test]
     fn list_saves_empty_when_no_dir() {
         let dir = unique_temp_dir("nodir");
@@ -250,6 +565,37 @@ mod tests {
         assert!(sm.list_saves().is_empty());
     }
 
+    #[test]
+    fn count_and_total_size_cover_all_save_files() {
+        let dir = unique_temp_dir("usage");
+        let sm = SaveManager::new(&dir);
+        assert_eq!(sm.count_saves(), 0);
+        assert_eq!(sm.total_size_bytes(), 0);
+
+        sm.save(&make_save(1)).unwrap();
+        sm.save(&make_save(4)).unwrap();
+        sm.save_thumbnail_png(1, &[0x89, b'P', b'N', b'G']).unwrap();
+        sm.save_continue(&make_save(0)).unwrap();
+
+        assert_eq!(sm.count_saves(), 2);
+        let expected: u64 = [
+            sm.slot_path(1),
+            sm.slot_path(4),
+            sm.thumbnail_path(1),
+            sm.index_path(),
+        ]
+        .iter()
+        .chain(std::iter::once(&sm.continue_path()))
+        .map(|p| std::fs::metadata(p).unwrap().len())
+        .sum();
+        assert_eq!(sm.total_size_bytes(), expected);
+
+        let empty = SaveManager::new(unique_temp_dir("usage_empty"));
+        empty.ensure_dir().unwrap();
+        assert_eq!(empty.count_saves(), 0);
+        assert_eq!(empty.total_size_bytes(), 0);
+    }
+
     #[test]
     fn continue_save_lifecycle() {
         let dir = unique_temp_dir("continue");
@@ -258,11 +604,74 @@ mod tests {
         sm.save_continue(&make_save(0)).unwrap();
         assert!(sm.has_continue());
         let loaded = sm.load_continue().unwrap();
-        assert_eq!(loaded.metadata.slot, 0);
+        // 旧版以 slot 0 写入的 Continue 存档读回后统一为 Continue
+        assert_eq!(loaded.metadata.slot, SaveSlot::Continue);
         sm.delete_continue().unwrap();
         assert!(!sm.has_continue());
     }
 
+    #[test]
+    fn continue_save_is_separate_from_numbered_slots() {
+        let dir = unique_temp_dir("continue_slot");
+        let sm = SaveManager::new(&dir);
+        let cont = SaveData::for_slot(SaveSlot::Continue, RuntimeState::new("cont"), 0)
+            .with_chapter("继续");
+        sm.save(&cont).unwrap();
+        sm.save(&make_save(1)).unwrap();
+
+        assert!(sm.has_continue());
+        assert_eq!(sm.list_saves().len(), 1);
+        assert!(sm.get_save_info(0).is_none());
+
+        let info = sm.get_continue_info().unwrap();
+        assert_eq!(info.slot, SaveSlot::Continue);
+        assert_eq!(info.chapter_title.as_deref(), Some("继续"));
+        assert_eq!(info.script_id, "cont");
+        assert_eq!(sm.get_save_info(1).unwrap().slot, SaveSlot::Slot(1));
+
+        sm.delete_continue().unwrap();
+        assert!(sm.get_continue_info().is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn prune_autosaves_keeps_newest_and_leaves_manual_saves() {
+        let dir = unique_temp_dir("prune_auto");
+        let sm = SaveManager::new(&dir);
+        sm.save(&make_save(1)).unwrap();
+        sm.save_continue(&make_save(0)).unwrap();
+        for i in 0..5 {
+            let data = SaveData::for_slot(SaveSlot::Continue, RuntimeState::new("auto"), 0)
+                .with_chapter(format!("第{i}章"));
+            assert_eq!(sm.save_autosave(&data).unwrap(), i + 1);
+        }
+        assert_eq!(sm.list_autosaves(), vec![1, 2, 3, 4, 5]);
+
+        // 未超过 keep_count 时不删除
+        assert_eq!(sm.prune_autosaves(5).unwrap(), 0);
+        assert_eq!(sm.prune_autosaves(2).unwrap(), 3);
+        assert_eq!(sm.list_autosaves(), vec![4, 5]);
+        assert_eq!(
+            sm.load_autosave(5)
+                .unwrap()
+                .metadata
+                .chapter_title
+                .as_deref(),
+            Some("第4章")
+        );
+        assert!(matches!(sm.load_autosave(1), Err(SaveError::NotFound(_))));
+
+        // 手动存档与 Continue 存档不受影响；新自动存档继续递增序号
+        assert_eq!(sm.list_saves().len(), 1);
+        assert!(sm.has_continue());
+        assert_eq!(sm.save_autosave(&make_save(0)).unwrap(), 6);
+
+        assert_eq!(sm.prune_autosaves(0).unwrap(), 3);
+        assert!(sm.list_autosaves().is_empty());
+        assert_eq!(sm.list_saves().len(), 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn load_continue_missing_returns_not_found() {
         let dir = unique_temp_dir("cont_missing");
@@ -271,6 +680,126 @@ mod tests {
         assert!(matches!(err, SaveError::NotFound(_)));
     }
 
+    fn temp_files_in(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.to_string_lossy().ends_with(TEMP_FILE_SUFFIX))
+            .collect()
+    }
+
+    #[test]
+    fn save_leaves_no_temp_file_behind() {
+        let dir = unique_temp_dir("atomic_ok");
+        let sm = SaveManager::new(&dir);
+        sm.save(&make_save(1)).unwrap();
+        sm.save(&make_save(1).with_label("覆盖")).unwrap();
+        sm.save_continue(&make_save(0)).unwrap();
+
+        assert!(sm.slot_path(1).exists());
+        assert_eq!(sm.load(1).unwrap().metadata.label.as_deref(), Some("覆盖"));
+        assert!(sm.has_continue());
+        assert!(temp_files_in(&dir).is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn failed_write_keeps_existing_save_intact() {
+        let dir = unique_temp_dir("atomic_fail");
+        let sm = SaveManager::new(&dir);
+        sm.save(&make_save(1).with_label("原存档")).unwrap();
+        let path = sm.slot_path(1);
+
+        let err = write_atomic(&path, |file| {
+            file.write_all(b"{ \"partial")?;
+            // 写入过程中目标文件未被触碰，数据只进入临时文件
+            assert!(temp_path_for(&path).exists());
+            assert_eq!(
+                sm.load(1).unwrap().metadata.label.as_deref(),
+                Some("原存档")
+            );
+            Err(io::Error::other("模拟磁盘已满"))
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "模拟磁盘已满");
+
+        assert_eq!(
+            sm.load(1).unwrap().metadata.label.as_deref(),
+            Some("原存档")
+        );
+        assert!(temp_files_in(&dir).is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn cleanup_removes_stale_temp_files_only() {
+        let dir = unique_temp_dir("atomic_cleanup");
+        let sm = SaveManager::new(&dir);
+        assert_eq!(sm.cleanup_temp_files(), 0);
+        sm.save(&make_save(2)).unwrap();
+        fs::write(temp_path_for(&sm.slot_path(3)), "{ partial").unwrap();
+        fs::write(temp_path_for(&sm.continue_path()), "{ partial").unwrap();
+
+        assert_eq!(sm.cleanup_temp_files(), 2);
+        assert!(temp_files_in(&dir).is_empty());
+        assert!(sm.slot_path(2).exists());
+        let slots: Vec<u32> = sm.list_saves().into_iter().map(|(s, _)| s).collect();
+        assert_eq!(slots, [2]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn index_tracks_saved_and_deleted_slots() {
+        let dir = unique_temp_dir("index");
+        let sm = SaveManager::new(&dir);
+        sm.save(&make_save(1).with_chapter("第一章 相遇")).unwrap();
+        sm.save(&make_save(7)).unwrap();
+
+        // 外部工具直接读 index.json 即可辨认槽位
+        let raw: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(sm.index_path()).unwrap()).unwrap();
+        assert_eq!(raw["1"]["chapter_title"], "第一章 相遇");
+        assert_eq!(raw["1"]["script_id"], "test_script");
+        assert!(raw["1"]["timestamp"].is_string());
+        assert!(raw["7"]["chapter_title"].is_null());
+
+        sm.delete(1).unwrap();
+        let index = sm.read_index();
+        assert!(!index.contains_key(&1));
+        assert!(index.contains_key(&7));
+
+        // 索引损坏时按现有存档重建
+        fs::write(sm.index_path(), "{ broken").unwrap();
+        assert_eq!(sm.read_index().keys().copied().collect::<Vec<_>>(), [7]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn list_saves_reads_slot_info_from_index() {
+        let dir = unique_temp_dir("index_list");
+        let sm = SaveManager::new(&dir);
+        sm.save(&make_save(1).with_chapter("第一章")).unwrap();
+        sm.save(&make_save(2)).unwrap();
+
+        // 已索引的存档不再解析：槽位信息直接来自 index.json
+        let mut index = sm.read_index();
+        index.get_mut(&1).unwrap().chapter_title = Some("索引中的标题".to_string());
+        sm.write_index(&index);
+        assert_eq!(
+            sm.get_save_info(1).unwrap().chapter_title.as_deref(),
+            Some("索引中的标题")
+        );
+
+        // 索引缺失时补齐并写回；文件已删除的条目被丢弃
+        fs::remove_file(sm.index_path()).unwrap();
+        fs::remove_file(sm.slot_path(2)).unwrap();
+        let slots: Vec<u32> = sm.list_saves().into_iter().map(|(s, _)| s).collect();
+        assert_eq!(slots, [1]);
+        assert!(sm.read_stored_index().unwrap().contains_key(&1));
+        fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn thumbnail_roundtrip() {
         let dir = unique_temp_dir("thumb");
@@ -288,9 +817,64 @@ mod tests {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SaveInfo {
-    pub slot: Option<u32>,
+    pub slot: SaveSlot,
     pub timestamp: String,
     pub chapter_title: Option<String>,
+    pub label: Option<String>,
     pub script_id: String,
     pub play_time_secs: u64,
+    /// 所在章节的预计阅读时长（秒）
+    pub reading_time_secs: Option<u64>,
+}
+
+/// `index.json` 中单个槽位的条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveIndexEntry {
+    pub chapter_title: Option<String>,
+    pub label: Option<String>,
+    pub timestamp: String,
+    pub script_id: String,
+    #[serde(default)]
+    pub play_time_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_time_secs: Option<u64>,
+}
+
+impl SaveIndexEntry {
+    fn from_data(data: &SaveData) -> Self {
+        Self {
+            chapter_title: data.metadata.chapter_title.clone(),
+            label: data.metadata.label.clone(),
+            timestamp: data.metadata.timestamp.clone(),
+            script_id: data.runtime_state.position.script_id.clone(),
+            play_time_secs: data.metadata.play_time_secs,
+            reading_time_secs: data.metadata.reading_time_secs,
+        }
+    }
+}
+
+impl SaveInfo {
+    fn from_data(slot: SaveSlot, data: &SaveData) -> Self {
+        Self {
+            slot,
+            timestamp: data.metadata.timestamp.clone(),
+            chapter_title: data.metadata.chapter_title.clone(),
+            label: data.metadata.label.clone(),
+            script_id: data.runtime_state.position.script_id.clone(),
+            play_time_secs: data.metadata.play_time_secs,
+            reading_time_secs: data.metadata.reading_time_secs,
+        }
+    }
+
+    fn from_index(slot: u32, entry: SaveIndexEntry) -> Self {
+        Self {
+            slot: SaveSlot::Slot(slot),
+            timestamp: entry.timestamp,
+            chapter_title: entry.chapter_title,
+            label: entry.label,
+            script_id: entry.script_id,
+            play_time_secs: entry.play_time_secs,
+            reading_time_secs: entry.reading_time_secs,
+        }
+    }
 }
@@ -2,15 +2,37 @@ use dioxus::prelude::*;
 
 use crate::components::GameMenuFrame;
 use crate::render_state::{HostScreen, RenderState};
-use crate::state::AppState;
+use crate::state::{AppState, HistoryEntry};
+
+/// 命中查询的条目下标（按列表显示顺序）
+fn search_hits(history: &[HistoryEntry], query: &str) -> Vec<usize> {
+    history
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| {
+            vn_runtime::history::dialogue_matches(e.speaker.as_deref(), &e.text, query)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// 将第 `index` 条滚动到可视区域
+fn scroll_to_entry(index: usize) {
+    document::eval(&format!(
+        "document.getElementById('history-entry-{index}')?.scrollIntoView({{block: 'center'}});"
+    ));
+}
 
 /// 历史 screen（嵌入 GameMenuFrame）
 ///
 /// 双列布局：角色名（右对齐加粗）+ 对话文本。
 /// 支持 ChapterMark 事件渲染（分隔线 + 标题）。
+/// 顶部搜索框按正文或角色名过滤命中（大小写不敏感），上一条/下一条跳转定位。
 #[component]
 pub fn HistoryScreen(render_state: Signal<RenderState>) -> Element {
     let app_state = use_context::<AppState>();
+    let mut query = use_signal(String::new);
+    let mut current_hit = use_signal(|| 0usize);
 
     let history = {
         let Ok(inner) = app_state.inner.lock() else {
@@ -19,11 +41,81 @@ pub fn HistoryScreen(render_state: Signal<RenderState>) -> Element {
         inner.history.clone()
     };
 
+    let hits = search_hits(&history, &query.read());
+    let hit_count = hits.len();
+    let current = current_hit().min(hit_count.saturating_sub(1));
+    let focused = hits.get(current).copied();
+    let hits_nav = hits.clone();
+    let mut jump = move |step: isize| {
+        if hits_nav.is_empty() {
+            return;
+        }
+        let next = (current as isize + step).rem_euclid(hits_nav.len() as isize) as usize;
+        current_hit.set(next);
+        scroll_to_entry(hits_nav[next]);
+    };
+    let mut jump_prev = jump.clone();
+    let mut jump_enter = jump.clone();
+
     rsx! {
         GameMenuFrame { title: "历史".to_string(), active_screen: HostScreen::History,
+            div { class: "history__search",
+                input {
+                    class: "history__search-input",
+                    r#type: "text",
+                    placeholder: "搜索对话或角色名",
+                    value: "{query}",
+                    oninput: {
+                        let history = history.clone();
+                        move |evt: Event<FormData>| {
+                            let value = evt.value();
+                            if let Some(&first) = search_hits(&history, &value).first() {
+                                scroll_to_entry(first);
+                            }
+                            current_hit.set(0);
+                            query.set(value);
+                        }
+                    },
+                    onkeydown: move |evt: Event<KeyboardData>| {
+                        if evt.key() == Key::Enter {
+                            jump_enter(if evt.modifiers().shift() { -1 } else { 1 });
+                        }
+                    },
+                }
+                if !query.read().trim().is_empty() {
+                    span { class: "history__search-count",
+                        if hit_count == 0 {
+                            "无结果"
+                        } else {
+                            "{current + 1} / {hit_count}"
+                        }
+                    }
+                    button {
+                        class: "history__search-btn",
+                        disabled: hit_count == 0,
+                        onclick: move |_| jump_prev(-1),
+                        "上一条"
+                    }
+                    button {
+                        class: "history__search-btn",
+                        disabled: hit_count == 0,
+                        onclick: move |_| jump(1),
+                        "下一条"
+                    }
+                }
+            }
             div { class: "history__scroll",
                 for (i, entry) in history.iter().enumerate() {
-                    div { key: "{i}", class: "history__entry",
+                    div {
+                        key: "{i}",
+                        id: "history-entry-{i}",
+                        class: if focused == Some(i) {
+                            "history__entry history__entry--focused"
+                        } else if hits.contains(&i) {
+                            "history__entry history__entry--hit"
+                        } else {
+                            "history__entry"
+                        },
                         div { class: "history__name",
                             if let Some(ref speaker) = entry.speaker {
                                 "{speaker}"
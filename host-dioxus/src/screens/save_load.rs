@@ -3,6 +3,7 @@ use tracing::error;
 
 use crate::components::{GameMenuFrame, PendingConfirm};
 use crate::render_state::{HostScreen, RenderState};
+use crate::save_manager::MAX_SAVE_SLOTS;
 use crate::screen_defs::ActionDef;
 use crate::state::AppState;
 
@@ -17,12 +18,12 @@ enum PageKind {
 }
 
 impl PageKind {
-    /// 该分页的起始 slot（含）
-    fn start_slot(self) -> u32 {
+    /// 该分页的起始 slot（含）；自动存档页列出自动存档历史，不对应编号槽位
+    fn start_slot(self) -> Option<u32> {
         match self {
-            PageKind::Auto => 61,
-            PageKind::Quick => 55,
-            PageKind::Manual(n) => (n - 1) * SLOTS_PER_PAGE + 1,
+            PageKind::Auto => None,
+            PageKind::Quick => Some(55),
+            PageKind::Manual(n) => Some((n - 1) * SLOTS_PER_PAGE + 1),
         }
     }
 
@@ -60,42 +61,88 @@ pub fn SaveLoadScreen(render_state: Signal<RenderState>) -> Element {
     let app_state = use_context::<AppState>();
     let mut pending_confirm = use_context::<Signal<Option<PendingConfirm>>>();
     let mut current_page = use_signal(|| PageKind::Manual(1));
+    // 保存时附带的存档备注（保存成功后清空）
+    let mut label_input = use_signal(String::new);
 
     let rs = render_state.read();
     let is_save_mode = rs.host_screen == HostScreen::Save;
     let title = if is_save_mode { "保存" } else { "读取" };
     let active_screen = rs.host_screen.clone();
 
-    // 当前页的 slot 范围
     let page = current_page();
-    let start_slot = page.start_slot();
 
-    // 获取存档列表
-    let saves_info: Vec<SlotInfo> = {
+    // 获取存档列表与占用情况
+    let (saves_info, used_slots, used_kb): (Vec<SlotInfo>, usize, u64) = {
         let Ok(inner) = app_state.inner.lock() else {
             return rsx! {};
         };
         let sm = &inner.services().saves;
-        let existing: std::collections::HashSet<u32> =
-            sm.list_saves().into_iter().map(|(slot, _)| slot).collect();
-        (start_slot..start_slot + SLOTS_PER_PAGE)
-            .map(|slot| {
-                let exists = existing.contains(&slot);
-                let thumb = if exists {
-                    sm.load_thumbnail_base64(slot)
-                } else {
-                    None
-                };
-                let info = if exists { sm.get_save_info(slot) } else { None };
-                SlotInfo {
-                    slot,
-                    exists,
-                    thumb,
-                    chapter: info.as_ref().and_then(|i| i.chapter_title.clone()),
-                    timestamp: info.as_ref().map(|i| i.timestamp.clone()),
-                }
-            })
-            .collect()
+        let used_slots = sm.count_saves();
+        let used_kb = sm.total_size_bytes().div_ceil(1024);
+        let saves_info = match page.start_slot() {
+            Some(start_slot) => {
+                let existing: std::collections::HashSet<u32> =
+                    sm.list_saves().into_iter().map(|(slot, _)| slot).collect();
+                (start_slot..start_slot + SLOTS_PER_PAGE)
+                    .map(|slot| {
+                        let restorable = existing.contains(&slot);
+                        let exists = restorable || sm.slot_path(slot).exists();
+                        let thumb = if exists {
+                            sm.load_thumbnail_base64(slot)
+                        } else {
+                            None
+                        };
+                        let mut info = SlotInfo {
+                            slot,
+                            auto: false,
+                            exists,
+                            restorable,
+                            thumb,
+                            chapter: None,
+                            label: None,
+                            timestamp: None,
+                            reading_time: None,
+                        };
+                        if restorable {
+                            if let Some(save) = sm.get_save_info(slot) {
+                                info.chapter = save.chapter_title;
+                                info.label = save.label;
+                                info.timestamp = Some(save.timestamp);
+                                info.reading_time = save.reading_time_secs;
+                            }
+                        } else if let Ok(preview) = sm.preview(slot) {
+                            // 版本不兼容或字段残缺的存档无法读取，仍尽量展示其元数据
+                            info.chapter = preview.chapter_title;
+                            info.label = preview.label;
+                            info.timestamp = preview.timestamp;
+                        }
+                        info
+                    })
+                    .collect()
+            }
+            // 自动存档历史：最新在前，只读（不可覆盖或删除）
+            None => sm
+                .list_autosaves()
+                .into_iter()
+                .rev()
+                .take(SLOTS_PER_PAGE as usize)
+                .filter_map(|seq| {
+                    let data = sm.load_autosave(seq).ok()?;
+                    Some(SlotInfo {
+                        slot: seq,
+                        auto: true,
+                        exists: true,
+                        restorable: true,
+                        thumb: None,
+                        chapter: data.metadata.chapter_title,
+                        label: data.metadata.label,
+                        timestamp: Some(data.metadata.timestamp),
+                        reading_time: data.metadata.reading_time_secs,
+                    })
+                })
+                .collect(),
+        };
+        (saves_info, used_slots, used_kb)
     };
 
     let all_pages = PageKind::all();
@@ -130,15 +177,37 @@ pub fn SaveLoadScreen(render_state: Signal<RenderState>) -> Element {
                 }
             }
 
+            div { class: "save-load__usage", "已用 {used_slots}/{MAX_SAVE_SLOTS} 槽 · {used_kb} KB" }
+
+            // 存档备注输入（仅保存模式）
+            if is_save_mode {
+                div { class: "save-load__label-row",
+                    input {
+                        class: "save-load__label-input",
+                        r#type: "text",
+                        maxlength: "32",
+                        placeholder: "存档备注（可选）",
+                        value: "{label_input}",
+                        oninput: move |evt: Event<FormData>| {
+                            label_input.set(evt.value());
+                        },
+                    }
+                }
+            }
+
             // Slot 网格 3×2
             div { class: "save-load__grid",
                 for si in &saves_info {
                     {
                         let slot = si.slot;
+                        let auto = si.auto;
                         let exists = si.exists;
+                        let restorable = si.restorable;
                         let thumb = si.thumb.clone();
                         let chapter = si.chapter.clone();
+                        let slot_label = si.label.clone();
                         let timestamp = si.timestamp.clone();
+                        let reading_time = si.reading_time.map(format_reading_time);
                         let app = app_state.clone();
                         let app_del = app_state.clone();
 
@@ -148,21 +217,32 @@ pub fn SaveLoadScreen(render_state: Signal<RenderState>) -> Element {
                                 class: if exists { "save-load__slot save-load__slot--filled" } else { "save-load__slot" },
                                 onclick: move |_| {
                                     if let Ok(mut inner) = app.inner.lock() {
-                                        if is_save_mode {
+                                        if auto {
+                                            if !is_save_mode {
+                                                match inner.services().saves.load_autosave(slot) {
+                                                    Ok(save) => {
+                                                        if let Err(e) = inner.restore_from_save(save) {
+                                                            error!(error = %e, "Restore failed");
+                                                        }
+                                                    }
+                                                    Err(e) => error!(error = %e, seq = slot, "Load autosave failed"),
+                                                }
+                                            }
+                                        } else if is_save_mode {
                                             if exists {
                                                 // 覆盖已有存档 → 确认弹窗
                                                 pending_confirm.set(Some(PendingConfirm {
                                                     message: format!("覆盖 Slot {slot} 的存档？"),
                                                     on_confirm: ActionDef::GoBack, // placeholder
                                                 }));
-                                                // 直接保存（确认弹窗与保存的联动尚未实现，弹窗仅作视觉提示）
-                                                if let Err(e) = inner.save_to_slot(slot) {
-                                                    error!(error = %e, slot, "Save failed");
-                                                }
-                                            } else if let Err(e) = inner.save_to_slot(slot) {
-                                                error!(error = %e, slot, "Save failed");
                                             }
-                                        } else if exists {
+                                            // 直接保存（确认弹窗与保存的联动尚未实现，弹窗仅作视觉提示）
+                                            let label = label_input.peek().clone();
+                                            match inner.save_to_slot_with_label(slot, Some(&label)) {
+                                                Ok(()) => label_input.set(String::new()),
+                                                Err(e) => error!(error = %e, slot, "Save failed"),
+                                            }
+                                        } else if restorable {
                                             match inner.services().saves.load(slot) {
                                                 Ok(save) => {
                                                     if let Err(e) = inner.restore_from_save(save) {
@@ -186,19 +266,28 @@ pub fn SaveLoadScreen(render_state: Signal<RenderState>) -> Element {
                                 // 信息区
                                 div { class: "save-load__slot-info",
                                     if exists {
+                                        if !restorable {
+                                            span { class: "save-load__slot-broken", "无法读取（版本不兼容或已损坏）" }
+                                        }
+                                        if let Some(ref lb) = slot_label {
+                                            span { class: "save-load__slot-label", "{lb}" }
+                                        }
                                         if let Some(ref ch) = chapter {
                                             span { class: "save-load__slot-chapter", "{ch}" }
                                         }
                                         if let Some(ref ts) = timestamp {
                                             span { class: "save-load__slot-time", "{ts}" }
                                         }
+                                        if let Some(ref rt) = reading_time {
+                                            span { class: "save-load__slot-reading", "{rt}" }
+                                        }
                                     } else {
                                         span { class: "save-load__slot-empty", "-- 空 --" }
                                     }
                                 }
 
                                 // 删除按钮
-                                if exists {
+                                if exists && !auto {
                                     button {
                                         class: "save-load__delete-btn",
                                         onclick: move |evt: Event<MouseData>| {
@@ -246,9 +335,21 @@ pub fn SaveLoadScreen(render_state: Signal<RenderState>) -> Element {
 }
 
 struct SlotInfo {
+    /// 编号槽位；自动存档页为自动存档序号
     slot: u32,
+    auto: bool,
     exists: bool,
+    /// 能否完整读取；为 false 时信息来自只读预览
+    restorable: bool,
     thumb: Option<String>,
     chapter: Option<String>,
+    label: Option<String>,
     timestamp: Option<String>,
+    /// 所在章节的预计阅读时长（秒）
+    reading_time: Option<u64>,
+}
+
+/// 章节阅读时长的显示文本（按分钟向上取整，至少 1 分钟）
+fn format_reading_time(secs: u64) -> String {
+    format!("本章约 {} 分钟", secs.div_ceil(60).max(1))
 }
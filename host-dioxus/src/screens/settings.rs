@@ -1,13 +1,13 @@
 use dioxus::prelude::*;
 
 use crate::components::GameMenuFrame;
-use crate::render_state::{HostScreen, RenderState};
+use crate::render_state::{HostScreen, RenderState, TextRevealMode};
 use crate::state::AppState;
 
 /// 设置 screen（嵌入 GameMenuFrame）
 ///
 /// 滑块参数对齐 egui host：文字速度 5-100 cps，自动延迟 0.5-5.0s，
-/// BGM/SFX 0-100%，静音复选框，"应用"按钮。
+/// 主音量/BGM/SFX 0-100%，静音与逐字淡入复选框，"应用"按钮。
 #[component]
 pub fn SettingsScreen(render_state: Signal<RenderState>) -> Element {
     let app_state = use_context::<AppState>();
@@ -20,11 +20,13 @@ pub fn SettingsScreen(render_state: Signal<RenderState>) -> Element {
         inner.user_settings.clone()
     };
 
+    let mut master_vol = use_signal(|| settings.master_volume);
     let mut bgm_vol = use_signal(|| settings.bgm_volume);
     let mut sfx_vol = use_signal(|| settings.sfx_volume);
     let mut text_speed = use_signal(|| settings.text_speed);
     let mut auto_delay = use_signal(|| settings.auto_delay);
     let mut muted = use_signal(|| settings.muted);
+    let mut fade_in = use_signal(|| settings.text_reveal == TextRevealMode::FadeIn);
 
     let app_apply = app_state.clone();
 
@@ -50,6 +52,21 @@ pub fn SettingsScreen(render_state: Signal<RenderState>) -> Element {
                     span { class: "settings__value", "{text_speed:.0} cps" }
                 }
 
+                // 文字显示方式
+                div { class: "settings__row",
+                    label { class: "settings__label", " " }
+                    label { class: "settings__checkbox-label",
+                        input {
+                            r#type: "checkbox",
+                            checked: "{fade_in}",
+                            oninput: move |evt: Event<FormData>| {
+                                fade_in.set(evt.value() == "true");
+                            },
+                        }
+                        " 文字逐字淡入"
+                    }
+                }
+
                 // 自动延迟
                 div { class: "settings__row",
                     label { class: "settings__label", "自动延迟" }
@@ -69,6 +86,25 @@ pub fn SettingsScreen(render_state: Signal<RenderState>) -> Element {
                     span { class: "settings__value", "{auto_delay:.1} s" }
                 }
 
+                // 主音量
+                div { class: "settings__row",
+                    label { class: "settings__label", "主音量" }
+                    input {
+                        class: "settings__slider",
+                        r#type: "range",
+                        min: "0",
+                        max: "100",
+                        step: "1",
+                        value: "{master_vol}",
+                        oninput: move |evt: Event<FormData>| {
+                            if let Ok(v) = evt.value().parse::<f32>() {
+                                master_vol.set(v);
+                            }
+                        },
+                    }
+                    span { class: "settings__value", "{master_vol:.0}%" }
+                }
+
                 // BGM 音量
                 div { class: "settings__row",
                     label { class: "settings__label", "BGM 音量" }
@@ -128,12 +164,19 @@ pub fn SettingsScreen(render_state: Signal<RenderState>) -> Element {
                         class: "settings__apply-btn",
                         onclick: move |_| {
                             if let Ok(mut inner) = app_apply.inner.lock() {
+                                inner.user_settings.master_volume = master_vol();
                                 inner.user_settings.bgm_volume = bgm_vol();
                                 inner.user_settings.sfx_volume = sfx_vol();
                                 inner.user_settings.text_speed = text_speed();
                                 inner.text_speed = text_speed();
                                 inner.user_settings.auto_delay = auto_delay();
                                 inner.user_settings.muted = muted();
+                                inner.user_settings.text_reveal = if fade_in() {
+                                    TextRevealMode::FadeIn
+                                } else {
+                                    TextRevealMode::Typewriter
+                                };
+                                inner.apply_volume_settings();
                             }
                         },
                         "应用"
@@ -0,0 +1,303 @@
+//! 启动时脚本检查
+//!
+//! `debug.script_check` 开启时，从入口脚本出发沿 `callScript` 遍历所有可达脚本，
+//! 汇总解析错误、诊断分析结果与缺失资源，进入标题时以 Toast 提示，点击查看详情。
+//! 音效池（manifest `sfx_pools`）不在脚本中写路径，单独核对池定义与池内候选资源。
+//!
+//! 聚合逻辑只依赖"读文本 / 判断资源存在"两个回调，便于脱离 ResourceManager 测试。
+
+use std::collections::{BTreeMap, HashSet};
+
+use vn_runtime::{
+    ComplexityLimits, Diagnostic, DiagnosticResult, Parser, ScriptNode, analyze_script,
+    check_complexity, diagnostic_rules, extract_resource_references,
+};
+
+use crate::resources::normalize_logical_path;
+
+/// 脚本检查汇总
+#[derive(Debug, Clone, Default)]
+pub struct ScriptCheckSummary {
+    /// 检查的脚本数量
+    pub scripts_checked: usize,
+    /// 全部诊断（解析失败记为错误）
+    pub diagnostics: DiagnosticResult,
+}
+
+/// 启动 Toast 内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptCheckToast {
+    pub text: String,
+    /// 是否含错误（决定 Toast 样式）
+    pub has_errors: bool,
+    /// 详情面板逐行内容
+    pub details: Vec<String>,
+}
+
+impl ScriptCheckSummary {
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.error_count()
+    }
+
+    pub fn warn_count(&self) -> usize {
+        self.diagnostics.warn_count()
+    }
+
+    /// 生成启动 Toast；无错误也无警告时不提示
+    pub fn toast(&self) -> Option<ScriptCheckToast> {
+        let (errors, warnings) = (self.error_count(), self.warn_count());
+        if errors == 0 && warnings == 0 {
+            return None;
+        }
+        Some(ScriptCheckToast {
+            text: format!("脚本检查：{errors} 个错误，{warnings} 个警告（点击查看详情）"),
+            has_errors: errors > 0,
+            details: self
+                .diagnostics
+                .diagnostics
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// 从入口脚本开始检查所有可达脚本
+///
+/// - `read_text(path)`：读取脚本文本，不存在时返回 None
+/// - `resource_exists(path)`：资源是否存在
+/// - `sfx_pools`：manifest 中的音效池；脚本引用未定义或为空的池记为错误，池内缺失的资源记为警告
+pub fn check_scripts(
+    entry_path: &str,
+    read_text: impl Fn(&str) -> Option<String>,
+    resource_exists: impl Fn(&str) -> bool,
+    sfx_pools: &BTreeMap<String, Vec<String>>,
+) -> ScriptCheckSummary {
+    let mut summary = ScriptCheckSummary::default();
+    let mut visited = HashSet::new();
+    let mut pending = vec![normalize_logical_path(entry_path)];
+
+    while let Some(path) = pending.pop() {
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+        summary.scripts_checked += 1;
+
+        let Some(content) = read_text(&path) else {
+            summary
+                .diagnostics
+                .push(Diagnostic::error(&path, "脚本文件不存在或无法读取"));
+            continue;
+        };
+
+        let (script_id, base_path) = match path.rsplit_once('/') {
+            Some((dir, file)) => (file.trim_end_matches(".md"), dir),
+            None => (path.trim_end_matches(".md"), ""),
+        };
+        let mut parser = Parser::new();
+        let (script, errors) = parser.parse_collecting_errors(script_id, &content, base_path);
+        if !errors.is_empty() {
+            // 一次报告全部解析错误；部分解析的脚本不做后续分析，避免连带误报
+            for e in errors {
+                summary
+                    .diagnostics
+                    .push(Diagnostic::error(&path, e.to_string()));
+            }
+            continue;
+        }
+
+        for warning in parser.warnings() {
+            summary
+                .diagnostics
+                .push(Diagnostic::warn(&path, warning).with_rule(diagnostic_rules::PARSE_WARNING));
+        }
+        summary.diagnostics.merge(analyze_script(&script));
+        summary
+            .diagnostics
+            .merge(check_complexity(&script, &ComplexityLimits::default()));
+
+        for r in extract_resource_references(&script) {
+            if !resource_exists(&normalize_logical_path(&r.resolved_path)) {
+                summary.diagnostics.push(
+                    Diagnostic::warn(
+                        &path,
+                        format!("资源不存在 [{}] {}", r.resource_type, r.resolved_path),
+                    )
+                    .with_rule(diagnostic_rules::MISSING_RESOURCE),
+                );
+            }
+        }
+
+        for pool in collect_sfx_pool_names(&script.nodes) {
+            if sfx_pools.get(pool).is_none_or(Vec::is_empty) {
+                summary.diagnostics.push(Diagnostic::error(
+                    &path,
+                    format!("音效池 @{pool} 未在 manifest 中定义或为空"),
+                ));
+            }
+        }
+
+        for node in collect_call_nodes(&script.nodes) {
+            if let ScriptNode::CallScript { path, .. } = node {
+                pending.push(normalize_logical_path(&script.resolve_path(path)));
+            }
+        }
+    }
+
+    for (pool, candidates) in sfx_pools {
+        for candidate in candidates {
+            if !resource_exists(&normalize_logical_path(candidate)) {
+                summary.diagnostics.push(
+                    Diagnostic::warn(
+                        "manifest.json",
+                        format!("资源不存在 [音效池 @{pool}] {candidate}"),
+                    )
+                    .with_rule(diagnostic_rules::MISSING_RESOURCE),
+                );
+            }
+        }
+    }
+
+    summary
+}
+
+/// 从 AST 中收集 `playSfx @池名` 引用的池名（包括条件分支内部的）
+fn collect_sfx_pool_names(nodes: &[ScriptNode]) -> Vec<&str> {
+    let mut result = Vec::new();
+    for node in nodes {
+        match node {
+            ScriptNode::PlaySfxPool { pool } => result.push(pool.as_str()),
+            ScriptNode::Conditional { branches } => {
+                for branch in branches {
+                    result.extend(collect_sfx_pool_names(&branch.body));
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+/// 从 AST 中收集所有 CallScript 节点（包括条件分支内部的）
+pub(crate) fn collect_call_nodes(nodes: &[ScriptNode]) -> Vec<&ScriptNode> {
+    let mut result = Vec::new();
+    for node in nodes {
+        match node {
+            ScriptNode::CallScript { .. } => result.push(node),
+            ScriptNode::Conditional { branches } => {
+                for branch in branches {
+                    result.extend(collect_call_nodes(&branch.body));
+                }
+            }
+            _ => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn check(files: &[(&str, &str)]) -> ScriptCheckSummary {
+        check_with_pools(files, &BTreeMap::new())
+    }
+
+    fn check_with_pools(
+        files: &[(&str, &str)],
+        sfx_pools: &BTreeMap<String, Vec<String>>,
+    ) -> ScriptCheckSummary {
+        let files: HashMap<String, String> = files
+            .iter()
+            .map(|(p, c)| (p.to_string(), c.to_string()))
+            .collect();
+        check_scripts(
+            "scripts/main.md",
+            |p| files.get(p).cloned(),
+            |p| files.contains_key(p),
+            sfx_pools,
+        )
+    }
+
+    #[test]
+    fn script_with_errors_produces_error_count_toast() {
+        let summary = check(&[(
+            "scripts/main.md",
+            "：\"开始。\"\n\ngoto **不存在的标签**\n\ncallScript [入口](missing.md)\n",
+        )]);
+
+        assert_eq!(summary.scripts_checked, 2);
+        assert_eq!(summary.error_count(), 2);
+
+        let toast = summary.toast().expect("errors should produce a toast");
+        assert!(toast.has_errors);
+        assert!(
+            toast.text.starts_with("脚本检查：2 个错误"),
+            "{}",
+            toast.text
+        );
+        assert_eq!(toast.details.len(), summary.diagnostics.diagnostics.len());
+    }
+
+    #[test]
+    fn every_parse_error_in_a_script_is_reported() {
+        let summary = check(&[("scripts/main.md", "hide\n\n：\"中间。\"\n\nfilter vivid\n")]);
+
+        assert_eq!(summary.error_count(), 2);
+    }
+
+    #[test]
+    fn clean_scripts_produce_no_toast() {
+        let summary = check(&[
+            (
+                "scripts/main.md",
+                "：\"开始。\"\n\ncallScript [子](sub.md)\n",
+            ),
+            ("scripts/sub.md", "：\"子脚本。\"\n"),
+        ]);
+
+        assert_eq!(summary.scripts_checked, 2);
+        assert!(summary.toast().is_none());
+    }
+
+    #[test]
+    fn missing_resource_is_a_warning() {
+        let summary = check(&[(
+            "scripts/main.md",
+            "changeBG <img src=\"../bg/none.png\" />\n",
+        )]);
+
+        assert_eq!(summary.error_count(), 0);
+        assert_eq!(summary.warn_count(), 1);
+        let toast = summary.toast().unwrap();
+        assert!(!toast.has_errors);
+        assert!(toast.text.contains("0 个错误，1 个警告"));
+    }
+
+    #[test]
+    fn sfx_pools_are_checked_against_scripts_and_resources() {
+        let pools = BTreeMap::from([(
+            "steps".to_string(),
+            vec![
+                "audio/step1.ogg".to_string(),
+                "audio/missing.ogg".to_string(),
+            ],
+        )]);
+        let summary = check_with_pools(
+            &[
+                ("scripts/main.md", "playSfx @steps\n\nplaySfx @doors\n"),
+                ("audio/step1.ogg", ""),
+            ],
+            &pools,
+        );
+
+        // 未定义的池是错误，池内缺失的候选是警告
+        assert_eq!(summary.error_count(), 1);
+        assert_eq!(summary.warn_count(), 1);
+        let details = summary.toast().unwrap().details.join("\n");
+        assert!(details.contains("@doors"), "{details}");
+        assert!(details.contains("audio/missing.ogg"), "{details}");
+    }
+}
@@ -3,21 +3,24 @@ use std::collections::HashSet;
 use tracing::{error, warn};
 use vn_runtime::history::HistoryEvent;
 use vn_runtime::state::{RuntimeState, WaitingReason};
-use vn_runtime::{Parser, Script, ScriptNode, VNRuntime};
+use vn_runtime::{LocaleTables, Parser, Script, ScriptNode, VNRuntime};
+
+use crate::config::I18nConfig;
 
 use crate::error::HostResult;
 use crate::render_state::{HostScreen, PlaybackMode};
 use crate::resources::{LogicalPath, ResourceManager};
+use crate::script_check::collect_call_nodes;
 
 use super::*;
 
 impl AppStateInner {
-    /// 将 PersistentStore 中的变量注入到当前 runtime。
+    /// 将 PersistentStore 中的变量、已解锁结局、已看过的 CG 与已走过的分支注入到当前 runtime。
     pub(super) fn inject_persistent_vars(&mut self) {
-        if self.persistent_store.variables.is_empty() {
-            return;
-        }
         if let Some(rt) = self.runtime.as_mut() {
+            rt.import_endings(&self.persistent_store.endings);
+            rt.import_seen_cgs(&self.persistent_store.seen_cgs);
+            rt.import_chosen_branches(&self.persistent_store.chosen_branches);
             for (k, v) in &self.persistent_store.variables {
                 rt.state_mut().set_persistent_var(k.clone(), v.clone());
             }
@@ -31,6 +34,7 @@ impl AppStateInner {
     pub(super) fn reset_session(&mut self) {
         if let Some(svc) = self.services.as_mut() {
             svc.audio.stop_bgm(None);
+            svc.audio.reset_script_bgm_volume();
         }
         self.runtime = None;
         self.render_state = crate::render_state::RenderState::new();
@@ -51,7 +55,9 @@ impl AppStateInner {
         let mut parser = Parser::new();
         let script = parser.parse("main", script_content)?;
         self.reset_session();
-        self.runtime = Some(VNRuntime::new(script));
+        let mut runtime = VNRuntime::new(script);
+        runtime.set_textbox_auto_hide(true);
+        self.runtime = Some(runtime);
         self.inject_persistent_vars();
         self.set_host_screen(HostScreen::InGame);
         self.run_script_tick();
@@ -76,6 +82,20 @@ impl AppStateInner {
         }
 
         let mut runtime = VNRuntime::new(script.clone());
+        runtime.set_textbox_auto_hide(true);
+        runtime.set_locale_tables(load_locale_tables(rm, &self.services().config.i18n));
+        runtime.set_sfx_pools(self.services().manifest.sfx_pools.clone());
+        // 新游戏按当前时间取种子；读档时由存档中的随机数状态覆盖
+        runtime.set_rng_seed(crate::now_secs());
+        if self
+            .services()
+            .config
+            .debug
+            .resolve_debug_server()
+            .is_some()
+        {
+            runtime.enable_metrics();
+        }
         let normalized = logical.as_str().to_string();
         runtime.register_script(&normalized, script.clone());
         runtime.state_mut().position.set_path(&normalized);
@@ -232,6 +252,32 @@ impl AppStateInner {
     }
 }
 
+/// 加载默认语言与当前语言的文本表
+///
+/// 语言表缺失或格式错误时只记录告警；`@key` 对话此时按原文显示。
+pub(crate) fn load_locale_tables(rm: &ResourceManager, config: &I18nConfig) -> LocaleTables {
+    let mut tables = LocaleTables::new(&config.default_locale);
+    tables.set_locale(config.current_locale());
+
+    let mut locales = vec![config.default_locale.as_str()];
+    if config.current_locale() != config.default_locale {
+        locales.push(config.current_locale());
+    }
+    for locale in locales {
+        let path = LogicalPath::new(&format!("{}/{}.json", config.tables_dir, locale));
+        let Some(content) = rm.read_text_optional(&path) else {
+            if config.locale.is_some() {
+                warn!(path = %path.as_str(), "语言表不存在");
+            }
+            continue;
+        };
+        if let Err(e) = tables.load_json(locale, &content) {
+            warn!(path = %path.as_str(), error = %e, "语言表解析失败");
+        }
+    }
+    tables
+}
+
 pub(crate) fn preload_called_scripts(
     runtime: &mut VNRuntime,
     rm: &ResourceManager,
@@ -273,23 +319,6 @@ pub(crate) fn preload_called_scripts(
     }
 }
 
-/// 从 AST 中收集所有 CallScript 节点（包括条件分支内部的）
-fn collect_call_nodes(nodes: &[ScriptNode]) -> Vec<&ScriptNode> {
-    let mut result = Vec::new();
-    for node in nodes {
-        match node {
-            ScriptNode::CallScript { .. } => result.push(node),
-            ScriptNode::Conditional { branches } => {
-                for branch in branches {
-                    result.extend(collect_call_nodes(&branch.body));
-                }
-            }
-            _ => {}
-        }
-    }
-    result
-}
-
 pub(crate) fn load_call_stack_scripts(
     runtime: &mut VNRuntime,
     resources: &ResourceManager,
@@ -354,5 +383,6 @@ pub(crate) fn map_runtime_waiting(waiting_reason: &WaitingReason) -> WaitingFor
             WaitingFor::Signal(kind)
         }
         WaitingReason::WaitForUIResult { key, .. } => WaitingFor::UIResult { key: key.clone() },
+        WaitingReason::Breakpoint(_) => WaitingFor::Breakpoint,
     }
 }
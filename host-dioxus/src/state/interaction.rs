@@ -1,16 +1,32 @@
-use tracing::warn;
-use vn_runtime::RuntimeInput;
+use tracing::{info, warn};
 use vn_runtime::command::Command;
 use vn_runtime::state::WaitingReason;
+use vn_runtime::{Breakpoint, ResourceType, RuntimeError, RuntimeInput, VNRuntime};
 
 use crate::command_executor::{
     AudioCommand, BatchOutput, ExecuteResult, SceneEffectKind, SceneEffectRequest,
 };
 use crate::error::{HostError, HostResult};
 use crate::render_state::{CutsceneState, HostScreen, PlaybackMode};
+use crate::resources::LogicalPath;
+use crate::touch::{TouchAction, TouchStroke};
 
 use super::*;
 
+/// 图片预取的前瞻节点数
+const PRELOAD_LOOKAHEAD_NODES: usize = 30;
+
+/// 执行一次 runtime tick，并把 Host 测得的耗时注入性能计数（未开启时忽略）
+fn timed_tick(
+    rt: &mut VNRuntime,
+    input: Option<RuntimeInput>,
+) -> Result<(Vec<Command>, WaitingReason), RuntimeError> {
+    let start = std::time::Instant::now();
+    let result = rt.tick(input);
+    rt.record_tick_time(start.elapsed().as_micros() as u64);
+    result
+}
+
 impl AppStateInner {
     /// 处理用户点击
     pub fn process_click(&mut self) {
@@ -19,6 +35,11 @@ impl AppStateInner {
         }
         self.auto_timer = 0.0;
 
+        // 过渡进行中：点击只跳过过渡，不推进脚本
+        if self.skip_active_transitions() {
+            return;
+        }
+
         if !self.render_state.is_dialogue_complete() {
             self.render_state.complete_typewriter();
             if self
@@ -90,10 +111,12 @@ impl AppStateInner {
         };
         let target_bgm = snapshot.current_bgm.clone();
         if let Some(rt) = self.runtime.as_mut() {
-            rt.restore_state(snapshot.runtime_state.clone());
+            // 快照来自本次会话，脚本未变更，无需兼容性校验
+            *rt.state_mut() = snapshot.runtime_state.clone();
             rt.restore_history(snapshot.runtime_history.clone());
         }
         self.render_state = snapshot.render_state;
+        self.anim.timeline.clear();
         self.history = host_history_from_runtime(&snapshot.runtime_history);
         self.waiting = map_runtime_waiting(&snapshot.runtime_state.waiting);
         {
@@ -143,7 +166,7 @@ impl AppStateInner {
             .as_mut()
             .expect("invariant: UIResult requires loaded runtime");
         rt.set_now(crate::now_secs());
-        let tick_result = rt.tick(Some(input))?;
+        let tick_result = timed_tick(rt, Some(input))?;
         self.render_state.active_ui_mode = None;
         self.waiting = WaitingFor::Nothing;
         self.apply_runtime_tick_output(tick_result.0, tick_result.1);
@@ -151,6 +174,28 @@ impl AppStateInner {
         Ok(())
     }
 
+    /// 处理鼠标点击；触摸刚结束时视为合成点击并忽略
+    pub fn process_mouse_click(&mut self, now_ms: f64) {
+        if self.touch_guard.should_suppress_mouse(now_ms) {
+            return;
+        }
+        self.process_click();
+    }
+
+    /// 处理一次触摸（按下到抬起）
+    pub fn process_touch(&mut self, stroke: &TouchStroke, now_ms: f64) {
+        self.touch_guard.record_touch(now_ms);
+        if self.host_screen != HostScreen::InGame {
+            return;
+        }
+        match stroke.action() {
+            Some(TouchAction::Click) => self.process_click(),
+            Some(TouchAction::ChoiceSelected(index)) => self.process_choose(index),
+            Some(TouchAction::OpenHistory) => self.set_host_screen(HostScreen::History),
+            None => {}
+        }
+    }
+
     /// 处理用户选择
     pub fn process_choose(&mut self, index: usize) {
         if !self.host_screen.allows_progression() {
@@ -159,12 +204,19 @@ impl AppStateInner {
         if self.waiting != WaitingFor::Choice {
             return;
         }
+        // 选择提示尚未打完时选项面板还未出现
+        if !self.render_state.is_dialogue_complete() {
+            return;
+        }
+        if !self.render_state.is_choice_enabled(index) {
+            return;
+        }
         let rt = self
             .runtime
             .as_mut()
             .expect("invariant: choice selection requires loaded runtime");
         rt.set_now(crate::now_secs());
-        let tick_result = rt.tick(Some(RuntimeInput::choice(index)));
+        let tick_result = timed_tick(rt, Some(RuntimeInput::choice(index)));
         self.render_state.clear_choices();
         self.waiting = WaitingFor::Nothing;
         match tick_result {
@@ -178,23 +230,66 @@ impl AppStateInner {
         }
     }
 
+    /// 更新选项悬停（鼠标移入 / 移出）
+    pub fn process_choice_hover(&mut self, index: Option<usize>) {
+        if self.waiting != WaitingFor::Choice {
+            return;
+        }
+        self.render_state.set_choice_hover(index);
+    }
+
     pub(super) fn apply_runtime_tick_output(
         &mut self,
         commands: Vec<Command>,
         waiting_reason: WaitingReason,
     ) {
-        let manifest = &self
+        // 上一段 timeline 尚未播完时先立即完成，保证新命令作用在最终画面上
+        self.flush_timeline();
+
+        if let Some(rt) = self.runtime.as_mut() {
+            for w in rt.take_text_warnings() {
+                if w.fell_back {
+                    warn!(key = %w.key, locale = %w.locale, "文本 key 缺失，已回退到默认语言");
+                } else {
+                    warn!(key = %w.key, locale = %w.locale, "文本 key 缺失，按原文显示");
+                }
+            }
+        }
+
+        let services = self
             .services
             .as_ref()
-            .expect("invariant: services initialized in setup()")
-            .manifest;
+            .expect("invariant: services initialized in setup()");
+        let resource_exists =
+            |path: &str| services.resources.resource_exists(&LogicalPath::new(path));
+        let batch = self.command_executor.execute_batch(
+            &commands,
+            &mut self.render_state,
+            &services.manifest,
+            &resource_exists,
+        );
         let BatchOutput {
             result,
             audio_commands,
             scene_effect_request,
-        } = self
-            .command_executor
-            .execute_batch(&commands, &mut self.render_state, manifest);
+            warnings,
+            transition_events,
+            scheduled,
+        } = match batch {
+            Ok(output) => output,
+            Err(error) => {
+                // 整批已回滚，画面停留在批次执行前
+                warn!(%error, "command batch failed, render state rolled back");
+                self.pending_warnings
+                    .push(format!("脚本命令执行失败：{error}"));
+                self.script_finished = true;
+                return;
+            }
+        };
+        self.pending_warnings.extend(warnings);
+        self.anim.transition_events.extend(transition_events);
+        self.anim.timeline = scheduled;
+        self.anim.timeline_elapsed = 0.0;
 
         if let Some(ref d) = self.render_state.dialogue
             && (d.visible_chars == 0 || !d.content.is_empty())
@@ -208,6 +303,7 @@ impl AppStateInner {
         for cmd in audio_commands {
             self.dispatch_audio_command(cmd);
         }
+        self.refresh_preload_images();
 
         if let Some(req) = scene_effect_request {
             self.apply_scene_effect(req);
@@ -246,10 +342,26 @@ impl AppStateInner {
 
         // 同步 runtime persistent 变量到 PersistentStore
         if let Some(rt) = self.runtime.as_ref() {
-            let pv = &rt.state().persistent_variables;
+            let pv = rt.persistent_vars();
             if !pv.is_empty() {
                 self.persistent_store.merge_from(pv);
             }
+            self.persistent_store.endings.merge(rt.unlocked_endings());
+            self.persistent_store
+                .seen_cgs
+                .extend(rt.seen_cgs().iter().cloned());
+            self.persistent_store
+                .merge_chosen_branches(rt.chosen_branches());
+        }
+
+        // 经过章节标记或完成选择后自动存档（脚本已结束时不写）
+        if let Some(trigger) = self
+            .runtime
+            .as_mut()
+            .and_then(|rt| rt.take_autosave_point())
+            && waiting_reason != WaitingReason::None
+        {
+            self.save_auto(trigger);
         }
 
         if waiting_reason == WaitingReason::None && commands.is_empty() {
@@ -261,6 +373,53 @@ impl AppStateInner {
         self.project_render_state();
     }
 
+    /// 执行 timeline 中到时的命令
+    ///
+    /// 命令整批事务式执行；失败时只提示玩家，不中断脚本。
+    pub(super) fn run_timeline_commands(&mut self, commands: &[Command]) {
+        let services = self
+            .services
+            .as_ref()
+            .expect("invariant: services initialized in setup()");
+        let resource_exists =
+            |path: &str| services.resources.resource_exists(&LogicalPath::new(path));
+        let batch = match self.command_executor.execute_batch(
+            commands,
+            &mut self.render_state,
+            &services.manifest,
+            &resource_exists,
+        ) {
+            Ok(batch) => batch,
+            Err(error) => {
+                warn!(%error, "timeline command failed, render state rolled back");
+                self.pending_warnings
+                    .push(format!("timeline 命令执行失败：{error}"));
+                return;
+            }
+        };
+        self.pending_warnings.extend(batch.warnings);
+        self.anim.transition_events.extend(batch.transition_events);
+        for cmd in batch.audio_commands {
+            self.dispatch_audio_command(cmd);
+        }
+        if let Some(req) = batch.scene_effect_request {
+            self.apply_scene_effect(req);
+        }
+    }
+
+    /// 按 Runtime 前瞻刷新图片预取列表（音频与视频由各自播放器按需加载，不预取）
+    fn refresh_preload_images(&mut self) {
+        let Some(rt) = self.runtime.as_ref() else {
+            return;
+        };
+        self.render_state.preload_images = rt
+            .upcoming_resources(PRELOAD_LOOKAHEAD_NODES)
+            .into_iter()
+            .filter(|r| !matches!(r.resource_type, ResourceType::Audio | ResourceType::Video))
+            .map(|r| r.resolved_path)
+            .collect();
+    }
+
     /// 调用 runtime.tick() 并执行产出的 commands
     pub(crate) fn run_script_tick(&mut self) {
         let Some(rt) = self.runtime.as_mut() else {
@@ -268,7 +427,7 @@ impl AppStateInner {
         };
         rt.set_now(crate::now_secs());
 
-        match rt.tick(None) {
+        match timed_tick(rt, None) {
             Ok((commands, waiting_reason)) => {
                 self.apply_runtime_tick_output(commands, waiting_reason)
             }
@@ -279,18 +438,86 @@ impl AppStateInner {
         }
     }
 
+    /// 替换脚本调试断点集合
+    pub fn set_breakpoints(&mut self, breakpoints: impl IntoIterator<Item = Breakpoint>) {
+        let Some(rt) = self.runtime.as_mut() else {
+            return;
+        };
+        rt.clear_breakpoints();
+        for breakpoint in breakpoints {
+            rt.add_breakpoint(breakpoint);
+        }
+    }
+
+    /// 从断点处恢复脚本执行
+    pub fn continue_from_breakpoint(&mut self) {
+        if self.waiting != WaitingFor::Breakpoint {
+            return;
+        }
+        let rt = self
+            .runtime
+            .as_mut()
+            .expect("invariant: breakpoint requires loaded runtime");
+        rt.set_now(crate::now_secs());
+        let tick_result = rt.resume();
+        self.waiting = WaitingFor::Nothing;
+        match tick_result {
+            Ok((commands, waiting_reason)) => {
+                self.apply_runtime_tick_output(commands, waiting_reason)
+            }
+            Err(error) => {
+                warn!(%error, "断点恢复执行失败");
+                self.script_finished = true;
+            }
+        }
+    }
+
     /// 追加对话历史
     pub fn push_history(&mut self, speaker: Option<String>, text: String) {
         self.history.insert(0, HistoryEntry { speaker, text });
     }
 
     pub fn set_host_screen(&mut self, screen: HostScreen) {
+        // 存档界面会盖住场景层：有前端时先抓取场景缩略图，抓帧结束后再切换
+        if screen == HostScreen::Save
+            && self.host_screen.shows_scene()
+            && self.session.client_owner.is_some()
+        {
+            if self.deferred_screen.is_none() {
+                self.scene_capture_requested = true;
+            }
+            self.deferred_screen = Some(screen);
+            return;
+        }
+        self.deferred_screen = None;
+        self.switch_host_screen(screen);
+    }
+
+    /// 场景抓帧结束（`png` 为 `None` 表示失败），完成被推迟的界面切换；
+    /// 抓帧期间已切到其他界面时丢弃结果
+    pub fn finish_scene_capture(&mut self, png: Option<Vec<u8>>) {
+        if let Some(screen) = self.deferred_screen.take() {
+            self.scene_thumbnail = png;
+            self.switch_host_screen(screen);
+        }
+    }
+
+    /// 取走场景抓帧请求（前端每帧调用）
+    pub fn take_scene_capture_request(&mut self) -> bool {
+        std::mem::take(&mut self.scene_capture_requested)
+    }
+
+    fn switch_host_screen(&mut self, screen: HostScreen) {
         if self.host_screen == screen {
             self.project_render_state();
             return;
         }
 
         self.host_screen = screen;
+        // 回到场景后画面会继续变化，之前抓取的缩略图作废
+        if self.host_screen.shows_scene() {
+            self.scene_thumbnail = None;
+        }
 
         if !self.host_screen.allows_progression() && self.playback_mode != PlaybackMode::Normal {
             self.playback_mode = PlaybackMode::Normal;
@@ -316,6 +543,72 @@ impl AppStateInner {
         self.project_render_state();
     }
 
+    /// 把用户设置中的音量（0~100）同步到 AudioManager
+    pub fn apply_volume_settings(&mut self) {
+        let settings = self.user_settings.clone();
+        if let Some(svc) = self.services.as_mut() {
+            svc.audio.set_master_volume(settings.master_volume / 100.0);
+            svc.audio.set_bgm_volume(settings.bgm_volume / 100.0);
+            svc.audio.set_sfx_volume(settings.sfx_volume / 100.0);
+            svc.audio.set_muted(settings.muted);
+        }
+    }
+
+    /// 整体替换用户设置，音量与文字速度立即生效
+    pub fn apply_user_settings(&mut self, settings: UserSettings) {
+        self.text_speed = settings.text_speed;
+        self.user_settings = settings;
+        self.apply_volume_settings();
+    }
+
+    /// `user_settings.json` 被修改时重新加载并应用，返回是否应用了新设置
+    ///
+    /// 解析失败时保留当前设置，并提示玩家。
+    pub fn reload_user_settings_if_changed(&mut self) -> bool {
+        let Some(result) = self.settings_watcher.as_mut().and_then(|w| w.check()) else {
+            return false;
+        };
+        match result {
+            Ok(settings) => {
+                info!("检测到用户设置变更，已重新加载");
+                self.apply_user_settings(settings);
+                true
+            }
+            Err(e) => {
+                warn!(error = %e, "用户设置重新加载失败，保留当前设置");
+                self.pending_warnings.push(format!("{e}，已保留当前设置"));
+                false
+            }
+        }
+    }
+
+    /// 记录玩家调整后的窗口尺寸（逻辑像素）；窗口不可调整大小或尺寸为 0（最小化）时忽略
+    pub fn record_window_size(&mut self, width: u32, height: u32) {
+        let resizable = self
+            .services
+            .as_ref()
+            .is_some_and(|s| s.config.window.resizable);
+        if resizable && width > 0 && height > 0 {
+            self.user_settings.window_size = Some([width, height]);
+        }
+    }
+
+    /// 把当前用户设置写回 `user_settings.json`（退出时调用；未初始化时忽略）
+    pub fn save_user_settings(&mut self) -> HostResult<()> {
+        let Some(path) = self
+            .settings_watcher
+            .as_ref()
+            .map(|w| w.path().to_path_buf())
+        else {
+            return Ok(());
+        };
+        self.user_settings.save(&path)?;
+        // 以本次写入为基准，避免被热加载当作外部改动
+        self.settings_watcher = Some(SettingsWatcher::new(&path));
+        info!(path = %path.display(), "用户设置已保存");
+        Ok(())
+    }
+
     /// 分派音频命令到 AudioManager
     pub(super) fn dispatch_audio_command(&mut self, cmd: AudioCommand) {
         let audio = &mut self.services_mut().audio;
@@ -336,6 +629,9 @@ impl AppStateInner {
             AudioCommand::BgmUnduck => {
                 audio.unduck();
             }
+            AudioCommand::SetBgmVolume { volume, duration } => {
+                audio.set_script_bgm_volume(volume, duration);
+            }
             AudioCommand::PlaySfx { path } => {
                 audio.play_sfx(&path);
             }
@@ -373,6 +669,21 @@ impl AppStateInner {
                 self.render_state.scene_effect.dim_level = 0.0;
                 self.anim.scene_effect_active = false;
             }
+            SceneEffectKind::Pan { x, y } => {
+                if req.duration > 0.0 {
+                    self.anim.active_pan = Some(CameraPanAnimation {
+                        from: self.render_state.camera_pan,
+                        to: (x, y),
+                        duration: req.duration,
+                        elapsed: 0.0,
+                    });
+                    self.anim.scene_effect_active = true;
+                } else {
+                    self.anim.active_pan = None;
+                    self.render_state.camera_pan = (x, y);
+                    self.anim.scene_effect_active = false;
+                }
+            }
         }
     }
 }
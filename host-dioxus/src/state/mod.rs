@@ -13,22 +13,32 @@ pub use types::*;
 pub(crate) use game_lifecycle::{
     host_history_from_runtime, load_call_stack_scripts, map_runtime_waiting,
 };
-pub use save_load::waiting_requires_snapshot_fallback;
+pub use save_load::{AUTOSAVE_KEEP_COUNT, waiting_requires_snapshot_fallback};
+pub use tick::SKIP_ANIMATION_TIME_SCALE;
 
-use crate::command_executor::CommandExecutor;
-use crate::render_state::{HostScreen, PlaybackMode, RenderState};
+use crate::command_executor::{CommandExecutor, ScheduledCommand};
+use crate::render_state::{HostScreen, PlaybackMode, RenderState, TransitionEvent};
+use crate::touch::TouchGuard;
 
 /// 动画/过渡计时器（仅在 tick 和 interaction 中使用）
 #[derive(Default)]
 pub struct AnimationTimers {
     /// 背景过渡内部计时器
     pub bg_transition_elapsed: f32,
-    /// 场景过渡内部计时器
+    /// 场景过渡内部计时器（当前阶段已经过的时间）
     pub scene_transition_elapsed: f32,
+    /// 待主循环消费的场景过渡生命周期事件（按产生顺序）
+    pub transition_events: Vec<TransitionEvent>,
     /// 活跃的 shake 动画状态
     pub active_shake: Option<ShakeAnimation>,
+    /// 活跃的镜头平移动画
+    pub active_pan: Option<CameraPanAnimation>,
     /// 是否有活跃的场景效果（用于 signal 解析）
     pub scene_effect_active: bool,
+    /// timeline 中尚未到时的命令（按延时升序）
+    pub timeline: Vec<ScheduledCommand>,
+    /// timeline 已经过的时间
+    pub timeline_elapsed: f32,
 }
 
 /// Debug session 管理（完全隔离在 session.rs 中）
@@ -55,6 +65,8 @@ pub struct AppStateInner {
     pub history: Vec<HistoryEntry>,
     /// 用户设置
     pub user_settings: UserSettings,
+    /// `user_settings.json` 热加载（setup() 中创建）
+    pub settings_watcher: Option<SettingsWatcher>,
     /// 持久化变量存储
     pub persistent_store: PersistentStore,
     /// 快照栈（Backspace 回退用）
@@ -68,6 +80,21 @@ pub struct AppStateInner {
     pub typewriter_timer: f32,
     /// 打字机基础速度（字符/秒）
     pub text_speed: f32,
+    /// 全局动画时间倍率（1.0 为原速；Skip 模式下至少为 `SKIP_ANIMATION_TIME_SCALE`）
+    pub animation_time_scale: f32,
+
+    /// 触摸与鼠标去重
+    pub touch_guard: TouchGuard,
+    /// 待提示给玩家的警告（由前端取走并以 Toast 显示）
+    pub pending_warnings: Vec<String>,
+    /// 已写入存档、等待补写缩略图的槽位（由前端下一帧抓取画面后补写）
+    pub pending_thumbnails: Vec<u32>,
+    /// 等待场景抓帧结束后再切换到的界面（存档界面会盖住场景层，须先抓帧）
+    pub deferred_screen: Option<HostScreen>,
+    /// 是否有待前端发起的场景抓帧
+    pub scene_capture_requested: bool,
+    /// 打开存档界面前抓取的场景缩略图（PNG），在存档界面保存时随存档写入
+    pub scene_thumbnail: Option<Vec<u8>>,
 
     // ── 子结构 ──
     pub anim: AnimationTimers,
@@ -92,12 +119,20 @@ impl AppStateInner {
             services: None,
             history: Vec::new(),
             user_settings: UserSettings::default(),
+            settings_watcher: None,
             persistent_store: PersistentStore::empty(),
             snapshot_stack: SnapshotStack::new(50),
             playback_mode: PlaybackMode::Normal,
             auto_timer: 0.0,
             typewriter_timer: 0.0,
             text_speed: 30.0,
+            animation_time_scale: 1.0,
+            touch_guard: TouchGuard::default(),
+            pending_warnings: Vec::new(),
+            pending_thumbnails: Vec::new(),
+            deferred_screen: None,
+            scene_capture_requested: false,
+            scene_thumbnail: None,
             anim: AnimationTimers::default(),
             session: SessionAuthority::default(),
         }
@@ -1,4 +1,4 @@
-use tracing::warn;
+use tracing::{info, warn};
 use vn_runtime::command::Position;
 use vn_runtime::state::WaitingReason;
 
@@ -7,6 +7,9 @@ use crate::render_state::RenderState;
 
 use super::*;
 
+/// 自动存档历史保留的份数，更旧的在每次自动存档后清理
+pub const AUTOSAVE_KEEP_COUNT: usize = 10;
+
 pub fn waiting_requires_snapshot_fallback(waiting: &WaitingFor) -> bool {
     matches!(
         waiting,
@@ -14,6 +17,7 @@ pub fn waiting_requires_snapshot_fallback(waiting: &WaitingFor) -> bool {
             | WaitingFor::Cutscene
             | WaitingFor::Signal(_)
             | WaitingFor::UIResult { .. }
+            | WaitingFor::Breakpoint
     )
 }
 
@@ -28,12 +32,16 @@ fn parse_saved_position(name: &str) -> Position {
         "FarLeft" => Position::FarLeft,
         "FarRight" => Position::FarRight,
         "FarMiddle" => Position::FarMiddle,
+        "Auto" => Position::Auto,
         _ => Position::Center,
     }
 }
 
 impl AppStateInner {
-    pub(super) fn build_save_data(&self, slot: u32) -> HostResult<vn_runtime::SaveData> {
+    pub(super) fn build_save_data(
+        &self,
+        slot: vn_runtime::SaveSlot,
+    ) -> HostResult<vn_runtime::SaveData> {
         let (runtime_state, runtime_history, render_state, current_bgm) =
             if waiting_requires_snapshot_fallback(&self.waiting) {
                 let snapshot = self.snapshot_stack.last().ok_or_else(|| {
@@ -68,57 +76,141 @@ impl AppStateInner {
                 )
             };
 
-        let mut save_data = vn_runtime::SaveData::new(slot, runtime_state, crate::now_secs())
+        let mut save_data = vn_runtime::SaveData::for_slot(slot, runtime_state, crate::now_secs())
             .with_history(runtime_history)
             .with_render(vn_runtime::RenderSnapshot {
                 background: render_state.current_background.clone(),
+                fill: render_state.background_fill,
+                background_layers: render_state.background_layers.clone(),
+                cg: render_state
+                    .cg
+                    .as_ref()
+                    .filter(|cg| !cg.hiding)
+                    .map(|cg| cg.path.clone()),
+                // 按入场顺序保存，读档后自动布局的左右顺序不变
                 characters: render_state
-                    .visible_characters
-                    .iter()
+                    .characters_in_show_order()
+                    .into_iter()
                     .map(|(alias, sprite)| vn_runtime::CharacterSnapshot {
                         alias: alias.clone(),
                         texture_path: sprite.texture_path.clone(),
                         position: format!("{:?}", sprite.position),
+                        idle: sprite.idle.map(|kind| kind.as_str().to_string()),
+                        rim_light: (sprite.rim_light > 0.0).then_some(sprite.rim_light as f64),
                     })
                     .collect(),
+                weather: render_state
+                    .weather
+                    .as_ref()
+                    .map(|w| vn_runtime::WeatherSnapshot {
+                        kind: w.kind,
+                        intensity: w.intensity as f64,
+                    }),
+                filter: render_state
+                    .screen_filter
+                    .as_ref()
+                    .map(|f| vn_runtime::FilterSnapshot {
+                        kind: f.kind,
+                        intensity: f.intensity as f64,
+                    }),
+                textbox_style: render_state.textbox_style.clone(),
             })
             .with_audio(vn_runtime::AudioState {
                 current_bgm,
                 bgm_looping: true,
+                bgm_volume: Some(self.services().audio.script_bgm_volume_target() as f64)
+                    .filter(|volume| *volume < 1.0),
             });
 
         if let Some(ref chapter) = render_state.chapter_mark {
             save_data = save_data.with_chapter(&chapter.title);
         }
+        // 快照回退时存档位置可能不在当前脚本，此时不估算
+        if let Some(runtime) = self.runtime.as_ref()
+            && runtime.state().position.script_id == save_data.runtime_state.position.script_id
+        {
+            let node_index = save_data.runtime_state.position.node_index;
+            let estimate = runtime.chapter_reading_time_at(node_index);
+            save_data = save_data.with_reading_time(estimate.seconds.round() as u64);
+        }
 
         Ok(save_data)
     }
 
+    /// 保存到槽位（快速存档等不经过存档界面的保存）
     pub fn save_to_slot(&mut self, slot: u32) -> HostResult<()> {
-        let save_data = self.build_save_data(slot)?;
+        self.save_to_slot_with_label(slot, None)
+    }
+
+    /// 保存到槽位并附带玩家备注（空白备注视为无备注）
+    ///
+    /// 存档数据立即写入。缩略图优先使用打开存档界面前抓好的场景画面；
+    /// 场景仍可见时（快速存档）不在此同步抓帧编码，而是登记到 `pending_thumbnails`，
+    /// 由前端下一帧抓取画面后经 [`Self::complete_thumbnail`] 补写。
+    pub fn save_to_slot_with_label(&mut self, slot: u32, label: Option<&str>) -> HostResult<()> {
+        let mut save_data = self.build_save_data(vn_runtime::SaveSlot::Slot(slot))?;
+        if let Some(label) = label.map(str::trim).filter(|l| !l.is_empty()) {
+            save_data = save_data.with_label(label);
+        }
         self.services().saves.save(&save_data)?;
+        if let Some(png) = &self.scene_thumbnail {
+            self.services().saves.save_thumbnail_png(slot, png)?;
+        } else if self.host_screen.shows_scene() {
+            self.request_thumbnail(slot);
+        } else {
+            // 场景不可见且没有预先抓好的画面：旧缩略图属于被覆盖的存档，直接移除
+            self.services().saves.delete_thumbnail(slot);
+        }
         Ok(())
     }
 
-    pub fn save_to_slot_with_thumbnail(
-        &mut self,
-        slot: u32,
-        thumbnail_png: &[u8],
-    ) -> HostResult<()> {
-        let save_data = self.build_save_data(slot)?;
-        self.services()
-            .saves
-            .save_thumbnail_png(slot, thumbnail_png)?;
-        self.services().saves.save(&save_data)?;
+    /// 登记待补写缩略图的槽位；旧缩略图属于被覆盖的存档，先删除
+    fn request_thumbnail(&mut self, slot: u32) {
+        self.services().saves.delete_thumbnail(slot);
+        if !self.pending_thumbnails.contains(&slot) {
+            self.pending_thumbnails.push(slot);
+        }
+    }
+
+    /// 取走待抓帧的槽位（前端每帧调用）
+    pub fn take_thumbnail_requests(&mut self) -> Vec<u32> {
+        std::mem::take(&mut self.pending_thumbnails)
+    }
+
+    /// 补写缩略图；抓帧期间槽位已被删除时丢弃
+    pub fn complete_thumbnail(&mut self, slot: u32, thumbnail_png: &[u8]) -> HostResult<()> {
+        let saves = &self.services().saves;
+        if !saves.slot_path(slot).exists() {
+            return Ok(());
+        }
+        saves.save_thumbnail_png(slot, thumbnail_png)?;
         Ok(())
     }
 
     pub fn save_continue(&mut self) -> HostResult<()> {
-        let save_data = self.build_save_data(0)?;
+        let save_data = self.build_save_data(vn_runtime::SaveSlot::Continue)?;
         self.services().saves.save_continue(&save_data)?;
         Ok(())
     }
 
+    /// 自动存档：覆盖 Continue 存档并追加一份自动存档历史（只保留最近
+    /// [`AUTOSAVE_KEEP_COUNT`] 份），失败只记录告警不打断游戏
+    pub fn save_auto(&mut self, trigger: vn_runtime::AutosaveTrigger) {
+        let result = self
+            .build_save_data(vn_runtime::SaveSlot::Continue)
+            .and_then(|save_data| {
+                let saves = &self.services().saves;
+                saves.save_continue(&save_data)?;
+                saves.save_autosave(&save_data)?;
+                saves.prune_autosaves(AUTOSAVE_KEEP_COUNT)?;
+                Ok(())
+            });
+        match result {
+            Ok(()) => info!(?trigger, "自动存档完成"),
+            Err(error) => warn!(%error, ?trigger, "自动存档失败"),
+        }
+    }
+
     pub fn delete_continue(&mut self) -> HostResult<()> {
         self.services().saves.delete_continue()?;
         Ok(())
@@ -128,8 +220,27 @@ impl AppStateInner {
         self.render_state = RenderState::new();
         if let Some(background) = &render.background {
             self.render_state.set_background(background.clone());
+        } else if let Some(fill) = render.fill {
+            self.render_state.set_fill(fill);
+        } else if !render.background_layers.is_empty() {
+            self.render_state
+                .set_background_layers(render.background_layers.clone());
         }
 
+        if let Some(cg) = &render.cg {
+            self.render_state.show_cg(cg.clone(), None);
+        }
+
+        if let Some(weather) = &render.weather {
+            self.render_state
+                .set_weather(weather.kind, weather.intensity as f32);
+        }
+        if let Some(filter) = &render.filter {
+            self.render_state
+                .set_filter(filter.kind, filter.intensity as f32);
+        }
+        self.render_state.textbox_style = render.textbox_style.clone();
+
         let manifest = self.services().manifest.clone();
         for character in &render.characters {
             self.render_state.show_character(
@@ -146,8 +257,14 @@ impl AppStateInner {
                 sprite.alpha = 1.0;
                 sprite.target_alpha = 1.0;
                 sprite.transition_duration = None;
+                sprite.idle = character
+                    .idle
+                    .as_deref()
+                    .and_then(crate::render_state::IdleKind::from_name);
+                sprite.rim_light = character.rim_light.unwrap_or(0.0) as f32;
             }
         }
+        self.render_state.apply_auto_layout();
     }
 
     pub(super) fn apply_audio_state(&mut self, audio: &vn_runtime::AudioState) {
@@ -156,6 +273,7 @@ impl AppStateInner {
             Some(path) => manager.play_bgm(path, audio.bgm_looping, None),
             None => manager.stop_bgm(None),
         }
+        manager.set_script_bgm_volume(audio.bgm_volume.unwrap_or(1.0) as f32, 0.0);
         self.sync_audio(0.0);
     }
 
@@ -197,9 +315,21 @@ impl AppStateInner {
 
         let mut runtime = self.build_runtime_from_resource(&path)?;
         load_call_stack_scripts(&mut runtime, &self.services().resources, &runtime_state);
-        runtime.restore_state(runtime_state.clone());
+        if let vn_runtime::RestoreOutcome::FellBack { label, reason } =
+            runtime.restore_state_with_fallback(runtime_state.clone())
+        {
+            warn!(error = %reason, ?label, "存档与脚本不兼容，回退到最近标签");
+            self.pending_warnings.push(match &label {
+                Some(label) => format!("存档与脚本不兼容，已回退到标签「{label}」"),
+                None => "存档与脚本不兼容，已回退到脚本开头".to_string(),
+            });
+        }
+        let runtime_state = runtime.state().clone();
         runtime.restore_history(history.clone());
         runtime.state_mut().persistent_variables = self.persistent_store.variables.clone();
+        runtime.import_endings(&self.persistent_store.endings);
+        runtime.import_seen_cgs(&self.persistent_store.seen_cgs);
+        runtime.import_chosen_branches(&self.persistent_store.chosen_branches);
 
         self.reset_session();
         self.runtime = Some(runtime);
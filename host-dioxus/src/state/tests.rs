@@ -1,5 +1,5 @@
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use vn_runtime::command::Command;
 use vn_runtime::state::WaitingReason;
@@ -92,6 +92,108 @@ fn return_to_title_with_save_continue_writes_continue_file() {
     std::fs::remove_dir_all(root).ok();
 }
 
+#[test]
+fn passing_chapter_mark_writes_autosave_but_plain_dialogue_does_not() {
+    let (mut inner, root) = make_state_with_services("scripts/plain.md", "：\"开场白\"\n");
+    inner.init_game_from_resource("scripts/plain.md").unwrap();
+    assert!(!inner.services().saves.has_continue());
+    std::fs::remove_dir_all(root).ok();
+
+    let script = "# 第一章\n\n：\"章节正文\"\n";
+    let (mut inner, root) = make_state_with_services("scripts/chapter.md", script);
+    inner.init_game_from_resource("scripts/chapter.md").unwrap();
+    assert!(inner.services().saves.has_continue());
+    assert_eq!(inner.services().saves.list_autosaves(), vec![1]);
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn save_to_slot_with_label_persists_trimmed_label() {
+    let script = "changeBG <img src=\"../backgrounds/entry.png\" />\n";
+    let (mut inner, root) = make_state_with_services("scripts/scene.md", script);
+
+    inner.init_game_from_resource("scripts/scene.md").unwrap();
+    inner
+        .save_to_slot_with_label(1, Some("  第一章结尾  "))
+        .unwrap();
+    inner.save_to_slot_with_label(2, Some("   ")).unwrap();
+
+    let saves = &inner.services().saves;
+    assert_eq!(
+        saves.load(1).unwrap().metadata.label.as_deref(),
+        Some("第一章结尾")
+    );
+    assert_eq!(saves.load(2).unwrap().metadata.label, None);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn save_writes_data_first_and_thumbnail_is_filled_in_later() {
+    let script = "changeBG <img src=\"../backgrounds/entry.png\" />\n";
+    let (mut inner, root) = make_state_with_services("scripts/scene.md", script);
+
+    inner.init_game_from_resource("scripts/scene.md").unwrap();
+    inner
+        .services()
+        .saves
+        .save_thumbnail_png(3, b"stale")
+        .unwrap();
+    // 快速存档：场景仍可见，缩略图由前端下一帧抓取后补写
+    inner.save_to_slot(3).unwrap();
+
+    // 补写前：存档可正常读取，被覆盖存档的旧缩略图已移除
+    let saves = &inner.services().saves;
+    assert!(saves.load(3).is_ok());
+    assert!(saves.load_thumbnail_base64(3).is_none());
+    assert_eq!(inner.take_thumbnail_requests(), [3]);
+    assert!(inner.take_thumbnail_requests().is_empty());
+
+    // 抽象抓帧：前端抓到的 PNG 字节直接补写
+    inner.complete_thumbnail(3, b"\x89PNG").unwrap();
+    assert!(inner.services().saves.load_thumbnail_base64(3).is_some());
+
+    // 抓帧期间槽位被删除：丢弃结果，不留下孤立缩略图
+    inner.services().saves.delete(3).unwrap();
+    inner.complete_thumbnail(3, b"\x89PNG").unwrap();
+    assert!(!inner.services().saves.thumbnail_path(3).exists());
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn opening_save_screen_captures_scene_before_switching() {
+    let script = "changeBG <img src=\"../backgrounds/entry.png\" />\n";
+    let (mut inner, root) = make_state_with_services("scripts/scene.md", script);
+    inner.frontend_connected(None);
+    inner.init_game_from_resource("scripts/scene.md").unwrap();
+    inner.set_host_screen(HostScreen::InGame);
+
+    // 抓帧完成前仍停留在场景界面
+    inner.set_host_screen(HostScreen::Save);
+    assert_eq!(inner.host_screen, HostScreen::InGame);
+    assert!(inner.take_scene_capture_request());
+    assert!(!inner.take_scene_capture_request());
+
+    inner.finish_scene_capture(Some(b"\x89PNG".to_vec()));
+    assert_eq!(inner.host_screen, HostScreen::Save);
+
+    // 在存档界面保存：直接写入预先抓好的缩略图，无需再抓帧
+    inner.save_to_slot_with_label(4, None).unwrap();
+    assert!(inner.services().saves.load_thumbnail_base64(4).is_some());
+    assert!(inner.take_thumbnail_requests().is_empty());
+
+    // 回到场景后旧画面作废；抓帧期间切走的界面切换被取消
+    inner.set_host_screen(HostScreen::InGame);
+    assert!(inner.scene_thumbnail.is_none());
+    inner.set_host_screen(HostScreen::Save);
+    inner.set_host_screen(HostScreen::History);
+    inner.finish_scene_capture(None);
+    assert_eq!(inner.host_screen, HostScreen::History);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
 #[test]
 fn restore_from_save_keeps_saved_render_snapshot_without_entry_tick() {
     let script = "changeBG <img src=\"../backgrounds/entry.png\" />\n";
@@ -103,7 +205,13 @@ fn restore_from_save_keeps_saved_render_snapshot_without_entry_tick() {
     let save_data = vn_runtime::SaveData::new(1, runtime_state, 0)
         .with_render(vn_runtime::RenderSnapshot {
             background: Some("backgrounds/saved.png".to_string()),
+            fill: None,
+            background_layers: Vec::new(),
+            cg: None,
             characters: Vec::new(),
+            weather: None,
+            filter: None,
+            textbox_style: Some("memory".to_string()),
         })
         .with_history(vn_runtime::History::new());
 
@@ -113,6 +221,7 @@ fn restore_from_save_keeps_saved_render_snapshot_without_entry_tick() {
         inner.render_state.current_background.as_deref(),
         Some("backgrounds/saved.png")
     );
+    assert_eq!(inner.render_state.textbox_style.as_deref(), Some("memory"));
     assert_eq!(inner.host_screen, HostScreen::InGame);
 
     std::fs::remove_dir_all(root).ok();
@@ -142,7 +251,9 @@ fn build_save_data_uses_snapshot_boundary_while_waiting_for_choice() {
     assert!(inner.render_state.choices.is_some());
     assert!(inner.snapshot_stack.last().is_some());
 
-    let save_data = inner.build_save_data(1).unwrap();
+    let save_data = inner
+        .build_save_data(vn_runtime::SaveSlot::Slot(1))
+        .unwrap();
     assert!(matches!(
         save_data.runtime_state.waiting,
         WaitingReason::WaitForClick
@@ -179,7 +290,9 @@ endif
     assert!(inner.render_state.active_ui_mode.is_some());
     assert!(inner.snapshot_stack.last().is_some());
 
-    let save_data = inner.build_save_data(1).unwrap();
+    let save_data = inner
+        .build_save_data(vn_runtime::SaveSlot::Slot(1))
+        .unwrap();
     assert!(matches!(
         save_data.runtime_state.waiting,
         WaitingReason::WaitForClick
@@ -200,7 +313,13 @@ fn restore_from_save_normalizes_legacy_ui_wait_to_click() {
     let save_data = vn_runtime::SaveData::new(1, runtime_state, 0)
         .with_render(vn_runtime::RenderSnapshot {
             background: Some("backgrounds/saved.png".to_string()),
+            fill: None,
+            background_layers: Vec::new(),
+            cg: None,
             characters: Vec::new(),
+            weather: None,
+            filter: None,
+            textbox_style: None,
         })
         .with_history(vn_runtime::History::new());
 
@@ -344,3 +463,428 @@ fn script_end_returns_to_title_screen() {
 
     std::fs::remove_dir_all(root).ok();
 }
+
+#[test]
+fn click_during_transition_only_skips_transition() {
+    let script = r#"
+changeBG <img src="../backgrounds/a.png" /> with Dissolve(2)
+："第一句。"
+："第二句。"
+"#;
+    let (mut inner, root) = make_state_with_services("scripts/skip.md", script);
+
+    inner.init_game_from_resource("scripts/skip.md").unwrap();
+    inner.render_state.complete_typewriter();
+    assert!(inner.is_transition_active());
+    assert_eq!(inner.waiting, WaitingFor::Click);
+
+    // 过渡中点击：只完成过渡，不推进脚本
+    inner.process_click();
+    inner.process_tick(0.0);
+    assert!(!inner.is_transition_active());
+    assert!(inner.render_state.background_transition.is_none());
+    assert_eq!(inner.waiting, WaitingFor::Click);
+    assert_eq!(
+        inner.render_state.dialogue.as_ref().unwrap().content,
+        "第一句。"
+    );
+
+    // 过渡结束后点击才推进
+    inner.process_click();
+    inner.process_tick(0.0);
+    assert_eq!(
+        inner.render_state.dialogue.as_ref().unwrap().content,
+        "第二句。"
+    );
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn crossfading_characters_animate_independently_and_remove_only_at_zero_alpha() {
+    let script = r#"
+show <img src="../characters/a.png" /> as old at center
+："旧角色在场。"
+hide old with Dissolve(1)
+show <img src="../characters/b.png" /> as new at center with Dissolve(1)
+："新角色登场。"
+"#;
+    let (mut inner, root) = make_state_with_services("scripts/crossfade.md", script);
+
+    inner
+        .init_game_from_resource("scripts/crossfade.md")
+        .unwrap();
+    inner.render_state.complete_typewriter();
+    inner.process_click();
+    inner.process_tick(0.0);
+
+    let chars = &inner.render_state.visible_characters;
+    assert!(chars["old"].fading_out);
+    assert_eq!(chars["old"].alpha, 1.0);
+    assert_eq!(chars["new"].alpha, 0.0);
+
+    // 交叉过程中两者各自推进，旧立绘仍在且垫在新立绘下面
+    inner.process_tick(0.5);
+    let chars = &inner.render_state.visible_characters;
+    assert!((chars["old"].alpha - 0.5).abs() < 1e-4);
+    assert!((chars["new"].alpha - 0.5).abs() < 1e-4);
+    let order: Vec<_> = crate::render_state::characters_in_draw_order(chars)
+        .into_iter()
+        .map(|(alias, _)| alias.as_str())
+        .collect();
+    assert_eq!(order, ["old", "new"]);
+
+    // 旧立绘 alpha 到 0 才移除
+    inner.process_tick(0.49);
+    assert!(inner.render_state.visible_characters.contains_key("old"));
+    inner.process_tick(0.02);
+    let chars = &inner.render_state.visible_characters;
+    assert!(!chars.contains_key("old"));
+    assert_eq!(chars["new"].alpha, 1.0);
+    assert!(!chars["new"].fading_out);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn animation_time_scale_speeds_up_and_skip_completes_transitions_in_one_frame() {
+    let script = r#"
+show <img src="../characters/a.png" /> as a at center with Dissolve(1)
+："登场。"
+"#;
+    let start = || {
+        let (mut inner, root) = make_state_with_services("scripts/time_scale.md", script);
+        inner
+            .init_game_from_resource("scripts/time_scale.md")
+            .unwrap();
+        inner.process_tick(0.0);
+        assert_eq!(inner.render_state.visible_characters["a"].alpha, 0.0);
+        (inner, root)
+    };
+
+    // 4 倍速：1 秒的淡入在 0.25 秒内完成
+    let (mut inner, root) = start();
+    inner.animation_time_scale = 4.0;
+    inner.process_tick(0.2);
+    assert!((inner.render_state.visible_characters["a"].alpha - 0.8).abs() < 1e-4);
+    inner.process_tick(0.05);
+    assert_eq!(inner.render_state.visible_characters["a"].alpha, 1.0);
+    std::fs::remove_dir_all(root).ok();
+
+    // 倍率极大时等价于瞬间完成；Skip 模式自动采用该倍率
+    let (mut inner, root) = start();
+    inner.playback_mode = PlaybackMode::Skip;
+    assert_eq!(
+        inner.effective_animation_time_scale(),
+        SKIP_ANIMATION_TIME_SCALE
+    );
+    inner.process_tick(1.0 / 60.0);
+    assert_eq!(inner.render_state.visible_characters["a"].alpha, 1.0);
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn scene_effect_pan_moves_camera_and_releases_wait() {
+    let script = r#"
+sceneEffect pan(x: 100, y: 20, duration: 1)
+："平移之后。"
+sceneEffect panReset
+"#;
+    let (mut inner, root) = make_state_with_services("scripts/pan.md", script);
+    inner.init_game_from_resource("scripts/pan.md").unwrap();
+    assert_eq!(inner.waiting, WaitingFor::Signal(SignalKind::SceneEffect));
+
+    // 缓入缓出：时间过半时恰好走到一半
+    inner.process_tick(0.5);
+    let (x, y) = inner.render_state.camera_pan;
+    assert!(
+        (x - 50.0).abs() < 1e-3 && (y - 10.0).abs() < 1e-3,
+        "{x} {y}"
+    );
+
+    inner.process_tick(0.6);
+    inner.process_tick(0.0);
+    assert_eq!(inner.render_state.camera_pan, (100.0, 20.0));
+    assert_eq!(inner.waiting, WaitingFor::Click);
+
+    // 不带 duration 时立即复位
+    inner.render_state.complete_typewriter();
+    inner.process_click();
+    inner.process_tick(0.0);
+    assert_eq!(inner.render_state.camera_pan, (0.0, 0.0));
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn click_during_scene_transition_completes_mask_before_advancing() {
+    let script = r#"
+changeScene <img src="../backgrounds/b.png" /> with Fade(duration: 1)
+："转场之后。"
+"#;
+    let (mut inner, root) = make_state_with_services("scripts/scene_skip.md", script);
+
+    inner
+        .init_game_from_resource("scripts/scene_skip.md")
+        .unwrap();
+    assert!(inner.is_transition_active());
+    assert_eq!(
+        inner.waiting,
+        WaitingFor::Signal(SignalKind::SceneTransition)
+    );
+
+    inner.process_click();
+    assert!(!inner.is_transition_active());
+    assert!(inner.render_state.current_background.is_some());
+    assert!(inner.render_state.dialogue.is_none());
+
+    // 下一帧按完成状态解除 Signal 等待，脚本自然继续
+    inner.process_tick(0.0);
+    assert_eq!(inner.waiting, WaitingFor::Click);
+    assert_eq!(
+        inner.render_state.dialogue.as_ref().unwrap().content,
+        "转场之后。"
+    );
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn scene_transition_emits_started_midpoint_completed_in_order() {
+    use crate::render_state::TransitionEvent;
+
+    let script = r#"
+changeScene <img src="../backgrounds/b.png" /> with Fade(duration: 1)
+："转场之后。"
+"#;
+    let (mut inner, root) = make_state_with_services("scripts/scene_events.md", script);
+    inner
+        .init_game_from_resource("scripts/scene_events.md")
+        .unwrap();
+    assert_eq!(inner.anim.transition_events, [TransitionEvent::Started]);
+
+    // 遮罩淡入完成：产出 Midpoint 并切换背景
+    inner.update_scene_transition(1.0);
+    assert!(inner.render_state.current_background.is_some());
+    // 停留 + 淡出
+    inner.update_scene_transition(0.2);
+    inner.update_scene_transition(1.0);
+    assert_eq!(
+        inner.anim.transition_events,
+        [
+            TransitionEvent::Started,
+            TransitionEvent::Midpoint,
+            TransitionEvent::Completed,
+        ]
+    );
+
+    // 主循环消费事件后解除等待，脚本继续
+    inner.process_tick(0.0);
+    assert!(inner.anim.transition_events.is_empty());
+    assert_eq!(inner.waiting, WaitingFor::Click);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn timeline_runs_commands_at_their_offsets_and_click_flushes_the_rest() {
+    let script = r#"
+timeline
+  0 show <img src="../characters/alice.png" /> as alice at left
+  0.5 show <img src="../characters/bob.png" /> as bob at right
+  1 changeBG <img src="../backgrounds/b.png" />
+endTimeline
+："编排之后。"
+"#;
+    let (mut inner, root) = make_state_with_services("scripts/timeline.md", script);
+    inner
+        .init_game_from_resource("scripts/timeline.md")
+        .unwrap();
+
+    // 偏移为 0 的动作随批次立即执行，对白不等待 timeline
+    let visible = |inner: &AppStateInner| {
+        let mut aliases: Vec<String> = inner
+            .render_state
+            .visible_characters
+            .keys()
+            .cloned()
+            .collect();
+        aliases.sort();
+        aliases
+    };
+    assert_eq!(visible(&inner), ["alice"]);
+    assert_eq!(inner.waiting, WaitingFor::Click);
+    assert!(inner.render_state.dialogue.is_some());
+
+    inner.process_tick(0.6);
+    assert_eq!(visible(&inner), ["alice", "bob"]);
+    assert!(inner.render_state.current_background.is_none());
+
+    // 点击先完成剩余动作，不推进脚本
+    inner.process_click();
+    assert!(inner.anim.timeline.is_empty());
+    assert!(inner.render_state.current_background.is_some());
+    assert_eq!(inner.waiting, WaitingFor::Click);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn apply_volume_settings_chains_master_into_bgm() {
+    let (mut inner, root) = make_state_with_services("scripts/volume.md", "");
+
+    inner.user_settings.master_volume = 50.0;
+    inner.user_settings.bgm_volume = 80.0;
+    inner.apply_volume_settings();
+    inner
+        .services_mut()
+        .audio
+        .play_bgm("audio/theme.ogg", true, None);
+    inner.sync_audio(0.0);
+
+    let volume = inner.render_state.audio.bgm.as_ref().unwrap().volume;
+    assert!((volume - 0.5 * 0.8).abs() < 1e-6);
+
+    // 静音时有效音量为 0，取消静音后恢复原设置
+    inner.user_settings.muted = true;
+    inner.apply_volume_settings();
+    inner.sync_audio(0.0);
+    assert_eq!(inner.render_state.audio.bgm.as_ref().unwrap().volume, 0.0);
+    assert_eq!(inner.services().audio.effective_sfx_volume(), 0.0);
+
+    inner.user_settings.muted = false;
+    inner.apply_volume_settings();
+    inner.sync_audio(0.0);
+    let volume = inner.render_state.audio.bgm.as_ref().unwrap().volume;
+    assert!((volume - 0.5 * 0.8).abs() < 1e-6);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn touch_on_choice_selects_it_and_suppresses_synthetic_click() {
+    use crate::touch::{TouchPoint, TouchRect, TouchStroke};
+
+    let script = r#"
+："选择前。"
+| 选择 |        |
+| ---- | ------ |
+| 选项A | label_a |
+| 选项B | label_b |
+**label_a**
+："选了A。"
+**label_b**
+："选了B。"
+"#;
+    let (mut inner, root) = make_state_with_services("scripts/touch.md", script);
+
+    inner.init_game_from_resource("scripts/touch.md").unwrap();
+    inner.render_state.complete_typewriter();
+    inner.process_click();
+    inner.process_tick(0.0);
+    assert_eq!(inner.waiting, WaitingFor::Choice);
+
+    let rect = |y: f32| TouchRect {
+        x: 0.0,
+        y,
+        width: 200.0,
+        height: 40.0,
+    };
+    let point = TouchPoint { x: 50.0, y: 70.0 };
+    let stroke = TouchStroke {
+        start: point,
+        end: point,
+        choice_rects: vec![rect(0.0), rect(50.0)],
+    };
+    inner.process_touch(&stroke, 1_000.0);
+    inner.render_state.complete_typewriter();
+    assert_eq!(
+        inner.render_state.dialogue.as_ref().unwrap().content,
+        "选了B。"
+    );
+
+    // 紧随触摸的合成鼠标点击不会再推进
+    let waiting = inner.waiting.clone();
+    inner.process_mouse_click(1_050.0);
+    assert_eq!(inner.waiting, waiting);
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+/// 写入设置文件并把修改时间推后 `secs` 秒，避免文件系统时间精度导致变更不可见
+fn write_settings_file(path: &Path, content: &str, secs: u64) {
+    std::fs::write(path, content).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() + Duration::from_secs(secs))
+        .unwrap();
+}
+
+#[test]
+fn window_size_saved_on_exit_is_restored_on_next_launch() {
+    let (mut inner, root) = make_state_with_services("scripts/scene.md", "旁白：\"hi\"\n");
+    let path = root.join("saves").join(USER_SETTINGS_FILE);
+    inner.settings_watcher = Some(SettingsWatcher::new(&path));
+
+    // 不可调整大小时不记录
+    inner.record_window_size(1600, 900);
+    assert_eq!(inner.user_settings.window_size, None);
+
+    inner.services_mut().config.window.resizable = true;
+    inner.record_window_size(1600, 900);
+    inner.record_window_size(0, 0); // 最小化
+    inner.user_settings.text_speed = 70.0;
+    inner.save_user_settings().unwrap();
+    // 自身写入不被热加载当作外部改动
+    assert!(!inner.reload_user_settings_if_changed());
+
+    let loaded = UserSettings::load(&path).unwrap();
+    assert_eq!(loaded.window_size, Some([1600, 900]));
+    assert_eq!(loaded.text_speed, 70.0);
+    let window = &inner.services().config.window;
+    assert_eq!(window.initial_size(loaded.window_size), (1600, 900));
+
+    // 关闭可调整大小后回到默认尺寸
+    let fixed = crate::config::WindowConfig {
+        resizable: false,
+        ..window.clone()
+    };
+    assert_eq!(
+        fixed.initial_size(loaded.window_size),
+        crate::config::DEFAULT_WINDOW_SIZE
+    );
+
+    std::fs::remove_dir_all(root).ok();
+}
+
+#[test]
+fn user_settings_hot_reload_applies_changes_and_keeps_old_on_parse_error() {
+    let (mut inner, root) = make_state_with_services("scripts/scene.md", "旁白：\"hi\"\n");
+    let path = root.join("saves").join(USER_SETTINGS_FILE);
+    let mut settings = UserSettings::default();
+    std::fs::write(&path, serde_json::to_string(&settings).unwrap()).unwrap();
+    inner.settings_watcher = Some(SettingsWatcher::new(&path));
+    assert!(!inner.reload_user_settings_if_changed());
+
+    // 文件变更后新值被应用（文字速度、音量）
+    settings.text_speed = 80.0;
+    settings.bgm_volume = 50.0;
+    write_settings_file(&path, &serde_json::to_string(&settings).unwrap(), 10);
+    assert!(inner.reload_user_settings_if_changed());
+    assert_eq!(inner.user_settings.text_speed, 80.0);
+    assert_eq!(inner.text_speed, 80.0);
+    assert!((inner.services().audio.effective_bgm_volume() - 0.5).abs() < 1e-6);
+    assert!(!inner.reload_user_settings_if_changed());
+
+    // 解析失败保留旧值，只提示一次
+    write_settings_file(&path, "{ broken", 20);
+    assert!(!inner.reload_user_settings_if_changed());
+    assert_eq!(inner.user_settings.text_speed, 80.0);
+    assert_eq!(inner.text_speed, 80.0);
+    assert_eq!(inner.pending_warnings.len(), 1);
+    assert!(!inner.reload_user_settings_if_changed());
+    assert_eq!(inner.pending_warnings.len(), 1);
+
+    std::fs::remove_dir_all(root).ok();
+}
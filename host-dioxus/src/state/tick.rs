@@ -1,17 +1,26 @@
-use crate::render_state::{PlaybackMode, SceneTransitionPhaseState};
+use vn_runtime::command::Command;
+
+use crate::render_state::{PlaybackMode, SceneTransitionPhaseState, TransitionEvent};
 
 use super::*;
 
+/// Skip 模式下的动画时间倍率：足够大，使任何过渡在一帧内到达终态
+pub const SKIP_ANIMATION_TIME_SCALE: f32 = 1000.0;
+
 impl AppStateInner {
     /// 每帧调用，推进打字机和计时器
     pub fn process_tick(&mut self, dt: f32) {
+        if self.settings_watcher.as_mut().is_some_and(|w| w.due(dt)) {
+            self.reload_user_settings_if_changed();
+        }
+
         if !self.host_screen.allows_progression() {
             self.project_render_state();
             return;
         }
 
         self.advance_playback_mode(dt);
-        self.update_animations(dt);
+        self.update_animations(dt * self.effective_animation_time_scale());
         self.resolve_waits(dt);
 
         if self.waiting == WaitingFor::Nothing && !self.script_finished {
@@ -23,6 +32,15 @@ impl AppStateInner {
         self.project_render_state();
     }
 
+    /// 本帧实际使用的动画时间倍率
+    pub fn effective_animation_time_scale(&self) -> f32 {
+        if self.playback_mode == PlaybackMode::Skip {
+            self.animation_time_scale.max(SKIP_ANIMATION_TIME_SCALE)
+        } else {
+            self.animation_time_scale
+        }
+    }
+
     /// Skip 模式立即推进 + Auto 模式计时推进
     ///
     /// Skip 采用两帧策略（与旧 host 一致）：
@@ -73,19 +91,16 @@ impl AppStateInner {
 
     pub(super) fn complete_signal_wait(&mut self, signal_kind: SignalKind) {
         match signal_kind {
-            SignalKind::SceneTransition => {
-                if let Some(st) = self.render_state.scene_transition.as_mut() {
-                    if let Some(bg) = st.pending_background.take() {
-                        self.render_state.current_background = Some(bg);
-                    }
-                    st.phase = SceneTransitionPhaseState::Completed;
-                }
-            }
+            SignalKind::SceneTransition => self.finish_scene_transition(),
             SignalKind::TitleCard => {
                 self.render_state.title_card = None;
             }
             SignalKind::SceneEffect => {
                 self.anim.scene_effect_active = false;
+                // 提前结束的镜头平移直接落到目标位置
+                if let Some(pan) = self.anim.active_pan.take() {
+                    self.render_state.camera_pan = pan.to;
+                }
             }
             SignalKind::Cutscene => {
                 self.finish_cutscene();
@@ -95,7 +110,45 @@ impl AppStateInner {
         self.clear_wait();
     }
 
-    /// 推进 chapter_mark / title_card / background_transition / scene_transition / 角色 alpha
+    /// 推进 timeline，执行已到时的命令
+    pub(super) fn update_timeline(&mut self, dt: f32) {
+        if self.anim.timeline.is_empty() {
+            return;
+        }
+        self.anim.timeline_elapsed += dt;
+        let elapsed = self.anim.timeline_elapsed;
+        let due = self
+            .anim
+            .timeline
+            .iter()
+            .take_while(|step| step.delay <= elapsed)
+            .count();
+        if due == 0 {
+            return;
+        }
+        let commands: Vec<Command> = self
+            .anim
+            .timeline
+            .drain(..due)
+            .map(|step| step.command)
+            .collect();
+        self.run_timeline_commands(&commands);
+    }
+
+    /// 立即执行 timeline 中剩余的全部命令
+    pub(super) fn flush_timeline(&mut self) {
+        self.anim.timeline_elapsed = 0.0;
+        if self.anim.timeline.is_empty() {
+            return;
+        }
+        let commands: Vec<Command> = std::mem::take(&mut self.anim.timeline)
+            .into_iter()
+            .map(|step| step.command)
+            .collect();
+        self.run_timeline_commands(&commands);
+    }
+
+    /// 推进 timeline / chapter_mark / title_card / background_transition / scene_transition / CG / 角色 alpha / 天气粒子 / 选项激活
     pub(super) fn update_animations(&mut self, dt: f32) {
         self.render_state.update_chapter_mark(dt);
 
@@ -106,15 +159,28 @@ impl AppStateInner {
             }
         }
 
+        self.update_timeline(dt);
         self.update_background_transition(dt);
         self.update_scene_transition(dt);
+        self.render_state.update_cg(dt);
         self.update_character_alpha(dt);
         self.update_shake(dt);
+        self.update_camera_pan(dt);
+        if let Some(weather) = self.render_state.weather.as_mut() {
+            weather.update(dt);
+        }
+        self.render_state.update_choices(dt);
     }
 
-    /// 推进角色 alpha 过渡，淡出完成后移除
+    /// 推进角色 alpha 过渡、表情交叉淡化与弹跳，淡出完成后移除
     pub(super) fn update_character_alpha(&mut self, dt: f32) {
         for c in self.render_state.visible_characters.values_mut() {
+            if c.crossfade.as_mut().is_some_and(|cf| cf.advance(dt)) {
+                c.crossfade = None;
+            }
+            if c.bounce.as_mut().is_some_and(|b| b.advance(dt)) {
+                c.bounce = None;
+            }
             let duration = c.transition_duration.unwrap_or(0.0);
             if duration > 0.0 && (c.alpha - c.target_alpha).abs() > f32::EPSILON {
                 let speed = dt / duration;
@@ -137,14 +203,16 @@ impl AppStateInner {
     }
 
     /// 解析 Signal 等待 + Time 等待
+    ///
+    /// 同时消费本帧之前累积的过渡事件：场景过渡以 `Completed` 事件解除等待。
     pub(super) fn resolve_waits(&mut self, dt: f32) {
+        let transition_events = std::mem::take(&mut self.anim.transition_events);
         if let WaitingFor::Signal(signal_kind) = self.waiting {
             let resolved = match signal_kind {
-                SignalKind::SceneTransition => self
-                    .render_state
-                    .scene_transition
-                    .as_ref()
-                    .is_none_or(|st| st.phase == SceneTransitionPhaseState::Completed),
+                SignalKind::SceneTransition => {
+                    transition_events.contains(&TransitionEvent::Completed)
+                        || self.render_state.scene_transition.is_none()
+                }
                 SignalKind::TitleCard => self.render_state.title_card.is_none(),
                 SignalKind::SceneEffect => !self.anim.scene_effect_active,
                 SignalKind::Cutscene => self.render_state.cutscene.is_none(),
@@ -167,8 +235,9 @@ impl AppStateInner {
         }
     }
 
-    /// 推进打字机 + inline wait
+    /// 推进打字机 + 逐字淡入 + inline wait
     pub(super) fn advance_typewriter(&mut self, dt: f32) {
+        self.render_state.advance_char_fade(dt);
         if !self.render_state.is_dialogue_complete() && !self.render_state.has_inline_wait() {
             let speed = self.render_state.effective_text_speed(self.text_speed);
             self.typewriter_timer += dt * speed;
@@ -222,42 +291,102 @@ impl AppStateInner {
     }
 
     /// 推进场景遮罩过渡（内部计时器推进 phase，不计算渐变值）
+    ///
+    /// 阶段切换产生的事件进入 `anim.transition_events`，由 `resolve_waits` 消费。
     pub(super) fn update_scene_transition(&mut self, dt: f32) {
-        const HOLD_DURATION: f32 = 0.2;
-
         let Some(st) = self.render_state.scene_transition.as_mut() else {
             return;
         };
 
+        if st.phase == SceneTransitionPhaseState::Completed {
+            self.render_state.scene_transition = None;
+            self.anim.scene_transition_elapsed = 0.0;
+            return;
+        }
+
         self.anim.scene_transition_elapsed += dt;
+        let (phase_changed, event) = st.advance(self.anim.scene_transition_elapsed);
+        if phase_changed {
+            self.anim.scene_transition_elapsed = 0.0;
+        }
+        if let Some(event) = event {
+            self.on_scene_transition_event(event);
+        }
+    }
 
-        match st.phase {
-            SceneTransitionPhaseState::FadeIn => {
-                if self.anim.scene_transition_elapsed >= st.duration {
-                    if let Some(bg) = st.pending_background.take() {
-                        self.render_state.current_background = Some(bg);
-                    }
-                    st.phase = SceneTransitionPhaseState::Hold;
-                    self.anim.scene_transition_elapsed = 0.0;
-                }
-            }
-            SceneTransitionPhaseState::Hold => {
-                if self.anim.scene_transition_elapsed >= HOLD_DURATION {
-                    st.phase = SceneTransitionPhaseState::FadeOut;
-                    self.anim.scene_transition_elapsed = 0.0;
-                }
-            }
-            SceneTransitionPhaseState::FadeOut => {
-                if self.anim.scene_transition_elapsed >= st.duration {
-                    st.phase = SceneTransitionPhaseState::Completed;
-                    self.anim.scene_transition_elapsed = 0.0;
-                }
-            }
-            SceneTransitionPhaseState::Completed => {
-                self.render_state.scene_transition = None;
-                self.anim.scene_transition_elapsed = 0.0;
+    /// 立即完成场景过渡，补发尚未产出的事件
+    pub(super) fn finish_scene_transition(&mut self) {
+        let Some(st) = self.render_state.scene_transition.as_mut() else {
+            return;
+        };
+        for event in st.finish() {
+            self.on_scene_transition_event(event);
+        }
+        self.anim.scene_transition_elapsed = 0.0;
+    }
+
+    /// 记录过渡事件；`Midpoint` 时遮罩已完全覆盖，切换到新背景
+    fn on_scene_transition_event(&mut self, event: TransitionEvent) {
+        if event == TransitionEvent::Midpoint
+            && let Some(bg) = self
+                .render_state
+                .scene_transition
+                .as_mut()
+                .and_then(|st| st.pending_background.take())
+        {
+            self.render_state.set_background(bg);
+        }
+        self.anim.transition_events.push(event);
+    }
+
+    /// 是否存在进行中的过渡（背景 dissolve / 场景遮罩 / CG / 角色淡入淡出 / 表情交叉淡化 / timeline）
+    pub fn is_transition_active(&self) -> bool {
+        let rs = &self.render_state;
+        !self.anim.timeline.is_empty()
+            || rs.background_transition.is_some()
+            || rs.is_cg_transitioning()
+            || rs
+                .scene_transition
+                .as_ref()
+                .is_some_and(|st| st.phase != SceneTransitionPhaseState::Completed)
+            || rs.visible_characters.values().any(|c| {
+                c.crossfade.is_some()
+                    || (c.transition_duration.is_some()
+                        && (c.alpha - c.target_alpha).abs() > f32::EPSILON)
+            })
+    }
+
+    /// 立即完成所有进行中的过渡，返回是否跳过了任何过渡
+    ///
+    /// 只把过渡推到终态，不解除脚本等待；场景过渡的 Signal 等待
+    /// 由下一帧 `resolve_waits` 按完成状态自然解除。
+    pub fn skip_active_transitions(&mut self) -> bool {
+        if !self.is_transition_active() {
+            return false;
+        }
+
+        // 先执行 timeline 剩余动作，其产生的过渡随后一并完成
+        self.flush_timeline();
+
+        if self.render_state.background_transition.take().is_some() {
+            self.anim.bg_transition_elapsed = 0.0;
+        }
+        self.render_state.finish_cg_transition();
+
+        self.finish_scene_transition();
+
+        for c in self.render_state.visible_characters.values_mut() {
+            if c.transition_duration.take().is_some() {
+                c.alpha = c.target_alpha;
             }
+            c.crossfade = None;
+            c.bounce = None;
         }
+        self.render_state
+            .visible_characters
+            .retain(|_, c| !(c.fading_out && c.alpha <= f32::EPSILON));
+
+        true
     }
 
     /// 推进 shake 动画
@@ -280,4 +409,22 @@ impl AppStateInner {
             self.render_state.scene_effect.shake_offset_y = shake.amplitude_y * decay * phase.cos();
         }
     }
+
+    /// 推进镜头平移动画（缓入缓出）
+    pub(super) fn update_camera_pan(&mut self, dt: f32) {
+        let Some(pan) = self.anim.active_pan.as_mut() else {
+            return;
+        };
+        pan.elapsed += dt;
+        if pan.elapsed >= pan.duration {
+            self.render_state.camera_pan = pan.to;
+            self.anim.active_pan = None;
+            self.anim.scene_effect_active = false;
+        } else {
+            let t = pan.elapsed / pan.duration;
+            let eased = t * t * (3.0 - 2.0 * t);
+            let lerp = |a: f32, b: f32| a + (b - a) * eased;
+            self.render_state.camera_pan = (lerp(pan.from.0, pan.to.0), lerp(pan.from.1, pan.to.1));
+        }
+    }
 }
@@ -1,21 +1,25 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
-use vn_runtime::state::VarValue;
+use vn_runtime::state::{EndingCollection, VarValue, branch_script_id};
 
 use crate::audio::AudioManager;
 use crate::config::AppConfig;
 use crate::error::{HostError, HostResult};
-use crate::render_state::RenderState;
+use crate::render_state::{RenderState, TextRevealMode};
 use crate::resources::ResourceManager;
 use crate::save_manager::SaveManager;
+use crate::text_effect::TextEffectStyle;
 
 /// 用户可调设置（前端 ↔ 后端同步）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSettings {
+    #[serde(default = "default_master_volume")]
+    pub master_volume: f32,
     pub bgm_volume: f32,
     pub sfx_volume: f32,
     pub text_speed: f32,
@@ -23,21 +27,116 @@ pub struct UserSettings {
     pub fullscreen: bool,
     #[serde(default)]
     pub muted: bool,
+    /// 对话/选项文字的描边与投影
+    #[serde(default)]
+    pub text_effect: TextEffectStyle,
+    /// 对话文本显示方式（打字机 / 逐字淡入）
+    #[serde(default)]
+    pub text_reveal: TextRevealMode,
+    /// 上次退出时的窗口尺寸（逻辑像素 `[宽, 高]`），仅 `window.resizable` 开启时记录
+    #[serde(default)]
+    pub window_size: Option<[u32; 2]>,
 }
 
 impl Default for UserSettings {
     fn default() -> Self {
         Self {
+            master_volume: default_master_volume(),
             bgm_volume: 80.0,
             sfx_volume: 100.0,
             text_speed: 40.0,
             auto_delay: 2.0,
             fullscreen: false,
             muted: false,
+            text_effect: TextEffectStyle::default(),
+            text_reveal: TextRevealMode::default(),
+            window_size: None,
         }
     }
 }
 
+fn default_master_volume() -> f32 {
+    100.0
+}
+
+pub const USER_SETTINGS_FILE: &str = "user_settings.json";
+/// 热加载检查 `user_settings.json` 修改时间的间隔（秒）
+pub const SETTINGS_POLL_INTERVAL: f32 = 1.0;
+
+impl UserSettings {
+    /// 从 JSON 文件读取；读取或解析失败时返回错误
+    pub fn load(path: impl AsRef<Path>) -> HostResult<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| {
+            HostError::InvalidInput(format!("用户设置解析失败 ({}): {e}", path.display()))
+        })
+    }
+
+    /// 写入 JSON 文件（目录不存在时创建）
+    pub fn save(&self, path: impl AsRef<Path>) -> HostResult<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| HostError::Internal(format!("用户设置序列化失败: {e}")))?;
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// `user_settings.json` 热加载：按修改时间检测外部改动
+pub struct SettingsWatcher {
+    path: PathBuf,
+    last_modified: Option<SystemTime>,
+    elapsed: f32,
+}
+
+impl SettingsWatcher {
+    /// 以文件当前的修改时间为基准，之后的改动才视为变更
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let last_modified = modified_time(&path);
+        Self {
+            path,
+            last_modified,
+            elapsed: 0.0,
+        }
+    }
+
+    /// 监视的设置文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 累计帧时间，每隔 [`SETTINGS_POLL_INTERVAL`] 返回一次 true
+    pub fn due(&mut self, dt: f32) -> bool {
+        self.elapsed += dt;
+        if self.elapsed < SETTINGS_POLL_INTERVAL {
+            return false;
+        }
+        self.elapsed = 0.0;
+        true
+    }
+
+    /// 修改时间变化时重新读取；未变更或文件不存在时返回 None
+    ///
+    /// 解析失败同样记下这次修改时间，文件再次被修改前不会重复报错。
+    pub fn check(&mut self) -> Option<HostResult<UserSettings>> {
+        let modified = modified_time(&self.path)?;
+        if self.last_modified == Some(modified) {
+            return None;
+        }
+        self.last_modified = Some(modified);
+        Some(UserSettings::load(&self.path))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 /// 对话历史条目
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -68,11 +167,18 @@ pub struct FrontendSession {
 // ── 持久化存储 ──────────────────────────────────────────────────────────────
 
 pub const PERSISTENT_FILE: &str = "persistent.json";
+pub const ENDINGS_FILE: &str = "endings.json";
+pub const SEEN_CGS_FILE: &str = "seen_cgs.json";
+pub const CHOSEN_BRANCHES_FILE: &str = "chosen_branches.json";
 
-/// 持久化变量存储（跨会话保留的 `$persistent.key` 变量）
+/// 持久化变量存储（跨会话保留的 `$persistent.key` 变量、已解锁结局、已看过的 CG 与已走过的分支）
 pub struct PersistentStore {
     pub saves_dir: PathBuf,
     pub variables: HashMap<String, VarValue>,
+    pub endings: EndingCollection,
+    pub seen_cgs: BTreeSet<String>,
+    /// 选择点 → 选过的 target_label
+    pub chosen_branches: BTreeMap<String, BTreeSet<String>>,
 }
 
 impl PersistentStore {
@@ -81,30 +187,29 @@ impl PersistentStore {
         Self {
             saves_dir: PathBuf::new(),
             variables: HashMap::new(),
+            endings: EndingCollection::new(),
+            seen_cgs: BTreeSet::new(),
+            chosen_branches: BTreeMap::new(),
         }
     }
 
     /// 从存档目录加载；文件不存在或解析失败时返回空 store
     pub fn load(saves_dir: impl AsRef<Path>) -> Self {
         let saves_dir = saves_dir.as_ref().to_path_buf();
-        let path = saves_dir.join(PERSISTENT_FILE);
-
-        let variables = if path.exists() {
-            fs::read_to_string(&path)
-                .ok()
-                .and_then(|content| serde_json::from_str(&content).ok())
-                .unwrap_or_else(|| {
-                    warn!(path = %path.display(), "持久化变量加载失败，使用空 store");
-                    HashMap::new()
-                })
-        } else {
-            HashMap::new()
-        };
-
-        Self {
+        let variables = load_json_or_default(&saves_dir.join(PERSISTENT_FILE), "持久化变量");
+        let endings = load_json_or_default(&saves_dir.join(ENDINGS_FILE), "结局记录");
+        let seen_cgs = load_json_or_default(&saves_dir.join(SEEN_CGS_FILE), "CG 鉴赏记录");
+        let mut store = Self {
             saves_dir,
             variables,
-        }
+            endings,
+            seen_cgs,
+            chosen_branches: BTreeMap::new(),
+        };
+        let branches: BTreeMap<String, BTreeSet<String>> =
+            load_json_or_default(&store.saves_dir.join(CHOSEN_BRANCHES_FILE), "分支记录");
+        store.merge_chosen_branches(&branches);
+        store
     }
 
     /// 写入磁盘
@@ -112,12 +217,37 @@ impl PersistentStore {
         if !self.saves_dir.exists() {
             fs::create_dir_all(&self.saves_dir)?;
         }
-        let path = self.saves_dir.join(PERSISTENT_FILE);
-        let content = serde_json::to_string_pretty(&self.variables)
-            .map_err(|e| HostError::Internal(format!("持久化变量序列化失败: {e}")))?;
-        fs::write(&path, content)?;
-        info!(path = %path.display(), count = self.variables.len(), "持久化变量保存成功");
-        Ok(())
+        save_json(
+            &self.saves_dir.join(PERSISTENT_FILE),
+            &self.variables,
+            "持久化变量",
+        )?;
+        info!(count = self.variables.len(), "持久化变量保存成功");
+        save_json(
+            &self.saves_dir.join(ENDINGS_FILE),
+            &self.endings,
+            "结局记录",
+        )?;
+        save_json(
+            &self.saves_dir.join(SEEN_CGS_FILE),
+            &self.seen_cgs,
+            "CG 鉴赏记录",
+        )?;
+        save_json(
+            &self.saves_dir.join(CHOSEN_BRANCHES_FILE),
+            &self.chosen_branches,
+            "分支记录",
+        )
+    }
+
+    /// 将 runtime 记录的已走过分支合并入 store（旧版 `脚本 id:节点索引` key 归并到脚本 id）
+    pub fn merge_chosen_branches(&mut self, branches: &BTreeMap<String, BTreeSet<String>>) {
+        for (key, labels) in branches {
+            self.chosen_branches
+                .entry(branch_script_id(key).to_string())
+                .or_default()
+                .extend(labels.iter().cloned());
+        }
     }
 
     /// 将 runtime persistent_variables 合并入 store（runtime 值覆盖）
@@ -128,6 +258,28 @@ impl PersistentStore {
     }
 }
 
+/// 读取 JSON 文件；文件不存在时返回默认值，读取或解析失败时告警并返回默认值
+fn load_json_or_default<T: serde::de::DeserializeOwned + Default>(path: &Path, what: &str) -> T {
+    if !path.exists() {
+        return T::default();
+    }
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_else(|| {
+            warn!(path = %path.display(), "{what}加载失败，使用空记录");
+            T::default()
+        })
+}
+
+/// 以带缩进的 JSON 写入文件
+fn save_json<T: Serialize>(path: &Path, value: &T, what: &str) -> HostResult<()> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| HostError::Internal(format!("{what}序列化失败: {e}")))?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
 // ── 快照栈 ──────────────────────────────────────────────────────────────────
 
 /// 状态快照（用于 Backspace 回退）
@@ -196,6 +348,15 @@ pub struct ShakeAnimation {
     pub elapsed: f32,
 }
 
+/// 镜头平移动画状态
+#[derive(Debug, Clone)]
+pub struct CameraPanAnimation {
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
 /// Host 侧 Signal 等待的具体种类。
 ///
 /// 与 `vn_runtime::command::SIGNAL_*` 常量一一对应，
@@ -224,4 +385,6 @@ pub enum WaitingFor {
     UIResult {
         key: String,
     },
+    /// 命中脚本调试断点，需调试端调用 continue 恢复
+    Breakpoint,
 }
@@ -0,0 +1,119 @@
+//! 文字描边与投影
+//!
+//! 对话与选项文本共用同一套描边/投影参数（来自 `UserSettings`），
+//! 前端用 CSS `text-shadow` 多层叠加实现：描边 = 按方向偏移绘制多次，
+//! 投影 = 额外一层偏移。
+
+use serde::{Deserialize, Serialize};
+
+/// 描边方向数（上下左右 + 四个对角）
+pub const OUTLINE_DIRECTIONS: usize = 8;
+
+/// 文字描边/投影参数（像素基于 1920×1080 基准分辨率）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextEffectStyle {
+    /// 描边宽度，0 表示不描边
+    pub outline_width: f32,
+    /// 描边颜色（CSS 颜色）
+    pub outline_color: String,
+    /// 投影偏移 (x, y)，(0, 0) 表示不投影
+    pub shadow_offset: [f32; 2],
+    /// 投影颜色（CSS 颜色）
+    pub shadow_color: String,
+}
+
+impl Default for TextEffectStyle {
+    fn default() -> Self {
+        Self {
+            outline_width: 0.0,
+            outline_color: "#000000".to_string(),
+            shadow_offset: [2.0, 2.0],
+            shadow_color: "rgba(0,0,0,0.6)".to_string(),
+        }
+    }
+}
+
+/// 描边需要额外绘制的各层偏移
+///
+/// 宽度 ≤ 0 时不产生任何层；否则按 8 个方向各偏移 `width` 绘制一次。
+pub fn outline_offsets(width: f32) -> Vec<(f32, f32)> {
+    if width <= 0.0 {
+        return Vec::new();
+    }
+    (0..OUTLINE_DIRECTIONS)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::TAU / OUTLINE_DIRECTIONS as f32;
+            // 对角方向同样偏移 width（而不是 width/√2），保证描边粗细一致
+            // `+ 0.0` 把 -0.0 归一为 0.0，避免输出 "-0px"
+            let (dx, dy) = (angle.cos().round() + 0.0, angle.sin().round() + 0.0);
+            (dx * width, dy * width)
+        })
+        .collect()
+}
+
+impl TextEffectStyle {
+    /// 生成 CSS `text-shadow` 值；无描边也无投影时为 `none`
+    pub fn text_shadow_css(&self) -> String {
+        let mut layers: Vec<String> = outline_offsets(self.outline_width)
+            .into_iter()
+            .map(|(dx, dy)| format!("{dx}px {dy}px 0 {}", self.outline_color))
+            .collect();
+        let [sx, sy] = self.shadow_offset;
+        if sx != 0.0 || sy != 0.0 {
+            layers.push(format!("{sx}px {sy}px 2px {}", self.shadow_color));
+        }
+        if layers.is_empty() {
+            "none".to_string()
+        } else {
+            layers.join(", ")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_width_draws_no_extra_layers() {
+        assert!(outline_offsets(0.0).is_empty());
+        assert!(outline_offsets(-1.0).is_empty());
+
+        let style = TextEffectStyle {
+            outline_width: 0.0,
+            shadow_offset: [0.0, 0.0],
+            ..Default::default()
+        };
+        assert_eq!(style.text_shadow_css(), "none");
+    }
+
+    #[test]
+    fn positive_width_draws_each_direction_once() {
+        let offsets = outline_offsets(2.0);
+        assert_eq!(offsets.len(), OUTLINE_DIRECTIONS);
+        for &(dx, dy) in &offsets {
+            assert!(dx.abs() == 2.0 || dx == 0.0, "dx = {dx}");
+            assert!(dy.abs() == 2.0 || dy == 0.0, "dy = {dy}");
+            assert!(dx != 0.0 || dy != 0.0);
+        }
+        let mut unique = offsets.clone();
+        unique.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        unique.dedup();
+        assert_eq!(unique.len(), OUTLINE_DIRECTIONS);
+    }
+
+    #[test]
+    fn css_combines_outline_and_shadow() {
+        let style = TextEffectStyle {
+            outline_width: 1.0,
+            outline_color: "#fff".to_string(),
+            shadow_offset: [3.0, 4.0],
+            shadow_color: "black".to_string(),
+        };
+        let css = style.text_shadow_css();
+        assert_eq!(css.split(", ").count(), OUTLINE_DIRECTIONS + 1);
+        assert!(css.starts_with("1px 0px 0 #fff"));
+        assert!(css.ends_with("3px 4px 2px black"));
+    }
+}
@@ -0,0 +1,206 @@
+//! 触摸输入
+//!
+//! 前端只上报触摸起点/终点与当前选项按钮的矩形（视口坐标），
+//! 手势识别与命中判定都在这里完成，便于脱离 WebView 测试：
+//!
+//! - 轻触 → 推进（等价鼠标点击）；选择界面下 → 选中触点所在选项
+//! - 向上滑动 → 打开历史
+//!
+//! 触摸结束后 WebView 还会合成一次 `click`，由 [`TouchGuard`] 在
+//! 时间窗口内吞掉，避免同一次轻触触发两次推进。
+
+use serde::Deserialize;
+
+/// 判定为轻触的最大位移（像素）
+pub const TAP_MAX_DISTANCE: f32 = 16.0;
+/// 判定为向上滑动的最小纵向位移（像素）
+pub const SWIPE_MIN_DISTANCE: f32 = 80.0;
+/// 触摸结束后屏蔽合成鼠标点击的时间窗口（毫秒）
+pub const MOUSE_SUPPRESS_MS: f64 = 600.0;
+
+/// 视口坐标系中的点
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct TouchPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// 视口坐标系中的矩形（对应 `getBoundingClientRect`）
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct TouchRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl TouchRect {
+    /// 点是否落在矩形内（含边界）
+    pub fn contains(&self, p: TouchPoint) -> bool {
+        p.x >= self.x && p.x <= self.x + self.width && p.y >= self.y && p.y <= self.y + self.height
+    }
+}
+
+/// 一次完整触摸（按下到抬起）
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TouchStroke {
+    pub start: TouchPoint,
+    pub end: TouchPoint,
+    /// 选项按钮矩形，按选项序号排列；不在选择界面时为空
+    #[serde(default)]
+    pub choice_rects: Vec<TouchRect>,
+}
+
+/// 触摸手势
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchGesture {
+    Tap,
+    SwipeUp,
+    /// 其他方向滑动或位移介于轻触与滑动之间，忽略
+    None,
+}
+
+/// 触摸映射出的输入动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TouchAction {
+    /// 等价于鼠标点击（推进对话）
+    Click,
+    /// 选中第 n 个选项
+    ChoiceSelected(usize),
+    /// 打开历史
+    OpenHistory,
+}
+
+/// 根据起点/终点识别手势
+pub fn classify(start: TouchPoint, end: TouchPoint) -> TouchGesture {
+    let dx = end.x - start.x;
+    let dy = end.y - start.y;
+    if dx.hypot(dy) <= TAP_MAX_DISTANCE {
+        TouchGesture::Tap
+    } else if -dy >= SWIPE_MIN_DISTANCE && -dy > dx.abs() {
+        TouchGesture::SwipeUp
+    } else {
+        TouchGesture::None
+    }
+}
+
+impl TouchStroke {
+    /// 映射为输入动作
+    ///
+    /// 有选项时轻触只在命中某个选项矩形时生效，落在选项外不推进，
+    /// 与鼠标在选择界面点空白处无反应的行为一致。
+    pub fn action(&self) -> Option<TouchAction> {
+        match classify(self.start, self.end) {
+            TouchGesture::Tap if self.choice_rects.is_empty() => Some(TouchAction::Click),
+            TouchGesture::Tap => self
+                .choice_rects
+                .iter()
+                .position(|r| r.contains(self.end))
+                .map(TouchAction::ChoiceSelected),
+            TouchGesture::SwipeUp => Some(TouchAction::OpenHistory),
+            TouchGesture::None => None,
+        }
+    }
+}
+
+/// 触摸与鼠标去重
+#[derive(Debug, Clone, Default)]
+pub struct TouchGuard {
+    last_touch_ms: Option<f64>,
+}
+
+impl TouchGuard {
+    /// 记录一次触摸结束
+    pub fn record_touch(&mut self, now_ms: f64) {
+        self.last_touch_ms = Some(now_ms);
+    }
+
+    /// 此刻的鼠标点击是否应被视为触摸合成事件而忽略
+    pub fn should_suppress_mouse(&self, now_ms: f64) -> bool {
+        self.last_touch_ms
+            .is_some_and(|t| now_ms >= t && now_ms - t < MOUSE_SUPPRESS_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pt(x: f32, y: f32) -> TouchPoint {
+        TouchPoint { x, y }
+    }
+
+    fn rect(y: f32) -> TouchRect {
+        TouchRect {
+            x: 100.0,
+            y,
+            width: 400.0,
+            height: 60.0,
+        }
+    }
+
+    #[test]
+    fn classify_tap_and_swipes() {
+        assert_eq!(classify(pt(10.0, 10.0), pt(14.0, 12.0)), TouchGesture::Tap);
+        assert_eq!(
+            classify(pt(200.0, 500.0), pt(210.0, 300.0)),
+            TouchGesture::SwipeUp
+        );
+        // 向下、水平滑动与位移不足都忽略
+        assert_eq!(
+            classify(pt(200.0, 300.0), pt(200.0, 500.0)),
+            TouchGesture::None
+        );
+        assert_eq!(
+            classify(pt(100.0, 300.0), pt(400.0, 220.0)),
+            TouchGesture::None
+        );
+        assert_eq!(
+            classify(pt(200.0, 300.0), pt(200.0, 260.0)),
+            TouchGesture::None
+        );
+    }
+
+    #[test]
+    fn tap_inside_choice_rect_selects_that_choice() {
+        let stroke = TouchStroke {
+            start: pt(300.0, 232.0),
+            end: pt(302.0, 230.0),
+            choice_rects: vec![rect(100.0), rect(200.0), rect(300.0)],
+        };
+        assert_eq!(stroke.action(), Some(TouchAction::ChoiceSelected(1)));
+
+        let miss = TouchStroke {
+            start: pt(300.0, 180.0),
+            end: pt(300.0, 180.0),
+            ..stroke.clone()
+        };
+        assert_eq!(miss.action(), None);
+    }
+
+    #[test]
+    fn tap_without_choices_is_click_and_swipe_opens_history() {
+        let tap = TouchStroke {
+            start: pt(50.0, 50.0),
+            end: pt(50.0, 50.0),
+            choice_rects: Vec::new(),
+        };
+        assert_eq!(tap.action(), Some(TouchAction::Click));
+
+        let swipe = TouchStroke {
+            start: pt(50.0, 600.0),
+            end: pt(50.0, 400.0),
+            choice_rects: vec![rect(100.0)],
+        };
+        assert_eq!(swipe.action(), Some(TouchAction::OpenHistory));
+    }
+
+    #[test]
+    fn guard_suppresses_synthetic_mouse_click_only_within_window() {
+        let mut guard = TouchGuard::default();
+        assert!(!guard.should_suppress_mouse(1000.0));
+        guard.record_touch(1000.0);
+        assert!(guard.should_suppress_mouse(1100.0));
+        assert!(!guard.should_suppress_mouse(1000.0 + MOUSE_SUPPRESS_MS));
+    }
+}
@@ -1,12 +1,8 @@
 use dioxus::prelude::*;
 
+use crate::layout_config::UiAssetPaths;
 use crate::render_state::RenderState;
 
-/// 资源 URL 构建
-fn asset_url(path: &str) -> String {
-    format!("http://ring-asset.localhost/{path}")
-}
-
 /// 音频桥接组件：监听 `AudioRenderState` 变化，通过 JS Web Audio API 播放。
 ///
 /// 不渲染任何 DOM。在 mount 时注入 JS AudioManager，
@@ -21,6 +17,7 @@ pub fn AudioBridge(render_state: Signal<RenderState>) -> Element {
                 let bgmAudio = null;
                 let bgmPath = null;
                 let bgmFadeInterval = null;
+                const sfxInstances = new Map();
 
                 function stopBgmFade() {
                     if (bgmFadeInterval) {
@@ -126,10 +123,20 @@ pub fn AudioBridge(render_state: Signal<RenderState>) -> Element {
                         }
                     },
 
-                    playSfx(url, volume) {
+                    playSfx(url, volume, id) {
                         const audio = new Audio(url);
                         audio.volume = Math.max(0, Math.min(1, volume));
+                        sfxInstances.set(id, audio);
+                        audio.addEventListener("ended", () => sfxInstances.delete(id));
                         audio.play().catch(e => console.warn("[audio] SFX play failed:", e));
+                    },
+
+                    stopSfx(id) {
+                        const audio = sfxInstances.get(id);
+                        if (audio) {
+                            audio.pause();
+                            sfxInstances.delete(id);
+                        }
                     }
                 };
             })();
@@ -153,7 +160,7 @@ pub fn AudioBridge(render_state: Signal<RenderState>) -> Element {
 
     match current_bgm {
         Some(bgm) => {
-            let url = asset_url(&bgm.path);
+            let url = UiAssetPaths::asset_url(&bgm.path);
             let prev_path = prev_bgm_path.read().clone();
             let prev_vol = *prev_bgm_volume.read();
 
@@ -187,12 +194,18 @@ pub fn AudioBridge(render_state: Signal<RenderState>) -> Element {
         }
     }
 
-    // 处理 SFX 队列（drain 语义——每帧只出现一次）
+    // 处理 SFX 队列（drain 语义——每帧只出现一次）；先停被替换的，再播新的
+    for id in &audio.sfx_stop {
+        document::eval(&format!(
+            r#"if(window.__ringAudio) window.__ringAudio.stopSfx({id});"#
+        ));
+    }
     for sfx in &audio.sfx_queue {
-        let url = asset_url(&sfx.path);
+        let url = UiAssetPaths::asset_url(&sfx.path);
         let volume = sfx.volume;
+        let id = sfx.id;
         document::eval(&format!(
-            r#"if(window.__ringAudio) window.__ringAudio.playSfx("{url}", {volume});"#
+            r#"if(window.__ringAudio) window.__ringAudio.playSfx("{url}", {volume}, {id});"#
         ));
     }
 
@@ -1,16 +1,14 @@
 use dioxus::prelude::*;
 
+use crate::layout_config::UiAssetPaths;
 use crate::render_state::RenderState;
 
-/// 资源 URL 构建（Windows wry 格式）
-fn asset_url(path: &str) -> String {
-    format!("http://ring-asset.localhost/{path}")
-}
-
 /// 背景渲染层：双 `<img>` 实现 dissolve 交叉淡化。
 ///
 /// - `current_background`：当前背景，始终以 opacity 1 显示
-/// - `background_transition`：过渡中时，旧背景通过 `@keyframes` 从 opacity 1 淡化到 0
+/// - `background_fill`：纯色背景，直接绘制色块，不加载纹理
+/// - `background_layers`：多层视差背景，按远到近叠放，各层按镜头平移与视差系数偏移
+/// - `background_transition`：过渡中时，旧背景（图片或色块）通过 `@keyframes` 从 opacity 1 淡化到 0
 ///
 /// 使用 CSS animation 而非 transition：因为旧背景 `<img>` 是新创建的元素，
 /// CSS transition 没有先前状态可过渡，而 animation 自带起始值。
@@ -18,15 +16,34 @@ fn asset_url(path: &str) -> String {
 pub fn BackgroundLayer(render_state: Signal<RenderState>) -> Element {
     let rs = render_state.read();
     let current_bg = rs.current_background.clone();
+    let fill = rs.background_fill.map(|c| c.to_hex());
+    let layers: Vec<(String, (f32, f32))> = rs
+        .background_layers
+        .iter()
+        .map(|layer| UiAssetPaths::asset_url(&layer.path))
+        .zip(rs.background_layer_offsets())
+        .collect();
     let transition = rs.background_transition.clone();
 
     rsx! {
         div { class: "vn-background",
             // 旧背景层（过渡中显示，淡出后消失）
             if let Some(ref tr) = transition {
+                if let Some(old_fill) = tr.old_fill {
+                    {
+                        let color = old_fill.to_hex();
+                        let duration = tr.duration;
+                        rsx! {
+                            div {
+                                class: "vn-background__fill vn-background__img--old",
+                                style: "background-color: {color}; animation: vn-dissolve-out {duration}s ease forwards;",
+                            }
+                        }
+                    }
+                }
                 if let Some(ref old_bg) = tr.old_background {
                     {
-                        let old_url = asset_url(old_bg);
+                        let old_url = UiAssetPaths::asset_url(old_bg);
                         let duration = tr.duration;
                         rsx! {
                             img {
@@ -39,10 +56,27 @@ pub fn BackgroundLayer(render_state: Signal<RenderState>) -> Element {
                 }
             }
 
+            // 当前纯色背景
+            if let Some(ref color) = fill {
+                div {
+                    class: "vn-background__fill vn-background__img--current",
+                    style: "background-color: {color};",
+                }
+            }
+
+            // 视差背景层（四周留出余量，平移时不露边）
+            for (url, (dx, dy)) in layers {
+                img {
+                    class: "vn-background__layer",
+                    src: "{url}",
+                    style: "transform: translate({dx}px, {dy}px);",
+                }
+            }
+
             // 当前背景层
             if let Some(ref bg) = current_bg {
                 {
-                    let url = asset_url(bg);
+                    let url = UiAssetPaths::asset_url(bg);
                     rsx! {
                         img {
                             class: "vn-background__img vn-background__img--current",
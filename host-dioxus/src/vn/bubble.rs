@@ -0,0 +1,43 @@
+use dioxus::prelude::*;
+
+use crate::render_state::RenderState;
+
+/// 气泡层：在绑定角色的立绘上方绘制小气泡（旁注/心声）。
+///
+/// 水平位置跟随立绘锚点，垂直位置由 `.vn-bubble` 样式决定；
+/// 角色不在场或正在淡出时不绘制。
+#[component]
+pub fn BubbleLayer(render_state: Signal<RenderState>) -> Element {
+    let rs = render_state.read();
+    if rs.bubbles.is_empty() {
+        return rsx! {};
+    }
+
+    let bubbles: Vec<(String, String, f32)> = rs
+        .bubbles
+        .iter()
+        .filter_map(|bubble| {
+            let sprite = rs.visible_characters.get(&bubble.alias)?;
+            (!sprite.fading_out).then(|| {
+                (
+                    bubble.alias.clone(),
+                    bubble.content.clone(),
+                    sprite.pos_x * 100.0,
+                )
+            })
+        })
+        .collect();
+
+    rsx! {
+        div { class: "vn-bubbles",
+            for (alias, content, left_pct) in bubbles {
+                div {
+                    key: "{alias}",
+                    class: "vn-bubble",
+                    style: "left: {left_pct}%;",
+                    "{content}"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use dioxus::prelude::*;
+
+use crate::render_state::RenderState;
+
+/// 全屏 CG 层：黑底 + Contain 模式完整显示图片，覆盖背景与立绘。
+#[component]
+pub fn CgLayer(render_state: Signal<RenderState>) -> Element {
+    let cg = use_memo(move || render_state.read().cg.clone());
+
+    let cg_ref = cg.read();
+    let Some(cg) = cg_ref.as_ref() else {
+        return rsx! {};
+    };
+    let url = format!("http://ring-asset.localhost/{}", cg.path);
+    let alpha = cg.alpha;
+
+    rsx! {
+        div { class: "vn-cg", style: "opacity: {alpha};",
+            img { class: "vn-cg__img", src: "{url}" }
+        }
+    }
+}
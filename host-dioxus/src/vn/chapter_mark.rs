@@ -1,10 +1,12 @@
 use dioxus::prelude::*;
 
 use crate::render_state::RenderState;
+use crate::state::AppState;
 
-/// 章节标记组件：显示章节标题，alpha 由后端驱动。
+/// 章节标记组件：显示章节标题，alpha 由后端驱动；字号与位置按级别取自 layout.json。
 #[component]
 pub fn ChapterMark(render_state: Signal<RenderState>) -> Element {
+    let app_state = use_context::<AppState>();
     let rs = render_state.read();
 
     let mark = match &rs.chapter_mark {
@@ -12,20 +14,25 @@ pub fn ChapterMark(render_state: Signal<RenderState>) -> Element {
         None => return rsx! {},
     };
 
+    let style = {
+        let Ok(inner) = app_state.inner.lock() else {
+            return rsx! {};
+        };
+        let Some(svc) = inner.services.as_ref() else {
+            return rsx! {};
+        };
+        svc.layout.chapter_mark.style_for(mark.level).clone()
+    };
+
     let alpha = mark.alpha;
     let title = &mark.title;
-    let level = mark.level;
-
-    let font_size = match level {
-        1 => "2.2em",
-        2 => "1.6em",
-        _ => "1.3em",
-    };
+    let anchor_class = style.anchor.css_class();
+    let font_size = style.text_size;
 
     rsx! {
         div {
-            class: "vn-chapter-mark",
-            style: "opacity: {alpha}; font-size: {font_size};",
+            class: "vn-chapter-mark {anchor_class}",
+            style: "opacity: {alpha}; font-size: {font_size}px;",
             "{title}"
         }
     }
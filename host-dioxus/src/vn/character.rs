@@ -1,16 +1,18 @@
 use dioxus::prelude::*;
 
-use crate::render_state::RenderState;
-
-/// 资源 URL 构建（Windows wry 格���）
-fn asset_url(path: &str) -> String {
-    format!("http://ring-asset.localhost/{path}")
-}
+use crate::layout_config::UiAssetPaths;
+use crate::render_state::{RenderState, characters_in_draw_order};
 
 /// 立绘层：遍历 `visible_characters`，为每个角色渲染 `<img>`。
 ///
 /// 位置、缩放、透明度、过渡时长全部由后端 `CharacterSprite` 提供，
 /// 前端通过 CSS `transition` 实现平滑动���。
+///
+/// 表情交叉淡化时旧纹理以同样的位置叠在上层，用 `vn-dissolve-out` 关键帧淡出。
+/// 弹跳动画由后端逐帧给出纵向偏移，叠加在 `offset_y` 上。
+/// 开启轮廓光时，在立绘下方按 [`CharacterSprite::rim_light_passes`] 逐层偏移绘制亮色剪影。
+///
+/// [`CharacterSprite::rim_light_passes`]: crate::render_state::CharacterSprite::rim_light_passes
 #[component]
 pub fn CharacterLayer(render_state: Signal<RenderState>) -> Element {
     let characters = use_memo(move || render_state.read().visible_characters.clone());
@@ -20,15 +22,14 @@ pub fn CharacterLayer(render_state: Signal<RenderState>) -> Element {
         return rsx! {};
     }
 
-    // 按 z-order 排序
-    let mut sorted: Vec<_> = characters.iter().collect();
-    sorted.sort_by_key(|(_, sprite)| sprite.z_order);
+    // 按 z-order 排序，淡出中的立绘在下层
+    let sorted = characters_in_draw_order(&characters);
 
     rsx! {
         div { class: "vn-characters",
             for (alias, sprite) in sorted {
                 {
-                    let url = asset_url(&sprite.texture_path);
+                    let url = UiAssetPaths::asset_url(&sprite.texture_path);
                     let z = sprite.z_order;
                     let opacity = sprite.target_alpha;
 
@@ -38,7 +39,7 @@ pub fn CharacterLayer(render_state: Signal<RenderState>) -> Element {
                     let ax = sprite.anchor_x * 100.0;
                     let ay = sprite.anchor_y * 100.0;
                     let ox = sprite.offset_x;
-                    let oy = sprite.offset_y;
+                    let oy = sprite.offset_y + sprite.bounce_offset_y();
                     let sx = sprite.scale_x;
                     let sy = sprite.scale_y;
                     let rs = sprite.render_scale;
@@ -51,22 +52,63 @@ pub fn CharacterLayer(render_state: Signal<RenderState>) -> Element {
                         String::new()
                     };
 
-                    let style = format!(
-                        "left: {left_pct}%; top: {top_pct}%; \
-                         opacity: {opacity}; \
-                         z-index: {z}; \
-                         transform-origin: {ax}% {ay}%; \
-                         transform: translate(-{ax}%, -{ay}%) translate({ox}px, {oy}px) scale({sx}, {sy}) scale({rs}); \
-                         {transition}"
-                    );
+                    let style_at = |opacity: f32, dx: f32, dy: f32| {
+                        format!(
+                            "left: {left_pct}%; top: {top_pct}%; \
+                             opacity: {opacity}; \
+                             z-index: {z}; \
+                             transform-origin: {ax}% {ay}%; \
+                             transform: translate(-{ax}%, -{ay}%) translate({}px, {}px) scale({sx}, {sy}) scale({rs}); \
+                             {transition}",
+                            ox + dx,
+                            oy + dy,
+                        )
+                    };
+                    let style = style_at(opacity, 0.0, 0.0);
+
+                    // 轮廓光剪影层：透明度随立绘一起变化
+                    let rim_styles: Vec<String> = sprite
+                        .rim_light_passes()
+                        .iter()
+                        .map(|pass| style_at(opacity * pass.alpha, pass.dx, pass.dy))
+                        .collect();
+
+                    // 待机动画用独立的 CSS `translate`/`scale` 属性叠加，不覆盖上面的 transform
+                    let idle_class = sprite
+                        .idle
+                        .map(|kind| format!(" vn-characters__sprite--idle-{}", kind.as_str()))
+                        .unwrap_or_default();
+
+                    let old_expression = sprite.crossfade.as_ref().map(|cf| {
+                        (
+                            UiAssetPaths::asset_url(&cf.old_texture),
+                            format!("{style} animation: vn-dissolve-out {}s ease forwards;", cf.duration),
+                        )
+                    });
 
                     rsx! {
+                        for (i, rim_style) in rim_styles.into_iter().enumerate() {
+                            img {
+                                key: "{alias}__rim{i}",
+                                class: "vn-characters__sprite vn-characters__sprite--rim{idle_class}",
+                                src: "{url}",
+                                style: "{rim_style}",
+                            }
+                        }
                         img {
                             key: "{alias}",
-                            class: "vn-characters__sprite",
+                            class: "vn-characters__sprite{idle_class}",
                             src: "{url}",
                             style: "{style}",
                         }
+                        if let Some((old_url, old_style)) = old_expression {
+                            img {
+                                key: "{alias}__old",
+                                class: "vn-characters__sprite{idle_class}",
+                                src: "{old_url}",
+                                style: "{old_style}",
+                            }
+                        }
                     }
                 }
             }
@@ -1,13 +1,23 @@
 use dioxus::prelude::*;
 
+use crate::nine_slice::NineSlice;
 use crate::render_state::RenderState;
 use crate::state::AppState;
 
 /// 选项面板：显示选择支并处理用户选择。
+///
+/// 选择带提示时，提示在对话框中打完后才显示选项。
 #[component]
 pub fn ChoicePanel(render_state: Signal<RenderState>) -> Element {
     let app_state = use_context::<AppState>();
-    let choices = use_memo(move || render_state.read().choices.clone());
+    let choices = use_memo(move || {
+        let rs = render_state.read();
+        if rs.is_dialogue_complete() {
+            rs.choices.clone()
+        } else {
+            None
+        }
+    });
 
     let choices_ref = choices.read();
     let choices_state = match choices_ref.as_ref() {
@@ -16,6 +26,18 @@ pub fn ChoicePanel(render_state: Signal<RenderState>) -> Element {
     };
 
     let items = &choices_state.choices;
+    let (text_shadow, slice_css) = app_state
+        .inner
+        .lock()
+        .map(|inner| {
+            let slice_css = inner
+                .services
+                .as_ref()
+                .map(|svc| NineSlice::from_borders(svc.layout.choice.button_borders).css(None))
+                .unwrap_or_default();
+            (inner.user_settings.text_effect.text_shadow_css(), slice_css)
+        })
+        .unwrap_or_else(|_| ("none".to_string(), String::new()));
 
     rsx! {
         div { class: "vn-choices",
@@ -23,18 +45,51 @@ pub fn ChoicePanel(render_state: Signal<RenderState>) -> Element {
                 for (i, choice) in items.iter().enumerate() {
                     {
                         let text = choice.text.clone();
+                        let enabled = choice.enabled;
+                        let reason = choice.disabled_reason.clone();
+                        let class = match (enabled, choice.visited) {
+                            (false, _) => "vn-choices__btn vn-choices__btn--disabled",
+                            (true, true) => "vn-choices__btn vn-choices__btn--visited",
+                            (true, false) => "vn-choices__btn",
+                        };
+                        let active = if choices_state.active_index() == Some(i) {
+                            " vn-choices__btn--active"
+                        } else {
+                            ""
+                        };
+                        let scale = choices_state.item_scale(i);
+                        let brightness = 1.0 + 0.2 * choices_state.item_highlight(i);
                         let app = app_state.clone();
+                        let app_enter = app_state.clone();
+                        let app_leave = app_state.clone();
                         rsx! {
                             button {
                                 key: "{i}",
-                                class: "vn-choices__btn",
+                                class: "{class}{active}",
+                                style: "text-shadow: {text_shadow};{slice_css}transform: scale({scale});filter: brightness({brightness});",
+                                disabled: !enabled,
+                                onmouseenter: move |_| {
+                                    if let Ok(mut inner) = app_enter.inner.lock() {
+                                        inner.process_choice_hover(Some(i));
+                                    }
+                                },
+                                onmouseleave: move |_| {
+                                    if let Ok(mut inner) = app_leave.inner.lock() {
+                                        inner.process_choice_hover(None);
+                                    }
+                                },
                                 onclick: move |evt: Event<MouseData>| {
                                     evt.stop_propagation();
-                                    if let Ok(mut inner) = app.inner.lock() {
+                                    if let Ok(mut inner) = app.inner.lock()
+                                        && !inner.touch_guard.should_suppress_mouse(crate::now_millis())
+                                    {
                                         inner.process_choose(i);
                                     }
                                 },
                                 "{text}"
+                                if let Some(reason) = reason {
+                                    span { class: "vn-choices__reason", "{reason}" }
+                                }
                             }
                         }
                     }
@@ -2,19 +2,25 @@ use dioxus::prelude::*;
 
 use vn_runtime::command::TextMode;
 
-use crate::render_state::RenderState;
+use crate::layout_config::{TextboxStyle, UiAssetPaths};
+use crate::nine_slice::NineSlice;
+use crate::render_state::{DialogueIndicator, RenderState, TextRevealMode};
+use crate::state::AppState;
 
-/// ADV 对话框组件：显示说话人 + 打字机文本 + 推进指示器。
+/// ADV 对话框组件：显示说话人 + 打字机文本 + 指示器（打字中跳动点，打完闪烁箭头）。
 ///
 /// 打字机效果由后端 `process_tick` 驱动 `visible_chars` 递增，
-/// 前端只负责截取对应长度的文本渲染。
+/// 前端只负责截取对应长度的文本渲染，正文中的 `**粗体**` 以 `<b>` 显示。
+/// 逐字淡入模式下每个字符单独包一层 `<span>`，不透明度取自 `char_alpha`。
 /// NVL 模式下不渲染（NVL 有独立的全屏面板）。
+/// 脚本 `textBoxStyle` 选中的样式取自 layout.json 的 `textbox_styles`，未定义时使用默认外观。
 #[component]
 pub fn DialogueBox(render_state: Signal<RenderState>) -> Element {
+    let app_state = use_context::<AppState>();
     let rs = render_state.read();
 
     // NVL 模式、不可见、或无对话时不渲染
-    if rs.text_mode == TextMode::NVL || !rs.ui_visible {
+    if rs.text_mode == TextMode::NVL || !rs.textbox_visible() {
         return rsx! {};
     }
     let dialogue = match &rs.dialogue {
@@ -28,29 +34,120 @@ pub fn DialogueBox(render_state: Signal<RenderState>) -> Element {
         .as_deref()
         .filter(|s| !s.is_empty() && *s != "旁白")
         .map(|s| s.to_string());
-    let visible_text: String = dialogue
-        .content
-        .chars()
-        .take(dialogue.visible_chars)
-        .collect();
-    let is_complete = dialogue.is_complete;
+    let indicator = dialogue.indicator();
+    let (text_shadow, style, borders, reveal) = app_state
+        .inner
+        .lock()
+        .map(|inner| {
+            let layout = inner.services.as_ref().map(|svc| &svc.layout);
+            let style = rs
+                .textbox_style
+                .as_deref()
+                .zip(layout)
+                .and_then(|(name, layout)| layout.textbox_styles.get(name).cloned())
+                .unwrap_or_default();
+            let borders = layout.map(|l| (l.dialogue.textbox_borders, l.dialogue.namebox_borders));
+            (
+                inner.user_settings.text_effect.text_shadow_css(),
+                style,
+                borders,
+                inner.user_settings.text_reveal,
+            )
+        })
+        .unwrap_or_else(|_| {
+            (
+                "none".to_string(),
+                TextboxStyle::default(),
+                None,
+                TextRevealMode::default(),
+            )
+        });
+    let fade_in = reveal == TextRevealMode::FadeIn;
+    let segments = if fade_in {
+        Vec::new()
+    } else {
+        dialogue.visible_segments()
+    };
+    let glyphs: Vec<(char, String)> = if fade_in {
+        dialogue
+            .visible_glyphs()
+            .into_iter()
+            .map(|(ch, bold, alpha)| (ch, glyph_css(bold, alpha)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let box_css = border_image_css(
+        style.textbox.as_deref(),
+        style.textbox_borders.or(borders.map(|b| b.0)),
+    );
+    let name_css = format!(
+        "{}{}",
+        border_image_css(
+            style.namebox.as_deref(),
+            style.namebox_borders.or(borders.map(|b| b.1)),
+        ),
+        color_css(style.name_color.as_deref())
+    );
+    let text_color_css = color_css(style.text_color.as_deref());
 
     rsx! {
-        div { class: "vn-dialogue",
+        div { class: "vn-dialogue", style: "{box_css}",
             // 说话人名牌（旁白不显示）
             if let Some(name) = speaker {
-                div { class: "vn-dialogue__name", "{name}" }
+                div { class: "vn-dialogue__name", style: "{name_css}", "{name}" }
             }
 
             // 文本区域
-            div { class: "vn-dialogue__text",
-                "{visible_text}"
+            div {
+                class: "vn-dialogue__text",
+                style: "text-shadow: {text_shadow};{text_color_css}",
+                for (text, bold) in segments {
+                    if bold {
+                        b { "{text}" }
+                    } else {
+                        "{text}"
+                    }
+                }
+                for (ch, css) in glyphs {
+                    span { style: "{css}", "{ch}" }
+                }
 
-                // 推进指示器（打字完成后闪烁）
-                if is_complete {
+                // 指示器：打字中跳动点，打完（或句中点击等待）闪烁箭头
+                if indicator == DialogueIndicator::Advance {
                     span { class: "vn-dialogue__advance", "\u{25BC}" }
+                } else {
+                    span { class: "vn-dialogue__typing",
+                        for _ in 0..3 {
+                            span { class: "vn-dialogue__typing-dot", "\u{00B7}" }
+                        }
+                    }
                 }
             }
         }
     }
 }
+
+/// 覆盖 NinePatch 背景纹理与九宫格边框的内联样式（未配置的部分沿用 CSS 默认）
+fn border_image_css(path: Option<&str>, borders: Option<[f32; 4]>) -> String {
+    let source = path.map(UiAssetPaths::asset_url);
+    match borders {
+        Some(borders) => NineSlice::from_borders(borders).css(source.as_deref()),
+        None => source
+            .map(|url| format!("border-image-source: url(\"{url}\");"))
+            .unwrap_or_default(),
+    }
+}
+
+/// 逐字淡入时单个字符的内联样式
+fn glyph_css(bold: bool, alpha: f32) -> String {
+    if bold {
+        format!("opacity: {alpha}; font-weight: bold;")
+    } else {
+        format!("opacity: {alpha};")
+    }
+}
+
+fn color_css(color: Option<&str>) -> String {
+    color.map(|c| format!("color: {c};")).unwrap_or_default()
+}
@@ -1,5 +1,7 @@
 mod audio_bridge;
 mod background;
+mod bubble;
+mod cg;
 mod chapter_mark;
 mod character;
 mod choice;
@@ -7,11 +9,14 @@ mod dialogue;
 mod map_overlay;
 mod minigame_overlay;
 mod nvl;
+mod preload;
 mod quick_menu;
 mod rule_transition;
+mod rumble;
 mod scene;
 mod title_card;
 mod transition;
 mod video;
+mod weather;
 
 pub use scene::VNScene;
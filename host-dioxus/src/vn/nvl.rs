@@ -10,7 +10,7 @@ use crate::render_state::RenderState;
 pub fn NvlPanel(render_state: Signal<RenderState>) -> Element {
     let rs = render_state.read();
 
-    if rs.text_mode != TextMode::NVL || !rs.ui_visible {
+    if rs.text_mode != TextMode::NVL || !rs.textbox_visible() {
         return rsx! {};
     }
 
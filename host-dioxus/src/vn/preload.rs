@@ -0,0 +1,24 @@
+use dioxus::prelude::*;
+
+use crate::layout_config::UiAssetPaths;
+use crate::render_state::RenderState;
+
+/// 图片预取层：把 Runtime 前瞻到的图片以隐藏 `<img>` 挂载，
+/// 浏览器提前下载并缓存，真正切换背景/立绘时不再等待加载。
+#[component]
+pub fn PreloadLayer(render_state: Signal<RenderState>) -> Element {
+    let images = use_memo(move || render_state.read().preload_images.clone());
+    let images = images.read();
+
+    if images.is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "vn-preload",
+            for path in images.iter() {
+                img { key: "{path}", src: "{UiAssetPaths::asset_url(path)}", alt: "" }
+            }
+        }
+    }
+}
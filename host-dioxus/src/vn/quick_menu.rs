@@ -14,8 +14,11 @@ pub fn QuickMenu(render_state: Signal<RenderState>) -> Element {
     let app_state = use_context::<AppState>();
     let rs = render_state.read();
 
-    // 仅在 InGame + ADV 模式 + ui_visible 时显示（NVL 模式无 quick menu）
-    if rs.host_screen != HostScreen::InGame || rs.text_mode == TextMode::NVL || !rs.ui_visible {
+    // 仅在 InGame + ADV 模式 + 对话框可见时显示（NVL 模式无 quick menu）
+    if rs.host_screen != HostScreen::InGame
+        || rs.text_mode == TextMode::NVL
+        || !rs.textbox_visible()
+    {
         return rsx! {};
     }
 
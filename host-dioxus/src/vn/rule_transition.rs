@@ -1,12 +1,8 @@
 use dioxus::prelude::*;
 
+use crate::layout_config::UiAssetPaths;
 use crate::render_state::{RenderState, SceneTransitionKind, SceneTransitionPhaseState};
 
-/// 资源 URL 构建（Windows wry 格式）
-fn asset_url(path: &str) -> String {
-    format!("http://ring-asset.localhost/{path}")
-}
-
 /// Rule 遮罩过渡组件：使用 WebGL shader 实现遮罩过渡。
 ///
 /// 架构：
@@ -37,7 +33,7 @@ pub fn RuleTransitionCanvas(render_state: Signal<RenderState>) -> Element {
         return rsx! {};
     }
 
-    let mask_url = asset_url(&mask_path);
+    let mask_url = UiAssetPaths::asset_url(&mask_path);
 
     // 初始化 WebGL（仅首次 mount）
     use_effect({
@@ -0,0 +1,39 @@
+use dioxus::prelude::*;
+
+use crate::render_state::RenderState;
+
+/// 手柄震动桥接组件：监听 `RenderState::rumble`，通过 Gamepad API 驱动震动。
+///
+/// 不渲染任何 DOM。无手柄或 WebView 不支持 `vibrationActuator` 时静默忽略。
+/// mount 时记录当前请求 id，避免重新挂载后重放旧请求。
+#[component]
+pub fn RumbleBridge(render_state: Signal<RenderState>) -> Element {
+    let mut last_id = use_signal(|| render_state.peek().rumble.as_ref().map(|r| r.id));
+
+    let rumble = render_state.read().rumble.clone();
+    if let Some(req) = rumble
+        && *last_id.peek() != Some(req.id)
+    {
+        last_id.set(Some(req.id));
+        let duration_ms = (req.duration * 1000.0).round() as u32;
+        let magnitude = req.intensity.clamp(0.0, 1.0);
+        document::eval(&format!(
+            r#"
+            (function() {{
+                const pads = navigator.getGamepads ? navigator.getGamepads() : [];
+                for (const pad of pads) {{
+                    if (pad && pad.vibrationActuator) {{
+                        pad.vibrationActuator.playEffect("dual-rumble", {{
+                            duration: {duration_ms},
+                            strongMagnitude: {magnitude},
+                            weakMagnitude: {magnitude},
+                        }}).catch(() => {{}});
+                    }}
+                }}
+            }})();
+            "#
+        ));
+    }
+
+    rsx! {}
+}
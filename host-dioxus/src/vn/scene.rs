@@ -5,6 +5,8 @@ use crate::state::AppState;
 
 use super::audio_bridge::AudioBridge;
 use super::background::BackgroundLayer;
+use super::bubble::BubbleLayer;
+use super::cg::CgLayer;
 use super::chapter_mark::ChapterMark;
 use super::character::CharacterLayer;
 use super::choice::ChoicePanel;
@@ -12,11 +14,14 @@ use super::dialogue::DialogueBox;
 use super::map_overlay::MapOverlay;
 use super::minigame_overlay::MinigameOverlay;
 use super::nvl::NvlPanel;
+use super::preload::PreloadLayer;
 use super::quick_menu::QuickMenu;
 use super::rule_transition::RuleTransitionCanvas;
+use super::rumble::RumbleBridge;
 use super::title_card::TitleCard;
 use super::transition::TransitionOverlay;
 use super::video::VideoOverlay;
+use super::weather::WeatherOverlay;
 
 /// VN 场景容器：组合背景、立绘、对话框等子层。
 ///
@@ -28,7 +33,7 @@ pub fn VNScene(render_state: Signal<RenderState>) -> Element {
 
     let rs = render_state.read();
 
-    // 场景效果：shake 偏移 + blur + dim
+    // 场景效果：shake 偏移 + blur + 整帧滤镜 + dim
     let se = &rs.scene_effect;
     let scene_transform = if se.shake_offset_x != 0.0 || se.shake_offset_y != 0.0 {
         format!(
@@ -38,10 +43,17 @@ pub fn VNScene(render_state: Signal<RenderState>) -> Element {
     } else {
         String::new()
     };
-    let scene_filter = if se.blur_amount > 0.0 {
-        format!("filter: blur({}px);", se.blur_amount)
-    } else {
+    let mut filters = Vec::new();
+    if se.blur_amount > 0.0 {
+        filters.push(format!("blur({}px)", se.blur_amount));
+    }
+    if let Some(filter) = rs.screen_filter.as_ref() {
+        filters.push(filter.css());
+    }
+    let scene_filter = if filters.is_empty() {
         String::new()
+    } else {
+        format!("filter: {};", filters.join(" "))
     };
     let scene_style = format!("{scene_transform} {scene_filter}");
 
@@ -60,7 +72,7 @@ pub fn VNScene(render_state: Signal<RenderState>) -> Element {
             class: "vn-scene{skip_class}",
             onclick: move |_| {
                 if let Ok(mut inner) = app_state.inner.lock() {
-                    inner.process_click();
+                    inner.process_mouse_click(crate::now_millis());
                 }
             },
 
@@ -71,6 +83,9 @@ pub fn VNScene(render_state: Signal<RenderState>) -> Element {
 
                 BackgroundLayer { render_state }
                 CharacterLayer { render_state }
+                BubbleLayer { render_state }
+                CgLayer { render_state }
+                WeatherOverlay { render_state }
                 TransitionOverlay { render_state }
                 RuleTransitionCanvas { render_state }
             }
@@ -93,6 +108,8 @@ pub fn VNScene(render_state: Signal<RenderState>) -> Element {
             MinigameOverlay { render_state }
             QuickMenu { render_state }
             AudioBridge { render_state }
+            PreloadLayer { render_state }
+            RumbleBridge { render_state }
         }
     }
 }
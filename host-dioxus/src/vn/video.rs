@@ -1,13 +1,9 @@
 use dioxus::prelude::*;
 
+use crate::layout_config::UiAssetPaths;
 use crate::render_state::RenderState;
 use crate::state::AppState;
 
-/// 资源 URL 构建
-fn asset_url(path: &str) -> String {
-    format!("http://ring-asset.localhost/{path}")
-}
-
 /// 视频覆盖层：HTML5 `<video>` 播放 cutscene。
 ///
 /// 点击或视频结束时调用 `finish_cutscene()`。
@@ -22,7 +18,7 @@ pub fn VideoOverlay(render_state: Signal<RenderState>) -> Element {
         _ => return rsx! {},
     };
 
-    let video_url = asset_url(&cutscene_state.video_path);
+    let video_url = UiAssetPaths::asset_url(&cutscene_state.video_path);
     let app_click = app_state.clone();
     let app_ended = app_state.clone();
 
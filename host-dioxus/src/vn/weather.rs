@@ -0,0 +1,46 @@
+use dioxus::prelude::*;
+use vn_runtime::WeatherKind;
+
+use crate::render_state::RenderState;
+
+/// 天气粒子覆盖层：按 `RenderState::weather` 中的归一化坐标绘制雨/雪粒子。
+///
+/// 粒子数值由宿主 tick 推进，这里只负责映射到百分比定位。
+#[component]
+pub fn WeatherOverlay(render_state: Signal<RenderState>) -> Element {
+    let rs = render_state.read();
+    let Some(weather) = rs.weather.as_ref() else {
+        return rsx! {};
+    };
+
+    let particle_class = match weather.kind {
+        WeatherKind::Rain => "vn-weather__particle vn-weather__particle--rain",
+        WeatherKind::Snow => "vn-weather__particle vn-weather__particle--snow",
+        WeatherKind::None => return rsx! {},
+    };
+
+    rsx! {
+        div { class: "vn-weather",
+            for (i, p) in weather.particles.iter().enumerate() {
+                {
+                    let left = p.x * 100.0;
+                    let top = p.y * 100.0;
+                    let size = p.size * 100.0;
+                    let style = match weather.kind {
+                        // 雨：细长线条，长度随 size 变化
+                        WeatherKind::Rain => format!(
+                            "left: {left:.2}%; top: {top:.2}%; height: {size:.2}%;"
+                        ),
+                        // 雪：圆点，直径按屏宽百分比
+                        _ => format!(
+                            "left: {left:.2}%; top: {top:.2}%; width: {size:.2}vw; height: {size:.2}vw;"
+                        ),
+                    };
+                    rsx! {
+                        div { key: "{i}", class: particle_class, style: "{style}" }
+                    }
+                }
+            }
+        }
+    }
+}
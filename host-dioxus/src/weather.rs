@@ -0,0 +1,182 @@
+//! 天气粒子系统
+//!
+//! 基于时间推进的简单粒子数组，坐标归一化到屏幕 `0.0..1.0`。
+//! 逻辑侧只负责数值更新，前端 `WeatherOverlay` 按坐标绘制。
+
+use serde::Serialize;
+use vn_runtime::command::WeatherKind;
+
+/// 雨在强度 1.0 时的粒子数
+pub const RAIN_MAX_PARTICLES: usize = 200;
+/// 雪在强度 1.0 时的粒子数
+pub const SNOW_MAX_PARTICLES: usize = 120;
+
+/// 单个粒子（位置与速度均为屏幕归一化单位）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct WeatherParticle {
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub size: f32,
+}
+
+/// 天气覆盖层状态
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeatherState {
+    pub kind: WeatherKind,
+    pub intensity: f32,
+    pub particles: Vec<WeatherParticle>,
+    /// xorshift 随机状态（固定种子，保证数值可复现）
+    #[serde(skip)]
+    rng: u64,
+}
+
+impl WeatherState {
+    /// 创建天气并在全屏范围内铺满初始粒子；`kind` 为 `None` 时不生成粒子
+    pub fn new(kind: WeatherKind, intensity: f32) -> Self {
+        let mut state = Self {
+            kind,
+            intensity: intensity.clamp(0.0, 1.0),
+            particles: Vec::new(),
+            rng: 0x9E37_79B9_7F4A_7C15,
+        };
+        let count = state.target_count();
+        state.particles = (0..count)
+            .map(|_| {
+                let y = state.next_f32();
+                state.spawn(y)
+            })
+            .collect();
+        state
+    }
+
+    /// 当前强度对应的粒子数
+    pub fn target_count(&self) -> usize {
+        let max = match self.kind {
+            WeatherKind::None => 0,
+            WeatherKind::Rain => RAIN_MAX_PARTICLES,
+            WeatherKind::Snow => SNOW_MAX_PARTICLES,
+        };
+        (max as f32 * self.intensity).round() as usize
+    }
+
+    /// 按 `dt` 秒推进粒子；落出屏幕底部的粒子从顶部重新生成
+    pub fn update(&mut self, dt: f32) {
+        for i in 0..self.particles.len() {
+            let mut p = self.particles[i];
+            p.x += p.vx * dt;
+            p.y += p.vy * dt;
+            if p.y > 1.0 {
+                p = self.spawn(0.0);
+                p.y = -p.size;
+            }
+            p.x = p.x.rem_euclid(1.0);
+            if p.x >= 1.0 {
+                // rem_euclid 对极小负数可能舍入为 1.0
+                p.x = 0.0;
+            }
+            self.particles[i] = p;
+        }
+    }
+
+    fn spawn(&mut self, y: f32) -> WeatherParticle {
+        let x = self.next_f32();
+        let r = self.next_f32();
+        match self.kind {
+            WeatherKind::Rain => WeatherParticle {
+                x,
+                y,
+                vx: -0.08,
+                vy: 1.2 + 0.6 * r,
+                size: 0.02 + 0.02 * r,
+            },
+            WeatherKind::Snow | WeatherKind::None => WeatherParticle {
+                x,
+                y,
+                vx: (r - 0.5) * 0.1,
+                vy: 0.08 + 0.07 * r,
+                size: 0.004 + 0.006 * r,
+            },
+        }
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn particle_count_scales_with_intensity() {
+        assert_eq!(
+            WeatherState::new(WeatherKind::Rain, 1.0).particles.len(),
+            200
+        );
+        assert_eq!(
+            WeatherState::new(WeatherKind::Rain, 0.5).particles.len(),
+            100
+        );
+        assert_eq!(
+            WeatherState::new(WeatherKind::Snow, 0.25).particles.len(),
+            30
+        );
+        assert!(
+            WeatherState::new(WeatherKind::None, 1.0)
+                .particles
+                .is_empty()
+        );
+        // 强度钳制到 0~1
+        assert_eq!(
+            WeatherState::new(WeatherKind::Snow, 3.0).particles.len(),
+            120
+        );
+    }
+
+    #[test]
+    fn update_moves_particles_by_velocity() {
+        let mut weather = WeatherState::new(WeatherKind::Snow, 0.5);
+        // 取一个不会在本帧越界的粒子
+        weather.particles[0] = WeatherParticle {
+            x: 0.5,
+            y: 0.2,
+            vx: 0.1,
+            vy: 0.1,
+            size: 0.005,
+        };
+        weather.update(0.5);
+        let p = weather.particles[0];
+        assert!((p.x - 0.55).abs() < 1e-6);
+        assert!((p.y - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn particles_wrap_and_stay_in_bounds() {
+        let mut weather = WeatherState::new(WeatherKind::Rain, 1.0);
+        weather.particles[0] = WeatherParticle {
+            x: 0.01,
+            y: 0.99,
+            vx: -0.08,
+            vy: 1.5,
+            size: 0.03,
+        };
+        weather.update(0.1);
+        // 落出底部后从顶部重生
+        assert!(weather.particles[0].y < 0.0);
+
+        for _ in 0..120 {
+            weather.update(1.0 / 30.0);
+        }
+        assert_eq!(weather.particles.len(), 200);
+        for p in &weather.particles {
+            assert!((0.0..1.0).contains(&p.x), "x out of range: {}", p.x);
+            assert!(p.y <= 1.0, "y out of range: {}", p.y);
+        }
+    }
+}
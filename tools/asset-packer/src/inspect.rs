@@ -1,5 +1,7 @@
 //! ZIP 内容查看与完整性验证
 
+use crate::manifest::{AssetManifest, manifest_path_for};
+use crate::optimize::is_optimized_copy;
 use crate::utils::format_size;
 use anyhow::{Context, Result, bail};
 use std::fs::File;
@@ -48,9 +50,18 @@ pub fn list_zip(zip_path: &Path) -> Result<()> {
 }
 
 /// 验证 ZIP 文件的完整性，可选与原始目录对比内容
-pub fn verify_zip(zip_path: &Path, input: Option<&Path>) -> Result<()> {
+///
+/// 指定 `input` 时逐文件与原始目录对比，`--optimize-images` 优化过的图片按
+/// [`is_optimized_copy`] 判断；指定 `manifest` 时另按清单校验路径/大小/哈希，
+/// 两者可同时使用。未指定原始目录时，若 ZIP 同目录存在 `assets.manifest.json` 也会自动使用。
+pub fn verify_zip(zip_path: &Path, input: Option<&Path>, manifest: Option<&Path>) -> Result<()> {
     println!("验证 ZIP: {:?}", zip_path);
 
+    let default_manifest = manifest_path_for(zip_path);
+    let manifest_path = manifest.or_else(|| {
+        (input.is_none() && default_manifest.exists()).then_some(default_manifest.as_path())
+    });
+
     let file =
         File::open(zip_path).with_context(|| format!("无法打开 ZIP 文件: {:?}", zip_path))?;
     let mut archive = ZipArchive::new(file)?;
@@ -72,13 +83,18 @@ pub fn verify_zip(zip_path: &Path, input: Option<&Path>) -> Result<()> {
             if source.exists() {
                 let mut src_buf = Vec::new();
                 File::open(&source)?.read_to_end(&mut src_buf)?;
-                if buf != src_buf {
+                if buf != src_buf && !is_optimized_copy(&name, &buf, &src_buf) {
                     errors.push(format!("{name}: 内容与源文件不一致"));
                 }
             }
         }
     }
 
+    if let Some(path) = manifest_path {
+        println!("使用资源清单: {:?}", path);
+        errors.extend(AssetManifest::load(path)?.check_zip(zip_path)?);
+    }
+
     if errors.is_empty() {
         println!("验证通过！共 {} 个文件", archive.len());
         Ok(())
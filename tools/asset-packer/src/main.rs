@@ -8,8 +8,10 @@
 //! # 在项目根目录使用 cargo 运行
 //! cargo run -p asset-packer
 //! cargo run -p asset-packer -- --input assets --output game.zip
+//! cargo run -p asset-packer -- --optimize-images --jpeg-quality 85
 //! cargo run -p asset-packer -- list game.zip
 //! cargo run -p asset-packer -- verify game.zip --input assets
+//! cargo run -p asset-packer -- verify game.zip --manifest assets.manifest.json
 //! cargo run -p asset-packer -- release
 //! cargo run -p asset-packer -- release --output-dir dist --zip
 //!
@@ -17,13 +19,17 @@
 //! cargo install --path tools/asset-packer
 //! packer
 //! packer --input assets --output game.zip
+//! packer --optimize-images
 //! packer list game.zip
 //! packer verify game.zip --input assets
+//! packer verify game.zip --manifest assets.manifest.json
 //! packer release
 //! packer release --output-dir dist --zip
 //! ```
 
 mod inspect;
+mod manifest;
+mod optimize;
 mod pack;
 mod release;
 mod utils;
@@ -47,6 +53,19 @@ struct Cli {
     /// 输出 ZIP 文件（默认：game.zip）
     #[arg(short, long, default_value = "game.zip", global = true)]
     output: PathBuf,
+
+    /// 打包前优化图片：PNG 无损重压缩，失败时保留原图
+    #[arg(long, global = true)]
+    optimize_images: bool,
+
+    /// 配合 --optimize-images 按指定质量（1~100）重编码 JPEG（有损）
+    #[arg(
+        long,
+        global = true,
+        requires = "optimize_images",
+        value_parser = clap::value_parser!(u8).range(1..=100)
+    )]
+    jpeg_quality: Option<u8>,
 }
 
 #[derive(Subcommand)]
@@ -65,6 +84,10 @@ enum Commands {
         /// 原始目录（用于对比）
         #[arg(short, long)]
         input: Option<PathBuf>,
+
+        /// 资源清单（默认：ZIP 同目录的 assets.manifest.json，未指定 --input 时自动使用）
+        #[arg(short, long)]
+        manifest: Option<PathBuf>,
     },
 
     /// 创建完整发行版
@@ -91,15 +114,20 @@ fn main() {
 
 fn run() -> Result<()> {
     let cli = Cli::parse();
+    let images = cli.optimize_images.then_some(optimize::ImageOptions {
+        jpeg_quality: cli.jpeg_quality,
+    });
 
     match cli.command {
-        None => pack::pack_assets(&cli.input, &cli.output),
+        None => pack::pack_assets(&cli.input, &cli.output, images.as_ref()),
         Some(Commands::List { zip_file }) => inspect::list_zip(&zip_file),
-        Some(Commands::Verify { zip_file, input }) => {
-            inspect::verify_zip(&zip_file, input.as_deref())
-        }
+        Some(Commands::Verify {
+            zip_file,
+            input,
+            manifest,
+        }) => inspect::verify_zip(&zip_file, input.as_deref(), manifest.as_deref()),
         Some(Commands::Release { output_dir, zip }) => {
-            release::create_release(&cli.input, &cli.output, &output_dir, zip)
+            release::create_release(&cli.input, &cli.output, &output_dir, zip, images.as_ref())
         }
     }
 }
@@ -0,0 +1,183 @@
+//! 资源清单（`assets.manifest.json`）
+//!
+//! 打包后根据 ZIP 实际内容生成，列出每个文件的路径、大小与 SHA-256，
+//! 供发布后的完整性校验或增量更新使用。`verify` 可仅凭清单校验 ZIP，
+//! 不需要原始资源目录。
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// 清单文件名（与资源 ZIP 放在同一目录）
+pub const MANIFEST_FILE: &str = "assets.manifest.json";
+
+/// 清单格式版本
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// 清单条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// ZIP 内路径（`/` 分隔）
+    pub path: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 内容 SHA-256（小写十六进制）
+    pub sha256: String,
+}
+
+/// 资源清单
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetManifest {
+    pub version: u32,
+    /// 按路径排序的文件列表
+    pub files: Vec<ManifestEntry>,
+}
+
+/// 给定 ZIP 对应的清单路径
+pub fn manifest_path_for(zip_path: &Path) -> PathBuf {
+    zip_path.with_file_name(MANIFEST_FILE)
+}
+
+/// 计算内容哈希
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 读取 ZIP 全部文件条目（路径 -> 内容）
+fn read_zip_entries(zip_path: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let file =
+        File::open(zip_path).with_context(|| format!("无法打开 ZIP 文件: {:?}", zip_path))?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut entries = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let mut buf = Vec::new();
+        entry
+            .read_to_end(&mut buf)
+            .with_context(|| format!("{}: 读取失败", entry.name()))?;
+        entries.insert(entry.name().to_string(), buf);
+    }
+    Ok(entries)
+}
+
+impl AssetManifest {
+    /// 根据 ZIP 实际内容生成清单
+    pub fn from_zip(zip_path: &Path) -> Result<Self> {
+        let files = read_zip_entries(zip_path)?
+            .into_iter()
+            .map(|(path, data)| ManifestEntry {
+                size: data.len() as u64,
+                sha256: sha256_hex(&data),
+                path,
+            })
+            .collect();
+        Ok(Self {
+            version: MANIFEST_VERSION,
+            files,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("无法读取清单文件: {:?}", path))?;
+        serde_json::from_str(&content).with_context(|| format!("清单格式无效: {:?}", path))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content).with_context(|| format!("无法写入清单文件: {:?}", path))
+    }
+
+    /// 用清单校验 ZIP，返回发现的问题（为空表示一致）
+    pub fn check_zip(&self, zip_path: &Path) -> Result<Vec<String>> {
+        let mut actual = read_zip_entries(zip_path)?;
+        let mut problems = Vec::new();
+
+        for expected in &self.files {
+            let Some(data) = actual.remove(&expected.path) else {
+                problems.push(format!("{}: ZIP 中缺失", expected.path));
+                continue;
+            };
+            if data.len() as u64 != expected.size {
+                problems.push(format!(
+                    "{}: 大小不一致（清单 {}，实际 {}）",
+                    expected.path,
+                    expected.size,
+                    data.len()
+                ));
+            } else if sha256_hex(&data) != expected.sha256 {
+                problems.push(format!("{}: 内容哈希不一致", expected.path));
+            }
+        }
+        for extra in actual.keys() {
+            problems.push(format!("{extra}: 不在清单中"));
+        }
+
+        Ok(problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::pack_directory;
+
+    fn write_assets(dir: &Path) {
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::write(dir.join("scripts/main.md"), "：\"你好。\"\n").unwrap();
+        std::fs::write(dir.join("bg.png"), [0u8, 1, 2, 3]).unwrap();
+    }
+
+    #[test]
+    fn manifest_lists_every_packed_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let assets = tmp.path().join("assets");
+        write_assets(&assets);
+        let zip_path = tmp.path().join("game.zip");
+        pack_directory(&assets, &zip_path).unwrap();
+
+        let manifest = AssetManifest::from_zip(&zip_path).unwrap();
+        let paths: Vec<_> = manifest.files.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["bg.png", "scripts/main.md"]);
+
+        let bg = &manifest.files[0];
+        assert_eq!(bg.size, 4);
+        assert_eq!(bg.sha256, sha256_hex(&[0, 1, 2, 3]));
+
+        let manifest_path = manifest_path_for(&zip_path);
+        manifest.save(&manifest_path).unwrap();
+        assert_eq!(AssetManifest::load(&manifest_path).unwrap(), manifest);
+        assert!(manifest.check_zip(&zip_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn verify_with_manifest_detects_tampering() {
+        let tmp = tempfile::tempdir().unwrap();
+        let assets = tmp.path().join("assets");
+        write_assets(&assets);
+        let zip_path = tmp.path().join("game.zip");
+        pack_directory(&assets, &zip_path).unwrap();
+        let manifest = AssetManifest::from_zip(&zip_path).unwrap();
+
+        // 同大小改写内容 + 新增文件，重新打包模拟篡改
+        std::fs::write(assets.join("bg.png"), [9u8, 9, 9, 9]).unwrap();
+        std::fs::write(assets.join("extra.txt"), "x").unwrap();
+        pack_directory(&assets, &zip_path).unwrap();
+
+        let problems = manifest.check_zip(&zip_path).unwrap();
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert!(problems[0].starts_with("bg.png: 内容哈希不一致"));
+        assert!(problems[1].starts_with("extra.txt: 不在清单中"));
+    }
+}
@@ -0,0 +1,206 @@
+//! 打包前的图片优化（`--optimize-images`）
+//!
+//! - PNG：oxipng 无损重压缩（去除非必要 chunk、缩减位深/调色板），
+//!   优化结果必须解码为相同像素才会采用
+//! - JPEG：仅在指定 `--jpeg-quality` 时按该质量重编码（有损），尺寸不变才采用
+//!
+//! 任何一步失败或结果没有变小都保留原图，失败原因由调用方记录。
+
+use anyhow::{Context, Result, bail};
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat};
+
+/// 图片优化选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageOptions {
+    /// JPEG 重编码质量（1~100），None 表示不处理 JPEG
+    pub jpeg_quality: Option<u8>,
+}
+
+/// 按扩展名判断可优化的图片格式
+fn image_format(name: &str) -> Option<ImageFormat> {
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some(ImageFormat::Png),
+        "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+        _ => None,
+    }
+}
+
+/// 优化单个图片文件
+///
+/// 返回 `Ok(Some(data))` 表示采用优化结果；`Ok(None)` 表示不适用或没有变小；
+/// `Err` 表示优化失败，调用方应回退原图。
+pub fn optimize_image(name: &str, data: &[u8], options: &ImageOptions) -> Result<Option<Vec<u8>>> {
+    let optimized = match image_format(name) {
+        Some(ImageFormat::Png) => optimize_png(data)?,
+        Some(ImageFormat::Jpeg) => match options.jpeg_quality {
+            Some(quality) => reencode_jpeg(data, quality)?,
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    Ok((optimized.len() < data.len()).then_some(optimized))
+}
+
+fn optimize_png(data: &[u8]) -> Result<Vec<u8>> {
+    let original = decode(data, ImageFormat::Png)?;
+    let options = oxipng::Options {
+        strip: oxipng::StripChunks::Safe,
+        ..oxipng::Options::from_preset(2)
+    };
+    let optimized = oxipng::optimize_from_memory(data, &options).context("PNG 重压缩失败")?;
+
+    let result = decode(&optimized, ImageFormat::Png)?;
+    if original.width() != result.width()
+        || original.height() != result.height()
+        || original.to_rgba16().as_raw() != result.to_rgba16().as_raw()
+    {
+        bail!("PNG 重压缩后像素不一致");
+    }
+    Ok(optimized)
+}
+
+fn reencode_jpeg(data: &[u8], quality: u8) -> Result<Vec<u8>> {
+    let original = decode(data, ImageFormat::Jpeg)?;
+    let mut optimized = Vec::new();
+    JpegEncoder::new_with_quality(&mut optimized, quality)
+        .encode_image(&original)
+        .context("JPEG 重编码失败")?;
+
+    let result = decode(&optimized, ImageFormat::Jpeg)?;
+    if original.width() != result.width() || original.height() != result.height() {
+        bail!("JPEG 重编码后尺寸不一致");
+    }
+    Ok(optimized)
+}
+
+/// 打包内容与源文件字节不同时，判断是否为同一图片的优化版本
+///
+/// PNG 须解码为相同像素（无损）；JPEG 重编码是有损的，只要求尺寸一致。
+/// 非图片或无法解码时返回 false。
+pub fn is_optimized_copy(name: &str, packed: &[u8], source: &[u8]) -> bool {
+    let Some(format) = image_format(name) else {
+        return false;
+    };
+    let (Ok(packed), Ok(source)) = (decode(packed, format), decode(source, format)) else {
+        return false;
+    };
+    let same_size = packed.width() == source.width() && packed.height() == source.height();
+    match format {
+        ImageFormat::Png => same_size && packed.to_rgba16().as_raw() == source.to_rgba16().as_raw(),
+        _ => same_size,
+    }
+}
+
+fn decode(data: &[u8], format: ImageFormat) -> Result<DynamicImage> {
+    image::load_from_memory_with_format(data, format).context("图片解码失败")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+    use image::{ImageEncoder, Rgba, RgbaImage};
+
+    /// 用最低压缩率编码一张只有少量颜色的 PNG，留出优化空间
+    fn bloated_png() -> (RgbaImage, Vec<u8>) {
+        let img = RgbaImage::from_fn(64, 48, |x, y| {
+            if (x / 8 + y / 8) % 2 == 0 {
+                Rgba([200, 40, 40, 255])
+            } else {
+                Rgba([20, 20, 90, 128])
+            }
+        });
+        let mut data = Vec::new();
+        PngEncoder::new_with_quality(&mut data, CompressionType::Fast, FilterType::NoFilter)
+            .write_image(
+                img.as_raw(),
+                img.width(),
+                img.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+            .unwrap();
+        (img, data)
+    }
+
+    #[test]
+    fn optimized_png_decodes_to_same_pixels_and_is_not_larger() {
+        let (img, data) = bloated_png();
+        let optimized = optimize_image("bg/room.PNG", &data, &ImageOptions::default())
+            .unwrap()
+            .expect("bloated PNG should shrink");
+        assert!(optimized.len() <= data.len());
+
+        let decoded = image::load_from_memory(&optimized).unwrap().to_rgba8();
+        assert_eq!(decoded.dimensions(), img.dimensions());
+        assert_eq!(decoded.as_raw(), img.as_raw());
+
+        // 已优化过的图片不会再变大
+        let again = optimize_image("bg/room.png", &optimized, &ImageOptions::default()).unwrap();
+        assert!(again.is_none_or(|d| d.len() < optimized.len()));
+    }
+
+    #[test]
+    fn optimized_copy_is_recognized_against_source() {
+        let (_, data) = bloated_png();
+        let optimized = optimize_image("bg/room.png", &data, &ImageOptions::default())
+            .unwrap()
+            .unwrap();
+        assert!(is_optimized_copy("bg/room.png", &optimized, &data));
+
+        // 像素不同的 PNG、非图片文件都不算
+        let mut other = bloated_png().0;
+        other.put_pixel(0, 0, image::Rgba([0, 0, 0, 255]));
+        let mut other_data = Vec::new();
+        PngEncoder::new(&mut other_data)
+            .write_image(
+                other.as_raw(),
+                other.width(),
+                other.height(),
+                image::ExtendedColorType::Rgba8,
+            )
+            .unwrap();
+        assert!(!is_optimized_copy("bg/room.png", &other_data, &data));
+        assert!(!is_optimized_copy("scripts/main.md", b"a", b"b"));
+    }
+
+    #[test]
+    fn jpeg_is_only_reencoded_with_quality() {
+        let img = DynamicImage::ImageRgba8(bloated_png().0).to_rgb8();
+        let mut data = Vec::new();
+        JpegEncoder::new_with_quality(&mut data, 100)
+            .encode_image(&img)
+            .unwrap();
+
+        assert!(
+            optimize_image("cg.jpg", &data, &ImageOptions::default())
+                .unwrap()
+                .is_none()
+        );
+
+        let options = ImageOptions {
+            jpeg_quality: Some(60),
+        };
+        let optimized = optimize_image("cg.jpg", &data, &options)
+            .unwrap()
+            .expect("lower quality should shrink");
+        assert!(optimized.len() < data.len());
+        let decoded = image::load_from_memory(&optimized).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), img.dimensions());
+    }
+
+    #[test]
+    fn broken_image_fails_and_other_files_are_skipped() {
+        let options = ImageOptions {
+            jpeg_quality: Some(80),
+        };
+        assert!(optimize_image("bg.png", &[0, 1, 2, 3], &options).is_err());
+        assert!(optimize_image("cg.jpeg", b"not a jpeg", &options).is_err());
+        assert!(
+            optimize_image("scripts/main.md", b"png", &options)
+                .unwrap()
+                .is_none()
+        );
+    }
+}
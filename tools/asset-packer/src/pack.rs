@@ -1,5 +1,8 @@
 //! ZIP 打包操作
 
+use crate::manifest::{AssetManifest, manifest_path_for};
+use crate::optimize::{ImageOptions, optimize_image};
+use crate::utils::format_size;
 use anyhow::{Context, Result, bail};
 use std::fs::File;
 use std::io::{Read, Write};
@@ -13,10 +16,18 @@ use zip::{CompressionMethod, ZipWriter};
 pub struct PackStats {
     pub file_count: usize,
     pub total_size: u64,
+    /// 采用了优化结果的图片数
+    pub optimized_count: usize,
+    /// 图片优化节省的字节数
+    pub saved_size: u64,
+    /// 优化失败、回退原图的图片数
+    pub optimize_failures: usize,
 }
 
 /// 将资源目录打包为 ZIP 文件，并打印统计信息
-pub fn pack_assets(input: &Path, output: &Path) -> Result<()> {
+///
+/// `images` 为 Some 时先对图片做优化（见 [`crate::optimize`]）。
+pub fn pack_assets(input: &Path, output: &Path, images: Option<&ImageOptions>) -> Result<()> {
     println!("打包资源目录: {:?} -> {:?}", input, output);
 
     if !input.exists() {
@@ -27,11 +38,14 @@ pub fn pack_assets(input: &Path, output: &Path) -> Result<()> {
     let mut zip = ZipWriter::new(file);
 
     let mut stats = PackStats::default();
-    add_dir_to_zip(input, input, &mut zip, Some(&mut stats))?;
+    add_dir_to_zip(input, input, &mut zip, images, Some(&mut stats))?;
     zip.finish()?;
 
     let zip_size = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
 
+    let manifest_path = manifest_path_for(output);
+    AssetManifest::from_zip(output)?.save(&manifest_path)?;
+
     println!();
     println!("打包完成！");
     println!("   文件数: {}", stats.file_count);
@@ -43,7 +57,16 @@ pub fn pack_assets(input: &Path, output: &Path) -> Result<()> {
         "   ZIP 大小: {:.2} MB（无压缩）",
         zip_size as f64 / 1024.0 / 1024.0,
     );
+    if images.is_some() {
+        println!(
+            "   图片优化: {} 个，节省 {}，失败 {} 个（已使用原图）",
+            stats.optimized_count,
+            format_size(stats.saved_size),
+            stats.optimize_failures
+        );
+    }
     println!("   输出文件: {:?}", output);
+    println!("   资源清单: {:?}", manifest_path);
 
     Ok(())
 }
@@ -52,7 +75,7 @@ pub fn pack_assets(input: &Path, output: &Path) -> Result<()> {
 pub fn pack_directory(input: &Path, output: &Path) -> Result<()> {
     let file = File::create(output)?;
     let mut zip = ZipWriter::new(file);
-    add_dir_to_zip(input, input, &mut zip, None)?;
+    add_dir_to_zip(input, input, &mut zip, None, None)?;
     zip.finish()?;
     Ok(())
 }
@@ -65,6 +88,7 @@ fn add_dir_to_zip(
     root: &Path,
     input: &Path,
     zip: &mut ZipWriter<File>,
+    images: Option<&ImageOptions>,
     mut stats: Option<&mut PackStats>,
 ) -> Result<()> {
     for entry in WalkDir::new(input).into_iter().filter_map(|e| e.ok()) {
@@ -80,6 +104,25 @@ fn add_dir_to_zip(
         File::open(path)?.read_to_end(&mut buf)?;
         let size = buf.len() as u64;
 
+        if let Some(options) = images {
+            match optimize_image(&name, &buf, options) {
+                Ok(Some(optimized)) => {
+                    if let Some(s) = stats.as_deref_mut() {
+                        s.optimized_count += 1;
+                        s.saved_size += size - optimized.len() as u64;
+                    }
+                    buf = optimized;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("  警告: {name} 优化失败，使用原图: {e:#}");
+                    if let Some(s) = stats.as_deref_mut() {
+                        s.optimize_failures += 1;
+                    }
+                }
+            }
+        }
+
         zip.start_file(&name, stored_options())?;
         zip.write_all(&buf)?;
 
@@ -2,6 +2,8 @@
 //!
 //! 将资源打包、编译宿主应用、组装发行版目录。
 
+use crate::manifest::{MANIFEST_FILE, manifest_path_for};
+use crate::optimize::ImageOptions;
 use crate::pack::{pack_assets, pack_directory};
 use crate::utils::{required_file_name, run_command};
 use anyhow::{Result, bail};
@@ -14,7 +16,7 @@ const DEFAULT_GAME_NAME: &str = "Ring";
 /// 创建完整发行版
 ///
 /// 步骤：
-/// 1. 打包 assets -> game.zip
+/// 1. 打包 assets -> game.zip（附带 assets.manifest.json）
 /// 2. `cargo build --release -p host-dioxus` 编译宿主应用
 /// 3. 检查 config.json
 /// 4. 组装发行版目录（并可选打包为 ZIP）
@@ -23,6 +25,7 @@ pub fn create_release(
     zip_output: &Path,
     release_dir: &Path,
     create_zip: bool,
+    images: Option<&ImageOptions>,
 ) -> Result<()> {
     println!("创建发行版...");
     println!();
@@ -35,7 +38,7 @@ pub fn create_release(
     };
 
     println!("步骤 1/4: 打包资源...");
-    pack_assets(assets_dir, zip_output)?;
+    pack_assets(assets_dir, zip_output, images)?;
     println!();
 
     println!("步骤 2/4: 编译宿主应用（release）...");
@@ -101,6 +104,11 @@ fn assemble_release_dir(
     std::fs::rename(zip_output, &zip_dest)?;
     println!("  移动资源包: {:?} -> {:?}", zip_output, zip_dest);
 
+    let manifest_src = manifest_path_for(zip_output);
+    let manifest_dest = release_dir.join(MANIFEST_FILE);
+    std::fs::rename(&manifest_src, &manifest_dest)?;
+    println!("  移动资源清单: {:?} -> {:?}", manifest_src, manifest_dest);
+
     let binary_filename = binary_name(game_name);
     let binary_dest = release_dir.join(&binary_filename);
     std::fs::copy(host_binary, &binary_dest)?;
@@ -119,6 +127,7 @@ fn assemble_release_dir(
     println!("   发行版目录: {:?}", release_dir);
     println!("   包含文件:");
     println!("     - {}", zip_name.to_string_lossy());
+    println!("     - {}", MANIFEST_FILE);
     println!("     - {}", binary_filename);
     println!("     - config.json");
 
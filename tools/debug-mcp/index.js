@@ -103,7 +103,7 @@ server.tool(
   },
 );
 
-server.tool("get_audio", "Get audio state: BGM, SFX", {}, async () => {
+server.tool("get_audio", "Get audio state: BGM, SFX, silent mode", {}, async () => {
   const data = await httpGet("/api/state/audio");
   return { content: [{ type: "text", text: jsonText(data) }] };
 });
@@ -185,6 +185,24 @@ server.tool(
   },
 );
 
+server.tool(
+  "set_breakpoints",
+  "Replace script breakpoints (source lines of the running script and/or label names)",
+  {
+    lines: z.array(z.number().int().min(1)).default([]).describe("Source line numbers (1-based)"),
+    labels: z.array(z.string()).default([]).describe("Label names"),
+  },
+  async ({ lines, labels }) => {
+    const data = await httpPost("/api/breakpoints", { lines, labels });
+    return { content: [{ type: "text", text: jsonText(data) }] };
+  },
+);
+
+server.tool("continue", "Resume script execution after hitting a breakpoint", {}, async () => {
+  const data = await httpPost("/api/continue");
+  return { content: [{ type: "text", text: jsonText(data) }] };
+});
+
 // ── Tools: Screenshot ────────────────────────────────────────────────────────
 
 server.tool(
@@ -233,6 +251,16 @@ server.tool(
   },
 );
 
+server.tool(
+  "check_runtime",
+  "Get runtime performance metrics (tick timing, nodes per tick) and texture/audio cache stats",
+  {},
+  async () => {
+    const data = await httpGet("/api/diag/runtime");
+    return { content: [{ type: "text", text: jsonText(data) }] };
+  },
+);
+
 // ── Start ────────────────────────────────────────────────────────────────────
 
 const transport = new StdioServerTransport();
@@ -7,18 +7,27 @@
 //! - `check-all`: fmt（直接应用）、clippy（自动 fix）、test
 //! - `cov`: 运行 workspace 覆盖率（排除工具 crate 与平台胶水代码）
 //! - `script-check`: 检查脚本文件（语法、label、资源引用）
+//! - `script-cov`: 用选择序列驱动脚本，统计 label 覆盖率
 //! - `mutants`: 运行变异测试（vn-runtime），检测测试质量
 //! - `gen-symbols`: 从 rustdoc JSON 生成符号索引（`docs/engine/symbol-index.md`）
 
 mod gen_symbols;
+mod script_cov;
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::{ExitCode, ExitStatus};
 
 use anyhow::Context;
 use clap::{Args, Parser, Subcommand};
+use vn_runtime::diagnostic::{
+    DEFAULT_MAX_CHOICE_OPTIONS, DEFAULT_MAX_CONDITIONAL_DEPTH, DEFAULT_MAX_SCRIPT_NODES,
+};
 use vn_runtime::{
-    DiagnosticResult, Parser as ScriptParser, analyze_script, extract_resource_references,
+    ChoiceBranchStats, ComplexityLimits, Diagnostic, DiagnosticLevel, DiagnosticResult,
+    Parser as ScriptParser, ReadingTimeEstimate, ScriptIndexEntry, analyze_branch_weights,
+    analyze_script, check_complexity, clean_whitespace, diagnostic_rules, estimate_reading_time,
+    extract_resource_references, lint_whitespace, order_scripts,
 };
 use walkdir::WalkDir;
 use xshell::Shell;
@@ -32,6 +41,7 @@ use xshell::Shell;
   cargo check-all          -> cargo run -p xtask -- check-all
   cargo cov                -> cargo run -p xtask -- cov
   cargo script-check       -> cargo run -p xtask -- script-check
+  cargo script-cov         -> cargo run -p xtask -- script-cov
   cargo mutants-check      -> cargo run -p xtask -- mutants
 "#
 )]
@@ -51,6 +61,9 @@ enum XtaskCommand {
     /// 检查脚本文件（语法、label、资源引用）
     ScriptCheck(ScriptCheckArgs),
 
+    /// 用选择序列驱动脚本执行，统计 label 覆盖率
+    ScriptCov(script_cov::ScriptCovArgs),
+
     /// 运行变异测试（vn-runtime），检测测试质量
     Mutants(MutantsArgs),
 
@@ -107,6 +120,23 @@ struct MutantsArgs {
   - 脚本语法错误
   - 未定义的跳转目标（goto/choice 引用的 label）
   - 资源文件是否存在（背景/立绘/音频）
+  - 脚本体量与复杂度：节点数、条件嵌套深度、单个选择点选项数超过阈值时提示
+
+阈值：
+  - --max-nodes 1500           单脚本节点数上限（超过报 warn）
+  - --max-depth 3              条件嵌套深度上限（超过报 warn）
+  - --max-options 6            单个选择点选项数上限（超过报 info）
+
+过滤：
+  - --level error              只输出错误
+  - --allow missing-resource   屏蔽指定规则（可重复）
+
+统计：
+  - --reading-time             按章节输出阅读时长估算（默认 400 字/分钟）
+  - --reading-time 300         指定阅读速度（字/分钟）
+
+规则 id：undefined-label、missing-resource、parse-warning、
+        script-too-large、deep-nesting、too-many-options
 "#)]
 struct ScriptCheckArgs {
     /// 脚本文件或目录路径（可选）
@@ -119,6 +149,43 @@ struct ScriptCheckArgs {
     /// 资源根目录（用于验证资源引用是否存在）
     #[arg(long, default_value = "assets")]
     assets_root: PathBuf,
+
+    /// 最低输出级别（info / warn / error）
+    #[arg(long, default_value = "info")]
+    level: DiagnosticLevel,
+
+    /// 屏蔽指定规则 id 的诊断（可重复）
+    #[arg(long = "allow", value_name = "RULE")]
+    allow: Vec<String>,
+
+    /// 按章节输出阅读时长估算，可指定阅读速度（字/分钟）
+    #[arg(
+        long,
+        value_name = "CPM",
+        num_args = 0..=1,
+        default_missing_value = "400"
+    )]
+    reading_time: Option<f64>,
+
+    /// 输出各选择点的分支体量（各选项后续可达节点数）
+    #[arg(long)]
+    branch_stats: bool,
+
+    /// 自动清理行尾空白、引号外全角空格与行首制表符（直接改写脚本文件）
+    #[arg(long)]
+    fix_whitespace: bool,
+
+    /// 单脚本节点数上限
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_SCRIPT_NODES)]
+    max_nodes: usize,
+
+    /// 条件嵌套深度上限
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_CONDITIONAL_DEPTH)]
+    max_depth: usize,
+
+    /// 单个选择点选项数上限
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_CHOICE_OPTIONS)]
+    max_options: usize,
 }
 
 fn run(step: &str, sh: &Shell, program: &str, args: &[&str]) -> anyhow::Result<()> {
@@ -237,6 +304,9 @@ fn real_main() -> anyhow::Result<()> {
         XtaskCommand::ScriptCheck(args) => {
             script_check(args)?;
         }
+        XtaskCommand::ScriptCov(args) => {
+            script_cov::script_cov(args)?;
+        }
         XtaskCommand::Mutants(args) => {
             run_mutants(&sh, args)?;
         }
@@ -410,6 +480,14 @@ struct ScriptCheckConfig {
     scripts_dir: PathBuf,
     /// 资源根目录（相对于 workspace root）
     assets_root: PathBuf,
+    /// 阅读速度（字/分钟）；None 时不估算阅读时长
+    reading_speed: Option<f64>,
+    /// 是否统计分支体量
+    branch_stats: bool,
+    /// 是否自动清理空白字符问题
+    fix_whitespace: bool,
+    /// 复杂度阈值
+    complexity: ComplexityLimits,
 }
 
 /// 脚本检查结果
@@ -418,17 +496,37 @@ struct ScriptCheckResult {
     scripts_checked: usize,
     /// 解析错误数量
     parse_errors: usize,
-    /// 诊断结果
+    /// 诊断结果（含解析警告与缺失资源）
     diagnostics: DiagnosticResult,
-    /// 缺失的资源文件
-    missing_resources: Vec<MissingResource>,
+    /// 资源引用次数（resolved_path → 全部脚本中的引用次数）
+    resource_usage: BTreeMap<String, usize>,
+    /// 各脚本的阅读时长估算（启用 `--reading-time` 时）
+    reading_times: Vec<(String, ReadingTimeEstimate)>,
+    /// 各脚本的分支体量统计（启用 `--branch-stats` 时）
+    branch_stats: Vec<(String, Vec<ChoiceBranchStats>)>,
+}
+
+/// 输出中“被引用最多”列出的资源数
+const TOP_REFERENCED_LIMIT: usize = 5;
+
+/// 按引用次数降序（次数相同按路径）取前 `limit` 个资源
+fn top_referenced(usage: &BTreeMap<String, usize>, limit: usize) -> Vec<(&str, usize)> {
+    let mut sorted: Vec<(&str, usize)> = usage
+        .iter()
+        .map(|(path, &count)| (path.as_str(), count))
+        .collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    sorted.truncate(limit);
+    sorted
 }
 
-/// 缺失的资源信息
-struct MissingResource {
-    script_id: String,
-    resource_type: String,
-    path: String,
+/// 只被引用一次的资源（按路径排序）
+fn single_referenced(usage: &BTreeMap<String, usize>) -> Vec<&str> {
+    usage
+        .iter()
+        .filter(|&(_, &count)| count == 1)
+        .map(|(path, _)| path.as_str())
+        .collect()
 }
 
 /// 执行脚本检查
@@ -436,6 +534,14 @@ fn script_check(args: ScriptCheckArgs) -> anyhow::Result<()> {
     let config = ScriptCheckConfig {
         scripts_dir: args.scripts_dir,
         assets_root: args.assets_root,
+        reading_speed: args.reading_time,
+        branch_stats: args.branch_stats,
+        fix_whitespace: args.fix_whitespace,
+        complexity: ComplexityLimits {
+            max_nodes: args.max_nodes,
+            max_conditional_depth: args.max_depth,
+            max_choice_options: args.max_options,
+        },
     };
 
     // 确定要检查的文件
@@ -448,7 +554,7 @@ fn script_check(args: ScriptCheckArgs) -> anyhow::Result<()> {
                     anyhow::bail!("仅支持 .md 脚本文件: {}", p.display());
                 }
             } else if p.is_dir() {
-                collect_script_files(&p)?
+                order_by_manifest(collect_script_files(&p)?, &config.assets_root)?
             } else {
                 anyhow::bail!("路径不存在: {}", p.display());
             }
@@ -460,7 +566,10 @@ fn script_check(args: ScriptCheckArgs) -> anyhow::Result<()> {
                     config.scripts_dir.display()
                 );
             }
-            collect_script_files(&config.scripts_dir)?
+            order_by_manifest(
+                collect_script_files(&config.scripts_dir)?,
+                &config.assets_root,
+            )?
         }
     };
 
@@ -475,7 +584,9 @@ fn script_check(args: ScriptCheckArgs) -> anyhow::Result<()> {
         scripts_checked: 0,
         parse_errors: 0,
         diagnostics: DiagnosticResult::new(),
-        missing_resources: Vec::new(),
+        resource_usage: BTreeMap::new(),
+        reading_times: Vec::new(),
+        branch_stats: Vec::new(),
     };
 
     // 检查每个脚本
@@ -483,6 +594,10 @@ fn script_check(args: ScriptCheckArgs) -> anyhow::Result<()> {
         check_script_file(file, &config, &mut result)?;
     }
 
+    // 按级别与规则过滤
+    result.diagnostics.retain_min_level(args.level);
+    result.diagnostics.suppress_rules(&args.allow);
+
     // 输出结果
     print_check_result(&result);
 
@@ -507,6 +622,45 @@ fn collect_script_files(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// 按 `manifest.json` 中的脚本包含表排列脚本（没有包含表时保持字母序）
+fn order_by_manifest(files: Vec<PathBuf>, assets_root: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let manifest_path = assets_root.join("manifest.json");
+    if !manifest_path.exists() {
+        return Ok(files);
+    }
+    let content = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("无法读取 {}", manifest_path.display()))?;
+    let manifest: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("无法解析 {}", manifest_path.display()))?;
+    let declared: Vec<ScriptIndexEntry> = match manifest.get("scripts") {
+        Some(scripts) => serde_json::from_value(scripts.clone())
+            .with_context(|| format!("{} 的 scripts 格式错误", manifest_path.display()))?,
+        None => return Ok(files),
+    };
+    Ok(order_script_files(files, &declared, assets_root))
+}
+
+/// 按包含表排列脚本文件：声明的按声明顺序在前，未声明的按路径字母序追加在后
+fn order_script_files(
+    files: Vec<PathBuf>,
+    declared: &[ScriptIndexEntry],
+    assets_root: &Path,
+) -> Vec<PathBuf> {
+    let mut by_key = BTreeMap::new();
+    for file in files {
+        let key = file
+            .strip_prefix(assets_root)
+            .map(|relative| normalize_path(relative.to_string_lossy().as_ref()))
+            .unwrap_or_else(|_| normalize_path(file.to_string_lossy().as_ref()));
+        by_key.insert(key, file);
+    }
+    let keys: Vec<String> = by_key.keys().cloned().collect();
+    order_scripts(declared, keys)
+        .into_iter()
+        .filter_map(|script| by_key.remove(&script.path))
+        .collect()
+}
+
 /// 检查单个脚本文件
 fn check_script_file(
     file: &Path,
@@ -517,7 +671,7 @@ fn check_script_file(
     result.scripts_checked += 1;
 
     // 读取文件
-    let content = match std::fs::read_to_string(file) {
+    let mut content = match std::fs::read_to_string(file) {
         Ok(c) => c,
         Err(e) => {
             eprintln!("[ERROR] {}: 无法读取文件 - {}", script_id, e);
@@ -526,39 +680,76 @@ fn check_script_file(
         }
     };
 
+    if config.fix_whitespace {
+        let cleaned = clean_whitespace(&content);
+        if cleaned != content {
+            std::fs::write(file, &cleaned)
+                .with_context(|| format!("无法写入 {}", file.display()))?;
+            eprintln!("[FIX] {}: 已清理空白字符", script_id);
+            content = cleaned;
+        }
+    }
+    result
+        .diagnostics
+        .merge(lint_whitespace(&script_id, &content));
+
     // 计算 base_path（脚本所在目录，相对于 assets_root）
     let base_path = compute_base_path(file, &config.assets_root);
 
     // 解析脚本
     let mut parser = ScriptParser::new();
-    let script = match parser.parse_with_base_path(&script_id, &content, &base_path) {
-        Ok(s) => s,
-        Err(e) => {
+    let (script, errors) = parser.parse_collecting_errors(&script_id, &content, &base_path);
+    if !errors.is_empty() {
+        for e in &errors {
             eprintln!("[ERROR] {}: {}", script_id, e);
-            result.parse_errors += 1;
-            return Ok(());
         }
-    };
+        result.parse_errors += errors.len();
+        return Ok(());
+    }
 
-    // 输出解析警告
+    // 收集解析警告
     for warning in parser.warnings() {
-        eprintln!("[WARN] {}: {}", script_id, warning);
+        result
+            .diagnostics
+            .push(Diagnostic::warn(&script_id, warning).with_rule(diagnostic_rules::PARSE_WARNING));
     }
 
     // 运行诊断分析
     let diag = analyze_script(&script);
     result.diagnostics.merge(diag);
+    result
+        .diagnostics
+        .merge(check_complexity(&script, &config.complexity));
+
+    if let Some(speed) = config.reading_speed {
+        result
+            .reading_times
+            .push((script_id.clone(), estimate_reading_time(&script, speed)));
+    }
 
-    // 检查资源引用
+    if config.branch_stats {
+        let stats = analyze_branch_weights(&script);
+        if !stats.is_empty() {
+            result.branch_stats.push((script_id.clone(), stats));
+        }
+    }
+
+    // 检查资源引用（已按 resolved_path 去重，每个资源只 stat 一次）
     let refs = extract_resource_references(&script);
     for r in refs {
+        *result
+            .resource_usage
+            .entry(r.resolved_path.clone())
+            .or_default() += r.count;
         let resource_path = config.assets_root.join(&r.resolved_path);
         if !resource_path.exists() {
-            result.missing_resources.push(MissingResource {
-                script_id: script_id.clone(),
-                resource_type: format!("{}", r.resource_type),
-                path: r.resolved_path,
-            });
+            result.diagnostics.push(
+                Diagnostic::warn(
+                    &script_id,
+                    format!("资源不存在 [{}] {}", r.resource_type, r.resolved_path),
+                )
+                .with_rule(diagnostic_rules::MISSING_RESOURCE),
+            );
         }
     }
 
@@ -601,17 +792,13 @@ fn print_check_result(result: &ScriptCheckResult) {
         eprintln!("{}", diag);
     }
 
-    // 输出缺失资源
-    for mr in &result.missing_resources {
-        eprintln!(
-            "[WARN] {}: 资源不存在 [{}] {}",
-            mr.script_id, mr.resource_type, mr.path
-        );
-    }
+    print_resource_usage(&result.resource_usage);
+    print_reading_times(&result.reading_times);
+    print_branch_stats(&result.branch_stats);
 
     // 汇总
     let error_count = result.parse_errors + result.diagnostics.error_count();
-    let warn_count = result.diagnostics.warn_count() + result.missing_resources.len();
+    let warn_count = result.diagnostics.warn_count();
 
     eprintln!();
     if error_count > 0 {
@@ -623,9 +810,193 @@ fn print_check_result(result: &ScriptCheckResult) {
     }
 }
 
+/// 输出各脚本按章节的阅读时长估算
+fn print_reading_times(reading_times: &[(String, ReadingTimeEstimate)]) {
+    let Some((_, first)) = reading_times.first() else {
+        return;
+    };
+
+    eprintln!();
+    eprintln!("阅读时长估算（{} 字/分钟）:", first.chars_per_minute);
+    for (script_id, estimate) in reading_times {
+        eprintln!(
+            "  {script_id}: {} 字，约 {}",
+            estimate.total_chars,
+            format_reading_duration(estimate.total_seconds)
+        );
+        for chapter in &estimate.chapters {
+            eprintln!(
+                "    {}: {} 字，约 {}",
+                chapter.title.as_deref().unwrap_or("（开头）"),
+                chapter.chars,
+                format_reading_duration(chapter.seconds)
+            );
+        }
+    }
+}
+
+/// 输出各选择点的分支体量，失衡的选择点加标记
+fn print_branch_stats(branch_stats: &[(String, Vec<ChoiceBranchStats>)]) {
+    if branch_stats.is_empty() {
+        return;
+    }
+
+    eprintln!();
+    eprintln!("分支体量（各选项后续可达节点数）:");
+    for (script_id, choices) in branch_stats {
+        eprintln!("  {script_id}:");
+        for choice in choices {
+            eprintln!("    {}", format_choice_branch_stats(choice));
+        }
+    }
+}
+
+/// 单个选择点的一行摘要：`第 N 行: 选项A → a (12), 选项B → b (3) ⚠ 失衡`
+fn format_choice_branch_stats(choice: &ChoiceBranchStats) -> String {
+    let location = match choice.line {
+        Some(line) => format!("第 {line} 行"),
+        None => format!("节点 #{}", choice.node_index),
+    };
+    let options: Vec<String> = choice
+        .options
+        .iter()
+        .map(|o| format!("{} → {} ({})", o.text, o.target_label, o.reachable_nodes))
+        .collect();
+    let mut line = format!("{location}: {}", options.join(", "));
+    if choice.is_unbalanced() {
+        line.push_str(" ⚠ 失衡");
+    }
+    line
+}
+
+/// 秒数格式化为 `X 小时 Y 分` / `Y 分 Z 秒`
+fn format_reading_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, secs) = (total / 3600, total % 3600 / 60, total % 60);
+    if hours > 0 {
+        format!("{hours} 小时 {minutes} 分")
+    } else {
+        format!("{minutes} 分 {secs} 秒")
+    }
+}
+
+/// 输出资源引用统计：被引用最多的资源与只被引用一次的资源
+fn print_resource_usage(usage: &BTreeMap<String, usize>) {
+    if usage.is_empty() {
+        return;
+    }
+
+    eprintln!();
+    eprintln!("资源引用: {} 个资源", usage.len());
+    eprintln!("被引用最多:");
+    for (path, count) in top_referenced(usage, TOP_REFERENCED_LIMIT) {
+        eprintln!("  {count:>4} × {path}");
+    }
+    let single = single_referenced(usage);
+    eprintln!("唯一引用（仅 1 次）: {} 个", single.len());
+    for path in single {
+        eprintln!("  {path}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{build_mutants_command_args, cargo_mutants_runs_in_place};
+    use clap::Parser;
+    use vn_runtime::{
+        DiagnosticLevel, Parser as ScriptParser, ScriptIndexEntry, analyze_branch_weights,
+    };
+
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    use super::{
+        Cli, XtaskCommand, build_mutants_command_args, cargo_mutants_runs_in_place,
+        format_choice_branch_stats, format_reading_duration, order_script_files, single_referenced,
+        top_referenced,
+    };
+
+    #[test]
+    fn script_check_parses_level_and_allow_rules() {
+        let cli = Cli::try_parse_from([
+            "xtask",
+            "script-check",
+            "--level",
+            "error",
+            "--allow",
+            "missing-resource",
+            "--allow",
+            "parse-warning",
+        ])
+        .unwrap();
+        let XtaskCommand::ScriptCheck(args) = cli.command else {
+            panic!("expected script-check");
+        };
+        assert_eq!(args.level, DiagnosticLevel::Error);
+        assert_eq!(args.allow, vec!["missing-resource", "parse-warning"]);
+
+        assert!(Cli::try_parse_from(["xtask", "script-check", "--level", "fatal"]).is_err());
+    }
+
+    #[test]
+    fn script_check_reading_time_defaults_speed_when_flag_has_no_value() {
+        let reading_time = |argv: &[&str]| {
+            let XtaskCommand::ScriptCheck(args) = Cli::try_parse_from(argv).unwrap().command else {
+                panic!("expected script-check");
+            };
+            args.reading_time
+        };
+        assert_eq!(reading_time(&["xtask", "script-check"]), None);
+        assert_eq!(
+            reading_time(&["xtask", "script-check", "--reading-time"]),
+            Some(400.0)
+        );
+        assert_eq!(
+            reading_time(&["xtask", "script-check", "--reading-time", "300"]),
+            Some(300.0)
+        );
+        assert_eq!(format_reading_duration(125.4), "2 分 5 秒");
+        assert_eq!(format_reading_duration(3720.0), "1 小时 2 分");
+    }
+
+    #[test]
+    fn script_check_complexity_thresholds_default_and_override() {
+        let args = |argv: &[&str]| {
+            let XtaskCommand::ScriptCheck(args) = Cli::try_parse_from(argv).unwrap().command else {
+                panic!("expected script-check");
+            };
+            (args.max_nodes, args.max_depth, args.max_options)
+        };
+        assert_eq!(args(&["xtask", "script-check"]), (1500, 3, 6));
+        assert_eq!(
+            args(&[
+                "xtask",
+                "script-check",
+                "--max-nodes",
+                "800",
+                "--max-depth",
+                "2",
+                "--max-options",
+                "4",
+            ]),
+            (800, 2, 4)
+        );
+    }
+
+    #[test]
+    fn script_check_branch_stats_summary_marks_unbalanced_choices() {
+        let script = ScriptParser::new()
+            .parse(
+                "test",
+                "| 选择 | |\n| --- | --- |\n| 长 | long |\n| 短 | short |\n\n\
+                 **long**\n：“一”\n：“二”\n：“三”\n：“四”\n\n**short**\nreturnFromScript\n",
+            )
+            .unwrap();
+        let stats = analyze_branch_weights(&script);
+        assert_eq!(
+            format_choice_branch_stats(&stats[0]),
+            "第 1 行: 长 → long (5), 短 → short (1) ⚠ 失衡"
+        );
+    }
 
     #[test]
     fn build_mutants_command_args_includes_jobs_without_in_place() {
@@ -667,4 +1038,54 @@ mod tests {
             "60".into()
         ]));
     }
+
+    #[test]
+    fn resource_usage_ranks_most_and_single_referenced() {
+        let usage = BTreeMap::from([
+            ("bg/a.png".to_string(), 1),
+            ("bg/b.png".to_string(), 4),
+            ("bgm/x.mp3".to_string(), 4),
+            ("chara/c.png".to_string(), 2),
+            ("cg/d.png".to_string(), 1),
+        ]);
+        assert_eq!(
+            top_referenced(&usage, 3),
+            [("bg/b.png", 4), ("bgm/x.mp3", 4), ("chara/c.png", 2)]
+        );
+        assert_eq!(single_referenced(&usage), ["bg/a.png", "cg/d.png"]);
+    }
+
+    #[test]
+    fn script_files_follow_manifest_order() {
+        let root = Path::new("assets");
+        let files = [
+            "scripts/b.md",
+            "scripts/a.md",
+            "scripts/z.md",
+            "scripts/c.md",
+        ]
+        .map(|p| root.join(p))
+        .to_vec();
+        let declared = vec![
+            ScriptIndexEntry {
+                path: "scripts/z.md".to_string(),
+                title: Some("终章".to_string()),
+            },
+            ScriptIndexEntry {
+                path: "scripts/b.md".to_string(),
+                title: None,
+            },
+        ];
+
+        let ordered = order_script_files(files, &declared, root);
+        let expected: Vec<PathBuf> = [
+            "scripts/z.md",
+            "scripts/b.md",
+            "scripts/a.md",
+            "scripts/c.md",
+        ]
+        .map(|p| root.join(p))
+        .to_vec();
+        assert_eq!(ordered, expected);
+    }
 }
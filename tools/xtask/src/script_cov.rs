@@ -0,0 +1,498 @@
+//! # script-cov 命令实现
+//!
+//! 用预设的选择序列驱动 `VNRuntime` 跑完脚本，统计触达的 label/节点，
+//! 输出覆盖率与未触达 label 列表。
+//!
+//! ## 选择序列文件格式
+//!
+//! ```text
+//! # 注释以 # 开头
+//! 0 1 0      # 一行是一条游玩路线：依次在每个选择分支处选择的索引（从 0 开始）
+//! 1, 0       # 逗号或空白分隔均可
+//! -          # 单独的 - 表示"不做任何选择"（跑到第一个选择分支为止）
+//! ```
+//!
+//! 驱动规则：点击等待/定时等待一律点击推进，Signal 等待直接回传对应信号，
+//! 选择等待消耗序列中的下一个索引；序列耗尽或遇到 UI 交互请求时该路线结束。
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use clap::Args;
+use vn_runtime::{
+    Parser as ScriptParser, RuntimeInput, Script, ScriptNode, VNRuntime, WaitingReason,
+};
+
+use super::{compute_base_path, normalize_path};
+
+/// 单条路线最多推进的 tick 次数（防止脚本死循环时卡住）
+const MAX_TICKS_PER_RUN: usize = 100_000;
+
+#[derive(Args, Debug)]
+#[command(after_help = r#"说明：
+  用选择序列文件驱动脚本执行，统计 label 覆盖率。
+  不提供 --inputs 时只跑一条"不做选择"的路线。
+
+选择序列文件（每行一条路线，索引从 0 开始）：
+  0 1 0
+  1, 0
+  -            # 不做任何选择
+
+示例：
+  cargo script-cov assets/scripts/main.md --inputs tests/routes.txt
+  cargo script-cov assets/scripts/main.md --inputs routes.txt --min 80
+"#)]
+pub(crate) struct ScriptCovArgs {
+    /// 入口脚本路径
+    script: PathBuf,
+
+    /// 选择序列文件
+    #[arg(long)]
+    inputs: Option<PathBuf>,
+
+    /// 资源根目录（用于解析 callScript 目标）
+    #[arg(long, default_value = "assets")]
+    assets_root: PathBuf,
+
+    /// label 覆盖率下限（百分比），低于时返回失败
+    #[arg(long)]
+    min: Option<f64>,
+}
+
+/// 已加载的脚本集合（入口脚本 + 递归 callScript 引用的子脚本）
+pub(crate) struct LoadedScripts {
+    /// 入口脚本的逻辑路径
+    entry_key: String,
+    /// key=逻辑路径，按加载顺序排列
+    scripts: Vec<(String, Script)>,
+}
+
+impl LoadedScripts {
+    fn entry(&self) -> &Script {
+        &self
+            .scripts
+            .iter()
+            .find(|(key, _)| *key == self.entry_key)
+            .expect("invariant: entry script is always loaded")
+            .1
+    }
+
+    /// 创建一个开启覆盖记录、已注册全部脚本的 runtime
+    fn new_runtime(&self) -> VNRuntime {
+        let mut runtime = VNRuntime::new(self.entry().clone());
+        for (key, script) in &self.scripts {
+            runtime.register_script(key, script.clone());
+        }
+        runtime.state_mut().position.set_path(&self.entry_key);
+        runtime.enable_coverage();
+        runtime
+    }
+}
+
+/// 单条路线的结束原因
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RunEnd {
+    /// 脚本正常结束
+    Finished,
+    /// 选择序列耗尽（停在选择分支处）
+    ChoicesExhausted,
+    /// 遇到需要 UI 交互结果的等待
+    UiRequested { key: String },
+    /// 超过单路线 tick 上限
+    TickLimit,
+    /// 运行时错误
+    Error(String),
+}
+
+/// 单条路线的运行结果
+#[derive(Debug)]
+pub(crate) struct RunOutcome {
+    pub end: RunEnd,
+    /// 未被消耗的选择数量
+    pub unused_choices: usize,
+    /// 本路线执行过的节点（key=脚本逻辑路径）
+    pub visited: HashMap<String, BTreeSet<usize>>,
+}
+
+/// 覆盖率汇总
+#[derive(Debug, Default)]
+pub(crate) struct CoverageReport {
+    pub total_nodes: usize,
+    pub reached_nodes: usize,
+    /// 已触达的 label（`脚本逻辑路径#label`）
+    pub reached_labels: Vec<String>,
+    /// 未触达的 label（`脚本逻辑路径#label`）
+    pub missed_labels: Vec<String>,
+}
+
+impl CoverageReport {
+    /// label 覆盖率百分比；脚本中没有 label 时视为 100%
+    pub fn label_percent(&self) -> f64 {
+        let total = self.reached_labels.len() + self.missed_labels.len();
+        if total == 0 {
+            return 100.0;
+        }
+        self.reached_labels.len() as f64 * 100.0 / total as f64
+    }
+
+    /// 节点覆盖率百分比
+    pub fn node_percent(&self) -> f64 {
+        if self.total_nodes == 0 {
+            return 100.0;
+        }
+        self.reached_nodes as f64 * 100.0 / self.total_nodes as f64
+    }
+}
+
+/// 执行 script-cov 命令
+pub(crate) fn script_cov(args: ScriptCovArgs) -> anyhow::Result<()> {
+    let sequences = match &args.inputs {
+        Some(path) => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("无法读取选择序列文件: {}", path.display()))?;
+            parse_choice_sequences(&content)?
+        }
+        None => vec![Vec::new()],
+    };
+    if sequences.is_empty() {
+        anyhow::bail!("选择序列文件中没有任何路线");
+    }
+
+    let scripts = load_scripts(&args.script, &args.assets_root)?;
+    eprintln!(
+        "==> 加载 {} 个脚本，运行 {} 条路线...\n",
+        scripts.scripts.len(),
+        sequences.len()
+    );
+
+    let mut merged: HashMap<String, BTreeSet<usize>> = HashMap::new();
+    for (i, choices) in sequences.iter().enumerate() {
+        let outcome = run_sequence(&scripts, choices);
+        print_run_outcome(i + 1, &outcome);
+        for (key, nodes) in outcome.visited {
+            merged.entry(key).or_default().extend(nodes);
+        }
+    }
+
+    let report = build_report(&scripts, &merged);
+    print_report(&report);
+
+    if let Some(min) = args.min
+        && report.label_percent() < min
+    {
+        anyhow::bail!(
+            "label 覆盖率 {:.1}% 低于下限 {:.1}%",
+            report.label_percent(),
+            min
+        );
+    }
+    Ok(())
+}
+
+/// 解析选择序列文件
+pub(crate) fn parse_choice_sequences(content: &str) -> anyhow::Result<Vec<Vec<usize>>> {
+    let mut sequences = Vec::new();
+    for (line_no, raw) in content.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "-" {
+            sequences.push(Vec::new());
+            continue;
+        }
+        let choices = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<usize>()
+                    .with_context(|| format!("第 {} 行：无效的选择索引 '{s}'", line_no + 1))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        sequences.push(choices);
+    }
+    Ok(sequences)
+}
+
+/// 读取入口脚本并递归加载 callScript 引用的子脚本
+fn load_scripts(entry: &Path, assets_root: &Path) -> anyhow::Result<LoadedScripts> {
+    let content = std::fs::read_to_string(entry)
+        .with_context(|| format!("无法读取脚本: {}", entry.display()))?;
+    let base_path = compute_base_path(entry, assets_root);
+    let file_name = entry
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let entry_key = if base_path.is_empty() {
+        file_name
+    } else {
+        format!("{base_path}/{file_name}")
+    };
+
+    let script = parse_script(&entry_key, &content)?;
+    let mut loaded = LoadedScripts {
+        entry_key: entry_key.clone(),
+        scripts: Vec::new(),
+    };
+    let mut visited = HashSet::from([entry_key.clone()]);
+    let mut pending = vec![(entry_key, script)];
+
+    while let Some((key, script)) = pending.pop() {
+        for path in collect_call_paths(&script.nodes) {
+            let resolved = script.resolve_path(&path);
+            if !visited.insert(resolved.clone()) {
+                continue;
+            }
+            let file = assets_root.join(&resolved);
+            match std::fs::read_to_string(&file) {
+                Ok(child) => pending.push((resolved.clone(), parse_script(&resolved, &child)?)),
+                Err(e) => eprintln!("[WARN] callScript 目标无法读取: {} - {}", resolved, e),
+            }
+        }
+        loaded.scripts.push((key, script));
+    }
+    Ok(loaded)
+}
+
+fn parse_script(logical_path: &str, content: &str) -> anyhow::Result<Script> {
+    let normalized = normalize_path(logical_path);
+    let (base_path, file_name) = normalized.rsplit_once('/').unwrap_or(("", &normalized));
+    let script_id = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem);
+    let mut parser = ScriptParser::new();
+    parser
+        .parse_with_base_path(script_id, content, base_path)
+        .with_context(|| format!("脚本解析失败: {normalized}"))
+}
+
+/// 收集所有 callScript 目标路径（包括条件分支内部的）
+fn collect_call_paths(nodes: &[ScriptNode]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for node in nodes {
+        match node {
+            ScriptNode::CallScript { path, .. } => paths.push(path.clone()),
+            ScriptNode::Conditional { branches } => {
+                for branch in branches {
+                    paths.extend(collect_call_paths(&branch.body));
+                }
+            }
+            _ => {}
+        }
+    }
+    paths
+}
+
+/// 用一条选择序列驱动脚本直到结束
+pub(crate) fn run_sequence(scripts: &LoadedScripts, choices: &[usize]) -> RunOutcome {
+    let mut runtime = scripts.new_runtime();
+    let mut remaining = choices.iter();
+    let mut input = None;
+    let mut end = RunEnd::TickLimit;
+
+    for _ in 0..MAX_TICKS_PER_RUN {
+        let waiting = match runtime.tick(input.take()) {
+            Ok((_, waiting)) => waiting,
+            Err(e) => {
+                end = RunEnd::Error(e.to_string());
+                break;
+            }
+        };
+        input = match waiting {
+            WaitingReason::None => {
+                end = RunEnd::Finished;
+                break;
+            }
+            WaitingReason::WaitForClick | WaitingReason::WaitForTime(_) => {
+                Some(RuntimeInput::click())
+            }
+            WaitingReason::WaitForSignal(id) => Some(RuntimeInput::signal(id)),
+            WaitingReason::WaitForChoice { .. } => match remaining.next() {
+                Some(&index) => Some(RuntimeInput::choice(index)),
+                None => {
+                    end = RunEnd::ChoicesExhausted;
+                    break;
+                }
+            },
+            WaitingReason::WaitForUIResult { key, .. } => {
+                end = RunEnd::UiRequested { key };
+                break;
+            }
+            WaitingReason::Breakpoint(_) => unreachable!("script-cov 不设置断点"),
+        };
+    }
+
+    RunOutcome {
+        end,
+        unused_choices: remaining.count(),
+        visited: runtime.coverage().cloned().unwrap_or_default(),
+    }
+}
+
+/// 汇总所有路线的覆盖记录
+pub(crate) fn build_report(
+    scripts: &LoadedScripts,
+    visited: &HashMap<String, BTreeSet<usize>>,
+) -> CoverageReport {
+    let mut report = CoverageReport::default();
+    let mut ordered: Vec<&(String, Script)> = scripts.scripts.iter().collect();
+    ordered.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (key, script) in ordered {
+        let reached = visited.get(key);
+        let is_reached = |index: usize| reached.is_some_and(|set| set.contains(&index));
+
+        report.total_nodes += script.len();
+        report.reached_nodes += (0..script.len()).filter(|&i| is_reached(i)).count();
+
+        for (index, node) in script.nodes.iter().enumerate() {
+            if let Some(label) = node.as_label() {
+                let entry = format!("{key}#{label}");
+                if is_reached(index) {
+                    report.reached_labels.push(entry);
+                } else {
+                    report.missed_labels.push(entry);
+                }
+            }
+        }
+    }
+    report
+}
+
+fn print_run_outcome(index: usize, outcome: &RunOutcome) {
+    let end = match &outcome.end {
+        RunEnd::Finished => "脚本结束".to_string(),
+        RunEnd::ChoicesExhausted => "选择序列耗尽".to_string(),
+        RunEnd::UiRequested { key } => format!("遇到 UI 交互请求（{key}），停止"),
+        RunEnd::TickLimit => format!("超过 {MAX_TICKS_PER_RUN} 次 tick，疑似死循环"),
+        RunEnd::Error(e) => format!("运行时错误: {e}"),
+    };
+    eprintln!("路线 #{index}: {end}");
+    if outcome.unused_choices > 0 {
+        eprintln!(
+            "  [WARN] 有 {} 个选择未被使用（脚本先于序列结束）",
+            outcome.unused_choices
+        );
+    }
+}
+
+fn print_report(report: &CoverageReport) {
+    eprintln!("─────────────────────────────────────────────────────");
+    eprintln!(
+        "label 覆盖率: {:.1}% ({}/{})",
+        report.label_percent(),
+        report.reached_labels.len(),
+        report.reached_labels.len() + report.missed_labels.len()
+    );
+    eprintln!(
+        "节点覆盖率:  {:.1}% ({}/{})",
+        report.node_percent(),
+        report.reached_nodes,
+        report.total_nodes
+    );
+    if !report.missed_labels.is_empty() {
+        eprintln!("\n未触达 label:");
+        for label in &report.missed_labels {
+            eprintln!("  - {label}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BRANCHING_SCRIPT: &str = r#"
+"开始"
+
+| 选择 |           |
+| ---- | --------- |
+| 左   | left      |
+| 右   | right     |
+
+**left**
+"走左边"
+
+| 再选 |          |
+| ---- | -------- |
+| 上   | up       |
+| 下   | down     |
+
+**up**
+goto **end**
+
+**down**
+goto **end**
+
+**right**
+"走右边"
+goto **end**
+
+**end**
+"结束"
+"#;
+
+    fn loaded(content: &str) -> LoadedScripts {
+        LoadedScripts {
+            entry_key: "scripts/main.md".to_string(),
+            scripts: vec![(
+                "scripts/main.md".to_string(),
+                parse_script("scripts/main.md", content).unwrap(),
+            )],
+        }
+    }
+
+    fn run_all(scripts: &LoadedScripts, sequences: &[Vec<usize>]) -> CoverageReport {
+        let mut merged: HashMap<String, BTreeSet<usize>> = HashMap::new();
+        for choices in sequences {
+            for (key, nodes) in run_sequence(scripts, choices).visited {
+                merged.entry(key).or_default().extend(nodes);
+            }
+        }
+        build_report(scripts, &merged)
+    }
+
+    #[test]
+    fn parse_choice_sequences_supports_comments_separators_and_empty_route() {
+        let content = "# 注释\n0 1 0\n1, 0  # 行尾注释\n\n-\n";
+        let sequences = parse_choice_sequences(content).unwrap();
+        assert_eq!(sequences, vec![vec![0, 1, 0], vec![1, 0], vec![]]);
+    }
+
+    #[test]
+    fn parse_choice_sequences_rejects_invalid_index() {
+        let error = parse_choice_sequences("0 x").unwrap_err().to_string();
+        assert!(error.contains("第 1 行"));
+    }
+
+    #[test]
+    fn given_sequences_reach_expected_labels() {
+        let scripts = loaded(BRANCHING_SCRIPT);
+        let report = run_all(&scripts, &[vec![0, 0], vec![1]]);
+
+        assert_eq!(
+            report.reached_labels,
+            vec![
+                "scripts/main.md#left",
+                "scripts/main.md#up",
+                "scripts/main.md#right",
+                "scripts/main.md#end",
+            ]
+        );
+        assert_eq!(report.missed_labels, vec!["scripts/main.md#down"]);
+        assert!((report.label_percent() - 80.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn run_sequence_reports_exhausted_and_unused_choices() {
+        let scripts = loaded(BRANCHING_SCRIPT);
+
+        let exhausted = run_sequence(&scripts, &[0]);
+        assert_eq!(exhausted.end, RunEnd::ChoicesExhausted);
+
+        let finished = run_sequence(&scripts, &[1, 0, 0]);
+        assert_eq!(finished.end, RunEnd::Finished);
+        assert_eq!(finished.unused_choices, 2);
+    }
+}
@@ -10,7 +10,7 @@
 //! - **引擎无关**：不包含任何 Bevy 或其他引擎的类型
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use crate::state::VarValue;
@@ -36,7 +36,7 @@ pub const SIGNAL_TITLE_CARD: &str = "title_card";
 /// Host 在视频播放完毕或被跳过后发送此信号以解除等待。
 pub const SIGNAL_CUTSCENE: &str = "cutscene";
 
-/// 内联效果（对话文本中的节奏控制标签）
+/// 内联效果（对话文本中的节奏控制标签与粗体标记）
 ///
 /// 标记在纯文本的字符位置上，由打字机推进时触发。
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -60,6 +60,10 @@ pub enum InlineEffectKind {
     SetCpsRelative(f64),
     /// `{/speed}` -- 重置字速到用户默认
     ResetCps,
+    /// `**词**` 的起点 -- 之后的文字以粗体显示（只影响样式，不影响打字机节奏）
+    BoldStart,
+    /// `**词**` 的终点 -- 结束粗体
+    BoldEnd,
 }
 
 /// 过渡效果参数
@@ -197,6 +201,8 @@ pub enum Position {
     FarRight,
     /// 远中
     FarMiddle,
+    /// 自动布局：所有 auto 角色按数量在舞台上均匀分布（站位由 Host 计算）
+    Auto,
 }
 
 impl Position {
@@ -221,6 +227,7 @@ impl FromStr for Position {
             "farleft" => Ok(Self::FarLeft),
             "farright" => Ok(Self::FarRight),
             "farmiddle" => Ok(Self::FarMiddle),
+            "auto" => Ok(Self::Auto),
             _ => Err(()),
         }
     }
@@ -233,6 +240,19 @@ pub struct Choice {
     pub text: String,
     /// 跳转目标标签
     pub target_label: String,
+    /// 是否可选；不可选的选项仍显示（灰显），但不能被选中
+    #[serde(default = "default_choice_enabled")]
+    pub enabled: bool,
+    /// 不可选的理由（可选）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
+    /// 此前（含以往周目）是否选过该选项，供 UI 标记已走过的分支
+    #[serde(default)]
+    pub visited: bool,
+}
+
+fn default_choice_enabled() -> bool {
+    true
 }
 
 /// 文本显示模式
@@ -249,6 +269,151 @@ pub enum TextMode {
     NVL,
 }
 
+/// 纯色填充颜色（不透明 RGB）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const BLACK: Self = Self::rgb(0, 0, 0);
+    pub const WHITE: Self = Self::rgb(255, 255, 255);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// 从脚本写法解析：`#rrggbb`、`#rgb` 或命名色（大小写不敏感）
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix('#') {
+            return Self::from_hex(hex);
+        }
+        let color = match text.to_ascii_lowercase().as_str() {
+            "black" => Self::BLACK,
+            "white" => Self::WHITE,
+            "red" => Self::rgb(255, 0, 0),
+            "green" => Self::rgb(0, 128, 0),
+            "blue" => Self::rgb(0, 0, 255),
+            "yellow" => Self::rgb(255, 255, 0),
+            "gray" | "grey" => Self::rgb(128, 128, 128),
+            "sepia" => Self::rgb(112, 66, 20),
+            _ => return None,
+        };
+        Some(color)
+    }
+
+    fn from_hex(hex: &str) -> Option<Self> {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+        match hex.len() {
+            6 => Some(Self::rgb(
+                channel(&hex[0..2])?,
+                channel(&hex[2..4])?,
+                channel(&hex[4..6])?,
+            )),
+            // #rgb 每位重复一次：#f80 = #ff8800
+            3 => Some(Self::rgb(
+                channel(&hex[0..1])? * 17,
+                channel(&hex[1..2])? * 17,
+                channel(&hex[2..3])? * 17,
+            )),
+            _ => None,
+        }
+    }
+
+    /// `#rrggbb` 形式（小写），可直接用作 CSS 颜色
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+/// 天气粒子类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherKind {
+    /// 无天气（清除粒子覆盖层）
+    #[default]
+    None,
+    /// 雨
+    Rain,
+    /// 雪
+    Snow,
+}
+
+impl WeatherKind {
+    /// 从脚本名称解析（大小写不敏感）
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" | "clear" => Some(Self::None),
+            "rain" => Some(Self::Rain),
+            "snow" => Some(Self::Snow),
+            _ => None,
+        }
+    }
+}
+
+/// 整帧滤镜类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    /// 无滤镜（清除）
+    #[default]
+    None,
+    /// 去色
+    Grayscale,
+    /// 复古（棕褐色调）
+    Sepia,
+    /// 模糊
+    Blur,
+}
+
+impl FilterKind {
+    /// 从脚本名称解析（大小写不敏感）
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" | "clear" => Some(Self::None),
+            "grayscale" | "greyscale" | "gray" | "grey" => Some(Self::Grayscale),
+            "sepia" => Some(Self::Sepia),
+            "blur" => Some(Self::Blur),
+            _ => None,
+        }
+    }
+}
+
+/// 对话速度系数缺省值（serde 兼容旧数据）
+pub fn default_text_speed() -> f64 {
+    1.0
+}
+
+/// 带延时标注的命令（timeline 中的一个动作）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedCommand {
+    /// 相对 timeline 开始的延时（秒）
+    pub delay: f64,
+    /// 到时执行的命令
+    pub command: Command,
+}
+
+/// 视差背景中的一层
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundLayer {
+    /// 图层图片路径
+    pub path: String,
+    /// 视差系数：镜头平移时该层随之偏移的比例（0 为不动的远景，1 与镜头同步）
+    pub parallax: f64,
+}
+
 /// Runtime 向 Host 发出的指令
 ///
 /// 这是 Runtime 与 Host 之间的**唯一通信方式**。
@@ -271,6 +436,34 @@ pub enum Command {
         transition: Option<Transition>,
     },
 
+    /// 多层视差背景（按远到近排列，替换当前背景图）
+    SetBackgroundLayers {
+        /// 各层图片与视差系数
+        layers: Vec<BackgroundLayer>,
+    },
+
+    /// 纯色背景（替换当前背景图）
+    FillColor {
+        /// 填充颜色
+        color: Color,
+        /// 过渡效果（可选）
+        transition: Option<Transition>,
+    },
+
+    /// 显示全屏 CG（Contain 模式，覆盖背景与立绘）
+    ShowCg {
+        /// CG 图片路径
+        path: String,
+        /// 过渡效果（可选）
+        transition: Option<Transition>,
+    },
+
+    /// 隐藏 CG，回到背景
+    HideCg {
+        /// 过渡效果（可选）
+        transition: Option<Transition>,
+    },
+
     /// 显示角色立绘
     ShowCharacter {
         /// 立绘图片路径
@@ -281,6 +474,9 @@ pub enum Command {
         position: Position,
         /// 过渡效果（可选）
         transition: Option<Transition>,
+        /// 循环待机动画名（如 `breathe` / `sway`），Host 负责解释；None 表示停止待机动画
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        idle: Option<String>,
     },
 
     /// 隐藏角色立绘
@@ -291,6 +487,16 @@ pub enum Command {
         transition: Option<Transition>,
     },
 
+    /// 设置角色立绘轮廓光
+    ///
+    /// `intensity` 为 0 时关闭。不阻塞脚本执行。
+    SetRimLight {
+        /// 角色别名
+        alias: String,
+        /// 强度（0.0 ~ 1.0）
+        intensity: f64,
+    },
+
     /// 显示对话文本
     ShowText {
         /// 说话者名称（None 表示旁白）
@@ -301,6 +507,9 @@ pub enum Command {
         inline_effects: Vec<InlineEffect>,
         /// 是否自动推进（行尾 `-->` 修饰符）
         no_wait: bool,
+        /// 整行打字机速度系数，Host 乘以全局文字速度（缺省 1.0）
+        #[serde(default = "default_text_speed")]
+        text_speed: f64,
     },
 
     /// 台词续接（不清屏追加文本）
@@ -313,12 +522,27 @@ pub enum Command {
         no_wait: bool,
     },
 
+    /// 显示角色头顶气泡（旁注/心声）
+    ///
+    /// Host 在别名对应立绘上方绘制小气泡，与主对话框同时显示；
+    /// 同一角色再次显示时替换原气泡，`content` 为空时清除。
+    /// 角色被隐藏时气泡随之移除。不阻塞脚本执行。
+    ShowBubble {
+        /// 绑定的角色别名
+        alias: String,
+        /// 气泡文本（纯文本）；空串表示清除
+        content: String,
+    },
+
     /// 显示选择分支
     PresentChoices {
         /// 选择界面样式（从表头提取）
         style: Option<String>,
         /// 选项列表
         choices: Vec<Choice>,
+        /// 选择提示：Host 先在对话框显示提示再呈现选项，等待选择期间保持显示
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt: Option<String>,
     },
 
     /// 播放背景音乐
@@ -341,6 +565,16 @@ pub enum Command {
     /// 恢复 BGM 音量（unduck）
     BgmUnduck,
 
+    /// 平滑调整脚本层 BGM 音量
+    ///
+    /// 与玩家设置的 BGM 音量相乘，独立于 duck；不阻塞脚本执行。
+    SetBgmVolume {
+        /// 目标音量（0.0 ~ 1.0）
+        volume: f64,
+        /// 过渡时长（秒），0 表示立即生效
+        duration: f64,
+    },
+
     /// 播放音效
     PlaySfx {
         /// 音效文件路径
@@ -361,9 +595,23 @@ pub enum Command {
     /// 显示对话框
     TextBoxShow,
 
+    /// 演出空档提示：本段只有演出、没有文本，Host 可暂时隐藏对话框
+    ///
+    /// 与 `TextBoxHide` 不同，下一条文本（对话/续接/选项）出现时 Host 应自动恢复显示。
+    /// 仅在 Runtime 开启自动隐藏（`set_textbox_auto_hide`）时产出。
+    TextBoxAutoHide,
+
     /// 清理对话框内容（对话/选择分支等）
     TextBoxClear,
 
+    /// 切换对话框样式（背景纹理/配色）
+    ///
+    /// 样式由 Host 按名称查找；`default` 或未定义的名称使用默认样式。
+    SetTextboxStyle {
+        /// 样式名
+        name: String,
+    },
+
     /// 清除所有角色立绘
     ClearCharacters,
 
@@ -388,6 +636,37 @@ pub enum Command {
         duration: f64,
     },
 
+    /// 手柄震动反馈
+    ///
+    /// Host 在支持的平台上驱动手柄震动；无手柄或平台不支持时静默忽略。
+    /// 不阻塞脚本执行。
+    Rumble {
+        /// 震动强度（0.0 ~ 1.0）
+        intensity: f64,
+        /// 震动时长（秒）
+        duration: f64,
+    },
+
+    /// 设置天气粒子覆盖层
+    ///
+    /// `kind` 为 `None` 时清除天气。不阻塞脚本执行。
+    SetWeather {
+        /// 天气类型
+        kind: WeatherKind,
+        /// 强度（0.0 ~ 1.0），决定粒子密度
+        intensity: f64,
+    },
+
+    /// 设置整帧滤镜
+    ///
+    /// `kind` 为 `None` 时清除滤镜。不阻塞脚本执行。
+    SetFilter {
+        /// 滤镜类型
+        kind: FilterKind,
+        /// 强度（0.0 ~ 1.0）
+        intensity: f64,
+    },
+
     /// 完整重启游戏会话
     ///
     /// Host 收到此命令后应：
@@ -422,8 +701,18 @@ pub enum Command {
         key: String,
         /// UI 模式标识（Host 据此选择展示哪种 UI）
         mode: String,
-        /// 模式特定参数
-        params: HashMap<String, VarValue>,
+        /// 模式特定参数（按键排序，保证输出确定）
+        params: BTreeMap<String, VarValue>,
+    },
+
+    /// 按时间编排的一组命令
+    ///
+    /// 由 `timeline` 块产生，`steps` 已按延时升序排列（同一时刻保持声明顺序）。
+    /// Host 应在各自的延时到达时执行对应命令；不阻塞脚本执行，
+    /// 玩家点击或快进时可立即执行剩余命令。
+    Timeline {
+        /// 带延时标注的命令序列
+        steps: Vec<TimedCommand>,
     },
 }
 
@@ -23,6 +23,7 @@ fn test_position_parse_supports_all_variants() {
         ("farleft", Position::FarLeft),
         ("FARRIGHT", Position::FarRight),
         ("farmiddle", Position::FarMiddle),
+        ("Auto", Position::Auto),
     ];
 
     for (raw, expected) in cases {
@@ -92,6 +93,7 @@ fn test_command_serialization() {
         content: "你好".to_string(),
         inline_effects: vec![],
         no_wait: false,
+        text_speed: 1.0,
     };
 
     let json = serde_json::to_string(&cmd).unwrap();
@@ -99,6 +101,14 @@ fn test_command_serialization() {
     assert_eq!(cmd, deserialized);
 }
 
+#[test]
+fn test_show_text_text_speed_defaults_to_one() {
+    // 旧版序列化数据没有 text_speed 字段
+    let json = r#"{"ShowText":{"speaker":null,"content":"x","inline_effects":[],"no_wait":false}}"#;
+    let cmd: Command = serde_json::from_str(json).unwrap();
+    assert!(matches!(cmd, Command::ShowText { text_speed, .. } if text_speed == 1.0));
+}
+
 #[test]
 fn test_transition_serialization_with_named_args() {
     let t = Transition::with_named_args(
@@ -111,3 +121,19 @@ fn test_transition_serialization_with_named_args() {
     assert_eq!(t, deserialized);
     assert_eq!(deserialized.get_duration(), Some(1.5));
 }
+
+#[test]
+fn test_color_parse_hex_and_named() {
+    assert_eq!(Color::parse("#000000"), Some(Color::BLACK));
+    assert_eq!(Color::parse("#FF8800"), Some(Color::rgb(255, 136, 0)));
+    assert_eq!(Color::parse("#f80"), Some(Color::rgb(255, 136, 0)));
+    assert_eq!(Color::parse("white"), Some(Color::WHITE));
+    assert_eq!(Color::parse("Grey"), Color::parse("gray"));
+
+    for bad in ["#12345", "#gggggg", "000000", "#", "purple-ish", ""] {
+        assert_eq!(Color::parse(bad), None, "{bad}");
+    }
+
+    assert_eq!(Color::rgb(255, 136, 0).to_hex(), "#ff8800");
+    assert_eq!(Color::BLACK.to_string(), "#000000");
+}
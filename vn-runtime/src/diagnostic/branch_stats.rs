@@ -0,0 +1,180 @@
+//! 分支体量统计
+//!
+//! 统计每个选择点的选项数与各选项后续可达节点数，供平衡性分析使用，
+//! 帮助发现失衡分支（如某选项之后内容极少）。
+//!
+//! - 从选项目标 label 出发，沿顺序执行、`goto` 与后续选择的所有目标遍历
+//! - 每个节点只计一次；汇合到公共路线的部分同样计入
+//! - label 本身不计数；条件分支的各分支全部计入（给出上限估计）
+//! - `goto $var`、`returnFromScript` 与 `fullRestart` 视为路径终点
+
+use std::collections::{HashMap, HashSet};
+
+use crate::script::{ChoiceOption, Script, ScriptNode};
+
+/// 判定失衡的体量倍数：最大分支体量达到最小分支的该倍数即视为失衡
+pub const BRANCH_IMBALANCE_RATIO: usize = 4;
+
+/// 单个选项的分支统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionBranchStats {
+    /// 选项显示文本
+    pub text: String,
+    /// 跳转目标标签
+    pub target_label: String,
+    /// 选中后可达的节点数（目标 label 不存在时为 0）
+    pub reachable_nodes: usize,
+}
+
+/// 单个选择点的分支统计
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChoiceBranchStats {
+    /// 选择节点在脚本中的索引（位于条件分支内时为外层条件节点的索引）
+    pub node_index: usize,
+    /// 源码行号
+    pub line: Option<usize>,
+    /// 按出现顺序的选项统计
+    pub options: Vec<OptionBranchStats>,
+}
+
+impl ChoiceBranchStats {
+    /// 各选项中最少的可达节点数
+    pub fn min_reachable(&self) -> usize {
+        self.options
+            .iter()
+            .map(|o| o.reachable_nodes)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// 各选项中最多的可达节点数
+    pub fn max_reachable(&self) -> usize {
+        self.options
+            .iter()
+            .map(|o| o.reachable_nodes)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// 分支体量是否失衡（见 [`BRANCH_IMBALANCE_RATIO`]）
+    pub fn is_unbalanced(&self) -> bool {
+        self.options.len() > 1
+            && self.max_reachable() >= self.min_reachable().max(1) * BRANCH_IMBALANCE_RATIO
+    }
+}
+
+/// 统计脚本中每个选择点的分支体量
+///
+/// 结果按选择点在脚本中出现的顺序排列。
+pub fn analyze_branch_weights(script: &Script) -> Vec<ChoiceBranchStats> {
+    let labels: HashMap<&str, usize> = script
+        .nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, node)| node.as_label().map(|label| (label, index)))
+        .collect();
+
+    let mut stats = Vec::new();
+    for (index, node) in script.nodes.iter().enumerate() {
+        let mut choices = Vec::new();
+        collect_choices(node, &mut choices);
+        for options in choices {
+            let options = options
+                .iter()
+                .map(|option| OptionBranchStats {
+                    text: option.text.clone(),
+                    target_label: option.target_label.clone(),
+                    reachable_nodes: labels
+                        .get(option.target_label.as_str())
+                        .map_or(0, |&start| count_reachable(script, &labels, start)),
+                })
+                .collect();
+            stats.push(ChoiceBranchStats {
+                node_index: index,
+                line: script.get_source_line(index),
+                options,
+            });
+        }
+    }
+    stats
+}
+
+/// 收集节点中的选择（含条件分支内部）
+fn collect_choices<'a>(node: &'a ScriptNode, choices: &mut Vec<&'a [ChoiceOption]>) {
+    match node {
+        ScriptNode::Choice { options, .. } => choices.push(options),
+        ScriptNode::Conditional { branches } => {
+            for branch in branches {
+                for inner in &branch.body {
+                    collect_choices(inner, choices);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 从 `start` 出发遍历顶层节点，累计可达节点数
+fn count_reachable(script: &Script, labels: &HashMap<&str, usize>, start: usize) -> usize {
+    let mut visited = HashSet::new();
+    let mut pending = vec![start];
+    let mut total = 0;
+
+    while let Some(index) = pending.pop() {
+        let Some(node) = script.nodes.get(index) else {
+            continue;
+        };
+        if !visited.insert(index) {
+            continue;
+        }
+        total += node_weight(node);
+
+        let mut targets = Vec::new();
+        let falls_through = successors(node, &mut targets);
+        pending.extend(targets.iter().filter_map(|label| labels.get(label)));
+        if falls_through {
+            pending.push(index + 1);
+        }
+    }
+    total
+}
+
+/// 节点计数：label 不计，条件节点按各分支内部节点合计
+fn node_weight(node: &ScriptNode) -> usize {
+    match node {
+        ScriptNode::Label { .. } => 0,
+        ScriptNode::Conditional { branches } => branches
+            .iter()
+            .flat_map(|branch| &branch.body)
+            .map(node_weight)
+            .sum(),
+        _ => 1,
+    }
+}
+
+/// 收集节点的跳转目标，返回执行是否会继续到下一个节点
+fn successors<'a>(node: &'a ScriptNode, targets: &mut Vec<&'a str>) -> bool {
+    match node {
+        ScriptNode::Goto { target_label } | ScriptNode::TimedGoto { target_label, .. } => {
+            targets.push(target_label);
+            false
+        }
+        ScriptNode::Choice { options, .. } => {
+            targets.extend(options.iter().map(|o| o.target_label.as_str()));
+            false
+        }
+        ScriptNode::DynamicGoto { .. } | ScriptNode::ReturnFromScript | ScriptNode::FullRestart => {
+            false
+        }
+        ScriptNode::Conditional { branches } => {
+            // 条件分支内部的跳转全部计入；保守地认为总能落到下一个节点
+            for branch in branches {
+                for inner in &branch.body {
+                    successors(inner, targets);
+                }
+            }
+            true
+        }
+        _ => true,
+    }
+}
@@ -0,0 +1,138 @@
+//! 脚本体量与复杂度检查
+//!
+//! 超长单脚本难以维护。节点总数、条件嵌套深度、单个选择点的选项数超过阈值时
+//! 给出提示，帮助作者拆分脚本：
+//!
+//! - 节点总数（含条件分支内的节点）超限：Warn
+//! - 条件嵌套深度超限：Warn，定位到最外层的 `if`
+//! - 单个选择点选项数超限：Info（菜单类选择可能是有意为之）
+
+use crate::script::{Script, ScriptNode};
+
+use super::{Diagnostic, DiagnosticResult, rules};
+
+/// 默认单脚本节点数上限
+pub const DEFAULT_MAX_SCRIPT_NODES: usize = 1500;
+/// 默认条件嵌套深度上限
+pub const DEFAULT_MAX_CONDITIONAL_DEPTH: usize = 3;
+/// 默认单个选择点选项数上限
+pub const DEFAULT_MAX_CHOICE_OPTIONS: usize = 6;
+
+/// 复杂度阈值（超过即提示，等于不提示）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComplexityLimits {
+    /// 单脚本节点数上限
+    pub max_nodes: usize,
+    /// 条件嵌套深度上限（单层 `if` 深度为 1）
+    pub max_conditional_depth: usize,
+    /// 单个选择点选项数上限
+    pub max_choice_options: usize,
+}
+
+impl Default for ComplexityLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: DEFAULT_MAX_SCRIPT_NODES,
+            max_conditional_depth: DEFAULT_MAX_CONDITIONAL_DEPTH,
+            max_choice_options: DEFAULT_MAX_CHOICE_OPTIONS,
+        }
+    }
+}
+
+/// 按阈值检查脚本体量与复杂度
+pub fn check_complexity(script: &Script, limits: &ComplexityLimits) -> DiagnosticResult {
+    let mut result = DiagnosticResult::new();
+
+    let total = count_nodes(&script.nodes);
+    if total > limits.max_nodes {
+        result.push(
+            Diagnostic::warn(
+                &script.id,
+                format!(
+                    "脚本共 {} 个节点，超过上限 {}，建议按章节拆分",
+                    total, limits.max_nodes
+                ),
+            )
+            .with_rule(rules::SCRIPT_TOO_LARGE),
+        );
+    }
+
+    for (index, node) in script.nodes.iter().enumerate() {
+        let line = script.get_source_line(index);
+        let depth = conditional_depth(node);
+        if depth > limits.max_conditional_depth {
+            let mut diag = Diagnostic::warn(
+                &script.id,
+                format!(
+                    "条件嵌套 {} 层，超过上限 {}，建议拆分为 label 或子脚本",
+                    depth, limits.max_conditional_depth
+                ),
+            )
+            .with_rule(rules::DEEP_NESTING);
+            if let Some(line) = line {
+                diag = diag.with_line(line);
+            }
+            result.push(diag);
+        }
+
+        for options in choice_option_counts(node) {
+            if options > limits.max_choice_options {
+                let mut diag = Diagnostic::info(
+                    &script.id,
+                    format!(
+                        "选择点有 {} 个选项，超过上限 {}",
+                        options, limits.max_choice_options
+                    ),
+                )
+                .with_rule(rules::TOO_MANY_OPTIONS);
+                if let Some(line) = line {
+                    diag = diag.with_line(line);
+                }
+                result.push(diag);
+            }
+        }
+    }
+
+    result
+}
+
+/// 节点总数（条件节点本身计 1，分支内节点另计）
+fn count_nodes(nodes: &[ScriptNode]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            ScriptNode::Conditional { branches } => {
+                1 + branches.iter().map(|b| count_nodes(&b.body)).sum::<usize>()
+            }
+            _ => 1,
+        })
+        .sum()
+}
+
+/// 节点内条件嵌套的最大深度（非条件节点为 0）
+fn conditional_depth(node: &ScriptNode) -> usize {
+    match node {
+        ScriptNode::Conditional { branches } => {
+            1 + branches
+                .iter()
+                .flat_map(|b| &b.body)
+                .map(conditional_depth)
+                .max()
+                .unwrap_or(0)
+        }
+        _ => 0,
+    }
+}
+
+/// 节点（含条件分支内部）中每个选择点的选项数
+fn choice_option_counts(node: &ScriptNode) -> Vec<usize> {
+    match node {
+        ScriptNode::Choice { options, .. } => vec![options.len()],
+        ScriptNode::Conditional { branches } => branches
+            .iter()
+            .flat_map(|b| &b.body)
+            .flat_map(choice_option_counts)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
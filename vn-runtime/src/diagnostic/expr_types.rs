@@ -0,0 +1,296 @@
+//! 表达式静态类型检查
+//!
+//! 表达式语言只有字面量、变量、`==` / `!=` 与 `and` / `or` / `not`，
+//! 类型错误在运行时才暴露：逻辑运算遇到非布尔值直接报错，
+//! 不同类型的比较恒不相等。这里做一次不依赖执行路径的简单推断：
+//!
+//! - 变量类型取自脚本中所有 `set` / 选项动作的赋值；赋值类型不一致或由
+//!   Host 回传（`requestUI`）、持久变量注入的变量视为未知，不参与检查
+//! - 只在两侧类型都确定时报 Warning，宁可漏报也不误报
+
+use std::collections::HashMap;
+
+use crate::script::{Expr, Script, ScriptNode};
+use crate::state::{VarValue, persistent_key};
+
+use super::{Diagnostic, rules};
+
+/// 推断出的表达式类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExprType {
+    Bool,
+    Int,
+    Float,
+    String,
+}
+
+impl ExprType {
+    fn of(value: &VarValue) -> Self {
+        match value {
+            VarValue::Bool(_) => Self::Bool,
+            VarValue::Int(_) => Self::Int,
+            VarValue::Float(_) => Self::Float,
+            VarValue::String(_) => Self::String,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bool => "布尔",
+            Self::Int => "整数",
+            Self::Float => "浮点数",
+            Self::String => "字符串",
+        }
+    }
+}
+
+/// 变量名 → 类型；None 表示类型不确定
+type TypeEnv = HashMap<String, Option<ExprType>>;
+
+/// 类型推断的最大轮数（赋值链更长时剩余变量视为未知）
+const MAX_INFER_PASSES: usize = 8;
+
+/// 检查脚本中所有表达式的类型，返回 Warning 列表
+pub(super) fn check_expression_types(script: &Script) -> Vec<Diagnostic> {
+    // 赋值可能引用后面才赋值的变量，用上一轮结果重新推断直到不再变化
+    let mut env = TypeEnv::new();
+    for _ in 0..MAX_INFER_PASSES {
+        let mut next = TypeEnv::new();
+        collect_assignments(&script.nodes, &env, &mut next);
+        if next == env {
+            break;
+        }
+        env = next;
+    }
+
+    let mut checker = Checker {
+        script,
+        env: &env,
+        diagnostics: Vec::new(),
+    };
+    for (index, node) in script.nodes.iter().enumerate() {
+        checker.check_node(node, script.get_source_line(index));
+    }
+    checker.diagnostics
+}
+
+/// 按上一轮的类型表 `prev` 推断每次赋值，结果汇总到 `next`
+fn collect_assignments(nodes: &[ScriptNode], prev: &TypeEnv, next: &mut TypeEnv) {
+    for node in nodes {
+        match node {
+            ScriptNode::SetVar { name, value } => assign(next, name, infer(value, prev)),
+            ScriptNode::DeclareLocal {
+                name,
+                value: Some(value),
+            } => assign(next, name, infer(value, prev)),
+            ScriptNode::Choice { options, .. } => {
+                for action in options.iter().flat_map(|o| &o.actions) {
+                    assign(next, &action.name, infer(&action.value, prev));
+                }
+            }
+            ScriptNode::RequestUI { result_var, .. } => assign(next, result_var, None),
+            ScriptNode::Conditional { branches } => {
+                for branch in branches {
+                    collect_assignments(&branch.body, prev, next);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn assign(env: &mut TypeEnv, name: &str, ty: Option<ExprType>) {
+    // 持久变量可能由 Host 注入任意类型
+    let ty = if persistent_key(name).is_some() {
+        None
+    } else {
+        ty
+    };
+    env.entry(name.to_string())
+        .and_modify(|existing| {
+            if *existing != ty {
+                *existing = None;
+            }
+        })
+        .or_insert(ty);
+}
+
+/// 推断表达式类型；无法确定时返回 None
+fn infer(expr: &Expr, env: &TypeEnv) -> Option<ExprType> {
+    match expr {
+        Expr::Literal(value) => Some(ExprType::of(value)),
+        Expr::Variable(name) => env.get(name).copied().flatten(),
+        Expr::Add(left, right) => {
+            let (l, r) = (infer(left, env), infer(right, env));
+            if l == Some(ExprType::String) || r == Some(ExprType::String) {
+                return Some(ExprType::String);
+            }
+            match (l?, r?) {
+                (ExprType::Int, ExprType::Int) => Some(ExprType::Int),
+                (ExprType::Int | ExprType::Float, ExprType::Int | ExprType::Float) => {
+                    Some(ExprType::Float)
+                }
+                _ => None,
+            }
+        }
+        Expr::Eq(..)
+        | Expr::NotEq(..)
+        | Expr::Compare(..)
+        | Expr::In(..)
+        | Expr::And(..)
+        | Expr::Or(..)
+        | Expr::Not(..) => Some(ExprType::Bool),
+    }
+}
+
+struct Checker<'a> {
+    script: &'a Script,
+    env: &'a TypeEnv,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn check_node(&mut self, node: &ScriptNode, line: Option<usize>) {
+        match node {
+            ScriptNode::SetVar { value, .. }
+            | ScriptNode::DeclareLocal {
+                value: Some(value), ..
+            } => self.check_expr(value, line),
+            ScriptNode::Choice { options, .. } => {
+                for option in options {
+                    if let Some(condition) = &option.condition {
+                        self.check_condition(condition, line);
+                    }
+                    for action in &option.actions {
+                        self.check_expr(&action.value, line);
+                    }
+                }
+            }
+            ScriptNode::RequestUI { params, .. } => {
+                for (_, value) in params {
+                    self.check_expr(value, line);
+                }
+            }
+            ScriptNode::Assert { condition, .. } => self.check_condition(condition, line),
+            ScriptNode::Conditional { branches } => {
+                for branch in branches {
+                    if let Some(condition) = &branch.condition {
+                        self.check_condition(condition, line);
+                    }
+                    // 条件分支内部节点没有独立行号
+                    for inner in &branch.body {
+                        self.check_node(inner, line);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 条件表达式：自身必须是布尔
+    fn check_condition(&mut self, expr: &Expr, line: Option<usize>) {
+        self.check_expr(expr, line);
+        if let Some(ty) = infer(expr, self.env).filter(|ty| *ty != ExprType::Bool) {
+            self.warn(line, format!("条件表达式是{}类型，运行时会报错", ty.name()));
+        }
+    }
+
+    fn check_expr(&mut self, expr: &Expr, line: Option<usize>) {
+        match expr {
+            Expr::Literal(_) | Expr::Variable(_) => {}
+            Expr::Eq(left, right) | Expr::NotEq(left, right) => {
+                self.check_expr(left, line);
+                self.check_expr(right, line);
+                if let (Some(l), Some(r)) = (infer(left, self.env), infer(right, self.env))
+                    && l != r
+                {
+                    let result = matches!(expr, Expr::NotEq(..));
+                    self.warn(
+                        line,
+                        format!(
+                            "比较两侧类型不同（{} 与 {}），结果恒为 {}",
+                            l.name(),
+                            r.name(),
+                            result
+                        ),
+                    );
+                }
+            }
+            Expr::Compare(left, op, right) => {
+                for operand in [left, right] {
+                    self.check_expr(operand, line);
+                    if let Some(ty) = infer(operand, self.env)
+                        .filter(|ty| !matches!(ty, ExprType::Int | ExprType::Float))
+                    {
+                        self.warn(
+                            line,
+                            format!("{} 的操作数是{}类型，运行时会报错", op.symbol(), ty.name()),
+                        );
+                    }
+                }
+            }
+            Expr::In(inner, values) => {
+                self.check_expr(inner, line);
+                if let Some(ty) = infer(inner, self.env)
+                    && let Some(other) = values
+                        .iter()
+                        .map(ExprType::of)
+                        .find(|candidate| *candidate != ty)
+                {
+                    self.warn(
+                        line,
+                        format!(
+                            "in 列表含{}类型元素，与左侧{}类型不同，永远不会匹配",
+                            other.name(),
+                            ty.name()
+                        ),
+                    );
+                }
+            }
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                let op = if matches!(expr, Expr::And(..)) {
+                    "and"
+                } else {
+                    "or"
+                };
+                for operand in [left, right] {
+                    self.check_expr(operand, line);
+                    self.check_logical_operand(operand, op, line);
+                }
+            }
+            Expr::Not(inner) => {
+                self.check_expr(inner, line);
+                self.check_logical_operand(inner, "not", line);
+            }
+            Expr::Add(left, right) => {
+                self.check_expr(left, line);
+                self.check_expr(right, line);
+                // 另一侧类型未知时可能是字符串，不报
+                if let (Some(l), Some(r)) = (infer(left, self.env), infer(right, self.env))
+                    && l != ExprType::String
+                    && r != ExprType::String
+                    && (l == ExprType::Bool || r == ExprType::Bool)
+                {
+                    self.warn(line, "+ 的操作数是布尔类型，运行时会报错".to_string());
+                }
+            }
+        }
+    }
+
+    fn check_logical_operand(&mut self, operand: &Expr, op: &str, line: Option<usize>) {
+        if let Some(ty) = infer(operand, self.env).filter(|ty| *ty != ExprType::Bool) {
+            self.warn(
+                line,
+                format!("{} 的操作数是{}类型，运行时会报错", op, ty.name()),
+            );
+        }
+    }
+
+    fn warn(&mut self, line: Option<usize>, message: String) {
+        let mut diag = Diagnostic::warn(&self.script.id, message).with_rule(rules::EXPR_TYPE);
+        if let Some(line) = line {
+            diag = diag.with_line(line);
+        }
+        self.diagnostics.push(diag);
+    }
+}
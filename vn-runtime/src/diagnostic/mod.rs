@@ -7,10 +7,37 @@
 //! - 纯函数 API，可在无 IO 环境下运行
 //! - 诊断分级：Error（必须修复）、Warn（建议修复）、Info（信息提示）
 //! - 复用 parser/AST，不重复解析逻辑
+//! - 每条诊断携带稳定的规则 id（见 [`rules`]），便于按规则屏蔽
+//! - 附带制作统计：资源引用计数、阅读时长估算（见 [`estimate_reading_time`]）、
+//!   分支体量统计（见 [`analyze_branch_weights`]）
+//! - 源码级空白字符检查与清理（见 [`lint_whitespace`]）
+//! - 脚本体量与复杂度阈值提示（见 [`check_complexity`]）
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use crate::script::{Script, ScriptNode};
+use crate::script::{Expr, Script, ScriptNode};
+
+mod branch_stats;
+mod complexity;
+mod expr_types;
+mod reading_time;
+mod whitespace;
+
+pub use branch_stats::{
+    BRANCH_IMBALANCE_RATIO, ChoiceBranchStats, OptionBranchStats, analyze_branch_weights,
+};
+
+pub use complexity::{
+    ComplexityLimits, DEFAULT_MAX_CHOICE_OPTIONS, DEFAULT_MAX_CONDITIONAL_DEPTH,
+    DEFAULT_MAX_SCRIPT_NODES, check_complexity,
+};
+
+pub use reading_time::{
+    ChapterReadingTime, DEFAULT_READING_CHARS_PER_MINUTE, ReadingTimeEstimate,
+    chapter_reading_time_at, estimate_reading_time,
+};
+
+pub use whitespace::{clean_whitespace, lint_whitespace};
 
 /// 诊断级别
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -33,11 +60,57 @@ impl std::fmt::Display for DiagnosticLevel {
     }
 }
 
+impl std::str::FromStr for DiagnosticLevel {
+    type Err = String;
+
+    /// 解析级别名（大小写不敏感）：`info` / `warn`（或 `warning`）/ `error`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "info" => Ok(Self::Info),
+            "warn" | "warning" => Ok(Self::Warn),
+            "error" => Ok(Self::Error),
+            _ => Err(format!("未知的诊断级别: {}", s)),
+        }
+    }
+}
+
+/// 稳定的诊断规则 id
+///
+/// 规则 id 一经发布不再改名，可用于按规则屏蔽诊断。
+pub mod rules {
+    /// 未分类诊断（构造时未指定规则）
+    pub const GENERAL: &str = "general";
+    /// goto/choice 引用了未定义的 label
+    pub const UNDEFINED_LABEL: &str = "undefined-label";
+    /// 脚本引用的资源文件不存在
+    pub const MISSING_RESOURCE: &str = "missing-resource";
+    /// 解析器产生的容错警告
+    pub const PARSE_WARNING: &str = "parse-warning";
+    /// 表达式类型不匹配（逻辑运算用了非布尔值、比较两侧类型不同等）
+    pub const EXPR_TYPE: &str = "expr-type";
+    /// goto 目标由变量决定，无法静态校验
+    pub const DYNAMIC_JUMP: &str = "dynamic-jump";
+    /// 脚本中含全角空格
+    pub const FULLWIDTH_SPACE: &str = "fullwidth-space";
+    /// 行首缩进混用制表符与空格
+    pub const MIXED_INDENT: &str = "mixed-indent";
+    /// 行尾多余空白
+    pub const TRAILING_WHITESPACE: &str = "trailing-whitespace";
+    /// 单脚本节点数超过阈值
+    pub const SCRIPT_TOO_LARGE: &str = "script-too-large";
+    /// 条件嵌套深度超过阈值
+    pub const DEEP_NESTING: &str = "deep-nesting";
+    /// 单个选择点选项数超过阈值
+    pub const TOO_MANY_OPTIONS: &str = "too-many-options";
+}
+
 /// 诊断条目
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Diagnostic {
     /// 诊断级别
     pub level: DiagnosticLevel,
+    /// 规则 id（见 [`rules`]）
+    pub rule: &'static str,
     /// 脚本 ID / 文件路径
     pub script_id: String,
     /// 行号（如果可定位，从 1 开始）
@@ -53,6 +126,7 @@ impl Diagnostic {
     pub fn error(script_id: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             level: DiagnosticLevel::Error,
+            rule: rules::GENERAL,
             script_id: script_id.into(),
             line: None,
             message: message.into(),
@@ -64,6 +138,7 @@ impl Diagnostic {
     pub fn warn(script_id: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             level: DiagnosticLevel::Warn,
+            rule: rules::GENERAL,
             script_id: script_id.into(),
             line: None,
             message: message.into(),
@@ -75,6 +150,7 @@ impl Diagnostic {
     pub fn info(script_id: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             level: DiagnosticLevel::Info,
+            rule: rules::GENERAL,
             script_id: script_id.into(),
             line: None,
             message: message.into(),
@@ -82,6 +158,12 @@ impl Diagnostic {
         }
     }
 
+    /// 设置规则 id
+    pub fn with_rule(mut self, rule: &'static str) -> Self {
+        self.rule = rule;
+        self
+    }
+
     /// 设置行号
     pub fn with_line(mut self, line: usize) -> Self {
         self.line = Some(line);
@@ -101,7 +183,7 @@ impl std::fmt::Display for Diagnostic {
         if let Some(line) = self.line {
             write!(f, ":{}", line)?;
         }
-        write!(f, ": {}", self.message)?;
+        write!(f, ": {} [{}]", self.message, self.rule)?;
         if let Some(detail) = &self.detail {
             write!(f, "\n  | {}", detail)?;
         }
@@ -165,6 +247,17 @@ impl DiagnosticResult {
             .filter(|d| d.level >= min_level)
             .collect()
     }
+
+    /// 只保留不低于 `min_level` 的诊断
+    pub fn retain_min_level(&mut self, min_level: DiagnosticLevel) {
+        self.diagnostics.retain(|d| d.level >= min_level);
+    }
+
+    /// 屏蔽指定规则 id 的诊断
+    pub fn suppress_rules<S: AsRef<str>>(&mut self, rules: &[S]) {
+        self.diagnostics
+            .retain(|d| !rules.iter().any(|r| r.as_ref() == d.rule));
+    }
 }
 
 /// 资源引用信息
@@ -176,6 +269,8 @@ pub struct ResourceReference {
     pub path: String,
     /// 解析后的逻辑路径（相对于 assets_root）
     pub resolved_path: String,
+    /// 脚本内引用次数（按 `resolved_path` 合并）
+    pub count: usize,
 }
 
 /// 资源类型
@@ -185,6 +280,8 @@ pub enum ResourceType {
     Background,
     /// 场景图片
     Scene,
+    /// 全屏 CG
+    Cg,
     /// 角色立绘
     Character,
     /// 音频（BGM/SFX）
@@ -198,6 +295,7 @@ impl std::fmt::Display for ResourceType {
         match self {
             Self::Background => write!(f, "背景"),
             Self::Scene => write!(f, "场景"),
+            Self::Cg => write!(f, "CG"),
             Self::Character => write!(f, "立绘"),
             Self::Audio => write!(f, "音频"),
             Self::Video => write!(f, "视频"),
@@ -219,6 +317,7 @@ struct JumpTarget {
 ///
 /// 执行以下检查：
 /// - 未定义的跳转目标（goto/choice 目标 label 不存在）
+/// - 变量跳转目标（`goto $var`）无法静态校验，给出提示
 ///
 /// # 参数
 ///
@@ -245,6 +344,7 @@ pub fn analyze_script(script: &Script) -> DiagnosticResult {
                 &script.id,
                 format!("未定义的跳转目标: **{}**", target.label),
             )
+            .with_rule(rules::UNDEFINED_LABEL)
             .with_detail(format!(
                 "goto 或 choice 引用了不存在的 label '{}'",
                 target.label
@@ -256,6 +356,15 @@ pub fn analyze_script(script: &Script) -> DiagnosticResult {
         }
     }
 
+    for (index, node) in script.nodes.iter().enumerate() {
+        let line = script.get_source_line(index);
+        collect_dynamic_jumps(node, line, &script.id, &mut result);
+    }
+
+    for diag in expr_types::check_expression_types(script) {
+        result.push(diag);
+    }
+
     result
 }
 
@@ -278,7 +387,7 @@ fn collect_targets_from_node(
     targets: &mut Vec<JumpTarget>,
 ) {
     match node {
-        ScriptNode::Goto { target_label } => {
+        ScriptNode::Goto { target_label } | ScriptNode::TimedGoto { target_label, .. } => {
             targets.push(JumpTarget {
                 label: target_label.clone(),
                 line,
@@ -304,9 +413,42 @@ fn collect_targets_from_node(
     }
 }
 
+/// 对变量跳转目标给出"无法静态校验"提示（含条件分支内部）
+fn collect_dynamic_jumps(
+    node: &ScriptNode,
+    line: Option<usize>,
+    script_id: &str,
+    result: &mut DiagnosticResult,
+) {
+    match node {
+        ScriptNode::DynamicGoto { target } => {
+            let mut diag = Diagnostic::info(script_id, "goto 目标由变量决定，无法静态校验")
+                .with_rule(rules::DYNAMIC_JUMP)
+                .with_detail(match target {
+                    Expr::Variable(name) => format!("目标变量: ${}", name),
+                    other => format!("目标表达式: {:?}", other),
+                });
+            if let Some(line) = line {
+                diag = diag.with_line(line);
+            }
+            result.push(diag);
+        }
+        ScriptNode::Conditional { branches } => {
+            for branch in branches {
+                for inner_node in &branch.body {
+                    collect_dynamic_jumps(inner_node, line, script_id, result);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 /// 提取脚本中的所有资源引用
 ///
 /// 遍历脚本节点，提取背景、场景、角色立绘、音频等资源引用。
+/// 同一资源（`resolved_path` 相同）只保留首次出现的一条，`count` 记录引用次数，
+/// 调用方检查资源是否存在时无需重复访问文件系统。
 ///
 /// # 参数
 ///
@@ -314,59 +456,73 @@ fn collect_targets_from_node(
 ///
 /// # 返回
 ///
-/// 资源引用列表
+/// 资源引用列表（按首次出现顺序）
 pub fn extract_resource_references(script: &Script) -> Vec<ResourceReference> {
     let mut refs = Vec::new();
-    extract_from_nodes(&script.nodes, script, &mut refs);
+    let mut index = HashMap::new();
+    extract_from_nodes(&script.nodes, script, &mut refs, &mut index);
     refs
 }
 
-/// 从节点列表提取资源引用
-fn extract_from_nodes(nodes: &[ScriptNode], script: &Script, refs: &mut Vec<ResourceReference>) {
+/// 从节点列表提取资源引用；`index` 记录 resolved_path 在 `refs` 中的下标
+pub(crate) fn extract_from_nodes(
+    nodes: &[ScriptNode],
+    script: &Script,
+    refs: &mut Vec<ResourceReference>,
+    index: &mut HashMap<String, usize>,
+) {
     for node in nodes {
-        match node {
-            ScriptNode::ChangeBG { path, .. } => {
-                refs.push(ResourceReference {
-                    resource_type: ResourceType::Background,
-                    path: path.clone(),
-                    resolved_path: script.resolve_path(path),
-                });
-            }
-            ScriptNode::ChangeScene { path, .. } => {
-                refs.push(ResourceReference {
-                    resource_type: ResourceType::Scene,
-                    path: path.clone(),
-                    resolved_path: script.resolve_path(path),
-                });
-            }
-            ScriptNode::ShowCharacter { path: Some(p), .. } => {
-                refs.push(ResourceReference {
-                    resource_type: ResourceType::Character,
-                    path: p.clone(),
-                    resolved_path: script.resolve_path(p),
-                });
-            }
-            ScriptNode::PlayAudio { path, .. } => {
-                refs.push(ResourceReference {
-                    resource_type: ResourceType::Audio,
-                    path: path.clone(),
-                    resolved_path: script.resolve_path(path),
-                });
-            }
-            ScriptNode::Cutscene { path } => {
-                refs.push(ResourceReference {
-                    resource_type: ResourceType::Video,
-                    path: path.clone(),
-                    resolved_path: script.resolve_path(path),
-                });
+        let (resource_type, path) = match node {
+            ScriptNode::ChangeBG { path, .. } => (ResourceType::Background, path),
+            ScriptNode::ChangeScene { path, .. } => (ResourceType::Scene, path),
+            ScriptNode::ShowCg { path, .. } => (ResourceType::Cg, path),
+            ScriptNode::ShowCharacter { path: Some(p), .. } => (ResourceType::Character, p),
+            ScriptNode::PlayAudio { path, .. } => (ResourceType::Audio, path),
+            ScriptNode::Cutscene { path } => (ResourceType::Video, path),
+            ScriptNode::SetBackgroundLayers { layers } => {
+                for layer in layers {
+                    record_reference(ResourceType::Background, &layer.path, script, refs, index);
+                }
+                continue;
             }
             ScriptNode::Conditional { branches } => {
                 // 递归提取条件分支中的资源引用
                 for branch in branches {
-                    extract_from_nodes(&branch.body, script, refs);
+                    extract_from_nodes(&branch.body, script, refs, index);
+                }
+                continue;
+            }
+            ScriptNode::Timeline { steps } => {
+                for step in steps {
+                    extract_from_nodes(std::slice::from_ref(&step.node), script, refs, index);
                 }
+                continue;
             }
-            _ => {}
+            _ => continue,
+        };
+        record_reference(resource_type, path, script, refs, index);
+    }
+}
+
+/// 登记一次资源引用，同一 resolved_path 只累加次数
+fn record_reference(
+    resource_type: ResourceType,
+    path: &str,
+    script: &Script,
+    refs: &mut Vec<ResourceReference>,
+    index: &mut HashMap<String, usize>,
+) {
+    let resolved_path = script.resolve_path(path);
+    match index.get(&resolved_path) {
+        Some(&i) => refs[i].count += 1,
+        None => {
+            index.insert(resolved_path.clone(), refs.len());
+            refs.push(ResourceReference {
+                resource_type,
+                path: path.to_string(),
+                resolved_path,
+                count: 1,
+            });
         }
     }
 }
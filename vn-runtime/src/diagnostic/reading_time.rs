@@ -0,0 +1,159 @@
+//! 阅读时长估算
+//!
+//! 按文本字数与平均阅读速度估算脚本的阅读时长，并按章节标记分段统计，
+//! 供存档界面与制作统计使用。
+//!
+//! - 计入对话、`extend` 追加文本与选项文本，不计空白字符
+//! - 条件分支的各个分支全部计入（给出上限估计）
+//! - 第一个章节标记之前的文本归入无标题的开头段
+
+use crate::script::{Script, ScriptNode};
+
+/// 默认平均阅读速度（字/分钟）
+pub const DEFAULT_READING_CHARS_PER_MINUTE: f64 = 400.0;
+
+/// 单个章节的阅读统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterReadingTime {
+    /// 章节标题；第一个章节标记之前的开头段为 None
+    pub title: Option<String>,
+    /// 章节级别（开头段为 0）
+    pub level: u8,
+    /// 文本字数
+    pub chars: usize,
+    /// 预计阅读秒数
+    pub seconds: f64,
+}
+
+/// 脚本阅读时长估算结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadingTimeEstimate {
+    /// 估算使用的阅读速度（字/分钟）
+    pub chars_per_minute: f64,
+    /// 全部文本字数
+    pub total_chars: usize,
+    /// 预计总阅读秒数
+    pub total_seconds: f64,
+    /// 按出现顺序的章节统计（没有文本的开头段不列出）
+    pub chapters: Vec<ChapterReadingTime>,
+}
+
+/// 估算脚本阅读时长
+///
+/// `chars_per_minute` 不为正数时按 [`DEFAULT_READING_CHARS_PER_MINUTE`] 计算。
+pub fn estimate_reading_time(script: &Script, chars_per_minute: f64) -> ReadingTimeEstimate {
+    let chars_per_minute = effective_speed(chars_per_minute);
+
+    let mut chapters = vec![ChapterReadingTime {
+        title: None,
+        level: 0,
+        chars: 0,
+        seconds: 0.0,
+    }];
+    count_nodes(&script.nodes, &mut chapters);
+    if chapters[0].chars == 0 {
+        chapters.remove(0);
+    }
+
+    let seconds_of = |chars: usize| chars as f64 * 60.0 / chars_per_minute;
+    for chapter in &mut chapters {
+        chapter.seconds = seconds_of(chapter.chars);
+    }
+    let total_chars = chapters.iter().map(|c| c.chars).sum();
+
+    ReadingTimeEstimate {
+        chars_per_minute,
+        total_chars,
+        total_seconds: seconds_of(total_chars),
+        chapters,
+    }
+}
+
+/// 估算第 `node_index` 个顶层节点所在章节的阅读时长
+///
+/// `chars_per_minute` 的处理与 [`estimate_reading_time`] 相同。章节范围为该节点之前最近的顶层章节标记到下一个顶层章节标记；
+/// 之前没有章节标记时为开头段（`title` 为 None）。
+pub fn chapter_reading_time_at(
+    script: &Script,
+    node_index: usize,
+    chars_per_minute: f64,
+) -> ChapterReadingTime {
+    let is_chapter = |node: &ScriptNode| matches!(node, ScriptNode::Chapter { .. });
+    let nodes = &script.nodes;
+    let position = node_index.min(nodes.len().saturating_sub(1));
+    let start = nodes
+        .get(..=position)
+        .and_then(|head| head.iter().rposition(is_chapter))
+        .unwrap_or(0);
+    let end = nodes[start..]
+        .iter()
+        .skip(1)
+        .position(is_chapter)
+        .map_or(nodes.len(), |offset| start + 1 + offset);
+
+    let mut chapters = vec![ChapterReadingTime {
+        title: None,
+        level: 0,
+        chars: 0,
+        seconds: 0.0,
+    }];
+    count_nodes(&nodes[start..end], &mut chapters);
+    // 分支内嵌的章节标记也计入本章
+    let chars = chapters.iter().map(|c| c.chars).sum();
+    let (title, level) = match nodes.get(start) {
+        Some(ScriptNode::Chapter { title, level }) => (Some(title.clone()), *level),
+        _ => (None, 0),
+    };
+    ChapterReadingTime {
+        title,
+        level,
+        chars,
+        seconds: chars as f64 * 60.0 / effective_speed(chars_per_minute),
+    }
+}
+
+/// 不为正数的阅读速度按默认值计算
+fn effective_speed(chars_per_minute: f64) -> f64 {
+    if chars_per_minute > 0.0 {
+        chars_per_minute
+    } else {
+        DEFAULT_READING_CHARS_PER_MINUTE
+    }
+}
+
+/// 累计字数到当前（最后一个）章节，遇到章节标记时开新段
+fn count_nodes(nodes: &[ScriptNode], chapters: &mut Vec<ChapterReadingTime>) {
+    for node in nodes {
+        let chars = match node {
+            ScriptNode::Chapter { title, level } => {
+                chapters.push(ChapterReadingTime {
+                    title: Some(title.clone()),
+                    level: *level,
+                    chars: 0,
+                    seconds: 0.0,
+                });
+                continue;
+            }
+            ScriptNode::Dialogue { content, .. } | ScriptNode::Extend { content, .. } => {
+                text_chars(content)
+            }
+            ScriptNode::Choice { options, .. } => {
+                options.iter().map(|option| text_chars(&option.text)).sum()
+            }
+            ScriptNode::Conditional { branches } => {
+                for branch in branches {
+                    count_nodes(&branch.body, chapters);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if let Some(current) = chapters.last_mut() {
+            current.chars += chars;
+        }
+    }
+}
+
+fn text_chars(text: &str) -> usize {
+    text.chars().filter(|c| !c.is_whitespace()).count()
+}
@@ -36,6 +36,31 @@ goto **end**
     assert!(result.is_empty());
 }
 
+#[test]
+fn test_analyze_script_marks_variable_goto_as_unverifiable() {
+    let mut parser = Parser::new();
+    let text = r#"
+set $next = "end"
+goto $next
+
+**end**
+角色："结束"
+"#;
+
+    let script = parser.parse("test", text).unwrap();
+    let result = analyze_script(&script);
+
+    assert!(!result.has_errors());
+    assert_eq!(result.diagnostics.len(), 1);
+    let diag = &result.diagnostics[0];
+    assert_eq!(diag.level, DiagnosticLevel::Info);
+    assert_eq!(diag.rule, rules::DYNAMIC_JUMP);
+    assert_eq!(diag.line, Some(3));
+    assert_eq!(diag.detail.as_deref(), Some("目标变量: $next"));
+    // 动态目标不进入静态跳转目标集合
+    assert!(get_jump_targets(&script).is_empty());
+}
+
 #[test]
 fn test_analyze_script_choice_targets() {
     let mut parser = Parser::new();
@@ -225,6 +250,40 @@ fn test_extract_resource_references_includes_cutscene() {
     assert_eq!(refs[0].resolved_path, "scripts/video/opening.mp4");
 }
 
+#[test]
+fn test_extract_resource_references_dedups_and_counts() {
+    let mut parser = Parser::new();
+    let text = r#"
+changeBG <img src="bg/room.png" />
+show <img src="chara/alice.png" /> as alice at center
+changeBG <img src="bg/room.png" />
+if $flag == true
+  changeBG <img src="bg/room.png" />
+endif
+show <img src="chara/alice.png" /> as alice at left
+<audio src="bgm/theme.mp3"></audio> loop
+"#;
+    let script = parser
+        .parse_with_base_path("test", text, "scripts")
+        .unwrap();
+    let refs = extract_resource_references(&script);
+
+    // 条件分支内的引用同样计入，顺序按首次出现
+    let summary: Vec<(&str, usize)> = refs
+        .iter()
+        .map(|r| (r.resolved_path.as_str(), r.count))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("scripts/bg/room.png", 3),
+            ("scripts/chara/alice.png", 2),
+            ("scripts/bgm/theme.mp3", 1),
+        ]
+    );
+    assert_eq!(refs[0].path, "bg/room.png");
+}
+
 #[test]
 fn test_get_jump_targets() {
     let mut parser = Parser::new();
@@ -280,3 +339,498 @@ fn test_diagnostic_result_merge() {
     assert_eq!(result1.error_count(), 1);
     assert_eq!(result1.warn_count(), 1);
 }
+
+#[test]
+fn test_diagnostic_result_retain_errors_and_suppress_rules() {
+    let mut result = DiagnosticResult::new();
+    result.push(Diagnostic::error("a.md", "未定义").with_rule(rules::UNDEFINED_LABEL));
+    result.push(Diagnostic::warn("a.md", "缺资源").with_rule(rules::MISSING_RESOURCE));
+    result.push(Diagnostic::info("a.md", "提示"));
+
+    let mut errors_only = result.clone();
+    errors_only.retain_min_level(DiagnosticLevel::Error);
+    assert_eq!(errors_only.diagnostics.len(), 1);
+    assert!(
+        errors_only
+            .diagnostics
+            .iter()
+            .all(|d| d.level == DiagnosticLevel::Error)
+    );
+
+    result.suppress_rules(&[rules::MISSING_RESOURCE, rules::GENERAL]);
+    assert_eq!(result.diagnostics.len(), 1);
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .all(|d| d.rule == rules::UNDEFINED_LABEL)
+    );
+}
+
+#[test]
+fn test_undefined_label_has_stable_rule_and_level_parses() {
+    let mut parser = Parser::new();
+    let script = parser.parse("test", "goto **nowhere**").unwrap();
+    let result = analyze_script(&script);
+    assert_eq!(result.diagnostics[0].rule, "undefined-label");
+    assert!(
+        result.diagnostics[0]
+            .to_string()
+            .contains("[undefined-label]")
+    );
+
+    assert_eq!(
+        "ERROR".parse::<DiagnosticLevel>(),
+        Ok(DiagnosticLevel::Error)
+    );
+    assert_eq!(
+        "warning".parse::<DiagnosticLevel>(),
+        Ok(DiagnosticLevel::Warn)
+    );
+    assert!("fatal".parse::<DiagnosticLevel>().is_err());
+}
+
+fn expr_type_warnings(text: &str) -> Vec<Diagnostic> {
+    let script = Parser::new().parse("test", text).unwrap();
+    analyze_script(&script)
+        .diagnostics
+        .into_iter()
+        .filter(|d| d.rule == rules::EXPR_TYPE)
+        .collect()
+}
+
+#[test]
+fn test_expr_type_mismatch_is_warned() {
+    // 布尔结果再与数字比较：恒为 false
+    let warnings = expr_type_warnings(
+        r#"
+set $a = "yes"
+set $x = $a == "yes"
+if $x == 1
+  角色："不会出现"
+endif
+"#,
+    );
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].level, DiagnosticLevel::Warn);
+    assert_eq!(warnings[0].line, Some(4));
+    assert!(warnings[0].message.contains("布尔 与 整数"));
+
+    // 字符串用于逻辑运算 / 作为条件
+    let warnings = expr_type_warnings(
+        r#"
+set $name = "alice"
+set $ok = not $name
+if $name
+  角色："不会出现"
+endif
+"#,
+    );
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert!(warnings[0].message.contains("not 的操作数是字符串类型"));
+    assert!(warnings[1].message.contains("条件表达式是字符串类型"));
+}
+
+#[test]
+fn test_expr_type_in_list_with_mismatched_element_is_warned() {
+    let warnings = expr_type_warnings(
+        r#"
+set $item = "a"
+if $item in ["a", 2]
+  角色："一半不会匹配"
+endif
+if $item in ["a", "b"]
+  角色："类型一致"
+endif
+"#,
+    );
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].line, Some(3));
+    assert!(warnings[0].message.contains("in 列表含整数类型元素"));
+}
+
+#[test]
+fn test_expr_type_infers_plus_result() {
+    let warnings = expr_type_warnings(
+        r#"
+set $count = 1
+set $msg = "第" + $count
+set $next = $count + 1
+if $msg == 3 or $next == 2
+  角色："拼接结果是字符串"
+endif
+set $bad = true + 1
+"#,
+    );
+    assert_eq!(warnings.len(), 2, "{warnings:?}");
+    assert!(warnings[0].message.contains("字符串 与 整数"));
+    assert!(warnings[1].message.contains("+ 的操作数是布尔类型"));
+    assert_eq!(warnings[1].line, Some(8));
+}
+
+#[test]
+fn test_expr_type_compare_with_non_number_is_warned() {
+    let warnings = expr_type_warnings(
+        r#"
+set $gold = 10
+set $name = "alice"
+if $gold >= 5
+  角色："数字比较"
+endif
+if $name < 3
+  角色："字符串不能比较大小"
+endif
+"#,
+    );
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].line, Some(7));
+    assert!(warnings[0].message.contains("< 的操作数是字符串类型"));
+}
+
+#[test]
+fn test_expr_type_consistent_or_unknown_is_not_warned() {
+    let warnings = expr_type_warnings(
+        r#"
+set $x = $flag
+set $flag = true
+set $count = 1
+if $x and not $flag or $count == 2
+  角色："类型一致"
+endif
+requestUI "name_input" as $name
+if $name == 3 and $persistent.cleared == "yes"
+  角色："未知类型不参与检查"
+endif
+"#,
+    );
+    assert!(warnings.is_empty(), "{warnings:?}");
+}
+
+#[test]
+fn test_estimate_reading_time_totals_and_per_chapter() {
+    let mut parser = Parser::new();
+    let text = r#"
+：“序幕十个字的旁白文本”
+
+# 第一章
+
+北风：“一二三四五六七八九十”
+extend "十个字 的 追加文本内容"
+
+| 选项 | 跳转 |
+| --- | --- |
+| 五个字选项 | a |
+| 五个字选项 | a |
+
+**a**
+
+## 第二章
+
+if $flag == true
+  ：“条件分支的十个字文本”
+else
+  ：“另一分支的十个字文本”
+endif
+"#;
+    let script = parser.parse("test", text).unwrap();
+
+    // 600 字/分钟 → 每字 0.1 秒
+    let estimate = estimate_reading_time(&script, 600.0);
+    let chapters: Vec<(Option<&str>, u8, usize)> = estimate
+        .chapters
+        .iter()
+        .map(|c| (c.title.as_deref(), c.level, c.chars))
+        .collect();
+    assert_eq!(
+        chapters,
+        [
+            (None, 0, 10),
+            (Some("第一章"), 1, 30),
+            (Some("第二章"), 2, 20),
+        ]
+    );
+    assert_eq!(estimate.total_chars, 60);
+    assert!((estimate.total_seconds - 6.0).abs() < 1e-9);
+    assert!((estimate.chapters[1].seconds - 3.0).abs() < 1e-9);
+
+    // 非正速度回退到默认速度
+    let fallback = estimate_reading_time(&script, 0.0);
+    assert_eq!(fallback.chars_per_minute, DEFAULT_READING_CHARS_PER_MINUTE);
+    assert!((fallback.total_seconds - 9.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_chapter_reading_time_at_covers_enclosing_chapter() {
+    let mut parser = Parser::new();
+    let text = r#"
+：“序幕十个字的旁白文本”
+
+# 第一章
+
+北风：“一二三四五六七八九十”
+北风：“一二三四五六七八九十”
+
+# 第二章
+
+：“第二章里的十个字文本”
+"#;
+    let script = parser.parse("test", text).unwrap();
+    let chapter_of = |node_index: usize| {
+        let c = chapter_reading_time_at(&script, node_index, 600.0);
+        (c.title, c.chars)
+    };
+    let first_chapter = script
+        .nodes
+        .iter()
+        .position(|n| matches!(n, ScriptNode::Chapter { .. }))
+        .unwrap();
+
+    assert_eq!(chapter_of(0), (None, 10));
+    assert_eq!(chapter_of(first_chapter), (Some("第一章".to_string()), 20));
+    assert_eq!(
+        chapter_of(first_chapter + 2),
+        (Some("第一章".to_string()), 20)
+    );
+    // 超出范围的位置按最后一个章节计算
+    assert_eq!(chapter_of(usize::MAX), (Some("第二章".to_string()), 10));
+    let seconds = chapter_reading_time_at(&script, first_chapter + 1, 600.0).seconds;
+    assert!((seconds - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_analyze_branch_weights_counts_reachable_nodes_per_option() {
+    let mut parser = Parser::new();
+    let text = r#"
+：“开场”
+
+| 去哪里 | |
+| --- | --- |
+| 海边 | sea |
+| 山上 | mountain |
+| 回家 | home |
+
+**sea**
+：“海风”
+：“浪花”
+：“沙滩”
+goto **end**
+
+**mountain**
+if $tired
+  ：“太累了”
+  goto **end**
+endif
+：“山顶”
+goto **end**
+
+**home**
+：“到家了”
+returnFromScript
+
+**end**
+：“结局”
+"#;
+    let script = parser.parse("test", text).unwrap();
+
+    let stats = analyze_branch_weights(&script);
+    assert_eq!(stats.len(), 1);
+    let choice = &stats[0];
+    assert!(choice.line.is_some());
+
+    let weights: Vec<(&str, usize)> = choice
+        .options
+        .iter()
+        .map(|o| (o.target_label.as_str(), o.reachable_nodes))
+        .collect();
+    // sea: 3 句 + goto + 汇合后的 1 句
+    // mountain: 条件内 2 个节点 + 1 句 + goto + 汇合后的 1 句
+    // home: 1 句 + returnFromScript
+    assert_eq!(weights, [("sea", 5), ("mountain", 5), ("home", 2)]);
+    assert_eq!(choice.min_reachable(), 2);
+    assert_eq!(choice.max_reachable(), 5);
+    assert!(!choice.is_unbalanced());
+}
+
+#[test]
+fn test_analyze_branch_weights_flags_unbalanced_and_handles_loops() {
+    let mut parser = Parser::new();
+    let text = r#"
+**loop**
+：“一”
+：“二”
+：“三”
+
+| 继续吗 | |
+| --- | --- |
+| 再来一次 | loop |
+| 结束 | stop |
+| 不存在 | missing |
+
+**stop**
+returnFromScript
+"#;
+    let script = parser.parse("test", text).unwrap();
+
+    let stats = analyze_branch_weights(&script);
+    let weights: Vec<usize> = stats[0].options.iter().map(|o| o.reachable_nodes).collect();
+    // loop 回到选择点本身，循环中每个节点只计一次：3 句 + 选择 + returnFromScript
+    assert_eq!(weights, [5, 1, 0]);
+    assert!(stats[0].is_unbalanced());
+}
+
+#[test]
+fn test_lint_whitespace_flags_fullwidth_space_lines_only() {
+    let source = "北风：\"早上好\"\n\u{3000}changeBG <img src=\"bg.png\" />\n红叶：\"\u{3000}\u{3000}嗯。\"\n";
+
+    let result = lint_whitespace("test", source);
+    let flagged: Vec<(usize, DiagnosticLevel)> = result
+        .diagnostics
+        .iter()
+        .map(|d| (d.line.unwrap(), d.level))
+        .collect();
+    // 纯净行不标记；引号外的全角空格为 Warn，引号内的排版空格只给 Info
+    assert_eq!(
+        flagged,
+        [(2, DiagnosticLevel::Warn), (3, DiagnosticLevel::Info)]
+    );
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .all(|d| d.rule == rules::FULLWIDTH_SPACE)
+    );
+}
+
+#[test]
+fn test_lint_whitespace_mixed_indent_trailing_and_clean() {
+    let source = "\t  ：\"你好\"  \r\n：\"行尾全角\"\u{3000}\n";
+
+    let rules_hit: Vec<&str> = lint_whitespace("test", source)
+        .diagnostics
+        .iter()
+        .map(|d| d.rule)
+        .collect();
+    assert_eq!(
+        rules_hit,
+        [
+            rules::MIXED_INDENT,
+            rules::TRAILING_WHITESPACE,
+            // 引号外的行尾全角空格同时属于全角空格与行尾空白
+            rules::FULLWIDTH_SPACE,
+            rules::TRAILING_WHITESPACE
+        ]
+    );
+
+    let cleaned = clean_whitespace(source);
+    assert_eq!(cleaned, "      ：\"你好\"\r\n：\"行尾全角\"\n");
+    assert!(lint_whitespace("test", &cleaned).is_empty());
+}
+
+#[test]
+fn test_check_complexity_flags_node_count_over_limit() {
+    let text = (1..=6)
+        .map(|i| format!("：\"第 {i} 句\""))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let script = Parser::new().parse("test", &text).unwrap();
+    let limits = ComplexityLimits {
+        max_nodes: 5,
+        ..ComplexityLimits::default()
+    };
+
+    let result = check_complexity(&script, &limits);
+    assert_eq!(result.diagnostics.len(), 1);
+    let diag = &result.diagnostics[0];
+    assert_eq!(diag.rule, rules::SCRIPT_TOO_LARGE);
+    assert_eq!(diag.level, DiagnosticLevel::Warn);
+    assert!(diag.message.contains("6"), "{}", diag.message);
+
+    // 恰好等于上限不提示
+    let at_limit = ComplexityLimits {
+        max_nodes: 6,
+        ..limits
+    };
+    assert!(check_complexity(&script, &at_limit).is_empty());
+}
+
+#[test]
+fn test_check_complexity_flags_deep_nesting() {
+    use crate::script::ast::ConditionalBranch;
+
+    // 按 AST 计算深度：分支体内的条件节点再加一层
+    let nest = |body: Vec<ScriptNode>| ScriptNode::Conditional {
+        branches: vec![ConditionalBranch {
+            condition: None,
+            body,
+        }],
+    };
+    let innermost = nest(vec![ScriptNode::Pause]);
+    let script = crate::script::Script::new(
+        "test",
+        vec![ScriptNode::Pause, nest(vec![nest(vec![innermost])])],
+        "",
+    );
+    let limits = ComplexityLimits {
+        max_conditional_depth: 2,
+        ..ComplexityLimits::default()
+    };
+
+    let result = check_complexity(&script, &limits);
+    assert_eq!(result.diagnostics.len(), 1);
+    assert_eq!(result.diagnostics[0].rule, rules::DEEP_NESTING);
+    assert_eq!(result.diagnostics[0].level, DiagnosticLevel::Warn);
+    assert!(result.diagnostics[0].message.contains("3 层"));
+
+    let relaxed = ComplexityLimits {
+        max_conditional_depth: 3,
+        ..limits
+    };
+    assert!(check_complexity(&script, &relaxed).is_empty());
+}
+
+#[test]
+fn test_check_complexity_reports_choice_with_many_options() {
+    let text = r#"
+："开场"
+| 选择 | |
+| --- | --- |
+| 一 | **end** |
+| 二 | **end** |
+| 三 | **end** |
+**end**
+"#;
+    let script = Parser::new().parse("test", text).unwrap();
+    let limits = ComplexityLimits {
+        max_choice_options: 2,
+        ..ComplexityLimits::default()
+    };
+
+    let result = check_complexity(&script, &limits);
+    let hits: Vec<_> = result
+        .diagnostics
+        .iter()
+        .map(|d| (d.rule, d.level, d.line))
+        .collect();
+    assert_eq!(
+        hits,
+        [(rules::TOO_MANY_OPTIONS, DiagnosticLevel::Info, Some(3))]
+    );
+}
+
+#[test]
+fn test_check_complexity_quiet_for_ordinary_script() {
+    let text = r#"
+**start**
+："你好"
+if $flag == true
+  ："分支"
+endif
+| 选择 | |
+| --- | --- |
+| 去 | **start** |
+| 留 | **end** |
+**end**
+"#;
+    let script = Parser::new().parse("test", text).unwrap();
+    assert!(check_complexity(&script, &ComplexityLimits::default()).is_empty());
+}
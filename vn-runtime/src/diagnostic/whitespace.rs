@@ -0,0 +1,151 @@
+//! 空白字符检查
+//!
+//! 直接扫描脚本源码，报告容易导致解析怪异的空白字符，并提供自动清理：
+//!
+//! - 引号外的全角空格（U+3000）：Warn；引号内的全角空格属于正文排版，只给 Info
+//! - 行首缩进混用制表符与空格：Warn
+//! - 行尾空白（含全角空格）：Info
+
+use super::{Diagnostic, DiagnosticLevel, DiagnosticResult, rules};
+
+/// 全角空格
+const FULLWIDTH_SPACE: char = '\u{3000}';
+/// 清理时一个制表符展开成的空格数
+const TAB_WIDTH: usize = 4;
+
+/// 检查脚本源码中的空白字符问题
+pub fn lint_whitespace(script_id: &str, source: &str) -> DiagnosticResult {
+    let mut result = DiagnosticResult::new();
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let diagnostic = |level, rule, message: &str| Diagnostic {
+            level,
+            rule,
+            script_id: script_id.to_string(),
+            line: Some(line_number),
+            message: message.to_string(),
+            detail: Some(line.to_string()),
+        };
+
+        let (outside, inside) = count_fullwidth_spaces(line);
+        if outside > 0 {
+            result.push(diagnostic(
+                DiagnosticLevel::Warn,
+                rules::FULLWIDTH_SPACE,
+                "引号外含全角空格",
+            ));
+        } else if inside > 0 {
+            result.push(diagnostic(
+                DiagnosticLevel::Info,
+                rules::FULLWIDTH_SPACE,
+                "文本中含全角空格",
+            ));
+        }
+
+        let indent: String = line
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        if indent.contains(' ') && indent.contains('\t') {
+            result.push(diagnostic(
+                DiagnosticLevel::Warn,
+                rules::MIXED_INDENT,
+                "行首缩进混用制表符与空格",
+            ));
+        }
+
+        if line.ends_with(is_lint_whitespace) {
+            result.push(diagnostic(
+                DiagnosticLevel::Info,
+                rules::TRAILING_WHITESPACE,
+                "行尾有多余空白",
+            ));
+        }
+    }
+    result
+}
+
+/// 自动清理空白字符问题，返回清理后的源码
+///
+/// 删除行尾空白，引号外的全角空格换成半角空格，行首制表符按 4 个空格展开；
+/// 引号内的全角空格与原有换行符保持不变。
+pub fn clean_whitespace(source: &str) -> String {
+    let mut cleaned = String::with_capacity(source.len());
+    for segment in source.split_inclusive('\n') {
+        let (line, ending) = match segment.strip_suffix("\r\n") {
+            Some(line) => (line, "\r\n"),
+            None => match segment.strip_suffix('\n') {
+                Some(line) => (line, "\n"),
+                None => (segment, ""),
+            },
+        };
+        let line = line.trim_end_matches(is_lint_whitespace);
+
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        for c in line[..indent_len].chars() {
+            match c {
+                '\t' => cleaned.extend(std::iter::repeat_n(' ', TAB_WIDTH)),
+                c => cleaned.push(c),
+            }
+        }
+        let mut quote = QuoteTracker::default();
+        for c in line[indent_len..].chars() {
+            let quoted = quote.step(c);
+            cleaned.push(if c == FULLWIDTH_SPACE && !quoted {
+                ' '
+            } else {
+                c
+            });
+        }
+        cleaned.push_str(ending);
+    }
+    cleaned
+}
+
+fn is_lint_whitespace(c: char) -> bool {
+    c == ' ' || c == '\t' || c == FULLWIDTH_SPACE
+}
+
+/// 统计一行中引号外 / 引号内的全角空格数
+fn count_fullwidth_spaces(line: &str) -> (usize, usize) {
+    let mut quote = QuoteTracker::default();
+    let (mut outside, mut inside) = (0, 0);
+    for c in line.chars() {
+        let quoted = quote.step(c);
+        if c == FULLWIDTH_SPACE {
+            if quoted {
+                inside += 1;
+            } else {
+                outside += 1;
+            }
+        }
+    }
+    (outside, inside)
+}
+
+/// 逐字符跟踪是否位于引号内（支持 `"..."` 与 `“...”`）
+#[derive(Default)]
+struct QuoteTracker {
+    closing: Option<char>,
+}
+
+impl QuoteTracker {
+    /// 处理一个字符，返回该字符是否位于引号内部（引号本身不算）
+    fn step(&mut self, c: char) -> bool {
+        match self.closing {
+            Some(closing) if c == closing => {
+                self.closing = None;
+                false
+            }
+            Some(_) => true,
+            None => {
+                self.closing = match c {
+                    '"' => Some('"'),
+                    '“' => Some('”'),
+                    _ => None,
+                };
+                false
+            }
+        }
+    }
+}
@@ -1,6 +1,10 @@
 //! # Error 模块
 //!
 //! 定义 vn-runtime 中使用的错误类型。
+//!
+//! 每个错误变体都有稳定的错误码（[`ParseError::code`] / [`RuntimeError::code`]），
+//! `Display` 输出形如 `[E0101] 标签 'x' 未找到`，供 Host 上报与文档检索。
+//! 错误码一经分配不再变更；新增变体只追加新码，删除变体时其码作废不复用。
 
 use crate::script::EvalError;
 use thiserror::Error;
@@ -9,15 +13,25 @@ use thiserror::Error;
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum ParseError {
     /// 无效的行格式
-    #[error("第 {line} 行：无效的格式 - {message}")]
-    InvalidLine { line: usize, message: String },
+    ///
+    /// 表达式错误附带列号（从 1 开始，按字符计），`Display` 输出为 `行:列`。
+    #[error(
+        "[{code}] 第 {location} 行：无效的格式 - {message}",
+        code = self.code(),
+        location = location(*line, *column)
+    )]
+    InvalidLine {
+        line: usize,
+        column: Option<usize>,
+        message: String,
+    },
 
     /// 无效的指令
-    #[error("第 {line} 行：未知指令 '{command}'")]
+    #[error("[{code}] 第 {line} 行：未知指令 '{command}'", code = self.code())]
     UnknownCommand { line: usize, command: String },
 
     /// 缺少必需参数
-    #[error("第 {line} 行：指令 '{command}' 缺少参数 '{param}'")]
+    #[error("[{code}] 第 {line} 行：指令 '{command}' 缺少参数 '{param}'", code = self.code())]
     MissingParameter {
         line: usize,
         command: String,
@@ -25,7 +39,7 @@ pub enum ParseError {
     },
 
     /// 无效的参数值
-    #[error("第 {line} 行：参数 '{param}' 的值无效 - {message}")]
+    #[error("[{code}] 第 {line} 行：参数 '{param}' 的值无效 - {message}", code = self.code())]
     InvalidParameter {
         line: usize,
         param: String,
@@ -33,53 +47,396 @@ pub enum ParseError {
     },
 
     /// 无效的表格格式
-    #[error("第 {line} 行：无效的表格格式 - {message}")]
+    #[error("[{code}] 第 {line} 行：无效的表格格式 - {message}", code = self.code())]
     InvalidTable { line: usize, message: String },
 
     /// 无效的过渡效果语法
-    #[error("第 {line} 行：无效的过渡效果语法 - {message}")]
+    #[error("[{code}] 第 {line} 行：无效的过渡效果语法 - {message}", code = self.code())]
     InvalidTransition { line: usize, message: String },
+
+    /// 脚本声明的语法版本不支持该语法
+    #[error(
+        "[{code}] 第 {line} 行：{feature} 需要 syntax {required} 及以上，脚本声明为 syntax {declared}",
+        code = self.code()
+    )]
+    UnsupportedSyntax {
+        line: usize,
+        feature: String,
+        required: u32,
+        declared: u32,
+    },
+
+    /// 无效的关键字别名配置
+    #[error("[{code}] 关键字别名 '{alias}' 无效 - {message}", code = self.code())]
+    InvalidKeywordAlias { alias: String, message: String },
+}
+
+impl ParseError {
+    /// 稳定错误码（`E00xx`）
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidLine { .. } => "E0001",
+            Self::UnknownCommand { .. } => "E0002",
+            Self::MissingParameter { .. } => "E0003",
+            Self::InvalidParameter { .. } => "E0004",
+            Self::InvalidTable { .. } => "E0005",
+            Self::InvalidTransition { .. } => "E0006",
+            Self::InvalidKeywordAlias { .. } => "E0007",
+            Self::UnsupportedSyntax { .. } => "E0008",
+        }
+    }
+
+    /// 不含错误码前缀的消息
+    pub fn message(&self) -> String {
+        strip_code(self.to_string(), self.code())
+    }
+
+    /// 出错的脚本行号（从 1 开始）；别名配置错误没有行号
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::InvalidLine { line, .. }
+            | Self::UnknownCommand { line, .. }
+            | Self::MissingParameter { line, .. }
+            | Self::InvalidParameter { line, .. }
+            | Self::InvalidTable { line, .. }
+            | Self::InvalidTransition { line, .. }
+            | Self::UnsupportedSyntax { line, .. } => Some(*line),
+            Self::InvalidKeywordAlias { .. } => None,
+        }
+    }
+
+    /// 出错的列号（从 1 开始，按字符计）；目前只有表达式错误记录列号
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            Self::InvalidLine { column, .. } => *column,
+            _ => None,
+        }
+    }
+
+    /// 平移列号：把片段内的列换算为所在行的列（无列号时原样返回）
+    pub(crate) fn shift_column(mut self, delta: isize) -> Self {
+        if let Self::InvalidLine {
+            column: Some(column),
+            ..
+        } = &mut self
+        {
+            *column = column.saturating_add_signed(delta).max(1);
+        }
+        self
+    }
+}
+
+fn location(line: usize, column: Option<usize>) -> String {
+    match column {
+        Some(column) => format!("{line}:{column}"),
+        None => line.to_string(),
+    }
 }
 
 /// 运行时错误
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum RuntimeError {
     /// 标签未找到
-    #[error("标签 '{label}' 未找到")]
+    #[error("[{code}] 标签 '{label}' 未找到", code = self.code())]
     LabelNotFound { label: String },
 
+    /// 书签未找到
+    #[error("[{code}] 书签 '{name}' 未找到", code = self.code())]
+    BookmarkNotFound { name: String },
+
     /// 无效的选择索引
-    #[error("无效的选择索引 {index}，有效范围是 0..{max}")]
+    #[error("[{code}] 无效的选择索引 {index}，有效范围是 0..{max}", code = self.code())]
     InvalidChoiceIndex { index: usize, max: usize },
 
-    /// 状态不匹配
-    #[error("当前状态不允许此操作：期望 {expected}，实际 {actual}")]
-    StateMismatch { expected: String, actual: String },
+    /// 选中了条件不满足的选项
+    #[error("[{code}] 选项 {index} 当前不可选", code = self.code())]
+    DisabledChoice { index: usize },
+
+    /// 选择分支的所有选项条件都不满足，玩家无从选择
+    #[error("[{code}] 选择分支的 {count} 个选项都不可选", code = self.code())]
+    NoEnabledChoice { count: usize },
+
+    /// 输入与当前等待状态不匹配（如等待选择时收到 Click）
+    #[error("[{code}] 当前等待 {waiting}，不接受输入 {input}", code = self.code())]
+    UnexpectedInput { waiting: String, input: String },
 
     /// 无效的状态操作
-    #[error("无效的状态操作: {message}")]
+    #[error("[{code}] 无效的状态操作: {message}", code = self.code())]
     InvalidState { message: String },
 
     /// 目标脚本未加载
-    #[error("脚本 '{path}' 未加载，无法执行 callScript")]
+    #[error("[{code}] 脚本 '{path}' 未加载，无法执行 callScript", code = self.code())]
     ScriptNotLoaded { path: String },
 
+    /// 存档位置与当前脚本不兼容（脚本已变更）
+    #[error("[{code}] 存档与脚本不兼容: {reason}", code = self.code())]
+    IncompatibleSave { reason: String },
+
     /// 表达式求值错误
-    #[error("表达式求值错误: {0}")]
+    #[error("[{code}] 表达式求值错误: {0}", code = self.code())]
     EvalError(#[from] EvalError),
+
+    /// 脚本断言失败
+    #[error("[{code}] 断言失败: {message}", code = self.code())]
+    AssertionFailed { message: String },
+
+    /// 执行脚本节点时出错，附带源码位置
+    #[error("[{code}] {script_id} 第 {line} 行：{detail}", code = self.code(), detail = source.message())]
+    AtLine {
+        script_id: String,
+        line: usize,
+        source: Box<RuntimeError>,
+    },
+}
+
+impl RuntimeError {
+    /// 稳定错误码（`E01xx`）；带位置的错误沿用原始错误的码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::LabelNotFound { .. } => "E0101",
+            Self::BookmarkNotFound { .. } => "E0102",
+            Self::InvalidChoiceIndex { .. } => "E0103",
+            Self::DisabledChoice { .. } => "E0104",
+            Self::UnexpectedInput { .. } => "E0105",
+            Self::InvalidState { .. } => "E0106",
+            Self::ScriptNotLoaded { .. } => "E0107",
+            Self::EvalError(_) => "E0108",
+            Self::IncompatibleSave { .. } => "E0109",
+            Self::AssertionFailed { .. } => "E0110",
+            Self::NoEnabledChoice { .. } => "E0111",
+            Self::AtLine { source, .. } => source.code(),
+        }
+    }
+
+    /// 不含错误码前缀的消息
+    pub fn message(&self) -> String {
+        strip_code(self.to_string(), self.code())
+    }
+
+    /// 附加源码位置；行号未知时原样返回
+    pub fn at_line(self, script_id: impl Into<String>, line: Option<usize>) -> Self {
+        match line {
+            Some(line) if !matches!(self, Self::AtLine { .. }) => Self::AtLine {
+                script_id: script_id.into(),
+                line,
+                source: Box::new(self),
+            },
+            _ => self,
+        }
+    }
+
+    /// 出错的脚本行号（从 1 开始）
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::AtLine { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+
+    /// 去掉位置信息后的原始错误
+    pub fn kind(&self) -> &RuntimeError {
+        match self {
+            Self::AtLine { source, .. } => source.kind(),
+            other => other,
+        }
+    }
 }
 
 /// vn-runtime 统一错误类型
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum VnError {
     /// 解析错误
-    #[error("解析错误: {0}")]
+    #[error("[{code}] 解析错误: {detail}", code = self.code(), detail = .0.message())]
     Parse(#[from] ParseError),
 
     /// 运行时错误
-    #[error("运行时错误: {0}")]
+    #[error("[{code}] 运行时错误: {detail}", code = self.code(), detail = .0.message())]
     Runtime(#[from] RuntimeError),
 }
 
+impl VnError {
+    /// 稳定错误码（取自内部错误）
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Parse(e) => e.code(),
+            Self::Runtime(e) => e.code(),
+        }
+    }
+}
+
+fn strip_code(display: String, code: &str) -> String {
+    match display.strip_prefix(&format!("[{code}] ")) {
+        Some(message) => message.to_string(),
+        None => display,
+    }
+}
+
 /// Result 类型别名
 pub type VnResult<T> = Result<T, VnError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_has_stable_code_in_display() {
+        let parse = [
+            (
+                ParseError::InvalidLine {
+                    line: 1,
+                    column: None,
+                    message: "m".into(),
+                },
+                "E0001",
+            ),
+            (
+                ParseError::UnknownCommand {
+                    line: 1,
+                    command: "c".into(),
+                },
+                "E0002",
+            ),
+            (
+                ParseError::MissingParameter {
+                    line: 1,
+                    command: "c".into(),
+                    param: "p".into(),
+                },
+                "E0003",
+            ),
+            (
+                ParseError::InvalidParameter {
+                    line: 1,
+                    param: "p".into(),
+                    message: "m".into(),
+                },
+                "E0004",
+            ),
+            (
+                ParseError::InvalidTable {
+                    line: 1,
+                    message: "m".into(),
+                },
+                "E0005",
+            ),
+            (
+                ParseError::InvalidTransition {
+                    line: 1,
+                    message: "m".into(),
+                },
+                "E0006",
+            ),
+            (
+                ParseError::InvalidKeywordAlias {
+                    alias: "a".into(),
+                    message: "m".into(),
+                },
+                "E0007",
+            ),
+            (
+                ParseError::UnsupportedSyntax {
+                    line: 1,
+                    feature: "f".into(),
+                    required: 2,
+                    declared: 1,
+                },
+                "E0008",
+            ),
+        ];
+        for (err, code) in parse {
+            assert_eq!(err.code(), code);
+            assert!(err.to_string().starts_with(&format!("[{code}] ")), "{err}");
+            let vn = VnError::from(err);
+            assert_eq!(vn.code(), code);
+            assert!(
+                vn.to_string().starts_with(&format!("[{code}] 解析错误: ")),
+                "{vn}"
+            );
+        }
+
+        let runtime = [
+            (RuntimeError::LabelNotFound { label: "l".into() }, "E0101"),
+            (RuntimeError::BookmarkNotFound { name: "b".into() }, "E0102"),
+            (
+                RuntimeError::InvalidChoiceIndex { index: 3, max: 2 },
+                "E0103",
+            ),
+            (RuntimeError::DisabledChoice { index: 0 }, "E0104"),
+            (
+                RuntimeError::UnexpectedInput {
+                    waiting: "a".into(),
+                    input: "b".into(),
+                },
+                "E0105",
+            ),
+            (
+                RuntimeError::InvalidState {
+                    message: "m".into(),
+                },
+                "E0106",
+            ),
+            (RuntimeError::ScriptNotLoaded { path: "p".into() }, "E0107"),
+            (
+                RuntimeError::EvalError(EvalError::UndefinedVariable { name: "x".into() }),
+                "E0108",
+            ),
+            (
+                RuntimeError::IncompatibleSave { reason: "r".into() },
+                "E0109",
+            ),
+            (
+                RuntimeError::AssertionFailed {
+                    message: "m".into(),
+                },
+                "E0110",
+            ),
+            (RuntimeError::NoEnabledChoice { count: 2 }, "E0111"),
+        ];
+        for (err, code) in runtime {
+            assert_eq!(err.code(), code);
+            assert!(err.to_string().starts_with(&format!("[{code}] ")), "{err}");
+            let vn = VnError::from(err);
+            assert_eq!(vn.code(), code);
+            assert!(
+                vn.to_string()
+                    .starts_with(&format!("[{code}] 运行时错误: ")),
+                "{vn}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_error_display_includes_column_when_known() {
+        let err = ParseError::InvalidLine {
+            line: 3,
+            column: Some(12),
+            message: "m".into(),
+        };
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(err.column(), Some(12));
+        assert_eq!(err.to_string(), "[E0001] 第 3:12 行：无效的格式 - m");
+        assert_eq!(err.shift_column(-20).column(), Some(1));
+
+        let err = ParseError::InvalidLine {
+            line: 3,
+            column: None,
+            message: "m".into(),
+        };
+        assert_eq!(err.to_string(), "[E0001] 第 3 行：无效的格式 - m");
+        assert_eq!(err.shift_column(4).column(), None);
+    }
+
+    #[test]
+    fn located_error_keeps_inner_code_without_repeating_it() {
+        let err = RuntimeError::LabelNotFound {
+            label: "end".into(),
+        }
+        .at_line("main", Some(4));
+        assert_eq!(err.code(), "E0101");
+        assert_eq!(err.to_string(), "[E0101] main 第 4 行：标签 'end' 未找到");
+        assert_eq!(
+            VnError::from(err).to_string(),
+            "[E0101] 运行时错误: main 第 4 行：标签 'end' 未找到"
+        );
+    }
+}
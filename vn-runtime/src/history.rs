@@ -11,7 +11,7 @@
 use serde::{Deserialize, Serialize};
 
 /// 历史事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum HistoryEvent {
     /// 对话事件
     Dialogue {
@@ -133,7 +133,7 @@ impl HistoryEvent {
 }
 
 /// 历史记录容器
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct History {
     /// 事件列表（按时间顺序）
     events: Vec<HistoryEvent>,
@@ -156,6 +156,11 @@ impl History {
         self
     }
 
+    /// 最大记录数
+    pub fn max_events(&self) -> usize {
+        self.max_events
+    }
+
     /// 添加事件
     pub fn push(&mut self, event: HistoryEvent) {
         self.events.push(event);
@@ -204,6 +209,22 @@ impl History {
             .collect()
     }
 
+    /// 搜索对话，返回命中事件在 [`events`](Self::events) 中的下标（按时间顺序）
+    ///
+    /// 对正文与说话者名做大小写不敏感的子串匹配；空白查询不命中任何事件。
+    pub fn search(&self, query: &str) -> Vec<usize> {
+        self.events
+            .iter()
+            .enumerate()
+            .filter_map(|(i, event)| match event {
+                HistoryEvent::Dialogue {
+                    speaker, content, ..
+                } if dialogue_matches(speaker.as_deref(), content, query) => Some(i),
+                _ => None,
+            })
+            .collect()
+    }
+
     /// 清空历史
     pub fn clear(&mut self) {
         self.events.clear();
@@ -220,6 +241,16 @@ impl History {
     }
 }
 
+/// 一条对话是否命中查询（与 [`History::search`] 规则一致，供 Host 侧对话列表复用）
+pub fn dialogue_matches(speaker: Option<&str>, content: &str, query: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return false;
+    }
+    content.to_lowercase().contains(&query)
+        || speaker.is_some_and(|s| s.to_lowercase().contains(&query))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +297,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_history_search() {
+        let mut history = History::new();
+        history.push(HistoryEvent::dialogue(
+            Some("Alice".to_string()),
+            "今天天气不错".to_string(),
+            0,
+        ));
+        history.push(HistoryEvent::chapter_mark("天气之章".to_string(), 0));
+        history.push(HistoryEvent::dialogue(None, "Hello World".to_string(), 0));
+        history.push(HistoryEvent::dialogue(
+            Some("Bob".to_string()),
+            "天气预报说会下雨".to_string(),
+            0,
+        ));
+
+        // 正文命中（章节标题不参与）
+        assert_eq!(history.search("天气"), vec![0, 3]);
+        // 大小写不敏感
+        assert_eq!(history.search("hello"), vec![2]);
+        // 说话者命中
+        assert_eq!(history.search("alice"), vec![0]);
+        // 无结果与空查询
+        assert!(history.search("不存在").is_empty());
+        assert!(history.search("  ").is_empty());
+    }
+
     #[test]
     fn test_recent_dialogues() {
         let mut history = History::new();
@@ -0,0 +1,178 @@
+//! # 多语言文本表
+//!
+//! 对话内容可以用 `@key` 引用外部语言表（如 `北风："@greeting_01"`），
+//! 执行时按当前语言查找实际文本：
+//!
+//! - 语言表按 locale 加载，格式为扁平 JSON 对象 `{"key": "文本"}`
+//! - 当前语言缺少 key 时回退到默认语言，并记录一条告警
+//! - 默认语言也缺少时保留 `@key` 原文，便于在画面上发现遗漏
+//! - 查到的文本同样支持内联标签与 `{$var}` 插值
+//!
+//! 语言表属于 Host 注入的运行配置，不参与存档。
+
+use std::collections::HashMap;
+
+/// 文本 key 前缀
+pub const TEXT_KEY_PREFIX: char = '@';
+
+/// 单个语言的文本表
+pub type StringTable = HashMap<String, String>;
+
+/// 识别 `@key` 形式的文本引用，返回 key
+///
+/// key 只允许 ASCII 字母、数字与 `_` `.` `-`，因此普通的 `@` 开头文本不受影响。
+pub fn text_key(content: &str) -> Option<&str> {
+    let key = content.trim().strip_prefix(TEXT_KEY_PREFIX)?;
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    valid.then_some(key)
+}
+
+/// 查找文本时产生的告警
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextKeyWarning {
+    /// 文本 key
+    pub key: String,
+    /// 缺少该 key 的语言
+    pub locale: String,
+    /// 是否已回退到默认语言（false 表示默认语言也缺少，保留原文）
+    pub fell_back: bool,
+}
+
+/// 各语言文本表与当前语言
+#[derive(Debug, Clone, Default)]
+pub struct LocaleTables {
+    default_locale: String,
+    current_locale: String,
+    tables: HashMap<String, StringTable>,
+    warnings: Vec<TextKeyWarning>,
+}
+
+impl LocaleTables {
+    /// 创建空表，当前语言初始为默认语言
+    pub fn new(default_locale: impl Into<String>) -> Self {
+        let default_locale = default_locale.into();
+        Self {
+            current_locale: default_locale.clone(),
+            default_locale,
+            tables: HashMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// 加载某个语言的 JSON 文本表（覆盖同名语言已有的表）
+    pub fn load_json(
+        &mut self,
+        locale: impl Into<String>,
+        content: &str,
+    ) -> Result<(), serde_json::Error> {
+        let table: StringTable = serde_json::from_str(content)?;
+        self.insert_table(locale, table);
+        Ok(())
+    }
+
+    /// 注册某个语言的文本表
+    pub fn insert_table(&mut self, locale: impl Into<String>, table: StringTable) {
+        self.tables.insert(locale.into(), table);
+    }
+
+    /// 切换当前语言
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.current_locale = locale.into();
+    }
+
+    /// 当前语言
+    pub fn locale(&self) -> &str {
+        &self.current_locale
+    }
+
+    /// 默认语言
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// 是否已加载任何语言表
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// 按当前语言查找 key，缺失时回退到默认语言并记录告警
+    pub fn lookup(&mut self, key: &str) -> Option<String> {
+        let find = |locale: &str| {
+            self.tables
+                .get(locale)
+                .and_then(|table| table.get(key))
+                .cloned()
+        };
+        if let Some(text) = find(&self.current_locale) {
+            return Some(text);
+        }
+        let fallback = if self.current_locale != self.default_locale {
+            find(&self.default_locale)
+        } else {
+            None
+        };
+        self.warnings.push(TextKeyWarning {
+            key: key.to_string(),
+            locale: self.current_locale.clone(),
+            fell_back: fallback.is_some(),
+        });
+        fallback
+    }
+
+    /// 取出并清空累计的告警
+    pub fn take_warnings(&mut self) -> Vec<TextKeyWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables() -> LocaleTables {
+        let mut tables = LocaleTables::new("zh");
+        tables
+            .load_json("zh", r#"{"greeting_01": "早上好", "farewell": "再见"}"#)
+            .unwrap();
+        tables
+            .load_json("en", r#"{"greeting_01": "Good morning"}"#)
+            .unwrap();
+        tables
+    }
+
+    #[test]
+    fn text_key_accepts_only_key_references() {
+        assert_eq!(text_key("@greeting_01"), Some("greeting_01"));
+        assert_eq!(text_key(" @ch1.line-2 "), Some("ch1.line-2"));
+        assert_eq!(text_key("@"), None);
+        assert_eq!(text_key("@北风 你好"), None);
+        assert_eq!(text_key("邮箱 a@b.c"), None);
+    }
+
+    #[test]
+    fn lookup_uses_current_locale_and_falls_back_with_warning() {
+        let mut tables = tables();
+        tables.set_locale("en");
+        assert_eq!(
+            tables.lookup("greeting_01").as_deref(),
+            Some("Good morning")
+        );
+        assert!(tables.take_warnings().is_empty());
+
+        assert_eq!(tables.lookup("farewell").as_deref(), Some("再见"));
+        assert_eq!(
+            tables.take_warnings(),
+            vec![TextKeyWarning {
+                key: "farewell".to_string(),
+                locale: "en".to_string(),
+                fell_back: true,
+            }]
+        );
+
+        assert_eq!(tables.lookup("missing"), None);
+        assert!(!tables.take_warnings()[0].fell_back);
+    }
+}
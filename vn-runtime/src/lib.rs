@@ -63,11 +63,13 @@
 //! - [`error`]：错误类型定义
 //! - [`script`]：脚本解析（AST 和 Parser）
 //! - [`runtime`]：执行引擎
+//! - [`i18n`]：多语言文本表
 
 pub mod command;
 pub mod diagnostic;
 pub mod error;
 pub mod history;
+pub mod i18n;
 pub mod input;
 pub mod runtime;
 pub mod save;
@@ -76,18 +78,30 @@ pub mod state;
 
 // 重导出核心类型
 pub use command::{
-    Choice, Command, InlineEffect, InlineEffectKind, Position, Transition, TransitionArg,
+    BackgroundLayer, Choice, Color, Command, FilterKind, InlineEffect, InlineEffectKind, Position,
+    TimedCommand, Transition, TransitionArg, WeatherKind,
 };
 pub use diagnostic::{
-    Diagnostic, DiagnosticLevel, DiagnosticResult, ResourceReference, ResourceType, analyze_script,
-    extract_resource_references, get_defined_labels, get_jump_targets,
+    ChapterReadingTime, ChoiceBranchStats, ComplexityLimits, Diagnostic, DiagnosticLevel,
+    DiagnosticResult, OptionBranchStats, ReadingTimeEstimate, ResourceReference, ResourceType,
+    analyze_branch_weights, analyze_script, chapter_reading_time_at, check_complexity,
+    clean_whitespace, estimate_reading_time, extract_resource_references, get_defined_labels,
+    get_jump_targets, lint_whitespace, rules as diagnostic_rules,
 };
 pub use error::{ParseError, RuntimeError, VnError, VnResult};
 pub use history::{History, HistoryEvent};
+pub use i18n::{LocaleTables, TextKeyWarning};
 pub use input::{RuntimeInput, SignalId};
-pub use runtime::VNRuntime;
+pub use runtime::{AutosaveTrigger, RestoreOutcome, RuntimeMetrics, VNRuntime};
 pub use save::{
-    AudioState, CharacterSnapshot, RenderSnapshot, SaveData, SaveError, SaveMetadata, SaveVersion,
+    AudioState, CharacterSnapshot, FilterSnapshot, HistoryDelta, RenderSnapshot, SaveBaseRef,
+    SaveData, SaveDelta, SaveError, SaveMetadata, SavePreview, SaveSlot, SaveVersion,
+    VariableDelta, WeatherSnapshot,
+};
+pub use script::{
+    ChoiceOption, KeywordAliases, OrderedScript, Parser, Script, ScriptIndexEntry, ScriptNode,
+    order_scripts,
+};
+pub use state::{
+    Breakpoint, EndingCollection, RuntimeState, ScriptPosition, VarValue, WaitingReason,
 };
-pub use script::{ChoiceOption, Parser, Script, ScriptNode};
-pub use state::{RuntimeState, ScriptPosition, VarValue, WaitingReason};
@@ -0,0 +1,132 @@
+//! # 资源前瞻
+//!
+//! 静态扫描当前位置之后的节点，提前告知 Host 即将用到的资源，
+//! 便于在章节切换前预加载、消除卡顿。只读脚本，不执行节点、不改变状态。
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::diagnostic::{ResourceReference, extract_from_nodes};
+use crate::script::{Script, ScriptNode};
+use crate::state::ScriptPosition;
+
+use super::VNRuntime;
+
+/// 前瞻中的一个待探索位置
+struct Frontier<'a> {
+    script: &'a Script,
+    node_index: usize,
+    budget: usize,
+    /// 遇到 `returnFromScript` 时回到的调用栈层：`Some(n)` 取运行时调用栈自顶向下第 n 帧；
+    /// `None` 表示经前瞻中的 `callScript` 进入，调用方的后续节点已另行排入
+    return_depth: Option<usize>,
+}
+
+impl VNRuntime {
+    /// 前瞻当前位置之后 `lookahead` 个节点引用的资源
+    ///
+    /// - 顺序前进；`goto`（含定时跳转）跟随目标标签
+    /// - 选择分支与条件分支取各分支的并集（条件分支体内的 `goto` 同样跟随）
+    /// - `callScript` 进入已注册的目标脚本，同时继续调用方的后续节点；
+    ///   `returnFromScript` 按当前调用栈回到调用方
+    /// - 变量跳转、`fullRestart`、未注册的脚本与脚本末尾处停止
+    ///
+    /// 结果按离当前位置由近到远排列、同一路径只出现一次，路径已按所在脚本目录解析。
+    pub fn upcoming_resources(&self, lookahead: usize) -> Vec<ResourceReference> {
+        let mut refs = Vec::new();
+        let mut index = HashMap::new();
+        // (脚本, 节点索引) → 探索时的剩余步数；剩余步数更多时才重新探索。
+        // 脚本按地址区分：不同路径的脚本可能同 id
+        let mut explored: HashMap<(*const Script, usize), usize> = HashMap::new();
+        let mut pending = VecDeque::from([Frontier {
+            script: &self.script,
+            node_index: self.state.position.node_index,
+            budget: lookahead,
+            return_depth: Some(0),
+        }]);
+
+        while let Some(frontier) = pending.pop_front() {
+            let Frontier {
+                script,
+                node_index,
+                budget,
+                return_depth,
+            } = frontier;
+            let key = (std::ptr::from_ref(script), node_index);
+            if budget == 0 || explored.get(&key).is_some_and(|&b| b >= budget) {
+                continue;
+            }
+            explored.insert(key, budget);
+            let Some(node) = script.get_node(node_index) else {
+                continue;
+            };
+            extract_from_nodes(std::slice::from_ref(node), script, &mut refs, &mut index);
+
+            let remaining = budget - 1;
+            let next = |node_index| Frontier {
+                script,
+                node_index,
+                budget: remaining,
+                return_depth,
+            };
+            let mut jump = |label: &str| {
+                if let Some(target) = script.find_label(label) {
+                    pending.push_back(next(target));
+                }
+            };
+            match node {
+                ScriptNode::Goto { target_label } | ScriptNode::TimedGoto { target_label, .. } => {
+                    jump(target_label)
+                }
+                ScriptNode::Choice { options, .. } => {
+                    for option in options {
+                        jump(&option.target_label);
+                    }
+                }
+                ScriptNode::Conditional { branches } => {
+                    for node in branches.iter().flat_map(|b| &b.body) {
+                        if let ScriptNode::Goto { target_label } = node {
+                            jump(target_label);
+                        }
+                    }
+                    pending.push_back(next(node_index + 1));
+                }
+                ScriptNode::CallScript { path, .. } => {
+                    if let Some(callee) = self.script_registry.get(&script.resolve_path(path)) {
+                        pending.push_back(Frontier {
+                            script: callee,
+                            node_index: 0,
+                            budget: remaining,
+                            return_depth: None,
+                        });
+                    }
+                    pending.push_back(next(node_index + 1));
+                }
+                ScriptNode::ReturnFromScript => {
+                    let stack = &self.state.call_stack;
+                    let caller = return_depth.and_then(|depth| {
+                        let position = &stack[stack.len().checked_sub(depth + 1)?];
+                        Some((depth, position, self.lookup_script(position)?))
+                    });
+                    if let Some((depth, position, caller)) = caller {
+                        pending.push_back(Frontier {
+                            script: caller,
+                            node_index: position.node_index,
+                            budget: remaining,
+                            return_depth: Some(depth + 1),
+                        });
+                    }
+                }
+                ScriptNode::DynamicGoto { .. } | ScriptNode::FullRestart => {}
+                _ => pending.push_back(next(node_index + 1)),
+            }
+        }
+        refs
+    }
+
+    /// 按返回位置查找已注册脚本（先按路径，再按 script_id）
+    fn lookup_script(&self, position: &ScriptPosition) -> Option<&Script> {
+        self.script_registry
+            .get(&position.script_path)
+            .or_else(|| self.script_registry.get(&position.script_id))
+    }
+}
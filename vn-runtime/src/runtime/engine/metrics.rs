@@ -0,0 +1,55 @@
+//! # 运行时性能计数
+//!
+//! 记录 tick 次数与每次 tick 执行的节点数，用于在长脚本中定位慢点。
+//!
+//! Runtime 不读取系统时钟：耗时由 Host 在 tick 外自行测量后通过
+//! [`VNRuntime::record_tick_time`](super::VNRuntime::record_tick_time) 注入。
+
+use serde::Serialize;
+
+/// 运行时性能计数（见 [`VNRuntime::enable_metrics`](super::VNRuntime::enable_metrics)）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RuntimeMetrics {
+    /// 累计 tick 次数
+    pub ticks: u64,
+    /// 累计执行的节点数
+    pub nodes_executed: u64,
+    /// 最近一次 tick 执行的节点数
+    pub last_tick_nodes: u64,
+    /// 单次 tick 执行节点数的最大值
+    pub max_tick_nodes: u64,
+    /// 累计产出的 Command 数
+    pub commands_emitted: u64,
+    /// Host 注入的累计 tick 耗时（微秒）
+    pub tick_time_micros: u64,
+    /// Host 注入的单次 tick 最大耗时（微秒）
+    pub max_tick_time_micros: u64,
+}
+
+impl RuntimeMetrics {
+    pub(super) fn begin_tick(&mut self) {
+        self.ticks += 1;
+        self.last_tick_nodes = 0;
+    }
+
+    pub(super) fn record_node(&mut self, commands: usize) {
+        self.nodes_executed += 1;
+        self.last_tick_nodes += 1;
+        self.max_tick_nodes = self.max_tick_nodes.max(self.last_tick_nodes);
+        self.commands_emitted += commands as u64;
+    }
+
+    pub(super) fn record_tick_time(&mut self, micros: u64) {
+        self.tick_time_micros += micros;
+        self.max_tick_time_micros = self.max_tick_time_micros.max(micros);
+    }
+
+    /// 平均每次 tick 执行的节点数
+    pub fn average_tick_nodes(&self) -> f64 {
+        if self.ticks == 0 {
+            0.0
+        } else {
+            self.nodes_executed as f64 / self.ticks as f64
+        }
+    }
+}
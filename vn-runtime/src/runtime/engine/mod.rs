@@ -12,15 +12,41 @@
 //! 2. 根据 input 决定是否解除等待
 //! 3. 若不再等待，继续执行脚本直到下一个阻塞点
 //! 4. 返回执行过程中产生的 Command 和新的等待状态
+//!
+//! ## 确定性
+//!
+//! 相同脚本 + 相同输入序列必须产生逐条相同的 Command：
+//!
+//! - tick 不读取系统时间，历史时间戳只来自 Host 传入的 [`VNRuntime::set_now`]
+//! - 表达式求值没有随机或时间来源；音效池抽取由状态中的种子驱动（[`VNRuntime::set_rng_seed`]）
+//! - Command 中的映射使用有序容器，序列化结果同样稳定
+//!
+//! 因此录制的输入序列（[`VNRuntime::start_recording`]）可通过 [`VNRuntime::replay`] 复现整段游玩。
 
 use crate::command::Command;
-use crate::error::RuntimeError;
+use crate::diagnostic::{
+    ChapterReadingTime, DEFAULT_READING_CHARS_PER_MINUTE, chapter_reading_time_at,
+};
+use crate::error::{RuntimeError, VnResult};
 use crate::history::{History, HistoryEvent};
+use crate::i18n::{LocaleTables, TextKeyWarning};
 use crate::input::RuntimeInput;
 use crate::runtime::executor::{Executor, ScriptControlFlow};
-use crate::script::{Script, ScriptNode};
-use crate::state::{RuntimeState, WaitingReason};
-use std::collections::HashMap;
+use crate::script::{Script, ScriptNode, evaluate, evaluate_to_bool};
+use crate::state::{
+    Breakpoint, EndingCollection, RuntimeState, ScriptPosition, VarValue, WaitingReason,
+    branch_script_id,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+mod lookahead;
+mod metrics;
+mod replay;
+
+pub use metrics::RuntimeMetrics;
+
+/// `skip_to_next_choice` 单次调用允许的最大 tick 次数（防止脚本死循环）
+pub const SKIP_TO_CHOICE_MAX_TICKS: usize = 10_000;
 
 /// VN Runtime 执行引擎
 ///
@@ -54,6 +80,42 @@ pub struct VNRuntime {
     /// Host 提供的当前时间戳（Unix 秒），用于历史事件打时间戳。
     /// Runtime 自身不读取系统时钟，由 Host 在每次 tick 前通过 `set_now()` 注入。
     now_timestamp: u64,
+    /// 节点覆盖记录（key=脚本逻辑路径或 id，value=已执行的节点索引）。
+    /// 默认关闭，仅供覆盖率统计等工具通过 `enable_coverage()` 开启；不参与存档。
+    coverage: Option<HashMap<String, BTreeSet<usize>>>,
+    /// 调试断点（不参与存档）
+    breakpoints: BTreeSet<Breakpoint>,
+    /// 最近一次暂停所在的 (脚本 id, 节点索引)；恢复执行时跳过该处断点一次
+    breakpoint_hit: Option<(String, usize)>,
+    /// 尚未被 Host 取走的自动存档时机（不参与存档）
+    autosave_point: Option<AutosaveTrigger>,
+    /// 性能计数。默认关闭，通过 `enable_metrics()` 开启；不参与存档。
+    metrics: Option<RuntimeMetrics>,
+    /// 演出空档时产出 `TextBoxAutoHide`。默认关闭；不参与存档。
+    textbox_auto_hide: bool,
+    /// 输入录制（见 [`VNRuntime::start_recording`]）。默认关闭；不参与存档。
+    recording: Option<Vec<Option<RuntimeInput>>>,
+}
+
+/// 适合自动存档的时机（见 [`VNRuntime::take_autosave_point`]）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutosaveTrigger {
+    /// 经过章节标记
+    ChapterMark,
+    /// 完成一次选择
+    ChoiceMade,
+}
+
+/// 读档结果（见 [`VNRuntime::restore_state_with_fallback`]）
+#[derive(Debug, Clone, PartialEq)]
+pub enum RestoreOutcome {
+    /// 按保存位置精确恢复
+    Exact,
+    /// 存档与脚本不兼容，已回退到标签（None 表示脚本开头）
+    FellBack {
+        label: Option<String>,
+        reason: RuntimeError,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -82,6 +144,13 @@ impl VNRuntime {
             history: History::new(),
             executor: Executor::new(),
             now_timestamp: 0,
+            coverage: None,
+            breakpoints: BTreeSet::new(),
+            breakpoint_hit: None,
+            autosave_point: None,
+            metrics: None,
+            textbox_auto_hide: false,
+            recording: None,
         }
     }
 
@@ -92,7 +161,8 @@ impl VNRuntime {
     /// - `script`: 脚本（必须与保存时相同）
     /// - `state`: 保存的运行时状态
     /// - `history`: 历史记录
-    pub fn restore(script: Script, state: RuntimeState, history: History) -> Self {
+    pub fn restore(script: Script, mut state: RuntimeState, history: History) -> Self {
+        state.align_local_scopes();
         let mut script_registry = HashMap::new();
         script_registry.insert(script.id.clone(), script.clone());
         Self {
@@ -102,6 +172,13 @@ impl VNRuntime {
             history,
             executor: Executor::new(),
             now_timestamp: 0,
+            coverage: None,
+            breakpoints: BTreeSet::new(),
+            breakpoint_hit: None,
+            autosave_point: None,
+            metrics: None,
+            textbox_auto_hide: false,
+            recording: None,
         }
     }
 
@@ -125,6 +202,161 @@ impl VNRuntime {
             .or_insert(script);
     }
 
+    /// 设置多语言文本表（不参与存档，读档后需重新设置）
+    pub fn set_locale_tables(&mut self, tables: LocaleTables) {
+        *self.executor.locale_tables_mut() = tables;
+    }
+
+    /// 多语言文本表
+    pub fn locale_tables(&self) -> &LocaleTables {
+        self.executor.locale_tables()
+    }
+
+    /// 切换当前语言，后续执行的 `@key` 对话按新语言查找
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.executor.locale_tables_mut().set_locale(locale);
+    }
+
+    /// 取出并清空文本查找告警（缺 key 回退等）
+    pub fn take_text_warnings(&mut self) -> Vec<TextKeyWarning> {
+        self.executor.locale_tables_mut().take_warnings()
+    }
+
+    /// 取出自上次调用以来出现的自动存档时机
+    ///
+    /// 经过章节标记或完成选择后返回对应时机；Host 应在应用完本次 tick 的 Command 后调用，
+    /// 据此写入自动存档。同一 tick 内出现多次时只保留最后一次。
+    pub fn take_autosave_point(&mut self) -> Option<AutosaveTrigger> {
+        self.autosave_point.take()
+    }
+
+    /// 开启节点覆盖记录
+    ///
+    /// 开启后每个被执行的节点都会按 `(脚本, 节点索引)` 记录，
+    /// 用于统计测试输入触达了哪些 label/分支。
+    pub fn enable_coverage(&mut self) {
+        self.coverage.get_or_insert_with(HashMap::new);
+    }
+
+    /// 获取节点覆盖记录（未开启时返回 `None`）
+    ///
+    /// key 为脚本逻辑路径（未设置时回退为脚本 id）。
+    pub fn coverage(&self) -> Option<&HashMap<String, BTreeSet<usize>>> {
+        self.coverage.as_ref()
+    }
+
+    fn record_coverage(&mut self) {
+        let Some(coverage) = self.coverage.as_mut() else {
+            return;
+        };
+        let position = &self.state.position;
+        let key = if position.script_path.is_empty() {
+            &position.script_id
+        } else {
+            &position.script_path
+        };
+        coverage
+            .entry(key.clone())
+            .or_default()
+            .insert(position.node_index);
+    }
+
+    /// 开启性能计数（已开启时保留现有计数）
+    pub fn enable_metrics(&mut self) {
+        self.metrics.get_or_insert_with(RuntimeMetrics::default);
+    }
+
+    /// 获取性能计数（未开启时返回 `None`）
+    pub fn metrics(&self) -> Option<&RuntimeMetrics> {
+        self.metrics.as_ref()
+    }
+
+    /// 设置音效池（池名 → 候选音效路径；不参与存档，读档后需重新设置）
+    pub fn set_sfx_pools(&mut self, pools: BTreeMap<String, Vec<String>>) {
+        self.executor.set_sfx_pools(pools);
+    }
+
+    /// 设置随机数种子（写入状态，随存档保存）
+    pub fn set_rng_seed(&mut self, seed: u64) {
+        self.state.rng_state = seed;
+    }
+
+    /// 开启/关闭脚本 `assert` 检查
+    ///
+    /// 默认在 debug 构建中开启、release 构建中关闭；关闭时断言节点直接跳过。
+    pub fn set_assertions_enabled(&mut self, enabled: bool) {
+        self.executor.set_assertions_enabled(enabled);
+    }
+
+    /// 开启/关闭对话框自动隐藏（见 [`Command::TextBoxAutoHide`]）
+    pub fn set_textbox_auto_hide(&mut self, enabled: bool) {
+        self.textbox_auto_hide = enabled;
+    }
+
+    /// 注入 Host 测得的一次 tick 耗时（微秒）；未开启计数时忽略
+    pub fn record_tick_time(&mut self, micros: u64) {
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.record_tick_time(micros);
+        }
+    }
+
+    /// 添加调试断点
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.insert(breakpoint);
+    }
+
+    /// 移除调试断点，返回是否存在
+    pub fn remove_breakpoint(&mut self, breakpoint: &Breakpoint) -> bool {
+        self.breakpoints.remove(breakpoint)
+    }
+
+    /// 清空所有断点
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// 当前断点集合
+    pub fn breakpoints(&self) -> &BTreeSet<Breakpoint> {
+        &self.breakpoints
+    }
+
+    /// 从断点处恢复执行
+    ///
+    /// 跳过当前位置的断点继续推进，返回值同 [`Self::tick`]；
+    /// 不在断点处时等同于 `tick(None)`。
+    pub fn resume(&mut self) -> Result<(Vec<Command>, WaitingReason), RuntimeError> {
+        if matches!(self.state.waiting, WaitingReason::Breakpoint(_)) {
+            self.breakpoint_hit = Some(self.current_node_key());
+            self.state.clear_wait();
+        }
+        self.tick(None)
+    }
+
+    fn current_node_key(&self) -> (String, usize) {
+        (
+            self.state.position.script_id.clone(),
+            self.state.position.node_index,
+        )
+    }
+
+    /// 当前节点命中的断点（行号优先于标签）
+    fn breakpoint_at(&self, node: &ScriptNode) -> Option<Breakpoint> {
+        if self.breakpoints.is_empty() {
+            return None;
+        }
+        let line = self
+            .script
+            .get_source_line(self.state.position.node_index)
+            .map(Breakpoint::Line);
+        let label = node
+            .as_label()
+            .map(|name| Breakpoint::Label(name.to_string()));
+        [line, label]
+            .into_iter()
+            .flatten()
+            .find(|bp| self.breakpoints.contains(bp))
+    }
+
     fn progress_snapshot(&self) -> ProgressSnapshot {
         ProgressSnapshot {
             script_id: self.state.position.script_id.clone(),
@@ -166,17 +398,38 @@ impl VNRuntime {
         input: Option<RuntimeInput>,
     ) -> Result<(Vec<Command>, WaitingReason), RuntimeError> {
         let mut commands = Vec::new();
+        if let Some(metrics) = self.metrics.as_mut() {
+            metrics.begin_tick();
+        }
+        // 只录制被接受的输入：被拒绝的输入不改变状态，回放时也不应出现
+        let recorded = self.recording.is_some().then(|| input.clone());
 
         // 1. 处理输入，尝试解除等待
         if let Some(input) = input {
             self.handle_input(input)?;
         }
+        if let Some(input) = recorded {
+            self.record_input(input);
+        }
 
         // 2. 如果仍在等待，直接返回
         if self.state.waiting.is_waiting() {
             return Ok((commands, self.state.waiting.clone()));
         }
 
+        // 等待已解除（到期或被点击打断），执行定时跳转
+        if let Some(label) = self.state.pending_goto.take() {
+            let target =
+                self.script
+                    .find_label(&label)
+                    .ok_or_else(|| RuntimeError::LabelNotFound {
+                        label: label.clone(),
+                    })?;
+            self.history
+                .push(HistoryEvent::jump(label, self.now_timestamp));
+            self.state.position.jump_to(target);
+        }
+
         // 3. 继续执行脚本直到阻塞或结束
         loop {
             // 检查是否到达脚本末尾
@@ -196,15 +449,37 @@ impl VNRuntime {
                 }
             };
 
-            // 执行当前节点
+            // 断点：执行节点之前暂停，恢复后同一位置只跳过一次
+            if let Some(breakpoint) = self.breakpoint_at(&node) {
+                let here = self.current_node_key();
+                if self.breakpoint_hit.as_ref() != Some(&here) {
+                    self.breakpoint_hit = Some(here);
+                    let reason = WaitingReason::Breakpoint(breakpoint);
+                    self.state.wait(reason.clone());
+                    return Ok((commands, reason));
+                }
+            }
+            self.breakpoint_hit = None;
+
+            self.record_coverage();
+
+            // 执行当前节点（出错时附带源码行号）
+            let line = self.script.get_source_line(self.state.position.node_index);
             let result = self
                 .executor
-                .execute(&node, &mut self.state, &self.script)?;
+                .execute(&node, &mut self.state, &self.script)
+                .map_err(|e| e.at_line(&self.script.id, line))?;
 
             // 记录历史事件
             for cmd in &result.commands {
                 self.record_history(cmd);
             }
+            if let Some(metrics) = self.metrics.as_mut() {
+                metrics.record_node(result.commands.len());
+            }
+            if matches!(node, ScriptNode::Chapter { .. }) {
+                self.autosave_point = Some(AutosaveTrigger::ChapterMark);
+            }
 
             commands.extend(result.commands);
 
@@ -218,9 +493,16 @@ impl VNRuntime {
             // 处理跳转（记录历史）
             if let Some(target) = result.jump_to {
                 // 从当前节点获取跳转目标标签（用于历史记录）
-                if let ScriptNode::Goto { target_label } = &node {
+                let jumped_label = match &node {
+                    ScriptNode::Goto { target_label } => Some(target_label.as_str()),
+                    ScriptNode::DynamicGoto { .. } => {
+                        self.script.get_node(target).and_then(ScriptNode::as_label)
+                    }
+                    _ => None,
+                };
+                if let Some(label) = jumped_label {
                     self.history
-                        .push(HistoryEvent::jump(target_label.clone(), self.now_timestamp));
+                        .push(HistoryEvent::jump(label.to_string(), self.now_timestamp));
                 }
                 let progress = self.progress_snapshot();
                 self.state.position.jump_to(target);
@@ -236,11 +518,77 @@ impl VNRuntime {
             // 如果需要等待，停止执行
             if let Some(reason) = result.waiting {
                 self.state.wait(reason.clone());
+                if self.textbox_auto_hide && is_textbox_idle(&commands, &reason) {
+                    commands.push(Command::TextBoxAutoHide);
+                }
                 return Ok((commands, reason));
             }
         }
     }
 
+    /// 做出选择（供自动化测试、AI 试玩等程序化驱动）
+    ///
+    /// 等价于 `tick(Some(ChoiceSelected { index }))`，但先校验当前确实在等待选择：
+    /// 非选择态返回 [`RuntimeError::UnexpectedInput`]（`tick` 在不等待时会静默忽略输入），
+    /// 索引越界返回 [`RuntimeError::InvalidChoiceIndex`]，两者都不改变状态。
+    pub fn choose(&mut self, index: usize) -> Result<(Vec<Command>, WaitingReason), RuntimeError> {
+        let input = RuntimeInput::ChoiceSelected { index };
+        match &self.state.waiting {
+            WaitingReason::WaitForChoice { choice_count } if index >= *choice_count => {
+                Err(RuntimeError::InvalidChoiceIndex {
+                    index,
+                    max: *choice_count,
+                })
+            }
+            WaitingReason::WaitForChoice { .. } => self.tick(Some(input)),
+            waiting => Err(RuntimeError::UnexpectedInput {
+                waiting: format!("{:?}", waiting),
+                input: format!("{:?}", input),
+            }),
+        }
+    }
+
+    /// 快进到下一个选择点
+    ///
+    /// 连续推进脚本并收集所有 Command，期间自动解除点击/定时/信号等待，
+    /// 直到遇到 `WaitForChoice`、需要玩家交互的 `WaitForUIResult` 或脚本结束。
+    /// 对话照常写入历史记录。
+    ///
+    /// 超过 [`SKIP_TO_CHOICE_MAX_TICKS`] 次仍未停下时返回错误，避免死循环。
+    pub fn skip_to_next_choice(&mut self) -> VnResult<(Vec<Command>, WaitingReason)> {
+        let mut commands = Vec::new();
+
+        for _ in 0..SKIP_TO_CHOICE_MAX_TICKS {
+            let input = match &self.state.waiting {
+                WaitingReason::WaitForChoice { .. }
+                | WaitingReason::WaitForUIResult { .. }
+                | WaitingReason::Breakpoint(_) => {
+                    return Ok((commands, self.state.waiting.clone()));
+                }
+                WaitingReason::WaitForClick | WaitingReason::WaitForTime(_) => {
+                    Some(RuntimeInput::Click)
+                }
+                WaitingReason::WaitForSignal(id) => Some(RuntimeInput::Signal { id: id.clone() }),
+                WaitingReason::None => None,
+            };
+
+            let (cmds, waiting) = self.tick(input)?;
+            commands.extend(cmds);
+
+            if matches!(waiting, WaitingReason::None) && self.is_finished() {
+                return Ok((commands, waiting));
+            }
+        }
+
+        Err(RuntimeError::InvalidState {
+            message: format!(
+                "skip_to_next_choice 超过 {} 次推进仍未到达选择点或脚本末尾",
+                SKIP_TO_CHOICE_MAX_TICKS
+            ),
+        }
+        .into())
+    }
+
     /// 处理输入，解除等待状态
     fn handle_input(&mut self, input: RuntimeInput) -> Result<(), RuntimeError> {
         match (&self.state.waiting, input) {
@@ -268,6 +616,13 @@ impl VNRuntime {
                 if let Some(ScriptNode::Choice { options, .. }) =
                     self.script.get_node(current_index)
                 {
+                    // 条件不满足的选项只展示不可选
+                    if let Some(condition) = options.get(index).and_then(|o| o.condition.as_ref())
+                        && !evaluate_to_bool(condition, &self.state)?
+                    {
+                        return Err(RuntimeError::DisabledChoice { index });
+                    }
+
                     // 记录选择事件到历史
                     let option_texts: Vec<String> =
                         options.iter().map(|o| o.text.clone()).collect();
@@ -278,6 +633,14 @@ impl VNRuntime {
                     ));
 
                     if let Some(option) = options.get(index) {
+                        self.state.record_chosen_branch(option.target_label.clone());
+
+                        // 选项附带的赋值在跳转前执行
+                        for action in &option.actions {
+                            let value = evaluate(&action.value, &self.state)?;
+                            self.state.assign_var(&action.name, value);
+                        }
+
                         // 记录跳转事件
                         self.history.push(HistoryEvent::jump(
                             option.target_label.clone(),
@@ -294,6 +657,7 @@ impl VNRuntime {
                 }
 
                 self.state.clear_wait();
+                self.autosave_point = Some(AutosaveTrigger::ChoiceMade);
                 Ok(())
             }
 
@@ -328,13 +692,16 @@ impl VNRuntime {
             // WaitForTime: 其他输入忽略
             (WaitingReason::WaitForTime(_), _) => Ok(()),
 
+            // 断点只能由 resume 解除，其他输入忽略
+            (WaitingReason::Breakpoint(_), _) => Ok(()),
+
             // 不等待时收到输入，忽略
             (WaitingReason::None, _) => Ok(()),
 
-            // 状态不匹配
-            (waiting, input) => Err(RuntimeError::StateMismatch {
-                expected: format!("{:?}", waiting),
-                actual: format!("{:?}", input),
+            // 输入与等待状态不匹配：拒绝且不改变状态，由 Host 决定是否重试
+            (waiting, input) => Err(RuntimeError::UnexpectedInput {
+                waiting: format!("{:?}", waiting),
+                input: format!("{:?}", input),
             }),
         }
     }
@@ -352,12 +719,14 @@ impl VNRuntime {
                     },
                 )?;
 
-                let return_position = crate::state::ScriptPosition::with_path(
+                let mut return_position = crate::state::ScriptPosition::with_path(
                     self.state.position.script_id.clone(),
                     self.state.position.script_path.clone(),
                     self.state.position.node_index + 1,
                 );
+                return_position.label = self.state.position.label.clone();
                 self.state.call_stack.push(return_position);
+                self.state.local_scopes.push(HashMap::new());
 
                 // `callScript [label](path)` 中的 label 仅用于展示，不参与入口寻址。
                 let target_index = 0;
@@ -378,6 +747,7 @@ impl VNRuntime {
                 self.state.position.script_id = self.script.id.clone();
                 self.state.position.script_path = resolved_path;
                 self.state.position.node_index = target_index;
+                self.state.position.label = None;
                 Ok(())
             }
             ScriptControlFlow::Return => {
@@ -388,6 +758,8 @@ impl VNRuntime {
                         .ok_or_else(|| RuntimeError::InvalidState {
                             message: "returnFromScript 但调用栈为空".to_string(),
                         })?;
+                // 与调用栈同步弹出：即使返回目标未加载而报错，两者也保持一一对应
+                self.state.local_scopes.pop();
 
                 let next_script = if !return_position.script_path.is_empty() {
                     self.script_registry
@@ -425,11 +797,39 @@ impl VNRuntime {
         self.script.find_label(label)
     }
 
+    /// 跳转到当前脚本中的书签（调试/章节定位用）
+    ///
+    /// 清除当前等待状态，下一次 `tick` 从书签位置继续执行。
+    pub fn jump_to_bookmark(&mut self, name: &str) -> Result<(), RuntimeError> {
+        let target_index =
+            self.script
+                .find_bookmark(name)
+                .ok_or_else(|| RuntimeError::BookmarkNotFound {
+                    name: name.to_string(),
+                })?;
+
+        self.history.push(HistoryEvent::jump(
+            format!("bookmark {}", name),
+            self.now_timestamp,
+        ));
+        self.state.position.jump_to(target_index);
+        self.state.position.label = self.script.label_before(target_index).map(str::to_string);
+        self.state.clear_wait();
+        Ok(())
+    }
+
     /// 获取当前状态（用于存档）
     pub fn state(&self) -> &RuntimeState {
         &self.state
     }
 
+    /// 全局持久变量（`$persistent.key` / `$g_key`），供 Host 跨存档保存到单独文件
+    ///
+    /// 会话变量不在其中；key 为 bare key（`g_` 前缀保留）。
+    pub fn persistent_vars(&self) -> &HashMap<String, VarValue> {
+        &self.state.persistent_variables
+    }
+
     /// 获取可变状态引用
     pub fn state_mut(&mut self) -> &mut RuntimeState {
         &mut self.state
@@ -439,8 +839,93 @@ impl VNRuntime {
     ///
     /// 将 Runtime 状态恢复到指定状态。
     /// 注意：调用方需要确保 state 中的 script_id 与当前加载的脚本匹配。
-    pub fn restore_state(&mut self, state: RuntimeState) {
+    ///
+    /// 会校验保存的位置在当前脚本中仍然有效（节点索引不越界、记录的标签仍存在
+    /// 且仍是该位置之前最近的标签），不一致时返回 [`RuntimeError::IncompatibleSave`]
+    /// 且不修改当前状态。
+    pub fn restore_state(&mut self, mut state: RuntimeState) -> Result<(), RuntimeError> {
+        self.check_position(&state.position)?;
+        state.align_local_scopes();
         self.state = state;
+        Ok(())
+    }
+
+    /// 恢复状态；位置与当前脚本不兼容时回退到最近的标签
+    ///
+    /// 回退目标依次为：保存时记录的标签（若仍存在）、保存位置之前最近的标签、脚本开头。
+    /// 回退后清除等待状态，下一次 `tick` 从回退位置继续执行。
+    pub fn restore_state_with_fallback(&mut self, mut state: RuntimeState) -> RestoreOutcome {
+        state.align_local_scopes();
+        let reason = match self.check_position(&state.position) {
+            Ok(()) => {
+                self.state = state;
+                return RestoreOutcome::Exact;
+            }
+            Err(e) => e,
+        };
+
+        let position = &state.position;
+        let label = position
+            .label
+            .as_deref()
+            .filter(|l| self.script.find_label(l).is_some())
+            .or_else(|| self.executed_label_before(position.node_index))
+            .map(str::to_string);
+        let target = label
+            .as_deref()
+            .and_then(|l| self.script.find_label(l))
+            .unwrap_or(0);
+
+        state.position.jump_to(target);
+        state.position.label = label.clone();
+        state.clear_wait();
+        self.state = state;
+        RestoreOutcome::FellBack { label, reason }
+    }
+
+    /// 执行到 `node_index`（下一个待执行节点）时最近经过的标签
+    fn executed_label_before(&self, node_index: usize) -> Option<&str> {
+        node_index
+            .checked_sub(1)
+            .and_then(|i| self.script.label_before(i))
+    }
+
+    /// 校验位置在当前脚本中是否有效
+    fn check_position(&self, position: &ScriptPosition) -> Result<(), RuntimeError> {
+        let len = self.script.len();
+        if position.node_index > len {
+            return Err(RuntimeError::IncompatibleSave {
+                reason: format!(
+                    "节点索引 {} 超出脚本范围（共 {} 个节点）",
+                    position.node_index, len
+                ),
+            });
+        }
+        if let Some(label) = &position.label {
+            if self.script.find_label(label).is_none() {
+                return Err(RuntimeError::IncompatibleSave {
+                    reason: format!("标签 '{label}' 已不存在"),
+                });
+            }
+            if self.executed_label_before(position.node_index) != Some(label.as_str()) {
+                return Err(RuntimeError::IncompatibleSave {
+                    reason: format!("标签 '{label}' 与保存位置不再对应"),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// 按变量名排序遍历全部会话变量（供调试监视）
+    pub fn iter_vars(&self) -> impl Iterator<Item = (&str, &VarValue)> {
+        let mut vars: Vec<(&str, &VarValue)> = self
+            .state
+            .variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+            .collect();
+        vars.sort_unstable_by_key(|(name, _)| *name);
+        vars.into_iter()
     }
 
     /// 获取当前等待状态
@@ -453,6 +938,57 @@ impl VNRuntime {
         self.state.position.node_index >= self.script.len() && !self.state.waiting.is_waiting()
     }
 
+    /// 本周目到达的结局 id（`is_finished` 后即为最终结局）
+    pub fn current_ending(&self) -> Option<&str> {
+        self.state.current_ending.as_deref()
+    }
+
+    /// 已解锁结局集合
+    pub fn unlocked_endings(&self) -> &EndingCollection {
+        &self.state.endings
+    }
+
+    /// 导出已解锁结局（用于持久化）
+    pub fn export_endings(&self) -> EndingCollection {
+        self.state.endings.clone()
+    }
+
+    /// 导入此前周目的已解锁结局（与当前集合合并）
+    pub fn import_endings(&mut self, endings: &EndingCollection) {
+        self.state.endings.merge(endings);
+    }
+
+    /// 已看过的 CG 路径（按路径排序）
+    pub fn seen_cgs(&self) -> &BTreeSet<String> {
+        &self.state.seen_cgs
+    }
+
+    /// 导入此前周目看过的 CG（与当前集合合并）
+    pub fn import_seen_cgs(&mut self, cgs: &BTreeSet<String>) {
+        self.state.seen_cgs.extend(cgs.iter().cloned());
+    }
+
+    /// 当前脚本第 `node_index` 个节点所在章节的预计阅读时长（按默认阅读速度）
+    pub fn chapter_reading_time_at(&self, node_index: usize) -> ChapterReadingTime {
+        chapter_reading_time_at(&self.script, node_index, DEFAULT_READING_CHARS_PER_MINUTE)
+    }
+
+    /// 已走过的分支（脚本 id → 选过的 target_label）
+    pub fn chosen_branches(&self) -> &BTreeMap<String, BTreeSet<String>> {
+        &self.state.chosen_branches
+    }
+
+    /// 导入此前周目走过的分支（与当前记录合并；旧版 key 见 [`branch_script_id`]）
+    pub fn import_chosen_branches(&mut self, branches: &BTreeMap<String, BTreeSet<String>>) {
+        for (key, labels) in branches {
+            self.state
+                .chosen_branches
+                .entry(branch_script_id(key).to_string())
+                .or_default()
+                .extend(labels.iter().cloned());
+        }
+    }
+
     /// 获取历史记录
     pub fn history(&self) -> &History {
         &self.history
@@ -500,5 +1036,27 @@ impl VNRuntime {
     }
 }
 
+/// 本次 tick 是否处于演出空档：产出了命令但没有任何文本，且停在非选择/非 UI 的等待上
+///
+/// 显式的 `textBoxShow` 视为作者要求显示对话框，不算空档。
+fn is_textbox_idle(commands: &[Command], waiting: &WaitingReason) -> bool {
+    let waits_without_text = matches!(
+        waiting,
+        WaitingReason::WaitForClick
+            | WaitingReason::WaitForTime(_)
+            | WaitingReason::WaitForSignal(_)
+    );
+    let has_text = commands.iter().any(|cmd| {
+        matches!(
+            cmd,
+            Command::ShowText { .. }
+                | Command::ExtendText { .. }
+                | Command::PresentChoices { .. }
+                | Command::TextBoxShow
+        )
+    });
+    waits_without_text && !commands.is_empty() && !has_text
+}
+
 #[cfg(test)]
 mod tests;
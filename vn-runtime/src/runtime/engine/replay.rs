@@ -0,0 +1,53 @@
+//! # 输入录制与回放
+//!
+//! 录制开启后，每次 `tick` 被接受的输入（含 `None`）按调用顺序记录；
+//! 与等待状态不匹配而被拒绝的输入不记录。
+//! 导出的序列可序列化保存，之后在新建的 Runtime 上 [`VNRuntime::replay`]，
+//! 借助引擎的确定性复现同一段游玩，用于复现 bug 与回归测试。
+//!
+//! 录制只包含输入：`set_now` 注入的时间戳不影响 Command，不随录制保存。
+
+use crate::command::Command;
+use crate::error::RuntimeError;
+use crate::input::RuntimeInput;
+
+use super::VNRuntime;
+
+impl VNRuntime {
+    /// 开始录制输入（已在录制时保留已有记录）
+    pub fn start_recording(&mut self) {
+        self.recording.get_or_insert_with(Vec::new);
+    }
+
+    /// 停止录制并导出输入序列（未开启时返回 `None`）
+    pub fn stop_recording(&mut self) -> Option<Vec<Option<RuntimeInput>>> {
+        self.recording.take()
+    }
+
+    /// 当前已录制的输入序列（未开启时返回 `None`）
+    pub fn recorded_inputs(&self) -> Option<&[Option<RuntimeInput>]> {
+        self.recording.as_deref()
+    }
+
+    /// 按顺序回放输入序列，每个元素驱动一次 `tick`
+    ///
+    /// 返回全部 tick 产生的 Command（按产生顺序拼接）；任一 tick 出错时立即返回该错误。
+    /// 录制开启时回放的输入同样会被记录。
+    pub fn replay(
+        &mut self,
+        inputs: impl IntoIterator<Item = Option<RuntimeInput>>,
+    ) -> Result<Vec<Command>, RuntimeError> {
+        let mut all = Vec::new();
+        for input in inputs {
+            let (commands, _) = self.tick(input)?;
+            all.extend(commands);
+        }
+        Ok(all)
+    }
+
+    pub(super) fn record_input(&mut self, input: Option<RuntimeInput>) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(input);
+        }
+    }
+}
@@ -87,6 +87,7 @@ fn test_runtime_with_goto() {
                 content: "开始".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Goto {
                 target_label: "end".to_string(),
@@ -96,6 +97,7 @@ fn test_runtime_with_goto() {
                 content: "这句不应该执行".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Label {
                 name: "end".to_string(),
@@ -105,6 +107,7 @@ fn test_runtime_with_goto() {
                 content: "结束".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "",
@@ -134,14 +137,21 @@ fn test_runtime_with_choice() {
         vec![
             ScriptNode::Choice {
                 style: None,
+                prompt: None,
                 options: vec![
                     ChoiceOption {
                         text: "选项A".to_string(),
                         target_label: "a".to_string(),
+                        condition: None,
+                        disabled_reason: None,
+                        actions: Vec::new(),
                     },
                     ChoiceOption {
                         text: "选项B".to_string(),
                         target_label: "b".to_string(),
+                        condition: None,
+                        disabled_reason: None,
+                        actions: Vec::new(),
                     },
                 ],
             },
@@ -153,6 +163,7 @@ fn test_runtime_with_choice() {
                 content: "选了A".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Label {
                 name: "b".to_string(),
@@ -162,6 +173,7 @@ fn test_runtime_with_choice() {
                 content: "选了B".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "",
@@ -186,6 +198,64 @@ fn test_runtime_with_choice() {
     assert!(matches!(&commands2[0], Command::ShowText { content, .. } if content == "选了B"));
 }
 
+#[test]
+fn test_coverage_records_executed_nodes_only() {
+    use crate::script::ChoiceOption;
+
+    let script = Script::new(
+        "test",
+        vec![
+            ScriptNode::Choice {
+                style: None,
+                prompt: None,
+                options: vec![
+                    ChoiceOption {
+                        text: "选项A".to_string(),
+                        target_label: "a".to_string(),
+                        condition: None,
+                        disabled_reason: None,
+                        actions: Vec::new(),
+                    },
+                    ChoiceOption {
+                        text: "选项B".to_string(),
+                        target_label: "b".to_string(),
+                        condition: None,
+                        disabled_reason: None,
+                        actions: Vec::new(),
+                    },
+                ],
+            },
+            ScriptNode::Label {
+                name: "a".to_string(),
+            },
+            ScriptNode::Goto {
+                target_label: "end".to_string(),
+            },
+            ScriptNode::Label {
+                name: "b".to_string(),
+            },
+            ScriptNode::Label {
+                name: "end".to_string(),
+            },
+        ],
+        "",
+    );
+    let mut runtime = VNRuntime::new(script);
+    assert!(runtime.coverage().is_none());
+
+    runtime.enable_coverage();
+    runtime.tick(None).unwrap();
+    runtime
+        .tick(Some(RuntimeInput::ChoiceSelected { index: 0 }))
+        .unwrap();
+
+    let visited: Vec<usize> = runtime.coverage().unwrap()["test"]
+        .iter()
+        .copied()
+        .collect();
+    assert_eq!(visited, vec![0, 1, 2, 4]);
+}
+
 #[test]
 fn test_invalid_choice_index_error() {
     use crate::script::ChoiceOption;
@@ -194,14 +264,21 @@ fn test_invalid_choice_index_error() {
         "test",
         vec![ScriptNode::Choice {
             style: None,
+            prompt: None,
             options: vec![
                 ChoiceOption {
                     text: "A".to_string(),
                     target_label: "a".to_string(),
+                    condition: None,
+                    disabled_reason: None,
+                    actions: Vec::new(),
                 },
                 ChoiceOption {
                     text: "B".to_string(),
                     target_label: "b".to_string(),
+                    condition: None,
+                    disabled_reason: None,
+                    actions: Vec::new(),
                 },
             ],
         }],
@@ -226,6 +303,773 @@ fn test_invalid_choice_index_error() {
     ));
 }
 
+#[test]
+fn test_jump_to_bookmark() {
+    let script = Script::new(
+        "test",
+        vec![
+            ScriptNode::Dialogue {
+                speaker: None,
+                content: "开头".to_string(),
+                inline_effects: vec![],
+                no_wait: false,
+                text_speed: 1.0,
+            },
+            ScriptNode::Dialogue {
+                speaker: None,
+                content: "跳过".to_string(),
+                inline_effects: vec![],
+                no_wait: false,
+                text_speed: 1.0,
+            },
+            ScriptNode::Bookmark {
+                name: "intro_end".to_string(),
+            },
+            ScriptNode::Dialogue {
+                speaker: None,
+                content: "书签之后".to_string(),
+                inline_effects: vec![],
+                no_wait: false,
+                text_speed: 1.0,
+            },
+        ],
+        "",
+    );
+    let mut runtime = VNRuntime::new(script);
+
+    // 停在第一句对话（WaitForClick）
+    runtime.tick(None).unwrap();
+    assert!(runtime.waiting().is_waiting());
+
+    runtime.jump_to_bookmark("intro_end").unwrap();
+    assert!(!runtime.waiting().is_waiting());
+
+    let (commands, _) = runtime.tick(None).unwrap();
+    assert_eq!(commands.len(), 1);
+    assert!(matches!(&commands[0], Command::ShowText { content, .. } if content == "书签之后"));
+}
+
+#[test]
+fn test_jump_to_unknown_bookmark_error() {
+    let script = Script::new(
+        "test",
+        vec![ScriptNode::Label {
+            name: "start".to_string(),
+        }],
+        "",
+    );
+    let mut runtime = VNRuntime::new(script);
+
+    // label 不能作为书签目标
+    let err = runtime.jump_to_bookmark("start").unwrap_err();
+    assert!(matches!(err, RuntimeError::BookmarkNotFound { name } if name == "start"));
+}
+
+fn parse_script(text: &str) -> Script {
+    crate::script::Parser::new().parse("test", text).unwrap()
+}
+
+/// 按固定输入序列跑完脚本，收集全部 Command
+///
+/// 等待点击/定时/信号时自动解除，选择时依次取 `choices`，UI 请求统一回传 `"ok"`。
+fn run_with_inputs(text: &str, choices: &[usize]) -> Vec<Command> {
+    let mut runtime = VNRuntime::new(parse_script(text));
+    let mut choices = choices.iter().copied();
+    let mut all = Vec::new();
+    let mut input = None;
+    for _ in 0..200 {
+        let (commands, waiting) = runtime.tick(input.take()).unwrap();
+        all.extend(commands);
+        input = match waiting {
+            WaitingReason::None => return all,
+            WaitingReason::WaitForClick | WaitingReason::WaitForTime(_) => {
+                Some(RuntimeInput::Click)
+            }
+            WaitingReason::WaitForSignal(id) => Some(RuntimeInput::Signal { id }),
+            WaitingReason::WaitForChoice { .. } => Some(RuntimeInput::ChoiceSelected {
+                index: choices.next().expect("选择次数超出输入序列"),
+            }),
+            WaitingReason::WaitForUIResult { key, .. } => Some(RuntimeInput::UIResult {
+                key,
+                value: VarValue::String("ok".to_string()),
+            }),
+            WaitingReason::Breakpoint(_) => unreachable!("未设置断点"),
+        };
+    }
+    panic!("脚本未在 200 次 tick 内结束");
+}
+
+#[test]
+fn test_textbox_auto_hide_during_performance_and_shows_for_dialogue() {
+    let text = r#"
+旁白："开始"
+show <img src="a.png" /> as a at center
+wait 1
+hide a
+wait 0.5
+pause
+旁白："继续"
+"#;
+    let auto_hidden = |enabled: bool| {
+        let mut runtime = VNRuntime::new(parse_script(text));
+        runtime.set_textbox_auto_hide(enabled);
+        let mut hints = Vec::new();
+        let mut input = None;
+        loop {
+            let (commands, waiting) = runtime.tick(input.take()).unwrap();
+            if waiting == WaitingReason::None {
+                return hints;
+            }
+            hints.push(commands.contains(&Command::TextBoxAutoHide));
+            input = Some(RuntimeInput::Click);
+        }
+    };
+
+    // 对话 → 演出+wait → 演出+wait → 无新命令的 pause → 对话
+    assert_eq!(auto_hidden(true), [false, true, true, false, false]);
+    assert_eq!(auto_hidden(false), [false; 5]);
+}
+
+#[test]
+fn test_upcoming_resources_follow_goto_and_union_choices() {
+    let runtime = VNRuntime::new(parse_script(
+        r#"
+changeBG <img src="bg/start.png" />
+旁白："开始"
+goto **pick**
+changeBG <img src="bg/skipped.png" />
+
+**pick**
+
+| 选项 | 跳转 |
+| --- | --- |
+| 左 | left |
+| 右 | right |
+
+**left**
+show <img src="ch/left.png" /> as l at left
+goto **end**
+
+**right**
+<audio src="sfx/right.ogg"></audio>
+
+**end**
+"#,
+    ));
+    let paths = |lookahead| {
+        runtime
+            .upcoming_resources(lookahead)
+            .into_iter()
+            .map(|r| r.resolved_path)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(paths(2), ["bg/start.png"]);
+    let all = paths(20);
+    assert_eq!(all[0], "bg/start.png");
+    assert!(all.contains(&"ch/left.png".to_string()), "{all:?}");
+    assert!(all.contains(&"sfx/right.ogg".to_string()), "{all:?}");
+    assert!(!all.contains(&"bg/skipped.png".to_string()), "{all:?}");
+}
+
+#[test]
+fn test_upcoming_resources_follow_call_script_and_return() {
+    let mut runtime = VNRuntime::new(parse_script(
+        r#"
+callScript [sub](child.md)
+changeBG <img src="bg/after_call.png" />
+"#,
+    ));
+    runtime.register_script(
+        "child.md",
+        parse_script(
+            r#"
+changeBG <img src="bg/child.png" />
+旁白："子流程"
+returnFromScript
+"#,
+        ),
+    );
+    let paths = |runtime: &VNRuntime| {
+        runtime
+            .upcoming_resources(10)
+            .into_iter()
+            .map(|r| r.resolved_path)
+            .collect::<Vec<_>>()
+    };
+
+    // 调用前：被调脚本与调用方后续节点都在前瞻范围内
+    let before = paths(&runtime);
+    assert!(before.contains(&"bg/child.png".to_string()), "{before:?}");
+    assert!(
+        before.contains(&"bg/after_call.png".to_string()),
+        "{before:?}"
+    );
+
+    // 进入子脚本后：returnFromScript 按调用栈回到调用方
+    runtime.tick(None).unwrap();
+    assert_eq!(runtime.state().call_stack.len(), 1);
+    let inside = paths(&runtime);
+    assert!(
+        inside.contains(&"bg/after_call.png".to_string()),
+        "{inside:?}"
+    );
+}
+
+#[test]
+fn test_same_inputs_produce_identical_commands() {
+    const BRANCHY: &str = r#"
+set $route = "none"
+set $score = 1
+
+：“开始。”
+
+| 选项 | 跳转 |
+| --- | --- |
+| 去海边 | sea |
+| 去山里 | mountain |
+
+**sea**
+
+set $route = "sea"
+goto **merge**
+
+**mountain**
+
+set $route = "mountain"
+
+**merge**
+
+if $route == "sea"
+  海边：“浪很大。”
+elseif $route == "mountain"
+  山里：“风很冷。”
+else
+  ：“哪也没去。”
+endif
+
+wait 0.5
+
+requestUI "show_map" as $picked (zoom: 2, map_id: "world", title: "地图", layer: 1)
+
+if $picked == "ok" and not ($score == 0)
+  ：“结束。”
+endif
+"#;
+    const LOOPING: &str = r#"
+set $visited = false
+
+**hub**
+
+：“回到起点。”
+
+if $visited == true
+  goto **end**
+endif
+
+set $visited = true
+
+| 选项 | 跳转 |
+| --- | --- |
+| 再来一次 | hub |
+| 结束 | end |
+
+**end**
+
+：“完。”
+"#;
+
+    for (text, choices) in [
+        (BRANCHY, &[0][..]),
+        (BRANCHY, &[1][..]),
+        (LOOPING, &[0][..]),
+    ] {
+        let first = run_with_inputs(text, choices);
+        let second = run_with_inputs(text, choices);
+        assert!(!first.is_empty());
+        assert_eq!(first.len(), second.len());
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a, b);
+            // 序列化结果也必须一致（映射迭代顺序稳定）
+            assert_eq!(
+                serde_json::to_string(a).unwrap(),
+                serde_json::to_string(b).unwrap()
+            );
+        }
+    }
+
+    // 不同分支确实产生不同输出，避免测试退化为空比较
+    assert_ne!(
+        run_with_inputs(BRANCHY, &[0]),
+        run_with_inputs(BRANCHY, &[1])
+    );
+}
+
+#[test]
+fn test_replay_recorded_inputs_reproduces_commands() {
+    let text = r#"
+：“开始。”
+
+| 选项 | 跳转 |
+| --- | --- |
+| 去海边 | sea |
+| 去山里 | mountain |
+
+**sea**
+
+海边：“浪很大。”
+wait 0.5
+goto **end**
+
+**mountain**
+
+山里：“风很冷。”
+
+**end**
+
+：“完。”
+"#;
+    let mut runtime = VNRuntime::new(parse_script(text));
+    assert!(runtime.recorded_inputs().is_none());
+    runtime.start_recording();
+
+    let mut original = Vec::new();
+    let (commands, _) = runtime.tick(None).unwrap();
+    original.extend(commands);
+    // 被拒绝的输入不进入录制，否则回放会在同一处报错
+    assert!(
+        runtime
+            .tick(Some(RuntimeInput::ChoiceSelected { index: 0 }))
+            .is_err()
+    );
+    assert_eq!(runtime.recorded_inputs().unwrap().len(), 1);
+
+    let mut input = Some(RuntimeInput::Click);
+    loop {
+        let (commands, waiting) = runtime.tick(input.take()).unwrap();
+        original.extend(commands);
+        input = match waiting {
+            WaitingReason::None => break,
+            WaitingReason::WaitForChoice { .. } => Some(RuntimeInput::ChoiceSelected { index: 0 }),
+            _ => Some(RuntimeInput::Click),
+        };
+    }
+    let recorded = runtime.stop_recording().unwrap();
+    assert!(recorded[0].is_none());
+    assert!(recorded.contains(&Some(RuntimeInput::ChoiceSelected { index: 0 })));
+    assert!(runtime.recorded_inputs().is_none());
+
+    // 导出导入后回放，Command 序列逐条一致
+    let json = serde_json::to_string(&recorded).unwrap();
+    let imported: Vec<Option<RuntimeInput>> = serde_json::from_str(&json).unwrap();
+    let mut replayed = VNRuntime::new(parse_script(text));
+    assert_eq!(replayed.replay(imported).unwrap(), original);
+    assert_eq!(replayed.state().waiting, WaitingReason::None);
+}
+
+#[test]
+fn test_present_choices_carries_prompt() {
+    let text = r#"
+> 要去哪里呢？
+| 横排 | |
+| --- | --- |
+| 去海边 | sea |
+| 去山里 | mountain |
+
+**sea**
+
+**mountain**
+"#;
+    let mut runtime = VNRuntime::new(parse_script(text));
+    let (commands, waiting) = runtime.tick(None).unwrap();
+    assert_eq!(waiting, WaitingReason::WaitForChoice { choice_count: 2 });
+    let [
+        Command::PresentChoices {
+            style,
+            choices,
+            prompt,
+        },
+    ] = commands.as_slice()
+    else {
+        panic!("expected a single PresentChoices, got {commands:?}");
+    };
+    assert_eq!(style.as_deref(), Some("横排"));
+    assert_eq!(choices.len(), 2);
+    assert_eq!(prompt.as_deref(), Some("要去哪里呢？"));
+
+    // 无提示时不序列化该字段，旧 Host 仍可解析
+    let plain = Command::PresentChoices {
+        style: None,
+        choices: choices.clone(),
+        prompt: None,
+    };
+    assert!(!serde_json::to_string(&plain).unwrap().contains("prompt"));
+}
+
+#[test]
+fn test_choose_validates_waiting_state_and_index() {
+    let text = r#"
+：“开始。”
+
+| 选项 | 跳转 |
+| --- | --- |
+| 去海边 | sea |
+| 去山里 | mountain |
+
+**sea**
+
+海边：“浪很大。”
+
+**mountain**
+
+山里：“风很冷。”
+"#;
+    let mut runtime = VNRuntime::new(parse_script(text));
+    runtime.tick(None).unwrap();
+
+    // 等待点击时不能选择，状态不变
+    let err = runtime.choose(0).unwrap_err();
+    assert!(matches!(err, RuntimeError::UnexpectedInput { .. }));
+    assert_eq!(runtime.state().waiting, WaitingReason::WaitForClick);
+
+    let (_, waiting) = runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert_eq!(waiting, WaitingReason::WaitForChoice { choice_count: 2 });
+
+    // 越界索引报错且仍停在选择点
+    let err = runtime.choose(2).unwrap_err();
+    assert!(matches!(
+        err,
+        RuntimeError::InvalidChoiceIndex { index: 2, max: 2 }
+    ));
+    assert_eq!(
+        runtime.state().waiting,
+        WaitingReason::WaitForChoice { choice_count: 2 }
+    );
+
+    // 合法选择推进到对应分支
+    let (commands, waiting) = runtime.choose(1).unwrap();
+    assert_eq!(waiting, WaitingReason::WaitForClick);
+    assert!(commands.iter().any(|c| matches!(
+        c,
+        Command::ShowText { content, .. } if content == "风很冷。"
+    )));
+
+    // 脚本结束（不再等待）后同样拒绝
+    runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert!(runtime.is_finished());
+    assert!(matches!(
+        runtime.choose(0),
+        Err(RuntimeError::UnexpectedInput { .. })
+    ));
+}
+
+#[test]
+fn test_unmet_choice_condition_marks_option_disabled_but_keeps_it() {
+    let script = parse_script(
+        r#"
+set $has_key = false
+
+| 选项 | 跳转 | 条件 | 理由 |
+| --- | --- | --- | --- |
+| 开门 | door | $has_key == true | 需要钥匙 |
+| 离开 | leave |
+
+**door**
+旁白："门开了"
+**leave**
+旁白："离开了"
+"#,
+    );
+    let mut runtime = VNRuntime::new(script);
+    let (commands, waiting) = runtime.tick(None).unwrap();
+
+    assert!(matches!(
+        waiting,
+        WaitingReason::WaitForChoice { choice_count: 2 }
+    ));
+    let choices = commands
+        .iter()
+        .find_map(|c| match c {
+            Command::PresentChoices { choices, .. } => Some(choices),
+            _ => None,
+        })
+        .expect("choices presented");
+    assert_eq!(choices.len(), 2);
+    assert!(!choices[0].enabled);
+    assert_eq!(choices[0].disabled_reason.as_deref(), Some("需要钥匙"));
+    assert!(choices[1].enabled);
+    assert_eq!(choices[1].disabled_reason, None);
+
+    // 禁用项不可选，等待状态保持
+    let err = runtime
+        .tick(Some(RuntimeInput::choice(0)))
+        .expect_err("disabled option must be rejected");
+    assert!(err.to_string().contains("不可选"), "{err}");
+    assert!(matches!(
+        runtime.waiting(),
+        WaitingReason::WaitForChoice { .. }
+    ));
+
+    let (commands, _) = runtime.tick(Some(RuntimeInput::choice(1))).unwrap();
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c, Command::ShowText { content, .. } if content == "离开了"))
+    );
+}
+
+#[test]
+fn test_choice_with_every_option_disabled_is_an_error() {
+    let script = parse_script(
+        r#"
+set $has_key = false
+
+| 选项 | 跳转 | 条件 |
+| --- | --- | --- |
+| 开门 | door | $has_key == true |
+| 撬锁 | door | $has_key == true |
+
+**door**
+旁白："门开了"
+"#,
+    );
+    let mut runtime = VNRuntime::new(script);
+    let err = runtime
+        .tick(None)
+        .expect_err("a choice with no enabled option must not wait");
+    assert!(matches!(
+        err.kind(),
+        RuntimeError::NoEnabledChoice { count: 2 }
+    ));
+    assert_eq!(err.code(), "E0111");
+    assert!(!matches!(
+        runtime.waiting(),
+        WaitingReason::WaitForChoice { .. }
+    ));
+}
+
+const REVISIT_MENU: &str = r#"
+**menu**
+| 选项 | 跳转 |
+| --- | --- |
+| 开门 | door |
+| 离开 | leave |
+
+**door**
+旁白："门开了"
+goto **menu**
+**leave**
+旁白："离开了"
+"#;
+
+/// 推进到下一次选择，返回各选项的 visited 标记
+fn next_choice_visited(runtime: &mut VNRuntime, input: Option<RuntimeInput>) -> Vec<bool> {
+    let mut input = input;
+    loop {
+        let (commands, waiting) = runtime.tick(input.take()).unwrap();
+        if let Some(choices) = commands.iter().find_map(|c| match c {
+            Command::PresentChoices { choices, .. } => Some(choices),
+            _ => None,
+        }) {
+            return choices.iter().map(|c| c.visited).collect();
+        }
+        assert!(waiting.is_waiting(), "脚本结束前未再次出现选择");
+        input = Some(RuntimeInput::Click);
+    }
+}
+
+#[test]
+fn test_chosen_option_is_marked_visited_on_revisit() {
+    let mut runtime = VNRuntime::new(parse_script(REVISIT_MENU));
+    assert_eq!(next_choice_visited(&mut runtime, None), [false, false]);
+
+    // 选"开门"后回到同一选择点，该选项被标记为已走过
+    let visited = next_choice_visited(&mut runtime, Some(RuntimeInput::choice(0)));
+    assert_eq!(visited, [true, false]);
+    let labels: Vec<_> = runtime.chosen_branches().values().flatten().collect();
+    assert_eq!(labels, ["door"]);
+}
+
+#[test]
+fn test_chosen_branches_accumulate_across_playthroughs() {
+    let mut first = VNRuntime::new(parse_script(REVISIT_MENU));
+    next_choice_visited(&mut first, None);
+    first.tick(Some(RuntimeInput::choice(1))).unwrap();
+
+    // 第二周目：导入上一周目的记录
+    let mut second = VNRuntime::new(parse_script(REVISIT_MENU));
+    second.import_chosen_branches(first.chosen_branches());
+    assert_eq!(next_choice_visited(&mut second, None), [false, true]);
+    let visited = next_choice_visited(&mut second, Some(RuntimeInput::choice(0)));
+    assert_eq!(visited, [true, true]);
+
+    // 记录由 host 单独持久化，第三周目导入后仍可见两条已走过的分支
+    let json = serde_json::to_string(second.chosen_branches()).unwrap();
+    let restored: BTreeMap<String, BTreeSet<String>> = serde_json::from_str(&json).unwrap();
+    let mut third = VNRuntime::new(parse_script(REVISIT_MENU));
+    third.import_chosen_branches(&restored);
+    assert_eq!(next_choice_visited(&mut third, None), [true, true]);
+}
+
+#[test]
+fn test_cross_playthrough_records_are_not_saved_with_state() {
+    let mut runtime = VNRuntime::new(parse_script(REVISIT_MENU));
+    next_choice_visited(&mut runtime, None);
+    runtime.tick(Some(RuntimeInput::choice(0))).unwrap();
+    runtime.import_endings(&{
+        let mut endings = EndingCollection::new();
+        endings.unlock("true_end", "真结局");
+        endings
+    });
+
+    let json = serde_json::to_value(runtime.state()).unwrap();
+    for field in ["endings", "seen_cgs", "chosen_branches"] {
+        assert!(json.get(field).is_none(), "{field} 不应写入存档");
+    }
+}
+
+#[test]
+fn test_legacy_node_index_branch_keys_merge_by_script() {
+    let mut runtime = VNRuntime::new(parse_script(REVISIT_MENU));
+    let script_id = runtime.state().position.script_id.clone();
+    let legacy = BTreeMap::from([(
+        format!("{script_id}:3"),
+        BTreeSet::from(["door".to_string()]),
+    )]);
+    runtime.import_chosen_branches(&legacy);
+    assert_eq!(next_choice_visited(&mut runtime, None), [true, false]);
+}
+
+#[test]
+fn test_choice_actions_run_before_jump() {
+    let script = parse_script(
+        r#"
+set $mood = "calm"
+
+| text | target | action |
+| --- | --- | --- |
+| 开门 | door | $opened = true; $mood = "brave" |
+| 离开 | leave | |
+
+**door**
+旁白："门开了"
+**leave**
+旁白："离开了"
+"#,
+    );
+    let mut runtime = VNRuntime::new(script);
+    runtime.tick(None).unwrap();
+    runtime.tick(Some(RuntimeInput::choice(0))).unwrap();
+
+    assert_eq!(
+        runtime.state().get_var("opened"),
+        Some(&VarValue::Bool(true))
+    );
+    assert_eq!(
+        runtime.state().get_var("mood"),
+        Some(&VarValue::String("brave".to_string()))
+    );
+}
+
+#[test]
+fn test_persistent_vars_only_contains_global_variables() {
+    let script = parse_script(
+        r#"
+set $g_cleared = true
+set $persistent.endings_seen = 2
+set $mood = "calm"
+"#,
+    );
+    let mut runtime = VNRuntime::new(script);
+    runtime.tick(None).unwrap();
+
+    let vars = runtime.persistent_vars();
+    assert_eq!(vars.get("g_cleared"), Some(&VarValue::Bool(true)));
+    assert_eq!(vars.get("endings_seen"), Some(&VarValue::Int(2)));
+    assert!(!vars.contains_key("mood"));
+    assert_eq!(vars.len(), 2);
+
+    // g_ 变量不落入会话变量；`$persistent.g_cleared` 与 `$g_cleared` 是同一个变量
+    assert_eq!(runtime.state().get_var("g_cleared"), None);
+    assert_eq!(
+        crate::script::EvalContext::get_var(runtime.state(), "persistent.g_cleared"),
+        Some(&VarValue::Bool(true))
+    );
+}
+
+#[test]
+fn test_skip_to_next_choice_stops_at_choice() {
+    let script = parse_script(
+        r#"
+旁白："第一句"
+wait 1.5
+旁白："第二句"
+旁白："第三句"
+
+| 选项 | 跳转 |
+| --- | --- |
+| A | **a** |
+| B | **b** |
+
+**a**
+旁白："选择之后"
+**b**
+"#,
+    );
+    let mut runtime = VNRuntime::new(script);
+    runtime.tick(None).unwrap();
+
+    let (commands, waiting) = runtime.skip_to_next_choice().unwrap();
+    assert!(matches!(
+        waiting,
+        WaitingReason::WaitForChoice { choice_count: 2 }
+    ));
+    let texts: Vec<&str> = commands
+        .iter()
+        .filter_map(|c| match c {
+            Command::ShowText { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, ["第二句", "第三句"]);
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c, Command::PresentChoices { .. }))
+    );
+    // 跳过的对话仍写入历史
+    assert_eq!(runtime.history().dialogue_count(), 3);
+
+    // 已停在选择点时再次调用不推进
+    let (commands, waiting) = runtime.skip_to_next_choice().unwrap();
+    assert!(commands.is_empty());
+    assert!(matches!(waiting, WaitingReason::WaitForChoice { .. }));
+}
+
+#[test]
+fn test_skip_to_next_choice_runs_to_script_end_without_choice() {
+    let script = parse_script("旁白：\"一\"\n旁白：\"二\"\n旁白：\"三\"\n");
+    let mut runtime = VNRuntime::new(script);
+
+    let (commands, waiting) = runtime.skip_to_next_choice().unwrap();
+    assert_eq!(waiting, WaitingReason::None);
+    assert!(runtime.is_finished());
+    assert_eq!(commands.len(), 3);
+    assert_eq!(runtime.history().dialogue_count(), 3);
+}
+
+#[test]
+fn test_skip_to_next_choice_iteration_cap() {
+    let script = parse_script("**loop**\n旁白：\"循环\"\ngoto **loop**\n");
+    let mut runtime = VNRuntime::new(script);
+
+    let err = runtime.skip_to_next_choice().unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::VnError::Runtime(RuntimeError::InvalidState { .. })
+    ));
+}
+
 #[test]
 fn test_choice_selected_label_not_found_error() {
     use crate::script::ChoiceOption;
@@ -235,9 +1079,13 @@ fn test_choice_selected_label_not_found_error() {
         vec![
             ScriptNode::Choice {
                 style: None,
+                prompt: None,
                 options: vec![ChoiceOption {
                     text: "A".to_string(),
                     target_label: "missing".to_string(),
+                    condition: None,
+                    disabled_reason: None,
+                    actions: Vec::new(),
                 }],
             },
             // 故意不提供 label "missing"
@@ -246,6 +1094,7 @@ fn test_choice_selected_label_not_found_error() {
                 content: "不会执行".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "",
@@ -296,6 +1145,51 @@ fn test_wait_for_time_click_interrupts() {
     assert!(!waiting.is_waiting() || matches!(waiting, WaitingReason::WaitForClick));
 }
 
+#[test]
+fn test_timed_goto_jumps_to_label_when_wait_expires() {
+    use std::time::Duration;
+
+    let script = parse_script(
+        r#"
+：“停留。”
+
+goto **结局** after 3
+
+：“不应显示。”
+
+**结局**
+
+：“到达结局。”
+"#,
+    );
+    let mut runtime = VNRuntime::new(script);
+
+    runtime.tick(None).unwrap();
+    let (commands, waiting) = runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert!(commands.is_empty());
+    assert_eq!(
+        waiting,
+        WaitingReason::WaitForTime(Duration::from_secs_f64(3.0))
+    );
+    assert_eq!(runtime.state().pending_goto.as_deref(), Some("结局"));
+
+    // Host 计时到期后解除等待并 tick
+    runtime.state_mut().clear_wait();
+    let (commands, _) = runtime.tick(None).unwrap();
+    assert!(matches!(
+        &commands[0],
+        Command::ShowText { content, .. } if content == "到达结局。"
+    ));
+    assert!(runtime.state().pending_goto.is_none());
+    assert!(
+        runtime
+            .history()
+            .events()
+            .iter()
+            .any(|e| matches!(e, HistoryEvent::Jump { label, .. } if label == "结局"))
+    );
+}
+
 #[test]
 fn test_wait_for_time_ignores_non_click_input() {
     use std::time::Duration;
@@ -314,7 +1208,7 @@ fn test_wait_for_time_ignores_non_click_input() {
 }
 
 #[test]
-fn test_state_mismatch_error() {
+fn test_unexpected_input_error() {
     let script = create_test_script();
     let mut runtime = VNRuntime::new(script);
 
@@ -325,7 +1219,45 @@ fn test_state_mismatch_error() {
     let err = runtime
         .tick(Some(RuntimeInput::ChoiceSelected { index: 0 }))
         .unwrap_err();
-    assert!(matches!(err, RuntimeError::StateMismatch { .. }));
+    assert!(matches!(err, RuntimeError::UnexpectedInput { .. }));
+}
+
+#[test]
+fn test_choice_wait_rejects_click_and_out_of_range_index() {
+    let mut runtime = VNRuntime::new(parse_script(
+        "| 选项 | 跳转 |\n| --- | --- |\n| 去 | go |\n| 留 | stay |\n**go**\n：\"出发。\"\n**stay**\n",
+    ));
+    let (_, waiting) = runtime.tick(None).unwrap();
+    assert_eq!(waiting, WaitingReason::WaitForChoice { choice_count: 2 });
+
+    // 误传 Click 被拒，等待状态不变
+    let err = runtime.tick(Some(RuntimeInput::Click)).unwrap_err();
+    assert!(matches!(err, RuntimeError::UnexpectedInput { .. }));
+    assert_eq!(err.code(), "E0105");
+    assert_eq!(
+        runtime.state().waiting,
+        WaitingReason::WaitForChoice { choice_count: 2 }
+    );
+
+    // 越界索引报错，同样不改变状态
+    let err = runtime
+        .tick(Some(RuntimeInput::ChoiceSelected { index: 5 }))
+        .unwrap_err();
+    assert_eq!(err, RuntimeError::InvalidChoiceIndex { index: 5, max: 2 });
+    assert_eq!(
+        runtime.state().waiting,
+        WaitingReason::WaitForChoice { choice_count: 2 }
+    );
+
+    // 之后合法选择仍可正常推进
+    let (commands, _) = runtime
+        .tick(Some(RuntimeInput::ChoiceSelected { index: 0 }))
+        .unwrap();
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c, Command::ShowText { content, .. } if content == "出发。"))
+    );
 }
 
 #[test]
@@ -342,6 +1274,7 @@ fn test_call_script_and_return_flow() {
                 content: "主线继续".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "scripts/remake",
@@ -358,6 +1291,7 @@ fn test_call_script_and_return_flow() {
                 content: "子流程".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::ReturnFromScript,
         ],
@@ -394,6 +1328,34 @@ fn test_call_script_and_return_flow() {
     assert!(runtime.state().call_stack.is_empty());
 }
 
+#[test]
+fn test_failed_return_pops_local_scope_with_call_frame() {
+    // 旧存档：调用栈中有一帧，但还没有局部帧
+    let mut state = RuntimeState::new("child");
+    state
+        .call_stack
+        .push(crate::state::ScriptPosition::with_path(
+            "ghost".to_string(),
+            "scripts/ghost.md".to_string(),
+            1,
+        ));
+    let mut runtime = VNRuntime::restore(
+        Script::new("child", vec![ScriptNode::ReturnFromScript], ""),
+        state,
+        History::new(),
+    );
+    assert_eq!(runtime.state().local_scopes.len(), 1);
+
+    // 返回目标未加载：报错，但调用帧与局部帧一起弹出
+    let err = runtime.tick(None).unwrap_err();
+    assert!(
+        matches!(err, RuntimeError::ScriptNotLoaded { .. }),
+        "{err:?}"
+    );
+    assert!(runtime.state().call_stack.is_empty());
+    assert!(runtime.state().local_scopes.is_empty());
+}
+
 #[test]
 fn test_call_script_missing_target_returns_error() {
     let script = Script::new(
@@ -414,6 +1376,79 @@ fn test_call_script_missing_target_returns_error() {
     assert!(matches!(err, RuntimeError::ScriptNotLoaded { .. }));
 }
 
+#[test]
+fn test_call_script_locals_dropped_on_return_globals_shared() {
+    let main_script = parse_script(
+        r#"
+set $score = 1
+set $tmp = "global"
+callScript [sub](child.md)
+旁白："回到主线"
+"#,
+    );
+    let child_script = parse_script(
+        r#"
+local $tmp = 5
+local $scratch
+set $scratch = true
+set $score = 2
+set $seen_tmp = $tmp
+returnFromScript
+"#,
+    );
+    let mut runtime = VNRuntime::new(main_script);
+    runtime.register_script("child.md", child_script);
+
+    let (_, waiting) = runtime.tick(None).unwrap();
+    assert!(matches!(waiting, WaitingReason::WaitForClick));
+    assert_eq!(runtime.state().position.script_id, "test");
+
+    let state = runtime.state();
+    assert!(state.local_scopes.is_empty());
+    // local 变量只写入局部帧，return 后不可见，同名全局不受影响
+    assert_eq!(
+        state.get_var("tmp"),
+        Some(&VarValue::String("global".to_string()))
+    );
+    assert_eq!(state.get_var("scratch"), None);
+    // 未声明为 local 的变量读写全局
+    assert_eq!(state.get_var("score"), Some(&VarValue::Int(2)));
+    assert_eq!(state.get_var("seen_tmp"), Some(&VarValue::Int(5)));
+}
+
+#[test]
+fn test_local_outside_call_is_error() {
+    let mut runtime = VNRuntime::new(parse_script("local $tmp = 1\n"));
+    let err = runtime.tick(None).unwrap_err();
+    assert!(matches!(err.kind(), RuntimeError::InvalidState { .. }));
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_assert_fails_with_message_only_when_enabled() {
+    let text = "set $gold = 1\nassert $gold == 1 \"金币应为 1\"\nassert $gold == 0 \"金币应为零\"\n：“之后”\n";
+
+    let mut runtime = VNRuntime::new(parse_script(text));
+    runtime.set_assertions_enabled(true);
+    let err = runtime.tick(None).unwrap_err();
+    // 第 2 行为真无事，第 3 行为假报错
+    assert_eq!(err.line(), Some(3));
+    assert!(
+        matches!(err.kind(), RuntimeError::AssertionFailed { message } if message == "金币应为零")
+    );
+    assert_eq!(err.code(), "E0110");
+
+    // 关闭断言（release 默认）时直接跳过
+    let mut runtime = VNRuntime::new(parse_script(text));
+    runtime.set_assertions_enabled(false);
+    let (commands, _) = runtime.tick(None).unwrap();
+    assert!(
+        commands
+            .iter()
+            .any(|c| matches!(c, Command::ShowText { content, .. } if content == "之后"))
+    );
+}
+
 #[test]
 fn test_call_script_auto_return_on_child_eof() {
     let main_script = Script::new(
@@ -428,6 +1463,7 @@ fn test_call_script_auto_return_on_child_eof() {
                 content: "主线恢复".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "scripts/remake",
@@ -444,6 +1480,7 @@ fn test_call_script_auto_return_on_child_eof() {
                 content: "子结尾自动返回".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "scripts/remake/ring",
@@ -494,6 +1531,7 @@ fn test_call_script_label_is_display_only() {
                 content: "从文件开头执行".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Label {
                 name: "entry".to_string(),
@@ -503,6 +1541,7 @@ fn test_call_script_label_is_display_only() {
                 content: "旧语义会先到这里".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "scripts/remake/ring",
@@ -541,6 +1580,7 @@ fn test_runtime_with_extend() {
                 content: "Hello".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Extend {
                 content: " world".to_string(),
@@ -611,6 +1651,7 @@ fn test_runtime_is_finished() {
             content: "only line".to_string(),
             inline_effects: vec![],
             no_wait: false,
+            text_speed: 1.0,
         }],
         "",
     );
@@ -638,12 +1679,14 @@ fn test_runtime_history_recording() {
                 content: "你好".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Dialogue {
                 speaker: None,
                 content: "旁白".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "",
@@ -671,18 +1714,21 @@ fn test_runtime_state_restore() {
                 content: "1".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Dialogue {
                 speaker: None,
                 content: "2".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Dialogue {
                 speaker: None,
                 content: "3".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "",
@@ -702,13 +1748,71 @@ fn test_runtime_state_restore() {
     assert_eq!(runtime.state().position.node_index, 3);
 
     // 恢复状态
-    runtime.restore_state(saved_state);
+    runtime.restore_state(saved_state).unwrap();
     runtime.restore_history(saved_history);
 
     assert_eq!(runtime.state().position.node_index, 2);
     assert_eq!(runtime.history().dialogue_count(), 2);
 }
 
+#[test]
+fn test_restore_state_rejects_out_of_range_position() {
+    let mut runtime = VNRuntime::new(parse_script("：\"一。\"\n\n：\"二。\"\n"));
+    runtime.tick(None).unwrap();
+    let before = runtime.state().clone();
+
+    let mut saved = before.clone();
+    saved.position.node_index = 99;
+    let err = runtime.restore_state(saved).unwrap_err();
+
+    assert!(
+        matches!(err, RuntimeError::IncompatibleSave { .. }),
+        "{err}"
+    );
+    assert_eq!(err.code(), "E0109");
+    // 校验失败时不修改当前状态
+    assert_eq!(runtime.state(), &before);
+}
+
+#[test]
+fn test_restore_falls_back_to_nearest_label_when_saved_label_missing() {
+    let v1 = "**start**\n\n：\"开场。\"\n\n**middle**\n\n：\"中段一。\"\n\n：\"中段二。\"\n";
+    let mut runtime = VNRuntime::new(parse_script(v1));
+    runtime.tick(None).unwrap();
+    runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    let saved = runtime.state().clone();
+    assert_eq!(saved.position.label.as_deref(), Some("middle"));
+
+    // 原脚本恢复：精确
+    let mut same = VNRuntime::new(parse_script(v1));
+    assert_eq!(
+        same.restore_state_with_fallback(saved.clone()),
+        RestoreOutcome::Exact
+    );
+
+    // 新版脚本删掉了 middle 标签
+    let v2 = "**start**\n\n：\"开场。\"\n\n：\"中段一。\"\n\n：\"中段二。\"\n";
+    let mut runtime = VNRuntime::new(parse_script(v2));
+    assert!(matches!(
+        runtime.restore_state(saved.clone()),
+        Err(RuntimeError::IncompatibleSave { .. })
+    ));
+
+    let outcome = runtime.restore_state_with_fallback(saved);
+    assert!(matches!(
+        outcome,
+        RestoreOutcome::FellBack { label: Some(ref l), .. } if l == "start"
+    ));
+    assert_eq!(runtime.state().position.node_index, 0);
+    assert!(!runtime.state().waiting.is_waiting());
+
+    let (commands, _) = runtime.tick(None).unwrap();
+    assert!(matches!(
+        &commands[0],
+        Command::ShowText { content, .. } if content == "开场。"
+    ));
+}
+
 #[test]
 fn test_record_history_for_extend_text() {
     let script = Script::new(
@@ -719,6 +1823,7 @@ fn test_record_history_for_extend_text() {
                 content: "First".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Extend {
                 content: " continued".to_string(),
@@ -786,3 +1891,388 @@ fn test_ui_result_ignores_mismatched_key() {
     assert!(waiting.is_waiting());
     assert_eq!(runtime.state().get_var("destination"), None);
 }
+
+#[test]
+fn test_ending_node_records_current_and_unlocked() {
+    let script = parse_script("：\"最后一句\"\nending true_end \"真结局\"\n");
+    let mut runtime = VNRuntime::new(script);
+
+    runtime.tick(None).unwrap();
+    assert_eq!(runtime.current_ending(), None);
+    runtime.tick(Some(RuntimeInput::Click)).unwrap();
+
+    assert!(runtime.is_finished());
+    assert_eq!(runtime.current_ending(), Some("true_end"));
+    assert_eq!(runtime.unlocked_endings().title("true_end"), Some("真结局"));
+}
+
+#[test]
+fn test_endings_accumulate_across_playthroughs_and_export() {
+    // 第一周目：普通结局
+    let mut first = VNRuntime::new(parse_script("ending normal_end \"普通结局\"\n"));
+    first.tick(None).unwrap();
+    let saved = first.export_endings();
+
+    // 第二周目：导入上一周目记录后到达真结局
+    let mut second = VNRuntime::new(parse_script("ending true_end \"真结局\"\n"));
+    second.import_endings(&saved);
+    assert_eq!(second.current_ending(), None);
+    second.tick(None).unwrap();
+    assert_eq!(second.current_ending(), Some("true_end"));
+
+    let exported = second.export_endings();
+    let ids: Vec<_> = exported.iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec!["normal_end", "true_end"]);
+
+    // 导出结果可序列化持久化
+    let json = serde_json::to_string(&exported).unwrap();
+    let restored: EndingCollection = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, exported);
+}
+
+#[test]
+fn test_runtime_error_carries_source_line() {
+    let script = parse_script("：\"开始\"\n\n**start**\ngoto **nowhere**\n");
+    let mut runtime = VNRuntime::new(script);
+
+    runtime.tick(None).unwrap();
+    let err = runtime.tick(Some(RuntimeInput::Click)).unwrap_err();
+
+    assert_eq!(err.line(), Some(4));
+    assert!(matches!(err.kind(), RuntimeError::LabelNotFound { label } if label == "nowhere"));
+    assert!(err.to_string().contains("第 4 行"));
+
+    // 无源码映射的脚本不附加位置
+    let err = RuntimeError::InvalidState {
+        message: "x".to_string(),
+    }
+    .at_line("test", None);
+    assert_eq!(err.line(), None);
+}
+
+#[test]
+fn test_iter_vars_returns_all_sorted_by_name() {
+    let script = parse_script("：\"开始。\"\n");
+    let mut runtime = VNRuntime::new(script);
+    for (name, value) in [
+        ("zeta", VarValue::Int(3)),
+        ("alpha", VarValue::Bool(true)),
+        ("mid", VarValue::String("中".to_string())),
+    ] {
+        runtime.state_mut().set_var(name, value);
+    }
+
+    let vars: Vec<_> = runtime.iter_vars().collect();
+    assert_eq!(
+        vars,
+        vec![
+            ("alpha", &VarValue::Bool(true)),
+            ("mid", &VarValue::String("中".to_string())),
+            ("zeta", &VarValue::Int(3)),
+        ]
+    );
+}
+
+#[test]
+fn test_line_breakpoint_pauses_before_node_until_resume() {
+    let script = parse_script("set $a = 1\nset $b = 2\n：\"之后\"\n");
+    let mut runtime = VNRuntime::new(script);
+    runtime.add_breakpoint(Breakpoint::Line(2));
+
+    let (commands, waiting) = runtime.tick(None).unwrap();
+    assert!(commands.is_empty());
+    assert_eq!(waiting, WaitingReason::Breakpoint(Breakpoint::Line(2)));
+    // 暂停在第 2 行之前：第 1 行已执行，第 2 行尚未执行
+    assert_eq!(runtime.state().get_var("a"), Some(&VarValue::Int(1)));
+    assert_eq!(runtime.state().get_var("b"), None);
+
+    // 普通输入不会解除断点
+    let (commands, waiting) = runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert!(commands.is_empty());
+    assert!(matches!(waiting, WaitingReason::Breakpoint(_)));
+
+    let (commands, waiting) = runtime.resume().unwrap();
+    assert_eq!(runtime.state().get_var("b"), Some(&VarValue::Int(2)));
+    assert!(matches!(&commands[0], Command::ShowText { content, .. } if content == "之后"));
+    assert_eq!(waiting, WaitingReason::WaitForClick);
+}
+
+#[test]
+fn test_label_breakpoint_hits_on_goto_each_time() {
+    let script = parse_script("**loop**\n：\"循环\"\ngoto **loop**\n");
+    let mut runtime = VNRuntime::new(script);
+    let bp = Breakpoint::Label("loop".to_string());
+    runtime.add_breakpoint(bp.clone());
+
+    let (_, waiting) = runtime.tick(None).unwrap();
+    assert_eq!(waiting, WaitingReason::Breakpoint(bp.clone()));
+
+    let (_, waiting) = runtime.resume().unwrap();
+    assert_eq!(waiting, WaitingReason::WaitForClick);
+
+    // goto 回到标签时再次命中
+    let (_, waiting) = runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert_eq!(waiting, WaitingReason::Breakpoint(bp.clone()));
+
+    assert!(runtime.remove_breakpoint(&bp));
+    let (_, waiting) = runtime.resume().unwrap();
+    assert_eq!(waiting, WaitingReason::WaitForClick);
+    let (_, waiting) = runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert_eq!(waiting, WaitingReason::WaitForClick);
+}
+
+#[test]
+fn test_goto_variable_target_resolves_at_runtime() {
+    let script = parse_script(
+        r#"
+set $next_scene = "night"
+goto $next_scene
+旁白："白天"
+**night**
+旁白："夜晚"
+"#,
+    );
+    let mut runtime = VNRuntime::new(script);
+    let (commands, _) = runtime.tick(None).unwrap();
+
+    let texts: Vec<&str> = commands
+        .iter()
+        .filter_map(|c| match c {
+            Command::ShowText { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, ["夜晚"]);
+}
+
+#[test]
+fn test_goto_variable_with_invalid_label_errors() {
+    let script = parse_script("set $next = \"nowhere\"\ngoto $next\n");
+    let err = VNRuntime::new(script).tick(None).unwrap_err();
+    assert_eq!(err.line(), Some(2));
+    assert!(matches!(err.kind(), RuntimeError::LabelNotFound { label } if label == "nowhere"));
+
+    // 非字符串值不能作为标签名
+    let script = parse_script("**3**\nset $next = 3\ngoto $next\n");
+    let err = VNRuntime::new(script).tick(None).unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        RuntimeError::EvalError(crate::script::EvalError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_set_with_plus_concatenates_or_adds() {
+    let commands = run_with_inputs(
+        r#"
+set $name = "北风"
+set $day = 2
+set $day = $day + 1
+set $msg = "你好，" + $name + "，第" + $day + "天"
+if $msg == "你好，北风，第" + 3 + "天"
+  ：“{$msg}”
+endif
+"#,
+        &[],
+    );
+    let shown = commands
+        .iter()
+        .find_map(|c| match c {
+            Command::ShowText { content, .. } => Some(content.as_str()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(shown, "你好，北风，第3天");
+}
+
+#[test]
+fn test_sfx_pool_picks_reproducibly_from_seed() {
+    let pools = std::collections::BTreeMap::from([(
+        "footsteps".to_string(),
+        vec![
+            "sfx/step1.ogg".to_string(),
+            "sfx/step2.ogg".to_string(),
+            "sfx/step3.ogg".to_string(),
+        ],
+    )]);
+    let played = |seed: u64| {
+        let mut runtime = VNRuntime::new(parse_script(&"playSfx @footsteps\n".repeat(20)));
+        runtime.set_sfx_pools(pools.clone());
+        runtime.set_rng_seed(seed);
+        let (commands, _) = runtime.tick(None).unwrap();
+        commands
+            .into_iter()
+            .filter_map(|c| match c {
+                Command::PlaySfx { path } => Some(path),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let first = played(42);
+    assert_eq!(first.len(), 20);
+    assert!(first.iter().all(|path| pools["footsteps"].contains(path)));
+    // 同一种子结果可复现；不会连续抽中同一个
+    assert_eq!(played(42), first);
+    assert!(first.windows(2).all(|pair| pair[0] != pair[1]));
+    // 换种子得到不同序列，且多次抽取覆盖了多个候选
+    assert_ne!(played(7), first);
+    assert!(pools["footsteps"].iter().all(|path| first.contains(path)));
+
+    // 未定义的池报错
+    let mut runtime = VNRuntime::new(parse_script("playSfx @door\n"));
+    runtime.set_sfx_pools(pools.clone());
+    let err = runtime.tick(None).unwrap_err();
+    assert!(matches!(err.kind(), RuntimeError::InvalidState { .. }));
+}
+
+#[test]
+fn test_dialogue_interpolates_formatted_variables() {
+    let commands = run_with_inputs(
+        r#"
+set $gold = 1234567
+set $n = 3
+
+：“第{$n:02}天，金币 {$gold:,}{wait}（原值 {$gold}）。”
+"#,
+        &[],
+    );
+    let shown = commands
+        .iter()
+        .find_map(|c| match c {
+            Command::ShowText {
+                content,
+                inline_effects,
+                ..
+            } => Some((content.as_str(), inline_effects)),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(shown.0, "第03天，金币 1,234,567（原值 1234567）。");
+    // {wait} 紧跟千分位数字之后
+    assert_eq!(
+        shown.1[0].position,
+        "第03天，金币 1,234,567".chars().count()
+    );
+}
+
+#[test]
+fn test_text_key_uses_current_locale_and_falls_back_to_default() {
+    let script = parse_script(
+        r#"
+北风："@greeting_01"
+北风："@farewell"
+"#,
+    );
+    let mut tables = crate::i18n::LocaleTables::new("zh");
+    tables
+        .load_json(
+            "zh",
+            r#"{"greeting_01": "早上好", "farewell": "再见{wait}，{$name}"}"#,
+        )
+        .unwrap();
+    tables
+        .load_json("en", r#"{"greeting_01": "Good morning"}"#)
+        .unwrap();
+    let mut runtime = VNRuntime::new(script);
+    runtime.set_locale_tables(tables);
+    runtime.set_locale("en");
+    runtime
+        .state_mut()
+        .set_var("name", VarValue::String("红叶".to_string()));
+
+    let shown = |commands: &[Command]| {
+        commands
+            .iter()
+            .find_map(|c| match c {
+                Command::ShowText {
+                    content,
+                    inline_effects,
+                    ..
+                } => Some((content.clone(), inline_effects.len())),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    let (commands, _) = runtime.tick(None).unwrap();
+    assert_eq!(shown(&commands), ("Good morning".to_string(), 0));
+    assert!(runtime.take_text_warnings().is_empty());
+
+    // en 缺 key：回退到 zh，查到的文本同样解析内联标签与插值
+    let (commands, _) = runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert_eq!(shown(&commands), ("再见，红叶".to_string(), 1));
+    let warnings = runtime.take_text_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].key, "farewell");
+    assert_eq!(warnings[0].locale, "en");
+    assert!(warnings[0].fell_back);
+}
+
+#[test]
+fn test_autosave_point_after_chapter_mark_and_choice_but_not_dialogue() {
+    let mut runtime = VNRuntime::new(parse_script(
+        r#"
+："开场白"
+
+# 第一章
+
+："章节正文"
+
+| 选项 | 跳转 |
+| --- | --- |
+| 去 | go |
+
+**go**
+："选择之后"
+"#,
+    ));
+
+    // 普通对话不产出信号
+    runtime.tick(None).unwrap();
+    assert_eq!(runtime.take_autosave_point(), None);
+
+    // 经过章节标记后产出信号，取走后清空
+    runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert_eq!(
+        runtime.take_autosave_point(),
+        Some(AutosaveTrigger::ChapterMark)
+    );
+    assert_eq!(runtime.take_autosave_point(), None);
+
+    let (_, waiting) = runtime.tick(Some(RuntimeInput::Click)).unwrap();
+    assert!(matches!(waiting, WaitingReason::WaitForChoice { .. }));
+    assert_eq!(runtime.take_autosave_point(), None);
+
+    runtime.tick(Some(RuntimeInput::choice(0))).unwrap();
+    assert_eq!(
+        runtime.take_autosave_point(),
+        Some(AutosaveTrigger::ChoiceMade)
+    );
+}
+
+#[test]
+fn test_metrics_count_executed_nodes() {
+    let mut runtime = VNRuntime::new(parse_script(
+        "set $a = 1\nset $b = 2\nset $c = 3\nset $d = 4\nset $e = 5\n",
+    ));
+    runtime.tick(None).unwrap();
+    assert!(runtime.metrics().is_none());
+
+    let mut runtime = VNRuntime::new(parse_script(
+        "set $a = 1\nset $b = 2\nset $c = 3\nset $d = 4\nset $e = 5\n",
+    ));
+    runtime.enable_metrics();
+    runtime.tick(None).unwrap();
+    runtime.record_tick_time(120);
+    runtime.record_tick_time(80);
+
+    let metrics = runtime.metrics().unwrap();
+    assert_eq!(metrics.ticks, 1);
+    assert_eq!(metrics.nodes_executed, 5);
+    assert_eq!(metrics.last_tick_nodes, 5);
+    assert_eq!(metrics.max_tick_nodes, 5);
+    assert_eq!(metrics.commands_emitted, 0);
+    assert_eq!(metrics.tick_time_micros, 200);
+    assert_eq!(metrics.max_tick_time_micros, 120);
+}
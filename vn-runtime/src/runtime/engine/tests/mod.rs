@@ -14,12 +14,14 @@ fn create_test_script() -> Script {
                 content: "Hello".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Dialogue {
                 speaker: None,
                 content: "World".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
         ],
         "",
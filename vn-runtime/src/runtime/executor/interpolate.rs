@@ -0,0 +1,234 @@
+//! # 文本变量插值
+//!
+//! 对话文本中的 `{$name}` 在执行时替换为变量当前值，可带格式说明符：
+//!
+//! - `{$gold:,}`：整数千分位（`1234567` → `1,234,567`）
+//! - `{$n:02}`：整数补零到指定宽度（`7` → `07`）
+//!
+//! 说明符只作用于 `VarValue::Int`，其他类型按原值显示。
+//! 变量未定义或说明符无法识别时占位符原样保留，便于在画面上发现拼写错误。
+//!
+//! 内联标签（`{wait}` 等）在解析期已剥离，效果位置指向剥离后的纯文本；
+//! 插值改变文本长度后，这里同步平移位于占位符之后的效果位置。
+
+use crate::command::InlineEffect;
+use crate::script::EvalContext;
+use crate::state::VarValue;
+
+/// 占位符在纯文本中的替换记录（字符索引）
+struct Replacement {
+    /// 占位符起点
+    start: usize,
+    /// 占位符终点（不含）
+    end: usize,
+    /// 替换后文本的字符数
+    len: usize,
+}
+
+/// 替换文本中的变量占位符，并平移内联效果位置
+///
+/// 文本中不含占位符时直接返回原文本与原效果列表。
+pub fn interpolate(
+    text: &str,
+    effects: &[InlineEffect],
+    ctx: &impl EvalContext,
+) -> (String, Vec<InlineEffect>) {
+    if !text.contains("{$") {
+        return (text.to_string(), effects.to_vec());
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut replacements = Vec::new();
+    let mut rest = text;
+    let mut char_pos = 0;
+
+    while let Some(open) = rest.find("{$") {
+        output.push_str(&rest[..open]);
+        char_pos += rest[..open].chars().count();
+        let tail = &rest[open..];
+
+        let Some(close) = tail.find('}') else {
+            break;
+        };
+        let placeholder = &tail[..=close];
+        let placeholder_len = placeholder.chars().count();
+        match render_placeholder(&tail[2..close], ctx) {
+            Some(rendered) => {
+                replacements.push(Replacement {
+                    start: char_pos,
+                    end: char_pos + placeholder_len,
+                    len: rendered.chars().count(),
+                });
+                output.push_str(&rendered);
+            }
+            None => output.push_str(placeholder),
+        }
+        char_pos += placeholder_len;
+        rest = &tail[close + 1..];
+    }
+    output.push_str(rest);
+
+    let effects = effects
+        .iter()
+        .map(|effect| InlineEffect {
+            position: shift_position(effect.position, &replacements),
+            kind: effect.kind.clone(),
+        })
+        .collect();
+    (output, effects)
+}
+
+/// 将原文本中的字符位置映射到替换后的文本
+fn shift_position(position: usize, replacements: &[Replacement]) -> usize {
+    let mut shifted = position;
+    for r in replacements {
+        if position >= r.end {
+            shifted = shifted + r.len - (r.end - r.start);
+        } else if position > r.start {
+            // 落在占位符内部：对齐到替换文本末尾
+            shifted = shifted - (position - r.start) + r.len;
+        }
+    }
+    shifted
+}
+
+/// 渲染 `name` 或 `name:spec`；无法渲染时返回 None
+fn render_placeholder(inner: &str, ctx: &impl EvalContext) -> Option<String> {
+    let (name, spec) = match inner.split_once(':') {
+        Some((name, spec)) => (name.trim(), Some(spec.trim())),
+        None => (inner.trim(), None),
+    };
+    if name.is_empty() {
+        return None;
+    }
+    let value = ctx.get_var(name)?;
+    match spec {
+        None => Some(display_value(value)),
+        Some(spec) => format_value(value, spec),
+    }
+}
+
+fn display_value(value: &VarValue) -> String {
+    match value {
+        VarValue::Int(n) => n.to_string(),
+        VarValue::Float(f) => f.to_string(),
+        VarValue::String(s) => s.clone(),
+        VarValue::Bool(b) => b.to_string(),
+    }
+}
+
+/// 按说明符格式化；说明符无法识别时返回 None
+fn format_value(value: &VarValue, spec: &str) -> Option<String> {
+    let width = if spec == "," {
+        None
+    } else {
+        let digits = spec.strip_prefix('0')?;
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        Some(digits.parse::<usize>().ok()?)
+    };
+
+    let VarValue::Int(n) = value else {
+        return Some(display_value(value));
+    };
+    Some(match width {
+        None => group_thousands(*n),
+        Some(width) => format!("{n:0width$}"),
+    })
+}
+
+/// 整数千分位分组
+fn group_thousands(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if n < 0 {
+        grouped.push('-');
+    }
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::InlineEffectKind;
+    use crate::state::RuntimeState;
+
+    fn state_with(vars: &[(&str, VarValue)]) -> RuntimeState {
+        let mut state = RuntimeState::new("test");
+        for (name, value) in vars {
+            state.set_var(*name, value.clone());
+        }
+        state
+    }
+
+    fn render(text: &str, state: &RuntimeState) -> String {
+        interpolate(text, &[], state).0
+    }
+
+    #[test]
+    fn thousands_separator() {
+        let state = state_with(&[
+            ("gold", VarValue::Int(1234567)),
+            ("debt", VarValue::Int(-1000)),
+            ("small", VarValue::Int(999)),
+        ]);
+        assert_eq!(render("金币：{$gold:,}", &state), "金币：1,234,567");
+        assert_eq!(render("{$debt:,}", &state), "-1,000");
+        assert_eq!(render("{$small:,}", &state), "999");
+    }
+
+    #[test]
+    fn zero_padding() {
+        let state = state_with(&[("n", VarValue::Int(7)), ("day", VarValue::Int(123))]);
+        assert_eq!(render("第{$n:02}话", &state), "第07话");
+        assert_eq!(render("{$n:03}", &state), "007");
+        // 位数超过宽度时不截断
+        assert_eq!(render("{$day:02}", &state), "123");
+    }
+
+    #[test]
+    fn no_specifier_renders_value_as_is() {
+        let state = state_with(&[
+            ("gold", VarValue::Int(1234567)),
+            ("name", VarValue::String("小明".to_string())),
+        ]);
+        assert_eq!(render("{$name}有{$gold}金币", &state), "小明有1234567金币");
+        // 非整数忽略说明符
+        assert_eq!(render("{$name:,}", &state), "小明");
+    }
+
+    #[test]
+    fn unresolved_placeholders_are_kept() {
+        let state = state_with(&[("n", VarValue::Int(7))]);
+        assert_eq!(render("{$missing}", &state), "{$missing}");
+        assert_eq!(render("{$n:x}", &state), "{$n:x}");
+        assert_eq!(render("{$n", &state), "{$n");
+    }
+
+    #[test]
+    fn effects_after_placeholder_are_shifted() {
+        let state = state_with(&[("gold", VarValue::Int(1234))]);
+        // "有{$gold:,}枚" → "有1,234枚"：占位符 9 字符 → 5 字符
+        let effects = vec![
+            InlineEffect {
+                position: 1,
+                kind: InlineEffectKind::Wait(None),
+            },
+            InlineEffect {
+                position: 11,
+                kind: InlineEffectKind::ResetCps,
+            },
+        ];
+        let (text, shifted) = interpolate("有{$gold:,}枚", &effects, &state);
+        assert_eq!(text, "有1,234枚");
+        assert_eq!(shifted[0].position, 1);
+        assert_eq!(shifted[1].position, 7);
+    }
+}
@@ -7,12 +7,25 @@
 //! - 读取 ScriptNode
 //! - 产生对应的 Command
 //! - 决定是否需要等待
-
-use crate::command::{Choice, Command, SIGNAL_CUTSCENE, SIGNAL_SCENE_EFFECT, SIGNAL_TITLE_CARD};
+//! - 对话文本变量插值（见 [`interpolate`]）
+//! - `@key` 对话内容按当前语言查找文本（见 [`crate::i18n`]）
+//! - 音效池按状态中的随机数种子抽取候选（见 [`RuntimeState::next_random`]）
+
+use crate::command::{
+    BackgroundLayer, Choice, Command, InlineEffect, Position, SIGNAL_CUTSCENE, SIGNAL_SCENE_EFFECT,
+    SIGNAL_TITLE_CARD, TimedCommand,
+};
 use crate::error::RuntimeError;
+use crate::i18n::{LocaleTables, text_key};
 use crate::input::SignalId;
-use crate::script::{Script, ScriptNode, evaluate, evaluate_to_bool};
-use crate::state::{RuntimeState, WaitingReason};
+use crate::script::parser::inline_tags::parse_inline_tags;
+use crate::script::{EvalError, Script, ScriptNode, evaluate, evaluate_to_bool};
+use crate::state::{RuntimeState, VarValue, WaitingReason};
+use std::collections::BTreeMap;
+
+mod interpolate;
+
+use interpolate::interpolate;
 
 /// 脚本控制流动作（不经过 Host 命令层）
 #[derive(Debug, Clone, PartialEq)]
@@ -93,14 +106,62 @@ impl ExecuteResult {
 ///
 /// 负责将单个 ScriptNode 转换为 Command。
 pub struct Executor {
-    // 未来可添加执行上下文
+    /// 多语言文本表
+    locale: LocaleTables,
+    /// 是否检查 `assert`（默认随 debug 构建开启）
+    assertions: bool,
+    /// 音效池：池名 → 候选音效路径
+    sfx_pools: BTreeMap<String, Vec<String>>,
 }
 
 #[allow(clippy::new_without_default)]
 impl Executor {
     /// 创建新的执行器
     pub fn new() -> Self {
-        Self {}
+        Self {
+            locale: LocaleTables::default(),
+            assertions: cfg!(debug_assertions),
+            sfx_pools: BTreeMap::new(),
+        }
+    }
+
+    /// 设置音效池
+    pub fn set_sfx_pools(&mut self, pools: BTreeMap<String, Vec<String>>) {
+        self.sfx_pools = pools;
+    }
+
+    /// 开启/关闭 `assert` 检查
+    pub fn set_assertions_enabled(&mut self, enabled: bool) {
+        self.assertions = enabled;
+    }
+
+    /// 多语言文本表
+    pub fn locale_tables(&self) -> &LocaleTables {
+        &self.locale
+    }
+
+    /// 多语言文本表（可变）
+    pub fn locale_tables_mut(&mut self) -> &mut LocaleTables {
+        &mut self.locale
+    }
+
+    /// 解析对话文本：`@key` 先按当前语言查表，再做变量插值
+    ///
+    /// 未加载语言表或查不到 key 时按原文处理。
+    fn resolve_text(
+        &mut self,
+        content: &str,
+        inline_effects: &[InlineEffect],
+        state: &RuntimeState,
+    ) -> (String, Vec<InlineEffect>) {
+        if !self.locale.is_empty()
+            && let Some(key) = text_key(content)
+            && let Some(text) = self.locale.lookup(key)
+        {
+            let (text, effects) = parse_inline_tags(&text);
+            return interpolate(&text, &effects, state);
+        }
+        interpolate(content, inline_effects, state)
     }
 
     /// 执行单个脚本节点
@@ -125,8 +186,14 @@ impl Executor {
                 }]))
             }
 
-            ScriptNode::Label { .. } => {
-                // 标签节点不产生 Command，只是跳转目标
+            ScriptNode::Label { name } => {
+                // 标签节点不产生 Command，只是跳转目标；记录下来供读档校验
+                state.position.label = Some(name.clone());
+                Ok(ExecuteResult::empty())
+            }
+
+            ScriptNode::Bookmark { .. } => {
+                // 书签仅供调试定位，不产生 Command
                 Ok(ExecuteResult::empty())
             }
 
@@ -135,28 +202,44 @@ impl Executor {
                 content,
                 inline_effects,
                 no_wait,
-            } => Ok(ExecuteResult::with_wait(
-                vec![Command::ShowText {
-                    speaker: speaker.clone(),
-                    content: content.clone(),
-                    inline_effects: inline_effects.clone(),
-                    no_wait: *no_wait,
-                }],
-                WaitingReason::WaitForClick,
-            )),
+                text_speed,
+            } => {
+                let (content, inline_effects) = self.resolve_text(content, inline_effects, state);
+                Ok(ExecuteResult::with_wait(
+                    vec![Command::ShowText {
+                        speaker: speaker.clone(),
+                        content,
+                        inline_effects,
+                        no_wait: *no_wait,
+                        text_speed: *text_speed,
+                    }],
+                    WaitingReason::WaitForClick,
+                ))
+            }
 
             ScriptNode::Extend {
                 content,
                 inline_effects,
                 no_wait,
-            } => Ok(ExecuteResult::with_wait(
-                vec![Command::ExtendText {
-                    content: content.clone(),
-                    inline_effects: inline_effects.clone(),
-                    no_wait: *no_wait,
-                }],
-                WaitingReason::WaitForClick,
-            )),
+            } => {
+                let (content, inline_effects) = self.resolve_text(content, inline_effects, state);
+                Ok(ExecuteResult::with_wait(
+                    vec![Command::ExtendText {
+                        content,
+                        inline_effects,
+                        no_wait: *no_wait,
+                    }],
+                    WaitingReason::WaitForClick,
+                ))
+            }
+
+            ScriptNode::ShowBubble { alias, content } => {
+                let (content, _) = interpolate(content, &[], state);
+                Ok(ExecuteResult::with_commands(vec![Command::ShowBubble {
+                    alias: alias.clone(),
+                    content,
+                }]))
+            }
 
             ScriptNode::ChangeBG { path, transition } => {
                 // 解析路径（相对于脚本目录）
@@ -172,6 +255,45 @@ impl Executor {
                 ]))
             }
 
+            ScriptNode::SetBackgroundLayers { layers } => {
+                let layers: Vec<BackgroundLayer> = layers
+                    .iter()
+                    .map(|layer| BackgroundLayer {
+                        path: script.resolve_path(&layer.path),
+                        parallax: layer.parallax,
+                    })
+                    .collect();
+                // 最远的一层作为当前背景记录
+                state.current_background = layers.first().map(|layer| layer.path.clone());
+                Ok(ExecuteResult::with_commands(vec![
+                    Command::SetBackgroundLayers { layers },
+                ]))
+            }
+
+            ScriptNode::FillColor { color, transition } => {
+                state.current_background = None;
+                Ok(ExecuteResult::with_commands(vec![Command::FillColor {
+                    color: *color,
+                    transition: transition.clone(),
+                }]))
+            }
+
+            ScriptNode::ShowCg { path, transition } => {
+                let resolved_path = script.resolve_path(path);
+                state.seen_cgs.insert(resolved_path.clone());
+
+                Ok(ExecuteResult::with_commands(vec![Command::ShowCg {
+                    path: resolved_path,
+                    transition: transition.clone(),
+                }]))
+            }
+
+            ScriptNode::HideCg { transition } => {
+                Ok(ExecuteResult::with_commands(vec![Command::HideCg {
+                    transition: transition.clone(),
+                }]))
+            }
+
             ScriptNode::ChangeScene { path, transition } => {
                 // 解析路径（相对于脚本目录）
                 let resolved_path = script.resolve_path(path);
@@ -227,6 +349,7 @@ impl Executor {
                 alias,
                 position,
                 transition,
+                idle,
             } => {
                 // 如果 path 为 None，尝试从已绑定的别名中查找
                 let resolved_path = if let Some(p) = path {
@@ -270,9 +393,38 @@ impl Executor {
                     alias: alias.clone(),
                     position: *position,
                     transition: transition.clone(),
+                    idle: idle.clone(),
                 }]))
             }
 
+            ScriptNode::ShowCharacters {
+                aliases,
+                transition,
+            } => {
+                let mut commands = Vec::with_capacity(aliases.len());
+                for alias in aliases {
+                    let Some((path, _)) = state.visible_characters.get(alias).cloned() else {
+                        return Err(RuntimeError::InvalidState {
+                            message: format!(
+                                "别名 '{}' 尚未绑定立绘，无法自动布局。请先使用 'show <img src=\"...\"> as {} at ...' 绑定立绘。",
+                                alias, alias
+                            ),
+                        });
+                    };
+                    state
+                        .visible_characters
+                        .insert(alias.clone(), (path.clone(), Position::Auto));
+                    commands.push(Command::ShowCharacter {
+                        path,
+                        alias: alias.clone(),
+                        position: Position::Auto,
+                        transition: transition.clone(),
+                        idle: None,
+                    });
+                }
+                Ok(ExecuteResult::with_commands(commands))
+            }
+
             ScriptNode::HideCharacter { alias, transition } => {
                 // 更新状态
                 state.visible_characters.remove(alias);
@@ -283,21 +435,52 @@ impl Executor {
                 }]))
             }
 
-            ScriptNode::Choice { style, options } => {
-                let choices: Vec<Choice> = options
+            ScriptNode::SetRimLight { alias, intensity } => {
+                Ok(ExecuteResult::with_commands(vec![Command::SetRimLight {
+                    alias: alias.clone(),
+                    intensity: *intensity,
+                }]))
+            }
+
+            ScriptNode::Choice {
+                style,
+                options,
+                prompt,
+            } => {
+                let choices = options
                     .iter()
-                    .map(|opt| Choice {
-                        text: opt.text.clone(),
-                        target_label: opt.target_label.clone(),
+                    .map(|opt| {
+                        let enabled = match &opt.condition {
+                            Some(condition) => evaluate_to_bool(condition, state)?,
+                            None => true,
+                        };
+                        Ok(Choice {
+                            text: opt.text.clone(),
+                            target_label: opt.target_label.clone(),
+                            enabled,
+                            disabled_reason: if enabled {
+                                None
+                            } else {
+                                opt.disabled_reason.clone()
+                            },
+                            visited: state.has_chosen_branch(&opt.target_label),
+                        })
                     })
-                    .collect();
+                    .collect::<Result<Vec<Choice>, RuntimeError>>()?;
 
                 let choice_count = choices.len();
+                // 全部禁用时等待永远无法结束，直接报错而不是让玩家卡死
+                if !choices.iter().any(|choice| choice.enabled) {
+                    return Err(RuntimeError::NoEnabledChoice {
+                        count: choice_count,
+                    });
+                }
 
                 Ok(ExecuteResult::with_wait(
                     vec![Command::PresentChoices {
                         style: style.clone(),
                         choices,
+                        prompt: prompt.clone(),
                     }],
                     WaitingReason::WaitForChoice { choice_count },
                 ))
@@ -321,6 +504,20 @@ impl Executor {
                 }
             }
 
+            ScriptNode::PlaySfxPool { pool } => {
+                let candidates = self
+                    .sfx_pools
+                    .get(pool)
+                    .filter(|candidates| !candidates.is_empty())
+                    .ok_or_else(|| RuntimeError::InvalidState {
+                        message: format!("音效池 @{} 未定义或为空", pool),
+                    })?;
+                let index = pick_pool_index(state, pool, candidates.len());
+                Ok(ExecuteResult::with_commands(vec![Command::PlaySfx {
+                    path: candidates[index].clone(),
+                }]))
+            }
+
             ScriptNode::StopBgm => {
                 // 停止 BGM，默认使用淡出效果（1秒）
                 Ok(ExecuteResult::with_commands(vec![Command::StopBgm {
@@ -332,6 +529,13 @@ impl Executor {
 
             ScriptNode::BgmUnduck => Ok(ExecuteResult::with_commands(vec![Command::BgmUnduck])),
 
+            ScriptNode::SetBgmVolume { volume, duration } => {
+                Ok(ExecuteResult::with_commands(vec![Command::SetBgmVolume {
+                    volume: *volume,
+                    duration: *duration,
+                }]))
+            }
+
             ScriptNode::Goto { target_label } => {
                 // 查找标签位置
                 let target_index =
@@ -344,6 +548,42 @@ impl Executor {
                 Ok(ExecuteResult::with_jump(target_index))
             }
 
+            ScriptNode::DynamicGoto { target } => {
+                let label = match evaluate(target, state)? {
+                    VarValue::String(label) => label,
+                    other => {
+                        return Err(EvalError::TypeMismatch {
+                            expected: "字符串（标签名）",
+                            actual: format!("{:?}", other),
+                            context: "goto 变量目标".to_string(),
+                        }
+                        .into());
+                    }
+                };
+                let target_index = script
+                    .find_label(&label)
+                    .ok_or(RuntimeError::LabelNotFound { label })?;
+
+                Ok(ExecuteResult::with_jump(target_index))
+            }
+
+            ScriptNode::TimedGoto {
+                target_label,
+                duration,
+            } => {
+                // 先校验标签存在，到期后由引擎执行跳转
+                script
+                    .find_label(target_label)
+                    .ok_or_else(|| RuntimeError::LabelNotFound {
+                        label: target_label.clone(),
+                    })?;
+                state.pending_goto = Some(target_label.clone());
+                Ok(ExecuteResult::with_wait(
+                    vec![],
+                    WaitingReason::WaitForTime(std::time::Duration::from_secs_f64(*duration)),
+                ))
+            }
+
             ScriptNode::CallScript {
                 path,
                 display_label,
@@ -360,21 +600,64 @@ impl Executor {
 
             ScriptNode::SetVar { name, value } => {
                 let val = evaluate(value, state)?;
-                if let Some(bare) = name.strip_prefix("persistent.") {
-                    state.set_persistent_var(bare, val);
-                } else {
-                    state.set_var(name.clone(), val);
+                state.assign_var(name, val);
+                Ok(ExecuteResult::empty())
+            }
+
+            ScriptNode::DeclareLocal { name, value } => {
+                let val = value
+                    .as_ref()
+                    .map(|expr| evaluate(expr, state))
+                    .transpose()?;
+                if !state.declare_local(name.clone(), val) {
+                    return Err(RuntimeError::InvalidState {
+                        message: format!("local ${} 只能在 callScript 调用的脚本中使用", name),
+                    });
+                }
+                Ok(ExecuteResult::empty())
+            }
+
+            ScriptNode::Assert { condition, message } => {
+                if self.assertions && !evaluate_to_bool(condition, state)? {
+                    return Err(RuntimeError::AssertionFailed {
+                        message: message.clone().unwrap_or_else(|| "条件不成立".to_string()),
+                    });
                 }
                 Ok(ExecuteResult::empty())
             }
 
             ScriptNode::FullRestart => Ok(ExecuteResult::with_commands(vec![Command::FullRestart])),
 
+            ScriptNode::Ending { id, title } => {
+                // 结局只记录状态，不产生 Command
+                state.endings.unlock(id.clone(), title.clone());
+                state.current_ending = Some(id.clone());
+                Ok(ExecuteResult::empty())
+            }
+
             ScriptNode::Conditional { branches } => {
                 // 顺序求值每个分支条件，找到第一个为真的分支
                 self.execute_conditional(branches, state, script)
             }
 
+            ScriptNode::Timeline { steps } => {
+                // 按偏移（同一时刻按声明顺序）执行，运行时状态与 Host 最终画面一致
+                let mut ordered: Vec<_> = steps.iter().collect();
+                ordered.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+
+                let mut timed = Vec::new();
+                for step in ordered {
+                    let result = self.execute(&step.node, state, script)?;
+                    timed.extend(result.commands.into_iter().map(|command| TimedCommand {
+                        delay: step.offset,
+                        command,
+                    }));
+                }
+                Ok(ExecuteResult::with_commands(vec![Command::Timeline {
+                    steps: timed,
+                }]))
+            }
+
             ScriptNode::TextBoxHide => Ok(ExecuteResult::with_commands(vec![Command::TextBoxHide])),
 
             ScriptNode::TextBoxShow => Ok(ExecuteResult::with_commands(vec![Command::TextBoxShow])),
@@ -383,6 +666,10 @@ impl Executor {
                 Ok(ExecuteResult::with_commands(vec![Command::TextBoxClear]))
             }
 
+            ScriptNode::SetTextboxStyle { name } => Ok(ExecuteResult::with_commands(vec![
+                Command::SetTextboxStyle { name: name.clone() },
+            ])),
+
             ScriptNode::ClearCharacters => {
                 // 清除状态中的所有角色
                 state.visible_characters.clear();
@@ -423,6 +710,28 @@ impl Executor {
                 WaitingReason::WaitForSignal(SignalId::new(SIGNAL_TITLE_CARD)),
             )),
 
+            ScriptNode::Rumble {
+                intensity,
+                duration,
+            } => Ok(ExecuteResult::with_commands(vec![Command::Rumble {
+                intensity: *intensity,
+                duration: *duration,
+            }])),
+
+            ScriptNode::SetFilter { kind, intensity } => {
+                Ok(ExecuteResult::with_commands(vec![Command::SetFilter {
+                    kind: *kind,
+                    intensity: *intensity,
+                }]))
+            }
+
+            ScriptNode::SetWeather { kind, intensity } => {
+                Ok(ExecuteResult::with_commands(vec![Command::SetWeather {
+                    kind: *kind,
+                    intensity: *intensity,
+                }]))
+            }
+
             ScriptNode::Cutscene { path } => {
                 let resolved = script.resolve_path(path);
                 Ok(ExecuteResult::with_wait(
@@ -442,7 +751,7 @@ impl Executor {
                 result_var,
                 params,
             } => {
-                let mut evaluated_params = std::collections::HashMap::new();
+                let mut evaluated_params = std::collections::BTreeMap::new();
                 for (key, expr) in params {
                     let value = evaluate(expr, state)?;
                     evaluated_params.insert(key.clone(), value);
@@ -536,5 +845,23 @@ impl Executor {
     }
 }
 
+/// 从音效池 `len` 个候选中抽取下标；候选多于一个时不与该池上一次相同
+fn pick_pool_index(state: &mut RuntimeState, pool: &str, len: usize) -> usize {
+    let last = state
+        .sfx_pool_last
+        .get(pool)
+        .copied()
+        .filter(|&last| len > 1 && last < len);
+    let range = if last.is_some() { len - 1 } else { len };
+    let mut index = (state.next_random() % range as u64) as usize;
+    if let Some(last) = last
+        && index >= last
+    {
+        index += 1;
+    }
+    state.sfx_pool_last.insert(pool.to_string(), index);
+    index
+}
+
 #[cfg(test)]
 mod tests;
@@ -14,6 +14,7 @@ fn test_execute_show_character_without_path_uses_existing_binding() {
         alias: "alice".to_string(),
         position: Position::Right,
         transition: None,
+        idle: None,
     };
 
     let result = executor.execute(&node, &mut state, &script).unwrap();
@@ -38,12 +39,65 @@ fn test_execute_show_character_without_path_errors_when_not_bound() {
         alias: "alice".to_string(),
         position: Position::Center,
         transition: None,
+        idle: None,
     };
 
     let result = executor.execute(&node, &mut state, &script);
     assert!(matches!(result, Err(RuntimeError::InvalidState { .. })));
 }
 
+#[test]
+fn test_execute_show_cg_resolves_path_and_records_seen() {
+    let (mut executor, mut state, script) = test_ctx("scripts");
+
+    let node = ScriptNode::ShowCg {
+        path: "../cg/cg1.jpg".to_string(),
+        transition: Some(Transition::simple("fade")),
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+
+    assert!(matches!(
+        &result.commands[0],
+        Command::ShowCg { path, transition: Some(t) }
+        if path == "scripts/../cg/cg1.jpg" && t.name == "fade"
+    ));
+    assert!(state.seen_cgs.contains("scripts/../cg/cg1.jpg"));
+    // CG 不改变背景
+    assert_eq!(state.current_background, None);
+
+    let result = executor
+        .execute(
+            &ScriptNode::HideCg { transition: None },
+            &mut state,
+            &script,
+        )
+        .unwrap();
+    assert_eq!(result.commands, vec![Command::HideCg { transition: None }]);
+    assert_eq!(state.seen_cgs.len(), 1);
+}
+
+#[test]
+fn test_execute_fill_color_replaces_background() {
+    let (mut executor, mut state, script) = test_ctx("scripts");
+    state.current_background = Some("bg/room.png".to_string());
+
+    let node = ScriptNode::FillColor {
+        color: Color::BLACK,
+        transition: Some(Transition::simple("fade")),
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+
+    assert_eq!(
+        result.commands,
+        vec![Command::FillColor {
+            color: Color::BLACK,
+            transition: Some(Transition::simple("fade")),
+        }]
+    );
+    assert!(result.waiting.is_none());
+    assert_eq!(state.current_background, None);
+}
+
 #[test]
 fn test_execute_change_scene_resolves_mask_path() {
     let (mut executor, mut state, script) = test_ctx("scripts");
@@ -102,6 +156,7 @@ fn test_execute_change_scene_resolves_mask_path() {
             content: "x".to_string(),
             inline_effects: vec![],
             no_wait: false,
+            text_speed: 1.0,
         }),
         None
     );
@@ -131,6 +186,7 @@ fn test_execute_conditional_with_multiple_body_nodes() {
                     content: "对话".to_string(),
                     inline_effects: vec![],
                     no_wait: false,
+                    text_speed: 1.0,
                 },
             ],
         }],
@@ -169,6 +225,7 @@ fn test_execute_conditional_with_goto() {
                 content: "开始".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Label {
                 name: "end".to_string(),
@@ -190,6 +247,7 @@ fn test_execute_conditional_with_goto() {
                     content: "不会执行".to_string(),
                     inline_effects: vec![],
                     no_wait: false,
+                    text_speed: 1.0,
                 },
             ],
         }],
@@ -307,6 +365,60 @@ fn test_execute_title_card_waits_for_signal() {
     ));
 }
 
+#[test]
+fn test_execute_rumble_emits_command_without_wait() {
+    let (mut executor, mut state, script) = test_ctx("");
+    let node = ScriptNode::Rumble {
+        intensity: 0.8,
+        duration: 0.5,
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert_eq!(
+        result.commands,
+        vec![Command::Rumble {
+            intensity: 0.8,
+            duration: 0.5,
+        }]
+    );
+    assert!(result.waiting.is_none());
+}
+
+#[test]
+fn test_execute_set_weather_emits_command_without_wait() {
+    let (mut executor, mut state, script) = test_ctx("");
+    let node = ScriptNode::SetWeather {
+        kind: crate::command::WeatherKind::Snow,
+        intensity: 0.7,
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert_eq!(
+        result.commands,
+        vec![Command::SetWeather {
+            kind: crate::command::WeatherKind::Snow,
+            intensity: 0.7,
+        }]
+    );
+    assert!(result.waiting.is_none());
+}
+
+#[test]
+fn test_execute_set_filter_emits_command_without_wait() {
+    let (mut executor, mut state, script) = test_ctx("");
+    let node = ScriptNode::SetFilter {
+        kind: crate::command::FilterKind::Grayscale,
+        intensity: 0.5,
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert_eq!(
+        result.commands,
+        vec![Command::SetFilter {
+            kind: crate::command::FilterKind::Grayscale,
+            intensity: 0.5,
+        }]
+    );
+    assert!(result.waiting.is_none());
+}
+
 #[test]
 fn test_execute_cutscene_waits_for_signal() {
     let (mut executor, mut state, script) = test_ctx("");
@@ -384,6 +496,25 @@ fn test_execute_full_restart_emits_command() {
     assert!(result.script_control.is_none());
 }
 
+#[test]
+fn test_execute_dialogue_carries_text_speed() {
+    let (mut executor, mut state, script) = test_ctx("");
+
+    let node = ScriptNode::Dialogue {
+        speaker: None,
+        content: "慢".to_string(),
+        inline_effects: vec![],
+        no_wait: false,
+        text_speed: 0.5,
+    };
+
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert!(matches!(
+        &result.commands[0],
+        Command::ShowText { text_speed, .. } if *text_speed == 0.5
+    ));
+}
+
 #[test]
 fn test_execute_dialogue() {
     let (mut executor, mut state, script) = test_ctx("");
@@ -393,6 +524,7 @@ fn test_execute_dialogue() {
         content: "Hello".to_string(),
         inline_effects: vec![],
         no_wait: false,
+        text_speed: 1.0,
     };
 
     let result = executor.execute(&node, &mut state, &script).unwrap();
@@ -415,6 +547,7 @@ fn test_execute_show_character() {
         alias: "test_char".to_string(),
         position: Position::Center,
         transition: None,
+        idle: None,
     };
 
     let result = executor.execute(&node, &mut state, &script).unwrap();
@@ -426,6 +559,25 @@ fn test_execute_show_character() {
     assert!(state.visible_characters.contains_key("test_char"));
 }
 
+#[test]
+fn test_execute_show_character_forwards_idle() {
+    let (mut executor, mut state, script) = test_ctx("");
+
+    let node = ScriptNode::ShowCharacter {
+        path: Some("char.png".to_string()),
+        alias: "royu".to_string(),
+        position: Position::Center,
+        transition: None,
+        idle: Some("breathe".to_string()),
+    };
+
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert!(matches!(
+        &result.commands[0],
+        Command::ShowCharacter { idle: Some(kind), .. } if kind == "breathe"
+    ));
+}
+
 #[test]
 fn test_execute_hide_character_updates_state() {
     let (mut executor, mut state, script) = test_ctx("");
@@ -454,14 +606,21 @@ fn test_execute_choice() {
 
     let node = ScriptNode::Choice {
         style: Some("横排".to_string()),
+        prompt: None,
         options: vec![
             ChoiceOption {
                 text: "选项A".to_string(),
                 target_label: "label_a".to_string(),
+                condition: None,
+                disabled_reason: None,
+                actions: Vec::new(),
             },
             ChoiceOption {
                 text: "选项B".to_string(),
                 target_label: "label_b".to_string(),
+                condition: None,
+                disabled_reason: None,
+                actions: Vec::new(),
             },
         ],
     };
@@ -493,6 +652,7 @@ fn test_execute_goto() {
                 content: "Hello".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::Label {
                 name: "end".to_string(),
@@ -546,6 +706,7 @@ fn test_execute_conditional_true_branch() {
                 content: "条件为真".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             }],
         }],
     };
@@ -581,6 +742,7 @@ fn test_execute_conditional_else_branch() {
                     content: "条件为真".to_string(),
                     inline_effects: vec![],
                     no_wait: false,
+                    text_speed: 1.0,
                 }],
             },
             ConditionalBranch {
@@ -590,6 +752,7 @@ fn test_execute_conditional_else_branch() {
                     content: "条件为假".to_string(),
                     inline_effects: vec![],
                     no_wait: false,
+                    text_speed: 1.0,
                 }],
             },
         ],
@@ -647,3 +810,122 @@ fn test_execute_request_ui_no_params() {
             if key == "confirm_dialog" && mode == "confirm_dialog" && params.is_empty()
     ));
 }
+
+#[test]
+fn test_execute_show_characters_moves_bound_aliases_to_auto() {
+    let (mut executor, mut state, script) = test_ctx("");
+    for alias in ["royu", "kaze"] {
+        state
+            .visible_characters
+            .insert(alias.to_string(), (format!("{alias}.png"), Position::Left));
+    }
+
+    let node = ScriptNode::ShowCharacters {
+        aliases: vec!["kaze".to_string(), "royu".to_string()],
+        transition: None,
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    let shown: Vec<_> = result
+        .commands
+        .iter()
+        .map(|cmd| match cmd {
+            Command::ShowCharacter {
+                alias, position, ..
+            } => (alias.as_str(), *position),
+            other => panic!("unexpected command: {other:?}"),
+        })
+        .collect();
+    assert_eq!(shown, [("kaze", Position::Auto), ("royu", Position::Auto)]);
+    assert_eq!(state.visible_characters["royu"].1, Position::Auto);
+
+    // 未绑定的别名报错
+    let node = ScriptNode::ShowCharacters {
+        aliases: vec!["sora".to_string()],
+        transition: None,
+    };
+    assert!(matches!(
+        executor.execute(&node, &mut state, &script),
+        Err(RuntimeError::InvalidState { .. })
+    ));
+}
+
+#[test]
+fn test_execute_bubble_emits_command_without_waiting() {
+    let (mut executor, mut state, script) = test_ctx("");
+    state.set_var("n", VarValue::Int(3));
+
+    let node = ScriptNode::ShowBubble {
+        alias: "royu".to_string(),
+        content: "第{$n}次了……".to_string(),
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+
+    assert_eq!(
+        result.commands,
+        [Command::ShowBubble {
+            alias: "royu".to_string(),
+            content: "第3次了……".to_string(),
+        }]
+    );
+    assert!(result.waiting.is_none());
+}
+
+#[test]
+fn test_execute_timeline_emits_commands_sorted_by_delay() {
+    use crate::command::TimedCommand;
+    use crate::script::TimelineStep;
+
+    let (mut executor, mut state, script) = test_ctx("scripts");
+    let show = |alias: &str, position| ScriptNode::ShowCharacter {
+        path: Some(format!("{alias}.png")),
+        alias: alias.to_string(),
+        position,
+        transition: None,
+        idle: None,
+    };
+    let node = ScriptNode::Timeline {
+        steps: vec![
+            TimelineStep {
+                offset: 1.0,
+                node: ScriptNode::ChangeBG {
+                    path: "bg.png".to_string(),
+                    transition: None,
+                },
+            },
+            TimelineStep {
+                offset: 0.0,
+                node: show("alice", Position::Left),
+            },
+            TimelineStep {
+                offset: 0.5,
+                node: show("bob", Position::Right),
+            },
+        ],
+    };
+
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert!(result.waiting.is_none());
+    let [Command::Timeline { steps }] = &result.commands[..] else {
+        panic!("expected a single timeline command: {:?}", result.commands);
+    };
+    let summary: Vec<(f64, &str)> = steps
+        .iter()
+        .map(|TimedCommand { delay, command }| {
+            let name = match command {
+                Command::ShowCharacter { alias, .. } => alias.as_str(),
+                Command::ShowBackground { path, .. } => path.as_str(),
+                other => panic!("unexpected command: {other:?}"),
+            };
+            (*delay, name)
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [(0.0, "alice"), (0.5, "bob"), (1.0, "scripts/bg.png")]
+    );
+
+    // 运行时状态立即反映全部动作（存档记录最终画面）
+    assert!(state.visible_characters.contains_key("alice"));
+    assert!(state.visible_characters.contains_key("bob"));
+    assert_eq!(state.current_background.as_deref(), Some("scripts/bg.png"));
+}
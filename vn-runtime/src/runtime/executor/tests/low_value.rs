@@ -28,6 +28,39 @@ fn test_execute_bgm_unduck() {
     assert!(result.script_control.is_none());
 }
 
+#[test]
+fn test_execute_set_bgm_volume() {
+    let (mut executor, mut state, script) = test_ctx("");
+
+    let node = ScriptNode::SetBgmVolume {
+        volume: 0.3,
+        duration: 1.0,
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert_eq!(result.commands.len(), 1);
+    assert!(matches!(
+        &result.commands[0],
+        Command::SetBgmVolume { volume, duration } if *volume == 0.3 && *duration == 1.0
+    ));
+    assert!(result.waiting.is_none());
+}
+
+#[test]
+fn test_execute_set_textbox_style() {
+    let (mut executor, mut state, script) = test_ctx("");
+    let node = ScriptNode::SetTextboxStyle {
+        name: "diary".to_string(),
+    };
+    let result = executor.execute(&node, &mut state, &script).unwrap();
+    assert_eq!(
+        result.commands,
+        vec![Command::SetTextboxStyle {
+            name: "diary".to_string()
+        }]
+    );
+    assert!(result.waiting.is_none());
+}
+
 #[test]
 fn test_execute_textbox_hide() {
     let (mut executor, mut state, script) = test_ctx("");
@@ -245,6 +278,7 @@ fn test_execute_conditional_false_branch() {
                 content: "条件为真".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             }],
         }],
     };
@@ -276,6 +310,7 @@ fn test_execute_conditional_elseif() {
                     content: "管理员".to_string(),
                     inline_effects: vec![],
                     no_wait: false,
+                    text_speed: 1.0,
                 }],
             },
             ConditionalBranch {
@@ -285,6 +320,7 @@ fn test_execute_conditional_elseif() {
                     content: "用户".to_string(),
                     inline_effects: vec![],
                     no_wait: false,
+                    text_speed: 1.0,
                 }],
             },
             ConditionalBranch {
@@ -294,6 +330,7 @@ fn test_execute_conditional_elseif() {
                     content: "访客".to_string(),
                     inline_effects: vec![],
                     no_wait: false,
+                    text_speed: 1.0,
                 }],
             },
         ],
@@ -495,6 +532,7 @@ fn test_execute_conditional_with_wait_in_branch() {
                 content: "test".to_string(),
                 inline_effects: vec![],
                 no_wait: false,
+                text_speed: 1.0,
             },
             ScriptNode::TextBoxShow,
         ],
@@ -513,6 +551,7 @@ fn test_execute_dialogue_no_wait() {
         content: "auto".to_string(),
         inline_effects: vec![],
         no_wait: true,
+        text_speed: 1.0,
     };
     let result = executor.execute(&node, &mut state, &script).unwrap();
     assert!(matches!(
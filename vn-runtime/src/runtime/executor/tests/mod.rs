@@ -1,5 +1,5 @@
 use super::*;
-use crate::command::{Position, TextMode, Transition, TransitionArg};
+use crate::command::{Color, Position, TextMode, Transition, TransitionArg};
 use crate::script::{ChoiceOption, Expr};
 use crate::state::VarValue;
 
@@ -10,4 +10,4 @@
 pub mod engine;
 pub mod executor;
 
-pub use engine::VNRuntime;
+pub use engine::{AutosaveTrigger, RestoreOutcome, RuntimeMetrics, VNRuntime};
@@ -0,0 +1,244 @@
+//! # 差异存档
+//!
+//! 频繁自动存档时，相对某个基准存档只记录变化部分：
+//!
+//! - 变量 / 持久变量：只记录新增或修改的键，以及被删除的键
+//! - 历史记录：基准历史是当前历史的前缀时只记录追加的事件，否则整体记录
+//! - 其余体量小的状态（位置、等待、调用栈、音频、渲染快照等）整体记录
+//!
+//! 读取时用 [`SaveDelta::apply`] 与基准合并；基准不匹配或缺失时返回错误，
+//! 调用方可通过 [`SaveDelta::apply_or_else`] 回退到完整存档加载。
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::{History, HistoryEvent};
+use crate::state::{RuntimeState, VarValue};
+
+use super::{AudioState, RenderSnapshot, SaveData, SaveError, SaveMetadata, SaveSlot, SaveVersion};
+
+/// 基准存档引用
+///
+/// 用槽位号 + 存档时间戳标识基准，避免与被覆盖后的同槽位存档错误合并。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SaveBaseRef {
+    pub slot: SaveSlot,
+    pub timestamp: String,
+}
+
+impl SaveBaseRef {
+    /// 引用给定存档
+    pub fn of(base: &SaveData) -> Self {
+        Self {
+            slot: base.metadata.slot,
+            timestamp: base.metadata.timestamp.clone(),
+        }
+    }
+
+    /// 是否指向给定存档
+    pub fn matches(&self, base: &SaveData) -> bool {
+        *self == Self::of(base)
+    }
+}
+
+impl std::fmt::Display for SaveBaseRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} @ {}", self.slot, self.timestamp)
+    }
+}
+
+/// 变量表差异
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VariableDelta {
+    /// 新增或修改的变量
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub set: BTreeMap<String, VarValue>,
+    /// 被删除的变量
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<String>,
+}
+
+impl VariableDelta {
+    /// 计算 `base` → `current` 的差异
+    pub fn diff(base: &HashMap<String, VarValue>, current: &HashMap<String, VarValue>) -> Self {
+        let set = current
+            .iter()
+            .filter(|(k, v)| base.get(*k) != Some(*v))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        let mut removed: Vec<String> = base
+            .keys()
+            .filter(|k| !current.contains_key(*k))
+            .cloned()
+            .collect();
+        removed.sort();
+        Self { set, removed }
+    }
+
+    /// 把差异应用到变量表
+    pub fn apply(&self, vars: &mut HashMap<String, VarValue>) {
+        for key in &self.removed {
+            vars.remove(key);
+        }
+        for (key, value) in &self.set {
+            vars.insert(key.clone(), value.clone());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// 历史记录差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HistoryDelta {
+    /// 在基准历史之后追加的事件
+    Appended(Vec<HistoryEvent>),
+    /// 基准历史不是当前历史的前缀（如超出上限被裁剪），整体记录
+    Full(History),
+}
+
+impl HistoryDelta {
+    fn diff(base: &History, current: &History) -> Self {
+        match current.events().strip_prefix(base.events()) {
+            Some(appended) if base.max_events() == current.max_events() => {
+                Self::Appended(appended.to_vec())
+            }
+            _ => Self::Full(current.clone()),
+        }
+    }
+
+    fn apply(&self, base: &History) -> History {
+        match self {
+            Self::Appended(events) => {
+                let mut history = base.clone();
+                for event in events {
+                    history.push(event.clone());
+                }
+                history
+            }
+            Self::Full(history) => history.clone(),
+        }
+    }
+}
+
+/// 相对基准存档的差异存档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveDelta {
+    /// 存档格式版本
+    pub version: SaveVersion,
+    /// 基准存档引用
+    pub base: SaveBaseRef,
+    /// 本次存档的元数据
+    pub metadata: SaveMetadata,
+    /// 运行时状态（位置、等待、调用栈等）；变量表留空，由下方差异补齐
+    pub runtime_state: RuntimeState,
+    /// 会话变量差异
+    #[serde(default)]
+    pub variables: VariableDelta,
+    /// 持久变量差异
+    #[serde(default)]
+    pub persistent_variables: VariableDelta,
+    /// 历史记录差异
+    pub history: HistoryDelta,
+    /// 音频状态
+    pub audio: AudioState,
+    /// 渲染快照
+    pub render: RenderSnapshot,
+    /// 模态扩展数据
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mode_data: BTreeMap<String, serde_json::Value>,
+}
+
+impl SaveDelta {
+    /// 基于 `base` 为 `current` 生成差异存档
+    pub fn diff(base: &SaveData, current: &SaveData) -> Self {
+        let mut runtime_state = current.runtime_state.clone();
+        runtime_state.variables.clear();
+        runtime_state.persistent_variables.clear();
+
+        Self {
+            version: SaveVersion::current(),
+            base: SaveBaseRef::of(base),
+            metadata: current.metadata.clone(),
+            runtime_state,
+            variables: VariableDelta::diff(
+                &base.runtime_state.variables,
+                &current.runtime_state.variables,
+            ),
+            persistent_variables: VariableDelta::diff(
+                &base.runtime_state.persistent_variables,
+                &current.runtime_state.persistent_variables,
+            ),
+            history: HistoryDelta::diff(&base.history, &current.history),
+            audio: current.audio.clone(),
+            render: current.render.clone(),
+            mode_data: current.mode_data.clone(),
+        }
+    }
+
+    /// 与基准合并，恢复出完整存档
+    pub fn apply(&self, base: &SaveData) -> Result<SaveData, SaveError> {
+        if !self.base.matches(base) {
+            return Err(SaveError::BaseMismatch {
+                expected: self.base.to_string(),
+                found: SaveBaseRef::of(base).to_string(),
+            });
+        }
+
+        let mut runtime_state = self.runtime_state.clone();
+        runtime_state.variables = base.runtime_state.variables.clone();
+        self.variables.apply(&mut runtime_state.variables);
+        runtime_state.persistent_variables = base.runtime_state.persistent_variables.clone();
+        self.persistent_variables
+            .apply(&mut runtime_state.persistent_variables);
+
+        Ok(SaveData {
+            version: self.version.clone(),
+            metadata: self.metadata.clone(),
+            runtime_state,
+            audio: self.audio.clone(),
+            render: self.render.clone(),
+            history: self.history.apply(&base.history),
+            mode_data: self.mode_data.clone(),
+        })
+    }
+
+    /// 与基准合并；基准缺失或合并失败时回退到 `fallback`（通常为完整存档加载）
+    pub fn apply_or_else<F>(
+        &self,
+        base: Option<&SaveData>,
+        fallback: F,
+    ) -> Result<SaveData, SaveError>
+    where
+        F: FnOnce() -> Result<SaveData, SaveError>,
+    {
+        match base.map(|b| self.apply(b)) {
+            Some(Ok(data)) => Ok(data),
+            _ => fallback(),
+        }
+    }
+
+    /// 序列化为 JSON 字符串
+    pub fn to_json(&self) -> Result<String, SaveError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| SaveError::SerializationFailed(e.to_string()))
+    }
+
+    /// 从 JSON 字符串反序列化
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        let delta: SaveDelta = serde_json::from_str(json)
+            .map_err(|e| SaveError::DeserializationFailed(e.to_string()))?;
+
+        if !delta.version.is_compatible() {
+            return Err(SaveError::IncompatibleVersion {
+                save_version: delta.version.to_string(),
+                current_version: SaveVersion::current().to_string(),
+            });
+        }
+
+        Ok(delta)
+    }
+}
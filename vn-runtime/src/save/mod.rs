@@ -7,11 +7,20 @@
 //! - 所有存档数据必须可序列化（JSON）
 //! - 必须有版本号，支持向后兼容检测
 //! - 存档应包含足够信息恢复游戏状态
+//! - 支持相对基准存档的差异存档（见 [`delta`]）
+//! - 不兼容或残缺的存档仍可只读预览（见 [`preview`]）
+
+mod delta;
+mod preview;
+
+pub use delta::{HistoryDelta, SaveBaseRef, SaveDelta, VariableDelta};
+pub use preview::SavePreview;
 
 use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::command::{BackgroundLayer, Color, FilterKind, WeatherKind};
 use crate::history::History;
 use crate::state::RuntimeState;
 
@@ -21,7 +30,7 @@ use crate::state::RuntimeState;
 /// - MAJOR: 不兼容的格式变更
 /// - MINOR: 向后兼容的新字段
 pub const SAVE_VERSION_MAJOR: u32 = 1;
-pub const SAVE_VERSION_MINOR: u32 = 0;
+pub const SAVE_VERSION_MINOR: u32 = 1;
 
 /// 存档版本信息
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -55,17 +64,92 @@ impl SaveVersion {
     }
 }
 
+/// 存档位置：普通槽位或 Continue 存档
+///
+/// 序列化为槽位号（普通槽位）或字符串 `"continue"`，旧存档中的数字槽位可直接读取。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SaveSlot {
+    /// 普通槽位（1-based）
+    Slot(u32),
+    /// 返回标题时自动写入的 Continue 存档，不占用普通槽位
+    Continue,
+}
+
+impl SaveSlot {
+    /// 普通槽位号；Continue 存档返回 None
+    pub fn number(self) -> Option<u32> {
+        match self {
+            Self::Slot(n) => Some(n),
+            Self::Continue => None,
+        }
+    }
+
+    pub fn is_continue(self) -> bool {
+        self == Self::Continue
+    }
+}
+
+impl From<u32> for SaveSlot {
+    fn from(slot: u32) -> Self {
+        Self::Slot(slot)
+    }
+}
+
+impl std::fmt::Display for SaveSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Slot(n) => write!(f, "slot {n}"),
+            Self::Continue => f.write_str("continue"),
+        }
+    }
+}
+
+const CONTINUE_SLOT_NAME: &str = "continue";
+
+impl Serialize for SaveSlot {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Slot(n) => serializer.serialize_u32(*n),
+            Self::Continue => serializer.serialize_str(CONTINUE_SLOT_NAME),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SaveSlot {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(u32),
+            Name(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(n) => Ok(Self::Slot(n)),
+            Raw::Name(name) if name == CONTINUE_SLOT_NAME => Ok(Self::Continue),
+            Raw::Name(name) => Err(serde::de::Error::custom(format!(
+                "无效的存档槽位: {name:?}"
+            ))),
+        }
+    }
+}
+
 /// 存档元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveMetadata {
-    /// 存档槽位号（1-based）
-    pub slot: u32,
+    /// 存档位置
+    pub slot: SaveSlot,
     /// 保存时间（ISO 8601 格式）
     pub timestamp: String,
     /// 章节标题（用于 UI 显示）
     pub chapter_title: Option<String>,
     /// 游戏时长（秒）
     pub play_time_secs: u64,
+    /// 玩家自定义的存档备注（旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// 存档位置所在章节的预计阅读时长（秒，旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_time_secs: Option<u64>,
 }
 
 impl SaveMetadata {
@@ -73,11 +157,18 @@ impl SaveMetadata {
     ///
     /// `now_secs` 为 Unix 秒时间戳，由 Host 提供。
     pub fn new(slot: u32, now_secs: u64) -> Self {
+        Self::for_slot(SaveSlot::Slot(slot), now_secs)
+    }
+
+    /// 为指定存档位置（含 Continue）创建元数据
+    pub fn for_slot(slot: SaveSlot, now_secs: u64) -> Self {
         Self {
             slot,
             timestamp: format!("{now_secs}"),
             chapter_title: None,
             play_time_secs: 0,
+            label: None,
+            reading_time_secs: None,
         }
     }
 
@@ -92,6 +183,18 @@ impl SaveMetadata {
         self.play_time_secs = secs;
         self
     }
+
+    /// 设置存档备注
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// 设置所在章节的预计阅读时长
+    pub fn with_reading_time(mut self, secs: u64) -> Self {
+        self.reading_time_secs = Some(secs);
+        self
+    }
 }
 
 /// 音频状态（用于恢复）
@@ -101,6 +204,9 @@ pub struct AudioState {
     pub current_bgm: Option<String>,
     /// BGM 是否循环
     pub bgm_looping: bool,
+    /// 脚本用 `bgmVol` 设置的 BGM 音量系数（未调整或旧存档为 None，即 1.0）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bgm_volume: Option<f64>,
 }
 
 /// 渲染状态快照（用于恢复）
@@ -110,8 +216,40 @@ pub struct AudioState {
 pub struct RenderSnapshot {
     /// 当前背景路径
     pub background: Option<String>,
+    /// 纯色背景（与 `background` 互斥，旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<Color>,
+    /// 多层视差背景（与 `background`/`fill` 互斥，旧存档无此字段时为空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub background_layers: Vec<BackgroundLayer>,
+    /// 正在显示的 CG（旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cg: Option<String>,
     /// 可见角色列表 (alias -> (path, position_name))
     pub characters: Vec<CharacterSnapshot>,
+    /// 天气覆盖层（旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather: Option<WeatherSnapshot>,
+    /// 整帧滤镜（旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<FilterSnapshot>,
+    /// 对话框样式名（默认样式或旧存档为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub textbox_style: Option<String>,
+}
+
+/// 天气快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherSnapshot {
+    pub kind: WeatherKind,
+    pub intensity: f64,
+}
+
+/// 滤镜快照
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterSnapshot {
+    pub kind: FilterKind,
+    pub intensity: f64,
 }
 
 /// 角色快照
@@ -120,6 +258,12 @@ pub struct CharacterSnapshot {
     pub alias: String,
     pub texture_path: String,
     pub position: String,
+    /// 循环待机动画名（旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle: Option<String>,
+    /// 轮廓光强度（未开启或旧存档无此字段时为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rim_light: Option<f64>,
 }
 
 /// 存档数据
@@ -149,9 +293,14 @@ impl SaveData {
     ///
     /// `now_secs` 为 Unix 秒时间戳，由 Host 提供。
     pub fn new(slot: u32, runtime_state: RuntimeState, now_secs: u64) -> Self {
+        Self::for_slot(SaveSlot::Slot(slot), runtime_state, now_secs)
+    }
+
+    /// 为指定存档位置（含 Continue）创建存档数据
+    pub fn for_slot(slot: SaveSlot, runtime_state: RuntimeState, now_secs: u64) -> Self {
         Self {
             version: SaveVersion::current(),
-            metadata: SaveMetadata::new(slot, now_secs),
+            metadata: SaveMetadata::for_slot(slot, now_secs),
             runtime_state,
             audio: AudioState::default(),
             render: RenderSnapshot::default(),
@@ -178,6 +327,18 @@ impl SaveData {
         self
     }
 
+    /// 设置存档备注
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.metadata.label = Some(label.into());
+        self
+    }
+
+    /// 设置所在章节的预计阅读时长
+    pub fn with_reading_time(mut self, secs: u64) -> Self {
+        self.metadata.reading_time_secs = Some(secs);
+        self
+    }
+
     /// 设置历史记录
     pub fn with_history(mut self, history: History) -> Self {
         self.history = history;
@@ -229,6 +390,8 @@ pub enum SaveError {
     IoError(String),
     /// 存档不存在
     NotFound(String),
+    /// 差异存档的基准与提供的存档不匹配
+    BaseMismatch { expected: String, found: String },
 }
 
 impl std::fmt::Display for SaveError {
@@ -248,6 +411,9 @@ impl std::fmt::Display for SaveError {
             }
             SaveError::IoError(e) => write!(f, "文件操作失败: {}", e),
             SaveError::NotFound(path) => write!(f, "存档不存在: {}", path),
+            SaveError::BaseMismatch { expected, found } => {
+                write!(f, "差异存档基准不匹配: 需要 {} 实际 {}", expected, found)
+            }
         }
     }
 }
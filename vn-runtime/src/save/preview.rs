@@ -0,0 +1,105 @@
+//! # 存档只读预览
+//!
+//! 读档界面需要展示存档内容，即使存档来自不兼容的版本或缺少字段而无法完整恢复：
+//!
+//! - 先按普通 JSON 解析，再逐字段宽松提取，缺失或格式不符的字段记为 None
+//! - 变量只保留能识别的值，无法识别的变量跳过
+//! - 是否能完整恢复由 [`SavePreview::restorable`] 单独给出
+//!
+//! 预览不产生可恢复的状态，读档仍需走 [`SaveData::from_json`]。
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::state::VarValue;
+
+use super::{SaveData, SaveError, SaveSlot, SaveVersion};
+
+/// 存档只读预览
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavePreview {
+    /// 存档格式版本
+    pub version: Option<SaveVersion>,
+    /// 能否通过 [`SaveData::from_json`] 完整恢复
+    pub restorable: bool,
+    /// 存档位置
+    pub slot: Option<SaveSlot>,
+    /// 保存时间
+    pub timestamp: Option<String>,
+    /// 章节标题
+    pub chapter_title: Option<String>,
+    /// 游戏时长（秒）
+    pub play_time_secs: Option<u64>,
+    /// 存档备注
+    pub label: Option<String>,
+    /// 所在脚本路径（旧存档无路径时为脚本 id）
+    pub script_path: Option<String>,
+    /// 所在节点索引
+    pub node_index: Option<usize>,
+    /// 最近经过的标签
+    pub position_label: Option<String>,
+    /// 能识别的脚本变量
+    pub variables: BTreeMap<String, VarValue>,
+}
+
+impl SavePreview {
+    /// 存档版本是否与当前版本兼容（版本缺失时视为不兼容）
+    pub fn is_compatible(&self) -> bool {
+        self.version
+            .as_ref()
+            .is_some_and(SaveVersion::is_compatible)
+    }
+}
+
+impl SaveData {
+    /// 宽松解析存档 JSON 得到只读预览
+    ///
+    /// 只有文本不是合法 JSON 对象时才返回错误。
+    pub fn preview(json: &str) -> Result<SavePreview, SaveError> {
+        let root: Value = serde_json::from_str(json)
+            .map_err(|e| SaveError::DeserializationFailed(e.to_string()))?;
+        if !root.is_object() {
+            return Err(SaveError::DeserializationFailed(
+                "存档不是 JSON 对象".to_string(),
+            ));
+        }
+
+        let metadata = &root["metadata"];
+        let position = &root["runtime_state"]["position"];
+        let script_path = field::<String>(&position["script_path"])
+            .filter(|path| !path.is_empty())
+            .or_else(|| field(&position["script_id"]));
+        let variables = root["runtime_state"]["variables"]
+            .as_object()
+            .map(|vars| {
+                vars.iter()
+                    .filter_map(|(name, value)| Some((name.clone(), field(value)?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(SavePreview {
+            version: field(&root["version"]),
+            restorable: SaveData::from_json(json).is_ok(),
+            slot: field(&metadata["slot"]),
+            timestamp: field(&metadata["timestamp"]),
+            chapter_title: field(&metadata["chapter_title"]),
+            play_time_secs: field(&metadata["play_time_secs"]),
+            label: field(&metadata["label"]),
+            script_path,
+            node_index: field(&position["node_index"]),
+            position_label: field(&position["label"]),
+            variables,
+        })
+    }
+}
+
+/// 把单个字段解析为目标类型；缺失（null）或格式不符时返回 None
+fn field<T: DeserializeOwned>(value: &Value) -> Option<T> {
+    if value.is_null() {
+        return None;
+    }
+    T::deserialize(value).ok()
+}
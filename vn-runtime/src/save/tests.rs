@@ -1,6 +1,7 @@
 use super::*;
 use crate::command::Position;
 use crate::history::HistoryEvent;
+use crate::state::VarValue;
 
 #[test]
 fn test_save_version_compatibility() {
@@ -23,12 +24,36 @@ fn test_save_version_current_and_to_string() {
     );
 }
 
+#[test]
+fn test_continue_slot_does_not_collide_with_numbered_slots() {
+    let cont = SaveData::for_slot(SaveSlot::Continue, RuntimeState::new("main"), 0);
+    assert!(cont.metadata.slot.is_continue());
+    assert_eq!(cont.metadata.slot.number(), None);
+    assert_ne!(cont.metadata.slot, SaveSlot::Slot(0));
+
+    let json = cont.to_json().unwrap();
+    assert!(json.contains(r#""slot": "continue""#), "{json}");
+    let loaded = SaveData::from_json(&json).unwrap();
+    assert_eq!(loaded.metadata.slot, SaveSlot::Continue);
+
+    // 旧存档的数字槽位照常读取
+    let numbered = SaveData::new(0, RuntimeState::new("main"), 0)
+        .to_json()
+        .unwrap();
+    assert!(numbered.contains(r#""slot": 0"#));
+    assert_eq!(
+        SaveData::from_json(&numbered).unwrap().metadata.slot,
+        SaveSlot::Slot(0)
+    );
+    assert!(serde_json::from_str::<SaveSlot>(r#""slot_1""#).is_err());
+}
+
 #[test]
 fn test_save_metadata_builders() {
     let md = SaveMetadata::new(7, 1700000000)
         .with_chapter("第二章")
         .with_play_time(123);
-    assert_eq!(md.slot, 7);
+    assert_eq!(md.slot, SaveSlot::Slot(7));
     assert_eq!(md.chapter_title, Some("第二章".to_string()));
     assert_eq!(md.play_time_secs, 123);
     assert_eq!(md.timestamp, "1700000000");
@@ -48,6 +73,7 @@ fn test_save_data_serialization() {
         .with_audio(AudioState {
             current_bgm: Some("bgm.mp3".to_string()),
             bgm_looping: true,
+            bgm_volume: Some(0.4),
         });
 
     // 序列化
@@ -57,9 +83,19 @@ fn test_save_data_serialization() {
 
     // 反序列化
     let loaded = SaveData::from_json(&json).unwrap();
-    assert_eq!(loaded.metadata.slot, 1);
+    assert_eq!(loaded.metadata.slot, SaveSlot::Slot(1));
     assert_eq!(loaded.metadata.chapter_title, Some("第一章".to_string()));
     assert_eq!(loaded.runtime_state.position.script_id, "test_script");
+    assert_eq!(loaded.audio.bgm_volume, Some(0.4));
+
+    // 旧存档没有 bgm_volume，按未调整处理
+    let mut legacy: serde_json::Value = serde_json::from_str(&json).unwrap();
+    legacy["audio"]
+        .as_object_mut()
+        .unwrap()
+        .remove("bgm_volume");
+    let legacy = SaveData::from_json(&legacy.to_string()).unwrap();
+    assert_eq!(legacy.audio.bgm_volume, None);
 }
 
 #[test]
@@ -68,11 +104,25 @@ fn test_save_data_with_render_and_history() {
 
     let render = RenderSnapshot {
         background: Some("bg.png".to_string()),
+        fill: None,
+        background_layers: Vec::new(),
+        cg: None,
         characters: vec![CharacterSnapshot {
             alias: "char1".to_string(),
             texture_path: "char1.png".to_string(),
             position: "Center".to_string(),
+            idle: Some("breathe".to_string()),
+            rim_light: Some(0.5),
         }],
+        weather: Some(WeatherSnapshot {
+            kind: WeatherKind::Rain,
+            intensity: 0.6,
+        }),
+        filter: Some(FilterSnapshot {
+            kind: FilterKind::Sepia,
+            intensity: 0.8,
+        }),
+        textbox_style: Some("diary".to_string()),
     };
 
     let mut history = History::new();
@@ -90,6 +140,12 @@ fn test_save_data_with_render_and_history() {
     assert_eq!(save_data.render.characters.len(), 1);
     assert_eq!(save_data.render.characters[0].alias, "char1");
 
+    let loaded = SaveData::from_json(&save_data.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.render.weather, render.weather);
+    assert_eq!(loaded.render.filter, render.filter);
+    assert_eq!(loaded.render.textbox_style, render.textbox_style);
+    assert_eq!(loaded.render.characters[0].rim_light, Some(0.5));
+
     assert_eq!(save_data.history.len(), history.len());
 }
 
@@ -136,6 +192,37 @@ fn test_mode_data_backward_compatibility() {
     assert!(data.mode_data.is_empty());
 }
 
+#[test]
+fn test_save_label_round_trip() {
+    let data = SaveData::new(3, RuntimeState::new("main"), 0).with_label("Boss 战前");
+    let json = data.to_json().unwrap();
+    let loaded = SaveData::from_json(&json).unwrap();
+    assert_eq!(loaded.metadata.label.as_deref(), Some("Boss 战前"));
+}
+
+#[test]
+fn test_save_label_backward_compatibility() {
+    // 旧存档无 label 字段 → 反序列化应成功，label 为 None，且重新序列化不写出该字段
+    let json = r#"{
+        "version": { "major": 1, "minor": 0 },
+        "metadata": { "slot": 1, "timestamp": "0", "chapter_title": null, "play_time_secs": 0 },
+        "runtime_state": {
+            "position": { "script_id": "test", "node_index": 0 },
+            "variables": {},
+            "waiting": "None",
+            "visible_characters": {},
+            "current_background": null
+        },
+        "audio": { "current_bgm": null, "bgm_looping": false },
+        "render": { "background": null, "characters": [] },
+        "history": { "events": [], "max_events": 1000 }
+    }"#;
+
+    let data = SaveData::from_json(json).unwrap();
+    assert_eq!(data.metadata.label, None);
+    assert!(!data.to_json().unwrap().contains("\"label\""));
+}
+
 #[test]
 fn test_mode_data_round_trip() {
     let state = RuntimeState::new("test");
@@ -185,3 +272,133 @@ fn test_save_error_display() {
     assert!(e.to_string().contains("2.0"));
     assert!(e.to_string().contains("1.0"));
 }
+
+fn delta_base_save() -> SaveData {
+    let mut state = RuntimeState::new("main");
+    state.set_var("affection", VarValue::Int(1));
+    state.set_var("route", VarValue::String("summer".to_string()));
+    state.set_var("temp", VarValue::Bool(true));
+    state.set_persistent_var("cleared", VarValue::Bool(false));
+
+    let mut history = History::new();
+    history.push(HistoryEvent::dialogue(None, "第一句".to_string(), 10));
+
+    SaveData::new(1, state, 1_000)
+        .with_history(history)
+        .with_audio(AudioState {
+            current_bgm: Some("bgm/a.ogg".to_string()),
+            bgm_looping: true,
+            bgm_volume: None,
+        })
+}
+
+#[test]
+fn test_save_delta_records_only_changes_and_restores_full_state() {
+    let base = delta_base_save();
+
+    let mut current = base.clone();
+    current.metadata = SaveMetadata::new(1, 2_000);
+    current.runtime_state.position.jump_to(42);
+    current.runtime_state.set_var("affection", VarValue::Int(5));
+    current.runtime_state.variables.remove("temp");
+    current
+        .runtime_state
+        .set_persistent_var("cleared", VarValue::Bool(true));
+    current
+        .history
+        .push(HistoryEvent::dialogue(None, "第二句".to_string(), 20));
+    current.render.background = Some("bg/night.png".to_string());
+
+    let delta = SaveDelta::diff(&base, &current);
+    assert_eq!(delta.base, SaveBaseRef::of(&base));
+    assert_eq!(delta.variables.set.len(), 1);
+    assert_eq!(delta.variables.set["affection"], VarValue::Int(5));
+    assert_eq!(delta.variables.removed, vec!["temp".to_string()]);
+    assert!(delta.runtime_state.variables.is_empty());
+    assert!(matches!(&delta.history, HistoryDelta::Appended(e) if e.len() == 1));
+
+    // 经 JSON 往返后合并
+    let delta = SaveDelta::from_json(&delta.to_json().unwrap()).unwrap();
+    let restored = delta.apply(&base).unwrap();
+    assert_eq!(restored.runtime_state, current.runtime_state);
+    assert_eq!(restored.history, current.history);
+    assert_eq!(restored.metadata.timestamp, current.metadata.timestamp);
+    assert_eq!(restored.render.background, current.render.background);
+    assert_eq!(restored.audio.current_bgm, current.audio.current_bgm);
+}
+
+#[test]
+fn test_save_delta_base_mismatch_falls_back_to_full_load() {
+    let base = delta_base_save();
+    let mut current = base.clone();
+    current.metadata = SaveMetadata::new(1, 2_000);
+    current.runtime_state.position.jump_to(7);
+    let delta = SaveDelta::diff(&base, &current);
+
+    // 基准槽位已被覆盖（时间戳不同）
+    let other_base = SaveData::new(1, RuntimeState::new("main"), 1_500);
+    let err = delta.apply(&other_base).unwrap_err();
+    assert!(matches!(err, SaveError::BaseMismatch { .. }));
+
+    let loaded = delta
+        .apply_or_else(Some(&other_base), || Ok(current.clone()))
+        .unwrap();
+    assert_eq!(loaded.runtime_state.position.node_index, 7);
+
+    let loaded = delta
+        .apply_or_else(None, || Err(SaveError::NotFound("slot_001.json".into())))
+        .unwrap_err();
+    assert!(matches!(loaded, SaveError::NotFound(_)));
+}
+
+#[test]
+fn test_preview_survives_missing_fields_and_newer_version() {
+    let mut state = RuntimeState::new("chapter1");
+    state.position.set_path("scripts/chapter1.md");
+    state.position.node_index = 12;
+    state.set_var("affection", VarValue::Int(3));
+    state.set_var("route", VarValue::String("summer".to_string()));
+    let save = SaveData::new(2, state, 100)
+        .with_chapter("第一章")
+        .with_label("分歧前");
+
+    // 来自更新版本：major 偏高、缺少 render/history、含无法识别的变量
+    let mut value: serde_json::Value = serde_json::from_str(&save.to_json().unwrap()).unwrap();
+    value["version"]["major"] = serde_json::json!(SAVE_VERSION_MAJOR + 1);
+    let root = value.as_object_mut().unwrap();
+    root.remove("render");
+    root.remove("history");
+    value["runtime_state"]["variables"]["future"] = serde_json::json!({ "Vector": [1, 2] });
+    let json = value.to_string();
+
+    assert!(SaveData::from_json(&json).is_err());
+    let preview = SaveData::preview(&json).unwrap();
+    assert!(!preview.restorable);
+    assert!(!preview.is_compatible());
+    assert_eq!(preview.version.unwrap().major, SAVE_VERSION_MAJOR + 1);
+    assert_eq!(preview.slot, Some(SaveSlot::Slot(2)));
+    assert_eq!(preview.chapter_title.as_deref(), Some("第一章"));
+    assert_eq!(preview.label.as_deref(), Some("分歧前"));
+    assert_eq!(preview.timestamp.as_deref(), Some("100"));
+    assert_eq!(preview.script_path.as_deref(), Some("scripts/chapter1.md"));
+    assert_eq!(preview.node_index, Some(12));
+    assert_eq!(preview.variables.len(), 2);
+    assert_eq!(preview.variables["affection"], VarValue::Int(3));
+
+    // 元数据残缺时其余字段照常给出
+    let partial = r#"{"metadata": {"chapter_title": "序章"}, "runtime_state": {"position": {"script_id": "intro"}}}"#;
+    let preview = SaveData::preview(partial).unwrap();
+    assert_eq!(preview.chapter_title.as_deref(), Some("序章"));
+    assert_eq!(preview.script_path.as_deref(), Some("intro"));
+    assert_eq!(preview.version, None);
+    assert_eq!(preview.slot, None);
+    assert!(preview.variables.is_empty());
+
+    // 非 JSON 对象无法预览
+    assert!(SaveData::preview("not json").is_err());
+    assert!(SaveData::preview("[1, 2]").is_err());
+
+    // 完整的当前版本存档可恢复
+    let preview = SaveData::preview(&save.to_json().unwrap()).unwrap();
+    assert!(preview.restorable && preview.is_compatible());
+}
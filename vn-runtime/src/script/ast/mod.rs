@@ -9,7 +9,9 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::command::{InlineEffect, Position, TextMode, Transition};
+use crate::command::{
+    BackgroundLayer, Color, FilterKind, InlineEffect, Position, TextMode, Transition, WeatherKind,
+};
 use crate::script::Expr;
 
 /// 选择项（AST 级别）
@@ -19,6 +21,24 @@ pub struct ChoiceOption {
     pub text: String,
     /// 跳转目标标签
     pub target_label: String,
+    /// 可选条件（表格第三列）；不满足时选项仍显示但不可选
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Expr>,
+    /// 条件不满足时展示的理由（表格第四列）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
+    /// 选中后、跳转前依次执行的变量赋值（表格 action 列）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<ChoiceAction>,
+}
+
+/// 选项的附带动作：`$var = expr`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChoiceAction {
+    /// 变量名（不含 $ 前缀，持久变量带 `persistent.`）
+    pub name: String,
+    /// 表达式（选中时求值）
+    pub value: Expr,
 }
 
 /// 条件分支
@@ -30,6 +50,15 @@ pub struct ConditionalBranch {
     pub body: Vec<ScriptNode>,
 }
 
+/// timeline 块中的一个动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineStep {
+    /// 相对 timeline 开始的时间偏移（秒）
+    pub offset: f64,
+    /// 动作节点（仅限 [`ScriptNode::is_timeline_action`] 允许的指令）
+    pub node: ScriptNode,
+}
+
 /// 脚本节点
 ///
 /// 表示脚本中的一个执行单元。
@@ -53,10 +82,20 @@ pub enum ScriptNode {
         name: String,
     },
 
+    /// 书签
+    ///
+    /// 对应 `@bookmark name` 语法。仅用于调试跳转与章节定位，
+    /// 不参与 goto/choice 的跳转语义。
+    Bookmark {
+        /// 书签名
+        name: String,
+    },
+
     /// 对话
     ///
     /// 对应 `角色名："对话内容"` 语法。
-    /// 支持内联节奏标签（`{wait}`, `{speed}` 等）和行尾 `-->` 自动推进。
+    /// 支持内联节奏标签（`{wait}`, `{speed}` 等）、行尾 `-->` 自动推进
+    /// 和整行速度标注 `角色名[speed=0.5]："..."`。
     Dialogue {
         /// 说话者名称（None 表示旁白）
         speaker: Option<String>,
@@ -66,6 +105,9 @@ pub enum ScriptNode {
         inline_effects: Vec<InlineEffect>,
         /// 是否自动推进（来自行尾 `-->` 修饰符）
         no_wait: bool,
+        /// 整行打字机速度系数（来自 `[speed=N]` 标注，缺省 1.0）
+        #[serde(default = "crate::command::default_text_speed")]
+        text_speed: f64,
     },
 
     /// 台词续接（不清屏追加文本）
@@ -81,6 +123,28 @@ pub enum ScriptNode {
         no_wait: bool,
     },
 
+    /// 以自动布局批量显示已绑定的角色
+    ///
+    /// 对应 `show royu, kaze, sora auto [with transition]` 语法，
+    /// 等价于对每个别名依次执行 `show alias at auto`。
+    ShowCharacters {
+        /// 角色别名（按书写顺序，决定从左到右的站位）
+        aliases: Vec<String>,
+        /// 过渡效果（可选，作用于每个角色）
+        transition: Option<Transition>,
+    },
+
+    /// 角色头顶气泡（旁注/心声）
+    ///
+    /// 对应 `bubble royu "……"` 语法；省略文本（`bubble royu`）时清除该角色的气泡。
+    /// 不阻塞脚本执行。
+    ShowBubble {
+        /// 绑定的角色别名
+        alias: String,
+        /// 气泡文本；空串表示清除
+        content: String,
+    },
+
     /// 背景切换
     ///
     /// 对应 `changeBG <img> with transition` 语法
@@ -101,9 +165,46 @@ pub enum ScriptNode {
         transition: Option<Transition>,
     },
 
+    /// 多层视差背景
+    ///
+    /// 对应 `bgLayers <img src="far.png" /> 0.2, <img src="near.png" /> 1` 语法，
+    /// 各层按远到近排列，路径在执行时相对脚本目录解析
+    SetBackgroundLayers {
+        /// 各层图片与视差系数
+        layers: Vec<BackgroundLayer>,
+    },
+
+    /// 纯色背景
+    ///
+    /// 对应 `fill <color> with transition` 语法，用于黑场、留白等不需要图片的场合
+    FillColor {
+        /// 填充颜色
+        color: Color,
+        /// 过渡效果（可选）
+        transition: Option<Transition>,
+    },
+
+    /// 显示全屏 CG
+    ///
+    /// 对应 `showcg <path> with transition` 语法，覆盖在背景与立绘之上并计入 CG 鉴赏
+    ShowCg {
+        /// CG 图片路径
+        path: String,
+        /// 过渡效果（可选）
+        transition: Option<Transition>,
+    },
+
+    /// 隐藏 CG，回到背景
+    ///
+    /// 对应 `hidecg with transition` 语法
+    HideCg {
+        /// 过渡效果（可选）
+        transition: Option<Transition>,
+    },
+
     /// 显示角色
     ///
-    /// 对应 `show <img> as alias at position with transition` 或 `show alias at position` 语法
+    /// 对应 `show <img> as alias at position [idle kind] with transition` 或 `show alias at position` 语法
     ShowCharacter {
         /// 立绘图片路径（可选，如果为 None 则使用已绑定的别名）
         path: Option<String>,
@@ -113,6 +214,9 @@ pub enum ScriptNode {
         position: Position,
         /// 过渡效果（可选）
         transition: Option<Transition>,
+        /// 循环待机动画名（来自 `idle <kind>` 子句，由 Host 解释）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        idle: Option<String>,
     },
 
     /// 隐藏角色
@@ -125,6 +229,16 @@ pub enum ScriptNode {
         transition: Option<Transition>,
     },
 
+    /// 角色轮廓光
+    ///
+    /// 对应 `rimLight alias 0.8` / `rimLight alias off` 语法，强度已钳制到 0~1。
+    SetRimLight {
+        /// 角色别名
+        alias: String,
+        /// 强度（0.0 ~ 1.0，0 为关闭）
+        intensity: f64,
+    },
+
     /// 选择分支
     ///
     /// 对应 Markdown 表格语法
@@ -133,6 +247,9 @@ pub enum ScriptNode {
         style: Option<String>,
         /// 选项列表
         options: Vec<ChoiceOption>,
+        /// 选择提示（紧贴表格上方的 `> 提示` 引用行），等待选择期间显示在对话框中
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt: Option<String>,
     },
 
     /// 播放音频
@@ -147,6 +264,14 @@ pub enum ScriptNode {
         is_bgm: bool,
     },
 
+    /// 从音效池随机播放一个音效
+    ///
+    /// 对应 `playSfx @池名` 语法；池内候选由 Host 通过 `VNRuntime::set_sfx_pools` 提供。
+    PlaySfxPool {
+        /// 池名（不含 `@` 前缀）
+        pool: String,
+    },
+
     /// 停止 BGM
     ///
     /// 对应 `stopBGM` 语法
@@ -162,6 +287,17 @@ pub enum ScriptNode {
     /// 对应 `bgmUnduck` 语法
     BgmUnduck,
 
+    /// 调整 BGM 音量
+    ///
+    /// 对应 `bgmVol 0.3` / `bgmVol 0.3 over 1.0` 语法。
+    /// 音量在解析阶段已钳制到 0~1。
+    SetBgmVolume {
+        /// 目标音量（0.0 ~ 1.0，与玩家设置的 BGM 音量相乘）
+        volume: f64,
+        /// 过渡时长（秒）
+        duration: f64,
+    },
+
     /// 无条件跳转
     ///
     /// 对应 `goto **label**` 语法
@@ -170,6 +306,25 @@ pub enum ScriptNode {
         target_label: String,
     },
 
+    /// 计算目标跳转
+    ///
+    /// 对应 `goto $var` 语法；执行时求值为标签名再跳转，目标无法静态校验。
+    DynamicGoto {
+        /// 求值结果为目标标签名的表达式
+        target: Expr,
+    },
+
+    /// 定时跳转
+    ///
+    /// 对应 `goto **label** after <duration>` 语法。
+    /// 停留指定时长后跳转到目标标签；等待期间点击或快进会提前跳转。
+    TimedGoto {
+        /// 跳转目标标签
+        target_label: String,
+        /// 停留时长（秒）
+        duration: f64,
+    },
+
     /// 调用其他脚本
     ///
     /// 对应 `callScript [label](path/to/script.md)` 语法
@@ -196,6 +351,27 @@ pub enum ScriptNode {
         value: Expr,
     },
 
+    /// 声明子脚本局部变量
+    ///
+    /// 对应 `local $var` / `local $var = value` 语法；
+    /// 局部变量只在本次 callScript 内可见，return 后丢弃。
+    DeclareLocal {
+        /// 变量名（不含 $ 前缀）
+        name: String,
+        /// 初始值表达式（省略时为未赋值）
+        value: Option<Expr>,
+    },
+
+    /// 开发期断言
+    ///
+    /// 对应 `assert 条件 ["说明"]` 语法；断言开启时条件为假报错，关闭时跳过。
+    Assert {
+        /// 条件表达式
+        condition: Expr,
+        /// 断言失败时的说明
+        message: Option<String>,
+    },
+
     /// 条件分支
     ///
     /// 对应 `if/elseif/else/endif` 语法
@@ -207,6 +383,15 @@ pub enum ScriptNode {
         branches: Vec<ConditionalBranch>,
     },
 
+    /// 动画编排
+    ///
+    /// 对应 `timeline` ... `endTimeline` 块，每行 `<偏移秒数> <指令>`。
+    /// 执行时一次性产出带延时标注的命令序列，由 Host 按时间执行，不阻塞脚本。
+    Timeline {
+        /// 按声明顺序的动作列表
+        steps: Vec<TimelineStep>,
+    },
+
     /// 隐藏对话框
     ///
     /// 对应 `textBoxHide` 语法
@@ -222,6 +407,14 @@ pub enum ScriptNode {
     /// 对应 `textBoxClear` 语法
     TextBoxClear,
 
+    /// 切换对话框样式
+    ///
+    /// 对应 `textBoxStyle diary` 语法；`default` 恢复默认样式。
+    SetTextboxStyle {
+        /// 样式名（由 Host 的 UI 配置定义）
+        name: String,
+    },
+
     /// 清除所有角色立绘
     ///
     /// 对应 `clearCharacters` 语法
@@ -262,12 +455,56 @@ pub enum ScriptNode {
         duration: f64,
     },
 
+    /// 手柄震动反馈
+    ///
+    /// 对应 `rumble` / `rumble(intensity: N, duration: N)` 语法。
+    /// 参数在解析阶段已补齐默认值并钳制到合法范围。
+    Rumble {
+        /// 震动强度（0.0 ~ 1.0）
+        intensity: f64,
+        /// 震动时长（秒）
+        duration: f64,
+    },
+
+    /// 天气粒子覆盖层
+    ///
+    /// 对应 `weather rain` / `weather snow(intensity: N)` / `weather none` 语法。
+    /// 强度在解析阶段已补齐默认值并钳制到 0~1。
+    SetWeather {
+        /// 天气类型
+        kind: WeatherKind,
+        /// 强度（0.0 ~ 1.0）
+        intensity: f64,
+    },
+
+    /// 整帧滤镜
+    ///
+    /// 对应 `filter grayscale` / `filter sepia(0.6)` / `filter none` 语法。
+    /// 强度在解析阶段已补齐默认值并钳制到 0~1。
+    SetFilter {
+        /// 滤镜类型
+        kind: FilterKind,
+        /// 强度（0.0 ~ 1.0）
+        intensity: f64,
+    },
+
     /// 完整重启游戏会话
     ///
     /// 对应 `fullRestart` 语法。
     /// 触发后 host 将持久化 `persistent_variables`，清空会话状态并返回标题画面。
     FullRestart,
 
+    /// 结局标记
+    ///
+    /// 对应 `ending true_end "真结局"` 语法。
+    /// 到达时记入已解锁结局集合，并作为本周目的当前结局，不产生 Command。
+    Ending {
+        /// 结局 id（稳定标识，用于收集与持久化）
+        id: String,
+        /// 结局标题（省略时与 id 相同）
+        title: String,
+    },
+
     /// 视频过场
     ///
     /// 对应 `cutscene "path"` 语法。
@@ -308,6 +545,7 @@ impl ScriptNode {
                 | Self::Extend { .. }
                 | Self::Choice { .. }
                 | Self::Wait { .. }
+                | Self::TimedGoto { .. }
                 | Self::Pause
                 | Self::TitleCard { .. }
                 | Self::Cutscene { .. }
@@ -315,6 +553,34 @@ impl ScriptNode {
         )
     }
 
+    /// 判断节点能否作为 timeline 动作
+    ///
+    /// 只允许不等待、不跳转的显示与音频指令。
+    pub fn is_timeline_action(&self) -> bool {
+        matches!(
+            self,
+            Self::ChangeBG { .. }
+                | Self::SetBackgroundLayers { .. }
+                | Self::FillColor { .. }
+                | Self::ShowCg { .. }
+                | Self::HideCg { .. }
+                | Self::ShowCharacter { .. }
+                | Self::ShowCharacters { .. }
+                | Self::HideCharacter { .. }
+                | Self::SetRimLight { .. }
+                | Self::ClearCharacters
+                | Self::PlayAudio { .. }
+                | Self::PlaySfxPool { .. }
+                | Self::StopBgm
+                | Self::BgmDuck
+                | Self::BgmUnduck
+                | Self::SetBgmVolume { .. }
+                | Self::Rumble { .. }
+                | Self::SetWeather { .. }
+                | Self::SetFilter { .. }
+        )
+    }
+
     /// 判断节点是否是跳转目标
     pub fn is_jump_target(&self) -> bool {
         matches!(self, Self::Label { .. })
@@ -328,15 +594,27 @@ impl ScriptNode {
         }
     }
 
+    /// 如果是书签节点，返回书签名
+    pub fn as_bookmark(&self) -> Option<&str> {
+        match self {
+            Self::Bookmark { name } => Some(name),
+            _ => None,
+        }
+    }
+
     /// 判断节点是否是控制流节点（不产生 Command）
     pub fn is_control_flow(&self) -> bool {
         matches!(
             self,
             Self::Label { .. }
+                | Self::Bookmark { .. }
                 | Self::Goto { .. }
+                | Self::DynamicGoto { .. }
                 | Self::CallScript { .. }
                 | Self::ReturnFromScript
                 | Self::SetVar { .. }
+                | Self::DeclareLocal { .. }
+                | Self::Assert { .. }
                 | Self::Conditional { .. }
         )
     }
@@ -357,6 +635,9 @@ pub struct Script {
     /// 标签到节点索引的映射
     #[serde(skip)]
     label_index: std::collections::HashMap<String, usize>,
+    /// 书签到节点索引的映射
+    #[serde(skip)]
+    bookmark_index: std::collections::HashMap<String, usize>,
     /// 源码行号映射：source_map[node_index] = line_number
     /// 用于诊断输出时定位问题行
     #[serde(skip)]
@@ -382,6 +663,7 @@ impl Script {
             nodes,
             base_path,
             label_index: std::collections::HashMap::new(),
+            bookmark_index: std::collections::HashMap::new(),
             source_map: Vec::new(),
         };
         script.build_label_index();
@@ -408,6 +690,7 @@ impl Script {
             nodes,
             base_path,
             label_index: std::collections::HashMap::new(),
+            bookmark_index: std::collections::HashMap::new(),
             source_map,
         };
         script.build_label_index();
@@ -443,13 +726,17 @@ impl Script {
         format!("{}/{}", self.base_path, relative_path)
     }
 
-    /// 构建标签索引
+    /// 构建标签与书签索引
     fn build_label_index(&mut self) {
         self.label_index.clear();
+        self.bookmark_index.clear();
         for (index, node) in self.nodes.iter().enumerate() {
             if let Some(name) = node.as_label() {
                 self.label_index.insert(name.to_string(), index);
             }
+            if let Some(name) = node.as_bookmark() {
+                self.bookmark_index.insert(name.to_string(), index);
+            }
         }
     }
 
@@ -458,6 +745,27 @@ impl Script {
         self.label_index.get(name).copied()
     }
 
+    /// `index` 处或之前最近的标签名
+    pub fn label_before(&self, index: usize) -> Option<&str> {
+        let end = index.saturating_add(1).min(self.nodes.len());
+        self.nodes[..end]
+            .iter()
+            .rev()
+            .find_map(ScriptNode::as_label)
+    }
+
+    /// 根据书签名查找节点索引
+    pub fn find_bookmark(&self, name: &str) -> Option<usize> {
+        self.bookmark_index.get(name).copied()
+    }
+
+    /// 所有书签名（按名称排序）
+    pub fn bookmarks(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.bookmark_index.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
     /// 获取指定索引的节点
     pub fn get_node(&self, index: usize) -> Option<&ScriptNode> {
         self.nodes.get(index)
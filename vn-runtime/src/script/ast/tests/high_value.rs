@@ -10,11 +10,13 @@ fn test_script_node_causes_wait() {
         content: "Hello".to_string(),
         inline_effects: vec![],
         no_wait: false,
+        text_speed: 1.0,
     };
     assert!(dialogue.causes_wait());
 
     let choice = ScriptNode::Choice {
         style: None,
+        prompt: None,
         options: vec![],
     };
     assert!(choice.causes_wait());
@@ -38,6 +40,7 @@ fn test_script_node_is_jump_target() {
         content: "hi".to_string(),
         inline_effects: vec![],
         no_wait: false,
+        text_speed: 1.0,
     };
     assert!(!dialogue.is_jump_target());
 }
@@ -86,6 +89,7 @@ fn test_script_node_is_control_flow() {
         content: "hi".to_string(),
         inline_effects: vec![],
         no_wait: false,
+        text_speed: 1.0,
     };
     assert!(!dialogue.is_control_flow());
 }
@@ -101,6 +105,7 @@ fn test_script_label_index() {
             content: "Hello".to_string(),
             inline_effects: vec![],
             no_wait: false,
+            text_speed: 1.0,
         },
         ScriptNode::Label {
             name: "end".to_string(),
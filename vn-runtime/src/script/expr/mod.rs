@@ -12,12 +12,17 @@
 //!
 //! - `String`: 字符串
 //! - `Bool`: 布尔值
+//! - `Int` / `Float`: 数字
 //!
 //! ## 支持的操作
 //!
-//! - 比较: `==`, `!=`
+//! - 加法/拼接: `+`（任一侧为字符串时拼接，否则数值相加）
+//! - 比较: `==`, `!=`；大小比较 `<`, `<=`, `>`, `>=`（仅数字）
+//! - 集合成员: `in [字面量, ...]`
 //! - 逻辑: `and`, `or`, `not`
 
+use std::cmp::Ordering;
+
 use serde::{Deserialize, Serialize};
 
 use crate::state::VarValue;
@@ -39,6 +44,15 @@ pub enum Expr {
     /// 不等比较
     NotEq(Box<Expr>, Box<Expr>),
 
+    /// 大小比较（两侧须为数字）
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+
+    /// 集合成员判断：左值等于列表中任一字面量时为真
+    In(Box<Expr>, Vec<VarValue>),
+
+    /// 加法：任一侧为字符串时拼接，否则数值相加
+    Add(Box<Expr>, Box<Expr>),
+
     /// 逻辑与
     And(Box<Expr>, Box<Expr>),
 
@@ -80,6 +94,22 @@ impl Expr {
         Self::NotEq(Box::new(left), Box::new(right))
     }
 
+    /// 创建大小比较
+    pub fn compare(left: Expr, op: CompareOp, right: Expr) -> Self {
+        Self::Compare(Box::new(left), op, Box::new(right))
+    }
+
+    /// 创建集合成员判断
+    pub fn in_list(expr: Expr, values: Vec<VarValue>) -> Self {
+        Self::In(Box::new(expr), values)
+    }
+
+    /// 创建加法（字符串拼接）
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(left: Expr, right: Expr) -> Self {
+        Self::Add(Box::new(left), Box::new(right))
+    }
+
     /// 创建逻辑与
     pub fn and(left: Expr, right: Expr) -> Self {
         Self::And(Box::new(left), Box::new(right))
@@ -97,6 +127,44 @@ impl Expr {
     }
 }
 
+/// 大小比较运算符
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompareOp {
+    /// `<`
+    Less,
+    /// `<=`
+    LessEq,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEq,
+}
+
+impl CompareOp {
+    /// 运算符的脚本写法
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Less => "<",
+            CompareOp::LessEq => "<=",
+            CompareOp::Greater => ">",
+            CompareOp::GreaterEq => ">=",
+        }
+    }
+
+    /// 比较结果是否满足该运算符（`None` 表示含 NaN，恒不满足）
+    fn holds(self, ordering: Option<Ordering>) -> bool {
+        let Some(ordering) = ordering else {
+            return false;
+        };
+        match self {
+            CompareOp::Less => ordering.is_lt(),
+            CompareOp::LessEq => ordering.is_le(),
+            CompareOp::Greater => ordering.is_gt(),
+            CompareOp::GreaterEq => ordering.is_ge(),
+        }
+    }
+}
+
 /// 表达式求值错误
 #[derive(Debug, Clone, PartialEq)]
 pub enum EvalError {
@@ -109,6 +177,9 @@ pub enum EvalError {
         actual: String,
         context: String,
     },
+
+    /// 整数运算溢出
+    IntegerOverflow { context: String },
 }
 
 impl std::fmt::Display for EvalError {
@@ -128,6 +199,9 @@ impl std::fmt::Display for EvalError {
                     expected, actual, context
                 )
             }
+            EvalError::IntegerOverflow { context } => {
+                write!(f, "整数运算溢出 ({})", context)
+            }
         }
     }
 }
@@ -173,6 +247,28 @@ pub fn evaluate(expr: &Expr, ctx: &impl EvalContext) -> Result<VarValue, EvalErr
             Ok(VarValue::Bool(!values_equal(&left_val, &right_val)))
         }
 
+        Expr::Compare(left, op, right) => {
+            let left_val = evaluate(left, ctx)?;
+            let right_val = evaluate(right, ctx)?;
+            let ordering = compare_numbers(&left_val, &right_val, *op)?;
+            Ok(VarValue::Bool(op.holds(ordering)))
+        }
+
+        Expr::In(expr, values) => {
+            let value = evaluate(expr, ctx)?;
+            Ok(VarValue::Bool(
+                values
+                    .iter()
+                    .any(|candidate| values_equal(&value, candidate)),
+            ))
+        }
+
+        Expr::Add(left, right) => {
+            let left_val = evaluate(left, ctx)?;
+            let right_val = evaluate(right, ctx)?;
+            add_values(left_val, right_val)
+        }
+
         Expr::And(left, right) => {
             let left_val = evaluate(left, ctx)?;
             let left_bool = to_bool(&left_val, "and 左操作数")?;
@@ -223,6 +319,81 @@ fn values_equal(left: &VarValue, right: &VarValue) -> bool {
     }
 }
 
+/// 大小比较两个数字；整数与浮点数混合时按浮点数比较
+fn compare_numbers(
+    left: &VarValue,
+    right: &VarValue,
+    op: CompareOp,
+) -> Result<Option<Ordering>, EvalError> {
+    let as_float = |value: &VarValue| match value {
+        VarValue::Int(n) => Some(*n as f64),
+        VarValue::Float(f) => Some(*f),
+        _ => None,
+    };
+    if let (VarValue::Int(a), VarValue::Int(b)) = (left, right) {
+        return Ok(Some(a.cmp(b)));
+    }
+    match (as_float(left), as_float(right)) {
+        (Some(a), Some(b)) => Ok(a.partial_cmp(&b)),
+        (None, _) | (_, None) => {
+            let actual = if as_float(left).is_none() {
+                left
+            } else {
+                right
+            };
+            Err(EvalError::TypeMismatch {
+                expected: "数字",
+                actual: format!("{:?}", actual),
+                context: format!("{} 操作数", op.symbol()),
+            })
+        }
+    }
+}
+
+/// `+` 运算
+///
+/// 任一侧为字符串时把另一侧转为文本后拼接；两侧都是数字时相加，
+/// 整数与浮点数混合时结果为浮点数。
+fn add_values(left: VarValue, right: VarValue) -> Result<VarValue, EvalError> {
+    match (left, right) {
+        (VarValue::Int(a), VarValue::Int(b)) => {
+            a.checked_add(b)
+                .map(VarValue::Int)
+                .ok_or_else(|| EvalError::IntegerOverflow {
+                    context: format!("{} + {}", a, b),
+                })
+        }
+        (VarValue::Float(a), VarValue::Float(b)) => Ok(VarValue::Float(a + b)),
+        (VarValue::Int(a), VarValue::Float(b)) => Ok(VarValue::Float(a as f64 + b)),
+        (VarValue::Float(a), VarValue::Int(b)) => Ok(VarValue::Float(a + b as f64)),
+        (left @ VarValue::String(_), right) | (left, right @ VarValue::String(_)) => Ok(
+            VarValue::String(format!("{}{}", to_text(&left), to_text(&right))),
+        ),
+        (left, right) => {
+            let actual = if matches!(left, VarValue::Bool(_)) {
+                left
+            } else {
+                right
+            };
+            Err(EvalError::TypeMismatch {
+                expected: "数字或字符串",
+                actual: format!("{:?}", actual),
+                context: "+ 操作数".to_string(),
+            })
+        }
+    }
+}
+
+/// 拼接时的文本形式
+fn to_text(value: &VarValue) -> String {
+    match value {
+        VarValue::String(s) => s.clone(),
+        VarValue::Int(n) => n.to_string(),
+        VarValue::Float(f) => f.to_string(),
+        VarValue::Bool(b) => b.to_string(),
+    }
+}
+
 /// 将值转换为布尔值
 fn to_bool(value: &VarValue, context: &str) -> Result<bool, EvalError> {
     match value {
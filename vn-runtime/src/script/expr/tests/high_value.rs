@@ -213,6 +213,40 @@ fn test_complex_expression() {
     assert_eq!(evaluate(&expr, &ctx).unwrap(), VarValue::Bool(true));
 }
 
+#[test]
+fn test_in_list_membership() {
+    let ctx = TestContext::new()
+        .with_var("item", VarValue::String("b".to_string()))
+        .with_var("count", VarValue::Int(3));
+    let strings = || {
+        vec![
+            VarValue::String("a".to_string()),
+            VarValue::String("b".to_string()),
+        ]
+    };
+
+    // 在集合内为真
+    let expr = Expr::in_list(Expr::var("item"), strings());
+    assert_eq!(evaluate(&expr, &ctx).unwrap(), VarValue::Bool(true));
+
+    // 不在集合内为假（不同类型永不相等）
+    let expr = Expr::in_list(Expr::var("count"), strings());
+    assert_eq!(evaluate(&expr, &ctx).unwrap(), VarValue::Bool(false));
+    let expr = Expr::in_list(Expr::var("count"), vec![VarValue::Int(1), VarValue::Int(2)]);
+    assert_eq!(evaluate(&expr, &ctx).unwrap(), VarValue::Bool(false));
+
+    // 空集合为假
+    let expr = Expr::in_list(Expr::var("item"), vec![]);
+    assert_eq!(evaluate(&expr, &ctx).unwrap(), VarValue::Bool(false));
+
+    // 左值未定义照常报错
+    let expr = Expr::in_list(Expr::var("missing"), strings());
+    assert!(matches!(
+        evaluate(&expr, &ctx),
+        Err(EvalError::UndefinedVariable { .. })
+    ));
+}
+
 #[test]
 fn test_evaluate_to_bool() {
     let ctx = TestContext::new().with_var("flag", VarValue::Bool(true));
@@ -228,3 +262,66 @@ fn test_evaluate_to_bool_type_error() {
     let result = evaluate_to_bool(&Expr::var("name"), &ctx);
     assert!(matches!(result, Err(EvalError::TypeMismatch { .. })));
 }
+
+#[test]
+fn test_compare_orders_numbers_and_rejects_other_types() {
+    let ctx = TestContext::new()
+        .with_var("gold", VarValue::Int(5))
+        .with_var("rate", VarValue::Float(0.5));
+    let check = |left: Expr, op: CompareOp, right: Expr| {
+        evaluate_to_bool(&Expr::compare(left, op, right), &ctx).unwrap()
+    };
+
+    assert!(check(Expr::var("gold"), CompareOp::GreaterEq, Expr::int(0)));
+    assert!(check(Expr::var("gold"), CompareOp::GreaterEq, Expr::int(5)));
+    assert!(!check(Expr::var("gold"), CompareOp::Greater, Expr::int(5)));
+    assert!(check(Expr::var("gold"), CompareOp::LessEq, Expr::int(5)));
+    assert!(!check(Expr::var("gold"), CompareOp::Less, Expr::int(5)));
+    // 整数与浮点数混合按浮点数比较
+    assert!(check(Expr::var("rate"), CompareOp::Less, Expr::var("gold")));
+
+    assert!(matches!(
+        evaluate(
+            &Expr::compare(Expr::string("5"), CompareOp::Less, Expr::int(6)),
+            &ctx
+        ),
+        Err(EvalError::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_add_concatenates_strings_and_adds_numbers() {
+    let ctx = TestContext::new()
+        .with_var("name", VarValue::String("北风".to_string()))
+        .with_var("day", VarValue::Int(3));
+
+    // 字符串 + 字符串
+    assert_eq!(
+        evaluate(&Expr::add(Expr::string("你好，"), Expr::var("name")), &ctx).unwrap(),
+        VarValue::String("你好，北风".to_string())
+    );
+    // 字符串 + 整数：整数转为文本（任一侧均可）
+    assert_eq!(
+        evaluate(&Expr::add(Expr::string("第"), Expr::var("day")), &ctx).unwrap(),
+        VarValue::String("第3".to_string())
+    );
+    assert_eq!(
+        evaluate(&Expr::add(Expr::var("day"), Expr::string("天")), &ctx).unwrap(),
+        VarValue::String("3天".to_string())
+    );
+    // 整数 + 整数：数值相加
+    assert_eq!(
+        evaluate(&Expr::add(Expr::var("day"), Expr::int(4)), &ctx).unwrap(),
+        VarValue::Int(7)
+    );
+
+    // 没有字符串时布尔不能参与加法；整数溢出报错
+    assert!(matches!(
+        evaluate(&Expr::add(Expr::bool(true), Expr::int(1)), &ctx),
+        Err(EvalError::TypeMismatch { .. })
+    ));
+    assert!(matches!(
+        evaluate(&Expr::add(Expr::int(i64::MAX), Expr::int(1)), &ctx),
+        Err(EvalError::IntegerOverflow { .. })
+    ));
+}
@@ -0,0 +1,147 @@
+//! # 脚本包含表
+//!
+//! 在 manifest 中显式声明章节脚本的顺序与标题，取代按文件名排序：
+//!
+//! - 声明过的脚本按声明顺序排在前面，未声明的按路径字母序追加在后
+//! - 路径比较前统一分隔符（`\` → `/`）并去掉开头的 `./` 与 `/`
+//! - 声明了但实际不存在的脚本不会出现在结果中；重复声明以第一次为准
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// 包含表中的一条脚本声明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptIndexEntry {
+    /// 脚本路径（相对于资源根目录）
+    pub path: String,
+    /// 显示标题
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// 排序后的脚本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderedScript {
+    /// 脚本路径（保持发现时的原样）
+    pub path: String,
+    /// 包含表中声明的标题
+    pub title: Option<String>,
+    /// 是否在包含表中声明
+    pub declared: bool,
+}
+
+/// 按包含表排列已发现的脚本
+pub fn order_scripts(
+    declared: &[ScriptIndexEntry],
+    discovered: impl IntoIterator<Item = String>,
+) -> Vec<OrderedScript> {
+    let mut rank: HashMap<String, usize> = HashMap::new();
+    for (index, entry) in declared.iter().enumerate() {
+        rank.entry(normalize_script_path(&entry.path))
+            .or_insert(index);
+    }
+
+    let mut listed: Vec<(usize, OrderedScript)> = Vec::new();
+    let mut rest = Vec::new();
+    for path in discovered {
+        match rank.get(&normalize_script_path(&path)) {
+            Some(&index) => listed.push((
+                index,
+                OrderedScript {
+                    path,
+                    title: declared[index].title.clone(),
+                    declared: true,
+                },
+            )),
+            None => rest.push(path),
+        }
+    }
+    listed.sort_by_key(|(index, _)| *index);
+    rest.sort();
+
+    listed
+        .into_iter()
+        .map(|(_, script)| script)
+        .chain(rest.into_iter().map(|path| OrderedScript {
+            path,
+            title: None,
+            declared: false,
+        }))
+        .collect()
+}
+
+fn normalize_script_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut path = path.as_str();
+    while let Some(stripped) = path.strip_prefix("./") {
+        path = stripped;
+    }
+    path.trim_start_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, title: Option<&str>) -> ScriptIndexEntry {
+        ScriptIndexEntry {
+            path: path.to_string(),
+            title: title.map(str::to_string),
+        }
+    }
+
+    fn paths(scripts: &[OrderedScript]) -> Vec<&str> {
+        scripts.iter().map(|s| s.path.as_str()).collect()
+    }
+
+    #[test]
+    fn scripts_follow_declared_order_then_undeclared() {
+        let declared = vec![
+            entry("scripts/prologue.md", Some("序章")),
+            entry("scripts/chapter10.md", Some("第十章")),
+            entry("scripts/chapter2.md", None),
+            entry("scripts/missing.md", Some("不存在")),
+        ];
+        let discovered = [
+            "scripts/chapter10.md",
+            "scripts/bonus_b.md",
+            "scripts/chapter2.md",
+            "scripts/bonus_a.md",
+            "scripts/prologue.md",
+        ]
+        .map(String::from);
+
+        let ordered = order_scripts(&declared, discovered);
+        assert_eq!(
+            paths(&ordered),
+            [
+                "scripts/prologue.md",
+                "scripts/chapter10.md",
+                "scripts/chapter2.md",
+                "scripts/bonus_a.md",
+                "scripts/bonus_b.md",
+            ]
+        );
+        assert_eq!(ordered[0].title.as_deref(), Some("序章"));
+        assert_eq!(ordered[2].title, None);
+        assert!(ordered[2].declared);
+        assert!(!ordered[3].declared);
+    }
+
+    #[test]
+    fn path_separators_and_duplicates_are_normalized() {
+        let declared = vec![
+            entry("./scripts/b.md", Some("B")),
+            entry("scripts/a.md", Some("A")),
+            entry("scripts/b.md", Some("重复")),
+        ];
+        let discovered = ["scripts\\a.md", "scripts/b.md"].map(String::from);
+
+        let ordered = order_scripts(&declared, discovered);
+        assert_eq!(paths(&ordered), ["scripts/b.md", "scripts\\a.md"]);
+        assert_eq!(ordered[0].title.as_deref(), Some("B"));
+        assert_eq!(ordered[1].title.as_deref(), Some("A"));
+    }
+}
@@ -6,12 +6,15 @@
 //!
 //! - [`ast`]：脚本抽象语法树定义
 //! - [`expr`]：表达式 AST 和求值器
+//! - [`index`]：脚本包含表（声明章节脚本顺序）
 //! - [`parser`]：两阶段解析器实现
 
 pub mod ast;
 pub mod expr;
+pub mod index;
 pub mod parser;
 
 pub use ast::*;
-pub use expr::{EvalContext, EvalError, Expr, evaluate, evaluate_to_bool};
-pub use parser::Parser;
+pub use expr::{CompareOp, EvalContext, EvalError, Expr, evaluate, evaluate_to_bool};
+pub use index::{OrderedScript, ScriptIndexEntry, order_scripts};
+pub use parser::{KeywordAliases, LATEST_SYNTAX_VERSION, Parser};
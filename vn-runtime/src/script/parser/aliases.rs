@@ -0,0 +1,180 @@
+//! # 指令关键字别名
+//!
+//! 允许把中文或自定义关键字映射到内部指令关键字（如 `显示` → `show`）。
+//!
+//! 别名只作用于行首的指令关键字：解析单行前，若行首匹配某个别名且其后
+//! 为行尾、空白、`<` 或 `(`，则替换为对应的英文关键字再走正常分发。
+//! 英文关键字始终可用，不受别名表影响。
+
+use std::borrow::Cow;
+
+use crate::error::ParseError;
+
+/// 可作为别名目标的内部指令关键字
+pub const ALIASABLE_KEYWORDS: &[&str] = &[
+    "changeBG",
+    "changeScene",
+    "show",
+    "hide",
+    "rimLight",
+    "goto",
+    "callScript",
+    "returnFromScript",
+    "fullRestart",
+    "ending",
+    "set",
+    "local",
+    "const",
+    "assert",
+    "wait",
+    "pause",
+    "clearCharacters",
+    "stopBGM",
+    "bgmDuck",
+    "bgmUnduck",
+    "bgmVol",
+    "textBoxHide",
+    "textBoxShow",
+    "textBoxClear",
+    "textBoxStyle",
+    "sceneEffect",
+    "titleCard",
+    "rumble",
+    "weather",
+    "filter",
+    "cutscene",
+    "requestUI",
+    "textMode",
+    "showMap",
+    "callGame",
+    "extend",
+    "bubble",
+    "@bookmark",
+];
+
+/// 关键字别名表
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeywordAliases {
+    /// (别名, 内部关键字)，按别名长度降序排列以保证最长匹配
+    entries: Vec<(String, &'static str)>,
+}
+
+impl KeywordAliases {
+    /// 创建空别名表（仅英文关键字）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 内置中文别名预设
+    pub fn chinese() -> Self {
+        let pairs = [
+            ("切换背景", "changeBG"),
+            ("切换场景", "changeScene"),
+            ("显示", "show"),
+            ("隐藏", "hide"),
+            ("轮廓光", "rimLight"),
+            ("跳转", "goto"),
+            ("调用脚本", "callScript"),
+            ("返回脚本", "returnFromScript"),
+            ("重新开始", "fullRestart"),
+            ("结局", "ending"),
+            ("设置", "set"),
+            ("局部", "local"),
+            ("常量", "const"),
+            ("断言", "assert"),
+            ("等待", "wait"),
+            ("暂停", "pause"),
+            ("清除角色", "clearCharacters"),
+            ("停止音乐", "stopBGM"),
+            ("压低音乐", "bgmDuck"),
+            ("恢复音乐", "bgmUnduck"),
+            ("音乐音量", "bgmVol"),
+            ("隐藏对话框", "textBoxHide"),
+            ("显示对话框", "textBoxShow"),
+            ("清空对话框", "textBoxClear"),
+            ("对话框样式", "textBoxStyle"),
+            ("场景效果", "sceneEffect"),
+            ("标题卡", "titleCard"),
+            ("震动", "rumble"),
+            ("天气", "weather"),
+            ("滤镜", "filter"),
+            ("过场", "cutscene"),
+            ("续接", "extend"),
+            ("气泡", "bubble"),
+            ("书签", "@bookmark"),
+        ];
+        Self::from_pairs(pairs).expect("内置中文别名必须全部指向已知指令")
+    }
+
+    /// 从 (别名, 关键字) 列表构建
+    pub fn from_pairs<A, K>(pairs: impl IntoIterator<Item = (A, K)>) -> Result<Self, ParseError>
+    where
+        A: Into<String>,
+        K: AsRef<str>,
+    {
+        let mut aliases = Self::new();
+        for (alias, keyword) in pairs {
+            aliases.insert(alias, keyword.as_ref())?;
+        }
+        Ok(aliases)
+    }
+
+    /// 注册一个别名
+    ///
+    /// 目标关键字大小写不敏感；指向未知指令或别名为空/含空白时返回错误。
+    pub fn insert(&mut self, alias: impl Into<String>, keyword: &str) -> Result<(), ParseError> {
+        let alias = alias.into();
+        let invalid = |message: String| ParseError::InvalidKeywordAlias {
+            alias: alias.clone(),
+            message,
+        };
+
+        let canonical = ALIASABLE_KEYWORDS
+            .iter()
+            .find(|k| k.eq_ignore_ascii_case(keyword))
+            .ok_or_else(|| invalid(format!("未知指令 '{}'", keyword)))?;
+        if alias.is_empty() || alias.contains(char::is_whitespace) {
+            return Err(invalid("别名不能为空或包含空白字符".to_string()));
+        }
+
+        self.entries.retain(|(a, _)| *a != alias);
+        self.entries.push((alias, canonical));
+        self.entries
+            .sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        Ok(())
+    }
+
+    /// 查询别名对应的内部关键字
+    pub fn resolve(&self, alias: &str) -> Option<&'static str> {
+        self.entries
+            .iter()
+            .find(|(a, _)| a == alias)
+            .map(|(_, keyword)| *keyword)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 把行首别名展开为内部关键字；未命中时原样返回
+    pub fn expand<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        for (alias, keyword) in &self.entries {
+            // 按行内实际命中的前缀切分；长度不落在字符边界时 `get` 直接不命中
+            let Some(rest) = line
+                .get(..alias.len())
+                .filter(|head| head.eq_ignore_ascii_case(alias))
+                .map(|head| &line[head.len()..])
+            else {
+                continue;
+            };
+            let boundary = rest
+                .chars()
+                .next()
+                .is_none_or(|c| c.is_whitespace() || c == '<' || c == '(');
+            if boundary {
+                return Cow::Owned(format!("{}{}", keyword, rest));
+            }
+        }
+        Cow::Borrowed(line)
+    }
+}
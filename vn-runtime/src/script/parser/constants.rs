@@ -0,0 +1,115 @@
+//! # 脚本常量
+//!
+//! `const NAME = value` 在解析期声明常量，其后的指令行中出现的 `NAME`
+//! 按原文替换为 `value`。常量不是运行时变量：不进入 AST、不进入存档。
+//!
+//! ## 引用规则
+//!
+//! - 常量名为大写字母开头、只含大写字母/数字/下划线，且至少 2 个字符（如 `BG_ROOM`）
+//! - 只展开指令行的参数部分；行首指令关键字、对话、旁白、章节标题、标签保持原文
+//! - 引号内、`[...]` 链接文本、`](...)` 链接目标、`**label**` 引用与 `as` 之后的别名不展开
+//! - 紧邻 `$` / `.` / `/` / `#` 的词不视为常量（`$BG_ROOM` 仍是变量，路径片段与颜色值不受影响）
+//! - 只替换已声明的常量；未声明的大写词按原文保留，不报错
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// 是否为合法常量名
+pub(super) fn is_constant_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && name.len() >= 2
+        && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// 把行中引用的已声明常量替换为字面值
+pub(super) fn expand_constants<'a>(
+    line: &'a str,
+    constants: &HashMap<String, String>,
+) -> Cow<'a, str> {
+    if constants.is_empty() {
+        return Cow::Borrowed(line);
+    }
+    let mut out = String::with_capacity(line.len());
+    let mut changed = false;
+    // 当前所在的不展开区域的结束符（引号或括号）
+    let mut opaque_end: Option<char> = None;
+    let mut prev: Option<char> = None;
+    // 上一个词是 `as`：其后的别名原样保留
+    let mut after_as = false;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if let Some(end) = opaque_end {
+            if c == end {
+                opaque_end = None;
+            }
+            out.push(c);
+            prev = Some(c);
+            continue;
+        }
+        // `**label**` 引用整体原样保留
+        if line[start..].starts_with("**") {
+            let stop = line[start + 2..]
+                .find("**")
+                .map_or(line.len(), |i| start + 2 + i + 2);
+            out.push_str(&line[start..stop]);
+            while chars.next_if(|&(i, _)| i < stop).is_some() {}
+            prev = Some('*');
+            continue;
+        }
+        opaque_end = match c {
+            '"' | '\'' => Some(c),
+            '\u{201C}' => Some('\u{201D}'),
+            '[' => Some(']'),
+            '(' if prev == Some(']') => Some(')'),
+            _ => None,
+        };
+        if opaque_end.is_some() || !is_word_char(c) {
+            out.push(c);
+            prev = Some(c);
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        while let Some(&(i, next)) = chars.peek() {
+            if !is_word_char(next) {
+                break;
+            }
+            end = i + next.len_utf8();
+            chars.next();
+        }
+        let word = &line[start..end];
+        // 行首词是指令关键字（大小写不敏感，`STOPBGM` 也合法）
+        let keyword = start == 0;
+        let attached = prev.is_some_and(is_attach_char)
+            || chars.peek().is_some_and(|&(_, n)| is_attach_char(n));
+        let value = (!keyword && !attached && !after_as)
+            .then(|| constants.get(word))
+            .flatten();
+        match value {
+            Some(value) => {
+                out.push_str(value);
+                changed = true;
+            }
+            None => out.push_str(word),
+        }
+        after_as = word.eq_ignore_ascii_case("as");
+        prev = word.chars().last();
+    }
+
+    if changed {
+        Cow::Owned(out)
+    } else {
+        Cow::Borrowed(line)
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// 与常量名相邻时表明该词属于变量名、路径或颜色值
+fn is_attach_char(c: char) -> bool {
+    matches!(c, '$' | '.' | '/' | '#')
+}
@@ -1,36 +1,47 @@
 //! # 表达式解析器
 //!
-//! 递归下降表达式解析器，支持变量、字面量、比较和逻辑运算。
+//! 递归下降表达式解析器，支持变量、字面量、加法、比较和逻辑运算。
 
 use crate::error::ParseError;
-use crate::script::Expr;
+use crate::script::{CompareOp, Expr};
+use crate::state::VarValue;
 
 /// 解析表达式字符串
 ///
 /// 支持的语法:
 /// - 字面量: `"string"`, `true`, `false`
 /// - 变量: `$var_name`
+/// - 加法/拼接: `"你好" + $name`, `$score + 1`（优先级高于比较）
 /// - 比较: `$var == "value"`, `$var != "value"`
+/// - 大小比较: `$gold >= 0`, `$hp < 10`（`<`, `<=`, `>`, `>=`）
+/// - 集合成员: `$var in ["a", "b"]`（列表元素只能是字面量）
 /// - 逻辑: `expr and expr`, `expr or expr`, `not expr`
 /// - 括号: `(expr)`
+///
+/// 错误的列号相对 `input` 计算（从 1 开始，按字符计），
+/// 调用方需用 [`ParseError::shift_column`] 换算为所在行的列。
 pub fn parse_expression(input: &str, line_number: usize) -> Result<Expr, ParseError> {
+    let leading = input.len() - input.trim_start().len();
+    let column_base = input[..leading].chars().count();
     let input = input.trim();
     if input.is_empty() {
         return Err(ParseError::InvalidLine {
             line: line_number,
+            column: None,
             message: "空表达式".to_string(),
         });
     }
 
     // 使用简单的递归下降解析器
     let mut parser = ExprParser::new(input, line_number);
+    parser.column_base = column_base;
     let expr = parser.parse_or()?;
     parser.skip_whitespace()?;
     if !parser.remaining().is_empty() {
-        return Err(ParseError::InvalidLine {
-            line: line_number,
-            message: format!("表达式末尾存在无法解析的内容: '{}'", parser.remaining()),
-        });
+        return Err(parser.error_at(
+            parser.pos,
+            format!("表达式末尾存在无法解析的内容: '{}'", parser.remaining()),
+        ));
     }
     Ok(expr)
 }
@@ -40,6 +51,8 @@ struct ExprParser<'a> {
     input: &'a str,
     pos: usize,
     line_number: usize,
+    /// `input` 之前被裁掉的字符数（计算列号用）
+    column_base: usize,
 }
 
 impl<'a> ExprParser<'a> {
@@ -48,6 +61,7 @@ impl<'a> ExprParser<'a> {
             input,
             pos: 0,
             line_number,
+            column_base: 0,
         }
     }
 
@@ -55,22 +69,30 @@ impl<'a> ExprParser<'a> {
         &self.input[self.pos..]
     }
 
-    fn stalled_parse_error(&self, context: &str) -> ParseError {
+    /// 构造指向字节位置 `pos` 的错误
+    fn error_at(&self, pos: usize, message: String) -> ParseError {
+        let pos = pos.min(self.input.len());
+        let column = self.column_base + self.input[..pos].chars().count() + 1;
         ParseError::InvalidLine {
             line: self.line_number,
-            message: format!("{context} 时解析器未前进，已停止以避免死循环"),
+            column: Some(column),
+            message,
         }
     }
 
+    fn stalled_parse_error(&self, context: &str) -> ParseError {
+        self.error_at(
+            self.pos,
+            format!("{context} 时解析器未前进，已停止以避免死循环"),
+        )
+    }
+
     fn advance_bytes(&mut self, byte_count: usize, context: &str) -> Result<(), ParseError> {
         let old_pos = self.pos;
         self.pos = self
             .pos
             .checked_add(byte_count)
-            .ok_or_else(|| ParseError::InvalidLine {
-                line: self.line_number,
-                message: format!("{context} 时解析器位置溢出"),
-            })?;
+            .ok_or_else(|| self.error_at(old_pos, format!("{context} 时解析器位置溢出")))?;
         if self.pos <= old_pos {
             return Err(self.stalled_parse_error(context));
         }
@@ -107,12 +129,11 @@ impl<'a> ExprParser<'a> {
     fn starts_with_keyword(&self, keyword: &str) -> bool {
         let remaining = self.remaining().to_lowercase();
         if remaining.starts_with(&keyword.to_lowercase()) {
-            // 确保后面是空白或结束
+            // 确保后面是空白、括号、列表分隔符或结束
             let after = &self.input[self.pos + keyword.len()..];
             after.is_empty()
                 || after.starts_with(char::is_whitespace)
-                || after.starts_with('(')
-                || after.starts_with(')')
+                || after.starts_with(['(', ')', '[', ']', ','])
         } else {
             false
         }
@@ -173,7 +194,7 @@ impl<'a> ExprParser<'a> {
 
     /// 解析比较表达式
     fn parse_comparison(&mut self) -> Result<Expr, ParseError> {
-        let left = self.parse_primary()?;
+        let left = self.parse_additive()?;
 
         self.skip_whitespace()?;
 
@@ -181,26 +202,100 @@ impl<'a> ExprParser<'a> {
         if self.remaining().starts_with("==") {
             self.advance_bytes(2, "消费比较运算符 '=='")?;
             self.skip_whitespace()?;
-            let right = self.parse_primary()?;
+            let right = self.parse_additive()?;
             Ok(Expr::eq(left, right))
         } else if self.remaining().starts_with("!=") {
             self.advance_bytes(2, "消费比较运算符 '!='")?;
             self.skip_whitespace()?;
-            let right = self.parse_primary()?;
+            let right = self.parse_additive()?;
             Ok(Expr::not_eq(left, right))
+        } else if let Some((op, len)) = self.peek_compare_op() {
+            self.advance_bytes(len, "消费大小比较运算符")?;
+            self.skip_whitespace()?;
+            let right = self.parse_additive()?;
+            Ok(Expr::compare(left, op, right))
+        } else if self.starts_with_keyword("in") {
+            self.consume_keyword("in")?;
+            let values = self.parse_literal_list()?;
+            Ok(Expr::in_list(left, values))
         } else {
             Ok(left)
         }
     }
 
+    /// 识别大小比较运算符，返回运算符及其字节长度（双字符运算符优先）
+    fn peek_compare_op(&self) -> Option<(CompareOp, usize)> {
+        let rest = self.remaining();
+        [
+            ("<=", CompareOp::LessEq),
+            (">=", CompareOp::GreaterEq),
+            ("<", CompareOp::Less),
+            (">", CompareOp::Greater),
+        ]
+        .into_iter()
+        .find(|(symbol, _)| rest.starts_with(symbol))
+        .map(|(symbol, op)| (op, symbol.len()))
+    }
+
+    /// 解析加法表达式（左结合）
+    fn parse_additive(&mut self) -> Result<Expr, ParseError> {
+        let mut left = self.parse_primary()?;
+
+        loop {
+            self.skip_whitespace()?;
+            if self.peek_char() == Some('+') {
+                self.consume_char()?;
+                let right = self.parse_primary()?;
+                left = Expr::add(left, right);
+            } else {
+                break;
+            }
+        }
+
+        Ok(left)
+    }
+
+    /// 解析 `in` 右侧的字面量列表 `[lit, lit, ...]`（允许空列表与末尾逗号）
+    fn parse_literal_list(&mut self) -> Result<Vec<VarValue>, ParseError> {
+        if self.peek_char() != Some('[') {
+            return Err(self.error_at(self.pos, "in 之后需要列表 '[...]'".to_string()));
+        }
+        self.consume_char()?;
+
+        let mut values = Vec::new();
+        loop {
+            self.skip_whitespace()?;
+            if self.peek_char() == Some(']') {
+                self.consume_char()?;
+                return Ok(values);
+            }
+            let item_pos = self.pos;
+            match self.parse_primary()? {
+                Expr::Literal(value) => values.push(value),
+                _ => {
+                    return Err(self.error_at(item_pos, "in 的列表只能包含字面量".to_string()));
+                }
+            }
+            self.skip_whitespace()?;
+            match self.peek_char() {
+                Some(',') => {
+                    self.consume_char()?;
+                }
+                Some(']') => {}
+                _ => {
+                    return Err(self.error_at(self.pos, "列表缺少 ',' 或 ']'".to_string()));
+                }
+            }
+        }
+    }
+
     /// 解析基本表达式
     fn parse_primary(&mut self) -> Result<Expr, ParseError> {
         self.skip_whitespace()?;
 
-        let c = self.peek_char().ok_or_else(|| ParseError::InvalidLine {
-            line: self.line_number,
-            message: "表达式意外结束".to_string(),
-        })?;
+        let c = self
+            .peek_char()
+            .ok_or_else(|| self.error_at(self.pos, "表达式意外结束".to_string()))?;
 
         match c {
             // 括号
@@ -209,10 +304,7 @@ impl<'a> ExprParser<'a> {
                 let expr = self.parse_or()?;
                 self.skip_whitespace()?;
                 if self.peek_char() != Some(')') {
-                    return Err(ParseError::InvalidLine {
-                        line: self.line_number,
-                        message: "缺少右括号 ')'".to_string(),
-                    });
+                    return Err(self.error_at(self.pos, "缺少右括号 ')'".to_string()));
                 }
                 self.consume_char()?;
                 Ok(expr)
@@ -248,10 +340,7 @@ impl<'a> ExprParser<'a> {
                     let num = self.parse_number()?;
                     Ok(Expr::int(num))
                 } else {
-                    Err(ParseError::InvalidLine {
-                        line: self.line_number,
-                        message: format!("无法解析表达式，意外字符: '{}'", c),
-                    })
+                    Err(self.error_at(self.pos, format!("无法解析表达式，意外字符: '{}'", c)))
                 }
             }
         }
@@ -286,10 +375,7 @@ impl<'a> ExprParser<'a> {
         }
 
         if self.pos == start {
-            return Err(ParseError::InvalidLine {
-                line: self.line_number,
-                message: "期望标识符".to_string(),
-            });
+            return Err(self.error_at(self.pos, "期望标识符".to_string()));
         }
 
         Ok(self.input[start..self.pos].to_string())
@@ -297,6 +383,7 @@ impl<'a> ExprParser<'a> {
 
     /// 解析字符串字面量
     fn parse_string_literal(&mut self, quote: char) -> Result<String, ParseError> {
+        let open = self.pos;
         self.consume_char()?; // 消费开始引号
         let start = self.pos;
 
@@ -313,10 +400,8 @@ impl<'a> ExprParser<'a> {
             self.advance_bytes(c.len_utf8(), "解析字符串字面量")?;
         }
 
-        Err(ParseError::InvalidLine {
-            line: self.line_number,
-            message: format!("字符串字面量未闭合，缺少 '{}'", quote),
-        })
+        // 未闭合时指向开始引号
+        Err(self.error_at(open, format!("字符串字面量未闭合，缺少 '{}'", quote)))
     }
 
     /// 解析数字
@@ -341,10 +426,9 @@ impl<'a> ExprParser<'a> {
         }
 
         let num_str = &self.input[start..self.pos];
-        num_str.parse::<i64>().map_err(|_| ParseError::InvalidLine {
-            line: self.line_number,
-            message: format!("无法解析数字: '{}'", num_str),
-        })
+        num_str
+            .parse::<i64>()
+            .map_err(|_| self.error_at(start, format!("无法解析数字: '{}'", num_str)))
     }
 }
 
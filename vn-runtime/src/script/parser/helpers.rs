@@ -3,6 +3,7 @@
 //! 手写的字符串解析辅助函数，无正则依赖。
 
 use crate::command::{Transition, TransitionArg};
+use crate::error::ParseError;
 
 /// 跳过字符串开头的空白字符，返回剩余部分
 pub fn skip_whitespace(s: &str) -> &str {
@@ -120,7 +121,7 @@ pub fn extract_keyword_value<'a>(s: &'a str, keyword: &str) -> Option<&'a str> {
     let remaining_lower = remaining.to_lowercase();
 
     // 查找下一个关键字或边界的位置
-    let terminators = [" with ", " as ", " at ", ">with", ">as", ">at"];
+    let terminators = [" with ", " as ", " at ", " idle ", ">with", ">as", ">at"];
     let mut end_pos = remaining.len();
 
     for term in &terminators {
@@ -352,6 +353,31 @@ pub fn is_table_separator(s: &str) -> bool {
         .all(|c| c == '-' || c == ':' || c == '|' || c.is_whitespace())
 }
 
+/// 按 `|` 拆分表格行并 trim 各单元格，保留空单元格的位置
+pub fn split_table_row(s: &str) -> Vec<&str> {
+    let s = s.trim();
+    let s = s.strip_prefix('|').unwrap_or(s);
+    let s = s.strip_suffix('|').unwrap_or(s);
+    s.split('|').map(str::trim).collect()
+}
+
+/// 把 `part`（`line` 的子切片）内错误的列号换算为相对 `line` 的列号
+///
+/// 用法：`parse_expression(part, n).map_err(relocate_error(line, part))`
+pub fn relocate_error<'a>(
+    line: &'a str,
+    part: &'a str,
+) -> impl FnOnce(ParseError) -> ParseError + 'a {
+    move |err| {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(line.as_ptr() as usize)
+            .and_then(|start| line.get(..start));
+        debug_assert!(offset.is_some(), "part 必须是 line 的子切片");
+        let delta = offset.map_or(0, |prefix| prefix.chars().count());
+        err.shift_column(delta as isize)
+    }
+}
+
 /// 解析对话行，提取说话者和内容
 ///
 /// 支持格式:
@@ -365,10 +391,8 @@ pub fn parse_dialogue(s: &str) -> Option<(Option<String>, String)> {
     // 中文冒号 U+FF1A (：) 或 U+003A (:)
     let (colon_pos, colon_len) = if let Some(pos) = s.find('：') {
         (pos, '：'.len_utf8())
-    } else if let Some(pos) = s.find(':') {
-        (pos, ':'.len_utf8())
     } else {
-        return None;
+        (s.find(':')?, ':'.len_utf8())
     };
 
     let speaker_part = s[..colon_pos].trim();
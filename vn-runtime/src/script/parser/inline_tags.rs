@@ -1,6 +1,7 @@
 //! # 内联标签解析
 //!
-//! 解析对话文本中的节奏控制标签（`{wait}`, `{speed}`, `{/speed}`）。
+//! 解析对话文本中的节奏控制标签（`{wait}`/`{w}`, `{speed}`, `{/speed}`）
+//! 与 Markdown 粗体（`**词**`）。
 //!
 //! 输入为引号内的原始文本，输出为纯文本 + 效果列表。
 //! `-->` 行修饰符由 Phase2 在行级处理，不在本模块范围内。
@@ -15,8 +16,19 @@ pub fn parse_inline_tags(raw: &str) -> (String, Vec<InlineEffect>) {
     let mut plain = String::new();
     let mut effects = Vec::new();
     let mut chars = raw.char_indices().peekable();
+    let mut bold = false;
 
     while let Some(&(byte_idx, ch)) = chars.peek() {
+        if ch == '*'
+            && let Some(kind) = try_parse_bold(raw, byte_idx, bold)
+        {
+            bold = !bold;
+            chars.next();
+            chars.next();
+            let position = plain.chars().count();
+            effects.push(InlineEffect { position, kind });
+            continue;
+        }
         if ch == '{'
             && let Some((tag_byte_end, kind)) = try_parse_tag(raw, byte_idx)
         {
@@ -47,6 +59,21 @@ pub fn parse_inline_tags(raw: &str) -> (String, Vec<InlineEffect>) {
     (plain, effects)
 }
 
+/// 在 `start` 处识别粗体标记 `**`
+///
+/// 粗体未开启时，只有后面还有配对的 `**` 且中间非空才视为起点；
+/// 不成对的 `**` 保留为普通文本。
+fn try_parse_bold(raw: &str, start: usize, bold: bool) -> Option<InlineEffectKind> {
+    let rest = raw[start..].strip_prefix("**")?;
+    if bold {
+        return Some(InlineEffectKind::BoldEnd);
+    }
+    match rest.find("**") {
+        Some(close) if close > 0 => Some(InlineEffectKind::BoldStart),
+        _ => None,
+    }
+}
+
 /// Try to parse a tag starting at `start` (which points to `{`).
 /// Returns `(byte_index_after_closing_brace, InlineEffectKind)` on success.
 fn try_parse_tag(raw: &str, start: usize) -> Option<(usize, InlineEffectKind)> {
@@ -60,11 +87,13 @@ fn try_parse_tag(raw: &str, start: usize) -> Option<(usize, InlineEffectKind)> {
         return Some((tag_end, InlineEffectKind::ResetCps));
     }
 
-    // {wait} or {wait Ns} or {wait N}
-    if inner.eq_ignore_ascii_case("wait") {
+    // {wait} or {wait Ns} or {wait N}；简写 {w} / {w=N}
+    if inner.eq_ignore_ascii_case("wait") || inner.eq_ignore_ascii_case("w") {
         return Some((tag_end, InlineEffectKind::Wait(None)));
     }
-    if let Some(arg) = strip_prefix_ignore_case(inner, "wait ") {
+    if let Some(arg) =
+        strip_prefix_ignore_case(inner, "wait ").or_else(|| strip_prefix_ignore_case(inner, "w="))
+    {
         let arg = arg.trim();
         let num_str = arg.strip_suffix('s').unwrap_or(arg);
         let seconds: f64 = num_str.parse().ok()?;
@@ -140,6 +169,29 @@ mod tests {
         assert_eq!(effects[0].kind, InlineEffectKind::Wait(Some(0.5)));
     }
 
+    #[test]
+    fn wait_shorthand_marks_pause_positions() {
+        let (text, effects) = parse_inline_tags("长句，{w=0.5}停顿后，{w=1s}继续。{w}");
+        assert_eq!(text, "长句，停顿后，继续。");
+        let marks: Vec<_> = effects
+            .iter()
+            .map(|e| (e.position, e.kind.clone()))
+            .collect();
+        assert_eq!(
+            marks,
+            [
+                (3, InlineEffectKind::Wait(Some(0.5))),
+                (7, InlineEffectKind::Wait(Some(1.0))),
+                (10, InlineEffectKind::Wait(None)),
+            ]
+        );
+
+        // 时长非法时保留原文
+        let (text, effects) = parse_inline_tags("{w=0}{w=abc}");
+        assert_eq!(text, "{w=0}{w=abc}");
+        assert!(effects.is_empty());
+    }
+
     #[test]
     fn speed_absolute() {
         let (text, effects) = parse_inline_tags("{speed 20}慢速文本{/speed}");
@@ -205,6 +257,38 @@ mod tests {
         assert!(effects.is_empty());
     }
 
+    #[test]
+    fn bold_markers_become_effects() {
+        let (text, effects) = parse_inline_tags("这是**重点**，{wait}记住");
+        assert_eq!(text, "这是重点，记住");
+        assert_eq!(
+            effects,
+            [
+                InlineEffect {
+                    position: 2,
+                    kind: InlineEffectKind::BoldStart
+                },
+                InlineEffect {
+                    position: 4,
+                    kind: InlineEffectKind::BoldEnd
+                },
+                InlineEffect {
+                    position: 5,
+                    kind: InlineEffectKind::Wait(None)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unpaired_bold_markers_stay_literal() {
+        for raw in ["单个 ** 标记", "空粗体 ****"] {
+            let (text, effects) = parse_inline_tags(raw);
+            assert_eq!(text, raw);
+            assert!(effects.is_empty());
+        }
+    }
+
     #[test]
     fn case_insensitive() {
         let (text, effects) = parse_inline_tags("{Wait 1s}{Speed 2X}快{/Speed}");
@@ -16,16 +16,22 @@
 //!
 //! ## 模块结构
 //!
+//! - `aliases`: 指令关键字别名表
+//! - `constants`: 脚本常量展开
 //! - `helpers`: 辅助解析函数
 //! - `expr_parser`: 表达式解析器
 //! - `phase1`: 块识别
 //! - `phase2`: 块解析
+//! - `syntax`: 脚本语法版本声明
 
+mod aliases;
+mod constants;
 mod expr_parser;
 mod helpers;
 pub(crate) mod inline_tags;
 mod phase1;
 mod phase2;
+mod syntax;
 
 #[cfg(test)]
 mod tests;
@@ -36,10 +42,14 @@ use crate::script::ast::Script;
 use phase1::recognize_blocks;
 use phase2::Phase2Parser;
 
+pub use aliases::{ALIASABLE_KEYWORDS, KeywordAliases};
+pub use syntax::LATEST_SYNTAX_VERSION;
+
 // 重新导出辅助函数供测试使用
 pub use helpers::{
     extract_audio_src, extract_img_src, extract_keyword_value, is_table_separator, parse_arg_value,
-    parse_dialogue, parse_transition, parse_transition_args, starts_with_ignore_case,
+    parse_dialogue, parse_transition, parse_transition_args, split_table_row,
+    starts_with_ignore_case,
 };
 
 // 重新导出表达式解析函数
@@ -53,13 +63,22 @@ pub struct Parser {
 
 #[allow(clippy::new_without_default)]
 impl Parser {
-    /// 创建新的解析器
+    /// 创建新的解析器（仅英文关键字）
     pub fn new() -> Self {
         Self {
             phase2: Phase2Parser::new(),
         }
     }
 
+    /// 创建带关键字别名表的解析器
+    ///
+    /// 英文关键字始终可用，别名表只额外增加可识别的写法。
+    pub fn with_aliases(aliases: KeywordAliases) -> Self {
+        Self {
+            phase2: Phase2Parser::with_aliases(aliases),
+        }
+    }
+
     /// 解析脚本文本
     ///
     /// # 参数
@@ -96,14 +115,51 @@ impl Parser {
         text: &str,
         base_path: &str,
     ) -> Result<Script, ParseError> {
+        let (script, mut errors) = self.parse_blocks(script_id, text, base_path, false);
+        match errors.pop() {
+            Some(e) => Err(e),
+            None => Ok(script),
+        }
+    }
+
+    /// 解析脚本文本（错误恢复模式）
+    ///
+    /// 块级解析错误不会中断解析：跳过出错的块继续，返回已解析的脚本与全部错误
+    /// （按行号顺序），供脚本检查一次性报告。语法版本声明无效时无法继续，
+    /// 返回空脚本与该错误。
+    pub fn parse_collecting_errors(
+        &mut self,
+        script_id: &str,
+        text: &str,
+        base_path: &str,
+    ) -> (Script, Vec<ParseError>) {
+        self.parse_blocks(script_id, text, base_path, true)
+    }
+
+    /// 两阶段解析；`recover` 为 false 时遇到第一个错误即停止
+    fn parse_blocks(
+        &mut self,
+        script_id: &str,
+        text: &str,
+        base_path: &str,
+        recover: bool,
+    ) -> (Script, Vec<ParseError>) {
         self.phase2.reset_state();
 
+        // 语法版本声明（声明行替换为空行，不影响行号）
+        let (syntax_version, text) = match syntax::strip_syntax_declaration(text) {
+            Ok(stripped) => stripped,
+            Err(e) => return (Script::new(script_id, Vec::new(), base_path), vec![e]),
+        };
+        self.phase2.syntax_version = syntax_version;
+
         // 阶段 1：块识别
-        let blocks = recognize_blocks(text);
+        let (blocks, indents) = recognize_blocks(&text);
 
         // 阶段 2：块解析（同时收集行号）
         let mut nodes = Vec::new();
         let mut source_map = Vec::new();
+        let mut errors = Vec::new();
         for block in blocks {
             let line_number = block.start_line();
             match self.phase2.parse_block(block) {
@@ -112,13 +168,23 @@ impl Parser {
                     source_map.push(line_number);
                 }
                 Ok(None) => {} // 跳过（如空内容）
-                Err(e) => return Err(e),
+                // 阶段 1 裁掉了行首缩进，列号需加回
+                Err(e) => {
+                    let indent = e
+                        .line()
+                        .and_then(|line| indents.get(line.checked_sub(1)?))
+                        .copied()
+                        .unwrap_or(0);
+                    errors.push(e.shift_column(indent as isize));
+                    if !recover {
+                        break;
+                    }
+                }
             }
         }
 
-        Ok(Script::with_source_map(
-            script_id, nodes, base_path, source_map,
-        ))
+        let script = Script::with_source_map(script_id, nodes, base_path, source_map);
+        (script, errors)
     }
 
     /// 获取解析过程中的警告
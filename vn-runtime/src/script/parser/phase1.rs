@@ -1,6 +1,6 @@
 //! # 阶段 1：块识别
 //!
-//! 将原始文本按行分组为块（单行、表格、条件块）。
+//! 将原始文本按行分组为块（单行、表格、条件块、timeline 块）。
 
 use super::helpers::starts_with_ignore_case;
 
@@ -15,6 +15,8 @@ pub enum Block {
     Table {
         lines: Vec<String>,
         start_line: usize,
+        /// 紧贴表格上方的引用行（`> 提示`）去掉 `>` 后的文本，作为选择提示
+        prompt: Option<String>,
     },
     /// 条件块（if/elseif/else/endif）
     Conditional {
@@ -22,6 +24,12 @@ pub enum Block {
         lines: Vec<(String, usize)>,
         start_line: usize,
     },
+    /// timeline 块（timeline/endTimeline）
+    Timeline {
+        /// 原始行列表 (line, line_number)，含首尾的 timeline/endTimeline
+        lines: Vec<(String, usize)>,
+        start_line: usize,
+    },
 }
 
 impl Block {
@@ -31,19 +39,26 @@ impl Block {
             Block::SingleLine { line_number, .. } => *line_number,
             Block::Table { start_line, .. } => *start_line,
             Block::Conditional { start_line, .. } => *start_line,
+            Block::Timeline { start_line, .. } => *start_line,
         }
     }
 }
 
 /// 识别文本中的块
-pub fn recognize_blocks(text: &str) -> Vec<Block> {
+///
+/// 同时返回每行被裁掉的行首缩进（字符数，下标为行号 - 1），供阶段 2 的
+/// 错误列号换算回原文。
+pub fn recognize_blocks(text: &str) -> (Vec<Block>, Vec<usize>) {
     let mut blocks = Vec::new();
+    let mut indents = Vec::new();
     let mut current_table: Option<(Vec<String>, usize)> = None;
     let mut current_conditional: Option<ConditionalState> = None;
+    let mut current_timeline: Option<(Vec<(String, usize)>, usize)> = None;
 
     for (line_idx, line) in text.lines().enumerate() {
         let line_number = line_idx + 1;
         let trimmed = line.trim();
+        indents.push(line.chars().take_while(|c| c.is_whitespace()).count());
 
         // 检查是否是条件语句
         let is_if = starts_with_ignore_case(trimmed, "if ");
@@ -76,14 +91,37 @@ pub fn recognize_blocks(text: &str) -> Vec<Block> {
             continue;
         }
 
+        // 处理 timeline 块（块内不识别表格与条件，原样收集到 endTimeline）
+        if let Some((ref mut lines, _start)) = current_timeline {
+            if trimmed.is_empty() {
+                continue;
+            }
+            lines.push((trimmed.to_string(), line_number));
+            if trimmed.eq_ignore_ascii_case("endtimeline") {
+                let (lines, start) = current_timeline
+                    .take()
+                    .expect("invariant: inside timeline block");
+                blocks.push(Block::Timeline {
+                    lines,
+                    start_line: start,
+                });
+            }
+            continue;
+        }
+
+        if trimmed.eq_ignore_ascii_case("timeline") {
+            if let Some((tbl_lines, tbl_start)) = current_table.take() {
+                push_table(&mut blocks, tbl_lines, tbl_start);
+            }
+            current_timeline = Some((vec![(trimmed.to_string(), line_number)], line_number));
+            continue;
+        }
+
         // 开始新的条件块
         if is_if {
             // 先结束任何打开的表格块
             if let Some((tbl_lines, tbl_start)) = current_table.take() {
-                blocks.push(Block::Table {
-                    lines: tbl_lines,
-                    start_line: tbl_start,
-                });
+                push_table(&mut blocks, tbl_lines, tbl_start);
             }
 
             current_conditional = Some((vec![(trimmed.to_string(), line_number)], line_number, 0));
@@ -93,10 +131,7 @@ pub fn recognize_blocks(text: &str) -> Vec<Block> {
         // 空行：结束当前表格块
         if trimmed.is_empty() {
             if let Some((lines, start)) = current_table.take() {
-                blocks.push(Block::Table {
-                    lines,
-                    start_line: start,
-                });
+                push_table(&mut blocks, lines, start);
             }
             continue;
         }
@@ -114,10 +149,7 @@ pub fn recognize_blocks(text: &str) -> Vec<Block> {
         } else {
             // 非表格行：结束当前表格块，创建单行块
             if let Some((lines, start)) = current_table.take() {
-                blocks.push(Block::Table {
-                    lines,
-                    start_line: start,
-                });
+                push_table(&mut blocks, lines, start);
             }
             blocks.push(Block::SingleLine {
                 line: trimmed.to_string(),
@@ -128,7 +160,12 @@ pub fn recognize_blocks(text: &str) -> Vec<Block> {
 
     // 处理末尾的表格块
     if let Some((lines, start)) = current_table {
-        blocks.push(Block::Table {
+        push_table(&mut blocks, lines, start);
+    }
+
+    // 处理未闭合的 timeline 块（添加到 blocks 以便在 parse_block 阶段报错）
+    if let Some((lines, start)) = current_timeline {
+        blocks.push(Block::Timeline {
             lines,
             start_line: start,
         });
@@ -142,5 +179,25 @@ pub fn recognize_blocks(text: &str) -> Vec<Block> {
         });
     }
 
-    blocks
+    (blocks, indents)
+}
+
+/// 结束表格块；紧贴其上一行的 `>` 引用行并入表格作为提示
+fn push_table(blocks: &mut Vec<Block>, lines: Vec<String>, start_line: usize) {
+    let prompt = match blocks.last() {
+        Some(Block::SingleLine { line, line_number })
+            if line.starts_with('>') && line_number + 1 == start_line =>
+        {
+            Some(line[1..].trim().to_string()).filter(|p| !p.is_empty())
+        }
+        _ => None,
+    };
+    if prompt.is_some() {
+        blocks.pop();
+    }
+    blocks.push(Block::Table {
+        lines,
+        start_line,
+        prompt,
+    });
 }
@@ -1,13 +1,71 @@
-//! 控制流指令解析：goto、callScript、conditional、set、wait、choice table
+//! 控制流指令解析：bookmark、ending、goto、callScript、conditional、timeline、set、local、assert、wait、choice table
 
 use crate::error::ParseError;
 use crate::script::Expr;
-use crate::script::ast::{ChoiceOption, ConditionalBranch, ScriptNode};
+use crate::script::ast::{ChoiceAction, ChoiceOption, ConditionalBranch, ScriptNode, TimelineStep};
+use crate::state::persistent_key;
 
+use super::super::constants::{expand_constants, is_constant_name};
 use super::super::expr_parser::parse_expression;
-use super::super::helpers::{is_table_separator, starts_with_ignore_case};
+use super::super::helpers::{
+    is_table_separator, relocate_error, split_table_row, starts_with_ignore_case,
+};
 use super::Phase2Parser;
 
+/// 选择表格的列含义（表头单元格名）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChoiceColumn {
+    Text,
+    Target,
+    Condition,
+    Reason,
+    Action,
+}
+
+impl ChoiceColumn {
+    fn from_header(cell: &str) -> Option<Self> {
+        match cell.to_ascii_lowercase().as_str() {
+            "text" | "文本" => Some(Self::Text),
+            "target" | "跳转" => Some(Self::Target),
+            "condition" | "条件" => Some(Self::Condition),
+            "reason" | "理由" => Some(Self::Reason),
+            "action" | "动作" => Some(Self::Action),
+            _ => None,
+        }
+    }
+}
+
+/// 表头声明的列布局（列号）
+#[derive(Debug, Default)]
+struct ChoiceColumns {
+    text: Option<usize>,
+    target: Option<usize>,
+    condition: Option<usize>,
+    reason: Option<usize>,
+    action: Option<usize>,
+}
+
+impl ChoiceColumns {
+    fn slot(&mut self, column: ChoiceColumn) -> &mut Option<usize> {
+        match column {
+            ChoiceColumn::Text => &mut self.text,
+            ChoiceColumn::Target => &mut self.target,
+            ChoiceColumn::Condition => &mut self.condition,
+            ChoiceColumn::Reason => &mut self.reason,
+            ChoiceColumn::Action => &mut self.action,
+        }
+    }
+}
+
+/// 选择表格一行的原始单元格
+struct ChoiceRow<'a> {
+    text: &'a str,
+    target: &'a str,
+    condition: Option<&'a str>,
+    reason: Option<&'a str>,
+    action: Option<&'a str>,
+}
+
 impl Phase2Parser {
     /// 解析 set 指令
     ///
@@ -18,56 +76,122 @@ impl Phase2Parser {
         line_number: usize,
     ) -> Result<Option<ScriptNode>, ParseError> {
         let content = line[4..].trim();
+        let (name, value) =
+            parse_assignment(content, line_number).map_err(relocate_error(line, content))?;
+        Ok(Some(ScriptNode::SetVar { name, value }))
+    }
 
-        let eq_pos = content
-            .find('=')
-            .ok_or_else(|| ParseError::MissingParameter {
-                line: line_number,
-                command: "set".to_string(),
-                param: "赋值符号 '='".to_string(),
-            })?;
-
-        let var_part = content[..eq_pos].trim();
-        let value_part = content[eq_pos + 1..].trim();
-
-        let var_name = var_part
-            .strip_prefix('$')
-            .ok_or_else(|| ParseError::InvalidLine {
-                line: line_number,
-                message: format!("变量名必须以 '$' 开头，实际: '{}'", var_part),
-            })?;
-
-        if var_name.is_empty() {
-            return Err(ParseError::MissingParameter {
-                line: line_number,
-                command: "set".to_string(),
-                param: "变量名".to_string(),
-            });
-        }
-
-        let is_valid = if let Some(bare) = var_name.strip_prefix("persistent.") {
-            !bare.is_empty() && bare.chars().all(|c| c.is_alphanumeric() || c == '_')
+    /// 解析局部变量声明
+    ///
+    /// 语法: `local $var` 或 `local $var = value`
+    pub(super) fn parse_local_var(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "local", line_number)?;
+        let content = line[6..].trim();
+        let (name, value) = if content.contains('=') {
+            let (name, value) =
+                parse_assignment(content, line_number).map_err(relocate_error(line, content))?;
+            (name, Some(value))
         } else {
-            var_name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            let name = content
+                .strip_prefix('$')
+                .filter(|name| {
+                    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                })
+                .ok_or_else(|| ParseError::InvalidLine {
+                    line: line_number,
+                    column: None,
+                    message: format!(
+                        "local 需要 '$变量名'，变量名只能含字母、数字和下划线，实际: '{}'",
+                        content
+                    ),
+                })?;
+            (name.to_string(), None)
         };
-        if !is_valid {
+        if persistent_key(&name).is_some() {
             return Err(ParseError::InvalidLine {
                 line: line_number,
-                message: format!(
-                    "变量名格式无效。普通变量名只能含字母、数字和下划线；持久变量须为 persistent.<name> 格式，实际: '{}'",
-                    var_name
-                ),
+                column: None,
+                message: format!("持久变量不能声明为局部变量: '${}'", name),
             });
         }
+        Ok(Some(ScriptNode::DeclareLocal { name, value }))
+    }
 
-        let value = parse_expression(value_part, line_number)?;
+    /// 解析断言
+    ///
+    /// 语法: `assert 条件` 或 `assert 条件 "说明"`。
+    /// 整行能解析为表达式时不带说明；否则把末尾的引号字符串当作说明。
+    pub(super) fn parse_assert(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "assert", line_number)?;
+        let content = line[7..].trim();
+        let whole = parse_expression(content, line_number).map_err(relocate_error(line, content));
+        let err = match whole {
+            Ok(condition) => {
+                return Ok(Some(ScriptNode::Assert {
+                    condition,
+                    message: None,
+                }));
+            }
+            Err(err) => err,
+        };
 
-        Ok(Some(ScriptNode::SetVar {
-            name: var_name.to_string(),
-            value,
+        let split = ['"', '\''].into_iter().find_map(|quote| {
+            let body = content.strip_suffix(quote)?;
+            let open = body.rfind(quote)?;
+            let condition = parse_expression(&body[..open], line_number).ok()?;
+            Some((condition, body[open + 1..].to_string()))
+        });
+        let Some((condition, message)) = split else {
+            return Err(err);
+        };
+        Ok(Some(ScriptNode::Assert {
+            condition,
+            message: Some(message),
         }))
     }
 
+    /// 解析常量声明
+    ///
+    /// 语法: `const NAME = value`；值按原文保存（字符串保留引号），可引用此前声明的常量
+    pub(super) fn parse_const(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "const", line_number)?;
+        let content = line[5..].trim();
+        let invalid = |message: String| ParseError::InvalidLine {
+            line: line_number,
+            column: None,
+            message,
+        };
+        let (name, value) = content
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .filter(|(_, value)| !value.is_empty())
+            .ok_or_else(|| invalid(format!("const 需要 'NAME = 值'，实际: '{}'", content)))?;
+        if !is_constant_name(name) {
+            return Err(invalid(format!(
+                "常量名须以大写字母开头、只含大写字母/数字/下划线且至少 2 个字符，实际: '{}'",
+                name
+            )));
+        }
+        if self.constants.contains_key(name) {
+            return Err(invalid(format!("常量 '{}' 重复定义", name)));
+        }
+        let value = expand_constants(value, &self.constants).into_owned();
+        self.constants.insert(name.to_string(), value);
+        Ok(None)
+    }
+
     /// 解析条件块
     pub(super) fn parse_conditional(
         &mut self,
@@ -82,6 +206,7 @@ impl Phase2Parser {
         if !last_line.eq_ignore_ascii_case("endif") {
             return Err(ParseError::InvalidLine {
                 line: start_line,
+                column: None,
                 message: "条件块未闭合，缺少 'endif'".to_string(),
             });
         }
@@ -98,12 +223,16 @@ impl Phase2Parser {
                 if !starts_with_ignore_case(trimmed, "if ") {
                     return Err(ParseError::InvalidLine {
                         line: *line_number,
+                        column: None,
                         message: "条件块必须以 'if' 开头".to_string(),
                     });
                 }
 
-                let condition_str = &trimmed[3..].trim();
-                current_condition = Some(parse_expression(condition_str, *line_number)?);
+                let condition_str = trimmed[3..].trim();
+                current_condition = Some(
+                    parse_expression(condition_str, *line_number)
+                        .map_err(relocate_error(line, condition_str))?,
+                );
                 is_first = false;
                 continue;
             }
@@ -116,8 +245,11 @@ impl Phase2Parser {
                 });
                 current_body_lines.clear();
 
-                let condition_str = &trimmed[7..].trim();
-                current_condition = Some(parse_expression(condition_str, *line_number)?);
+                let condition_str = trimmed[7..].trim();
+                current_condition = Some(
+                    parse_expression(condition_str, *line_number)
+                        .map_err(relocate_error(line, condition_str))?,
+                );
                 continue;
             }
 
@@ -147,6 +279,7 @@ impl Phase2Parser {
         if branches.is_empty() {
             return Err(ParseError::InvalidLine {
                 line: start_line,
+                column: None,
                 message: "条件块没有有效分支".to_string(),
             });
         }
@@ -160,8 +293,22 @@ impl Phase2Parser {
         lines: &[(String, usize)],
     ) -> Result<Vec<ScriptNode>, ParseError> {
         let mut nodes = Vec::new();
-
-        for (line, line_number) in lines {
+        let mut rest = lines;
+
+        while let Some(((line, line_number), tail)) = rest.split_first() {
+            if line.trim().eq_ignore_ascii_case("timeline") {
+                // 分支内的 timeline 块：收集到 endTimeline（未闭合时交给 parse_timeline 报错）
+                let len = rest
+                    .iter()
+                    .position(|(l, _)| l.trim().eq_ignore_ascii_case("endtimeline"))
+                    .map_or(rest.len(), |end| end + 1);
+                if let Some(node) = self.parse_timeline(&rest[..len], *line_number)? {
+                    nodes.push(node);
+                }
+                rest = &rest[len..];
+                continue;
+            }
+            rest = tail;
             if line.trim().is_empty() {
                 continue;
             }
@@ -173,9 +320,152 @@ impl Phase2Parser {
         Ok(nodes)
     }
 
+    /// 解析 timeline 块
+    ///
+    /// 语法：`timeline` 与 `endTimeline` 之间每行 `<偏移秒数> <指令>`，
+    /// 偏移可带 `s` 后缀（如 `0.5s`）；指令仅限显示与音频指令。
+    pub(super) fn parse_timeline(
+        &mut self,
+        lines: &[(String, usize)],
+        start_line: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "timeline", start_line)?;
+
+        let closed = lines
+            .last()
+            .is_some_and(|(l, _)| l.trim().eq_ignore_ascii_case("endtimeline"));
+        if lines.len() < 2 || !closed {
+            return Err(ParseError::InvalidLine {
+                line: start_line,
+                column: None,
+                message: "timeline 块未闭合，缺少 'endTimeline'".to_string(),
+            });
+        }
+
+        let mut steps = Vec::new();
+        for (line, line_number) in &lines[1..lines.len() - 1] {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('>') {
+                continue;
+            }
+            let (offset_str, action) = line
+                .split_once(char::is_whitespace)
+                .map_or((line, ""), |(o, a)| (o, a.trim_start()));
+            let offset = parse_timeline_offset(offset_str, *line_number)?;
+            if action.is_empty() {
+                return Err(ParseError::MissingParameter {
+                    line: *line_number,
+                    command: "timeline".to_string(),
+                    param: "动作指令".to_string(),
+                });
+            }
+
+            let action_column = (line.chars().count() - action.chars().count()) as isize;
+            let node = self
+                .parse_single_line(action, *line_number)
+                .map_err(|err| err.shift_column(action_column))?;
+            match node {
+                Some(node) if node.is_timeline_action() => {
+                    steps.push(TimelineStep { offset, node });
+                }
+                _ => {
+                    return Err(ParseError::InvalidLine {
+                        line: *line_number,
+                        column: None,
+                        message: format!(
+                            "timeline 中不支持该指令: '{}'（仅支持显示与音频指令）",
+                            action
+                        ),
+                    });
+                }
+            }
+        }
+
+        if steps.is_empty() {
+            return Err(ParseError::InvalidLine {
+                line: start_line,
+                column: None,
+                message: "timeline 块没有动作".to_string(),
+            });
+        }
+
+        Ok(Some(ScriptNode::Timeline { steps }))
+    }
+
+    /// 解析书签
+    ///
+    /// 语法: `@bookmark name`
+    pub(super) fn parse_bookmark(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        let name = line["@bookmark".len()..].trim();
+        if name.is_empty() {
+            return Err(ParseError::MissingParameter {
+                line: line_number,
+                command: "@bookmark".to_string(),
+                param: "书签名".to_string(),
+            });
+        }
+        if name.contains(char::is_whitespace) {
+            return Err(ParseError::InvalidParameter {
+                line: line_number,
+                param: "书签名".to_string(),
+                message: format!("书签名不能包含空白字符: {}", name),
+            });
+        }
+
+        Ok(Some(ScriptNode::Bookmark {
+            name: name.to_string(),
+        }))
+    }
+
+    /// 解析结局标记
+    ///
+    /// 语法: `ending id "标题"`（标题可省略，缺省为 id）
+    pub(super) fn parse_ending(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        let content = line["ending".len()..].trim();
+        let (id, rest) = content
+            .split_once(char::is_whitespace)
+            .unwrap_or((content, ""));
+        if id.is_empty() || id.starts_with('"') {
+            return Err(ParseError::MissingParameter {
+                line: line_number,
+                command: "ending".to_string(),
+                param: "结局 id".to_string(),
+            });
+        }
+
+        let rest = rest.trim();
+        let title = if rest.is_empty() {
+            id.to_string()
+        } else {
+            rest.strip_prefix('"')
+                .and_then(|s| s.strip_suffix('"'))
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| ParseError::InvalidParameter {
+                    line: line_number,
+                    param: "title".to_string(),
+                    message: format!("结局标题需用引号包裹: {}", rest),
+                })?
+                .to_string()
+        };
+
+        Ok(Some(ScriptNode::Ending {
+            id: id.to_string(),
+            title,
+        }))
+    }
+
     /// 解析 goto 指令
     ///
-    /// 语法: `goto **label**`
+    /// 语法: `goto **label**`，或 `goto **label** after <duration>`（定时跳转），
+    /// 或 `goto $var`（计算目标，运行时求值为标签名）
     pub(super) fn parse_goto(
         &self,
         line: &str,
@@ -191,6 +481,36 @@ impl Phase2Parser {
                 param: "目标标签".to_string(),
             })?;
 
+        // 定时跳转：末尾 `after <秒数>`（关键字不区分大小写；ASCII 小写化不改变字节偏移）
+        let after = content
+            .to_ascii_lowercase()
+            .rfind(" after ")
+            .map(|pos| (&content[..pos], &content[pos + " after ".len()..]));
+        let (content, duration) = match after {
+            Some((label, duration)) => {
+                self.require_syntax(2, "goto 的 after 子句", line_number)?;
+                (
+                    label.trim(),
+                    Some(parse_timed_goto_duration(duration, line_number)?),
+                )
+            }
+            None => (content, None),
+        };
+
+        if content.starts_with('$') {
+            self.require_syntax(2, "goto 变量目标", line_number)?;
+            if duration.is_some() {
+                return Err(ParseError::InvalidLine {
+                    line: line_number,
+                    column: None,
+                    message: "goto 变量目标暂不支持 after 子句".to_string(),
+                });
+            }
+            let target =
+                parse_expression(content, line_number).map_err(relocate_error(line, content))?;
+            return Ok(Some(ScriptNode::DynamicGoto { target }));
+        }
+
         let target_label =
             if content.starts_with("**") && content.ends_with("**") && content.len() > 4 {
                 content[2..content.len() - 2].trim().to_string()
@@ -209,12 +529,19 @@ impl Phase2Parser {
         if target_label.contains("::") {
             return Err(ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: "暂不支持跨文件 goto，请使用 callScript/returnFromScript 组织流程"
                     .to_string(),
             });
         }
 
-        Ok(Some(ScriptNode::Goto { target_label }))
+        Ok(Some(match duration {
+            Some(duration) => ScriptNode::TimedGoto {
+                target_label,
+                duration,
+            },
+            None => ScriptNode::Goto { target_label },
+        }))
     }
 
     /// 解析 callScript 指令
@@ -238,6 +565,7 @@ impl Phase2Parser {
         let (display_label, path, after_link) =
             super::parse_markdown_link(content).ok_or_else(|| ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message:
                     "callScript 必须使用 Markdown 链接格式，例如 callScript [chapter1](ring/summer/1-1.md)"
                         .to_string(),
@@ -246,6 +574,7 @@ impl Phase2Parser {
         if !after_link.trim().is_empty() {
             return Err(ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: "callScript 链接后不允许额外参数".to_string(),
             });
         }
@@ -291,49 +620,108 @@ impl Phase2Parser {
     }
 
     /// 解析表格块（选择分支）
+    ///
+    /// `prompt` 为紧贴表格上方的引用行；语法版本 1 中引用行只是注释，忽略。
     pub(super) fn parse_table(
         &mut self,
         lines: &[String],
         start_line: usize,
+        prompt: Option<String>,
     ) -> Result<Option<ScriptNode>, ParseError> {
-        if lines.is_empty() {
+        let mut rows = lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| (start_line + idx, line.as_str()))
+            .filter(|(_, line)| !is_table_separator(line));
+        let Some((header_line, header)) = rows.next() else {
             return Ok(None);
-        }
-
-        let mut options = Vec::new();
-        let mut style = None;
-        let mut header_parsed = false;
-
-        for (idx, line) in lines.iter().enumerate() {
-            let line_number = start_line + idx;
-
-            if is_table_separator(line) {
-                continue;
-            }
+        };
 
-            let cells: Vec<&str> = line
+        // 表头含已知列名时按列名映射（语法 2），否则沿用固定列序
+        let columns = if self.syntax_version >= 2 {
+            self.parse_choice_header(&split_table_row(header), header_line)?
+        } else {
+            None
+        };
+        let style = match &columns {
+            Some((style, _)) => style.clone(),
+            None => header
                 .split('|')
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .collect();
+                .map(str::trim)
+                .find(|s| !s.is_empty())
+                .map(str::to_string),
+        };
 
-            if !header_parsed {
-                if !cells.is_empty() {
-                    style = Some(cells[0].to_string());
+        let mut options = Vec::new();
+        for (line_number, line) in rows {
+            let row = match &columns {
+                Some((_, columns)) => {
+                    let cells = split_table_row(line);
+                    let cell = |idx: Option<usize>| {
+                        idx.and_then(|i| cells.get(i).copied())
+                            .filter(|s| !s.is_empty())
+                    };
+                    match (cell(columns.text), cell(columns.target)) {
+                        (Some(text), Some(target)) => Some(ChoiceRow {
+                            text,
+                            target,
+                            condition: cell(columns.condition),
+                            reason: cell(columns.reason),
+                            action: cell(columns.action),
+                        }),
+                        _ => None,
+                    }
                 }
-                header_parsed = true;
-                continue;
-            }
-
-            if cells.len() < 2 {
+                None => {
+                    let cells: Vec<&str> = line
+                        .split('|')
+                        .map(|s| s.trim())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                    // 第三列：可选条件；第四列：条件不满足时的理由
+                    if cells.len() > 2 {
+                        self.require_syntax(2, "选择表格的条件列", line_number)?;
+                    }
+                    (cells.len() >= 2).then(|| ChoiceRow {
+                        text: cells[0],
+                        target: cells[1],
+                        condition: cells.get(2).copied(),
+                        reason: cells.get(3).copied(),
+                        action: None,
+                    })
+                }
+            };
+            let Some(row) = row else {
                 self.warnings
                     .push(format!("第 {} 行：表格行格式不完整，已跳过", line_number));
                 continue;
+            };
+
+            let condition = match row.condition {
+                Some(expr) => {
+                    Some(parse_expression(expr, line_number).map_err(relocate_error(line, expr))?)
+                }
+                None => None,
+            };
+            if condition.is_none() && row.reason.is_some() {
+                self.warnings.push(format!(
+                    "第 {} 行：选项没有条件，禁用理由将被忽略",
+                    line_number
+                ));
             }
+            let actions = match row.action {
+                Some(raw) => {
+                    parse_choice_actions(raw, line_number).map_err(relocate_error(line, raw))?
+                }
+                None => Vec::new(),
+            };
 
             options.push(ChoiceOption {
-                text: cells[0].to_string(),
-                target_label: cells[1].to_string(),
+                text: row.text.to_string(),
+                target_label: row.target.to_string(),
+                condition,
+                disabled_reason: row.reason.map(str::to_string),
+                actions,
             });
         }
 
@@ -344,6 +732,162 @@ impl Phase2Parser {
             });
         }
 
-        Ok(Some(ScriptNode::Choice { style, options }))
+        let prompt = prompt.filter(|_| self.syntax_version >= 2);
+        Ok(Some(ScriptNode::Choice {
+            style,
+            options,
+            prompt,
+        }))
+    }
+
+    /// 按表头单元格名识别列布局；没有任何已知列名时返回 None（固定列序）
+    ///
+    /// 首个单元格不是列名时仍视为分支样式，且该列默认作为 text 列。
+    fn parse_choice_header(
+        &mut self,
+        cells: &[&str],
+        line_number: usize,
+    ) -> Result<Option<(Option<String>, ChoiceColumns)>, ParseError> {
+        if !cells.iter().any(|c| ChoiceColumn::from_header(c).is_some()) {
+            return Ok(None);
+        }
+
+        let mut style = None;
+        let mut columns = ChoiceColumns::default();
+        for (idx, cell) in cells.iter().enumerate() {
+            match ChoiceColumn::from_header(cell) {
+                Some(column) => {
+                    let slot = columns.slot(column);
+                    if slot.is_some() {
+                        return Err(ParseError::InvalidTable {
+                            line: line_number,
+                            message: format!("表头列 '{}' 重复", cell),
+                        });
+                    }
+                    *slot = Some(idx);
+                }
+                None if idx == 0 => {
+                    style = (!cell.is_empty()).then(|| cell.to_string());
+                }
+                None if cell.is_empty() => {}
+                None => self.warnings.push(format!(
+                    "第 {} 行：未知的表头列 '{}'，该列将被忽略",
+                    line_number, cell
+                )),
+            }
+        }
+
+        if columns.text.is_none() && ChoiceColumn::from_header(cells[0]).is_none() {
+            columns.text = Some(0);
+        }
+        if columns.text.is_none() || columns.target.is_none() {
+            return Err(ParseError::InvalidTable {
+                line: line_number,
+                message: "表头缺少 text 或 target 列".to_string(),
+            });
+        }
+        Ok(Some((style, columns)))
+    }
+}
+
+/// 解析 `goto ... after <duration>` 的停留时长（秒，必须为有限正数）
+fn parse_timed_goto_duration(raw: &str, line_number: usize) -> Result<f64, ParseError> {
+    let raw = raw.trim();
+    let duration: f64 = raw.parse().map_err(|_| ParseError::InvalidParameter {
+        line: line_number,
+        param: "after".to_string(),
+        message: format!("无法解析为数字: '{}'", raw),
+    })?;
+    if !(duration.is_finite() && duration > 0.0) {
+        return Err(ParseError::InvalidParameter {
+            line: line_number,
+            param: "after".to_string(),
+            message: format!("定时跳转时长必须为有限正数，实际: {}", raw),
+        });
+    }
+    Ok(duration)
+}
+
+/// 解析赋值语句 `$var = expr`（`set` 指令与选择表格 action 列共用）
+fn parse_assignment(content: &str, line_number: usize) -> Result<(String, Expr), ParseError> {
+    let eq_pos = content
+        .find('=')
+        .ok_or_else(|| ParseError::MissingParameter {
+            line: line_number,
+            command: "set".to_string(),
+            param: "赋值符号 '='".to_string(),
+        })?;
+
+    let var_part = content[..eq_pos].trim();
+    let value_part = content[eq_pos + 1..].trim();
+
+    let var_name = var_part
+        .strip_prefix('$')
+        .ok_or_else(|| ParseError::InvalidLine {
+            line: line_number,
+            column: None,
+            message: format!("变量名必须以 '$' 开头，实际: '{}'", var_part),
+        })?;
+
+    if var_name.is_empty() {
+        return Err(ParseError::MissingParameter {
+            line: line_number,
+            command: "set".to_string(),
+            param: "变量名".to_string(),
+        });
+    }
+
+    let is_valid = if let Some(bare) = var_name.strip_prefix("persistent.") {
+        !bare.is_empty() && bare.chars().all(|c| c.is_alphanumeric() || c == '_')
+    } else {
+        var_name.chars().all(|c| c.is_alphanumeric() || c == '_')
+    };
+    if !is_valid {
+        return Err(ParseError::InvalidLine {
+            line: line_number,
+            column: None,
+            message: format!(
+                "变量名格式无效。普通变量名只能含字母、数字和下划线；持久变量须为 persistent.<name> 格式，实际: '{}'",
+                var_name
+            ),
+        });
+    }
+
+    let value =
+        parse_expression(value_part, line_number).map_err(relocate_error(content, value_part))?;
+    Ok((var_name.to_string(), value))
+}
+
+/// 解析 action 列：以 `;` 分隔的若干 `$var = expr`（可带 `set` 前缀）
+fn parse_choice_actions(raw: &str, line_number: usize) -> Result<Vec<ChoiceAction>, ParseError> {
+    raw.split(';')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|stmt| {
+            let stmt = if starts_with_ignore_case(stmt, "set ") {
+                stmt[4..].trim()
+            } else {
+                stmt
+            };
+            let (name, value) =
+                parse_assignment(stmt, line_number).map_err(relocate_error(raw, stmt))?;
+            Ok(ChoiceAction { name, value })
+        })
+        .collect()
+}
+
+/// 解析 timeline 动作的时间偏移（秒，可带 `s` 后缀，不能为负）
+fn parse_timeline_offset(text: &str, line_number: usize) -> Result<f64, ParseError> {
+    let number = text
+        .strip_suffix('s')
+        .or_else(|| text.strip_suffix('S'))
+        .unwrap_or(text);
+    match number.parse::<f64>() {
+        Ok(offset) if offset.is_finite() && offset >= 0.0 => Ok(offset),
+        _ => Err(ParseError::InvalidParameter {
+            line: line_number,
+            param: "offset".to_string(),
+            message: format!("时间偏移必须是非负秒数，实际: '{}'", text),
+        }),
     }
 }
@@ -1,4 +1,4 @@
-//! 对话与文本指令解析：chapter、extend
+//! 对话与文本指令解析：chapter、extend、bubble、对话速度标注
 
 use crate::error::ParseError;
 use crate::script::ast::ScriptNode;
@@ -7,7 +7,65 @@ use super::super::helpers::extract_quoted_content;
 use super::super::inline_tags::parse_inline_tags;
 use super::Phase2Parser;
 
+/// 整行速度系数下限
+pub(crate) const TEXT_SPEED_MIN: f64 = 0.1;
+/// 整行速度系数上限
+pub(crate) const TEXT_SPEED_MAX: f64 = 10.0;
+
 impl Phase2Parser {
+    /// 拆分说话者末尾的 `[key=value]` 标注
+    ///
+    /// 语法: `北风[speed=0.5]："..."`、`[speed=2]："..."`（旁白）。
+    /// 返回去掉标注后的说话者和速度系数（缺省 1.0）。
+    pub(super) fn split_speaker_annotation(
+        &mut self,
+        speaker: Option<String>,
+        line_number: usize,
+    ) -> Result<(Option<String>, f64), ParseError> {
+        let Some(raw) = speaker else {
+            return Ok((None, 1.0));
+        };
+        let Some(body) = raw.strip_suffix(']') else {
+            return Ok((Some(raw), 1.0));
+        };
+        let Some(open) = body.rfind('[') else {
+            return Ok((Some(raw), 1.0));
+        };
+        let annotation = &body[open + 1..];
+        if !annotation.contains('=') {
+            return Ok((Some(raw), 1.0));
+        }
+
+        let mut text_speed = 1.0;
+        for pair in annotation.split(',') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let (key, value) = (key.trim(), value.trim());
+            if !key.eq_ignore_ascii_case("speed") {
+                self.warnings.push(format!(
+                    "第 {} 行：未知的对话标注 '{}'，已忽略",
+                    line_number, key
+                ));
+                continue;
+            }
+            let raw_speed: f64 = value.parse().map_err(|_| ParseError::InvalidParameter {
+                line: line_number,
+                param: "speed".to_string(),
+                message: format!("expected a number, got: '{}'", value),
+            })?;
+            text_speed = raw_speed.clamp(TEXT_SPEED_MIN, TEXT_SPEED_MAX);
+            if text_speed != raw_speed {
+                self.warnings.push(format!(
+                    "第 {} 行：对话速度 {} 超出 {}~{}，已钳制为 {}",
+                    line_number, raw_speed, TEXT_SPEED_MIN, TEXT_SPEED_MAX, text_speed
+                ));
+            }
+        }
+
+        let name = body[..open].trim();
+        let speaker = (!name.is_empty()).then(|| name.to_string());
+        Ok((speaker, text_speed))
+    }
+
     /// 解析章节标记
     pub(super) fn parse_chapter(&self, line: &str) -> Result<Option<ScriptNode>, ParseError> {
         let level = line.chars().take_while(|&c| c == '#').count() as u8;
@@ -60,4 +118,44 @@ impl Phase2Parser {
             no_wait,
         }))
     }
+
+    /// 解析角色气泡
+    ///
+    /// 语法: `bubble royu "心声"` / `bubble royu “心声”`；`bubble royu` 清除该角色的气泡
+    pub(super) fn parse_bubble(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "bubble", line_number)?;
+        let rest = line.get("bubble".len()..).unwrap_or_default().trim();
+        let (alias, content_part) = rest
+            .split_once(char::is_whitespace)
+            .map(|(alias, content)| (alias, content.trim()))
+            .unwrap_or((rest, ""));
+        if alias.is_empty() {
+            return Err(ParseError::MissingParameter {
+                line: line_number,
+                command: "bubble".to_string(),
+                param: "角色别名".to_string(),
+            });
+        }
+
+        let content = if content_part.is_empty() {
+            String::new()
+        } else {
+            extract_quoted_content(content_part)
+                .ok_or_else(|| ParseError::InvalidParameter {
+                    line: line_number,
+                    param: "text".to_string(),
+                    message: format!("气泡文本需要用引号包围: '{}'", content_part),
+                })?
+                .to_string()
+        };
+
+        Ok(Some(ScriptNode::ShowBubble {
+            alias: alias.to_string(),
+            content,
+        }))
+    }
 }
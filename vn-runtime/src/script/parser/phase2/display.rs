@@ -1,6 +1,6 @@
-//! 显示相关指令解析：changeBG、changeScene、show、hide + 过渡效果提取
+//! 显示相关指令解析：changeBG、changeScene、bgLayers、fill、show、hide、rimLight、showcg + 过渡效果提取
 
-use crate::command::{Position, Transition, TransitionArg};
+use crate::command::{BackgroundLayer, Color, Position, Transition, TransitionArg};
 use crate::error::ParseError;
 use crate::script::ast::ScriptNode;
 
@@ -10,6 +10,9 @@ use super::super::helpers::{
 
 use super::Phase2Parser;
 
+/// `rimLight` 未指定强度时的默认值
+pub(crate) const RIM_LIGHT_DEFAULT_INTENSITY: f64 = 1.0;
+
 impl Phase2Parser {
     /// 解析 changeBG 指令
     ///
@@ -100,11 +103,202 @@ impl Phase2Parser {
         }))
     }
 
+    /// 解析 default_transition 指令
+    ///
+    /// 语法: `default_transition dissolve 0.3`、`default_transition Fade(duration: 1)`；
+    /// `default_transition none` 取消默认过渡。指令本身不产生节点
+    pub(super) fn parse_default_transition(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "default_transition", line_number)?;
+        let rest = line.trim()["default_transition".len()..].trim();
+        let missing = || ParseError::MissingParameter {
+            line: line_number,
+            command: "default_transition".to_string(),
+            param: "过渡效果".to_string(),
+        };
+        if rest.is_empty() {
+            return Err(missing());
+        }
+        if rest.eq_ignore_ascii_case("none") {
+            self.default_transition = None;
+            return Ok(None);
+        }
+
+        let transition = if rest.contains('(') {
+            parse_transition(rest).ok_or_else(|| ParseError::InvalidTransition {
+                line: line_number,
+                message: format!("无法解析默认过渡: '{rest}'"),
+            })?
+        } else {
+            let mut parts = rest.split_whitespace();
+            let name = parts.next().ok_or_else(missing)?;
+            match parts.next() {
+                None => Transition::simple(name),
+                Some(raw) => {
+                    let duration = raw
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|d| d.is_finite() && *d >= 0.0)
+                        .ok_or_else(|| ParseError::InvalidParameter {
+                            line: line_number,
+                            param: "duration".to_string(),
+                            message: format!("过渡时长必须是非负数: '{raw}'"),
+                        })?;
+                    Transition::with_args(name, vec![TransitionArg::Number(duration)])
+                }
+            }
+        };
+        self.default_transition = Some(transition);
+        Ok(None)
+    }
+
+    /// 为未显式指定过渡的 show/hide/changeBG 套用默认过渡
+    ///
+    /// - 只作用于带立绘路径的 show：不带路径的 `show alias at pos` / `show a, b auto`
+    ///   是对已绑定角色的移动，保持原样
+    /// - changeBG 只支持 dissolve，默认过渡不是 dissolve 时保持无过渡
+    pub(super) fn apply_default_transition(&self, mut node: ScriptNode) -> ScriptNode {
+        let Some(default) = &self.default_transition else {
+            return node;
+        };
+        match &mut node {
+            ScriptNode::ShowCharacter {
+                path: Some(_),
+                transition,
+                ..
+            }
+            | ScriptNode::HideCharacter { transition, .. }
+                if transition.is_none() =>
+            {
+                *transition = Some(default.clone());
+            }
+            ScriptNode::ChangeBG { transition, .. }
+                if transition.is_none() && default.name.eq_ignore_ascii_case("dissolve") =>
+            {
+                *transition = Some(default.clone());
+            }
+            _ => {}
+        }
+        node
+    }
+
+    /// 解析 bgLayers 指令
+    ///
+    /// 语法: `bgLayers <img src="far.png" /> 0.2, <img src="near.png" /> 1`，
+    /// 各层按远到近书写，`<img>` 后的数字为视差系数，省略时为 1
+    pub(super) fn parse_background_layers(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "bgLayers", line_number)?;
+        let mut layers = Vec::new();
+        let mut rest = line;
+        while let Some(start) = rest.find("<img") {
+            let chunk = &rest[start..];
+            let path = extract_img_src(chunk).ok_or_else(|| ParseError::MissingParameter {
+                line: line_number,
+                command: "bgLayers".to_string(),
+                param: "图片路径 (<img src=\"...\">)".to_string(),
+            })?;
+            let after_tag = chunk.find('>').map_or("", |end| &chunk[end + 1..]);
+            let next = after_tag.find("<img").unwrap_or(after_tag.len());
+            let raw = after_tag[..next].trim().trim_end_matches(',').trim();
+            let parallax = if raw.is_empty() {
+                1.0
+            } else {
+                raw.parse::<f64>()
+                    .ok()
+                    .filter(|p| p.is_finite() && *p >= 0.0)
+                    .ok_or_else(|| ParseError::InvalidParameter {
+                        line: line_number,
+                        param: "parallax".to_string(),
+                        message: format!("视差系数必须是非负数: '{raw}'"),
+                    })?
+            };
+            layers.push(BackgroundLayer {
+                path: path.to_string(),
+                parallax,
+            });
+            rest = &after_tag[next..];
+        }
+
+        if layers.is_empty() {
+            return Err(ParseError::MissingParameter {
+                line: line_number,
+                command: "bgLayers".to_string(),
+                param: "图层 (<img src=\"...\"> 视差系数)".to_string(),
+            });
+        }
+        Ok(Some(ScriptNode::SetBackgroundLayers { layers }))
+    }
+
+    /// 解析 fill 指令
+    ///
+    /// 语法: `fill #000000 with fade`，颜色支持 `#rrggbb`、`#rgb` 与命名色
+    pub(super) fn parse_fill(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "fill", line_number)?;
+        let raw = line
+            .split_whitespace()
+            .nth(1)
+            .filter(|c| !c.eq_ignore_ascii_case("with"))
+            .ok_or_else(|| ParseError::MissingParameter {
+                line: line_number,
+                command: "fill".to_string(),
+                param: "颜色".to_string(),
+            })?;
+        let color = Color::parse(raw).ok_or_else(|| ParseError::InvalidParameter {
+            line: line_number,
+            param: "color".to_string(),
+            message: format!("无法识别的颜色: '{raw}'（支持 #rrggbb、#rgb 或命名色）"),
+        })?;
+
+        Ok(Some(ScriptNode::FillColor {
+            color,
+            transition: self.extract_transition_from_line(line),
+        }))
+    }
+
+    /// 解析 showcg 指令
+    ///
+    /// 路径可写成 `<img src="...">` 或直接写文件名：`showcg cg1.jpg with fade`
+    pub(super) fn parse_show_cg(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "showcg", line_number)?;
+        let path = extract_img_src(line)
+            .or_else(|| {
+                line.split_whitespace()
+                    .nth(1)
+                    .filter(|p| !p.eq_ignore_ascii_case("with"))
+            })
+            .ok_or_else(|| ParseError::MissingParameter {
+                line: line_number,
+                command: "showcg".to_string(),
+                param: "CG 图片路径".to_string(),
+            })?;
+
+        Ok(Some(ScriptNode::ShowCg {
+            path: path.to_string(),
+            transition: self.extract_transition_from_line(line),
+        }))
+    }
+
     /// 解析 show 指令
     ///
-    /// 支持两种格式：
+    /// 支持三种格式：
     /// - `show <img src="..."> as alias at position` - 显示新立绘并绑定别名
     /// - `show alias at position` - 使用已绑定的别名改变位置
+    /// - `show a, b, c auto` - 已绑定的角色按自动布局均匀分布
     pub(super) fn parse_show(
         &mut self,
         line: &str,
@@ -126,10 +320,19 @@ impl Phase2Parser {
                 .find("show")
                 .ok_or_else(|| ParseError::InvalidLine {
                     line: line_number,
+                    column: None,
                     message: "无法找到 'show' 关键字".to_string(),
                 })?;
             let after_show = &line[show_pos + 4..].trim_start();
 
+            if let Some(aliases) = parse_auto_aliases(after_show) {
+                self.require_syntax(2, "show 自动布局", line_number)?;
+                return Ok(Some(ScriptNode::ShowCharacters {
+                    aliases,
+                    transition: self.extract_transition_from_line(line),
+                }));
+            }
+
             let at_pos = after_show.to_lowercase().find(" at ").ok_or_else(|| {
                 ParseError::MissingParameter {
                     line: line_number,
@@ -156,14 +359,24 @@ impl Phase2Parser {
                     param: "position".to_string(),
                     message: format!("未知位置 '{}'", position_str),
                 })?;
+        if position == Position::Auto {
+            self.require_syntax(2, "show 自动布局", line_number)?;
+        }
 
         let transition = self.extract_transition_from_line(line);
+        let idle = extract_keyword_value(line, "idle")
+            .and_then(|v| v.split_whitespace().next())
+            .map(str::to_lowercase);
+        if idle.is_some() {
+            self.require_syntax(2, "show 的 idle 子句", line_number)?;
+        }
 
         Ok(Some(ScriptNode::ShowCharacter {
             path,
             alias,
             position,
             transition,
+            idle,
         }))
     }
 
@@ -189,6 +402,47 @@ impl Phase2Parser {
         Ok(Some(ScriptNode::HideCharacter { alias, transition }))
     }
 
+    /// 解析轮廓光指令
+    ///
+    /// 语法: `rimLight alias` / `rimLight alias 0.6` / `rimLight alias off`
+    pub(super) fn parse_rim_light(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "rimLight", line_number)?;
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let alias = parts
+            .get(1)
+            .ok_or_else(|| ParseError::MissingParameter {
+                line: line_number,
+                command: "rimLight".to_string(),
+                param: "别名".to_string(),
+            })?
+            .to_string();
+
+        let raw_intensity = match parts.get(2) {
+            None => RIM_LIGHT_DEFAULT_INTENSITY,
+            Some(value) if value.eq_ignore_ascii_case("off") => 0.0,
+            Some(value) => value
+                .parse::<f64>()
+                .map_err(|_| ParseError::InvalidParameter {
+                    line: line_number,
+                    param: "intensity".to_string(),
+                    message: format!("expected a number or 'off', got: '{}'", value),
+                })?,
+        };
+        let intensity = raw_intensity.clamp(0.0, 1.0);
+        if intensity != raw_intensity {
+            self.warnings.push(format!(
+                "第 {} 行：rimLight 强度 {} 超出 0~1，已钳制为 {}",
+                line_number, raw_intensity, intensity
+            ));
+        }
+
+        Ok(Some(ScriptNode::SetRimLight { alias, intensity }))
+    }
+
     /// 从行中提取 with 子句的过渡效果
     ///
     /// 支持多种格式：
@@ -267,3 +521,26 @@ impl Phase2Parser {
         args
     }
 }
+
+/// 解析 `a, b, c auto [with ...]` 中的别名列表；不是该形式时返回 None
+fn parse_auto_aliases(after_show: &str) -> Option<Vec<String>> {
+    let head = match after_show.to_lowercase().find(" with ") {
+        Some(with_pos) => &after_show[..with_pos],
+        None => after_show,
+    }
+    .trim_end();
+    let names = head
+        .len()
+        .checked_sub(4)
+        .and_then(|split| head.get(split..).map(|tail| (split, tail)))
+        .filter(|(_, tail)| tail.eq_ignore_ascii_case("auto"))
+        .map(|(split, _)| &head[..split])?;
+    if !names.ends_with(char::is_whitespace) {
+        return None;
+    }
+    let aliases: Vec<String> = names.split(',').map(|a| a.trim().to_string()).collect();
+    aliases
+        .iter()
+        .all(|a| !a.is_empty() && !a.contains(char::is_whitespace))
+        .then_some(aliases)
+}
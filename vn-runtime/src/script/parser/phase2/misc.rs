@@ -1,14 +1,29 @@
-//! 杂项指令解析：audio、sceneEffect、titleCard、cutscene、requestUI、textMode
+//! 杂项指令解析：audio、bgmVol、playSfx、sceneEffect、titleCard、rumble、weather、filter、cutscene、requestUI、textMode
 
-use crate::command::{TextMode, TransitionArg};
+use crate::command::{FilterKind, TextMode, TransitionArg, WeatherKind};
 use crate::error::ParseError;
 use crate::script::Expr;
 use crate::script::ast::ScriptNode;
 
 use super::super::expr_parser::parse_expression;
-use super::super::helpers::{extract_audio_src, parse_transition, parse_transition_args};
+use super::super::helpers::{
+    extract_audio_src, parse_transition, parse_transition_args, relocate_error,
+};
 use super::Phase2Parser;
 
+/// `rumble` 未指定强度时的默认值
+pub(crate) const RUMBLE_DEFAULT_INTENSITY: f64 = 0.5;
+/// `rumble` 未指定时长时的默认值（秒）
+pub(crate) const RUMBLE_DEFAULT_DURATION: f64 = 0.3;
+/// `rumble` 时长上限（秒），避免脚本笔误导致长时间震动
+pub(crate) const RUMBLE_MAX_DURATION: f64 = 5.0;
+/// `weather` 未指定强度时的默认值
+pub(crate) const WEATHER_DEFAULT_INTENSITY: f64 = 0.5;
+/// `filter` 未指定强度时的默认值
+pub(crate) const FILTER_DEFAULT_INTENSITY: f64 = 1.0;
+/// `bgmVol` 未写 `over` 时的过渡时长（秒）
+pub(crate) const BGM_VOLUME_DEFAULT_DURATION: f64 = 0.5;
+
 impl Phase2Parser {
     /// 解析 audio 标签
     ///
@@ -39,6 +54,31 @@ impl Phase2Parser {
         }))
     }
 
+    /// 解析音效池播放
+    ///
+    /// 语法: `playSfx @池名`
+    pub(super) fn parse_play_sfx(
+        &self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "playSfx", line_number)?;
+        let arg = line["playSfx".len()..].trim();
+        let pool = arg
+            .strip_prefix('@')
+            .filter(|name| {
+                !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            })
+            .ok_or_else(|| ParseError::InvalidParameter {
+                line: line_number,
+                param: "pool".to_string(),
+                message: format!("playSfx 需要 '@池名'，实际: '{}'", arg),
+            })?;
+        Ok(Some(ScriptNode::PlaySfxPool {
+            pool: pool.to_string(),
+        }))
+    }
+
     /// 解析场景效果命令
     ///
     /// 语法: `sceneEffect name` 或 `sceneEffect name(args...)`
@@ -148,6 +188,238 @@ impl Phase2Parser {
         Ok(Some(ScriptNode::TitleCard { text, duration }))
     }
 
+    /// 解析手柄震动命令
+    ///
+    /// 语法: `rumble`、`rumble(0.8, 0.5)` 或 `rumble(intensity: 0.8, duration: 0.5)`
+    ///
+    /// 缺省参数使用默认值；越界参数钳制到合法范围并产生警告。
+    pub(super) fn parse_rumble(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "rumble", line_number)?;
+        let effect = parse_transition(line).ok_or_else(|| ParseError::InvalidParameter {
+            line: line_number,
+            param: "rumble".to_string(),
+            message: format!("unable to parse rumble arguments: '{}'", line),
+        })?;
+
+        let number_arg = |key: &str, index: usize| -> Result<Option<f64>, ParseError> {
+            match effect.get_arg(key, index) {
+                None => Ok(None),
+                Some(TransitionArg::Number(n)) => Ok(Some(*n)),
+                Some(other) => Err(ParseError::InvalidParameter {
+                    line: line_number,
+                    param: key.to_string(),
+                    message: format!("expected a number, got: {:?}", other),
+                }),
+            }
+        };
+
+        let raw_intensity = number_arg("intensity", 0)?.unwrap_or(RUMBLE_DEFAULT_INTENSITY);
+        let raw_duration = number_arg("duration", 1)?.unwrap_or(RUMBLE_DEFAULT_DURATION);
+
+        let intensity = raw_intensity.clamp(0.0, 1.0);
+        if intensity != raw_intensity {
+            self.warnings.push(format!(
+                "第 {} 行：rumble 强度 {} 超出 0~1，已钳制为 {}",
+                line_number, raw_intensity, intensity
+            ));
+        }
+        let duration = raw_duration.clamp(0.0, RUMBLE_MAX_DURATION);
+        if duration != raw_duration {
+            self.warnings.push(format!(
+                "第 {} 行：rumble 时长 {} 超出 0~{}，已钳制为 {}",
+                line_number, raw_duration, RUMBLE_MAX_DURATION, duration
+            ));
+        }
+
+        Ok(Some(ScriptNode::Rumble {
+            intensity,
+            duration,
+        }))
+    }
+
+    /// 解析天气命令
+    ///
+    /// 语法: `weather rain` / `weather snow(intensity: 0.8)` / `weather rain(0.3)` / `weather none`
+    pub(super) fn parse_weather(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "weather", line_number)?;
+        let content = line
+            .get("weather".len()..)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| ParseError::MissingParameter {
+                line: line_number,
+                command: "weather".to_string(),
+                param: "weather kind".to_string(),
+            })?;
+
+        let effect = parse_transition(content).ok_or_else(|| ParseError::InvalidParameter {
+            line: line_number,
+            param: "weather".to_string(),
+            message: format!("unable to parse weather arguments: '{}'", content),
+        })?;
+
+        let kind =
+            WeatherKind::from_name(&effect.name).ok_or_else(|| ParseError::InvalidParameter {
+                line: line_number,
+                param: "kind".to_string(),
+                message: format!("unknown weather '{}', expected rain/snow/none", effect.name),
+            })?;
+
+        let raw_intensity = match effect.get_arg("intensity", 0) {
+            None => WEATHER_DEFAULT_INTENSITY,
+            Some(TransitionArg::Number(n)) => *n,
+            Some(other) => {
+                return Err(ParseError::InvalidParameter {
+                    line: line_number,
+                    param: "intensity".to_string(),
+                    message: format!("expected a number, got: {:?}", other),
+                });
+            }
+        };
+        let intensity = raw_intensity.clamp(0.0, 1.0);
+        if intensity != raw_intensity {
+            self.warnings.push(format!(
+                "第 {} 行：weather 强度 {} 超出 0~1，已钳制为 {}",
+                line_number, raw_intensity, intensity
+            ));
+        }
+
+        Ok(Some(ScriptNode::SetWeather { kind, intensity }))
+    }
+
+    /// 解析滤镜命令
+    ///
+    /// 语法: `filter grayscale` / `filter sepia(0.6)` / `filter blur(intensity: 0.5)` / `filter none`
+    pub(super) fn parse_filter(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "filter", line_number)?;
+        let content = line
+            .get("filter".len()..)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| ParseError::MissingParameter {
+                line: line_number,
+                command: "filter".to_string(),
+                param: "filter kind".to_string(),
+            })?;
+
+        let effect = parse_transition(content).ok_or_else(|| ParseError::InvalidParameter {
+            line: line_number,
+            param: "filter".to_string(),
+            message: format!("unable to parse filter arguments: '{}'", content),
+        })?;
+
+        let kind =
+            FilterKind::from_name(&effect.name).ok_or_else(|| ParseError::InvalidParameter {
+                line: line_number,
+                param: "kind".to_string(),
+                message: format!(
+                    "unknown filter '{}', expected grayscale/sepia/blur/none",
+                    effect.name
+                ),
+            })?;
+
+        let raw_intensity = match effect.get_arg("intensity", 0) {
+            None => FILTER_DEFAULT_INTENSITY,
+            Some(TransitionArg::Number(n)) => *n,
+            Some(other) => {
+                return Err(ParseError::InvalidParameter {
+                    line: line_number,
+                    param: "intensity".to_string(),
+                    message: format!("expected a number, got: {:?}", other),
+                });
+            }
+        };
+        let intensity = raw_intensity.clamp(0.0, 1.0);
+        if intensity != raw_intensity {
+            self.warnings.push(format!(
+                "第 {} 行：filter 强度 {} 超出 0~1，已钳制为 {}",
+                line_number, raw_intensity, intensity
+            ));
+        }
+
+        Ok(Some(ScriptNode::SetFilter { kind, intensity }))
+    }
+
+    /// 解析 BGM 音量命令
+    ///
+    /// 语法: `bgmVol 0.3` / `bgmVol 0.3 over 1.0`（音量 0~1，`over` 后为过渡秒数）
+    pub(super) fn parse_bgm_volume(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "bgmvol", line_number)?;
+        let args: Vec<&str> = line
+            .get("bgmvol".len()..)
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect();
+
+        let number = |param: &str, raw: &str| -> Result<f64, ParseError> {
+            raw.parse()
+                .ok()
+                .filter(|n: &f64| n.is_finite())
+                .ok_or_else(|| ParseError::InvalidParameter {
+                    line: line_number,
+                    param: param.to_string(),
+                    message: format!("无法解析为数字: '{}'", raw),
+                })
+        };
+
+        let (raw_volume, duration) = match args.as_slice() {
+            [] => {
+                return Err(ParseError::MissingParameter {
+                    line: line_number,
+                    command: "bgmVol".to_string(),
+                    param: "音量".to_string(),
+                });
+            }
+            [volume] => (number("volume", volume)?, BGM_VOLUME_DEFAULT_DURATION),
+            [volume, over, duration] if over.eq_ignore_ascii_case("over") => {
+                (number("volume", volume)?, number("over", duration)?)
+            }
+            _ => {
+                return Err(ParseError::InvalidParameter {
+                    line: line_number,
+                    param: "bgmVol".to_string(),
+                    message: format!(
+                        "期望 `bgmVol <音量> [over <秒数>]`，实际: '{}'",
+                        line.trim()
+                    ),
+                });
+            }
+        };
+
+        if duration < 0.0 {
+            return Err(ParseError::InvalidParameter {
+                line: line_number,
+                param: "over".to_string(),
+                message: format!("过渡时长不能为负数，实际: {}", duration),
+            });
+        }
+        let volume = raw_volume.clamp(0.0, 1.0);
+        if volume != raw_volume {
+            self.warnings.push(format!(
+                "第 {} 行：bgmVol 音量 {} 超出 0~1，已钳制为 {}",
+                line_number, raw_volume, volume
+            ));
+        }
+
+        Ok(Some(ScriptNode::SetBgmVolume { volume, duration }))
+    }
+
     /// 解析视频过场命令
     ///
     /// 语法: `cutscene "path"`
@@ -262,12 +534,14 @@ impl Phase2Parser {
             .strip_prefix('$')
             .ok_or_else(|| ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: format!("callGame 变量名必须以 '$' 开头，实际: '{}'", var_part),
             })?;
 
         if result_var.is_empty() || !result_var.chars().all(|c| c.is_alphanumeric() || c == '_') {
             return Err(ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: format!(
                     "callGame 变量名格式无效，只能含字母、数字和下划线，实际: '{}'",
                     result_var
@@ -290,7 +564,8 @@ impl Phase2Parser {
                     message: "missing closing parenthesis".to_string(),
                 })?;
             let args_str = &params_rest[paren_start + 1..paren_end];
-            let extra_params = parse_request_ui_params(args_str, line_number)?;
+            let extra_params = parse_request_ui_params(args_str, line_number)
+                .map_err(relocate_error(line, args_str))?;
             params.extend(extra_params);
         }
 
@@ -362,12 +637,14 @@ impl Phase2Parser {
             .strip_prefix('$')
             .ok_or_else(|| ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: format!("showMap 变量名必须以 '$' 开头，实际: '{}'", after_as),
             })?;
 
         if result_var.is_empty() || !result_var.chars().all(|c| c.is_alphanumeric() || c == '_') {
             return Err(ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: format!(
                     "showMap 变量名格式无效，只能含字母、数字和下划线，实际: '{}'",
                     result_var
@@ -418,6 +695,41 @@ impl Phase2Parser {
         Ok(Some(ScriptNode::SetTextMode(mode)))
     }
 
+    /// 解析对话框样式切换
+    ///
+    /// 语法: `textBoxStyle diary` / `textBoxStyle "diary"`；`default` 恢复默认样式
+    pub(super) fn parse_textbox_style(
+        &mut self,
+        line: &str,
+        line_number: usize,
+    ) -> Result<Option<ScriptNode>, ParseError> {
+        self.require_syntax(2, "textBoxStyle", line_number)?;
+        let raw = line.get("textBoxStyle".len()..).unwrap_or_default().trim();
+        let name = raw
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .unwrap_or(raw)
+            .trim();
+        if name.is_empty() {
+            return Err(ParseError::MissingParameter {
+                line: line_number,
+                command: "textBoxStyle".to_string(),
+                param: "样式名".to_string(),
+            });
+        }
+        if name.contains(char::is_whitespace) {
+            return Err(ParseError::InvalidParameter {
+                line: line_number,
+                param: "样式名".to_string(),
+                message: format!("样式名不能包含空白字符: '{}'", name),
+            });
+        }
+
+        Ok(Some(ScriptNode::SetTextboxStyle {
+            name: name.to_string(),
+        }))
+    }
+
     /// 解析 requestUI 命令
     ///
     /// 语法:
@@ -486,12 +798,14 @@ impl Phase2Parser {
             .strip_prefix('$')
             .ok_or_else(|| ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: format!("requestUI 变量名必须以 '$' 开头，实际: '{}'", var_part),
             })?;
 
         if result_var.is_empty() || !result_var.chars().all(|c| c.is_alphanumeric() || c == '_') {
             return Err(ParseError::InvalidLine {
                 line: line_number,
+                column: None,
                 message: format!(
                     "requestUI 变量名格式无效，只能含字母、数字和下划线，实际: '{}'",
                     result_var
@@ -509,7 +823,8 @@ impl Phase2Parser {
                     message: "missing closing parenthesis".to_string(),
                 })?;
             let args_str = &params_rest[paren_start + 1..paren_end];
-            parse_request_ui_params(args_str, line_number)?
+            parse_request_ui_params(args_str, line_number)
+                .map_err(relocate_error(line, args_str))?
         } else {
             Vec::new()
         };
@@ -544,7 +859,8 @@ fn parse_request_ui_params(
         })?;
         let key = part[..colon_pos].trim().to_string();
         let value_str = part[colon_pos + 1..].trim();
-        let expr = parse_expression(value_str, line_number)?;
+        let expr = parse_expression(value_str, line_number)
+            .map_err(relocate_error(args_str, value_str))?;
         params.push((key, expr));
     }
 
@@ -1,46 +1,94 @@
 //! # 阶段 2：块解析
 //!
 //! 将块转换为 ScriptNode。按指令域拆分为子模块：
-//! - `display`: 显示指令（changeBG/changeScene/show/hide + 过渡效果）
-//! - `control`: 控制流（goto/callScript/conditional/set/wait/choice table）
-//! - `dialogue`: 对话与文本（chapter/extend）
-//! - `misc`: 杂项（audio/sceneEffect/titleCard/cutscene）
+//! - `display`: 显示指令（changeBG/changeScene/bgLayers/fill/show/hide/rimLight/showcg/hidecg + 过渡效果）
+//! - `control`: 控制流（bookmark/ending/goto/callScript/conditional/timeline/set/local/const/wait/choice table）
+//! - `dialogue`: 对话与文本（chapter/extend/bubble/速度标注）
+//! - `misc`: 杂项（audio/sceneEffect/titleCard/rumble/weather/filter/cutscene）
 
 mod control;
 mod dialogue;
 mod display;
 mod misc;
 
+use std::collections::HashMap;
+
+use crate::command::Transition;
 use crate::error::ParseError;
 use crate::script::ast::ScriptNode;
 
+use super::aliases::KeywordAliases;
+use super::constants::expand_constants;
 use super::helpers::{parse_dialogue, starts_with_ignore_case};
 use super::inline_tags::parse_inline_tags;
 use super::phase1::Block;
+use super::syntax::LATEST_SYNTAX_VERSION;
 
 /// 阶段 2 解析器
 pub struct Phase2Parser {
     /// 解析警告（非致命错误）
     pub warnings: Vec<String>,
+    /// 行首指令关键字别名
+    pub aliases: KeywordAliases,
+    /// 脚本声明的语法版本
+    pub syntax_version: u32,
+    /// `default_transition` 声明的默认过渡（作用于其后未写 with 的 show/hide/changeBG）
+    pub default_transition: Option<Transition>,
+    /// `const` 声明的脚本常量（名 → 字面值），解析期展开
+    pub constants: HashMap<String, String>,
 }
 
 impl Phase2Parser {
     pub fn new() -> Self {
+        Self::with_aliases(KeywordAliases::new())
+    }
+
+    pub fn with_aliases(aliases: KeywordAliases) -> Self {
         Self {
             warnings: Vec::new(),
+            aliases,
+            syntax_version: LATEST_SYNTAX_VERSION,
+            default_transition: None,
+            constants: HashMap::new(),
         }
     }
 
     pub fn reset_state(&mut self) {
         self.warnings.clear();
+        self.syntax_version = LATEST_SYNTAX_VERSION;
+        self.default_transition = None;
+        self.constants.clear();
+    }
+
+    /// 要求脚本声明的语法版本不低于 `since`
+    fn require_syntax(
+        &self,
+        since: u32,
+        feature: &str,
+        line_number: usize,
+    ) -> Result<(), ParseError> {
+        if self.syntax_version >= since {
+            return Ok(());
+        }
+        Err(ParseError::UnsupportedSyntax {
+            line: line_number,
+            feature: feature.to_string(),
+            required: since,
+            declared: self.syntax_version,
+        })
     }
 
     /// 解析单个块
     pub fn parse_block(&mut self, block: Block) -> Result<Option<ScriptNode>, ParseError> {
         match block {
             Block::SingleLine { line, line_number } => self.parse_single_line(&line, line_number),
-            Block::Table { lines, start_line } => self.parse_table(&lines, start_line),
+            Block::Table {
+                lines,
+                start_line,
+                prompt,
+            } => self.parse_table(&lines, start_line, prompt),
             Block::Conditional { lines, start_line } => self.parse_conditional(&lines, start_line),
+            Block::Timeline { lines, start_line } => self.parse_timeline(&lines, start_line),
         }
     }
 
@@ -51,8 +99,21 @@ impl Phase2Parser {
         line_number: usize,
     ) -> Result<Option<ScriptNode>, ParseError> {
         let (line, no_wait) = strip_arrow_suffix(line.trim());
-        let line = line.trim();
+        let original = line.trim();
+        let expanded = self.aliases.expand(original);
+        // 别名只替换行首关键字，错误列号按长度差换算回原文
+        let delta = original.chars().count() as isize - expanded.chars().count() as isize;
+        self.parse_expanded_line(&expanded, line_number, no_wait)
+            .map(|node| node.map(|node| self.apply_default_transition(node)))
+            .map_err(|err| err.shift_column(delta))
+    }
 
+    fn parse_expanded_line(
+        &mut self,
+        line: &str,
+        line_number: usize,
+        no_wait: bool,
+    ) -> Result<Option<ScriptNode>, ParseError> {
         // Markdown 注释块
         if line.starts_with('>') {
             return Ok(None);
@@ -63,50 +124,132 @@ impl Phase2Parser {
             return self.parse_chapter(line);
         }
 
-        // 标签定义 (**label_name**)
+        // 标签定义 (**label_name**)：独占一行；
+        // 其余位置的 `**词**` 是 Markdown 粗体，由对话文本按富文本处理
         if line.starts_with("**") && line.ends_with("**") && line.len() > 4 {
-            let name = &line[2..line.len() - 2];
+            let name = line[2..line.len() - 2].trim();
             if !name.contains('*') {
+                // 旧脚本中含空白的 label 仍然有效，只提示改名以免与粗体混淆
+                if name.contains(char::is_whitespace) {
+                    self.warnings.push(format!(
+                        "第 {} 行：label '{}' 含空白，容易与粗体混淆，建议改用不含空白的名称",
+                        line_number, name
+                    ));
+                }
                 return Ok(Some(ScriptNode::Label {
-                    name: name.trim().to_string(),
+                    name: name.to_string(),
                 }));
             }
         }
 
-        // --- 指令分发（大小写不敏感）---
+        // 书签 (@bookmark name)
+        if starts_with_command(line, "@bookmark") {
+            return self.parse_bookmark(line, line_number);
+        }
 
+        // 常量声明（解析期展开，不产生节点）
+        if starts_with_command(line, "const") {
+            return self.parse_const(line, line_number);
+        }
+
+        // 指令行展开常量；对话/旁白保持原文
+        let expanded = expand_constants(line, &self.constants);
+        if let Some(result) = self.parse_command(&expanded, line_number, no_wait) {
+            return result;
+        }
+
+        // 对话/旁白
+        if let Some((speaker, raw_content)) = parse_dialogue(line) {
+            let (speaker, text_speed) = self.split_speaker_annotation(speaker, line_number)?;
+            let (content, inline_effects) = parse_inline_tags(&raw_content);
+            return Ok(Some(ScriptNode::Dialogue {
+                speaker,
+                content,
+                inline_effects,
+                no_wait,
+                text_speed,
+            }));
+        }
+
+        // 未知行
+        self.warnings.push(format!(
+            "第 {} 行：无法识别的内容，已跳过: {}",
+            line_number, line
+        ));
+        Ok(None)
+    }
+
+    /// 指令分发（大小写不敏感）；不是指令行时返回 None
+    fn parse_command(
+        &mut self,
+        line: &str,
+        line_number: usize,
+        no_wait: bool,
+    ) -> Option<Result<Option<ScriptNode>, ParseError>> {
         // 显示指令
         if starts_with_ignore_case(line, "changebg") {
-            return self.parse_change_bg(line, line_number);
+            return Some(self.parse_change_bg(line, line_number));
         }
         if starts_with_ignore_case(line, "changescene") {
-            return self.parse_change_scene(line, line_number);
+            return Some(self.parse_change_scene(line, line_number));
+        }
+        if starts_with_command(line, "bgLayers") {
+            return Some(self.parse_background_layers(line, line_number));
+        }
+        if starts_with_command(line, "default_transition") {
+            return Some(self.parse_default_transition(line, line_number));
+        }
+        if starts_with_command(line, "fill") {
+            return Some(self.parse_fill(line, line_number));
+        }
+        if starts_with_command(line, "showcg") {
+            return Some(self.parse_show_cg(line, line_number));
+        }
+        if starts_with_command(line, "hidecg") {
+            if let Err(e) = self.require_syntax(2, "hidecg", line_number) {
+                return Some(Err(e));
+            }
+            return Some(Ok(Some(ScriptNode::HideCg {
+                transition: self.extract_transition_from_line(line),
+            })));
         }
         if starts_with_command(line, "show") {
-            return self.parse_show(line, line_number);
+            return Some(self.parse_show(line, line_number));
         }
         if starts_with_command(line, "hide") {
-            return self.parse_hide(line, line_number);
+            return Some(self.parse_hide(line, line_number));
+        }
+        if starts_with_command(line, "rimlight") {
+            return Some(self.parse_rim_light(line, line_number));
         }
 
         // 控制流指令
         if starts_with_ignore_case(line, "goto") {
-            return self.parse_goto(line, line_number);
+            return Some(self.parse_goto(line, line_number));
         }
         if starts_with_ignore_case(line, "callscript") {
-            return self.parse_call_script(line, line_number);
+            return Some(self.parse_call_script(line, line_number));
         }
         if starts_with_ignore_case(line, "returnfromscript") {
-            return Ok(Some(ScriptNode::ReturnFromScript));
+            return Some(Ok(Some(ScriptNode::ReturnFromScript)));
         }
         if starts_with_ignore_case(line, "fullrestart") {
-            return Ok(Some(ScriptNode::FullRestart));
+            return Some(Ok(Some(ScriptNode::FullRestart)));
+        }
+        if starts_with_command(line, "ending") {
+            return Some(self.parse_ending(line, line_number));
         }
         if starts_with_ignore_case(line, "set ") {
-            return self.parse_set_var(line, line_number);
+            return Some(self.parse_set_var(line, line_number));
+        }
+        if starts_with_ignore_case(line, "local ") {
+            return Some(self.parse_local_var(line, line_number));
+        }
+        if starts_with_ignore_case(line, "assert ") {
+            return Some(self.parse_assert(line, line_number));
         }
         if starts_with_command(line, "wait") {
-            return self.parse_wait(line, line_number);
+            return Some(self.parse_wait(line, line_number));
         }
         if starts_with_ignore_case(line, "pause")
             && (line.len() == 5
@@ -115,82 +258,92 @@ impl Phase2Parser {
                     .get(5)
                     .is_none_or(|b| b.is_ascii_whitespace()))
         {
-            return Ok(Some(ScriptNode::Pause));
+            return Some(Ok(Some(ScriptNode::Pause)));
         }
         if starts_with_ignore_case(line, "clearcharacters") {
-            return Ok(Some(ScriptNode::ClearCharacters));
+            return Some(Ok(Some(ScriptNode::ClearCharacters)));
         }
 
         // 音频指令（简单变体内联，复杂解析委托到 misc）
         if starts_with_ignore_case(line, "stopbgm") {
-            return Ok(Some(ScriptNode::StopBgm));
+            return Some(Ok(Some(ScriptNode::StopBgm)));
         }
         if starts_with_ignore_case(line, "bgmunduck") {
-            return Ok(Some(ScriptNode::BgmUnduck));
+            return Some(Ok(Some(ScriptNode::BgmUnduck)));
         }
         if starts_with_ignore_case(line, "bgmduck") {
-            return Ok(Some(ScriptNode::BgmDuck));
+            return Some(Ok(Some(ScriptNode::BgmDuck)));
+        }
+        if starts_with_command(line, "bgmvol") {
+            return Some(self.parse_bgm_volume(line, line_number));
+        }
+        if starts_with_command(line, "playsfx") {
+            return Some(self.parse_play_sfx(line, line_number));
         }
 
         // UI 指令
         if starts_with_ignore_case(line, "textboxhide") {
-            return Ok(Some(ScriptNode::TextBoxHide));
+            return Some(Ok(Some(ScriptNode::TextBoxHide)));
         }
         if starts_with_ignore_case(line, "textboxshow") {
-            return Ok(Some(ScriptNode::TextBoxShow));
+            return Some(Ok(Some(ScriptNode::TextBoxShow)));
         }
         if starts_with_ignore_case(line, "textboxclear") {
-            return Ok(Some(ScriptNode::TextBoxClear));
+            return Some(Ok(Some(ScriptNode::TextBoxClear)));
+        }
+        if starts_with_command(line, "textboxstyle") {
+            return Some(self.parse_textbox_style(line, line_number));
         }
 
         // 杂项指令
         if starts_with_ignore_case(line, "sceneeffect") {
-            return self.parse_scene_effect(line, line_number);
+            return Some(self.parse_scene_effect(line, line_number));
         }
         if starts_with_ignore_case(line, "titlecard") {
-            return self.parse_title_card(line, line_number);
+            return Some(self.parse_title_card(line, line_number));
+        }
+        if starts_with_ignore_case(line, "rumble")
+            && line
+                .as_bytes()
+                .get(6)
+                .is_none_or(|b| b.is_ascii_whitespace() || *b == b'(')
+        {
+            return Some(self.parse_rumble(line, line_number));
+        }
+        if starts_with_command(line, "weather") {
+            return Some(self.parse_weather(line, line_number));
+        }
+        if starts_with_command(line, "filter") {
+            return Some(self.parse_filter(line, line_number));
         }
         if starts_with_ignore_case(line, "cutscene") {
-            return self.parse_cutscene(line, line_number);
+            return Some(self.parse_cutscene(line, line_number));
         }
         if starts_with_ignore_case(line, "requestui") {
-            return self.parse_request_ui(line, line_number);
+            return Some(self.parse_request_ui(line, line_number));
         }
         if starts_with_ignore_case(line, "textmode") {
-            return self.parse_text_mode(line, line_number);
+            return Some(self.parse_text_mode(line, line_number));
         }
         if starts_with_ignore_case(line, "showmap") {
-            return self.parse_show_map(line, line_number);
+            return Some(self.parse_show_map(line, line_number));
         }
         if starts_with_ignore_case(line, "callgame") {
-            return self.parse_call_game(line, line_number);
+            return Some(self.parse_call_game(line, line_number));
         }
         if starts_with_command(line, "extend") {
-            return self.parse_extend(line, line_number, no_wait);
+            return Some(self.parse_extend(line, line_number, no_wait));
+        }
+        if starts_with_command(line, "bubble") {
+            return Some(self.parse_bubble(line, line_number));
         }
 
         // HTML audio 标签
         if line.starts_with("<audio") {
-            return self.parse_audio(line, line_number);
-        }
-
-        // 对话/旁白
-        if let Some((speaker, raw_content)) = parse_dialogue(line) {
-            let (content, inline_effects) = parse_inline_tags(&raw_content);
-            return Ok(Some(ScriptNode::Dialogue {
-                speaker,
-                content,
-                inline_effects,
-                no_wait,
-            }));
+            return Some(self.parse_audio(line, line_number));
         }
 
-        // 未知行
-        self.warnings.push(format!(
-            "第 {} 行：无法识别的内容，已跳过: {}",
-            line_number, line
-        ));
-        Ok(None)
+        None
     }
 }
 
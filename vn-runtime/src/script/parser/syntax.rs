@@ -0,0 +1,119 @@
+//! # 脚本语法版本
+//!
+//! 脚本可在开头声明所用的语法版本，新语法只在声明版本足够时启用，
+//! 旧脚本因此不会被新语法的解析规则误读：
+//!
+//! ```markdown
+//! ---
+//! syntax: 1
+//! ---
+//! ```
+//!
+//! 或首个非空行直接写 `syntax: 1`。未声明时视为最新版本。
+//!
+//! front matter 中的 `default_transition: dissolve 0.3` 等同于在同一行写
+//! `default_transition dissolve 0.3` 指令。
+//!
+//! ## 版本历史
+//!
+//! - `1`：初始语法
+//! - `2`：
+//!   - 演出：`showcg` / `hidecg`、`show ... idle`、`show ... auto` 自动布局、`bgLayers`、
+//!     `fill`、`bubble`、`textBoxStyle`、`rimLight`、`filter`、`weather`、`rumble`
+//!   - 音频：`bgmVol`、`playSfx @池名`
+//!   - 选择：表格的条件列与禁用理由列、按表头映射的列、选择提示（紧贴表格的引用行）
+//!   - 流程：`goto ... after`、`goto $var`、`timeline`、`const` 常量、`assert` 断言、
+//!     `local` 局部变量、`default_transition`
+
+use std::borrow::Cow;
+
+use crate::error::ParseError;
+
+use super::helpers::starts_with_ignore_case;
+
+/// 当前支持的最新语法版本（未声明时的默认值）
+pub const LATEST_SYNTAX_VERSION: u32 = 2;
+
+/// 可写在 front matter 中的指令（`key: value` 改写为 `key value` 交给阶段 2 解析）
+const FRONT_MATTER_DIRECTIVES: &[&str] = &["default_transition"];
+
+/// 读取并移除语法版本声明
+///
+/// 声明所在行替换为空行，保证后续解析的行号与原文一致；
+/// front matter 中的指令行改写为普通指令保留在原行。
+pub(super) fn strip_syntax_declaration(text: &str) -> Result<(u32, Cow<'_, str>), ParseError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(first) = lines.iter().position(|l| !l.trim().is_empty()) else {
+        return Ok((LATEST_SYNTAX_VERSION, Cow::Borrowed(text)));
+    };
+
+    // front matter：首行 `---` 到下一个 `---` 之间的 `key: value`
+    let front_matter = first == 0 && lines[0].trim() == "---";
+    let (range, declared) = if front_matter {
+        let Some(end) = lines[1..].iter().position(|l| l.trim() == "---") else {
+            return Ok((LATEST_SYNTAX_VERSION, Cow::Borrowed(text)));
+        };
+        let end = end + 1;
+        let declared = (1..end).find_map(|i| syntax_value(lines[i]).map(|v| (i, v)));
+        (0..=end, declared)
+    } else {
+        match syntax_value(lines[first]) {
+            Some(value) => (first..=first, Some((first, value))),
+            None => return Ok((LATEST_SYNTAX_VERSION, Cow::Borrowed(text))),
+        }
+    };
+
+    let version = match declared {
+        Some((idx, value)) => parse_version(value, idx + 1)?,
+        None => LATEST_SYNTAX_VERSION,
+    };
+
+    let stripped = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if !range.contains(&i) {
+                return line.to_string();
+            }
+            front_matter
+                .then(|| front_matter_directive(line))
+                .flatten()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok((version, Cow::Owned(stripped)))
+}
+
+/// front matter 中 `key: value` 形式的指令行，改写为 `key value`
+fn front_matter_directive(line: &str) -> Option<String> {
+    let (key, value) = line.split_once(':')?;
+    let key = key.trim();
+    FRONT_MATTER_DIRECTIVES
+        .iter()
+        .any(|d| d.eq_ignore_ascii_case(key))
+        .then(|| format!("{key} {}", value.trim()))
+}
+
+/// `syntax: N` 行的取值部分
+fn syntax_value(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if !starts_with_ignore_case(line, "syntax") {
+        return None;
+    }
+    line["syntax".len()..]
+        .trim_start()
+        .strip_prefix(':')
+        .map(str::trim)
+}
+
+fn parse_version(value: &str, line_number: usize) -> Result<u32, ParseError> {
+    match value.parse::<u32>() {
+        Ok(v) if (1..=LATEST_SYNTAX_VERSION).contains(&v) => Ok(v),
+        _ => Err(ParseError::InvalidParameter {
+            line: line_number,
+            param: "syntax".to_string(),
+            message: format!("不支持的语法版本 '{value}'，当前支持 1 到 {LATEST_SYNTAX_VERSION}"),
+        }),
+    }
+}
@@ -91,6 +91,7 @@ show <img src="assets/chara.png" style="zoom:25%;" /> as 红叶 at farleft with
         alias,
         position: _,
         transition,
+        ..
     } = &script.nodes[3]
     {
         assert_eq!(path.as_str(), "assets/chara.png");
@@ -109,6 +110,268 @@ fn test_parse_goto_cross_file_is_banned() {
     assert!(matches!(err, crate::error::ParseError::InvalidLine { .. }));
 }
 
+#[test]
+fn test_parse_fill_color() {
+    let node = parse_single_node("fill #000000 with fade");
+    assert!(matches!(
+        node,
+        ScriptNode::FillColor { color, transition: Some(t) }
+            if color == Color::BLACK && t.name == "fade"
+    ));
+
+    let node = parse_single_node("fill White");
+    assert!(matches!(
+        node,
+        ScriptNode::FillColor { color, transition: None } if color == Color::WHITE
+    ));
+
+    let err = parse_err("fill #12345");
+    assert!(
+        matches!(err, crate::error::ParseError::InvalidParameter { ref param, .. } if param == "color")
+    );
+    let err = parse_err("fill with fade");
+    assert!(matches!(
+        err,
+        crate::error::ParseError::MissingParameter { .. }
+    ));
+}
+
+#[test]
+fn test_default_transition_fills_missing_and_explicit_overrides() {
+    let script = parse_ok(
+        r#"
+show <img src="a.png" /> as alice at left
+default_transition dissolve 0.3
+show <img src="a.png" /> as alice at left
+hide alice with fade
+changeBG <img src="bg.png" />
+show alice at right
+default_transition none
+hide alice
+"#,
+    );
+    let transitions: Vec<Option<(String, Option<f64>)>> = script
+        .nodes
+        .iter()
+        .map(|node| match node {
+            ScriptNode::ShowCharacter { transition, .. }
+            | ScriptNode::HideCharacter { transition, .. }
+            | ScriptNode::ChangeBG { transition, .. } => transition
+                .as_ref()
+                .map(|t| (t.name.clone(), t.get_duration())),
+            other => panic!("unexpected node {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        transitions,
+        vec![
+            None,
+            Some(("dissolve".to_string(), Some(0.3))),
+            Some(("fade".to_string(), None)),
+            Some(("dissolve".to_string(), Some(0.3))),
+            // 不带路径的 show 是移动，不套用默认过渡
+            None,
+            None,
+        ]
+    );
+
+    // front matter 声明与指令等价，且不影响行号
+    let script = parse_ok(
+        "---\nsyntax: 2\ndefault_transition: Dissolve(duration: 0.5)\n---\nshow <img src=\"a.png\" /> as alice at left\n",
+    );
+    assert!(matches!(
+        &script.nodes[0],
+        ScriptNode::ShowCharacter { transition: Some(t), .. }
+            if t.name == "Dissolve" && t.get_duration() == Some(0.5)
+    ));
+    assert_eq!(script.get_source_line(0), Some(5));
+}
+
+#[test]
+fn test_parse_filter_kinds_and_intensity() {
+    use crate::command::FilterKind;
+
+    assert_eq!(
+        parse_single_node("filter grayscale"),
+        ScriptNode::SetFilter {
+            kind: FilterKind::Grayscale,
+            intensity: 1.0,
+        }
+    );
+    assert_eq!(
+        parse_single_node("filter Sepia(intensity: 0.6)"),
+        ScriptNode::SetFilter {
+            kind: FilterKind::Sepia,
+            intensity: 0.6,
+        }
+    );
+    assert_eq!(
+        parse_single_node("filter blur(0.4)"),
+        ScriptNode::SetFilter {
+            kind: FilterKind::Blur,
+            intensity: 0.4,
+        }
+    );
+    assert!(matches!(
+        parse_single_node("filter none"),
+        ScriptNode::SetFilter {
+            kind: FilterKind::None,
+            ..
+        }
+    ));
+
+    let mut parser = Parser::new();
+    let script = parser.parse("test", "filter sepia(3)").unwrap();
+    assert!(matches!(
+        script.nodes[0],
+        ScriptNode::SetFilter { intensity, .. } if intensity == 1.0
+    ));
+    assert_eq!(parser.warnings().len(), 1);
+
+    for input in ["filter", "filter vivid", r#"filter blur(intensity: "a")"#] {
+        let err = parse_err(input);
+        assert!(
+            matches!(
+                err,
+                crate::error::ParseError::MissingParameter { .. }
+                    | crate::error::ParseError::InvalidParameter { .. }
+            ),
+            "input={input}, got: {err:?}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_collecting_errors_reports_all_and_keeps_valid_blocks() {
+    let text = r#"旁白："开头"
+
+hide
+
+**mid**
+
+filter vivid
+
+旁白："结尾"
+"#;
+    let mut parser = Parser::new();
+    let (script, errors) = parser.parse_collecting_errors("test", text, "");
+    assert_eq!(
+        errors.iter().map(|e| e.line()).collect::<Vec<_>>(),
+        [Some(3), Some(7)]
+    );
+    assert_eq!(script.nodes.len(), 3);
+    assert!(matches!(&script.nodes[1], ScriptNode::Label { name } if name == "mid"));
+    assert_eq!(script.get_source_line(2), Some(9));
+
+    // 普通模式仍在第一个错误处返回
+    assert_eq!(parse_err(text).line(), Some(3));
+}
+
+#[test]
+fn test_parse_rim_light() {
+    let rim = |alias: &str, intensity: f64| ScriptNode::SetRimLight {
+        alias: alias.to_string(),
+        intensity,
+    };
+    assert_eq!(parse_single_node("rimLight royu"), rim("royu", 1.0));
+    assert_eq!(parse_single_node("rimlight royu 0.6"), rim("royu", 0.6));
+    assert_eq!(parse_single_node("rimLight royu off"), rim("royu", 0.0));
+
+    for input in ["rimLight", "rimLight royu bright"] {
+        let err = parse_err(input);
+        assert!(
+            matches!(
+                err,
+                crate::error::ParseError::MissingParameter { .. }
+                    | crate::error::ParseError::InvalidParameter { .. }
+            ),
+            "input={input}, got: {err:?}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_background_layers() {
+    let node = parse_single_node(
+        r#"bgLayers <img src="bg/sky.png" /> 0.1, <img src="bg/town.png"/> 0.5, <img src="bg/street.png" />"#,
+    );
+    let ScriptNode::SetBackgroundLayers { layers } = node else {
+        panic!("expected SetBackgroundLayers, got {node:?}");
+    };
+    let layers: Vec<(&str, f64)> = layers
+        .iter()
+        .map(|l| (l.path.as_str(), l.parallax))
+        .collect();
+    assert_eq!(
+        layers,
+        [
+            ("bg/sky.png", 0.1),
+            ("bg/town.png", 0.5),
+            ("bg/street.png", 1.0)
+        ]
+    );
+
+    let err = parse_err(r#"bgLayers <img src="bg/sky.png" /> -0.5"#);
+    assert!(
+        matches!(err, crate::error::ParseError::InvalidParameter { ref param, .. } if param == "parallax")
+    );
+    let err = parse_err("bgLayers");
+    assert!(matches!(
+        err,
+        crate::error::ParseError::MissingParameter { .. }
+    ));
+}
+
+#[test]
+fn test_parse_timed_goto() {
+    let node = parse_single_node("goto **结局** after 3");
+    assert!(matches!(
+        node,
+        ScriptNode::TimedGoto { target_label, duration }
+            if target_label == "结局" && duration == 3.0
+    ));
+
+    let node = parse_single_node("goto ending after 1.5");
+    assert!(matches!(
+        node,
+        ScriptNode::TimedGoto { target_label, duration }
+            if target_label == "ending" && duration == 1.5
+    ));
+
+    let node = parse_single_node("goto ending AFTER 2");
+    assert!(matches!(
+        node,
+        ScriptNode::TimedGoto { target_label, duration }
+            if target_label == "ending" && duration == 2.0
+    ));
+
+    for bad in [
+        "goto ending after 0",
+        "goto ending after soon",
+        "goto ending after NaN",
+        "goto ending after inf",
+        "goto ending after -inf",
+    ] {
+        let err = parse_err(bad);
+        assert!(
+            matches!(err, crate::error::ParseError::InvalidParameter { ref param, .. } if param == "after"),
+            "{bad}: {err:?}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_goto_variable_target() {
+    let node = parse_single_node("goto $next_scene");
+    assert!(matches!(
+        node,
+        ScriptNode::DynamicGoto { target: crate::script::Expr::Variable(ref name) } if name == "next_scene"
+    ));
+
+    let err = parse_err("goto $next after 2");
+    assert!(matches!(err, crate::error::ParseError::InvalidLine { .. }));
+}
+
 #[test]
 fn test_parse_call_script_and_return_from_script() {
     let node = parse_single_node(r#"callScript [prologue](ring/summer/prologue.md)"#);
@@ -344,6 +607,40 @@ fn test_parse_goto_label_extraction_and_empty_label_error() {
     ));
 }
 
+#[test]
+fn test_parse_bookmark_is_not_a_label() {
+    let mut parser = Parser::new();
+    let script = parser
+        .parse(
+            "test",
+            "@bookmark intro_end\n@BOOKMARK   ch2  \n旁白：\"……\"",
+        )
+        .unwrap();
+    assert!(matches!(
+        &script.nodes[0],
+        ScriptNode::Bookmark { name } if name == "intro_end"
+    ));
+    assert!(matches!(
+        &script.nodes[1],
+        ScriptNode::Bookmark { name } if name == "ch2"
+    ));
+    assert_eq!(script.find_bookmark("intro_end"), Some(0));
+    assert_eq!(script.find_bookmark("ch2"), Some(1));
+    // 书签不参与 goto/choice 的标签索引
+    assert_eq!(script.find_label("intro_end"), None);
+
+    let err = parser.parse("test", "@bookmark").unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::ParseError::MissingParameter { .. }
+    ));
+    let err = parser.parse("test", "@bookmark a b").unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::ParseError::InvalidParameter { .. }
+    ));
+}
+
 #[test]
 fn test_parse_audio_loop_and_no_close_tag() {
     let mut parser = Parser::new();
@@ -394,6 +691,170 @@ fn test_parse_table_incomplete_rows_and_empty_options_errors() {
     assert!(matches!(err, crate::error::ParseError::InvalidTable { .. }));
 }
 
+#[test]
+fn test_parse_table_condition_and_disabled_reason_columns() {
+    let mut parser = Parser::new();
+    let text = r#"
+| 横排 | | | |
+| --- | --- | --- | --- |
+| 开门 | door | $has_key == true | 需要钥匙 |
+| 敲门 | knock | $has_key == false |
+| 离开 | leave |
+"#
+    .trim();
+    let script = parser.parse("test", text).unwrap();
+    let ScriptNode::Choice { options, .. } = &script.nodes[0] else {
+        panic!("expected Choice");
+    };
+    assert!(options[0].condition.is_some());
+    assert_eq!(options[0].disabled_reason.as_deref(), Some("需要钥匙"));
+    assert!(options[1].condition.is_some());
+    assert_eq!(options[1].disabled_reason, None);
+    assert!(options[2].condition.is_none());
+    assert!(parser.warnings().is_empty());
+
+    // 条件列不是合法表达式 -> 报错
+    let text = r#"
+| 横排 | | |
+| --- | --- | --- |
+| 开门 | door | == == |
+"#
+    .trim();
+    assert!(Parser::new().parse("test", text).is_err());
+}
+
+#[test]
+fn test_parse_table_maps_columns_by_header() {
+    let options = |text: &str| {
+        let mut parser = Parser::new();
+        let script = parser.parse("test", text).unwrap();
+        assert!(parser.warnings().is_empty(), "{:?}", parser.warnings());
+        let ScriptNode::Choice { style, options, .. } = script.nodes[0].clone() else {
+            panic!("expected Choice");
+        };
+        (style, options)
+    };
+
+    // 完整表头：各列按名称映射
+    let (style, full) = options(
+        r#"
+| text | target | condition | reason | action |
+| --- | --- | --- | --- | --- |
+| 开门 | door | $has_key | 需要钥匙 | $opened = true; set $mood = "brave" |
+| 离开 | leave | | | |
+"#
+        .trim(),
+    );
+    assert_eq!(style, None);
+    assert_eq!(full[0].text, "开门");
+    assert_eq!(full[0].target_label, "door");
+    assert!(full[0].condition.is_some());
+    assert_eq!(full[0].disabled_reason.as_deref(), Some("需要钥匙"));
+    let names: Vec<_> = full[0].actions.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["opened", "mood"]);
+    assert_eq!(full[1].condition, None);
+    assert!(full[1].actions.is_empty());
+
+    // 缺 condition 列：默认无条件
+    let (_, no_condition) = options(
+        r#"
+| text | target | action |
+| --- | --- | --- |
+| 开门 | door | $opened = true |
+"#
+        .trim(),
+    );
+    assert_eq!(no_condition[0].condition, None);
+    assert_eq!(no_condition[0].actions.len(), 1);
+
+    // 列顺序变化：结果与完整表头一致
+    let (_, reordered) = options(
+        r#"
+| action | condition | target | reason | text |
+| --- | --- | --- | --- | --- |
+| $opened = true; set $mood = "brave" | $has_key | door | 需要钥匙 | 开门 |
+| | | leave | | 离开 |
+"#
+        .trim(),
+    );
+    assert_eq!(reordered, full);
+
+    // 首格不是列名时仍为样式，并作为 text 列
+    let (style, styled) = options(
+        r#"
+| 横排 | target | condition |
+| --- | --- | --- |
+| 开门 | door | $has_key |
+"#
+        .trim(),
+    );
+    assert_eq!(style.as_deref(), Some("横排"));
+    assert_eq!(styled[0].text, "开门");
+    assert_eq!(styled[0].target_label, "door");
+}
+
+#[test]
+fn test_parse_table_header_errors_and_legacy_syntax() {
+    // 缺 target 列 / 重复列 -> InvalidTable
+    for text in [
+        "| text | condition |\n| --- | --- |\n| 开门 | $x |",
+        "| text | target | target |\n| --- | --- | --- |\n| 开门 | a | b |",
+    ] {
+        let err = Parser::new().parse("test", text).unwrap_err();
+        assert!(
+            matches!(err, crate::error::ParseError::InvalidTable { .. }),
+            "{err}"
+        );
+    }
+
+    // 语法 1 不识别列名，表头首格仍是样式，按固定列序解析
+    let text = "syntax: 1\n| target | |\n| --- | --- |\n| 开门 | door |";
+    let script = Parser::new().parse("test", text).unwrap();
+    let ScriptNode::Choice { style, options, .. } = &script.nodes[0] else {
+        panic!("expected Choice");
+    };
+    assert_eq!(style.as_deref(), Some("target"));
+    assert_eq!(options[0].text, "开门");
+    assert_eq!(options[0].target_label, "door");
+}
+
+#[test]
+fn test_choice_prompt_from_quote_line_above_table() {
+    let prompt_of = |text: &str| {
+        let script = Parser::new().parse("test", text).unwrap();
+        let choice = script
+            .nodes
+            .iter()
+            .find_map(|n| match n {
+                ScriptNode::Choice { prompt, .. } => Some(prompt.clone()),
+                _ => None,
+            })
+            .expect("expected Choice");
+        (choice, script.nodes.len())
+    };
+    let table = "| 横排 | |\n| --- | --- |\n| 去海边 | sea |\n| 去山里 | mountain |";
+
+    // 紧贴表格的引用行成为提示，不再单独成为节点
+    assert_eq!(
+        prompt_of(&format!(">  要去哪里呢？\n{table}")),
+        (Some("要去哪里呢？".to_string()), 1)
+    );
+    // 只取紧贴的最后一行；更上方的引用行仍是注释
+    assert_eq!(
+        prompt_of(&format!("> 说明\n> 要去哪里呢？\n{table}")),
+        (Some("要去哪里呢？".to_string()), 1)
+    );
+    // 隔空行、空引用行、没有引用行时都没有提示
+    assert_eq!(prompt_of(&format!("> 要去哪里呢？\n\n{table}")).0, None);
+    assert_eq!(prompt_of(&format!(">\n{table}")).0, None);
+    assert_eq!(prompt_of(table).0, None);
+    // 语法 1 中引用行只是注释
+    assert_eq!(
+        prompt_of(&format!("syntax: 1\n> 要去哪里呢？\n{table}")).0,
+        None
+    );
+}
+
 #[test]
 fn test_extract_transition_from_line_rule_without_src_and_with_invalid_args() {
     let parser = phase2::Phase2Parser::new();
@@ -528,6 +989,175 @@ fn test_parse_set_persistent_var() {
     assert!(matches!(err2, crate::error::ParseError::InvalidLine { .. }));
 }
 
+#[test]
+fn test_parse_local_var() {
+    assert_eq!(
+        parse_single_node("local $tmp"),
+        ScriptNode::DeclareLocal {
+            name: "tmp".to_string(),
+            value: None,
+        }
+    );
+    assert!(matches!(
+        parse_single_node("local $count = 3"),
+        ScriptNode::DeclareLocal { name, value: Some(_) } if name == "count"
+    ));
+
+    // 持久变量不能声明为局部；缺少 $ 前缀报错
+    for input in [
+        "local $persistent.flag",
+        "local $g_cleared = true",
+        "local tmp",
+    ] {
+        assert!(
+            matches!(
+                parse_err(input),
+                crate::error::ParseError::InvalidLine { .. }
+            ),
+            "input={input}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_assert_with_optional_message() {
+    assert_eq!(
+        parse_single_node("assert $gold == 0 \"金币应为零\""),
+        ScriptNode::Assert {
+            condition: crate::script::Expr::eq(
+                crate::script::Expr::var("gold"),
+                crate::script::Expr::int(0)
+            ),
+            message: Some("金币应为零".to_string()),
+        }
+    );
+    // 末尾字符串属于条件本身时不视为说明
+    assert_eq!(
+        parse_single_node("assert $name == \"北风\""),
+        ScriptNode::Assert {
+            condition: crate::script::Expr::eq(
+                crate::script::Expr::var("name"),
+                crate::script::Expr::string("北风")
+            ),
+            message: None,
+        }
+    );
+    // 大小比较 + 说明
+    assert_eq!(
+        parse_single_node("assert $gold >= 0 \"金币不应为负\""),
+        ScriptNode::Assert {
+            condition: crate::script::Expr::compare(
+                crate::script::Expr::var("gold"),
+                crate::script::CompareOp::GreaterEq,
+                crate::script::Expr::int(0)
+            ),
+            message: Some("金币不应为负".to_string()),
+        }
+    );
+    assert!(matches!(
+        parse_err("assert $a == "),
+        crate::error::ParseError::InvalidLine { .. }
+    ));
+    // 语法版本 1 不支持 assert
+    assert!(matches!(
+        parse_err("syntax: 1\nassert $gold >= 0"),
+        crate::error::ParseError::UnsupportedSyntax { line: 2, .. }
+    ));
+}
+
+#[test]
+fn test_parse_const_expands_to_literals() {
+    let script = parse_ok(
+        r#"
+const BG_ROOM = <img src="bg/room.jpg" />
+const BG_NIGHT = "bg/night.jpg"
+const SHORT_WAIT = 1.5
+const MAX_HP = 10
+changebg BG_ROOM
+changebg <img src=BG_NIGHT />
+wait SHORT_WAIT
+set $MAX_HP = MAX_HP
+："BG_ROOM 原样保留"
+"#,
+    );
+    let expected_bg = |path: &str| ScriptNode::ChangeBG {
+        path: path.to_string(),
+        transition: None,
+    };
+    assert_eq!(script.nodes.len(), 5, "常量声明不产生节点");
+    assert_eq!(script.nodes[0], expected_bg("bg/room.jpg"));
+    assert_eq!(script.nodes[1], expected_bg("bg/night.jpg"));
+    assert_eq!(script.nodes[2], ScriptNode::Wait { duration: 1.5 });
+    // 常量名与变量名不冲突：`$MAX_HP` 是变量，右侧 `MAX_HP` 才是常量
+    assert!(matches!(
+        &script.nodes[3],
+        ScriptNode::SetVar {
+            name,
+            value: crate::script::Expr::Literal(crate::state::VarValue::Int(10)),
+        } if name == "MAX_HP"
+    ));
+    assert!(matches!(
+        &script.nodes[4],
+        ScriptNode::Dialogue { content, .. } if content == "BG_ROOM 原样保留"
+    ));
+}
+
+#[test]
+fn test_parse_const_leaves_labels_aliases_and_undeclared_names() {
+    // 没有常量的脚本里，大写下划线词照常解析
+    let script =
+        parse_ok("goto **END_A**\nshow <img src=\"a.png\" /> as HERO_A at left\n\n**END_A**\n");
+    assert_eq!(
+        script.nodes[0],
+        ScriptNode::Goto {
+            target_label: "END_A".to_string(),
+        }
+    );
+    assert!(matches!(
+        &script.nodes[1],
+        ScriptNode::ShowCharacter { alias, .. } if alias == "HERO_A"
+    ));
+
+    // 同名常量也不替换 label 引用与别名
+    let script = parse_ok(
+        "const END_A = 1\nconst HERO_A = 2\ngoto **END_A**\nshow <img src=\"a.png\" /> as HERO_A at left\n\n**END_A**\n",
+    );
+    assert_eq!(
+        script.nodes[0],
+        ScriptNode::Goto {
+            target_label: "END_A".to_string(),
+        }
+    );
+    assert!(matches!(
+        &script.nodes[1],
+        ScriptNode::ShowCharacter { alias, .. } if alias == "HERO_A"
+    ));
+}
+
+#[test]
+fn test_parse_const_errors() {
+    // 语法版本 1 不支持 const
+    assert!(matches!(
+        parse_err("syntax: 1\nconst BG_ROOM = 1"),
+        crate::error::ParseError::UnsupportedSyntax { line: 2, .. }
+    ));
+    // 非法常量名、缺少值、重复定义
+    for input in [
+        "const bg_room = 1",
+        "const X = 1",
+        "const BG_ROOM =",
+        "const BG_ROOM = 1\nconst BG_ROOM = 2",
+    ] {
+        assert!(
+            matches!(
+                parse_err(input),
+                crate::error::ParseError::InvalidLine { .. }
+            ),
+            "input={input}"
+        );
+    }
+}
+
 #[test]
 fn test_parse_conditionals() {
     let ok_cases = [
@@ -665,10 +1295,48 @@ fn test_parse_expression() {
         }
     }
 
-    let err_cases = ["", "($a == true"];
-    for input in err_cases {
-        let err = parse_expression(input, 1).unwrap_err();
-        assert!(matches!(err, crate::error::ParseError::InvalidLine { .. }));
+    let err_cases = ["", "($a == true"];
+    for input in err_cases {
+        let err = parse_expression(input, 1).unwrap_err();
+        assert!(matches!(err, crate::error::ParseError::InvalidLine { .. }));
+    }
+}
+
+#[test]
+fn test_parse_expression_in_list() {
+    use crate::script::Expr;
+    use crate::state::VarValue;
+
+    let expr = parse_expression(r#"$item in ["a", 'b', 3, true,]"#, 1).unwrap();
+    assert_eq!(
+        expr,
+        Expr::in_list(
+            Expr::var("item"),
+            vec![
+                VarValue::String("a".to_string()),
+                VarValue::String("b".to_string()),
+                VarValue::Int(3),
+                VarValue::Bool(true),
+            ],
+        )
+    );
+    assert_eq!(
+        parse_expression("$item in []", 1).unwrap(),
+        Expr::in_list(Expr::var("item"), vec![])
+    );
+    // 与逻辑运算组合：in 的优先级与比较相同
+    assert!(matches!(
+        parse_expression(r#"not $item in ["a"] and $ok == true"#, 1).unwrap(),
+        Expr::And(..)
+    ));
+
+    for input in [
+        r#"$item in "a""#,
+        r#"$item in [$other]"#,
+        r#"$item in ["a" "b"]"#,
+        r#"$item in ["a""#,
+    ] {
+        assert!(parse_expression(input, 1).is_err(), "input={input}");
     }
 }
 
@@ -969,14 +1637,14 @@ fn test_parse_show_character() {
     );
     assert!(matches!(
         node,
-        ScriptNode::ShowCharacter { path: Some(path), alias, position: Position::Center, transition: Some(t) }
+        ScriptNode::ShowCharacter { path: Some(path), alias, position: Position::Center, transition: Some(t), idle: None }
         if path.as_str() == "assets/char.png" && alias == "royu" && t.name == "Dissolve"
     ));
 
     let node = parse_single_node(r#"show beifeng at left"#);
     assert!(matches!(
         node,
-        ScriptNode::ShowCharacter { path: None, alias, position: Position::Left, transition: None }
+        ScriptNode::ShowCharacter { path: None, alias, position: Position::Left, transition: None, idle: None }
         if alias == "beifeng"
     ));
 
@@ -999,7 +1667,7 @@ fn test_parse_show_character() {
     let node = parse_single_node(r#"show <img src="assets/bg2.jpg" /> as 红叶 at left"#);
     assert!(matches!(
         node,
-        ScriptNode::ShowCharacter { alias, path: Some(path), position: Position::Left, transition: None }
+        ScriptNode::ShowCharacter { alias, path: Some(path), position: Position::Left, transition: None, idle: None }
         if alias == "红叶" && path.as_str() == "assets/bg2.jpg"
     ));
 
@@ -1025,6 +1693,57 @@ fn test_parse_show_character() {
     ));
 }
 
+/// 测试 show 指令的 idle 子句：位置之后、with 之前均可
+#[test]
+fn test_parse_show_character_idle() {
+    let node = parse_single_node("show royu at center idle breathe");
+    assert!(matches!(
+        node,
+        ScriptNode::ShowCharacter { alias, position: Position::Center, idle: Some(kind), .. }
+        if alias == "royu" && kind == "breathe"
+    ));
+
+    let node = parse_single_node(
+        r#"show <img src="assets/char.png" /> as royu at left idle Sway with dissolve"#,
+    );
+    assert!(matches!(
+        node,
+        ScriptNode::ShowCharacter { position: Position::Left, transition: Some(t), idle: Some(kind), .. }
+        if kind == "sway" && t.name == "dissolve"
+    ));
+}
+
+/// 测试 showcg / hidecg：路径可直接写文件名或 <img>，with 子句可选
+#[test]
+fn test_parse_show_and_hide_cg() {
+    let node = parse_single_node("showcg cg1.jpg with fade");
+    assert!(matches!(
+        node,
+        ScriptNode::ShowCg { path, transition: Some(t) } if path == "cg1.jpg" && t.name == "fade"
+    ));
+
+    let node = parse_single_node(r#"showCG <img src="../cg/cg2.png" />"#);
+    assert!(matches!(
+        node,
+        ScriptNode::ShowCg { path, transition: None } if path == "../cg/cg2.png"
+    ));
+
+    assert!(matches!(
+        parse_single_node("hidecg"),
+        ScriptNode::HideCg { transition: None }
+    ));
+    assert!(matches!(
+        parse_single_node("hidecg with dissolve"),
+        ScriptNode::HideCg { transition: Some(t) } if t.name == "dissolve"
+    ));
+
+    let mut parser = Parser::new();
+    assert!(matches!(
+        parser.parse("test", "showcg"),
+        Err(ParseError::MissingParameter { .. })
+    ));
+}
+
 /// 测试 hide 指令：
 /// - 带过渡效果（with fade）
 /// - 不带过渡效果
@@ -1184,6 +1903,22 @@ fn test_parse_audio_commands() {
 
     let stop_node = parse_single_node("stopBGM");
     assert!(matches!(stop_node, ScriptNode::StopBgm));
+
+    assert_eq!(
+        parse_single_node("playsfx @footsteps"),
+        ScriptNode::PlaySfxPool {
+            pool: "footsteps".to_string(),
+        }
+    );
+    for input in ["playSfx footsteps", "playSfx @", "playSfx @a.b"] {
+        assert!(
+            matches!(
+                parse_err(input),
+                crate::error::ParseError::InvalidParameter { .. }
+            ),
+            "input={input}"
+        );
+    }
 }
 
 //=========================================================================
@@ -1284,3 +2019,471 @@ fn test_parse_cutscene() {
         other => panic!("Expected Cutscene, got: {:?}", other),
     }
 }
+
+#[test]
+fn test_keyword_aliases_parse_chinese_show_and_goto() {
+    let mut parser = Parser::with_aliases(KeywordAliases::chinese());
+    let script = parser
+        .parse(
+            "test",
+            r#"
+显示 <img src="char.png" /> as 北风 at center
+跳转 **结尾**
+show <img src="char.png" /> as 南风 at left
+旁白："英文关键字仍然可用"
+**结尾**
+"#,
+        )
+        .unwrap();
+
+    assert!(matches!(
+        &script.nodes[0],
+        ScriptNode::ShowCharacter { alias, .. } if alias == "北风"
+    ));
+    assert!(matches!(
+        &script.nodes[1],
+        ScriptNode::Goto { target_label } if target_label == "结尾"
+    ));
+    assert!(matches!(
+        &script.nodes[2],
+        ScriptNode::ShowCharacter { alias, .. } if alias == "南风"
+    ));
+    assert!(parser.warnings().is_empty(), "{:?}", parser.warnings());
+}
+
+#[test]
+fn test_keyword_aliases_do_not_touch_dialogue_or_default_parser() {
+    // 以别名开头的对话（紧跟冒号）不应被展开
+    let mut parser = Parser::with_aliases(KeywordAliases::chinese());
+    let script = parser.parse("test", "显示：\"我是一个角色\"").unwrap();
+    assert!(matches!(
+        &script.nodes[0],
+        ScriptNode::Dialogue { speaker: Some(s), .. } if s == "显示"
+    ));
+
+    // 默认解析器不识别中文关键字
+    let mut parser = Parser::new();
+    let script = parser.parse("test", "跳转 **结尾**").unwrap();
+    assert!(script.nodes.is_empty());
+    assert_eq!(parser.warnings().len(), 1);
+}
+
+#[test]
+fn test_keyword_aliases_custom_and_unknown_keyword_error() {
+    let aliases = KeywordAliases::from_pairs([("jump", "GOTO")]).unwrap();
+    assert_eq!(aliases.resolve("jump"), Some("goto"));
+    let mut parser = Parser::with_aliases(aliases);
+    let script = parser.parse("test", "jump **end**\n**end**").unwrap();
+    assert!(matches!(&script.nodes[0], ScriptNode::Goto { .. }));
+
+    let err = KeywordAliases::from_pairs([("飞", "fly")]).unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::ParseError::InvalidKeywordAlias { alias, .. } if alias == "飞"
+    ));
+
+    let err = KeywordAliases::from_pairs([("跳 转", "goto")]).unwrap_err();
+    assert!(matches!(
+        err,
+        crate::error::ParseError::InvalidKeywordAlias { .. }
+    ));
+}
+
+#[test]
+fn test_keyword_aliases_non_ascii_prefix() {
+    let aliases = KeywordAliases::from_pairs([("Tiao跳", "goto"), ("jp", "goto")]).unwrap();
+    assert_eq!(aliases.expand("TIAO跳 **end**"), "goto **end**");
+    assert_eq!(aliases.expand("tiao跳(x)"), "goto(x)");
+    // 别名字节长度落在多字节字符中间时不命中，也不会越界切片
+    assert_eq!(aliases.expand("j跳 **end**"), "j跳 **end**");
+    assert_eq!(aliases.expand("Tiao"), "Tiao");
+    assert_eq!(aliases.expand("Tiao跃 **end**"), "Tiao跃 **end**");
+}
+
+#[test]
+fn test_dialogue_speed_annotation() {
+    let node = parse_single_node("北风[speed=0.5]：\"慢慢说\"");
+    assert!(matches!(
+        &node,
+        ScriptNode::Dialogue { speaker: Some(s), content, text_speed, .. }
+            if s == "北风" && content == "慢慢说" && *text_speed == 0.5
+    ));
+
+    // 旁白也可标注
+    let node = parse_single_node("[speed=2]：\"快\"");
+    assert!(matches!(
+        &node,
+        ScriptNode::Dialogue { speaker: None, text_speed, .. } if *text_speed == 2.0
+    ));
+
+    // 无标注时缺省 1.0
+    let node = parse_single_node("北风：\"正常\"");
+    assert!(matches!(
+        &node,
+        ScriptNode::Dialogue { speaker: Some(s), text_speed, .. } if s == "北风" && *text_speed == 1.0
+    ));
+}
+
+#[test]
+fn test_dialogue_speed_annotation_invalid_and_clamped() {
+    let err = parse_err("北风[speed=fast]：\"...\"");
+    assert!(matches!(
+        err,
+        crate::error::ParseError::InvalidParameter { param, .. } if param == "speed"
+    ));
+
+    let mut parser = Parser::new();
+    let script = parser.parse("test", "北风[speed=100]：\"...\"").unwrap();
+    assert!(matches!(
+        &script.nodes[0],
+        ScriptNode::Dialogue { text_speed, .. } if *text_speed == 10.0
+    ));
+    assert_eq!(parser.warnings().len(), 1);
+}
+
+#[test]
+fn test_parse_ending() {
+    let node = parse_single_node("ending true_end \"真结局\"");
+    assert!(matches!(
+        &node,
+        ScriptNode::Ending { id, title } if id == "true_end" && title == "真结局"
+    ));
+
+    // 标题可省略
+    let node = parse_single_node("ending bad_end");
+    assert!(matches!(
+        &node,
+        ScriptNode::Ending { id, title } if id == "bad_end" && title == "bad_end"
+    ));
+
+    assert!(matches!(
+        parse_err("ending"),
+        crate::error::ParseError::MissingParameter { .. }
+    ));
+    assert!(matches!(
+        parse_err("ending true_end 真结局"),
+        crate::error::ParseError::InvalidParameter { .. }
+    ));
+}
+
+/// 测试语法版本声明：旧版本拒绝新语法，匹配版本正常解析且行号不变
+#[test]
+fn test_syntax_version_declaration_gates_new_syntax() {
+    let mut parser = Parser::new();
+
+    let err = parser
+        .parse("test", "syntax: 1\n\nshowcg cg1.jpg with fade\n")
+        .unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            ParseError::UnsupportedSyntax { line: 3, required: 2, declared: 1, feature }
+            if feature == "showcg"
+        ),
+        "{err}"
+    );
+
+    let table = "---\nsyntax: 1\n---\n\n| 横排 |      |          |\n| --- | --- | --- |\n| 开门 | door | $key |\n";
+    assert!(matches!(
+        parser.parse("test", table),
+        Err(ParseError::UnsupportedSyntax { line: 7, .. })
+    ));
+
+    // 旧版本下旧语法照常解析，声明行不产生节点
+    let script = parser.parse("test", "syntax: 1\n\n：\"你好。\"\n").unwrap();
+    assert_eq!(script.len(), 1);
+    assert_eq!(script.get_source_line(0), Some(3));
+
+    let script = parser
+        .parse("test", "---\nsyntax: 2\n---\n\nshowcg cg1.jpg with fade\n")
+        .unwrap();
+    assert!(matches!(
+        script.get_node(0),
+        Some(ScriptNode::ShowCg { .. })
+    ));
+    assert_eq!(script.get_source_line(0), Some(5));
+
+    // 未声明时为最新版本
+    assert!(parser.parse("test", "showcg cg1.jpg\n").is_ok());
+
+    assert!(matches!(
+        parser.parse("test", "syntax: 99\n"),
+        Err(ParseError::InvalidParameter { line: 1, ref param, .. }) if param == "syntax"
+    ));
+}
+
+#[test]
+fn test_expression_error_reports_column() {
+    let err = parse_expression("$a == true and @x", 1).unwrap_err();
+    assert_eq!(err.column(), Some(16));
+    // 列号相对传入的原始片段（含被裁掉的前导空白），按字符计
+    let err = parse_expression("  $名字 == 'x", 1).unwrap_err();
+    assert_eq!(err.column(), Some(10));
+    // 空表达式没有列号
+    assert_eq!(parse_expression("  ", 1).unwrap_err().column(), None);
+}
+
+#[test]
+fn test_parse_error_column_points_into_source_line() {
+    let column_of = |parser: &mut Parser, text: &str| {
+        let err = parser.parse("test", text).unwrap_err();
+        (err.line(), err.column(), err.to_string())
+    };
+
+    // 缩进 + set 指令中途的意外字符
+    let text = "：\"开始\"\n\n    set $ok = ($a == 1 and @b)\n";
+    let (line, column, display) = column_of(&mut Parser::new(), text);
+    assert_eq!((line, column), (Some(3), Some(28)));
+    assert!(display.contains("第 3:28 行"), "{display}");
+
+    // 条件块：未闭合字符串指向开始引号
+    let text = "if $name == \"小明\" and $ok == \"未闭合\n：\"a\"\nendif\n";
+    let (line, column, _) = column_of(&mut Parser::new(), text);
+    assert_eq!((line, column), (Some(1), Some(29)));
+
+    // 选择表格的条件列
+    let text = "| 横排 | | |\n| --- | --- | --- |\n| 去海边 | sea | $a == 1 or ) |\n";
+    let (line, column, _) = column_of(&mut Parser::new(), text);
+    assert_eq!((line, column), (Some(3), Some(26)));
+
+    // 关键字别名按原文计算列号
+    let mut parser = Parser::with_aliases(KeywordAliases::chinese());
+    let (line, column, _) = column_of(&mut parser, "设置 $ok = $a == 1 and @b");
+    assert_eq!((line, column), (Some(1), Some(22)));
+}
+
+#[test]
+fn test_parse_show_auto_layout() {
+    assert_eq!(
+        parse_single_node("show royu, kaze, sora auto with dissolve"),
+        ScriptNode::ShowCharacters {
+            aliases: vec!["royu".to_string(), "kaze".to_string(), "sora".to_string()],
+            transition: Some(crate::command::Transition::simple("dissolve")),
+        }
+    );
+    assert_eq!(
+        parse_single_node("show royu,kaze AUTO"),
+        ScriptNode::ShowCharacters {
+            aliases: vec!["royu".to_string(), "kaze".to_string()],
+            transition: None,
+        }
+    );
+    // 单个角色也可以显式指定 auto 站位
+    assert!(matches!(
+        parse_single_node("show royu at auto"),
+        ScriptNode::ShowCharacter {
+            position: Position::Auto,
+            ..
+        }
+    ));
+
+    assert!(matches!(
+        parse_err("show royu, , kaze auto"),
+        ParseError::MissingParameter { .. }
+    ));
+    assert!(matches!(
+        parse_err("syntax: 1\nshow royu, kaze auto"),
+        ParseError::UnsupportedSyntax { required: 2, .. }
+    ));
+    assert!(matches!(
+        parse_err("syntax: 1\nshow royu at auto"),
+        ParseError::UnsupportedSyntax { required: 2, .. }
+    ));
+}
+
+#[test]
+fn test_parse_bubble() {
+    assert_eq!(
+        parse_single_node(r#"bubble royu "好像……有点紧张""#),
+        ScriptNode::ShowBubble {
+            alias: "royu".to_string(),
+            content: "好像……有点紧张".to_string(),
+        }
+    );
+    let script = Parser::with_aliases(KeywordAliases::chinese())
+        .parse("test", "气泡 royu “嗯？”")
+        .unwrap();
+    assert_eq!(
+        script.nodes,
+        [ScriptNode::ShowBubble {
+            alias: "royu".to_string(),
+            content: "嗯？".to_string(),
+        }]
+    );
+    // 省略文本即清除
+    assert_eq!(
+        parse_single_node("bubble royu"),
+        ScriptNode::ShowBubble {
+            alias: "royu".to_string(),
+            content: String::new(),
+        }
+    );
+
+    assert!(matches!(
+        parse_err("bubble"),
+        ParseError::MissingParameter { line: 1, .. }
+    ));
+    assert!(matches!(
+        parse_err("bubble royu 没有引号"),
+        ParseError::InvalidParameter { line: 1, .. }
+    ));
+    assert!(matches!(
+        parse_err("syntax: 1\nbubble royu \"嗯\""),
+        ParseError::UnsupportedSyntax { required: 2, .. }
+    ));
+}
+
+#[test]
+fn test_v2_directives_require_syntax_2() {
+    for line in [
+        "default_transition dissolve 0.3",
+        "rimLight royu 0.6",
+        "local $count = 1",
+        "filter grayscale",
+        "playSfx @footsteps",
+        "rumble(0.8, 0.5)",
+        "weather rain(0.3)",
+    ] {
+        let err = parse_err(&format!("syntax: 1\n{line}"));
+        assert!(
+            matches!(
+                err,
+                ParseError::UnsupportedSyntax {
+                    line: 2,
+                    required: 2,
+                    declared: 1,
+                    ..
+                }
+            ),
+            "{line}: {err:?}"
+        );
+    }
+}
+
+#[test]
+fn test_label_requires_whole_line() {
+    // 整行 **name** 是 label
+    assert_eq!(
+        parse_single_node("**chapter_end**"),
+        ScriptNode::Label {
+            name: "chapter_end".to_string(),
+        }
+    );
+
+    // 行内 **词** 是富文本粗体
+    let node = parse_single_node("北风：“这是**重点**。”");
+    let ScriptNode::Dialogue {
+        content,
+        inline_effects,
+        ..
+    } = node
+    else {
+        panic!("应为对话: {node:?}");
+    };
+    assert_eq!(content, "这是重点。");
+    let kinds: Vec<_> = inline_effects
+        .iter()
+        .map(|e| (e.position, e.kind.clone()))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            (2, crate::command::InlineEffectKind::BoldStart),
+            (4, crate::command::InlineEffectKind::BoldEnd)
+        ]
+    );
+
+    // 含空格的 **a b** 仍是 label（兼容旧脚本），但给出警告
+    let mut parser = Parser::new();
+    let script = parser.parse("test", "**a b**").unwrap();
+    assert_eq!(
+        script.nodes,
+        [ScriptNode::Label {
+            name: "a b".to_string(),
+        }]
+    );
+    assert!(
+        parser.warnings().iter().any(|w| w.contains("含空白")),
+        "{:?}",
+        parser.warnings()
+    );
+}
+
+#[test]
+fn test_parse_timeline_block_with_offsets() {
+    let node = parse_single_node(
+        r#"timeline
+  0 show <img src="alice.png"> as alice at left with dissolve
+  0.5s show <img src="bob.png"> as bob at right
+
+  1 changeBG <img src="bg2.png"> with dissolve
+endTimeline"#,
+    );
+    let ScriptNode::Timeline { steps } = node else {
+        panic!("expected timeline, got {node:?}");
+    };
+    let offsets: Vec<f64> = steps.iter().map(|s| s.offset).collect();
+    assert_eq!(offsets, [0.0, 0.5, 1.0]);
+    assert!(matches!(
+        &steps[0].node,
+        ScriptNode::ShowCharacter { alias, position: Position::Left, transition: Some(_), .. }
+            if alias == "alice"
+    ));
+    assert!(matches!(
+        &steps[1].node,
+        ScriptNode::ShowCharacter { alias, position: Position::Right, .. } if alias == "bob"
+    ));
+    assert!(matches!(
+        &steps[2].node,
+        ScriptNode::ChangeBG { path, .. } if path == "bg2.png"
+    ));
+}
+
+#[test]
+fn test_parse_timeline_inside_conditional() {
+    let node = parse_single_node(
+        r#"if $flag
+  timeline
+    0 hide alice
+    0.3 stopBGM
+  endTimeline
+  ："之后的对白"
+endif"#,
+    );
+    let ScriptNode::Conditional { branches } = node else {
+        panic!("expected conditional");
+    };
+    assert!(matches!(
+        &branches[0].body[..],
+        [ScriptNode::Timeline { steps }, ScriptNode::Dialogue { .. }] if steps.len() == 2
+    ));
+}
+
+#[test]
+fn test_parse_timeline_errors() {
+    assert!(matches!(
+        parse_err("timeline\n  0 show <img src=\"a.png\"> as a at left"),
+        ParseError::InvalidLine { line: 1, .. }
+    ));
+    // 只允许显示与音频指令
+    assert!(matches!(
+        parse_err("timeline\n  0 goto **end**\nendTimeline\n**end**"),
+        ParseError::InvalidLine { line: 2, .. }
+    ));
+    assert!(matches!(
+        parse_err("timeline\n  -1 stopBGM\nendTimeline"),
+        ParseError::InvalidParameter { ref param, .. } if param == "offset"
+    ));
+    assert!(matches!(
+        parse_err("timeline\n  0.5\nendTimeline"),
+        ParseError::MissingParameter { .. }
+    ));
+    assert!(matches!(
+        parse_err("timeline\nendTimeline"),
+        ParseError::InvalidLine { .. }
+    ));
+    assert!(matches!(
+        parse_err("syntax: 1\ntimeline\n  0 stopBGM\nendTimeline"),
+        ParseError::UnsupportedSyntax { required: 2, .. }
+    ));
+}
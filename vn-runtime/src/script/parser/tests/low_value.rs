@@ -70,7 +70,7 @@ fn test_parse_choice_table() {
 
     assert!(matches!(
         &script.nodes[0],
-        ScriptNode::Choice { style: Some(s), options }
+        ScriptNode::Choice { style: Some(s), options, .. }
         if s == "选择" && options.len() == 2
     ));
 }
@@ -161,7 +161,7 @@ fn test_parse_branch_vertical() {
 
     let script = parser.parse("test", text).unwrap();
 
-    if let ScriptNode::Choice { style, options } = &script.nodes[0] {
+    if let ScriptNode::Choice { style, options, .. } = &script.nodes[0] {
         assert_eq!(style.as_deref(), Some("竖排"));
         assert_eq!(options.len(), 3);
         assert_eq!(options[0].text, "选项1");
@@ -228,6 +228,84 @@ fn test_parse_bgm_duck_unduck() {
     assert!(matches!(unduck_upper, ScriptNode::BgmUnduck));
 }
 
+#[test]
+fn test_parse_bgm_volume() {
+    assert!(matches!(
+        parse_single_node("bgmVol 0.3 over 1.0"),
+        ScriptNode::SetBgmVolume { volume, duration } if volume == 0.3 && duration == 1.0
+    ));
+    assert!(matches!(
+        parse_single_node("BGMVOL 1"),
+        ScriptNode::SetBgmVolume { volume, duration } if volume == 1.0 && duration == 0.5
+    ));
+
+    // 超出范围的音量钳制并警告
+    let mut parser = Parser::new();
+    let script = parser.parse("test", "bgmVol 1.5").unwrap();
+    assert!(matches!(
+        script.nodes[0],
+        ScriptNode::SetBgmVolume { volume, .. } if volume == 1.0
+    ));
+    assert_eq!(parser.warnings().len(), 1);
+
+    assert!(matches!(
+        parse_err("bgmVol"),
+        crate::error::ParseError::MissingParameter { .. }
+    ));
+    assert!(matches!(
+        parse_err("bgmVol loud"),
+        crate::error::ParseError::InvalidParameter { ref param, .. } if param == "volume"
+    ));
+    assert!(matches!(
+        parse_err("bgmVol 0.3 over -1"),
+        crate::error::ParseError::InvalidParameter { ref param, .. } if param == "over"
+    ));
+    assert!(matches!(
+        parse_err("bgmVol 0.3 in 1.0"),
+        crate::error::ParseError::InvalidParameter { .. }
+    ));
+}
+
+#[test]
+fn test_parse_textbox_style() {
+    assert_eq!(
+        parse_single_node("textBoxStyle diary"),
+        ScriptNode::SetTextboxStyle {
+            name: "diary".to_string()
+        }
+    );
+    assert_eq!(
+        parse_single_node(r#"TEXTBOXSTYLE "回忆""#),
+        ScriptNode::SetTextboxStyle {
+            name: "回忆".to_string()
+        }
+    );
+
+    let mut parser = Parser::with_aliases(KeywordAliases::chinese());
+    let script = parser.parse("test", "对话框样式 default").unwrap();
+    assert_eq!(
+        script.nodes[0],
+        ScriptNode::SetTextboxStyle {
+            name: "default".to_string()
+        }
+    );
+
+    assert!(matches!(
+        parse_err("textBoxStyle"),
+        crate::error::ParseError::MissingParameter { .. }
+    ));
+    assert!(matches!(
+        parse_err(r#"textBoxStyle "two words""#),
+        crate::error::ParseError::InvalidParameter { .. }
+    ));
+    assert!(matches!(
+        Parser::new()
+            .parse("test", "syntax: 1\ntextBoxStyle diary")
+            .unwrap_err(),
+        crate::error::ParseError::UnsupportedSyntax { .. }
+    ));
+}
+
 //=========================================================================
 // 相对路径测试
 //=========================================================================
@@ -366,6 +444,129 @@ fn test_parse_title_card_default_duration() {
     }
 }
 
+#[test]
+fn test_parse_rumble_defaults() {
+    let node = parse_single_node("rumble");
+    assert_eq!(
+        node,
+        ScriptNode::Rumble {
+            intensity: 0.5,
+            duration: 0.3,
+        }
+    );
+}
+
+#[test]
+fn test_parse_rumble_positional_and_named_args() {
+    let node = parse_single_node("rumble(0.8, 1.2)");
+    assert_eq!(
+        node,
+        ScriptNode::Rumble {
+            intensity: 0.8,
+            duration: 1.2,
+        }
+    );
+
+    let node = parse_single_node("Rumble (duration: 2)");
+    assert_eq!(
+        node,
+        ScriptNode::Rumble {
+            intensity: 0.5,
+            duration: 2.0,
+        }
+    );
+}
+
+#[test]
+fn test_parse_rumble_clamps_out_of_range_with_warning() {
+    let mut parser = Parser::new();
+    let script = parser
+        .parse("test", "rumble(intensity: 3, duration: 60)")
+        .unwrap();
+    assert_eq!(
+        script.nodes[0],
+        ScriptNode::Rumble {
+            intensity: 1.0,
+            duration: 5.0,
+        }
+    );
+    assert_eq!(parser.warnings().len(), 2);
+
+    let node = parse_single_node("rumble(-1)");
+    assert!(matches!(node, ScriptNode::Rumble { intensity, .. } if intensity == 0.0));
+}
+
+#[test]
+fn test_parse_rumble_rejects_non_number() {
+    let err = parse_err(r#"rumble(intensity: "strong")"#);
+    assert!(
+        format!("{:?}", err).contains("InvalidParameter"),
+        "expected InvalidParameter, got: {:?}",
+        err
+    );
+}
+
+#[test]
+fn test_parse_weather_kinds_and_intensity() {
+    use crate::command::WeatherKind;
+
+    assert_eq!(
+        parse_single_node("weather rain"),
+        ScriptNode::SetWeather {
+            kind: WeatherKind::Rain,
+            intensity: 0.5,
+        }
+    );
+    assert_eq!(
+        parse_single_node("Weather snow(intensity: 0.8)"),
+        ScriptNode::SetWeather {
+            kind: WeatherKind::Snow,
+            intensity: 0.8,
+        }
+    );
+    assert_eq!(
+        parse_single_node("weather RAIN(0.3)"),
+        ScriptNode::SetWeather {
+            kind: WeatherKind::Rain,
+            intensity: 0.3,
+        }
+    );
+    assert!(matches!(
+        parse_single_node("weather none"),
+        ScriptNode::SetWeather {
+            kind: WeatherKind::None,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_parse_weather_clamps_and_rejects_invalid() {
+    let mut parser = Parser::new();
+    let script = parser.parse("test", "weather snow(2)").unwrap();
+    assert!(matches!(
+        script.nodes[0],
+        ScriptNode::SetWeather { intensity, .. } if intensity == 1.0
+    ));
+    assert_eq!(parser.warnings().len(), 1);
+
+    for input in [
+        "weather",
+        "weather fog",
+        r#"weather rain(intensity: "heavy")"#,
+    ] {
+        let err = parse_err(input);
+        assert!(
+            matches!(
+                err,
+                crate::error::ParseError::MissingParameter { .. }
+                    | crate::error::ParseError::InvalidParameter { .. }
+            ),
+            "input={input}, got: {err:?}"
+        );
+    }
+}
+
 #[test]
 fn test_parse_title_card_missing_text() {
     let err = parse_err("titleCard");
@@ -2,7 +2,7 @@
 
 use super::*;
 #[allow(unused_imports)]
-use crate::command::{Position, TextMode, TransitionArg};
+use crate::command::{Color, Position, TextMode, TransitionArg};
 use crate::script::ast::ScriptNode;
 
 mod high_value;
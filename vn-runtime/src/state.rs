@@ -9,7 +9,7 @@
 //! - 不允许隐式全局状态
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::time::Duration;
 
 use crate::input::SignalId;
@@ -27,6 +27,7 @@ use crate::input::SignalId;
 /// WaitForChoice -> 等待用户选择，收到 ChoiceSelected 输入后继续
 /// WaitForTime   -> Host 等待指定时长后调用 tick，Runtime 自动继续
 /// WaitForSignal -> 等待外部信号，收到匹配的 Signal 输入后继续
+/// Breakpoint    -> 命中调试断点，Host 调用 VNRuntime::resume 后继续
 /// ```
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WaitingReason {
@@ -61,6 +62,12 @@ pub enum WaitingReason {
         /// 结果存储的目标变量名
         result_var: String,
     },
+
+    /// 命中调试断点
+    ///
+    /// 在执行断点所在节点之前暂停；普通输入不会解除，
+    /// 需 Host 调用 [`crate::VNRuntime::resume`]。
+    Breakpoint(Breakpoint),
 }
 
 impl WaitingReason {
@@ -100,6 +107,15 @@ impl WaitingReason {
     }
 }
 
+/// 调试断点位置
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Breakpoint {
+    /// 源码行号（1-based，按当前执行的脚本匹配）
+    Line(usize),
+    /// 标签名
+    Label(String),
+}
+
 /// 脚本变量值
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VarValue {
@@ -129,6 +145,9 @@ pub struct ScriptPosition {
     pub script_path: String,
     /// 当前执行的节点索引
     pub node_index: usize,
+    /// 最近经过的标签（读档时校验脚本是否变更，不兼容时回退到这里）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 impl ScriptPosition {
@@ -139,6 +158,7 @@ impl ScriptPosition {
             script_id: id.clone(),
             script_path: String::new(), // 默认为空，由 host 在加载时设置
             node_index,
+            label: None,
         }
     }
 
@@ -152,6 +172,7 @@ impl ScriptPosition {
             script_id: script_id.into(),
             script_path: script_path.into(),
             node_index,
+            label: None,
         }
     }
 
@@ -203,7 +224,8 @@ pub struct RuntimeState {
 
     /// 持久变量（跨会话保留，key 为 bare key，不含 `persistent.` 前缀）
     ///
-    /// 通过 `$persistent.key` 语法访问，由 host 在启动时注入、在 fullRestart 时持久化。
+    /// 通过 `$persistent.key` 或 `$g_key`（key 保留 `g_`）访问，
+    /// 由 host 在启动时注入、在 fullRestart 时持久化。
     #[serde(default)]
     pub persistent_variables: HashMap<String, VarValue>,
 
@@ -213,6 +235,13 @@ pub struct RuntimeState {
     #[serde(default)]
     pub call_stack: Vec<ScriptPosition>,
 
+    /// 局部变量帧（用于 `local $var`）
+    ///
+    /// callScript 时压入空帧、returnFromScript 时弹出，栈顶为当前子脚本的帧。
+    /// 帧内声明但尚未赋值的变量为 None。
+    #[serde(default)]
+    pub local_scopes: Vec<HashMap<String, Option<VarValue>>>,
+
     /// 当前等待状态
     pub waiting: WaitingReason,
 
@@ -222,6 +251,85 @@ pub struct RuntimeState {
 
     /// 当前背景
     pub current_background: Option<String>,
+
+    /// 已解锁结局（跨周目累积，由 host 在启动与读档时注入、返回标题时持久化）
+    ///
+    /// 属于全局记录而非单次游玩，不随存档保存。
+    #[serde(skip)]
+    pub endings: EndingCollection,
+
+    /// 本周目到达的结局 id
+    #[serde(default)]
+    pub current_ending: Option<String>,
+
+    /// 已看过的 CG 路径（跨周目累积，供 CG 鉴赏使用；不随存档保存）
+    #[serde(skip)]
+    pub seen_cgs: BTreeSet<String>,
+
+    /// 已走过的分支：脚本 id → 选过的 target_label（跨周目累积；不随存档保存）
+    ///
+    /// 按标签而非节点索引记录，脚本增删行后记录仍然有效。
+    #[serde(skip)]
+    pub chosen_branches: BTreeMap<String, BTreeSet<String>>,
+
+    /// 定时跳转（`goto ... after`）到期后要跳转的标签
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_goto: Option<String>,
+
+    /// 确定性随机数状态（SplitMix64），随存档保存，读档后抽取结果可复现
+    #[serde(default)]
+    pub rng_state: u64,
+
+    /// 各音效池上次抽中的候选下标（避免连续重复）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sfx_pool_last: BTreeMap<String, usize>,
+}
+
+/// 已解锁结局集合（id → 标题，按 id 排序）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EndingCollection {
+    endings: BTreeMap<String, String>,
+}
+
+impl EndingCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录解锁；首次解锁返回 true（重复解锁时更新标题）
+    pub fn unlock(&mut self, id: impl Into<String>, title: impl Into<String>) -> bool {
+        self.endings.insert(id.into(), title.into()).is_none()
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.endings.contains_key(id)
+    }
+
+    /// 结局标题
+    pub fn title(&self, id: &str) -> Option<&str> {
+        self.endings.get(id).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.endings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endings.is_empty()
+    }
+
+    /// 按 id 顺序遍历 (id, 标题)
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.endings.iter().map(|(id, t)| (id.as_str(), t.as_str()))
+    }
+
+    /// 合并另一个集合（多周目累积）
+    pub fn merge(&mut self, other: &EndingCollection) {
+        for (id, title) in &other.endings {
+            self.endings.insert(id.clone(), title.clone());
+        }
+    }
 }
 
 impl RuntimeState {
@@ -232,12 +340,29 @@ impl RuntimeState {
             variables: HashMap::new(),
             persistent_variables: HashMap::new(),
             call_stack: Vec::new(),
+            local_scopes: Vec::new(),
             waiting: WaitingReason::None,
             visible_characters: HashMap::new(),
             current_background: None,
+            endings: EndingCollection::new(),
+            current_ending: None,
+            seen_cgs: BTreeSet::new(),
+            chosen_branches: BTreeMap::new(),
+            pending_goto: None,
+            rng_state: 0,
+            sfx_pool_last: BTreeMap::new(),
         }
     }
 
+    /// 推进随机数状态并返回下一个随机数（SplitMix64）
+    pub fn next_random(&mut self) -> u64 {
+        self.rng_state = self.rng_state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng_state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
     /// 设置会话变量
     pub fn set_var(&mut self, name: impl Into<String>, value: VarValue) {
         self.variables.insert(name.into(), value);
@@ -253,11 +378,67 @@ impl RuntimeState {
         self.persistent_variables.insert(bare_key.into(), value);
     }
 
+    /// 按脚本变量名赋值：持久变量名（见 [`persistent_key`]）写入持久变量，
+    /// 当前帧声明过的局部变量写入局部帧，其余写入会话变量
+    pub fn assign_var(&mut self, name: &str, value: VarValue) {
+        if let Some(bare) = persistent_key(name) {
+            self.set_persistent_var(bare, value);
+        } else if let Some(slot) = self
+            .local_scopes
+            .last_mut()
+            .and_then(|scope| scope.get_mut(name))
+        {
+            *slot = Some(value);
+        } else {
+            self.set_var(name, value);
+        }
+    }
+
+    /// 使局部变量帧与调用栈一一对应
+    ///
+    /// 旧存档没有局部帧：缺少的帧在底部补空帧，多余的帧从底部丢弃，栈顶帧始终属于当前子脚本。
+    pub(crate) fn align_local_scopes(&mut self) {
+        let depth = self.call_stack.len();
+        let len = self.local_scopes.len();
+        if len < depth {
+            self.local_scopes
+                .splice(0..0, std::iter::repeat_with(HashMap::new).take(depth - len));
+        } else {
+            self.local_scopes.drain(..len - depth);
+        }
+    }
+
+    /// 在当前局部帧声明变量（`local $var`）；不在子脚本调用中时返回 false
+    pub fn declare_local(&mut self, name: impl Into<String>, value: Option<VarValue>) -> bool {
+        match self.local_scopes.last_mut() {
+            Some(scope) => {
+                scope.insert(name.into(), value);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// 获取持久变量（bare key，不含 `persistent.` 前缀）
     pub fn get_persistent_var(&self, bare_key: &str) -> Option<&VarValue> {
         self.persistent_variables.get(bare_key)
     }
 
+    /// 记录当前脚本中选项选中了 `target_label`
+    pub fn record_chosen_branch(&mut self, target_label: impl Into<String>) {
+        self.chosen_branches
+            .entry(self.position.script_id.clone())
+            .or_default()
+            .insert(target_label.into());
+    }
+
+    /// 当前脚本中此前是否有选项选过 `target_label`
+    pub fn has_chosen_branch(&self, target_label: &str) -> bool {
+        self.chosen_branches
+            .get(&self.position.script_id)
+            .is_some_and(|labels| labels.contains(target_label))
+    }
+
     /// 进入等待状态
     pub fn wait(&mut self, reason: WaitingReason) {
         self.waiting = reason;
@@ -269,21 +450,50 @@ impl RuntimeState {
     }
 }
 
+/// 分支记录的脚本 id
+///
+/// 旧版记录以 `脚本 id:节点索引` 为 key，去掉末尾的 `:节点索引` 后与新记录合并。
+pub fn branch_script_id(key: &str) -> &str {
+    match key.rsplit_once(':') {
+        Some((script_id, index))
+            if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            script_id
+        }
+        _ => key,
+    }
+}
+
+/// 全局持久变量的命名前缀（跨周目继承），如 `$g_cleared`
+pub const GLOBAL_VAR_PREFIX: &str = "g_";
+
+/// 脚本变量名对应的持久变量 key；会话变量返回 None
+///
+/// - `persistent.foo` → `foo`
+/// - `g_foo` → `g_foo`（前缀保留，`$persistent.g_foo` 指向同一个变量）
+pub fn persistent_key(name: &str) -> Option<&str> {
+    name.strip_prefix("persistent.")
+        .or_else(|| name.starts_with(GLOBAL_VAR_PREFIX).then_some(name))
+}
+
 /// 为 RuntimeState 实现 EvalContext trait
 ///
 /// 这使得表达式求值器可以访问运行时变量。
 ///
 /// 命名空间严格隔离：
 /// - `persistent.foo` → 查 `persistent_variables["foo"]`，找不到返回 None
-/// - `foo` → 查 `variables["foo"]`，找不到返回 None
+/// - `g_foo` → 查 `persistent_variables["g_foo"]`，找不到返回 None
+/// - `foo` → 当前局部帧声明过 `foo` 时查局部帧，否则查 `variables["foo"]`，找不到返回 None
 ///
-/// 两域互不回退。
+/// 两域互不回退；局部变量遮蔽同名会话变量（即使尚未赋值）。
 impl crate::script::EvalContext for RuntimeState {
     fn get_var(&self, name: &str) -> Option<&VarValue> {
-        if let Some(bare) = name.strip_prefix("persistent.") {
-            self.persistent_variables.get(bare)
-        } else {
-            self.variables.get(name)
+        if let Some(bare) = persistent_key(name) {
+            return self.persistent_variables.get(bare);
+        }
+        match self.local_scopes.last().and_then(|scope| scope.get(name)) {
+            Some(local) => local.as_ref(),
+            None => self.variables.get(name),
         }
     }
 }